@@ -0,0 +1,266 @@
+// api.rs - Rust向け変換API
+//
+// WASMを経由せずにRustから直接ドキュメント変換を行うためのエントリーポイントです。
+// `WasmConverter` はこの `Converter` をラップしてJavaScriptに公開します。
+
+use crate::converter::{
+    detect_format, ConversionLimits, ConversionReport, ConvertError, Document, DocumentEnvelope, TextFlowOptions,
+};
+use crate::font_manager::{FontManager, FontResolver};
+use crate::formats;
+use crate::formats::deferred_assets::DeferredAsset;
+use crate::formats::docx_layout::DocComment;
+use crate::image_renderer::{self, ImageRenderConfig};
+use crate::pdf_writer::{self, PdfOptions};
+use std::sync::Arc;
+
+/// ドキュメントコンバーター
+/// フォント・テキストオプション・リソース上限を保持し、ファイルをPDF・画像ZIP・JSONに変換します。
+pub struct Converter {
+    font_manager: FontManager,
+    /// 不足しているフォントを問い合わせるコールバック
+    font_resolver: Option<FontResolver>,
+    /// TXT / CSV の既定フォント・サイズ等
+    text_options: TextFlowOptions,
+    /// ページ数・展開サイズ等のリソース上限
+    limits: ConversionLimits,
+    /// 直前の変換で見つからなかったフォント名
+    last_missing_fonts: Vec<String>,
+    /// 直前の変換の警告
+    last_warnings: Vec<String>,
+}
+
+impl Default for Converter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Converter {
+    /// 新しいコンバーターを作成
+    pub fn new() -> Self {
+        Self {
+            font_manager: FontManager::new(),
+            font_resolver: None,
+            text_options: TextFlowOptions::default(),
+            limits: ConversionLimits::default(),
+            last_missing_fonts: Vec::new(),
+            last_warnings: Vec::new(),
+        }
+    }
+
+    /// 変換に使用するフォントマネージャー
+    pub fn font_manager(&self) -> &FontManager {
+        &self.font_manager
+    }
+
+    /// 外部フォントデータ（TTF / OTF）を追加
+    pub fn add_font(&mut self, name: String, data: Vec<u8>) {
+        self.font_manager.add_font(name, data);
+    }
+
+    /// 外部フォントを削除
+    pub fn remove_font(&mut self, name: &str) {
+        self.font_manager.remove_font(name);
+    }
+
+    /// ドキュメント内の`requested`フォントを`provided`フォントで描画する別名を追加
+    pub fn add_font_alias(&mut self, requested: String, provided: String) {
+        self.font_manager.add_alias(requested, provided);
+    }
+
+    /// 不足フォントを遅延読み込みするコールバックを設定（None で解除）
+    /// 問い合わせ済みのフォント名の記録はリセットされます。
+    pub fn set_font_resolver(&mut self, resolver: Option<FontResolver>) {
+        self.font_resolver = resolver;
+        self.font_manager.clear_requested_fonts();
+    }
+
+    /// TXT / CSV 変換の既定フォント・サイズ等を設定（`add_asset` で供給したデータは保持）
+    pub fn set_text_options(&mut self, options: TextFlowOptions) {
+        let supplied_assets = std::mem::take(&mut self.text_options.supplied_assets);
+        self.text_options = TextFlowOptions { supplied_assets, ..options };
+    }
+
+    /// 読み込めなかった画像（`deferred_assets`）のデータを供給（同じパスは置き換え）
+    /// 以降の変換ではプレースホルダーの代わりにこのデータを描画します。
+    pub fn add_asset(&mut self, path: String, data: Vec<u8>) {
+        let assets = &mut self.text_options.supplied_assets;
+        assets.retain(|(name, _)| *name != path);
+        assets.push((path, data));
+    }
+
+    /// 供給した画像のデータをすべて破棄
+    pub fn clear_assets(&mut self) {
+        self.text_options.supplied_assets.clear();
+    }
+
+    /// 変換時のリソース上限を設定
+    pub fn set_limits(&mut self, limits: ConversionLimits) {
+        self.limits = limits;
+    }
+
+    /// 直前の変換で参照されたが利用できなかったフォント名
+    pub fn missing_fonts(&self) -> &[String] {
+        &self.last_missing_fonts
+    }
+
+    /// 直前の変換の警告（未対応の図形など）
+    pub fn warnings(&self) -> &[String] {
+        &self.last_warnings
+    }
+
+    /// 直前の変換で読み込めず、プレースホルダーで表示した画像
+    /// `path` のデータを `add_asset` で供給して再変換すると画像を描画します。
+    pub fn deferred_assets(&self) -> Vec<DeferredAsset> {
+        self.last_warnings.iter().filter_map(|w| DeferredAsset::from_warning(w)).collect()
+    }
+
+    /// ファイルをドキュメントモデルに変換
+    /// 埋め込みフォントの登録と不足フォントの記録も行います。
+    pub fn to_document(&mut self, filename: &str, data: &[u8]) -> Result<Document, ConvertError> {
+        let ext = detect_extension(filename)?;
+        self.refresh_layout_font();
+        let mut doc = formats::convert_by_extension_with_limits(ext, data, &self.text_options, &self.limits)?;
+        self.prepare(&mut doc);
+        Ok(doc)
+    }
+
+    /// ファイルをPDFに変換
+    pub fn to_pdf(&mut self, filename: &str, data: &[u8]) -> Result<Vec<u8>, ConvertError> {
+        let doc = self.to_document(filename, data)?;
+        Ok(pdf_writer::render_to_pdf_with_fonts(&doc, &self.font_manager))
+    }
+
+    /// 出力オプションを指定してファイルをPDFに変換
+    /// `search_highlight` の検索文字列が空、または正規表現が不正な場合はエラーを返します。
+    pub fn to_pdf_with_options(
+        &mut self,
+        filename: &str,
+        data: &[u8],
+        options: &PdfOptions,
+    ) -> Result<Vec<u8>, ConvertError> {
+        // 検索ハイライトのパターンは変換前に検証
+        if let Some(highlight) = &options.search_highlight {
+            highlight.pattern()?;
+        }
+        let doc = self.to_document(filename, data)?;
+        Ok(pdf_writer::render_to_pdf_with_source(&doc, &self.font_manager, options, filename, data))
+    }
+
+    /// ファイルをページごとの1ページのPDFに変換してZIP（`page-001.pdf`, ...）で返す
+    pub fn to_pdf_zip(&mut self, filename: &str, data: &[u8], options: &PdfOptions) -> Result<Vec<u8>, ConvertError> {
+        let doc = self.to_document(filename, data)?;
+        Ok(pdf_writer::render_to_pdf_zip(&doc, &self.font_manager, options))
+    }
+
+    /// 複数ファイル `(ファイル名, バイト列)` を順番に変換し、出力オプションを指定して1つのPDFに結合
+    /// 透かし・ページ番号は結合後のドキュメント全体に適用します（ページ番号は通し番号）。
+    pub fn to_pdf_batch(&mut self, files: &[(&str, &[u8])], options: &PdfOptions) -> Result<Vec<u8>, ConvertError> {
+        if let Some(highlight) = &options.search_highlight {
+            highlight.pattern()?;
+        }
+        self.refresh_layout_font();
+        let mut doc = formats::convert_batch_with_limits(files, &self.text_options, &self.limits)?;
+        self.prepare(&mut doc);
+        Ok(pdf_writer::render_to_pdf_with_options(&doc, &self.font_manager, options))
+    }
+
+    /// ファイルをページごとのPNG画像に変換してZIPで返す
+    /// DPIはメモリ枯渇を防ぐため `MIN_DPI`〜`MAX_DPI` の範囲に丸めます。
+    pub fn to_images_zip(
+        &mut self,
+        filename: &str,
+        data: &[u8],
+        mut config: ImageRenderConfig,
+    ) -> Result<Vec<u8>, ConvertError> {
+        let doc = self.to_document(filename, data)?;
+        config.dpi = config.dpi.clamp(image_renderer::MIN_DPI, image_renderer::MAX_DPI);
+        Ok(image_renderer::render_to_images_zip_with_config(&doc, &self.font_manager, &config))
+    }
+
+    /// ファイルをJSON形式のドキュメントモデルに変換
+    /// 出力は形式のバージョン付きの `{ "schemaVersion", "document" }`（`DocumentEnvelope`）です。
+    pub fn to_json(&mut self, filename: &str, data: &[u8]) -> Result<String, ConvertError> {
+        let doc = self.to_document(filename, data)?;
+        serde_json::to_string_pretty(&DocumentEnvelope::new(doc))
+            .map_err(|e| ConvertError::new("json", &format!("JSONシリアライズエラー: {}", e)))
+    }
+
+    /// `to_json` 形式のJSON（編集したものを含む）をPDFに描画
+    /// ページ数はファイルの変換と同じリソース上限で検査します。
+    pub fn render_json_to_pdf(&mut self, json: &str, options: &PdfOptions) -> Result<Vec<u8>, ConvertError> {
+        let mut doc = DocumentEnvelope::document_from_json(json)?;
+        formats::limits::check_pages("json", &doc, &self.limits)?;
+        self.prepare(&mut doc);
+        Ok(pdf_writer::render_to_pdf_with_options(&doc, &self.font_manager, options))
+    }
+
+    /// `to_json` 形式のJSON（編集したものを含む）をページごとの画像に描画してZIPで返す
+    /// ページ数はファイルの変換と同じリソース上限で検査します。
+    pub fn render_json_to_images_zip(&mut self, json: &str, mut config: ImageRenderConfig) -> Result<Vec<u8>, ConvertError> {
+        let mut doc = DocumentEnvelope::document_from_json(json)?;
+        formats::limits::check_pages("json", &doc, &self.limits)?;
+        self.prepare(&mut doc);
+        config.dpi = config.dpi.clamp(image_renderer::MIN_DPI, image_renderer::MAX_DPI);
+        Ok(image_renderer::render_to_images_zip_with_config(&doc, &self.font_manager, &config))
+    }
+
+    /// ファイルを変換し、ページごとの機能の再現度（そのまま・近似・省略）をまとめたレポートを返す
+    pub fn conversion_report(&mut self, filename: &str, data: &[u8]) -> Result<ConversionReport, ConvertError> {
+        let doc = self.to_document(filename, data)?;
+        Ok(doc.conversion_report(&self.last_missing_fonts))
+    }
+
+    /// ファイルからページごとのテキストを抽出（読み順、表のセルはタブ区切り）
+    /// フォントを使用しないため、フォントの登録や不足フォントの記録は行いません。
+    /// 画像・背景・グラフを読み込まないテキストのみのモード（`TextFlowOptions::text_only`）で変換します。
+    pub fn extract_text(&self, filename: &str, data: &[u8]) -> Result<Vec<String>, ConvertError> {
+        let ext = detect_extension(filename)?;
+        let options = TextFlowOptions {
+            text_only: true,
+            layout_font: self.font_manager.usable_font_data().map(Arc::from),
+            ..self.text_options.clone()
+        };
+        let doc = formats::convert_by_extension_with_limits(ext, data, &options, &self.limits)?;
+        Ok(doc.extract_text())
+    }
+
+    /// DOCXのコメント（作成者・日時・本文・コメントが付けられた本文のテキスト）を抽出
+    /// DOCX以外のファイルはエラーになります。
+    pub fn extract_comments(&self, filename: &str, data: &[u8]) -> Result<Vec<DocComment>, ConvertError> {
+        let ext = detect_extension(filename)?;
+        if ext != "docx" {
+            return Err(ConvertError::new(ext, "コメントの抽出はDOCXのみ対応しています"));
+        }
+        formats::limits::check_archive(ext, data, &self.limits)?;
+        formats::docx_layout::extract_comments(data)
+    }
+
+    /// 表のページ分割に使うフォントを、PDFに埋め込むフォントに合わせる（変わった場合だけ複製）
+    fn refresh_layout_font(&mut self) {
+        let font = self.font_manager.usable_font_data();
+        if self.text_options.layout_font.as_deref() != font {
+            self.text_options.layout_font = font.map(Arc::from);
+        }
+    }
+
+    /// ドキュメントの埋め込みフォントを今回の変換用に登録し、不足フォント・グリフと警告を記録
+    fn prepare(&mut self, doc: &mut Document) {
+        self.font_manager.set_document_fonts(std::mem::take(&mut doc.embedded_fonts));
+        if let Some(resolver) = self.font_resolver.as_mut() {
+            self.font_manager.request_missing_fonts(doc, resolver);
+        }
+        self.last_missing_fonts = self.font_manager.missing_fonts(doc);
+        // PDFに埋め込むのと同じフォントで判定する
+        doc.record_missing_glyphs(self.font_manager.usable_font_data());
+        self.last_warnings = doc.warnings.clone();
+    }
+}
+
+/// ファイル名の拡張子から入力フォーマットを判定
+fn detect_extension(filename: &str) -> Result<&'static str, ConvertError> {
+    detect_format(filename).ok_or_else(|| {
+        ConvertError::new("unknown", &format!("サポートされていないファイル形式です: {}", filename))
+    })
+}
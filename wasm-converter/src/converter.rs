@@ -65,6 +65,23 @@ impl Color {
     pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b, a: 255 }
     }
+
+    /// 不透明な下地の上にアルファ合成した不透明色を返す（source-over）
+    pub fn composite_over(self, backdrop: Color) -> Color {
+        let alpha = self.a as f64 / 255.0;
+        let mix = |fg: u8, bg: u8| (fg as f64 * alpha + bg as f64 * (1.0 - alpha)).round() as u8;
+        Color::rgb(mix(self.r, backdrop.r), mix(self.g, backdrop.g), mix(self.b, backdrop.b))
+    }
+
+    /// 輝度（ITU-R BT.601 の重み付け、0〜255）
+    pub fn luminance(&self) -> u8 {
+        luminance(self.r, self.g, self.b)
+    }
+}
+
+/// RGB値の輝度（ITU-R BT.601 の重み付け、0〜255）
+pub fn luminance(r: u8, g: u8, b: u8) -> u8 {
+    (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64).round().clamp(0.0, 255.0) as u8
 }
 
 /// テキストの水平揃え
@@ -75,6 +92,57 @@ pub enum TextAlign {
     Right,
 }
 
+/// タブ位置での揃え方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TabAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// 小数点の位置をタブ位置に揃える
+    Decimal,
+}
+
+/// タブ位置（行の左端からの距離、pt）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TabStop {
+    pub position: f64,
+    pub align: TabAlign,
+}
+
+impl TabStop {
+    /// 既定のタブ間隔（pt）。Word と同じ 0.5 インチ
+    pub const DEFAULT_INTERVAL: f64 = 36.0;
+
+    /// 行の左端から `x` の位置より右にある次のタブ位置を返す。
+    /// 定義済みのタブ位置を優先し、それより右では `interval` ごとの既定タブ位置（左揃え）を使います。
+    pub fn next(x: f64, stops: &[TabStop], interval: f64) -> TabStop {
+        if let Some(stop) = stops
+            .iter()
+            .filter(|s| s.position > x + 1e-6)
+            .min_by(|a, b| a.position.total_cmp(&b.position))
+        {
+            return *stop;
+        }
+        let interval = if interval > 0.0 { interval } else { Self::DEFAULT_INTERVAL };
+        TabStop {
+            position: ((x + 1e-6) / interval).floor() * interval + interval,
+            align: TabAlign::Left,
+        }
+    }
+
+    /// タブに続くテキスト片の開始位置（行の左端からの距離）を返す。
+    /// `width` はテキスト片の幅、`before_decimal` は小数点より前の部分の幅です。
+    pub fn segment_start(&self, width: f64, before_decimal: f64) -> f64 {
+        match self.align {
+            TabAlign::Left => self.position,
+            TabAlign::Center => self.position - width / 2.0,
+            TabAlign::Right => self.position - width,
+            TabAlign::Decimal => self.position - before_decimal,
+        }
+    }
+}
+
 /// フォントスタイル
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FontStyle {
@@ -83,6 +151,344 @@ pub struct FontStyle {
     pub bold: bool,
     pub italic: bool,
     pub color: Color,
+    /// 取り消し線
+    #[serde(default)]
+    pub strikethrough: bool,
+    /// ベースラインのずれ（フォントサイズに対する割合、正: 上付き / 負: 下付き）
+    #[serde(default)]
+    pub baseline_shift: f64,
+    /// 下線（None の場合は下線なし）
+    #[serde(default)]
+    pub underline: Option<UnderlineStyle>,
+    /// 文字間隔（pt、各文字の送り幅に加算。負の値で詰める）
+    #[serde(default)]
+    pub letter_spacing: f64,
+}
+
+/// 下線の線種
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum UnderlineKind {
+    Single,
+    Double,
+    Dotted,
+}
+
+/// 下線スタイル
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UnderlineStyle {
+    pub kind: UnderlineKind,
+    /// 下線の色（None の場合は文字色を使用）
+    pub color: Option<Color>,
+}
+
+/// ベースラインから下線までの距離（フォントサイズ比）
+pub const UNDERLINE_OFFSET: f64 = 0.12;
+
+impl UnderlineStyle {
+    pub fn new(kind: UnderlineKind) -> Self {
+        Self { kind, color: None }
+    }
+}
+
+/// フォントにグリフが無い文字の描画方法
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum MissingGlyphPolicy {
+    /// 何も描画しない（文字幅分の空白）
+    Hide,
+    /// .notdef 相当の矩形を描画
+    #[default]
+    Box,
+    /// 指定した文字で置き換える（例: '□'）
+    Replace(char),
+}
+
+/// ページからはみ出したテーブルの縮小方法（Excelの「1ページ幅に合わせる」相当）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum FitMode {
+    /// 縮小しない
+    #[default]
+    None,
+    /// 幅がページ（余白内）に収まるよう縮小
+    FitWidth,
+    /// 幅と高さの両方がページ（余白内）に収まるよう縮小
+    FitPage,
+}
+
+/// 画像と配置先の矩形の縦横比が異なる場合の収め方
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum ImageFit {
+    /// 矩形いっぱいに引き伸ばす（縦横比を保たない）
+    #[default]
+    Stretch,
+    /// 縦横比を保って矩形に収め、中央に配置する（余白は描画しない）
+    Contain,
+    /// 縦横比を保って矩形を覆うよう拡大し、はみ出した部分を中央基準で切り取る
+    Cover,
+}
+
+/// 出力の色モード（トナー節約の印刷や電子ペーパー向け）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum ColorMode {
+    /// 元の色のまま
+    #[default]
+    Color,
+    /// 輝度によるグレースケール
+    Grayscale,
+    /// 輝度をしきい値で白黒に2値化
+    BlackWhite,
+}
+
+/// `ColorMode::BlackWhite` で白とみなす輝度の下限
+pub const BLACK_WHITE_THRESHOLD: u8 = 128;
+
+impl ColorMode {
+    /// RGB値を変換後の灰色の濃さにする（`Color` モードでは None）
+    pub fn gray_level(self, r: u8, g: u8, b: u8) -> Option<u8> {
+        let level = luminance(r, g, b);
+        match self {
+            ColorMode::Color => None,
+            ColorMode::Grayscale => Some(level),
+            ColorMode::BlackWhite => Some(if level >= BLACK_WHITE_THRESHOLD { 255 } else { 0 }),
+        }
+    }
+
+    /// 色を変換（アルファは保持）
+    pub fn apply(self, color: Color) -> Color {
+        match self.gray_level(color.r, color.g, color.b) {
+            Some(level) => Color { r: level, g: level, b: level, a: color.a },
+            None => color,
+        }
+    }
+
+    /// デコード済みのRGBAビットマップを変換
+    pub fn apply_to_rgba(self, pixels: &mut [u8]) {
+        if self == ColorMode::Color {
+            return;
+        }
+        for px in pixels.chunks_exact_mut(4) {
+            if let Some(level) = self.gray_level(px[0], px[1], px[2]) {
+                px[..3].fill(level);
+            }
+        }
+    }
+}
+
+/// 未実装のプリセット図形（PPTXの a:prstGeom）の描画方法
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum UnknownGeometryPolicy {
+    /// 描画しない（テキストのみ配置）
+    #[default]
+    Skip,
+    /// 図形の外接矩形に塗り・線を描画
+    BoundingRect,
+    /// 外接矩形に内接する楕円に塗り・線を描画
+    Ellipse,
+}
+
+/// DOCXの変更履歴（`w:ins` / `w:del`）の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum TrackedChanges {
+    /// 変更を承諾した状態（挿入を表示し、削除を除く）
+    #[default]
+    Accept,
+    /// 変更を元に戻した状態（挿入を除き、削除を表示）
+    Reject,
+    /// 挿入を下線付き、削除を取り消し線付きでそれぞれ別の色で表示
+    Markup,
+}
+
+/// リンクの付いたテキストの見た目（DOCX の w:hyperlink、PPTX の a:hlinkClick、HTML の a 要素）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum HyperlinkStyle {
+    /// 元のファイルの書式のまま
+    #[default]
+    Source,
+    /// テーマのハイパーリンクの色（DOCX / PPTX の hlink）と下線で強調
+    /// HTML にはテーマが無いため、Office の既定テーマの hlink の色（#0563C1）に固定です。
+    Emphasize,
+    /// 下線を付けず、前後のテキストと同じ書式で表示
+    Plain,
+}
+
+/// 用紙サイズ（B列は JIS 規格）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum PaperSize {
+    /// 297×420mm
+    A3,
+    /// 210×297mm
+    #[default]
+    A4,
+    /// 148×210mm
+    A5,
+    /// 257×364mm
+    B4,
+    /// 182×257mm
+    B5,
+    /// 8.5×11インチ
+    Letter,
+    /// 8.5×14インチ
+    Legal,
+    /// 11×17インチ
+    Tabloid,
+    /// 任意のサイズ（縦向きの幅・高さ、ポイント単位）
+    Custom { width: f64, height: f64 },
+}
+
+impl PaperSize {
+    /// 縦向きの (幅, 高さ)（ポイント単位）
+    pub fn dimensions(self) -> (f64, f64) {
+        match self {
+            PaperSize::A3 => (841.89, 1190.55),
+            PaperSize::A4 => (595.28, 841.89),
+            PaperSize::A5 => (419.53, 595.28),
+            PaperSize::B4 => (728.5, 1031.81),
+            PaperSize::B5 => (515.91, 728.5),
+            PaperSize::Letter => (612.0, 792.0),
+            PaperSize::Legal => (612.0, 1008.0),
+            PaperSize::Tabloid => (792.0, 1224.0),
+            PaperSize::Custom { width, height } => (width, height),
+        }
+    }
+
+    /// 向きを反映した (幅, 高さ)（ポイント単位）
+    /// 横向きでは長辺を幅にします（Custom の幅・高さは縦向きとして扱い、横向きでは入れ替えます）。
+    pub fn oriented_dimensions(self, orientation: PageOrientation) -> (f64, f64) {
+        let (width, height) = self.dimensions();
+        match orientation {
+            PageOrientation::Portrait => (width, height),
+            PageOrientation::Landscape => (height, width),
+        }
+    }
+}
+
+/// 用紙の向き
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum PageOrientation {
+    /// 縦向き
+    #[default]
+    Portrait,
+    /// 横向き
+    Landscape,
+}
+
+/// TXT / CSV などテキストフロー系コンバーターの入力オプション
+/// JavaScript側からはJSON文字列として受け取り、未指定の項目はデフォルト値になります。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextFlowOptions {
+    /// 既定のフォント名（None の場合はレンダラーの既定フォント）
+    pub default_font: Option<String>,
+    /// 既定のフォントサイズ（pt）
+    pub default_font_size: f64,
+    /// CSVの列幅を等幅フォントの送り幅で計測して揃える
+    pub monospace: bool,
+    /// PPTXの未実装のプリセット図形の描画方法（図形名は `Document.warnings` に記録）
+    pub unknown_geometry_policy: UnknownGeometryPolicy,
+    /// CSVが複数ページにわたる場合、1行目（ヘッダー行）を各ページの先頭に繰り返す
+    pub repeat_header: bool,
+    /// TXTのタブ位置の間隔（pt）
+    pub tab_interval: f64,
+    /// CSVの各フィールドの前後の空白を取り除く（引用符で囲まれたフィールドも対象）
+    pub csv_trim_fields: bool,
+    /// PPTXで背景以外に要素の無いスライドを出力しない（既定では元のスライド数を保つ）
+    pub skip_empty_pages: bool,
+    /// PPTXで非表示スライド（`show="0"`）を出力しない（既定では非表示スライドも出力する）
+    pub skip_hidden_slides: bool,
+    /// PPTXで段落内の同じ書式が隣接するラン（`a:r`）を1つのテキストにまとめる（既定: 有効）
+    pub merge_text_runs: bool,
+    /// DOCXのコメント（comments.xml）を右余白に注釈として描画する（既定では描画しない）
+    pub render_comments: bool,
+    /// DOCXの変更履歴の扱い（`"Accept"` / `"Reject"` / `"Markup"`）
+    pub tracked_changes: TrackedChanges,
+    /// DOCX / PPTX / HTML のリンクの付いたテキストの見た目（`"Source"` / `"Emphasize"` / `"Plain"`）
+    pub hyperlink_style: HyperlinkStyle,
+    /// XLSX / XLS で使用範囲のセル境界に薄い枠線を引く（既定では引かない）
+    pub show_gridlines: bool,
+    /// XLSX / XLS で列番号（A, B, C…）の行と行番号（1, 2, 3…）の列を見出しとして追加する
+    pub show_headers: bool,
+    /// TXT / CSV の用紙サイズ
+    pub paper_size: PaperSize,
+    /// TXT / CSV の用紙の向き
+    pub orientation: PageOrientation,
+    /// テキストと表だけのドキュメントを作る高速モード（`Page::retain_text`、検索インデックス用）
+    /// 画像・背景・グラフの読み込みと埋め込みフォントの抽出を省きます。`extractText` では常に有効です。
+    pub text_only: bool,
+    /// 変換時に読み込めなかった画像の代わりに使うデータ（パッケージ内のパス → バイト列、`Converter::add_asset`）
+    #[serde(skip)]
+    pub supplied_assets: Vec<(String, Vec<u8>)>,
+    /// CSV / XLSX / XLS の表をページに分割する際に行の高さを計測するフォントデータ
+    /// `Converter` が描画時と同じフォントを設定します。None の場合は概算の文字幅で計測します。
+    #[serde(skip)]
+    pub layout_font: Option<std::sync::Arc<[u8]>>,
+}
+
+impl Default for TextFlowOptions {
+    fn default() -> Self {
+        Self {
+            default_font: None,
+            default_font_size: FontStyle::default().font_size,
+            monospace: false,
+            unknown_geometry_policy: UnknownGeometryPolicy::default(),
+            repeat_header: true,
+            tab_interval: TabStop::DEFAULT_INTERVAL,
+            csv_trim_fields: false,
+            skip_empty_pages: false,
+            skip_hidden_slides: false,
+            merge_text_runs: true,
+            render_comments: false,
+            tracked_changes: TrackedChanges::default(),
+            hyperlink_style: HyperlinkStyle::default(),
+            show_gridlines: false,
+            show_headers: false,
+            paper_size: PaperSize::default(),
+            orientation: PageOrientation::default(),
+            text_only: false,
+            supplied_assets: Vec::new(),
+            layout_font: None,
+        }
+    }
+}
+
+impl TextFlowOptions {
+    /// オプションの用紙サイズ・向きの空のページ
+    pub fn blank_page(&self) -> Page {
+        Page::with_size_oriented(self.paper_size, self.orientation)
+    }
+
+    /// オプションを反映した本文の文字スタイル
+    pub fn font_style(&self) -> FontStyle {
+        let mut style = FontStyle::default();
+        if let Some(name) = &self.default_font {
+            style.font_name = name.clone();
+        }
+        if self.default_font_size > 0.0 {
+            style.font_size = self.default_font_size;
+        }
+        style
+    }
+}
+
+/// 変換時のリソース上限（ZIP爆弾などによるメモリ枯渇の防止）
+/// JavaScript側からはJSON文字列として受け取り、未指定の項目はデフォルト値になります。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConversionLimits {
+    /// 変換結果の最大ページ数（PPTXはスライド数、XLSXはシート数でも事前に判定）
+    pub max_pages: usize,
+    /// 埋め込み画像1つあたりの最大バイト数（展開後）
+    pub max_image_bytes: u64,
+    /// アーカイブから展開する合計の最大バイト数
+    pub max_total_decompressed_bytes: u64,
+}
+
+impl Default for ConversionLimits {
+    fn default() -> Self {
+        Self {
+            max_pages: 5_000,
+            max_image_bytes: 64 * 1024 * 1024,
+            max_total_decompressed_bytes: 512 * 1024 * 1024,
+        }
+    }
 }
 
 impl Default for FontStyle {
@@ -93,7 +499,82 @@ impl Default for FontStyle {
             bold: false,
             italic: false,
             color: Color::BLACK,
+            strikethrough: false,
+            baseline_shift: 0.0,
+            underline: None,
+            letter_spacing: 0.0,
+        }
+    }
+}
+
+/// 上付き・下付き文字の縮小率
+const SCRIPT_SIZE_RATIO: f64 = 0.66;
+
+impl FontStyle {
+    /// 上付き・下付きを考慮した描画サイズと上端Y座標を返す
+    ///
+    /// `y` は通常サイズで描画した場合のテキスト上端。ベースラインを
+    /// `baseline_shift` だけずらし、文字サイズを約66%に縮小します。
+    pub fn script_metrics(&self, y: f64) -> (f64, f64) {
+        if self.baseline_shift == 0.0 {
+            return (self.font_size, y);
         }
+        let size = self.font_size * SCRIPT_SIZE_RATIO;
+        let baseline = y + self.font_size - self.baseline_shift * self.font_size;
+        (size, baseline - size)
+    }
+
+    /// 文字間隔によるテキスト幅の増分（文字と文字の間ごとに `letter_spacing` を加算）
+    pub fn letter_spacing_width(&self, text: &str) -> f64 {
+        self.letter_spacing * text.chars().count().saturating_sub(1) as f64
+    }
+}
+
+/// セル枠線の1辺
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BorderSpec {
+    /// 線幅（ポイント）
+    pub width: f64,
+    pub color: Color,
+    /// 二重線などの複合線
+    #[serde(default)]
+    pub compound: CompoundLine,
+}
+
+/// セル枠線（辺ごと、`None` の辺は描画しない）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CellBorders {
+    pub top: Option<BorderSpec>,
+    pub right: Option<BorderSpec>,
+    pub bottom: Option<BorderSpec>,
+    pub left: Option<BorderSpec>,
+}
+
+impl CellBorders {
+    /// 指定したセル矩形に対する各辺の線要素（上・右・下・左の順）
+    /// 複合線の辺は、セルの外側を正として平行にずらした複数の線になります。
+    pub fn edge_lines(&self, x: f64, y: f64, width: f64, height: f64) -> Vec<PageElement> {
+        let (right, bottom) = (x + width, y + height);
+        [
+            (self.top, (x, y, right, y), (0.0, -1.0)),
+            (self.right, (right, y, right, bottom), (1.0, 0.0)),
+            (self.bottom, (x, bottom, right, bottom), (0.0, 1.0)),
+            (self.left, (x, y, x, bottom), (-1.0, 0.0)),
+        ]
+        .into_iter()
+        .filter_map(|(spec, line, outward)| spec.map(|spec| (spec, line, outward)))
+        .flat_map(|(spec, (x1, y1, x2, y2), (ox, oy))| {
+            spec.compound.strokes(spec.width).into_iter().map(move |(offset, width)| PageElement::Line {
+                x1: x1 + ox * offset,
+                y1: y1 + oy * offset,
+                x2: x2 + ox * offset,
+                y2: y2 + oy * offset,
+                width,
+                color: spec.color,
+                stroke_style: StrokeStyle::default(),
+            })
+        })
+        .collect()
     }
 }
 
@@ -104,6 +585,16 @@ pub struct TableCell {
     pub style: FontStyle,
     pub col_span: u32,
     pub row_span: u32,
+    /// セルの塗りつぶし色
+    #[serde(default)]
+    pub background: Option<Color>,
+    /// 辺ごとの枠線（`None` の場合は既定の細い灰色の枠線）
+    #[serde(default)]
+    pub borders: Option<CellBorders>,
+    /// テキストがセルに収まるまでフォントサイズを縮小する（`Table::with_shrunk_cells`）
+    /// 行の高さは行内の他のセルで決まる高さ（最小 `TABLE_MIN_ROW_HEIGHT`）を上限とします。
+    #[serde(default)]
+    pub shrink_to_fit: bool,
 }
 
 impl TableCell {
@@ -113,6 +604,9 @@ impl TableCell {
             style: FontStyle::default(),
             col_span: 1,
             row_span: 1,
+            background: None,
+            borders: None,
+            shrink_to_fit: false,
         }
     }
 }
@@ -124,6 +618,157 @@ pub struct Table {
     pub column_widths: Vec<f64>,
 }
 
+/// テーブル行の最小高さ（ポイント）
+pub const TABLE_MIN_ROW_HEIGHT: f64 = 20.0;
+/// セル内の余白（ポイント）
+pub const TABLE_CELL_PADDING: f64 = 4.0;
+/// セル内テキストの行送り（フォントサイズ比）
+pub const TABLE_LINE_SPACING: f64 = 1.3;
+/// スプレッドシート系の列幅の下限（ポイント）
+/// 列数が多い場合はページ幅を超えるため、`FitMode` で縮小します。
+pub const TABLE_MIN_COLUMN_WIDTH: f64 = 48.0;
+/// `shrink_to_fit` のセルで縮小するフォントサイズの下限（ポイント）
+pub const TABLE_MIN_SHRINK_FONT_SIZE: f64 = 6.0;
+/// `shrink_to_fit` のセルでフォントサイズを縮小する刻み（ポイント）
+const TABLE_SHRINK_STEP: f64 = 0.5;
+
+impl Table {
+    /// 列幅を解決（指定が無い場合はテーブル幅を先頭行の列数で均等割り）
+    pub fn resolved_column_widths(&self, table_width: f64) -> Vec<f64> {
+        if self.column_widths.is_empty() {
+            let ncols = self.rows.first().map_or(1, |r| r.len().max(1));
+            vec![table_width / ncols as f64; ncols]
+        } else {
+            self.column_widths.clone()
+        }
+    }
+
+    /// 結合を考慮したセル幅（col_span 分の列幅の合計）
+    pub fn cell_width(column_widths: &[f64], col: usize, col_span: u32) -> f64 {
+        let fallback = column_widths.last().copied().unwrap_or(60.0);
+        (0..col_span.max(1) as usize)
+            .map(|i| column_widths.get(col + i).copied().unwrap_or(fallback))
+            .sum()
+    }
+
+    /// セルのテキストを余白を除いたセル幅で折り返した行
+    pub fn cell_lines(cell: &TableCell, cell_width: f64, font_data: Option<&[u8]>) -> Vec<String> {
+        if cell.text.is_empty() {
+            return Vec::new();
+        }
+        crate::font_manager::wrap_text_to_width(
+            &cell.text,
+            cell_width - TABLE_CELL_PADDING * 2.0,
+            cell.style.font_size,
+            font_data,
+        )
+    }
+
+    /// セルのテキストを折り返して描画するのに必要な高さ
+    fn cell_height(cell: &TableCell, cell_width: f64, font_data: Option<&[u8]>) -> f64 {
+        let lines = Self::cell_lines(cell, cell_width, font_data).len().max(1);
+        cell.style.font_size * TABLE_LINE_SPACING * lines as f64 + TABLE_CELL_PADDING * 2.0
+    }
+
+    /// 行内の各セル（結合の継続セルを除く）と列幅の組
+    fn row_cells<'a>(row: &'a [TableCell], widths: &'a [f64]) -> impl Iterator<Item = (&'a TableCell, f64)> + 'a {
+        let mut col = 0usize;
+        row.iter().filter_map(move |cell| {
+            // 結合の継続セルは1列分だけ進める
+            if cell.col_span == 0 || cell.row_span == 0 {
+                col += 1;
+                return None;
+            }
+            let cw = Self::cell_width(widths, col, cell.col_span);
+            col += cell.col_span as usize;
+            Some((cell, cw))
+        })
+    }
+
+    /// 各行の高さ（折り返し後の行数が最も多いセルに合わせて自動拡張）
+    pub fn row_heights(&self, table_width: f64, font_data: Option<&[u8]>) -> Vec<f64> {
+        let widths = self.resolved_column_widths(table_width);
+        self.rows
+            .iter()
+            .map(|row| {
+                Self::row_cells(row, &widths)
+                    .map(|(cell, cw)| Self::cell_height(cell, cw, font_data))
+                    .fold(TABLE_MIN_ROW_HEIGHT, f64::max)
+            })
+            .collect()
+    }
+
+    /// `shrink_to_fit` のセルのフォントサイズを、テキストがセルに収まる大きさまで縮小したテーブルを返す
+    ///
+    /// セル幅で折り返した高さが、行内の他のセルで決まる行の高さに収まるまで
+    /// `TABLE_SHRINK_STEP` ずつ縮小します（下限は `TABLE_MIN_SHRINK_FONT_SIZE`、下限でも収まらない場合は行を拡張）。
+    /// 縮小を指定したセルが無い場合は None。
+    pub fn with_shrunk_cells(&self, table_width: f64, font_data: Option<&[u8]>) -> Option<Table> {
+        if !self.rows.iter().flatten().any(|cell| cell.shrink_to_fit) {
+            return None;
+        }
+        let widths = self.resolved_column_widths(table_width);
+        let mut table = self.clone();
+        for (row, shrunk_row) in self.rows.iter().zip(&mut table.rows) {
+            let row_height = Self::row_cells(row, &widths)
+                .filter(|(cell, _)| !cell.shrink_to_fit)
+                .map(|(cell, cw)| Self::cell_height(cell, cw, font_data))
+                .fold(TABLE_MIN_ROW_HEIGHT, f64::max);
+            let mut col = 0usize;
+            for cell in shrunk_row {
+                if cell.col_span == 0 || cell.row_span == 0 {
+                    col += 1;
+                    continue;
+                }
+                let cw = Self::cell_width(&widths, col, cell.col_span);
+                col += cell.col_span as usize;
+                if !cell.shrink_to_fit {
+                    continue;
+                }
+                let min_size = TABLE_MIN_SHRINK_FONT_SIZE.min(cell.style.font_size);
+                while cell.style.font_size > min_size && Self::cell_height(cell, cw, font_data) > row_height {
+                    cell.style.font_size = (cell.style.font_size - TABLE_SHRINK_STEP).max(min_size);
+                }
+                cell.shrink_to_fit = false;
+            }
+        }
+        Some(table)
+    }
+
+    /// 行の高さの合計が `max_height` に収まるように行を分割（ページ分割用）
+    /// 1行だけで `max_height` を超える場合もその行単独で1グループにします。
+    /// `font_data` は行の高さの計測に使用します（描画時と同じフォントを渡します）。
+    pub fn split_rows_by_height(
+        rows: Vec<Vec<TableCell>>,
+        column_widths: &[f64],
+        table_width: f64,
+        max_height: f64,
+        font_data: Option<&[u8]>,
+    ) -> Vec<Vec<Vec<TableCell>>> {
+        let heights = Table {
+            rows: rows.clone(),
+            column_widths: column_widths.to_vec(),
+        }
+        .row_heights(table_width, font_data);
+
+        let mut groups: Vec<Vec<Vec<TableCell>>> = Vec::new();
+        let mut current = Vec::new();
+        let mut used = 0.0;
+        for (row, h) in rows.into_iter().zip(heights) {
+            if !current.is_empty() && used + h > max_height {
+                groups.push(std::mem::take(&mut current));
+                used = 0.0;
+            }
+            used += h;
+            current.push(row);
+        }
+        if !current.is_empty() {
+            groups.push(current);
+        }
+        groups
+    }
+}
+
 /// グラデーション停止点
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GradientStop {
@@ -143,7 +788,7 @@ pub enum GradientType {
 }
 
 /// パスコマンド（SVGライクなパス描画命令）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PathCommand {
     /// 移動 (x, y)
     MoveTo(f64, f64),
@@ -159,6 +804,299 @@ pub enum PathCommand {
     Close,
 }
 
+impl PathCommand {
+    /// 矩形 (x, y, width, height) を囲む閉じたパス
+    pub fn rect(x: f64, y: f64, width: f64, height: f64) -> Vec<PathCommand> {
+        vec![
+            PathCommand::MoveTo(x, y),
+            PathCommand::LineTo(x + width, y),
+            PathCommand::LineTo(x + width, y + height),
+            PathCommand::LineTo(x, y + height),
+            PathCommand::Close,
+        ]
+    }
+
+    /// パスが軸に平行な矩形なら (min_x, min_y, max_x, max_y) を返す（シザー矩形でのクリップ判定用）
+    pub fn axis_aligned_rect(commands: &[PathCommand]) -> Option<(f64, f64, f64, f64)> {
+        let mut points: Vec<(f64, f64)> = Vec::new();
+        for (i, cmd) in commands.iter().enumerate() {
+            match cmd {
+                PathCommand::MoveTo(x, y) if i == 0 => points.push((*x, *y)),
+                PathCommand::LineTo(x, y) if i > 0 => points.push((*x, *y)),
+                PathCommand::Close if i + 1 == commands.len() => {}
+                _ => return None,
+            }
+        }
+        if points.len() == 5 && points[4] == points[0] {
+            points.pop();
+        }
+        if points.len() != 4 {
+            return None;
+        }
+        let axis_aligned = (0..4).all(|i| {
+            let (a, b) = (points[i], points[(i + 1) % 4]);
+            (a.0 == b.0) != (a.1 == b.1)
+        });
+        axis_aligned.then(|| {
+            points.iter().fold((f64::MAX, f64::MAX, f64::MIN, f64::MIN), |r, p| {
+                (r.0.min(p.0), r.1.min(p.1), r.2.max(p.0), r.3.max(p.1))
+            })
+        })
+    }
+
+    /// ArcTo を現在点 `from` から始まる三次ベジェ曲線列 (cx1, cy1, cx2, cy2, x, y) に変換
+    /// SVGの弧の端点表現を中心表現に変換し（SVG 1.1 F.6.5）、90°以下の区間ごとに近似します。
+    /// 半径が0の弧は直線として扱い、ArcTo以外のコマンドや始点と終点が一致する弧は空を返します。
+    pub fn arc_cubics(&self, from: (f64, f64)) -> Vec<[f64; 6]> {
+        let PathCommand::ArcTo(rx, ry, rot_deg, large_arc, sweep, x2, y2) = *self else {
+            return Vec::new();
+        };
+        let (x1, y1) = from;
+        if x1 == x2 && y1 == y2 {
+            return Vec::new();
+        }
+        let (mut rx, mut ry) = (rx.abs(), ry.abs());
+        if rx == 0.0 || ry == 0.0 {
+            return vec![[x1, y1, x2, y2, x2, y2]];
+        }
+        let (sin_phi, cos_phi) = rot_deg.to_radians().sin_cos();
+
+        // 始点を弦の中点基準・楕円軸基準の座標に変換
+        let dx2 = (x1 - x2) / 2.0;
+        let dy2 = (y1 - y2) / 2.0;
+        let x1p = cos_phi * dx2 + sin_phi * dy2;
+        let y1p = -sin_phi * dx2 + cos_phi * dy2;
+
+        // 半径が小さすぎて端点に届かない場合は拡大
+        let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let num = rx * rx * ry * ry - rx * rx * y1p * y1p - ry * ry * x1p * x1p;
+        let den = rx * rx * y1p * y1p + ry * ry * x1p * x1p;
+        let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+        let coef = sign * (num / den).max(0.0).sqrt();
+        let cxp = coef * rx * y1p / ry;
+        let cyp = -coef * ry * x1p / rx;
+        let cx = cos_phi * cxp - sin_phi * cyp + (x1 + x2) / 2.0;
+        let cy = sin_phi * cxp + cos_phi * cyp + (y1 + y2) / 2.0;
+
+        let angle = |ux: f64, uy: f64, vx: f64, vy: f64| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+        let ux = (x1p - cxp) / rx;
+        let uy = (y1p - cyp) / ry;
+        let theta1 = angle(1.0, 0.0, ux, uy);
+        let mut delta = angle(ux, uy, (-x1p - cxp) / rx, (-y1p - cyp) / ry);
+        if !sweep && delta > 0.0 {
+            delta -= 2.0 * std::f64::consts::PI;
+        } else if sweep && delta < 0.0 {
+            delta += 2.0 * std::f64::consts::PI;
+        }
+
+        // 単位円上の点を楕円上の点に変換
+        let map = |ux: f64, uy: f64| {
+            (
+                cx + cos_phi * rx * ux - sin_phi * ry * uy,
+                cy + sin_phi * rx * ux + cos_phi * ry * uy,
+            )
+        };
+        let segments = (delta.abs() / std::f64::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = delta / segments as f64;
+        let k = 4.0 / 3.0 * (step / 4.0).tan();
+        (0..segments)
+            .map(|i| {
+                let a1 = theta1 + step * i as f64;
+                let a2 = a1 + step;
+                let (s1, c1) = a1.sin_cos();
+                let (s2, c2) = a2.sin_cos();
+                let (cp1x, cp1y) = map(c1 - k * s1, s1 + k * c1);
+                let (cp2x, cp2y) = map(c2 + k * s2, s2 - k * c2);
+                let (ex, ey) = if i + 1 == segments { (x2, y2) } else { map(c2, s2) };
+                [cp1x, cp1y, cp2x, cp2y, ex, ey]
+            })
+            .collect()
+    }
+}
+
+/// 線の破線パターン（DrawingMLの `a:prstDash`）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum DashPattern {
+    #[default]
+    Solid,
+    Dot,
+    Dash,
+    LargeDash,
+    DashDot,
+    LargeDashDot,
+    LargeDashDotDot,
+    SystemDash,
+    SystemDot,
+    SystemDashDot,
+    SystemDashDotDot,
+}
+
+impl DashPattern {
+    /// `prstDash` の val 属性から変換（未知の値は実線）
+    pub fn from_preset(val: &str) -> Self {
+        match val {
+            "dot" => Self::Dot,
+            "dash" => Self::Dash,
+            "lgDash" => Self::LargeDash,
+            "dashDot" => Self::DashDot,
+            "lgDashDot" => Self::LargeDashDot,
+            "lgDashDotDot" => Self::LargeDashDotDot,
+            "sysDash" => Self::SystemDash,
+            "sysDot" => Self::SystemDot,
+            "sysDashDot" => Self::SystemDashDot,
+            "sysDashDotDot" => Self::SystemDashDotDot,
+            _ => Self::Solid,
+        }
+    }
+
+    /// 線幅を単位とした描画・空白の長さの繰り返し（実線は空）
+    pub fn unit_lengths(&self) -> &'static [f64] {
+        match self {
+            Self::Solid => &[],
+            Self::Dot => &[1.0, 3.0],
+            Self::Dash => &[4.0, 3.0],
+            Self::LargeDash => &[8.0, 3.0],
+            Self::DashDot => &[4.0, 3.0, 1.0, 3.0],
+            Self::LargeDashDot => &[8.0, 3.0, 1.0, 3.0],
+            Self::LargeDashDotDot => &[8.0, 3.0, 1.0, 3.0, 1.0, 3.0],
+            Self::SystemDash => &[3.0, 1.0],
+            Self::SystemDot => &[1.0, 1.0],
+            Self::SystemDashDot => &[3.0, 1.0, 1.0, 1.0],
+            Self::SystemDashDotDot => &[3.0, 1.0, 1.0, 1.0, 1.0, 1.0],
+        }
+    }
+
+    /// 線幅 `width` での描画・空白の長さ（pt、実線は空）
+    pub fn lengths(&self, width: f64) -> Vec<f64> {
+        let width = width.max(0.5);
+        self.unit_lengths().iter().map(|l| l * width).collect()
+    }
+}
+
+/// 線端の形状（DrawingMLの `a:ln` の cap 属性）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum LineCap {
+    #[default]
+    Flat,
+    Round,
+    Square,
+}
+
+impl LineCap {
+    /// cap 属性（"flat" / "rnd" / "sq"）から変換
+    pub fn from_attr(val: &str) -> Self {
+        match val {
+            "rnd" => Self::Round,
+            "sq" => Self::Square,
+            _ => Self::Flat,
+        }
+    }
+
+    /// PDFの線端スタイル番号（J オペレーター）
+    pub fn pdf_value(&self) -> u8 {
+        match self {
+            Self::Flat => 0,
+            Self::Round => 1,
+            Self::Square => 2,
+        }
+    }
+}
+
+/// 線の角の形状（DrawingMLの `a:miter` / `a:round` / `a:bevel`）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+impl LineJoin {
+    /// PDFの線結合スタイル番号（j オペレーター）
+    pub fn pdf_value(&self) -> u8 {
+        match self {
+            Self::Miter => 0,
+            Self::Round => 1,
+            Self::Bevel => 2,
+        }
+    }
+}
+
+/// 線のスタイル（破線・線端・角）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub struct StrokeStyle {
+    #[serde(default)]
+    pub dash: DashPattern,
+    #[serde(default)]
+    pub cap: LineCap,
+    #[serde(default)]
+    pub join: LineJoin,
+}
+
+impl StrokeStyle {
+    /// 既定（実線・平坦な線端・マイター結合）かどうか
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// 複合線の種類（DrawingMLの `a:ln` の cmpd 属性）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum CompoundLine {
+    #[default]
+    Single,
+    /// 同じ太さの二重線
+    Double,
+    /// 外側が太い二重線
+    ThickThin,
+    /// 内側が太い二重線
+    ThinThick,
+    /// 細・太・細の三重線
+    Triple,
+}
+
+impl CompoundLine {
+    /// cmpd 属性（"sng" / "dbl" / "thickThin" / "thinThick" / "tri"）から変換
+    pub fn from_attr(val: &str) -> Self {
+        match val {
+            "dbl" => Self::Double,
+            "thickThin" => Self::ThickThin,
+            "thinThick" => Self::ThinThick,
+            "tri" => Self::Triple,
+            _ => Self::Single,
+        }
+    }
+
+    /// 線幅 `width` の複合線を構成する各線の（中心線からのずれ, 線幅）
+    /// ずれは外側を正とし、線と間隔の幅の合計が `width` になります。
+    pub fn strokes(&self, width: f64) -> Vec<(f64, f64)> {
+        // 外側から順に並べた線・間隔の比率（偶数番目が線）
+        let ratios: &[f64] = match self {
+            Self::Single => return vec![(0.0, width)],
+            Self::Double => &[1.0, 1.0, 1.0],
+            Self::ThickThin => &[2.0, 1.0, 1.0],
+            Self::ThinThick => &[1.0, 1.0, 2.0],
+            Self::Triple => &[1.0, 1.0, 3.0, 1.0, 1.0],
+        };
+        let unit = width / ratios.iter().sum::<f64>();
+        let mut outer = width / 2.0;
+        let mut strokes = Vec::new();
+        for (i, ratio) in ratios.iter().enumerate() {
+            let w = ratio * unit;
+            if i % 2 == 0 {
+                strokes.push((outer - w / 2.0, w));
+            }
+            outer -= w;
+        }
+        strokes
+    }
+}
+
 /// ページ要素
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PageElement {
@@ -188,6 +1126,9 @@ pub enum PageElement {
         y2: f64,
         width: f64,
         color: Color,
+        /// 破線・線端
+        #[serde(default)]
+        stroke_style: StrokeStyle,
     },
     /// 矩形
     Rect {
@@ -198,6 +1139,9 @@ pub enum PageElement {
         fill: Option<Color>,
         stroke: Option<Color>,
         stroke_width: f64,
+        /// 破線・線端・角
+        #[serde(default)]
+        stroke_style: StrokeStyle,
         #[serde(default)]
         rotation_deg: f64,
     },
@@ -221,6 +1165,9 @@ pub enum PageElement {
         fill: Option<Color>,
         stroke: Option<Color>,
         stroke_width: f64,
+        /// 破線・線端・角
+        #[serde(default)]
+        stroke_style: StrokeStyle,
         #[serde(default)]
         rotation_deg: f64,
     },
@@ -243,6 +1190,9 @@ pub enum PageElement {
         fill: Option<Color>,
         stroke: Option<Color>,
         stroke_width: f64,
+        /// 破線・線端・角
+        #[serde(default)]
+        stroke_style: StrokeStyle,
         #[serde(default)]
         rotation_deg: f64,
     },
@@ -256,6 +1206,16 @@ pub enum PageElement {
         #[serde(default)]
         rotation_deg: f64,
     },
+    /// パスクリップされたグラデーション
+    GradientPath {
+        commands: Vec<PathCommand>,
+        stops: Vec<GradientStop>,
+        gradient_type: GradientType,
+        stroke: Option<Color>,
+        stroke_width: f64,
+        #[serde(default)]
+        rotation_deg: f64,
+    },
     /// テーブル
     TableBlock {
         x: f64,
@@ -263,46 +1223,1445 @@ pub enum PageElement {
         width: f64,
         table: Table,
     },
+    /// クリップ領域付きの要素グループ（子要素は `clip_path` の内側だけが描画される）
+    ClipGroup {
+        clip_path: Vec<PathCommand>,
+        children: Vec<PageElement>,
+    },
+    /// 回転・不透明度を適用する要素グループ（透かし等）
+    /// 子要素を点 (cx, cy) を中心に `rotation_deg` 度（時計回り）回転し、不透明度 `opacity`（0〜1）で描画します。
+    TransformGroup {
+        cx: f64,
+        cy: f64,
+        rotation_deg: f64,
+        opacity: f64,
+        children: Vec<PageElement>,
+    },
 }
 
-/// ページ
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Page {
-    /// 幅（ポイント単位、1pt = 1/72インチ）
-    pub width: f64,
-    /// 高さ（ポイント単位）
-    pub height: f64,
-    /// ページ要素
-    pub elements: Vec<PageElement>,
-}
-
-impl Page {
-    /// A4サイズのページを作成
-    pub fn a4() -> Self {
-        Self {
-            width: 595.28,  // 210mm
-            height: 841.89, // 297mm
-            elements: Vec::new(),
+impl PageElement {
+    /// 線・矩形・楕円・パスの線スタイル（破線・線端・角）
+    pub fn stroke_style(&self) -> Option<&StrokeStyle> {
+        match self {
+            PageElement::Line { stroke_style, .. }
+            | PageElement::Rect { stroke_style, .. }
+            | PageElement::Ellipse { stroke_style, .. }
+            | PageElement::Path { stroke_style, .. } => Some(stroke_style),
+            _ => None,
         }
     }
 
-    /// レターサイズのページを作成
-    pub fn letter() -> Self {
-        Self {
-            width: 612.0,
-            height: 792.0,
-            elements: Vec::new(),
+    /// 要素のバウンディングボックス (min_x, min_y, max_x, max_y)（回転は考慮しない）
+    pub fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        let mut bounds: Option<(f64, f64, f64, f64)> = None;
+        let mut include = |x0: f64, y0: f64, x1: f64, y1: f64| {
+            let b = bounds.get_or_insert((x0, y0, x1, y1));
+            b.0 = b.0.min(x0).min(x1);
+            b.1 = b.1.min(y0).min(y1);
+            b.2 = b.2.max(x0).max(x1);
+            b.3 = b.3.max(y0).max(y1);
+        };
+        match self {
+            PageElement::Text { x, y, width, style, .. } => {
+                include(*x, *y, x + width, y + style.font_size * 1.2)
+            }
+            PageElement::Image { x, y, width, height, .. }
+            | PageElement::Rect { x, y, width, height, .. }
+            | PageElement::GradientRect { x, y, width, height, .. } => {
+                include(*x, *y, x + width, y + height)
+            }
+            PageElement::Line { x1, y1, x2, y2, .. } => include(*x1, *y1, *x2, *y2),
+            PageElement::Ellipse { cx, cy, rx, ry, .. }
+            | PageElement::EllipseImage { cx, cy, rx, ry, .. } => {
+                include(cx - rx, cy - ry, cx + rx, cy + ry)
+            }
+            PageElement::Path { commands, .. }
+            | PageElement::PathImage { commands, .. }
+            | PageElement::GradientPath { commands, .. } => {
+                if let Some((x0, y0, x1, y1)) = path_bounds(commands) {
+                    include(x0, y0, x1, y1);
+                }
+            }
+            PageElement::TableBlock { x, y, width, table } => {
+                let shrunk = table.with_shrunk_cells(*width, None);
+                let table = shrunk.as_ref().unwrap_or(table);
+                let height: f64 = table.row_heights(*width, None).iter().sum();
+                include(*x, *y, x + width, y + height)
+            }
+            PageElement::ClipGroup { clip_path, children } => {
+                // 子要素の範囲をクリップ領域で切り詰める
+                if let (Some(c), Some(clip)) = (union_bounds(children), path_bounds(clip_path)) {
+                    let (x0, y0, x1, y1) = (c.0.max(clip.0), c.1.max(clip.1), c.2.min(clip.2), c.3.min(clip.3));
+                    if x0 <= x1 && y0 <= y1 {
+                        include(x0, y0, x1, y1);
+                    }
+                }
+            }
+            PageElement::TransformGroup { cx, cy, rotation_deg, children, .. } => {
+                // 子要素の範囲の四隅を回転させた範囲
+                if let Some((x0, y0, x1, y1)) = union_bounds(children) {
+                    for (x, y) in [(x0, y0), (x1, y0), (x1, y1), (x0, y1)] {
+                        let (rx, ry) = rotate_point(x, y, *cx, *cy, *rotation_deg);
+                        include(rx, ry, rx, ry);
+                    }
+                }
+            }
         }
+        bounds
     }
-}
 
-/// ドキュメントメタデータ
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    /// 要素が描画に使う色への可変参照（文字色・塗り・線・グラデーションの各色）
+    pub fn colors_mut(&mut self) -> Vec<&mut Color> {
+        match self {
+            PageElement::Text { style, .. } => {
+                let mut colors = vec![&mut style.color];
+                if let Some(underline) = style.underline.as_mut() {
+                    colors.extend(underline.color.as_mut());
+                }
+                colors
+            }
+            PageElement::Line { color, .. } => vec![color],
+            PageElement::Rect { fill, stroke, .. }
+            | PageElement::Ellipse { fill, stroke, .. }
+            | PageElement::Path { fill, stroke, .. } => fill.iter_mut().chain(stroke.iter_mut()).collect(),
+            PageElement::EllipseImage { stroke, .. } | PageElement::PathImage { stroke, .. } => {
+                stroke.iter_mut().collect()
+            }
+            PageElement::GradientRect { stops, .. } => stops.iter_mut().map(|s| &mut s.color).collect(),
+            PageElement::GradientPath { stops, stroke, .. } => {
+                stops.iter_mut().map(|s| &mut s.color).chain(stroke.iter_mut()).collect()
+            }
+            PageElement::Image { .. } => Vec::new(),
+            PageElement::TableBlock { table, .. } => table
+                .rows
+                .iter_mut()
+                .flatten()
+                .flat_map(|cell| {
+                    let borders = cell.borders.iter_mut().flat_map(|b| {
+                        [&mut b.top, &mut b.right, &mut b.bottom, &mut b.left]
+                            .into_iter()
+                            .filter_map(|edge| edge.as_mut().map(|spec| &mut spec.color))
+                    });
+                    std::iter::once(&mut cell.style.color)
+                        .chain(cell.background.as_mut())
+                        .chain(borders)
+                })
+                .collect(),
+            PageElement::ClipGroup { children, .. } | PageElement::TransformGroup { children, .. } => {
+                children.iter_mut().flat_map(PageElement::colors_mut).collect()
+            }
+        }
+    }
+
+    /// 点 (origin_x, origin_y) を基準に要素を `factor` 倍に拡大縮小（座標・寸法・線幅・文字サイズ）
+    pub fn scale_about(&mut self, origin_x: f64, origin_y: f64, factor: f64) {
+        let sx = |x: &mut f64| *x = origin_x + (*x - origin_x) * factor;
+        let sy = |y: &mut f64| *y = origin_y + (*y - origin_y) * factor;
+        let sl = |len: &mut f64| *len *= factor;
+        match self {
+            PageElement::Text { x, y, width, style, .. } => {
+                sx(x);
+                sy(y);
+                sl(width);
+                sl(&mut style.font_size);
+            }
+            PageElement::Image { x, y, width, height, .. } => {
+                sx(x);
+                sy(y);
+                sl(width);
+                sl(height);
+            }
+            PageElement::Line { x1, y1, x2, y2, width, .. } => {
+                sx(x1);
+                sy(y1);
+                sx(x2);
+                sy(y2);
+                sl(width);
+            }
+            PageElement::Rect { x, y, width, height, stroke_width, .. } => {
+                sx(x);
+                sy(y);
+                sl(width);
+                sl(height);
+                sl(stroke_width);
+            }
+            PageElement::GradientRect { x, y, width, height, .. } => {
+                sx(x);
+                sy(y);
+                sl(width);
+                sl(height);
+            }
+            PageElement::Ellipse { cx, cy, rx, ry, stroke_width, .. }
+            | PageElement::EllipseImage { cx, cy, rx, ry, stroke_width, .. } => {
+                sx(cx);
+                sy(cy);
+                sl(rx);
+                sl(ry);
+                sl(stroke_width);
+            }
+            PageElement::Path { commands, stroke_width, .. }
+            | PageElement::PathImage { commands, stroke_width, .. }
+            | PageElement::GradientPath { commands, stroke_width, .. } => {
+                scale_path_about(commands, origin_x, origin_y, factor);
+                sl(stroke_width);
+            }
+            PageElement::TableBlock { x, y, width, table } => {
+                sx(x);
+                sy(y);
+                sl(width);
+                table.column_widths.iter_mut().for_each(sl);
+                for cell in table.rows.iter_mut().flatten() {
+                    sl(&mut cell.style.font_size);
+                    if let Some(borders) = cell.borders.as_mut() {
+                        for spec in [&mut borders.top, &mut borders.right, &mut borders.bottom, &mut borders.left]
+                            .into_iter()
+                            .flatten()
+                        {
+                            sl(&mut spec.width);
+                        }
+                    }
+                }
+            }
+            PageElement::ClipGroup { clip_path, children } => {
+                scale_path_about(clip_path, origin_x, origin_y, factor);
+                for child in children {
+                    child.scale_about(origin_x, origin_y, factor);
+                }
+            }
+            PageElement::TransformGroup { cx, cy, children, .. } => {
+                sx(cx);
+                sy(cy);
+                for child in children {
+                    child.scale_about(origin_x, origin_y, factor);
+                }
+            }
+        }
+    }
+
+    /// 要素を (dx, dy) だけ平行移動
+    pub fn translate(&mut self, dx: f64, dy: f64) {
+        match self {
+            PageElement::Text { x, y, .. }
+            | PageElement::Image { x, y, .. }
+            | PageElement::Rect { x, y, .. }
+            | PageElement::GradientRect { x, y, .. }
+            | PageElement::TableBlock { x, y, .. } => {
+                *x += dx;
+                *y += dy;
+            }
+            PageElement::Line { x1, y1, x2, y2, .. } => {
+                *x1 += dx;
+                *y1 += dy;
+                *x2 += dx;
+                *y2 += dy;
+            }
+            PageElement::Ellipse { cx, cy, .. } | PageElement::EllipseImage { cx, cy, .. } => {
+                *cx += dx;
+                *cy += dy;
+            }
+            PageElement::Path { commands, .. }
+            | PageElement::PathImage { commands, .. }
+            | PageElement::GradientPath { commands, .. } => translate_path(commands, dx, dy),
+            PageElement::ClipGroup { clip_path, children } => {
+                translate_path(clip_path, dx, dy);
+                for child in children {
+                    child.translate(dx, dy);
+                }
+            }
+            PageElement::TransformGroup { cx, cy, children, .. } => {
+                *cx += dx;
+                *cy += dy;
+                for child in children {
+                    child.translate(dx, dy);
+                }
+            }
+        }
+    }
+
+    /// 点 (x, y) でこの要素が塗りつぶす色（下地の判定用、判定できない場合は None）
+    fn fill_color_at(&self, x: f64, y: f64) -> Option<Color> {
+        match self {
+            PageElement::Rect { x: rx, y: ry, width, height, fill: Some(fill), rotation_deg, .. }
+                if *rotation_deg == 0.0 =>
+            {
+                (x >= *rx && x <= rx + width && y >= *ry && y <= ry + height).then_some(*fill)
+            }
+            PageElement::Ellipse { cx, cy, rx, ry, fill: Some(fill), .. } if *rx > 0.0 && *ry > 0.0 => {
+                let (dx, dy) = ((x - cx) / rx, (y - cy) / ry);
+                (dx * dx + dy * dy <= 1.0).then_some(*fill)
+            }
+            PageElement::GradientRect { x: gx, y: gy, width, height, stops, rotation_deg, .. }
+                if *rotation_deg == 0.0 && !stops.is_empty() =>
+            {
+                if !(x >= *gx && x <= gx + width && y >= *gy && y <= gy + height) {
+                    return None;
+                }
+                // 位置によらず各ストップの平均色で近似
+                let n = stops.len() as u32;
+                let avg = |f: fn(&Color) -> u8| (stops.iter().map(|s| f(&s.color) as u32).sum::<u32>() / n) as u8;
+                Some(Color::rgb(avg(|c| c.r), avg(|c| c.g), avg(|c| c.b)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 検索ハイライトの矩形の高さ（フォントサイズ比、`PageElement::bounds` のテキスト行の高さと同じ）
+const HIGHLIGHT_LINE_HEIGHT: f64 = 1.2;
+
+/// ハイライトの矩形 (x, y, width, height)（ページ座標）
+type HighlightRect = (f64, f64, f64, f64);
+
+/// ハイライトの矩形を不透明度付きのグループにまとめる
+fn highlight_group(rects: Vec<HighlightRect>, highlight: &SearchHighlight) -> PageElement {
+    let children = rects
+        .into_iter()
+        .map(|(x, y, width, height)| PageElement::Rect {
+            x,
+            y,
+            width,
+            height,
+            fill: Some(highlight.color),
+            stroke: None,
+            stroke_width: 0.0,
+            stroke_style: StrokeStyle::default(),
+            rotation_deg: 0.0,
+        })
+        .collect();
+    PageElement::TransformGroup {
+        cx: 0.0,
+        cy: 0.0,
+        rotation_deg: 0.0,
+        opacity: highlight.opacity.clamp(0.0, 1.0),
+        children,
+    }
+}
+
+/// 描画時と同じく改行・タブを空白に置き換えたテキスト（文字位置を保つため文字数は変えない）
+fn searchable_text(text: &str) -> String {
+    text.chars().map(|c| if matches!(c, '\n' | '\r' | '\t') { ' ' } else { c }).collect()
+}
+
+/// 要素列の検索に一致したテキストにハイライトを追加した要素列（一致が無ければ None）
+fn highlighted_elements(
+    elements: &[PageElement],
+    highlight: &SearchHighlight,
+    pattern: &crate::search::TextPattern,
+    font_data: Option<&[u8]>,
+) -> Option<Vec<PageElement>> {
+    let mut out = Vec::with_capacity(elements.len());
+    let mut found = false;
+    let mut i = 0;
+    while i < elements.len() {
+        match &elements[i] {
+            PageElement::Text { .. } => {
+                let end = i + same_line_text_count(&elements[i..]);
+                let rects = text_line_highlights(&elements[i..end], pattern, font_data);
+                if !rects.is_empty() {
+                    out.push(highlight_group(rects, highlight));
+                    found = true;
+                }
+                out.extend_from_slice(&elements[i..end]);
+                i = end;
+                continue;
+            }
+            PageElement::TableBlock { x, y, width, table } => {
+                let (under, over) = table_highlights(*x, *y, *width, table, pattern, font_data);
+                found |= !under.is_empty() || !over.is_empty();
+                if !under.is_empty() {
+                    out.push(highlight_group(under, highlight));
+                }
+                out.push(elements[i].clone());
+                if !over.is_empty() {
+                    out.push(highlight_group(over, highlight));
+                }
+            }
+            PageElement::ClipGroup { clip_path, children } => {
+                match highlighted_elements(children, highlight, pattern, font_data) {
+                    Some(children) => {
+                        out.push(PageElement::ClipGroup { clip_path: clip_path.clone(), children });
+                        found = true;
+                    }
+                    None => out.push(elements[i].clone()),
+                }
+            }
+            PageElement::TransformGroup { cx, cy, rotation_deg, opacity, children } => {
+                match highlighted_elements(children, highlight, pattern, font_data) {
+                    Some(children) => {
+                        out.push(PageElement::TransformGroup {
+                            cx: *cx,
+                            cy: *cy,
+                            rotation_deg: *rotation_deg,
+                            opacity: *opacity,
+                            children,
+                        });
+                        found = true;
+                    }
+                    None => out.push(elements[i].clone()),
+                }
+            }
+            other => out.push(other.clone()),
+        }
+        i += 1;
+    }
+    found.then_some(out)
+}
+
+/// 先頭から連続する、同じベースライン上に左から右へ並ぶテキスト要素（同じ行のラン）の数
+fn same_line_text_count(elements: &[PageElement]) -> usize {
+    let baseline = |style: &FontStyle, y: f64| {
+        let (size, top) = style.script_metrics(y);
+        top + size
+    };
+    let mut prev: Option<(f64, f64)> = None;
+    let mut count = 0;
+    for element in elements {
+        let PageElement::Text { x, y, style, .. } = element else { break };
+        let line = (baseline(style, *y), *x);
+        // 上付き・下付きはベースラインがずれるため、文字サイズの半分までのずれは同じ行とみなす
+        if let Some((prev_baseline, prev_x)) = prev {
+            if (line.0 - prev_baseline).abs() > style.font_size * 0.5 || line.1 < prev_x {
+                break;
+            }
+        }
+        prev = Some(line);
+        count += 1;
+    }
+    count.max(1)
+}
+
+/// 同じ行のテキスト要素を連結して検索し、一致ごとの矩形 (x, y, width, height) を返す
+fn text_line_highlights(
+    runs: &[PageElement],
+    pattern: &crate::search::TextPattern,
+    font_data: Option<&[u8]>,
+) -> Vec<HighlightRect> {
+    // 連結したテキストの各文字の左端・右端・上端・下端
+    let mut line = String::new();
+    let mut glyphs: Vec<(f64, f64, f64, f64)> = Vec::new();
+    for run in runs {
+        let PageElement::Text { x, y, text, style, .. } = run else { continue };
+        let (size, top) = style.script_metrics(*y);
+        let text = searchable_text(text);
+        let mut prefix = String::new();
+        let mut left = *x;
+        for ch in text.chars() {
+            prefix.push(ch);
+            let right = x
+                + crate::font_manager::estimate_text_width(&prefix, size, font_data)
+                + style.letter_spacing_width(&prefix);
+            glyphs.push((left, right, top, top + size * HIGHLIGHT_LINE_HEIGHT));
+            left = right + style.letter_spacing;
+        }
+        line.push_str(&text);
+    }
+    pattern
+        .find_all(&line)
+        .into_iter()
+        .map(|(start, end)| {
+            let span = &glyphs[start..end];
+            let left = span[0].0;
+            let right = span.iter().map(|g| g.1).fold(left, f64::max);
+            let top = span.iter().map(|g| g.2).fold(f64::INFINITY, f64::min);
+            let bottom = span.iter().map(|g| g.3).fold(top, f64::max);
+            (left, top, right - left, bottom - top)
+        })
+        .collect()
+}
+
+/// テーブルのセルを折り返し後の行ごとに検索し、一致ごとの矩形を
+/// (テーブルの下に敷くもの, 背景色のあるセルのためテーブルの上に重ねるもの) に分けて返す
+fn table_highlights(
+    x: f64,
+    y: f64,
+    width: f64,
+    table: &Table,
+    pattern: &crate::search::TextPattern,
+    font_data: Option<&[u8]>,
+) -> (Vec<HighlightRect>, Vec<HighlightRect>) {
+    // 描画時と同じく縮小指定のセルは縮小後のフォントサイズで配置
+    let shrunk = table.with_shrunk_cells(width, font_data);
+    let table = shrunk.as_ref().unwrap_or(table);
+    let widths = table.resolved_column_widths(width);
+    let row_heights = table.row_heights(width, font_data);
+    let (mut under, mut over) = (Vec::new(), Vec::new());
+    let mut row_y = y;
+    for (row_idx, row) in table.rows.iter().enumerate() {
+        let mut col = 0usize;
+        let mut cell_x = x;
+        for cell in row {
+            if cell.col_span == 0 || cell.row_span == 0 {
+                cell_x += Table::cell_width(&widths, col, 1);
+                col += 1;
+                continue;
+            }
+            let cell_w = Table::cell_width(&widths, col, cell.col_span);
+            let cell_h: f64 = (0..cell.row_span as usize)
+                .map(|i| row_heights.get(row_idx + i).copied().unwrap_or(row_heights[row_idx]))
+                .sum();
+            let fs = cell.style.font_size;
+            let rects = if cell.background.is_some() { &mut over } else { &mut under };
+            for (line_idx, line) in Table::cell_lines(cell, cell_w, font_data).iter().enumerate() {
+                let line_top = TABLE_CELL_PADDING + line_idx as f64 * fs * TABLE_LINE_SPACING;
+                if line_top >= cell_h {
+                    break;
+                }
+                let text_x = cell_x + TABLE_CELL_PADDING;
+                let chars: Vec<char> = line.chars().collect();
+                let measure = |n: usize| {
+                    crate::font_manager::estimate_text_width(&chars[..n].iter().collect::<String>(), fs, font_data)
+                };
+                for (start, end) in pattern.find_all(&searchable_text(line)) {
+                    let left = text_x + measure(start);
+                    // セル範囲でクリップして描画されるため、矩形もセルの右端までに収める
+                    let right = (text_x + measure(end)).min(cell_x + cell_w);
+                    if right > left {
+                        rects.push((left, row_y + line_top, right - left, fs * HIGHLIGHT_LINE_HEIGHT));
+                    }
+                }
+            }
+            cell_x += cell_w;
+            col += cell.col_span as usize;
+        }
+        row_y += row_heights[row_idx];
+    }
+    (under, over)
+}
+
+/// パスのバウンディングボックス (min_x, min_y, max_x, max_y)（弧のふくらみを含む）
+fn path_bounds(commands: &[PathCommand]) -> Option<(f64, f64, f64, f64)> {
+    let mut bounds: Option<(f64, f64, f64, f64)> = None;
+    let mut include = |x0: f64, y0: f64, x1: f64, y1: f64| {
+        let b = bounds.get_or_insert((x0, y0, x1, y1));
+        b.0 = b.0.min(x0).min(x1);
+        b.1 = b.1.min(y0).min(y1);
+        b.2 = b.2.max(x0).max(x1);
+        b.3 = b.3.max(y0).max(y1);
+    };
+    let mut current = (0.0, 0.0);
+    let mut subpath_start = current;
+    for cmd in commands {
+        match cmd {
+            PathCommand::MoveTo(x, y) | PathCommand::LineTo(x, y) => include(*x, *y, *x, *y),
+            PathCommand::QuadTo(cx, cy, x, y) => include(*cx, *cy, *x, *y),
+            PathCommand::CubicTo(cx1, cy1, cx2, cy2, x, y) => {
+                include(*cx1, *cy1, *cx2, *cy2);
+                include(*x, *y, *x, *y);
+            }
+            PathCommand::ArcTo(_, _, _, _, _, x, y) => {
+                // 弧のふくらみも含めるため、ベジェ近似の制御点を含める
+                for [cx1, cy1, cx2, cy2, ..] in cmd.arc_cubics(current) {
+                    include(cx1, cy1, cx2, cy2);
+                }
+                include(*x, *y, *x, *y);
+            }
+            PathCommand::Close => {}
+        }
+        current = match cmd {
+            PathCommand::MoveTo(x, y) => {
+                subpath_start = (*x, *y);
+                subpath_start
+            }
+            PathCommand::LineTo(x, y)
+            | PathCommand::QuadTo(_, _, x, y)
+            | PathCommand::CubicTo(_, _, _, _, x, y)
+            | PathCommand::ArcTo(_, _, _, _, _, x, y) => (*x, *y),
+            PathCommand::Close => subpath_start,
+        };
+    }
+    bounds
+}
+
+/// 要素群全体を囲むバウンディングボックス
+fn union_bounds(elements: &[PageElement]) -> Option<(f64, f64, f64, f64)> {
+    elements
+        .iter()
+        .filter_map(PageElement::bounds)
+        .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+}
+
+/// 点 (x, y) を (cx, cy) を中心に `degrees` 度（時計回り）回転した座標
+pub fn rotate_point(x: f64, y: f64, cx: f64, cy: f64, degrees: f64) -> (f64, f64) {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (dx, dy) = (x - cx, y - cy);
+    (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos)
+}
+
+/// パスの座標を (dx, dy) だけ平行移動
+fn translate_path(commands: &mut [PathCommand], dx: f64, dy: f64) {
+    for cmd in commands.iter_mut() {
+        match cmd {
+            PathCommand::MoveTo(x, y) | PathCommand::LineTo(x, y) | PathCommand::ArcTo(_, _, _, _, _, x, y) => {
+                *x += dx;
+                *y += dy;
+            }
+            PathCommand::QuadTo(cx, cy, x, y) => {
+                *cx += dx;
+                *cy += dy;
+                *x += dx;
+                *y += dy;
+            }
+            PathCommand::CubicTo(cx1, cy1, cx2, cy2, x, y) => {
+                *cx1 += dx;
+                *cy1 += dy;
+                *cx2 += dx;
+                *cy2 += dy;
+                *x += dx;
+                *y += dy;
+            }
+            PathCommand::Close => {}
+        }
+    }
+}
+
+/// パスの座標と弧の半径を点 (origin_x, origin_y) を基準に `factor` 倍に拡大縮小
+fn scale_path_about(commands: &mut [PathCommand], origin_x: f64, origin_y: f64, factor: f64) {
+    let sx = |x: &mut f64| *x = origin_x + (*x - origin_x) * factor;
+    let sy = |y: &mut f64| *y = origin_y + (*y - origin_y) * factor;
+    let sl = |len: &mut f64| *len *= factor;
+    for cmd in commands.iter_mut() {
+        match cmd {
+            PathCommand::MoveTo(x, y) | PathCommand::LineTo(x, y) => {
+                sx(x);
+                sy(y);
+            }
+            PathCommand::QuadTo(cx, cy, x, y) => {
+                sx(cx);
+                sy(cy);
+                sx(x);
+                sy(y);
+            }
+            PathCommand::CubicTo(cx1, cy1, cx2, cy2, x, y) => {
+                sx(cx1);
+                sy(cy1);
+                sx(cx2);
+                sy(cy2);
+                sx(x);
+                sy(y);
+            }
+            PathCommand::ArcTo(rx, ry, _, _, _, x, y) => {
+                sl(rx);
+                sl(ry);
+                sx(x);
+                sy(y);
+            }
+            PathCommand::Close => {}
+        }
+    }
+}
+
+/// 透かしの内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WatermarkContent {
+    /// テキスト
+    Text {
+        text: String,
+        /// フォント名（未指定時は既定フォント）
+        #[serde(default)]
+        font_name: Option<String>,
+        /// 文字サイズ（pt、未指定時はページ中央を通る回転方向の長さの7割に収まるサイズ）
+        #[serde(default)]
+        font_size: Option<f64>,
+        #[serde(default = "default_watermark_color")]
+        color: Color,
+        #[serde(default)]
+        bold: bool,
+    },
+    /// 画像（PNG / JPEG）
+    Image {
+        data: Vec<u8>,
+        /// ページ幅に対する画像の幅の割合（既定: 0.5）
+        #[serde(default = "default_watermark_scale")]
+        scale: f64,
+    },
+}
+
+/// 透かしを重ねる位置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum WatermarkLayer {
+    /// ページの内容の上
+    #[default]
+    Over,
+    /// ページの内容の下（背景の直上）
+    Under,
+}
+
+/// 出力の各ページに重ねる透かし（ページ中央に配置）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Watermark {
+    pub content: WatermarkContent,
+    /// 不透明度 0〜1（既定: 0.3）
+    #[serde(default = "default_watermark_opacity")]
+    pub opacity: f64,
+    /// 回転角（度、反時計回り。未指定時はテキストが45度、画像が0度）
+    #[serde(default)]
+    pub rotation_deg: Option<f64>,
+    #[serde(default)]
+    pub layer: WatermarkLayer,
+}
+
+fn default_watermark_color() -> Color {
+    Color::rgb(128, 128, 128)
+}
+
+fn default_watermark_scale() -> f64 {
+    0.5
+}
+
+fn default_watermark_opacity() -> f64 {
+    0.3
+}
+
+impl Watermark {
+    /// 既定のスタイル（灰色・不透明度0.3・45度回転・内容の上）のテキスト透かし
+    pub fn text(text: &str) -> Self {
+        Self {
+            content: WatermarkContent::Text {
+                text: text.to_string(),
+                font_name: None,
+                font_size: None,
+                color: default_watermark_color(),
+                bold: false,
+            },
+            opacity: default_watermark_opacity(),
+            rotation_deg: None,
+            layer: WatermarkLayer::default(),
+        }
+    }
+
+    /// 既定のスタイル（ページ幅の半分・不透明度0.3・内容の上）の画像透かし
+    pub fn image(data: Vec<u8>) -> Self {
+        Self {
+            content: WatermarkContent::Image { data, scale: default_watermark_scale() },
+            opacity: default_watermark_opacity(),
+            rotation_deg: None,
+            layer: WatermarkLayer::default(),
+        }
+    }
+}
+
+/// 検索ハイライト（`Page::with_search_highlights`）
+/// 一致した文字の範囲に半透明の矩形を敷きます。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHighlight {
+    /// 検索文字列（大文字・小文字を区別しない）
+    pub query: String,
+    /// `query` を正規表現として扱う（対応する構文は `search` モジュールを参照）
+    #[serde(default)]
+    pub regex: bool,
+    /// ハイライトの色（既定: 黄色）
+    #[serde(default = "default_highlight_color")]
+    pub color: Color,
+    /// 不透明度 0〜1（既定: 0.4）
+    #[serde(default = "default_highlight_opacity")]
+    pub opacity: f64,
+}
+
+fn default_highlight_color() -> Color {
+    Color::rgb(255, 230, 0)
+}
+
+fn default_highlight_opacity() -> f64 {
+    0.4
+}
+
+impl SearchHighlight {
+    /// 既定のスタイル（黄色・不透明度0.4）のハイライト
+    pub fn new(query: &str, regex: bool) -> Self {
+        Self {
+            query: query.to_string(),
+            regex,
+            color: default_highlight_color(),
+            opacity: default_highlight_opacity(),
+        }
+    }
+
+    /// 検索パターンをコンパイル（検索文字列が空、または正規表現が不正な場合はエラー）
+    pub fn pattern(&self) -> Result<crate::search::TextPattern, ConvertError> {
+        crate::search::TextPattern::new(&self.query, self.regex)
+    }
+}
+
+/// ページ番号を配置する位置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum PageNumberPosition {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomCenter,
+    BottomRight,
+}
+
+/// 出力の各ページに付けるページ番号（元のドキュメントのフッターとは別に描画）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PageNumberConfig {
+    pub position: PageNumberPosition,
+    /// 書式（`{n}` をページ番号、`{total}` を総ページ数に置き換え）
+    pub format: String,
+    /// フォント名（未指定時は既定フォント）
+    pub font_name: Option<String>,
+    /// 文字サイズ（pt）
+    pub font_size: f64,
+    pub color: Color,
+    /// ページ端からの余白（pt）
+    pub margin: f64,
+}
+
+impl Default for PageNumberConfig {
+    fn default() -> Self {
+        Self {
+            position: PageNumberPosition::default(),
+            format: "{n} / {total}".to_string(),
+            font_name: None,
+            font_size: 10.0,
+            color: Color::BLACK,
+            margin: 24.0,
+        }
+    }
+}
+
+impl PageNumberConfig {
+    /// `number` ページ目（1始まり）のページ番号の文字列
+    pub fn label(&self, number: usize, total: usize) -> String {
+        self.format.replace("{n}", &number.to_string()).replace("{total}", &total.to_string())
+    }
+}
+
+/// PNG / JPEG 画像のピクセル寸法（幅, 高さ）
+pub(crate) fn image_pixel_size(data: &[u8]) -> Option<(u32, u32)> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") && data.len() >= 24 {
+        let width = u32::from_be_bytes([data[16], data[17], data[18], data[19]]);
+        let height = u32::from_be_bytes([data[20], data[21], data[22], data[23]]);
+        return (width > 0 && height > 0).then_some((width, height));
+    }
+    crate::pdf_writer::extract_jpeg_dimensions(data)
+}
+
+/// ページ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page {
+    /// 幅（ポイント単位、1pt = 1/72インチ）
+    pub width: f64,
+    /// 高さ（ポイント単位）
+    pub height: f64,
+    /// ページ要素
+    pub elements: Vec<PageElement>,
+}
+
+impl Page {
+    /// A4サイズのページを作成
+    pub fn a4() -> Self {
+        Self {
+            width: 595.28,  // 210mm
+            height: 841.89, // 297mm
+            elements: Vec::new(),
+        }
+    }
+
+    /// レターサイズのページを作成
+    pub fn letter() -> Self {
+        Self {
+            width: 612.0,
+            height: 792.0,
+            elements: Vec::new(),
+        }
+    }
+
+    /// 指定した用紙サイズ（縦向き）のページを作成
+    pub fn with_size(size: PaperSize) -> Self {
+        Self::with_size_oriented(size, PageOrientation::Portrait)
+    }
+
+    /// 指定した用紙サイズ・向きのページを作成
+    pub fn with_size_oriented(size: PaperSize, orientation: PageOrientation) -> Self {
+        let (width, height) = size.oriented_dimensions(orientation);
+        Self { width, height, elements: Vec::new() }
+    }
+
+    /// 全要素を囲むバウンディングボックス (min_x, min_y, max_x, max_y) を返す
+    /// 回転は考慮しない概算値です。要素がない場合は None。
+    pub fn content_bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        union_bounds(&self.elements)
+    }
+
+    /// クリップグループ・変換グループを展開した描画要素の一覧（描画順、グループ自体は含まない）
+    pub fn leaf_elements(&self) -> Vec<&PageElement> {
+        fn visit<'a>(elements: &'a [PageElement], out: &mut Vec<&'a PageElement>) {
+            for element in elements {
+                match element {
+                    PageElement::ClipGroup { children, .. } | PageElement::TransformGroup { children, .. } => {
+                        visit(children, out)
+                    }
+                    _ => out.push(element),
+                }
+            }
+        }
+        let mut out = Vec::new();
+        visit(&self.elements, &mut out);
+        out
+    }
+
+    /// テキストと表以外の要素を取り除く（グループ内のテキストは取り出して平坦化）
+    pub fn retain_text(&mut self) {
+        fn visit(elements: Vec<PageElement>, out: &mut Vec<PageElement>) {
+            for element in elements {
+                match element {
+                    PageElement::ClipGroup { children, .. } | PageElement::TransformGroup { children, .. } => {
+                        visit(children, out)
+                    }
+                    PageElement::Text { .. } | PageElement::TableBlock { .. } => out.push(element),
+                    _ => {}
+                }
+            }
+        }
+        let mut out = Vec::new();
+        visit(std::mem::take(&mut self.elements), &mut out);
+        self.elements = out;
+    }
+
+    /// ページ内のテキストを読み順（上から下、左から右）に連結したプレーンテキスト
+    ///
+    /// ほぼ同じ高さにあるテキスト要素は1行にまとめ、行の間は改行で区切ります。
+    /// 空のテキスト要素（空の段落・空行）だけの行は空行として残し、ページ先頭・末尾の空行は省きます。
+    /// テーブルはセルをタブ、行を改行で区切ります。
+    pub fn plain_text(&self) -> String {
+        let mut items: Vec<TextItem> = Vec::new();
+        for element in self.leaf_elements() {
+            match element {
+                PageElement::Text { x, y, text, style, .. } => {
+                    let text = if text.trim().is_empty() { String::new() } else { text.clone() };
+                    items.push(TextItem {
+                        x: *x,
+                        y: *y,
+                        end_x: x + crate::font_manager::estimate_text_width(&text, style.font_size, None),
+                        tolerance: style.font_size * 0.5,
+                        text,
+                    });
+                }
+                PageElement::TableBlock { x, y, table, .. } => {
+                    let rows: Vec<String> = table
+                        .rows
+                        .iter()
+                        .map(|row| row.iter().map(|cell| cell.text.as_str()).collect::<Vec<_>>().join("\t"))
+                        .collect();
+                    items.push(TextItem { x: *x, y: *y, end_x: f64::MAX, tolerance: 0.0, text: rows.join("\n") });
+                }
+                _ => {}
+            }
+        }
+        items.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+
+        // 行ごとにまとめ、行内は左から右へ並べる
+        let mut lines: Vec<Vec<TextItem>> = Vec::new();
+        for item in items {
+            match lines.last_mut() {
+                Some(line) if (item.y - line[0].y).abs() <= line[0].tolerance.min(item.tolerance) => line.push(item),
+                _ => lines.push(vec![item]),
+            }
+        }
+        let lines: Vec<String> = lines
+            .into_iter()
+            .map(|mut line| {
+                line.sort_by(|a, b| a.x.total_cmp(&b.x));
+                let mut text = String::new();
+                let mut prev: Option<&TextItem> = None;
+                for item in line.iter().filter(|item| !item.text.is_empty()) {
+                    // 離れた位置にある要素（別のテキストボックス等）は空白で区切る
+                    if prev.is_some_and(|p| item.x > p.end_x + p.tolerance.max(item.tolerance)) {
+                        text.push(' ');
+                    }
+                    text.push_str(&item.text);
+                    prev = Some(item);
+                }
+                text
+            })
+            .collect();
+        let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+        let end = lines.iter().rposition(|line| !line.is_empty()).map_or(start, |last| last + 1);
+        lines[start..end].join("\n")
+    }
+
+    /// 半透明の要素を下地と合成して不透明色に置き換えたページを返す（透明の平坦化）
+    ///
+    /// 変換グループの不透明度（透かしなど）は子要素の色のアルファに掛け合わせて 1.0 にし、
+    /// 完全に透明な塗り・線・要素は取り除きます。そのうえで描画順に要素をたどり、
+    /// 各要素の中心にある下地の色（それまでに描いた塗りつぶし矩形・楕円・グラデーション、
+    /// 無ければ `background`）とアルファ合成します。
+    /// 画像やパスの下地は考慮せず、半透明のグループ内の画像は不透明のまま描きます。
+    /// 半透明の要素が無い場合は None。
+    pub fn flattened_transparency(&self, background: Color) -> Option<Page> {
+        let mut elements = self.elements.clone();
+        let mut changed = fold_group_opacity(&mut elements, 1.0);
+        changed |= remove_transparent_paint(&mut elements);
+        for i in 0..elements.len() {
+            let (below, rest) = elements.split_at_mut(i);
+            let element = &mut rest[0];
+            if element.colors_mut().iter().all(|c| c.a == 255) {
+                continue;
+            }
+            let backdrop = element
+                .bounds()
+                .and_then(|(x0, y0, x1, y1)| {
+                    let (cx, cy) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+                    below.iter().rev().find_map(|el| el.fill_color_at(cx, cy))
+                })
+                .unwrap_or(background);
+            for color in element.colors_mut() {
+                if color.a < 255 {
+                    *color = color.composite_over(backdrop);
+                }
+            }
+            changed = true;
+        }
+        changed.then_some(Page { width: self.width, height: self.height, elements })
+    }
+
+    /// すべての要素の色を `mode` で変換したページを返す（グレースケール・白黒出力）
+    ///
+    /// 埋め込み画像のビットマップはここでは変換せず、各出力側でデコード時に変換します。
+    /// `ColorMode::Color` の場合は None。
+    pub fn with_color_mode(&self, mode: ColorMode) -> Option<Page> {
+        if mode == ColorMode::Color {
+            return None;
+        }
+        let mut page = self.clone();
+        for element in &mut page.elements {
+            for color in element.colors_mut() {
+                *color = mode.apply(*color);
+            }
+        }
+        Some(page)
+    }
+
+    /// 画像要素を `fit` の方法で配置先の矩形に収め直したページを返す
+    ///
+    /// 画像の縦横比はPNG / JPEGのヘッダーから求め、大きさを判別できない画像はそのまま残します。
+    /// `Cover` では矩形でクリップするグループに拡大した画像を入れます。
+    /// `ImageFit::Stretch` の場合、または収め直す画像が無い場合は None。
+    pub fn with_image_fit(&self, fit: ImageFit) -> Option<Page> {
+        fn refit(elements: &mut [PageElement], fit: ImageFit) -> bool {
+            let mut changed = false;
+            for element in elements {
+                match element {
+                    PageElement::ClipGroup { children, .. } | PageElement::TransformGroup { children, .. } => {
+                        changed |= refit(children, fit);
+                    }
+                    PageElement::Image { x, y, width, height, data, .. } => {
+                        let Some((px_w, px_h)) = image_pixel_size(data) else { continue };
+                        let (rect_x, rect_y, rect_w, rect_h) = (*x, *y, *width, *height);
+                        let (sx, sy) = (rect_w / px_w as f64, rect_h / px_h as f64);
+                        let scale = if fit == ImageFit::Contain { sx.min(sy) } else { sx.max(sy) };
+                        let (new_w, new_h) = (px_w as f64 * scale, px_h as f64 * scale);
+                        if (new_w - rect_w).abs() < 0.01 && (new_h - rect_h).abs() < 0.01 {
+                            continue;
+                        }
+                        *x = rect_x + (rect_w - new_w) / 2.0;
+                        *y = rect_y + (rect_h - new_h) / 2.0;
+                        *width = new_w;
+                        *height = new_h;
+                        if fit == ImageFit::Cover {
+                            let image = std::mem::replace(element, PageElement::ClipGroup {
+                                clip_path: PathCommand::rect(rect_x, rect_y, rect_w, rect_h),
+                                children: Vec::new(),
+                            });
+                            if let PageElement::ClipGroup { children, .. } = element {
+                                children.push(image);
+                            }
+                        }
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+            changed
+        }
+
+        if fit == ImageFit::Stretch {
+            return None;
+        }
+        let mut page = self.clone();
+        refit(&mut page.elements, fit).then_some(page)
+    }
+
+    /// ページが背景（先頭のページ全面の塗り・グラデーション・画像）を持つか
+    pub fn has_background(&self) -> bool {
+        let covers = |x: f64, y: f64, w: f64, h: f64| {
+            x <= 0.01 && y <= 0.01 && x + w >= self.width - 0.01 && y + h >= self.height - 0.01
+        };
+        match self.elements.first() {
+            Some(PageElement::Rect { x, y, width, height, fill: Some(_), rotation_deg, .. }) => {
+                *rotation_deg == 0.0 && covers(*x, *y, *width, *height)
+            }
+            Some(PageElement::GradientRect { x, y, width, height, .. } | PageElement::Image { x, y, width, height, .. }) => {
+                covers(*x, *y, *width, *height)
+            }
+            _ => false,
+        }
+    }
+
+    /// 背景を持たないページの最背面に `color` で塗ったページ全面の矩形を敷いたページを返す
+    ///
+    /// 元のドキュメントで背景が指定されたページ（`has_background`）は上書きしません。
+    /// 背景がある場合、または `color` が完全に透明な場合は None。
+    pub fn with_default_background(&self, color: Color) -> Option<Page> {
+        if color.a == 0 || self.has_background() {
+            return None;
+        }
+        let mut page = self.clone();
+        page.elements.insert(0, PageElement::Rect {
+            x: 0.0,
+            y: 0.0,
+            width: self.width,
+            height: self.height,
+            fill: Some(color),
+            stroke: None,
+            stroke_width: 0.0,
+            stroke_style: StrokeStyle::default(),
+            rotation_deg: 0.0,
+        });
+        Some(page)
+    }
+
+    /// 透かしを重ねたページを返す
+    ///
+    /// 透かしはページ中央を中心に回転・半透明化した変換グループとして、
+    /// 要素の末尾（内容の上）または先頭（内容の下）に追加します。
+    /// `font_data` は文字サイズ未指定のテキスト透かしの幅の計測に使用します。
+    pub fn with_watermark(&self, watermark: &Watermark, font_data: Option<&[u8]>) -> Page {
+        let (cx, cy) = (self.width / 2.0, self.height / 2.0);
+        let (child, default_rotation) = match &watermark.content {
+            WatermarkContent::Text { text, font_name, font_size, color, bold } => {
+                let rotation = watermark.rotation_deg.unwrap_or(45.0);
+                let font_size = font_size.unwrap_or_else(|| {
+                    // ページ中央を通り回転方向に伸びる線分がページに収まる長さ
+                    let (sin, cos) = rotation.to_radians().sin_cos();
+                    let span = (self.width / cos.abs().max(1e-6)).min(self.height / sin.abs().max(1e-6));
+                    let unit_width = crate::font_manager::estimate_text_width(text, 100.0, font_data) / 100.0;
+                    (span * 0.7 / unit_width.max(1e-6)).clamp(8.0, 400.0)
+                });
+                let width = crate::font_manager::estimate_text_width(text, font_size, font_data);
+                let style = FontStyle {
+                    font_name: font_name.clone().unwrap_or_else(|| FontStyle::default().font_name),
+                    font_size,
+                    bold: *bold,
+                    color: *color,
+                    ..FontStyle::default()
+                };
+                let element = PageElement::Text {
+                    x: cx - width / 2.0,
+                    y: cy - font_size * 0.6,
+                    width,
+                    text: text.clone(),
+                    style,
+                    align: TextAlign::Left,
+                };
+                (element, 45.0)
+            }
+            WatermarkContent::Image { data, scale } => {
+                let width = self.width * scale;
+                let height = image_pixel_size(data).map_or(width, |(w, h)| width * h as f64 / w as f64);
+                let mime_type = if data.starts_with(b"\x89PNG") { "image/png" } else { "image/jpeg" };
+                let element = PageElement::Image {
+                    x: cx - width / 2.0,
+                    y: cy - height / 2.0,
+                    width,
+                    height,
+                    data: data.clone(),
+                    mime_type: mime_type.to_string(),
+                };
+                (element, 0.0)
+            }
+        };
+        let group = PageElement::TransformGroup {
+            cx,
+            cy,
+            rotation_deg: -watermark.rotation_deg.unwrap_or(default_rotation),
+            opacity: watermark.opacity.clamp(0.0, 1.0),
+            children: vec![child],
+        };
+        let mut page = self.clone();
+        match watermark.layer {
+            WatermarkLayer::Over => page.elements.push(group),
+            WatermarkLayer::Under => page.elements.insert(0, group),
+        }
+        page
+    }
+
+    /// 検索に一致したテキストの背後に半透明の矩形を敷いたページを返す
+    ///
+    /// 同じ行に並ぶテキスト要素（ランごとの要素）は連結して検索し、
+    /// ランをまたぐ一致は連続した1つの矩形にします。テーブルはセルの折り返し後の行ごとに検索し、
+    /// 背景色のあるセルでは背景に隠れないよう矩形をテーブルの上に重ねます。
+    /// `font_data` はテキスト幅の計測に使用します（描画時と同じフォントを渡します）。
+    /// 一致が無い場合は None。
+    pub fn with_search_highlights(
+        &self,
+        highlight: &SearchHighlight,
+        pattern: &crate::search::TextPattern,
+        font_data: Option<&[u8]>,
+    ) -> Option<Page> {
+        let elements = highlighted_elements(&self.elements, highlight, pattern, font_data)?;
+        Some(Page { width: self.width, height: self.height, elements })
+    }
+
+    /// ページ番号のテキストを末尾に追加したページを返す
+    ///
+    /// `number` は1始まりのページ番号、`total` は総ページ数です。
+    /// `font_data` は左右の位置合わせに使うテキスト幅の計測に使用します。
+    pub fn with_page_number(
+        &self,
+        config: &PageNumberConfig,
+        number: usize,
+        total: usize,
+        font_data: Option<&[u8]>,
+    ) -> Page {
+        let text = config.label(number, total);
+        let width = crate::font_manager::estimate_text_width(&text, config.font_size, font_data);
+        let x = match config.position {
+            PageNumberPosition::TopLeft | PageNumberPosition::BottomLeft => config.margin,
+            PageNumberPosition::TopCenter | PageNumberPosition::BottomCenter => (self.width - width) / 2.0,
+            PageNumberPosition::TopRight | PageNumberPosition::BottomRight => self.width - config.margin - width,
+        };
+        let y = match config.position {
+            PageNumberPosition::TopLeft | PageNumberPosition::TopCenter | PageNumberPosition::TopRight => {
+                config.margin
+            }
+            _ => self.height - config.margin - config.font_size,
+        };
+        let style = FontStyle {
+            font_name: config.font_name.clone().unwrap_or_else(|| FontStyle::default().font_name),
+            font_size: config.font_size,
+            color: config.color,
+            ..FontStyle::default()
+        };
+        let mut page = self.clone();
+        page.elements.push(PageElement::Text { x, y, width, text, style, align: TextAlign::Left });
+        page
+    }
+
+    /// テーブルを含むページのコンテンツを余白内に収まるよう一様に縮小したページを返す
+    ///
+    /// コンテンツ左上の位置を余白とみなし、右・下にも同じ余白が残るように
+    /// 左上を基準に全要素を縮小します。テーブルの最小行高とセル余白は縮小されないため、
+    /// `FitPage` では縮小後の高さを測り直して倍率を補正します。
+    /// テーブルが無い、既に収まっている、または `FitMode::None` の場合は None。
+    pub fn fitted(&self, mode: FitMode) -> Option<Page> {
+        if mode == FitMode::None
+            || !self.elements.iter().any(|el| matches!(el, PageElement::TableBlock { .. }))
+        {
+            return None;
+        }
+        let (min_x, min_y, max_x, max_y) = self.content_bounds()?;
+        let (origin_x, origin_y) = (min_x.max(0.0), min_y.max(0.0));
+        let available_w = self.width - origin_x * 2.0;
+        let available_h = self.height - origin_y * 2.0;
+        let fit_factor = |max_x: f64, max_y: f64| {
+            let mut factor = available_w / (max_x - origin_x);
+            if mode == FitMode::FitPage {
+                factor = factor.min(available_h / (max_y - origin_y));
+            }
+            factor
+        };
+        let mut factor = fit_factor(max_x, max_y);
+        if !(factor > 0.0 && factor < 1.0) {
+            return None;
+        }
+        let mut page = self.clone();
+        for _ in 0..3 {
+            page.elements = self.elements.clone();
+            for element in &mut page.elements {
+                element.scale_about(origin_x, origin_y, factor);
+            }
+            let Some((_, _, max_x, max_y)) = page.content_bounds() else { break };
+            let correction = fit_factor(max_x, max_y);
+            if correction >= 1.0 - 1e-6 || correction <= 0.0 {
+                break;
+            }
+            factor *= correction;
+        }
+        Some(page)
+    }
+
+    /// コンテンツの形状に合わせて縦横を入れ替えたページを返す（自動向き補正）
+    ///
+    /// 縦長ページに横長のコンテンツ（またはその逆）があり、コンテンツの長辺がページの短辺の
+    /// 大半を占める場合に幅と高さを入れ替え、要素全体を時計回りに90°回転した `TransformGroup` で包みます（用紙を回転させたのと同じ配置）。
+    /// 補正が不要な場合は None。
+    pub fn auto_oriented(&self) -> Option<Page> {
+        const ASPECT_THRESHOLD: f64 = 1.3;
+        const MIN_SPAN: f64 = 0.75;
+        let (min_x, min_y, max_x, max_y) = self.content_bounds()?;
+        let content_w = (max_x.min(self.width) - min_x.max(0.0)).max(0.0);
+        let content_h = (max_y.min(self.height) - min_y.max(0.0)).max(0.0);
+        if content_w <= 0.0 || content_h <= 0.0 {
+            return None;
+        }
+        let disagrees = if self.height > self.width {
+            content_w / content_h >= ASPECT_THRESHOLD && content_w >= self.width * MIN_SPAN
+        } else {
+            content_h / content_w >= ASPECT_THRESHOLD && content_h >= self.height * MIN_SPAN
+        };
+        if !disagrees {
+            return None;
+        }
+        // 時計回り90°で (x, y) → (height - y, x) となり、新しいページ（height × width）に収まる
+        let pivot = self.height / 2.0;
+        Some(Page {
+            width: self.height,
+            height: self.width,
+            elements: vec![PageElement::TransformGroup {
+                cx: pivot,
+                cy: pivot,
+                rotation_deg: 90.0,
+                opacity: 1.0,
+                children: self.elements.clone(),
+            }],
+        })
+    }
+}
+
+/// 変換グループの不透明度を子要素の色のアルファに掛け合わせ、グループの不透明度を 1.0 にする
+/// （`Page::flattened_transparency`）。変更があれば true。
+fn fold_group_opacity(elements: &mut [PageElement], factor: f64) -> bool {
+    let mut changed = false;
+    for element in elements {
+        match element {
+            PageElement::TransformGroup { opacity, children, .. } => {
+                let combined = factor * opacity.clamp(0.0, 1.0);
+                changed |= *opacity < 1.0;
+                *opacity = 1.0;
+                changed |= fold_group_opacity(children, combined);
+            }
+            PageElement::ClipGroup { children, .. } => changed |= fold_group_opacity(children, factor),
+            _ if factor < 1.0 => {
+                for color in element.colors_mut() {
+                    color.a = (color.a as f64 * factor).round() as u8;
+                }
+                changed = true;
+            }
+            _ => {}
+        }
+    }
+    changed
+}
+
+/// 完全に透明な塗り・線を外し、描くものが無くなった要素を取り除く
+/// （`Page::flattened_transparency`）。変更があれば true。
+fn remove_transparent_paint(elements: &mut Vec<PageElement>) -> bool {
+    fn clear(paint: &mut Option<Color>) -> bool {
+        let transparent = paint.is_some_and(|c| c.a == 0);
+        if transparent {
+            *paint = None;
+        }
+        transparent
+    }
+    let mut changed = false;
+    elements.retain_mut(|element| {
+        let visible = match element {
+            PageElement::Text { style, .. } => {
+                if let Some(underline) = style.underline.as_mut() {
+                    changed |= clear(&mut underline.color);
+                }
+                style.color.a > 0
+            }
+            PageElement::Line { color, .. } => color.a > 0,
+            PageElement::Rect { fill, stroke, .. }
+            | PageElement::Ellipse { fill, stroke, .. }
+            | PageElement::Path { fill, stroke, .. } => {
+                changed |= clear(fill) | clear(stroke);
+                fill.is_some() || stroke.is_some()
+            }
+            PageElement::EllipseImage { stroke, .. } | PageElement::PathImage { stroke, .. } => {
+                changed |= clear(stroke);
+                true
+            }
+            PageElement::GradientRect { stops, .. } => stops.iter().any(|s| s.color.a > 0),
+            PageElement::GradientPath { stops, stroke, .. } => {
+                changed |= clear(stroke);
+                stops.iter().any(|s| s.color.a > 0) || stroke.is_some()
+            }
+            PageElement::ClipGroup { children, .. } | PageElement::TransformGroup { children, .. } => {
+                changed |= remove_transparent_paint(children);
+                true
+            }
+            PageElement::Image { .. } | PageElement::TableBlock { .. } => true,
+        };
+        changed |= !visible;
+        visible
+    });
+    changed
+}
+
+/// `Page::plain_text` で並べ替えるテキスト片
+struct TextItem {
+    x: f64,
+    y: f64,
+    /// テキストの終端x（概算）
+    end_x: f64,
+    /// 同じ行とみなすy方向の許容差
+    tolerance: f64,
+    text: String,
+}
+
+/// ドキュメントメタデータ
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Metadata {
     pub title: Option<String>,
     pub author: Option<String>,
     pub subject: Option<String>,
     pub creator: Option<String>,
+    /// キーワード（docProps/core.xml の cp:keywords）
+    pub keywords: Option<String>,
+    /// 作成日時（ISO 8601形式, 例: "2024-01-15T10:30:00Z"）
+    pub created: Option<String>,
+}
+
+/// 機能の再現度（変換レポート用）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FeatureSupport {
+    /// そのまま再現
+    Supported,
+    /// 近似して描画（代替の図形・直線配置など）
+    Approximated,
+    /// 描画されない
+    Dropped,
+}
+
+/// ページ内で忠実に再現できなかった機能の記録（`Document::note_feature`）
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureNote {
+    /// ページ番号（1始まり）
+    pub page: usize,
+    /// 機能名（例: "preset geometry: madeUpShape"）
+    pub feature: String,
+    pub support: FeatureSupport,
+}
+
+/// 1ページ分の変換レポート
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PageReport {
+    /// ページ番号（1始まり）
+    pub page: usize,
+    /// そのまま再現した機能（ページ内の要素の種類）
+    pub ok: Vec<String>,
+    /// 近似して描画した機能
+    pub approximated: Vec<String>,
+    /// 描画されなかった機能
+    pub dropped: Vec<String>,
+}
+
+/// 変換レポート（`Document::conversion_report`）
+/// 本番導入前に、入力の機能がどこまで再現されるかをページごとに確認するためのものです。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversionReport {
+    pub pages: Vec<PageReport>,
+    /// ページに結び付かない警告を含む、変換時の全警告
+    pub warnings: Vec<String>,
+    /// 利用できず代替フォントで描画したフォント名
+    pub missing_fonts: Vec<String>,
 }
 
 /// 中間ドキュメント表現
@@ -312,6 +2671,87 @@ pub struct Metadata {
 pub struct Document {
     pub pages: Vec<Page>,
     pub metadata: Metadata,
+    /// 変換・描画時の警告（フォントに無い文字など）
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// ドキュメントに埋め込まれていたフォント（描画時にFontManagerへ登録）
+    #[serde(skip)]
+    pub embedded_fonts: Vec<EmbeddedFont>,
+    /// 近似・省略した機能のページごとの記録（変換レポート用）
+    #[serde(skip)]
+    pub feature_notes: Vec<FeatureNote>,
+}
+
+/// JSON出力（`DocumentEnvelope`）の形式のバージョン
+/// `Document` のJSON表現（要素・フィールドの追加・削除・名称変更）を変えた場合に上げます。
+pub const DOCUMENT_SCHEMA_VERSION: u32 = 1;
+
+/// JSONから読み込むページの幅・高さの上限（ポイント、PDFの用紙サイズの上限 200インチ）
+pub const MAX_PAGE_DIMENSION: f64 = 14_400.0;
+
+/// JSON出力の最上位の形式 `{ "schemaVersion": 1, "document": { "pages": [...], ... } }`
+/// 利用側は `schemaVersion` を確認してからドキュメントモデルを読み取ります。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentEnvelope {
+    /// 形式のバージョン（`DOCUMENT_SCHEMA_VERSION`）
+    pub schema_version: u32,
+    pub document: Document,
+}
+
+impl DocumentEnvelope {
+    /// 現在の形式のバージョンでドキュメントを包む
+    pub fn new(document: Document) -> Self {
+        Self { schema_version: DOCUMENT_SCHEMA_VERSION, document }
+    }
+
+    /// `convertToJson` 形式のJSONからドキュメントを読み込む
+    /// `schemaVersion` が無い、または現在のバージョンと異なる場合や、
+    /// ページの幅・高さが有限の正の値で `MAX_PAGE_DIMENSION` 以下でない場合はエラーにします。
+    pub fn document_from_json(json: &str) -> Result<Document, ConvertError> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| ConvertError::new("json", &format!("JSONの解析エラー: {}", e)))?;
+        match value.get("schemaVersion").and_then(|v| v.as_u64()) {
+            Some(version) if version == DOCUMENT_SCHEMA_VERSION as u64 => {}
+            Some(version) => {
+                return Err(ConvertError::new(
+                    "json",
+                    &format!(
+                        "対応していないJSON形式のバージョンです: {}（対応バージョン: {}）",
+                        version, DOCUMENT_SCHEMA_VERSION
+                    ),
+                ));
+            }
+            None => return Err(ConvertError::new("json", "JSONに schemaVersion がありません")),
+        }
+        let envelope: DocumentEnvelope = serde_json::from_value(value)
+            .map_err(|e| ConvertError::new("json", &format!("ドキュメントモデルの解析エラー: {}", e)))?;
+        let valid = |size: f64| size.is_finite() && size > 0.0 && size <= MAX_PAGE_DIMENSION;
+        if let Some((index, page)) =
+            envelope.document.pages.iter().enumerate().find(|(_, p)| !valid(p.width) || !valid(p.height))
+        {
+            return Err(ConvertError::new(
+                "json",
+                &format!(
+                    "ページ {} の大きさ（{} x {}）が不正です（0より大きく {} ポイント以下）",
+                    index + 1,
+                    page.width,
+                    page.height,
+                    MAX_PAGE_DIMENSION
+                ),
+            ));
+        }
+        Ok(envelope.document)
+    }
+}
+
+/// ドキュメントから抽出した埋め込みフォント
+#[derive(Debug, Clone)]
+pub struct EmbeddedFont {
+    /// フォント名（太字・斜体は " Bold" / " Italic" 付き）
+    pub name: String,
+    /// TrueType / OpenType のバイト列（難読化・EOTヘッダーは除去済み）
+    pub data: Vec<u8>,
 }
 
 impl Document {
@@ -319,34 +2759,171 @@ impl Document {
         Self {
             pages: Vec::new(),
             metadata: Metadata::default(),
+            warnings: Vec::new(),
+            embedded_fonts: Vec::new(),
+            feature_notes: Vec::new(),
+        }
+    }
+
+    /// ページ `page`（1始まり）で近似・省略した機能を記録（同じページの同じ機能は1回だけ）
+    pub fn note_feature(&mut self, page: usize, feature: &str, support: FeatureSupport) {
+        let note = FeatureNote { page, feature: feature.to_string(), support };
+        if !self.feature_notes.contains(&note) {
+            self.feature_notes.push(note);
+        }
+    }
+
+    /// ページごとの機能の再現度をまとめた変換レポート
+    /// そのまま再現した機能はページ内の要素の種類から求め、近似・省略は `feature_notes` から集めます。
+    pub fn conversion_report(&self, missing_fonts: &[String]) -> ConversionReport {
+        let pages = self
+            .pages
+            .iter()
+            .enumerate()
+            .map(|(i, page)| {
+                let mut report = PageReport { page: i + 1, ..Default::default() };
+                let mut add_ok = |feature: &str| {
+                    if !report.ok.iter().any(|f| f == feature) {
+                        report.ok.push(feature.to_string());
+                    }
+                };
+                fn visit(elements: &[PageElement], add_ok: &mut dyn FnMut(&str)) {
+                    for element in elements {
+                        match element {
+                            PageElement::Text { .. } => add_ok("text"),
+                            PageElement::Image { .. } => add_ok("image"),
+                            PageElement::EllipseImage { .. } | PageElement::PathImage { .. } => {
+                                add_ok("image");
+                                add_ok("clipping");
+                            }
+                            PageElement::Line { .. } | PageElement::Rect { .. } | PageElement::Ellipse { .. } => {
+                                add_ok("shape")
+                            }
+                            PageElement::Path { .. } => add_ok("path"),
+                            PageElement::GradientRect { .. } | PageElement::GradientPath { .. } => add_ok("gradient"),
+                            PageElement::TableBlock { .. } => add_ok("table"),
+                            PageElement::ClipGroup { children, .. } => {
+                                add_ok("clipping");
+                                visit(children, add_ok);
+                            }
+                            PageElement::TransformGroup { children, .. } => {
+                                add_ok("rotation / opacity");
+                                visit(children, add_ok);
+                            }
+                        }
+                    }
+                }
+                visit(&page.elements, &mut add_ok);
+                for note in self.feature_notes.iter().filter(|note| note.page == i + 1) {
+                    match note.support {
+                        FeatureSupport::Supported => report.ok.push(note.feature.clone()),
+                        FeatureSupport::Approximated => report.approximated.push(note.feature.clone()),
+                        FeatureSupport::Dropped => report.dropped.push(note.feature.clone()),
+                    }
+                }
+                report
+            })
+            .collect();
+        ConversionReport { pages, warnings: self.warnings.clone(), missing_fonts: missing_fonts.to_vec() }
+    }
+
+    /// ページごとのプレーンテキスト（検索インデックス用、`Page::plain_text`）
+    pub fn extract_text(&self) -> Vec<String> {
+        self.pages.iter().map(Page::plain_text).collect()
+    }
+
+    /// フォントにグリフが無い文字を走査し、コードポイントごとに警告を記録
+    /// フォントが無い場合（簡易描画フォールバック）は何もしません。
+    pub fn record_missing_glyphs(&mut self, font_data: Option<&[u8]>) {
+        let Some(font) = font_data.and_then(|d| ab_glyph::FontRef::try_from_slice(d).ok()) else {
+            return;
+        };
+        let mut missing: Vec<char> = Vec::new();
+        let mut check = |text: &str| {
+            for ch in text.chars() {
+                if !crate::font_manager::font_has_glyph(&font, ch) && !missing.contains(&ch) {
+                    missing.push(ch);
+                }
+            }
+        };
+        for page in &self.pages {
+            for element in page.leaf_elements() {
+                match element {
+                    PageElement::Text { text, .. } => check(text),
+                    PageElement::TableBlock { table, .. } => {
+                        table.rows.iter().flatten().for_each(|cell| check(&cell.text));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for ch in missing {
+            let warning = format!("フォントにグリフがありません: U+{:04X} '{}'", ch as u32, ch);
+            if !self.warnings.contains(&warning) {
+                self.warnings.push(warning);
+            }
         }
     }
 
     /// 単純なテキストドキュメントを作成するヘルパー
     /// テキストをA4ページに自動レイアウトします。
     pub fn from_text_lines(lines: &[String], style: &FontStyle) -> Self {
+        Self::from_text_lines_with_tabs(lines, style, TabStop::DEFAULT_INTERVAL)
+    }
+
+    /// `from_text_lines` と同様ですが、タブ文字を `tab_interval` ごとのタブ位置まで進めて配置します。
+    pub fn from_text_lines_with_tabs(lines: &[String], style: &FontStyle, tab_interval: f64) -> Self {
+        Self::from_text_lines_on_page(lines, style, tab_interval, &Page::a4())
+    }
+
+    /// `from_text_lines_with_tabs` と同様ですが、`blank` と同じ大きさのページに配置します。
+    pub fn from_text_lines_on_page(lines: &[String], style: &FontStyle, tab_interval: f64, blank: &Page) -> Self {
         let margin = 50.0;
         let line_height = style.font_size * 1.5;
-        let page_width = 595.28;
-        let page_height = 841.89;
+        let page_width = blank.width;
+        let page_height = blank.height;
         let usable_height = page_height - margin * 2.0;
         let max_lines_per_page = (usable_height / line_height) as usize;
 
         let mut doc = Document::new();
 
         for chunk in lines.chunks(max_lines_per_page.max(1)) {
-            let mut page = Page::a4();
+            let mut page = blank.clone();
             let mut y = margin;
 
             for line in chunk {
-                page.elements.push(PageElement::Text {
-                    x: margin,
-                    y,
-                    width: page_width - margin * 2.0,
-                    text: line.clone(),
-                    style: style.clone(),
-                    align: TextAlign::Left,
-                });
+                if !line.contains('\t') {
+                    page.elements.push(PageElement::Text {
+                        x: margin,
+                        y,
+                        width: page_width - margin * 2.0,
+                        text: line.clone(),
+                        style: style.clone(),
+                        align: TextAlign::Left,
+                    });
+                    y += line_height;
+                    continue;
+                }
+
+                // タブ区切りの各片を次のタブ位置から配置する
+                let mut x = 0.0;
+                for (i, segment) in line.split('\t').enumerate() {
+                    if i > 0 {
+                        x = TabStop::next(x, &[], tab_interval).position;
+                    }
+                    if segment.is_empty() {
+                        continue;
+                    }
+                    page.elements.push(PageElement::Text {
+                        x: margin + x,
+                        y,
+                        width: (page_width - margin * 2.0 - x).max(0.0),
+                        text: segment.to_string(),
+                        style: style.clone(),
+                        align: TextAlign::Left,
+                    });
+                    x += crate::font_manager::estimate_text_width(segment, style.font_size, None);
+                }
                 y += line_height;
             }
 
@@ -354,7 +2931,7 @@ impl Document {
         }
 
         if doc.pages.is_empty() {
-            doc.pages.push(Page::a4());
+            doc.pages.push(blank.clone());
         }
 
         doc
@@ -380,12 +2957,15 @@ pub fn detect_format(filename: &str) -> Option<&'static str> {
         "rtf" => Some("rtf"),
         "txt" => Some("txt"),
         "epub" => Some("epub"),
+        "html" | "htm" => Some("html"),
         "xps" => Some("xps"),
         "djvu" | "djv" => Some("djvu"),
         "xls" => Some("xls"),
         "xlsx" => Some("xlsx"),
         "ods" => Some("ods"),
         "csv" => Some("csv"),
+        "tsv" => Some("tsv"),
+        "psv" => Some("psv"),
         "ppt" => Some("ppt"),
         "pptx" => Some("pptx"),
         "odp" => Some("odp"),
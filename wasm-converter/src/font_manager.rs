@@ -4,7 +4,11 @@
 // コンパイル時に内蔵するフォント（embed-fontsフィーチャー）と、
 // 実行時に外部から読み込むフォントの両方をサポートします。
 
-use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use std::sync::{Arc, Mutex};
+
+use ab_glyph::{Font, FontRef, FontVec, OutlineCurve, PxScale, ScaleFont};
+
+use crate::converter::{Document, EmbeddedFont, PageElement, PathCommand};
 
 /// 内蔵フォント：Noto Sans JP Regular（サブセット版）
 /// ASCII + Latin-1 + ひらがな + カタカナ + 基本漢字（約500字）を含む
@@ -32,19 +36,100 @@ const CJK_FONT_NAMES: &[&str] = &[
     "Malgun Gothic", "Batang", "Gulim",
 ];
 
+/// フォント名からフォントデータを取得するコールバック（フォントの遅延読み込み用）
+/// 提供できない場合は None を返します。
+/// `FontManager` はページの並列描画でスレッド間に共有するため、リゾルバーは保持せず
+/// `Converter` が保持して `FontManager::request_missing_fonts` に渡します。
+pub type FontResolver = Box<dyn FnMut(&str) -> Option<Vec<u8>>>;
+
+/// 実際の太字・斜体の書体が無いために合成する強調
+/// 太字は輪郭を太らせ、斜体はグリフを `SYNTHETIC_ITALIC_SLANT` の傾きで斜めにして描画します。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SyntheticStyle {
+    pub bold: bool,
+    pub italic: bool,
+}
+
+/// 合成する斜体の傾き（tan 12°）
+pub const SYNTHETIC_ITALIC_SLANT: f64 = 0.2126;
+
+impl SyntheticStyle {
+    /// 合成する強調があるか
+    pub fn is_any(self) -> bool {
+        self.bold || self.italic
+    }
+
+    /// 合成する太字の太らせ幅（フォントサイズに対する割合の線幅）
+    pub fn embolden_width(self, font_size: f64) -> f64 {
+        if self.bold { font_size * 0.04 } else { 0.0 }
+    }
+}
+
+/// 解析済みフォント（cmap・hmtx・glyf等のテーブルを解析した状態）
+/// `FontManager` がフォント名ごとにキャッシュし、同じインスタンスでの変換間で再利用します。
+pub(crate) type DecodedFont = Arc<FontVec>;
+
 /// フォントマネージャー
 /// 利用可能なフォントの管理とフォントデータへのアクセスを提供します。
 /// コンパイル時内蔵フォントと実行時外部フォントの両方を管理します。
 pub struct FontManager {
     /// 外部から読み込まれたフォントデータ（実行時に追加可能）
     external_fonts: Vec<(String, Vec<u8>)>,
+    /// フォント名の別名（要求フォント名, 代替フォント名）
+    aliases: Vec<(String, String)>,
+    /// 変換中のドキュメントに埋め込まれていたフォント（変換ごとに置き換え）
+    document_fonts: Vec<EmbeddedFont>,
+    /// リゾルバーに問い合わせ済みのフォント名（結果に関わらず再度は問い合わせない）
+    requested_fonts: Vec<String>,
+    /// フォント名 → 解析済みフォント（None は解析できなかったもの）
+    /// フォントの追加・削除や別名の変更で破棄します。
+    decoded_fonts: Mutex<Vec<(String, Option<DecodedFont>)>>,
 }
 
 impl FontManager {
     pub fn new() -> Self {
         Self {
             external_fonts: Vec::new(),
+            aliases: Vec::new(),
+            document_fonts: Vec::new(),
+            requested_fonts: Vec::new(),
+            decoded_fonts: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 問い合わせ済みのフォント名の記録をリセット（リゾルバーを替えた場合に使用）
+    pub fn clear_requested_fonts(&mut self) {
+        self.requested_fonts.clear();
+    }
+
+    /// ドキュメントが参照する利用不可フォントをリゾルバーに問い合わせて追加
+    /// 各フォント名につき一度だけ問い合わせ、得られたフォントは外部フォントとして保持します。
+    /// データが無い・フォントとして解析できない場合は従来のフォールバックに任せます。
+    /// 戻り値は追加したフォントの数です。
+    pub fn request_missing_fonts(&mut self, doc: &Document, resolver: &mut FontResolver) -> usize {
+        let mut loaded = 0;
+        for name in self.missing_fonts(doc) {
+            if self.requested_fonts.iter().any(|r| r.eq_ignore_ascii_case(&name)) {
+                continue;
+            }
+            self.requested_fonts.push(name.clone());
+            let data = resolver(&name);
+            if let Some(data) = data.filter(|d| FontRef::try_from_slice(d).is_ok()) {
+                self.add_font(name, data);
+                loaded += 1;
+            }
         }
+        loaded
+    }
+
+    /// ドキュメントの埋め込みフォントを登録（前回の変換のものは破棄）
+    /// 名前が一致する場合、外部フォント・内蔵フォントより優先して使用します。
+    pub fn set_document_fonts(&mut self, fonts: Vec<EmbeddedFont>) {
+        // 埋め込みフォントが無い変換同士では解析済みフォントをそのまま使い回す
+        if !self.document_fonts.is_empty() || !fonts.is_empty() {
+            self.clear_decoded_fonts();
+        }
+        self.document_fonts = fonts;
     }
 
     /// 外部フォントデータを追加（実行時にフォントを読み込み）
@@ -58,11 +143,22 @@ impl FontManager {
         // 同名のフォントが既にある場合は置き換える
         self.external_fonts.retain(|(n, _)| n != &name);
         self.external_fonts.push((name, data));
+        self.clear_decoded_fonts();
     }
 
     /// 外部フォントを削除
     pub fn remove_font(&mut self, name: &str) {
         self.external_fonts.retain(|(n, _)| n != name);
+        self.clear_decoded_fonts();
+    }
+
+    /// フォント名の別名を追加（例: "Calibri" → "Carlito"）
+    /// ドキュメントが`requested`を参照した場合、`provided`のフォントデータを使用します。
+    /// 同じ要求フォント名の別名は置き換えられます。
+    pub fn add_alias(&mut self, requested: String, provided: String) {
+        self.aliases.retain(|(r, _)| !r.eq_ignore_ascii_case(&requested));
+        self.aliases.push((requested, provided));
+        self.clear_decoded_fonts();
     }
 
     /// 外部フォントの数を取得
@@ -114,14 +210,38 @@ impl FontManager {
         self.builtin_japanese_font()
     }
 
+    /// ab_glyphでパース可能なフォントデータを取得（外部フォント → 内蔵フォント → LINE Seed JP の順）
+    /// PDFに埋め込むフォントと、グリフ欠落の警告・透かしなどの計測に使うフォントはこれで揃えます。
+    pub fn usable_font_data(&self) -> Option<&[u8]> {
+        let external = self.external_fonts.iter().map(|(_, data)| data.as_slice());
+        let builtin = [self.builtin_japanese_font(), self.builtin_line_seed_jp()].into_iter().flatten();
+        external.chain(builtin).find(|data| !data.is_empty() && FontRef::try_from_slice(data).is_ok())
+    }
+
     /// 外部フォントのイテレータを返す
-    /// PDF生成時にab_glyphでパース可能なフォントを検索するために使用
     pub fn external_fonts_iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
         self.external_fonts.iter().map(|(name, data)| (name.as_str(), data.as_slice()))
     }
 
     /// 名前でフォントデータを取得
+    /// 別名が登録されていれば、代替フォント名での検索を優先します。
     pub fn get_font_data(&self, name: &str) -> Option<&[u8]> {
+        let alias = self
+            .aliases
+            .iter()
+            .find(|(requested, _)| requested.eq_ignore_ascii_case(name));
+        if let Some(data) = alias.and_then(|(_, provided)| self.lookup_font_data(provided)) {
+            return Some(data);
+        }
+        self.lookup_font_data(name)
+    }
+
+    /// 別名を考慮せずに名前でフォントデータを検索
+    fn lookup_font_data(&self, name: &str) -> Option<&[u8]> {
+        // ドキュメントの埋め込みフォントを最優先（大文字小文字を無視した完全一致）
+        if let Some(font) = self.document_fonts.iter().find(|f| f.name.eq_ignore_ascii_case(name)) {
+            return Some(font.data.as_slice());
+        }
         // まず外部フォントを検索（完全一致）
         for (font_name, data) in &self.external_fonts {
             if font_name == name {
@@ -163,12 +283,90 @@ impl FontManager {
         self.best_font_data()
     }
 
+    /// フォント名を解決して解析済みフォントを取得（`resolve_font` → `best_font_data` の順）
+    /// 初回のみフォントを解析し、以降は同じ名前・同じデータのフォントでキャッシュを共有します。
+    pub(crate) fn decoded_font(&self, name: &str) -> Option<DecodedFont> {
+        let mut cache = self.decoded_fonts.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, font)) = cache.iter().find(|(n, _)| n == name) {
+            return font.clone();
+        }
+        let data = self.resolve_font(name).or_else(|| self.best_font_data());
+        let font = data.and_then(|data| {
+            // 別名・フォールバックで同じデータに解決された解析済みフォントを共有
+            cache
+                .iter()
+                .filter_map(|(_, f)| f.as_ref())
+                .find(|f| f.as_slice() == data)
+                .cloned()
+                .or_else(|| FontVec::try_from_vec(data.to_vec()).ok().map(Arc::new))
+        });
+        cache.push((name.to_string(), font.clone()));
+        font
+    }
+
+    /// 解析済みフォントのキャッシュを破棄
+    fn clear_decoded_fonts(&mut self) {
+        self.decoded_fonts.get_mut().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// ドキュメントで参照されているが利用できないフォント名の一覧
+    /// 別名を解決しても見つからず、フォールバックフォントで描画されるフォント名を
+    /// 出現順に重複なく返します。
+    pub fn missing_fonts(&self, doc: &Document) -> Vec<String> {
+        let mut missing: Vec<String> = Vec::new();
+        let mut check = |name: &str| {
+            if !name.is_empty()
+                && self.get_font_data(name).is_none()
+                && !missing.iter().any(|m| m == name)
+            {
+                missing.push(name.to_string());
+            }
+        };
+        for page in &doc.pages {
+            for element in page.leaf_elements() {
+                match element {
+                    PageElement::Text { style, .. } => check(&style.font_name),
+                    PageElement::TableBlock { table, .. } => {
+                        table.rows.iter().flatten().for_each(|cell| check(&cell.style.font_name));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        missing
+    }
+
+    /// 太字・斜体のランを描画する書体名と、書体が無いために合成が必要な強調
+    /// `"{名前} Bold Italic"` / `"{名前} Bold"` / `"{名前} Italic"` の順に、
+    /// 同じ名前の埋め込みフォント・外部フォントがあればその書体を使います。
+    pub fn style_face(&self, name: &str, bold: bool, italic: bool) -> (String, SyntheticStyle) {
+        let candidates = [
+            (bold && italic, " Bold Italic", SyntheticStyle::default()),
+            (bold, " Bold", SyntheticStyle { bold: false, italic }),
+            (italic, " Italic", SyntheticStyle { bold, italic: false }),
+        ];
+        for (wanted, suffix, remaining) in candidates {
+            let face = format!("{}{}", name, suffix);
+            if wanted && self.has_face(&face) {
+                return (face, remaining);
+            }
+        }
+        (name.to_string(), SyntheticStyle { bold, italic })
+    }
+
+    /// 名前が完全に一致する埋め込みフォント・外部フォントがあるか（部分一致・代替フォントは含めない）
+    fn has_face(&self, name: &str) -> bool {
+        self.document_fonts.iter().any(|font| font.name.eq_ignore_ascii_case(name))
+            || self.external_fonts.iter().any(|(font_name, _)| font_name.eq_ignore_ascii_case(name))
+    }
+
     /// フォント名のリストを取得
     pub fn available_fonts(&self) -> Vec<String> {
         let mut fonts: Vec<String> = self
-            .external_fonts
+            .document_fonts
             .iter()
-            .map(|(name, _)| name.clone())
+            .map(|font| font.name.clone())
+            .chain(self.external_fonts.iter().map(|(name, _)| name.clone()))
             .collect();
         if !NOTO_SANS_JP_REGULAR.is_empty() {
             fonts.push("NotoSansJP-Regular".to_string());
@@ -196,6 +394,20 @@ fn is_cjk_font_name(name: &str) -> bool {
     })
 }
 
+/// フォントが文字のグリフを持つかどうか（空白・制御文字は常に true）
+pub fn font_has_glyph(font: &impl Font, ch: char) -> bool {
+    ch.is_whitespace() || ch.is_control() || font.glyph_id(ch).0 != 0
+}
+
+/// グリフが無い文字の送り幅（半角は0.6em、それ以外は1em）
+pub fn missing_glyph_advance(ch: char, font_size: f64) -> f64 {
+    if ch.is_ascii() {
+        font_size * 0.6
+    } else {
+        font_size
+    }
+}
+
 /// テキストの幅を概算するヘルパー関数
 /// フォントが利用可能な場合はab_glyphで正確に計測、
 /// そうでない場合はフォントサイズベースで概算します。
@@ -225,4 +437,142 @@ pub fn estimate_text_width(text: &str, font_size: f64, font_data: Option<&[u8]>)
     width
 }
 
+/// テキストのグリフの輪郭をパスコマンド（ページ座標、Y軸下向き）に変換し、送り幅の合計とともに返す
+/// グリフの大きさと送り幅は `estimate_text_width` と同じ縮尺（`PxScale`）で求め、
+/// 文字間隔は各文字の後に加算します。`slant` が0以外なら、ベースライン `baseline_y` からの
+/// 高さに比例して右へずらして斜体にします。グリフの無い文字・空白は送り幅だけ進めます。
+pub(crate) fn text_outline_commands(
+    font: &impl Font,
+    text: &str,
+    x: f64,
+    baseline_y: f64,
+    font_size: f64,
+    letter_spacing: f64,
+    slant: f64,
+) -> (Vec<PathCommand>, f64) {
+    let scaled = font.as_scaled(PxScale::from(font_size as f32));
+    let factor = scaled.scale_factor();
+    let mut commands = Vec::new();
+    let mut cursor_x = x;
+    for ch in text.chars() {
+        let glyph_id = font.glyph_id(ch);
+        let outline = if ch.is_whitespace() { None } else { font.outline(glyph_id) };
+        if let Some(outline) = outline {
+            // フォント単位（Y軸上向き）からページ座標へ
+            let point = |p: ab_glyph::Point| {
+                let height = (p.y * factor.vertical) as f64;
+                (cursor_x + (p.x * factor.horizontal) as f64 + height * slant, baseline_y - height)
+            };
+            let mut last: Option<ab_glyph::Point> = None;
+            for curve in &outline.curves {
+                let (start, end) = match *curve {
+                    OutlineCurve::Line(p0, p1) => (p0, p1),
+                    OutlineCurve::Quad(p0, _, p2) => (p0, p2),
+                    OutlineCurve::Cubic(p0, _, _, p3) => (p0, p3),
+                };
+                // 前の曲線の終点から続かない曲線は新しい輪郭の始まり
+                if last != Some(start) {
+                    if last.is_some() {
+                        commands.push(PathCommand::Close);
+                    }
+                    let (sx, sy) = point(start);
+                    commands.push(PathCommand::MoveTo(sx, sy));
+                }
+                commands.push(match *curve {
+                    OutlineCurve::Line(_, p1) => {
+                        let (x1, y1) = point(p1);
+                        PathCommand::LineTo(x1, y1)
+                    }
+                    OutlineCurve::Quad(_, p1, p2) => {
+                        let ((x1, y1), (x2, y2)) = (point(p1), point(p2));
+                        PathCommand::QuadTo(x1, y1, x2, y2)
+                    }
+                    OutlineCurve::Cubic(_, p1, p2, p3) => {
+                        let ((x1, y1), (x2, y2), (x3, y3)) = (point(p1), point(p2), point(p3));
+                        PathCommand::CubicTo(x1, y1, x2, y2, x3, y3)
+                    }
+                });
+                last = Some(end);
+            }
+            if last.is_some() {
+                commands.push(PathCommand::Close);
+            }
+        }
+        cursor_x += scaled.h_advance(glyph_id) as f64 + letter_spacing;
+    }
+    (commands, cursor_x - x)
+}
+
+/// テキストを指定幅で折り返す
+/// 改行文字で段落を分け、幅を超える場合は直前の空白で、
+/// 空白が無い場合（日本語など）は文字単位で折り返します。
+pub fn wrap_text_to_width(text: &str, max_width: f64, font_size: f64, font_data: Option<&[u8]>) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        if max_width <= 0.0 || estimate_text_width(paragraph, font_size, font_data) <= max_width {
+            lines.push(paragraph.to_string());
+            continue;
+        }
+        let mut current = String::new();
+        for ch in paragraph.chars() {
+            current.push(ch);
+            if current.chars().count() > 1 && estimate_text_width(&current, font_size, font_data) > max_width {
+                current.pop();
+                // 単語の途中であれば直前の空白で折り返す
+                let (line, rest) = match current.rfind(' ') {
+                    Some(pos) if pos > 0 && !ch.is_whitespace() => {
+                        (current[..pos].to_string(), current[pos + 1..].to_string())
+                    }
+                    _ => (current.clone(), String::new()),
+                };
+                lines.push(line.trim_end().to_string());
+                current = rest;
+                if !(ch.is_whitespace() && current.is_empty()) {
+                    current.push(ch);
+                }
+            }
+        }
+        lines.push(current);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::TextFlowOptions;
+
+    #[test]
+    fn test_decoded_font_reused_across_conversions() {
+        let mut fm = FontManager::new();
+        fm.add_font("CacheSans".to_string(), NOTO_SANS_JP_REGULAR.to_vec());
+        let options = TextFlowOptions { default_font: Some("CacheSans".to_string()), ..TextFlowOptions::default() };
+
+        let mut decoded = Vec::new();
+        for _ in 0..2 {
+            let mut doc =
+                crate::formats::convert_by_extension_with_options("txt", "キャッシュ".as_bytes(), &options).unwrap();
+            fm.set_document_fonts(std::mem::take(&mut doc.embedded_fonts));
+            crate::image_renderer::render_to_images_zip(&doc, &fm);
+            decoded.push(fm.decoded_font("CacheSans").unwrap());
+        }
+        // 2回目の変換は1回目に解析したフォントをそのまま使う
+        assert!(Arc::ptr_eq(&decoded[0], &decoded[1]));
+        // フォールバックで同じデータに解決される名前も解析済みフォントを共有する
+        assert!(Arc::ptr_eq(&decoded[0], &fm.decoded_font("Unknown Font").unwrap()));
 
+        // フォントを追加するとキャッシュは破棄される
+        fm.add_font("Other".to_string(), NOTO_SANS_JP_REGULAR.to_vec());
+        assert!(!Arc::ptr_eq(&decoded[0], &fm.decoded_font("CacheSans").unwrap()));
+    }
+
+    #[test]
+    fn test_usable_font_data_skips_unparsable_fonts() {
+        let mut fm = FontManager::new();
+        fm.add_font("Broken".to_string(), b"not a font".to_vec());
+        fm.add_font("Valid".to_string(), NOTO_SANS_JP_REGULAR.to_vec());
+        // パースできない外部フォントは飛ばす（`best_font_data` は先頭の外部フォントをそのまま返す）
+        assert_eq!(fm.best_font_data(), Some(&b"not a font"[..]));
+        assert_eq!(fm.usable_font_data(), Some(&NOTO_SANS_JP_REGULAR[..]));
+    }
+}
@@ -0,0 +1,94 @@
+// formats/assets.rs - 埋め込みアセット一覧モジュール
+//
+// OOXML / ODF などZIPベースのドキュメントから、埋め込みフォントと画像の
+// パーツを変換せずに列挙します。
+
+use crate::converter::ConvertError;
+use serde::Serialize;
+
+/// 埋め込みアセットの種類
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetKind {
+    Font,
+    Image,
+}
+
+/// ドキュメントに埋め込まれたアセット
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddedAsset {
+    /// アーカイブ内のパス（例: "ppt/media/image1.png"）
+    pub name: String,
+    pub kind: AssetKind,
+    pub mime_type: String,
+    /// 展開後のバイト数
+    pub size: u64,
+}
+
+/// フォーマットごとの埋め込みフォント・画像の格納フォルダー
+fn asset_folders(ext: &str) -> Option<&'static [(&'static str, AssetKind)]> {
+    match ext {
+        "pptx" => Some(&[("ppt/fonts/", AssetKind::Font), ("ppt/media/", AssetKind::Image)]),
+        "docx" => Some(&[("word/fonts/", AssetKind::Font), ("word/media/", AssetKind::Image)]),
+        "xlsx" => Some(&[("xl/media/", AssetKind::Image)]),
+        "odt" | "odp" | "ods" => Some(&[("Fonts/", AssetKind::Font), ("Pictures/", AssetKind::Image)]),
+        _ => None,
+    }
+}
+
+/// ドキュメントの埋め込みフォントと画像を列挙
+/// ZIPベースでないフォーマット（TXT / CSV 等）は空の一覧を返します。
+pub fn list_embedded_assets(ext: &str, data: &[u8]) -> Result<Vec<EmbeddedAsset>, ConvertError> {
+    let ext = ext.to_lowercase();
+    let Some(folders) = asset_folders(&ext) else {
+        return Ok(Vec::new());
+    };
+
+    let cursor = std::io::Cursor::new(data);
+    let mut archive = zip::ZipArchive::new(cursor).map_err(|e| {
+        ConvertError::new(&ext.to_uppercase(), &format!("ZIPアーカイブを開けません: {}", e))
+    })?;
+
+    let mut assets = Vec::new();
+    for i in 0..archive.len() {
+        let Ok(entry) = archive.by_index_raw(i) else { continue };
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let Some(&(_, kind)) = folders.iter().find(|(folder, _)| name.starts_with(folder)) else {
+            continue;
+        };
+        assets.push(EmbeddedAsset {
+            mime_type: asset_mime(&name).to_string(),
+            size: entry.size(),
+            name,
+            kind,
+        });
+    }
+    Ok(assets)
+}
+
+/// 拡張子からアセットのMIMEタイプを推定
+pub(crate) fn asset_mime(name: &str) -> &'static str {
+    let ext = name.rsplit('.').next().unwrap_or("").to_lowercase();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "emf" => "image/emf",
+        "wmf" => "image/wmf",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        // PowerPoint の埋め込みフォント / Word の難読化フォント
+        "fntdata" => "application/x-fontdata",
+        "odttf" => "application/vnd.openxmlformats-officedocument.obfuscatedFont",
+        _ => "application/octet-stream",
+    }
+}
@@ -73,6 +73,7 @@ pub fn render_chart(
                     stroke: Some(Color::rgb(200, 200, 200)),
                     stroke_width: 1.0,
                     rotation_deg: 0.0,
+                    stroke_style: Default::default(),
                 },
                 PageElement::Text {
                     x: x + 10.0,
@@ -308,6 +309,7 @@ fn render_chart_def(
         stroke: Some(Color::rgb(200, 200, 200)),
         stroke_width: 0.5,
         rotation_deg: 0.0,
+        stroke_style: Default::default(),
     });
 
     let margin = 20.0;
@@ -388,10 +390,12 @@ fn render_bar_chart(
     elements.push(PageElement::Line {
         x1: x, y1: y + h, x2: x + w, y2: y + h,
         width: 1.0, color: Color::rgb(128, 128, 128),
+        stroke_style: Default::default(),
     });
     elements.push(PageElement::Line {
         x1: x, y1: y, x2: x, y2: y + h,
         width: 1.0, color: Color::rgb(128, 128, 128),
+        stroke_style: Default::default(),
     });
 
     // Draw gridlines
@@ -401,6 +405,7 @@ fn render_bar_chart(
         elements.push(PageElement::Line {
             x1: x, y1: gy, x2: x + w, y2: gy,
             width: 0.3, color: Color::rgb(220, 220, 220),
+            stroke_style: Default::default(),
         });
         // Y-axis label
         let label_val = max_val * i as f64 / num_gridlines as f64;
@@ -442,6 +447,7 @@ fn render_bar_chart(
                         stroke: None,
                         stroke_width: 0.0,
                         rotation_deg: 0.0,
+                        stroke_style: Default::default(),
                     });
                 }
             }
@@ -463,6 +469,7 @@ fn render_bar_chart(
                         stroke: None,
                         stroke_width: 0.0,
                         rotation_deg: 0.0,
+                        stroke_style: Default::default(),
                     });
                 }
             }
@@ -513,6 +520,7 @@ fn render_pie_chart(
                 stroke: None,
                 stroke_width: 0.0,
                 rotation_deg: 0.0,
+                stroke_style: Default::default(),
             });
         }
     }
@@ -548,10 +556,12 @@ fn render_pie_chart(
                 elements.push(PageElement::Line {
                     x1: cx, y1: cy, x2: x1, y2: y1,
                     width: 2.0, color,
+                    stroke_style: Default::default(),
                 });
                 elements.push(PageElement::Line {
                     x1: x1, y1: y1, x2: x2, y2: y2,
                     width: 2.0, color,
+                    stroke_style: Default::default(),
                 });
             }
 
@@ -575,6 +585,7 @@ fn render_pie_chart(
                         stroke: None,
                         stroke_width: 0.0,
                         rotation_deg: 0.0,
+                        stroke_style: Default::default(),
                     });
                 }
             }
@@ -610,6 +621,7 @@ fn render_pie_chart(
         stroke: Some(Color::rgb(100, 100, 100)),
         stroke_width: 0.5,
         rotation_deg: 0.0,
+        stroke_style: Default::default(),
     });
 }
 
@@ -638,10 +650,12 @@ fn render_area_chart(
     elements.push(PageElement::Line {
         x1: x, y1: y + h, x2: x + w, y2: y + h,
         width: 1.0, color: Color::rgb(128, 128, 128),
+        stroke_style: Default::default(),
     });
     elements.push(PageElement::Line {
         x1: x, y1: y, x2: x, y2: y + h,
         width: 1.0, color: Color::rgb(128, 128, 128),
+        stroke_style: Default::default(),
     });
 
     // Draw gridlines
@@ -651,6 +665,7 @@ fn render_area_chart(
         elements.push(PageElement::Line {
             x1: x, y1: gy, x2: x + w, y2: gy,
             width: 0.3, color: Color::rgb(220, 220, 220),
+            stroke_style: Default::default(),
         });
     }
 
@@ -686,6 +701,7 @@ fn render_area_chart(
                 stroke: None,
                 stroke_width: 0.0,
                 rotation_deg: 0.0,
+                stroke_style: Default::default(),
             });
         }
 
@@ -701,6 +717,7 @@ fn render_area_chart(
             elements.push(PageElement::Line {
                 x1: px1, y1: py1, x2: px2, y2: py2,
                 width: 1.5, color: ser.color,
+                stroke_style: Default::default(),
             });
         }
     }
@@ -746,10 +763,12 @@ fn render_line_chart(
     elements.push(PageElement::Line {
         x1: x, y1: y + h, x2: x + w, y2: y + h,
         width: 1.0, color: Color::rgb(128, 128, 128),
+        stroke_style: Default::default(),
     });
     elements.push(PageElement::Line {
         x1: x, y1: y, x2: x, y2: y + h,
         width: 1.0, color: Color::rgb(128, 128, 128),
+        stroke_style: Default::default(),
     });
 
     // Draw gridlines
@@ -759,6 +778,7 @@ fn render_line_chart(
         elements.push(PageElement::Line {
             x1: x, y1: gy, x2: x + w, y2: gy,
             width: 0.3, color: Color::rgb(220, 220, 220),
+            stroke_style: Default::default(),
         });
     }
 
@@ -777,6 +797,7 @@ fn render_line_chart(
             elements.push(PageElement::Line {
                 x1: px1, y1: py1, x2: px2, y2: py2,
                 width: 2.0, color: ser.color,
+                stroke_style: Default::default(),
             });
         }
 
@@ -790,6 +811,7 @@ fn render_line_chart(
                 stroke: Some(Color::WHITE),
                 stroke_width: 1.0,
                 rotation_deg: 0.0,
+                stroke_style: Default::default(),
             });
         }
     }
@@ -812,6 +834,7 @@ fn render_legend(
             stroke: None,
             stroke_width: 0.0,
             rotation_deg: 0.0,
+            stroke_style: Default::default(),
         });
         // Label
         elements.push(PageElement::Text {
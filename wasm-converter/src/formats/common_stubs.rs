@@ -1,6 +1,6 @@
 // formats/common_stubs.rs - 未実装フォーマットのスタブ
 //
-// 複雑なバイナリフォーマット（DOC, PPT, XPS, DjVu等）については、
+// 複雑なバイナリフォーマット（DOC, XPS, DjVu等）については、
 // 将来の実装に向けたスタブを提供します。
 
 use crate::converter::{
@@ -50,6 +50,7 @@ impl DocumentConverter for StubConverter {
             y2: margin + 30.0,
             width: 1.0,
             color: Color::rgb(100, 100, 100),
+            stroke_style: Default::default(),
         });
 
         page.elements.push(PageElement::Text {
@@ -0,0 +1,80 @@
+// formats/core_properties.rs - OOXMLの文書プロパティ読み込みモジュール
+//
+// DOCX / XLSX / PPTX 共通の docProps/core.xml（Dublin Core）からタイトル・作成者・件名・
+// キーワード・作成日時を読み取り、ドキュメントのメタデータにします。
+
+use crate::converter::Metadata;
+
+/// PDFの Creator に記録するアプリケーション名
+pub(crate) const CREATOR: &str = "WASM Document Converter";
+
+/// パッケージの docProps/core.xml からメタデータを読み込み
+/// core.xml が無い・読めない場合は Creator だけを設定したメタデータを返します。
+pub(crate) fn read_core_properties(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>) -> Metadata {
+    let core_xml = archive.by_name("docProps/core.xml").ok().and_then(|mut file| {
+        let mut content = String::new();
+        super::limits::read_limited_string(&mut file, &mut content).ok()?;
+        Some(content)
+    });
+    let mut metadata = core_xml.map(|xml| parse_core_properties(&xml)).unwrap_or_default();
+    metadata.creator = Some(CREATOR.to_string());
+    metadata
+}
+
+/// core.xml の内容を解析
+fn parse_core_properties(core_xml: &str) -> Metadata {
+    let mut metadata = Metadata::default();
+    let mut reader = quick_xml::Reader::from_str(core_xml);
+    let mut buf = Vec::new();
+    let mut current_tag = String::new();
+    let mut in_tag = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) => {
+                current_tag = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                in_tag = true;
+            }
+            Ok(quick_xml::events::Event::Text(ref e)) if in_tag => {
+                if let Ok(text) = e.unescape() {
+                    match current_tag.as_str() {
+                        "title" => metadata.title = Some(text.to_string()),
+                        "creator" => metadata.author = Some(text.to_string()),
+                        "subject" => metadata.subject = Some(text.to_string()),
+                        "keywords" => metadata.keywords = Some(text.to_string()),
+                        "created" => metadata.created = Some(text.to_string()),
+                        _ => {}
+                    }
+                }
+            }
+            Ok(quick_xml::events::Event::End(_)) => in_tag = false,
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_core_properties() {
+        let xml = r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties"
+            xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/">
+          <dc:title>報告書</dc:title><dc:creator>Taro</dc:creator><dc:subject>売上</dc:subject>
+          <cp:keywords>a, b</cp:keywords><dcterms:created>2024-01-15T10:30:00Z</dcterms:created>
+        </cp:coreProperties>"#;
+        let metadata = parse_core_properties(xml);
+        assert_eq!(metadata.title.as_deref(), Some("報告書"));
+        assert_eq!(metadata.author.as_deref(), Some("Taro"));
+        assert_eq!(metadata.subject.as_deref(), Some("売上"));
+        assert_eq!(metadata.keywords.as_deref(), Some("a, b"));
+        assert_eq!(metadata.created.as_deref(), Some("2024-01-15T10:30:00Z"));
+        assert_eq!(metadata.creator, None);
+    }
+}
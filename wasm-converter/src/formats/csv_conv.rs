@@ -1,27 +1,47 @@
 // formats/csv_conv.rs - CSV変換モジュール
 //
 // CSVファイルを読み込み、テーブル形式でドキュメントモデルに変換します。
+// 引用符で囲まれたフィールド内の改行は行の区切りにせず、セル内の改行（LF）として保持します。
+// TSV（タブ区切り）・PSV（パイプ区切り）も区切り文字を固定して同じ処理で変換します。
 
 use crate::converter::{
-    ConvertError, Document, DocumentConverter, Metadata, Page, PageElement,
-    Table, TableCell,
+    ConvertError, Document, DocumentConverter, Metadata, PageElement,
+    Table, TableCell, TextFlowOptions, TABLE_CELL_PADDING, TABLE_MIN_COLUMN_WIDTH,
 };
 
 /// CSVコンバーター
-pub struct CsvConverter;
+pub struct CsvConverter {
+    options: TextFlowOptions,
+    /// フィールドの区切り文字（既定: ','）
+    delimiter: u8,
+}
 
 impl CsvConverter {
     pub fn new() -> Self {
-        Self
+        Self::with_options(TextFlowOptions::default())
+    }
+
+    /// 既定フォント・サイズ・等幅揃えを指定してコンバーターを作成
+    pub fn with_options(options: TextFlowOptions) -> Self {
+        Self { options, delimiter: b',' }
+    }
+
+    /// 区切り文字を指定（TSVは b'\t'、PSVは b'|'）
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
     }
 }
 
 impl DocumentConverter for CsvConverter {
     fn convert(&self, input: &[u8]) -> Result<Document, ConvertError> {
         let text = String::from_utf8_lossy(input);
+        let trim = if self.options.csv_trim_fields { csv::Trim::Fields } else { csv::Trim::None };
         let mut reader = csv::ReaderBuilder::new()
             .has_headers(false)
+            .delimiter(self.delimiter)
             .flexible(true)
+            .trim(trim)
             .from_reader(text.as_bytes());
 
         let mut rows: Vec<Vec<String>> = Vec::new();
@@ -30,31 +50,54 @@ impl DocumentConverter for CsvConverter {
         for result in reader.records() {
             let record = result
                 .map_err(|e| ConvertError::new("CSV", &format!("CSVパースエラー: {}", e)))?;
-            let row: Vec<String> = record.iter().map(|s| s.to_string()).collect();
+            let row: Vec<String> = record.iter().map(normalize_line_endings).collect();
             max_cols = max_cols.max(row.len());
             rows.push(row);
         }
 
         if rows.is_empty() {
             let mut doc = Document::new();
-            doc.pages.push(Page::a4());
+            doc.pages.push(self.options.blank_page());
             return Ok(doc);
         }
 
         // テーブルレイアウト
         let margin = 40.0;
-        let page_width = 595.28;
-        let page_height = 841.89;
+        let blank = self.options.blank_page();
+        let (page_width, page_height) = (blank.width, blank.height);
         let usable_width = page_width - margin * 2.0;
-        let row_height = 22.0;
-        let header_height = 26.0;
         let usable_height = page_height - margin * 2.0;
 
-        let col_width = usable_width / max_cols.max(1) as f64;
-        let column_widths: Vec<f64> = (0..max_cols).map(|_| col_width).collect();
+        let body_style = self.options.font_style();
+        let mut header_style = body_style.clone();
+        header_style.bold = true;
+        header_style.font_size += 1.0;
 
-        // ページごとに行を分割
-        let rows_per_page = ((usable_height - header_height) / row_height) as usize;
+        let column_widths: Vec<f64> = if self.options.monospace {
+            // 等幅フォントの送り幅（半角0.6em・全角1em）で各列の最長テキストを計測
+            (0..max_cols)
+                .map(|col| {
+                    let content = rows
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(row_idx, row)| {
+                            let size = if row_idx == 0 { header_style.font_size } else { body_style.font_size };
+                            row.get(col).map(|text| (text, size))
+                        })
+                        .flat_map(|(text, size)| {
+                            text.split('\n')
+                                .map(move |line| crate::font_manager::estimate_text_width(line, size, None))
+                        })
+                        .fold(0.0, f64::max);
+                    (content + TABLE_CELL_PADDING * 2.0).max(body_style.font_size * 2.0)
+                })
+                .collect()
+        } else {
+            // 列数が多い場合は最小列幅を保ってページ幅を超える（FitModeで縮小可能）
+            let col_width = (usable_width / max_cols.max(1) as f64).max(TABLE_MIN_COLUMN_WIDTH);
+            (0..max_cols).map(|_| col_width).collect()
+        };
+        let table_width: f64 = column_widths.iter().sum();
 
         let mut doc = Document::new();
         doc.metadata = Metadata {
@@ -62,43 +105,63 @@ impl DocumentConverter for CsvConverter {
             ..Default::default()
         };
 
-        for (chunk_idx, chunk) in rows.chunks(rows_per_page.max(1)).enumerate() {
-            let mut page = Page::a4();
+        // テーブルセルを構築
+        let table_rows: Vec<Vec<TableCell>> = rows
+            .iter()
+            .enumerate()
+            .map(|(row_idx, row)| {
+                let mut cells: Vec<TableCell> = row
+                    .iter()
+                    .map(|cell_text| {
+                        let mut cell = TableCell::new(cell_text);
+                        // 最初の行はヘッダースタイル
+                        cell.style = if row_idx == 0 { header_style.clone() } else { body_style.clone() };
+                        cell
+                    })
+                    .collect();
+                // 列数を揃える
+                while cells.len() < max_cols {
+                    let mut cell = TableCell::new("");
+                    cell.style = body_style.clone();
+                    cells.push(cell);
+                }
+                cells
+            })
+            .collect();
 
-            // テーブルセルを構築
-            let table_rows: Vec<Vec<TableCell>> = chunk
-                .iter()
-                .enumerate()
-                .map(|(row_idx, row)| {
-                    let mut cells: Vec<TableCell> = row
-                        .iter()
-                        .map(|cell_text| {
-                            let mut cell = TableCell::new(cell_text);
-                            // 最初のページの最初の行はヘッダースタイル
-                            if chunk_idx == 0 && row_idx == 0 {
-                                cell.style.bold = true;
-                                cell.style.font_size = 11.0;
-                            }
-                            cell
-                        })
-                        .collect();
-                    // 列数を揃える
-                    while cells.len() < max_cols {
-                        cells.push(TableCell::new(""));
-                    }
-                    cells
-                })
-                .collect();
+        // 折り返しで高くなった行を考慮してページごとに行を分割
+        // ヘッダー行を繰り返す場合は、各ページでヘッダー行の高さを除いた範囲に本文の行を収める
+        let mut table_rows = table_rows;
+        let font_data = self.options.layout_font.as_deref();
+        let header = (self.options.repeat_header && table_rows.len() > 1).then(|| table_rows.remove(0));
+        let header_height = header.as_ref().map_or(0.0, |row| {
+            Table { rows: vec![row.clone()], column_widths: column_widths.clone() }.row_heights(table_width, font_data)[0]
+        });
+        let mut chunks = Table::split_rows_by_height(
+            table_rows,
+            &column_widths,
+            table_width,
+            (usable_height - header_height).max(0.0),
+            font_data,
+        );
+        if let Some(header) = header {
+            for chunk in &mut chunks {
+                chunk.insert(0, header.clone());
+            }
+        }
+
+        for chunk in chunks {
+            let mut page = blank.clone();
 
             let table = Table {
-                rows: table_rows,
+                rows: chunk,
                 column_widths: column_widths.clone(),
             };
 
             page.elements.push(PageElement::TableBlock {
                 x: margin,
                 y: margin,
-                width: usable_width,
+                width: table_width,
                 table,
             });
 
@@ -106,14 +169,14 @@ impl DocumentConverter for CsvConverter {
         }
 
         if doc.pages.is_empty() {
-            doc.pages.push(Page::a4());
+            doc.pages.push(blank);
         }
 
         Ok(doc)
     }
 
     fn supported_extensions(&self) -> &[&str] {
-        &["csv"]
+        &["csv", "tsv", "psv"]
     }
 
     fn format_name(&self) -> &str {
@@ -121,6 +184,15 @@ impl DocumentConverter for CsvConverter {
     }
 }
 
+/// フィールド内の改行（CRLF / CR）をLFに揃える
+fn normalize_line_endings(field: &str) -> String {
+    if field.contains('\r') {
+        field.replace("\r\n", "\n").replace('\r', "\n")
+    } else {
+        field.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,6 +213,83 @@ mod tests {
         assert_eq!(doc.pages.len(), 1);
     }
 
+    #[test]
+    fn test_monospace_column_widths() {
+        let converter = CsvConverter::with_options(TextFlowOptions {
+            default_font_size: 10.0,
+            monospace: true,
+            ..Default::default()
+        });
+        let doc = converter.convert(b"id,description\n1,abcdefghijklmnopqrst").unwrap();
+        let PageElement::TableBlock { table, width, .. } = &doc.pages[0].elements[0] else {
+            panic!("テーブルがありません");
+        };
+        // 20文字 × 0.6em × 10pt + 左右の余白
+        assert!((table.column_widths[1] - (120.0 + TABLE_CELL_PADDING * 2.0)).abs() < 1e-9);
+        assert!(table.column_widths[0] < table.column_widths[1]);
+        assert!((width - table.column_widths.iter().sum::<f64>()).abs() < 1e-9);
+        assert_eq!(table.rows[1][1].style.font_size, 10.0);
+    }
+
+    #[test]
+    fn test_header_row_repeated_on_each_page() {
+        let mut input = String::from("id,name\n");
+        for i in 1..=500 {
+            input.push_str(&format!("{},item{}\n", i, i));
+        }
+        let doc = CsvConverter::new().convert(input.as_bytes()).unwrap();
+        assert!(doc.pages.len() > 1);
+        let tables: Vec<(&Table, f64)> = doc
+            .pages
+            .iter()
+            .map(|page| match &page.elements[0] {
+                PageElement::TableBlock { table, width, .. } => (table, *width),
+                _ => panic!("テーブルがありません"),
+            })
+            .collect();
+        let mut body_rows = 0;
+        for (table, width) in &tables {
+            // 各ページの先頭はヘッダー行で、列幅はページ間で揃う
+            assert_eq!(table.rows[0][0].text, "id");
+            assert!(table.rows[0][0].style.bold);
+            assert_eq!(table.column_widths, tables[0].0.column_widths);
+            // ヘッダーを含めてもページに収まる
+            let height: f64 = table.row_heights(*width, None).iter().sum();
+            assert!(height <= 841.89 - 80.0);
+            body_rows += table.rows.len() - 1;
+        }
+        assert_eq!(body_rows, 500);
+
+        // 無効にすると2ページ目以降にヘッダーを付けない
+        let converter = CsvConverter::with_options(TextFlowOptions { repeat_header: false, ..Default::default() });
+        let doc = converter.convert(input.as_bytes()).unwrap();
+        let PageElement::TableBlock { table, .. } = &doc.pages[1].elements[0] else {
+            panic!("テーブルがありません");
+        };
+        assert_ne!(table.rows[0][0].text, "id");
+    }
+
+    #[test]
+    fn test_quoted_multiline_field_stays_in_one_cell() {
+        let input = b"id,note\r\n1,\"first line\r\nsecond \"\"quoted\"\" line\"\r\n2,  plain  \r\n";
+        let cells = |doc: &Document| match &doc.pages[0].elements[0] {
+            PageElement::TableBlock { table, .. } => {
+                table.rows.iter().map(|row| row.iter().map(|cell| cell.text.clone()).collect::<Vec<_>>()).collect::<Vec<_>>()
+            }
+            _ => panic!("テーブルがありません"),
+        };
+
+        // 引用符内の改行はLFに揃えた1つのセルになり、二重の引用符は1つになる
+        let rows = cells(&CsvConverter::new().convert(input).unwrap());
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1][1], "first line\nsecond \"quoted\" line");
+        assert_eq!(rows[2][1], "  plain  ");
+
+        // 指定すると各フィールドの前後の空白を取り除く
+        let converter = CsvConverter::with_options(TextFlowOptions { csv_trim_fields: true, ..Default::default() });
+        assert_eq!(cells(&converter.convert(input).unwrap())[2][1], "plain");
+    }
+
     #[test]
     fn test_japanese_csv() {
         let input = "名前,年齢,都市\n太郎,30,東京\n花子,25,大阪".as_bytes();
@@ -0,0 +1,106 @@
+// formats/deferred_assets.rs - 遅延アセット（後から供給する画像）モジュール
+//
+// 外部リンクの画像やパッケージから取り除かれたメディアなど、変換時に読み込めない画像は
+// リレーションシップIDを記したプレースホルダーで表示し、`Document.warnings` に記録します。
+// 呼び出し側は `Converter::deferred_assets` で一覧を取得し、`Converter::add_asset` で
+// データを供給して再変換すると、プレースホルダーの代わりに画像を描画します。
+
+use crate::converter::{Color, FontStyle, PageElement, TextAlign};
+use serde::Serialize;
+
+/// 読み込めなかった画像の警告の接頭辞（後ろにリレーションシップIDとパス）
+pub(crate) const DEFERRED_ASSET_WARNING: &str = "画像を読み込めないためプレースホルダーで表示しました: ";
+
+/// 読み込めず、後から供給できる画像
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeferredAsset {
+    /// 画像を参照するリレーションシップID（例: "rId5"）
+    pub relationship_id: String,
+    /// リレーションシップの参照先（パッケージ内のパスまたは外部URL。解決できない場合は None）
+    pub path: Option<String>,
+}
+
+impl DeferredAsset {
+    /// `Document.warnings` に記録する警告文
+    pub(crate) fn warning(&self) -> String {
+        match &self.path {
+            Some(path) => format!("{}{} ({})", DEFERRED_ASSET_WARNING, self.relationship_id, path),
+            None => format!("{}{}", DEFERRED_ASSET_WARNING, self.relationship_id),
+        }
+    }
+
+    /// 警告文から遅延アセットを取り出す（遅延アセットの警告でなければ None）
+    pub fn from_warning(warning: &str) -> Option<Self> {
+        let rest = warning.strip_prefix(DEFERRED_ASSET_WARNING)?;
+        let (relationship_id, path) = match rest.split_once(" (") {
+            Some((r_id, path)) => (r_id, path.strip_suffix(')').map(str::to_string)),
+            None => (rest, None),
+        };
+        Some(Self { relationship_id: relationship_id.to_string(), path })
+    }
+}
+
+/// 供給済みのアセットからパス（先頭の '/' は無視）が一致するものを探す
+pub(crate) fn find_supplied<'a>(assets: &'a [(String, Vec<u8>)], path: &str) -> Option<&'a [u8]> {
+    let path = path.trim_start_matches('/');
+    assets
+        .iter()
+        .find(|(name, _)| name.trim_start_matches('/') == path)
+        .map(|(_, data)| data.as_slice())
+}
+
+/// 読み込めなかった画像の代わりに描画する、リレーションシップID付きの矩形
+pub(crate) fn placeholder_elements(
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    rotation_deg: f64,
+    r_id: &str,
+) -> Vec<PageElement> {
+    vec![
+        PageElement::Rect {
+            x,
+            y,
+            width,
+            height,
+            fill: Some(Color::rgb(240, 240, 240)),
+            stroke: Some(Color::rgb(180, 180, 180)),
+            stroke_width: 0.5,
+            rotation_deg,
+            stroke_style: Default::default(),
+        },
+        PageElement::Text {
+            x: x + 4.0,
+            y: y + height / 2.0 - 6.0,
+            width: (width - 8.0).max(0.0),
+            text: format!("[画像: {}]", r_id),
+            style: FontStyle {
+                font_size: 10.0,
+                color: Color::rgb(150, 150, 150),
+                italic: true,
+                ..FontStyle::default()
+            },
+            align: TextAlign::Center,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deferred_asset_warning_round_trip() {
+        let asset = DeferredAsset { relationship_id: "rId5".to_string(), path: Some("word/media/a.png".to_string()) };
+        assert_eq!(DeferredAsset::from_warning(&asset.warning()), Some(asset));
+        let unresolved = DeferredAsset { relationship_id: "rId9".to_string(), path: None };
+        assert_eq!(DeferredAsset::from_warning(&unresolved.warning()), Some(unresolved));
+        assert_eq!(DeferredAsset::from_warning("未実装のプリセット図形です: star5"), None);
+
+        let assets = vec![("/ppt/media/b.png".to_string(), vec![1, 2])];
+        assert_eq!(find_supplied(&assets, "ppt/media/b.png"), Some(&[1u8, 2][..]));
+        assert_eq!(find_supplied(&assets, "ppt/media/c.png"), None);
+    }
+}
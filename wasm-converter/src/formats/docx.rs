@@ -4,7 +4,7 @@
 // 抽出してドキュメントモデルに変換します。
 
 use crate::converter::{
-    ConvertError, Document, DocumentConverter, FontStyle, Metadata, Page, PageElement, TextAlign,
+    ConvertError, Document, DocumentConverter, FontStyle, Page, PageElement, TextAlign,
 };
 
 /// DOCXコンバーター
@@ -29,7 +29,7 @@ impl DocumentConverter for DocxConverter {
         let paragraphs = parse_docx_xml(&doc_xml)?;
 
         // メタデータの取得を試みる
-        let metadata = read_docx_metadata(&mut archive);
+        let metadata = super::core_properties::read_core_properties(&mut archive);
 
         // ドキュメントモデルに変換
         let style = FontStyle::default();
@@ -83,13 +83,12 @@ fn read_zip_entry(
     archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
     path: &str,
 ) -> Result<String, ConvertError> {
-    use std::io::Read;
     let mut file = archive
         .by_name(path)
         .map_err(|e| ConvertError::new("DOCX", &format!("{}が見つかりません: {}", path, e)))?;
 
     let mut content = String::new();
-    file.read_to_string(&mut content)
+    super::limits::read_limited_string(&mut file, &mut content)
         .map_err(|e| ConvertError::new("DOCX", &format!("{}の読み込みエラー: {}", path, e)))?;
 
     Ok(content)
@@ -146,50 +145,6 @@ fn parse_docx_xml(xml: &str) -> Result<Vec<String>, ConvertError> {
     Ok(paragraphs)
 }
 
-/// DOCXメタデータを読み取る
-fn read_docx_metadata(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>) -> Metadata {
-    let mut metadata = Metadata::default();
-
-    if let Ok(core_xml) = read_zip_entry(archive, "docProps/core.xml") {
-        let mut reader = quick_xml::Reader::from_str(&core_xml);
-        let mut buf = Vec::new();
-        let mut current_tag = String::new();
-        let mut in_tag = false;
-
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(quick_xml::events::Event::Start(ref e)) => {
-                    let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
-                    current_tag = name;
-                    in_tag = true;
-                }
-                Ok(quick_xml::events::Event::Text(ref e)) => {
-                    if in_tag {
-                        if let Ok(text) = e.unescape() {
-                            match current_tag.as_str() {
-                                "title" => metadata.title = Some(text.to_string()),
-                                "creator" => metadata.author = Some(text.to_string()),
-                                "subject" => metadata.subject = Some(text.to_string()),
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-                Ok(quick_xml::events::Event::End(_)) => {
-                    in_tag = false;
-                }
-                Ok(quick_xml::events::Event::Eof) => break,
-                Err(_) => break,
-                _ => {}
-            }
-            buf.clear();
-        }
-    }
-
-    metadata.creator = Some("WASM Document Converter".to_string());
-    metadata
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,16 +4,72 @@
 // 段落の書式・テーブル・画像・ページマージンを忠実に再現して
 // ドキュメントモデルに変換します。
 
+use std::collections::HashMap;
+
 use crate::converter::{
-    Color, ConvertError, Document, DocumentConverter, FontStyle, Metadata, Page, PageElement, TextAlign,
+    BorderSpec, CellBorders, Color, CompoundLine, ConvertError, Document, DocumentConverter, FontStyle, Page,
+    HyperlinkStyle, PageElement, TabAlign, TabStop, Table, TableCell, TextAlign, TrackedChanges, UnderlineKind,
+    UnderlineStyle,
 };
 
+use serde::Serialize;
+
+use super::deferred_assets::{self, DeferredAsset};
+use super::ole;
+
 /// DOCXコンバーター（レイアウト保持版）
-pub struct DocxConverter;
+pub struct DocxConverter {
+    /// コメント（comments.xml）を右余白に注釈として描画する
+    render_comments: bool,
+    /// 変更履歴（w:ins / w:del）の扱い
+    tracked_changes: TrackedChanges,
+    /// リンク（w:hyperlink）の付いたランの見た目
+    hyperlink_style: HyperlinkStyle,
+    /// テキストと表だけを出力する（画像の読み込みを省く）
+    text_only: bool,
+    /// パッケージ内に無い画像の代わりに使うデータ（パス → バイト列）
+    supplied_assets: Vec<(String, Vec<u8>)>,
+}
 
 impl DocxConverter {
     pub fn new() -> Self {
-        Self
+        Self {
+            render_comments: false,
+            tracked_changes: TrackedChanges::default(),
+            hyperlink_style: HyperlinkStyle::default(),
+            text_only: false,
+            supplied_assets: Vec::new(),
+        }
+    }
+
+    /// 読み込めない画像の代わりに使うデータ（`word/media/image1.png` などのパス → バイト列）を指定
+    pub fn with_supplied_assets(mut self, assets: Vec<(String, Vec<u8>)>) -> Self {
+        self.supplied_assets = assets;
+        self
+    }
+
+    /// テキストと表だけを出力するかどうかを指定（画像を読み込まず、図形も描画しない）
+    pub fn with_text_only(mut self, text_only: bool) -> Self {
+        self.text_only = text_only;
+        self
+    }
+
+    /// 変更履歴の挿入・削除を承諾・却下のどちらの状態で描画するか、または両方を色分けして描画するか
+    pub fn with_tracked_changes(mut self, tracked_changes: TrackedChanges) -> Self {
+        self.tracked_changes = tracked_changes;
+        self
+    }
+
+    /// リンク（w:hyperlink）の付いたランの見た目を指定（既定: 元の書式のまま）
+    pub fn with_hyperlink_style(mut self, style: HyperlinkStyle) -> Self {
+        self.hyperlink_style = style;
+        self
+    }
+
+    /// コメントを右余白に描画するか（`w:commentReference` の位置から細線で結ぶ）
+    pub fn with_render_comments(mut self, render: bool) -> Self {
+        self.render_comments = render;
+        self
     }
 }
 
@@ -30,23 +86,62 @@ impl DocumentConverter for DocxConverter {
         let rels = read_zip_entry_string(&mut archive, "word/_rels/document.xml.rels").ok();
 
         // メタデータ
-        let metadata = read_docx_metadata(&mut archive);
+        let metadata = super::core_properties::read_core_properties(&mut archive);
 
         // ページ設定を解析
         let page_setup = parse_section_properties(&doc_xml);
 
+        // スタイル定義（w:pStyle / w:rStyle の参照先）
+        let styles = read_zip_entry_string(&mut archive, "word/styles.xml")
+            .map(|xml| parse_styles(&xml))
+            .unwrap_or_default();
+
         // ドキュメント本文を解析
-        let body_elements = parse_document_body(&doc_xml);
+        let mut body_elements = parse_document_body(&doc_xml, &styles, self.tracked_changes);
+        if self.hyperlink_style != HyperlinkStyle::Source {
+            let theme_colors = read_zip_entry_string(&mut archive, "word/theme/theme1.xml")
+                .map(|xml| super::pptx_layout::parse_theme_colors(&xml))
+                .unwrap_or_default();
+            apply_hyperlink_style(&mut body_elements, self.hyperlink_style, theme_colors.hlink);
+        }
+
+        // 脚注・文末脚注
+        let notes = DocNotes {
+            footnotes: read_zip_entry_string(&mut archive, "word/footnotes.xml")
+                .map(|xml| parse_notes(&xml))
+                .unwrap_or_default(),
+            endnotes: read_zip_entry_string(&mut archive, "word/endnotes.xml")
+                .map(|xml| parse_notes(&xml))
+                .unwrap_or_default(),
+        };
 
-        // 画像を解決
-        let resolved_elements = resolve_images(&body_elements, &rels, &mut archive);
+        // コメント（余白への描画を指定した場合のみ）
+        let comments: HashMap<String, DocComment> = if self.render_comments {
+            read_zip_entry_string(&mut archive, "word/comments.xml")
+                .map(|xml| parse_comments(&xml).into_iter().map(|c| (c.id.clone(), c)).collect())
+                .unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
+
+        // 画像を解決（テキストのみのモードでは読み込まない）
+        let mut warnings = Vec::new();
+        let resolved_elements = if self.text_only {
+            without_images(&body_elements)
+        } else {
+            resolve_images(&body_elements, &rels, &mut archive, &self.supplied_assets, &mut warnings)
+        };
 
         // ページにレイアウト
-        let pages = layout_pages(&resolved_elements, &page_setup);
+        let mut pages = layout_pages(&resolved_elements, &page_setup, &notes, &comments);
+        if self.text_only {
+            pages.iter_mut().for_each(Page::retain_text);
+        }
 
         let mut doc = Document::new();
         doc.metadata = metadata;
         doc.pages = pages;
+        doc.warnings = warnings;
 
         if doc.pages.is_empty() {
             doc.pages.push(Page::a4());
@@ -75,6 +170,8 @@ struct PageSetup {
     margin_bottom: f64,
     margin_left: f64,
     margin_right: f64,
+    /// w:type="continuous"（改ページせずに前のセクションと同じページで開始）
+    continuous: bool,
 }
 
 impl Default for PageSetup {
@@ -86,6 +183,25 @@ impl Default for PageSetup {
             margin_bottom: 72.0,
             margin_left: 72.0,
             margin_right: 72.0,
+            continuous: false,
+        }
+    }
+}
+
+impl PageSetup {
+    fn usable_width(&self) -> f64 {
+        self.width - self.margin_left - self.margin_right
+    }
+
+    fn usable_height(&self) -> f64 {
+        self.height - self.margin_top - self.margin_bottom
+    }
+
+    fn blank_page(&self) -> Page {
+        Page {
+            width: self.width,
+            height: self.height,
+            elements: Vec::new(),
         }
     }
 }
@@ -93,11 +209,29 @@ impl Default for PageSetup {
 /// TWIP → ポイント変換 (1 pt = 20 twip)
 const TWIP_PER_PT: f64 = 20.0;
 
+/// VML の style 属性の長さ（pt / in / cm / mm / px、単位なしは px）をポイントに変換
+fn parse_css_length(val: &str) -> Option<f64> {
+    let val = val.trim();
+    let split = val.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(val.len());
+    let number: f64 = val[..split].trim().parse().ok()?;
+    let factor = match &val[split..] {
+        "pt" => 1.0,
+        "in" => 72.0,
+        "cm" => 72.0 / 2.54,
+        "mm" => 72.0 / 25.4,
+        "px" | "" => 0.75,
+        _ => return None,
+    };
+    Some(number * factor)
+}
+
 /// ドキュメント本文要素
 #[derive(Debug, Clone)]
 enum BodyElement {
     Paragraph(DocParagraph),
     Table(DocTable),
+    /// 段落内の w:sectPr によるセクション区切り（直前までのセクションのページ設定）
+    SectionBreak(PageSetup),
 }
 
 /// 段落
@@ -114,6 +248,7 @@ struct DocParagraph {
     is_heading: bool,
     heading_level: u32,
     numbering: Option<String>,
+    tab_stops: Vec<TabStop>, // w:tabs のタブ位置（左余白からの距離）
 }
 
 /// テキストラン
@@ -124,21 +259,326 @@ struct DocRun {
     font_size: f64,
     bold: bool,
     italic: bool,
-    underline: bool,
+    underline: Option<UnderlineStyle>,
     color: Color,
     font_name: Option<String>,
     highlight: Option<Color>,
+    strikethrough: bool,
+    baseline_shift: f64,
+    /// リンク（w:hyperlink）の内側のラン
+    hyperlink: bool,
 }
 
 #[derive(Debug, Clone)]
 enum RunContent {
     Text(String),
-    Image { r_id: String, width: f64, height: f64 },
-    ImageData { data: Vec<u8>, mime_type: String, width: f64, height: f64 },
+    Image { r_id: String, width: f64, height: f64, anchor: Option<ImageAnchor> },
+    ImageData { data: Vec<u8>, mime_type: String, width: f64, height: f64, anchor: Option<ImageAnchor> },
     LineBreak,
+    /// w:br w:type="page" による改ページ
+    PageBreak,
     Tab,
+    /// w:footnoteReference / w:endnoteReference（w:id で脚注本文を参照）
+    NoteRef { kind: NoteKind, id: String },
+    /// w:commentReference（w:id でコメントを参照）
+    CommentRef { id: String },
+    /// w:object（OLE埋め込みオブジェクト）。描画できるプレビューが無い場合はプレースホルダーで表示
+    Ole { prog_id: String, preview_r_id: Option<String>, width: f64, height: f64 },
+    /// w:drawing の図形（wps:wsp）または w:pict の VML 図形。テキストボックスの内容を含む
+    Shape { shape: DrawingShape, width: f64, height: f64, anchor: Option<ImageAnchor> },
+}
+
+impl DocRun {
+    /// 書式を持たない埋め込みオブジェクト（画像・OLE・図形）のラン
+    fn embedded(content: RunContent, font_size: f64) -> Self {
+        Self {
+            content,
+            font_size,
+            bold: false,
+            italic: false,
+            underline: None,
+            color: Color::BLACK,
+            font_name: None,
+            highlight: None,
+            strikethrough: false,
+            baseline_shift: 0.0,
+            hyperlink: false,
+        }
+    }
+}
+
+/// テキストボックス・図形（wps:wsp、VML の v:shape / v:rect など）
+#[derive(Debug, Clone, Default)]
+struct DrawingShape {
+    /// 塗り・線を矩形として描画する（prstGeom="rect"、v:shape / v:rect）
+    /// その他の図形はテキストボックスの内容だけを描画します。
+    rect: bool,
+    fill: Option<Color>,
+    stroke: Option<Color>,
+    stroke_width: f64,
+    /// テキストボックスの内容（w:txbxContent）
+    text_box: Vec<BodyElement>,
+}
+
+/// テキストボックスの既定の内側の余白（左右 0.1 インチ・上下 0.05 インチ）
+const TEXT_BOX_INSET_X: f64 = 7.2;
+const TEXT_BOX_INSET_Y: f64 = 3.6;
+
+impl DrawingShape {
+    /// VML の図形要素（v:shape / v:rect など）の塗り・線と、style 属性の位置・大きさ
+    /// VML は既定で白の塗りと黒の 0.75pt の線を持ち、filled="f" / stroked="f" で無効になります。
+    fn from_vml(e: &quick_xml::events::BytesStart) -> (Self, f64, f64, Option<ImageAnchor>) {
+        let rect = matches!(e.local_name().as_ref(), b"shape" | b"rect");
+        let mut shape = Self { rect, fill: Some(Color::WHITE), stroke: Some(Color::BLACK), stroke_width: 0.75, ..Self::default() };
+        let (mut width, mut height) = (72.0, 72.0);
+        let mut absolute = false;
+        let (mut offset_x, mut offset_y) = (0.0, 0.0);
+        let (mut relative_h, mut relative_v) = (AnchorRelative::Margin, AnchorRelative::Paragraph);
+        for attr in e.attributes().flatten() {
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+            match attr.key.local_name().as_ref() {
+                b"fillcolor" => shape.fill = parse_hex_color(value.trim_start_matches('#')).or(shape.fill),
+                b"strokecolor" => shape.stroke = parse_hex_color(value.trim_start_matches('#')).or(shape.stroke),
+                b"strokeweight" => shape.stroke_width = parse_css_length(&value).unwrap_or(shape.stroke_width),
+                b"filled" if matches!(value.as_str(), "f" | "false") => shape.fill = None,
+                b"stroked" if matches!(value.as_str(), "f" | "false") => shape.stroke = None,
+                b"style" => {
+                    for declaration in value.split(';') {
+                        let Some((key, val)) = declaration.split_once(':') else { continue };
+                        let val = val.trim();
+                        match key.trim() {
+                            "position" => absolute = val == "absolute",
+                            "margin-left" | "left" => offset_x = parse_css_length(val).unwrap_or(0.0),
+                            "margin-top" | "top" => offset_y = parse_css_length(val).unwrap_or(0.0),
+                            "width" => width = parse_css_length(val).unwrap_or(width),
+                            "height" => height = parse_css_length(val).unwrap_or(height),
+                            "mso-position-horizontal-relative" => relative_h = AnchorRelative::from_attr(val),
+                            // 縦方向の "text" は段落基準
+                            "mso-position-vertical-relative" => {
+                                relative_v = if val == "text" { AnchorRelative::Paragraph } else { AnchorRelative::from_attr(val) };
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let anchor = absolute.then_some(ImageAnchor { offset_x, offset_y, relative_h, relative_v });
+        (shape, width, height, anchor)
+    }
+
+    /// 図形を (x, y) に配置した描画要素
+    /// テキストボックスの内容は内側の余白を除いた幅のページとしてレイアウトし、枠の位置へ移動します（はみ出した行も描画）。
+    fn elements(&self, x: f64, y: f64, width: f64, height: f64) -> Vec<PageElement> {
+        let mut elements = Vec::new();
+        if self.rect && (self.fill.is_some() || self.stroke.is_some()) {
+            elements.push(PageElement::Rect {
+                x,
+                y,
+                width,
+                height,
+                fill: self.fill,
+                stroke: self.stroke,
+                stroke_width: if self.stroke.is_some() { self.stroke_width } else { 0.0 },
+                rotation_deg: 0.0,
+                stroke_style: Default::default(),
+            });
+        }
+        if self.text_box.is_empty() {
+            return elements;
+        }
+        let setup = PageSetup {
+            width,
+            height: f64::MAX,
+            margin_top: TEXT_BOX_INSET_Y,
+            margin_bottom: TEXT_BOX_INSET_Y,
+            margin_left: TEXT_BOX_INSET_X,
+            margin_right: TEXT_BOX_INSET_X,
+            continuous: false,
+        };
+        let pages = layout_pages(&self.text_box, &setup, &DocNotes::default(), &HashMap::new());
+        for mut element in pages.into_iter().flat_map(|page| page.elements) {
+            element.translate(x, y);
+            elements.push(element);
+        }
+        elements
+    }
 }
 
+/// 解析中の w:object（OLE埋め込みオブジェクト）
+#[derive(Debug, Clone, Default)]
+struct OleObjectState {
+    prog_id: String,
+    preview_r_id: Option<String>,
+    width: f64,
+    height: f64,
+}
+
+impl OleObjectState {
+    /// w:object の w:dxaOrig / w:dyaOrig（twip）を初期サイズにする
+    fn from_object(e: &quick_xml::events::BytesStart) -> Self {
+        let mut state = Self { width: 72.0, height: 72.0, ..Self::default() };
+        for attr in e.attributes().flatten() {
+            match attr.key.local_name().as_ref() {
+                b"dxaOrig" => state.width = parse_twip(&attr.value),
+                b"dyaOrig" => state.height = parse_twip(&attr.value),
+                _ => {}
+            }
+        }
+        state
+    }
+
+    /// w:object 内の v:shape（表示サイズ）・v:imagedata（プレビュー画像）・o:OLEObject（ProgID）を反映
+    fn apply(&mut self, e: &quick_xml::events::BytesStart) {
+        match e.local_name().as_ref() {
+            b"shape" => {
+                let Some(style) = e.attributes().flatten().find(|attr| attr.key.as_ref() == b"style") else {
+                    return;
+                };
+                for decl in String::from_utf8_lossy(&style.value).split(';') {
+                    let Some((key, value)) = decl.split_once(':') else {
+                        continue;
+                    };
+                    let Some(pt) = value.trim().strip_suffix("pt").and_then(|v| v.trim().parse::<f64>().ok()) else {
+                        continue;
+                    };
+                    match key.trim() {
+                        "width" => self.width = pt,
+                        "height" => self.height = pt,
+                        _ => {}
+                    }
+                }
+            }
+            b"imagedata" => {
+                self.preview_r_id = e
+                    .attributes()
+                    .flatten()
+                    .find(|attr| attr.key.local_name().as_ref() == b"id")
+                    .map(|attr| String::from_utf8_lossy(&attr.value).to_string());
+            }
+            b"OLEObject" => {
+                if let Some(attr) = e.attributes().flatten().find(|attr| attr.key.as_ref() == b"ProgID") {
+                    self.prog_id = String::from_utf8_lossy(&attr.value).to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 変更履歴の種類
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Revision {
+    Inserted,
+    Deleted,
+}
+
+/// 変更履歴の表示（`TrackedChanges::Markup`）での挿入の色
+const INSERTION_COLOR: Color = Color::rgb(0, 112, 192);
+/// 変更履歴の表示での削除の色
+const DELETION_COLOR: Color = Color::rgb(192, 0, 0);
+
+impl Revision {
+    /// 変更履歴の内側のラン（`runs[start..]`）を扱いに応じて除外、または下線・取り消し線で装飾する
+    fn apply(self, tracked_changes: TrackedChanges, runs: &mut Vec<DocRun>, start: usize) {
+        match (tracked_changes, self) {
+            (TrackedChanges::Accept, Revision::Deleted) | (TrackedChanges::Reject, Revision::Inserted) => {
+                runs.truncate(start);
+            }
+            (TrackedChanges::Markup, Revision::Inserted) => {
+                for run in &mut runs[start..] {
+                    run.color = INSERTION_COLOR;
+                    run.underline = Some(UnderlineStyle::new(UnderlineKind::Single));
+                }
+            }
+            (TrackedChanges::Markup, Revision::Deleted) => {
+                for run in &mut runs[start..] {
+                    run.color = DELETION_COLOR;
+                    run.strikethrough = true;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 脚注の種類
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NoteKind {
+    /// ページ下部に配置する脚注
+    Footnote,
+    /// 文書末尾にまとめて配置する文末脚注
+    Endnote,
+}
+
+/// 脚注・文末脚注の本文（w:id → 段落ごとのテキスト）
+#[derive(Debug, Clone, Default)]
+struct DocNotes {
+    footnotes: HashMap<String, Vec<String>>,
+    endnotes: HashMap<String, Vec<String>>,
+}
+
+impl DocNotes {
+    fn paragraphs(&self, kind: NoteKind, id: &str) -> &[String] {
+        let notes = match kind {
+            NoteKind::Footnote => &self.footnotes,
+            NoteKind::Endnote => &self.endnotes,
+        };
+        notes.get(id).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// フローティング画像（wp:anchor）の配置
+#[derive(Debug, Clone, Copy)]
+struct ImageAnchor {
+    offset_x: f64,   // ポイント
+    offset_y: f64,
+    relative_h: AnchorRelative,
+    relative_v: AnchorRelative,
+}
+
+/// wp:positionH / wp:positionV の relativeFrom
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AnchorRelative {
+    Page,
+    Margin,
+    Paragraph,
+}
+
+impl ImageAnchor {
+    /// 配置の基準からのずれを足したページ上の位置（`cur_y` は本文領域内の現在の段落の位置）
+    fn position(&self, setup: &PageSetup, cur_y: f64) -> (f64, f64) {
+        let base_x = match self.relative_h {
+            AnchorRelative::Page => 0.0,
+            AnchorRelative::Margin | AnchorRelative::Paragraph => setup.margin_left,
+        };
+        let base_y = match self.relative_v {
+            AnchorRelative::Page => 0.0,
+            AnchorRelative::Margin => setup.margin_top,
+            AnchorRelative::Paragraph => setup.margin_top + cur_y,
+        };
+        (base_x + self.offset_x, base_y + self.offset_y)
+    }
+}
+
+impl AnchorRelative {
+    fn from_attr(val: &str) -> Self {
+        match val {
+            "page" => AnchorRelative::Page,
+            "paragraph" | "line" => AnchorRelative::Paragraph,
+            _ => AnchorRelative::Margin,
+        }
+    }
+}
+
+/// w:vertAlign の上付き・下付きに対応するベースラインのずれ（フォントサイズ比）
+const SUPERSCRIPT_SHIFT: f64 = 0.33;
+const SUBSCRIPT_SHIFT: f64 = -0.14;
+
+/// EMU → ポイント変換 (1 pt = 12700 EMU)
+const EMU_PER_PT: f64 = 12700.0;
+
 /// テーブル
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -151,7 +591,6 @@ struct DocTable {
 #[derive(Debug, Clone)]
 struct DocTableRow {
     cells: Vec<DocTableCell>,
-    height: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
@@ -159,6 +598,179 @@ struct DocTableCell {
     paragraphs: Vec<DocParagraph>,
     width: f64,
     shading: Option<Color>,
+    /// w:tcBorders の辺ごとの枠線（未指定の場合は既定の枠線）
+    borders: Option<CellBorders>,
+}
+
+impl DocTable {
+    /// 共有のテーブル（`PageElement::TableBlock`）に変換
+    /// セルの段落はテキストを改行でつなぎ、書式は先頭のテキストのランに揃えます。
+    /// 列幅は w:tblGrid、無ければ先頭行の w:tcW（どちらも無い場合は本文幅を均等割り）です。
+    fn to_table(&self) -> Table {
+        let first_row_widths: Vec<f64> =
+            self.rows.first().map_or(Vec::new(), |row| row.cells.iter().map(|c| c.width).collect());
+        let column_widths = if !self.column_widths.is_empty() {
+            self.column_widths.clone()
+        } else if !first_row_widths.is_empty() && first_row_widths.iter().all(|w| *w > 0.0) {
+            first_row_widths
+        } else {
+            Vec::new()
+        };
+        let rows = self.rows.iter().map(|row| row.cells.iter().map(DocTableCell::to_table_cell).collect()).collect();
+        Table { rows, column_widths }
+    }
+}
+
+impl DocTableCell {
+    fn to_table_cell(&self) -> TableCell {
+        let text_of = |para: &DocParagraph| -> String {
+            para.runs
+                .iter()
+                .filter_map(|run| match &run.content {
+                    RunContent::Text(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect()
+        };
+        let mut cell = TableCell::new(&self.paragraphs.iter().map(text_of).collect::<Vec<_>>().join("\n"));
+        let first_run = self.paragraphs.iter().flat_map(|para| &para.runs).find(|run| {
+            matches!(&run.content, RunContent::Text(text) if !text.is_empty())
+        });
+        if let Some(run) = first_run {
+            cell.style = FontStyle {
+                font_size: run.font_size,
+                bold: run.bold,
+                italic: run.italic,
+                color: run.color,
+                strikethrough: run.strikethrough,
+                baseline_shift: run.baseline_shift,
+                underline: run.underline,
+                font_name: run.font_name.clone().unwrap_or_else(|| FontStyle::default().font_name),
+                letter_spacing: 0.0,
+            };
+        }
+        cell.background = self.shading;
+        cell.borders = self.borders;
+        cell
+    }
+}
+
+/// スタイルの継承（w:basedOn）をたどる深さの上限（循環参照対策）
+const MAX_STYLE_DEPTH: usize = 16;
+
+/// スタイルが定義する書式（None の項目は継承元・既定値に従う）
+#[derive(Debug, Clone, Default)]
+struct StyleProps {
+    font_size: Option<f64>,
+    bold: Option<bool>,
+    italic: Option<bool>,
+    color: Option<Color>,
+    font_name: Option<String>,
+    align: Option<TextAlign>,
+    spacing_before: Option<f64>,
+    spacing_after: Option<f64>,
+    line_spacing: Option<f64>,
+}
+
+impl StyleProps {
+    /// `other` で指定されている項目で上書き
+    fn merge(&mut self, other: &StyleProps) {
+        fn set<T: Clone>(target: &mut Option<T>, value: &Option<T>) {
+            if value.is_some() {
+                target.clone_from(value);
+            }
+        }
+        set(&mut self.font_size, &other.font_size);
+        set(&mut self.bold, &other.bold);
+        set(&mut self.italic, &other.italic);
+        set(&mut self.color, &other.color);
+        set(&mut self.font_name, &other.font_name);
+        set(&mut self.align, &other.align);
+        set(&mut self.spacing_before, &other.spacing_before);
+        set(&mut self.spacing_after, &other.spacing_after);
+        set(&mut self.line_spacing, &other.line_spacing);
+    }
+
+    /// 文字書式をランの状態に反映
+    fn apply_to_run(
+        &self,
+        font_size: &mut f64,
+        bold: &mut bool,
+        italic: &mut bool,
+        color: &mut Color,
+        font_name: &mut Option<String>,
+    ) {
+        *font_size = self.font_size.unwrap_or(*font_size);
+        *bold = self.bold.unwrap_or(*bold);
+        *italic = self.italic.unwrap_or(*italic);
+        *color = self.color.unwrap_or(*color);
+        if self.font_name.is_some() {
+            font_name.clone_from(&self.font_name);
+        }
+    }
+
+    /// 段落書式を段落の状態に反映
+    fn apply_to_paragraph(&self, align: &mut TextAlign, before: &mut f64, after: &mut f64, line: &mut f64) {
+        *align = self.align.unwrap_or(*align);
+        *before = self.spacing_before.unwrap_or(*before);
+        *after = self.spacing_after.unwrap_or(*after);
+        *line = self.line_spacing.unwrap_or(*line);
+    }
+}
+
+/// styles.xml のスタイル定義
+#[derive(Debug, Clone, Default)]
+struct DocStyle {
+    based_on: Option<String>,
+    props: StyleProps,
+}
+
+/// styles.xml のスタイル表（styles.xml が無い場合は空）
+#[derive(Debug, Clone, Default)]
+struct StyleTable {
+    /// w:docDefaults（文書全体の既定の書式）
+    defaults: StyleProps,
+    /// w:styleId → スタイル定義
+    styles: HashMap<String, DocStyle>,
+    /// w:default="1" の段落スタイル（通常は "Normal"）
+    default_paragraph: Option<String>,
+}
+
+impl StyleTable {
+    fn contains(&self, style_id: &str) -> bool {
+        self.styles.contains_key(style_id)
+    }
+
+    /// 継承をたどって解決したスタイルの書式（継承元から順に上書き）
+    fn resolve(&self, style_id: &str) -> StyleProps {
+        let mut chain = Vec::new();
+        let mut current = Some(style_id);
+        while let Some(id) = current {
+            let Some(style) = self.styles.get(id) else { break };
+            if chain.len() >= MAX_STYLE_DEPTH {
+                break;
+            }
+            chain.push(style);
+            current = style.based_on.as_deref();
+        }
+        let mut props = StyleProps::default();
+        for style in chain.iter().rev() {
+            props.merge(&style.props);
+        }
+        props
+    }
+
+    /// 段落の書式（文書の既定 → 段落スタイル、未指定・未定義なら既定の段落スタイル）
+    fn paragraph(&self, style_id: Option<&str>) -> StyleProps {
+        let mut props = self.defaults.clone();
+        let style_id = style_id
+            .filter(|id| self.contains(id))
+            .or(self.default_paragraph.as_deref());
+        if let Some(id) = style_id {
+            props.merge(&self.resolve(id));
+        }
+        props
+    }
 }
 
 // ── ZIP helpers ──
@@ -167,88 +779,285 @@ fn read_zip_entry_string(
     archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
     path: &str,
 ) -> Result<String, ConvertError> {
-    use std::io::Read;
     let mut file = archive
         .by_name(path)
         .map_err(|e| ConvertError::new("DOCX", &format!("{}が見つかりません: {}", path, e)))?;
     let mut content = String::new();
-    file.read_to_string(&mut content)
+    super::limits::read_limited_string(&mut file, &mut content)
         .map_err(|e| ConvertError::new("DOCX", &format!("{}の読み込みエラー: {}", path, e)))?;
-    Ok(content)
+    // mc:AlternateContent は採用する分岐だけを残して解析する
+    Ok(super::markup_compat::resolve_alternate_content(content))
 }
 
 fn read_zip_entry_bytes(
     archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
     path: &str,
 ) -> Result<Vec<u8>, ConvertError> {
-    use std::io::Read;
     let mut file = archive
         .by_name(path)
         .map_err(|e| ConvertError::new("DOCX", &format!("{}が見つかりません: {}", path, e)))?;
     let mut data = Vec::new();
-    file.read_to_end(&mut data)
+    super::limits::read_limited(&mut file, &mut data)
         .map_err(|e| ConvertError::new("DOCX", &format!("{}の読み込みエラー: {}", path, e)))?;
     Ok(data)
 }
 
 // ── セクションプロパティ解析 ──
 
+/// 本文末尾（最終セクション）のページ設定を解析
 fn parse_section_properties(xml: &str) -> PageSetup {
     let mut setup = PageSetup::default();
     let mut reader = quick_xml::Reader::from_str(xml);
     let mut buf = Vec::new();
+    let mut in_ppr = false;
 
     loop {
         match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) if e.local_name().as_ref() == b"pPr" => {
+                in_ppr = true;
+            }
+            Ok(quick_xml::events::Event::End(ref e)) if e.local_name().as_ref() == b"pPr" => {
+                in_ppr = false;
+            }
+            // 段落内の w:sectPr は途中のセクションなので除外
             Ok(quick_xml::events::Event::Empty(ref e))
-            | Ok(quick_xml::events::Event::Start(ref e)) => {
-                let local = e.local_name();
-                match local.as_ref() {
-                    b"pgSz" => {
+            | Ok(quick_xml::events::Event::Start(ref e)) if !in_ppr => {
+                apply_section_property(&mut setup, e);
+            }
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    setup
+}
+
+/// w:sectPr の子要素（pgSz / pgMar / type）をページ設定に反映
+fn apply_section_property(setup: &mut PageSetup, e: &quick_xml::events::BytesStart) {
+    match e.local_name().as_ref() {
+        b"pgSz" => {
+            let mut landscape = false;
+            for attr in e.attributes().flatten() {
+                match attr.key.local_name().as_ref() {
+                    b"w" => {
+                        setup.width = parse_twip(&attr.value);
+                    }
+                    b"h" => {
+                        setup.height = parse_twip(&attr.value);
+                    }
+                    b"orient" => {
+                        landscape = attr.value.as_ref() == b"landscape";
+                    }
+                    _ => {}
+                }
+            }
+            // orient="landscape" で幅と高さが縦向きのまま記述されている場合は入れ替える
+            if landscape && setup.width < setup.height {
+                std::mem::swap(&mut setup.width, &mut setup.height);
+            }
+        }
+        b"pgMar" => {
+            for attr in e.attributes().flatten() {
+                match attr.key.local_name().as_ref() {
+                    b"top" => setup.margin_top = parse_twip(&attr.value),
+                    b"bottom" => setup.margin_bottom = parse_twip(&attr.value),
+                    b"left" => setup.margin_left = parse_twip(&attr.value),
+                    b"right" => setup.margin_right = parse_twip(&attr.value),
+                    _ => {}
+                }
+            }
+        }
+        b"type" => {
+            for attr in e.attributes().flatten() {
+                if attr.key.local_name().as_ref() == b"val" {
+                    setup.continuous = attr.value.as_ref() == b"continuous";
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_twip(val: &[u8]) -> f64 {
+    let s = String::from_utf8_lossy(val);
+    s.parse::<f64>().unwrap_or(0.0) / TWIP_PER_PT
+}
+
+/// w:tabs 内の w:tab からタブ位置を取得
+/// 継承したタブ位置の解除（clear）や縦線（bar）はタブ位置として扱いません。
+fn parse_tab_stop(e: &quick_xml::events::BytesStart) -> Option<TabStop> {
+    let mut align = None;
+    let mut position = None;
+    for attr in e.attributes().flatten() {
+        match attr.key.local_name().as_ref() {
+            b"val" => {
+                align = match attr.value.as_ref() {
+                    b"left" | b"start" => Some(TabAlign::Left),
+                    b"center" => Some(TabAlign::Center),
+                    b"right" | b"end" => Some(TabAlign::Right),
+                    b"decimal" => Some(TabAlign::Decimal),
+                    _ => None,
+                };
+            }
+            b"pos" => position = Some(parse_twip(&attr.value)),
+            _ => {}
+        }
+    }
+    Some(TabStop { position: position?, align: align? })
+}
+
+// ── スタイル解析 ──
+
+/// styles.xml を解析してスタイル表を作成
+/// 段落スタイル・文字スタイルの書式と継承元、w:docDefaults の既定の書式を読み取ります。
+/// 表スタイルの条件付き書式（w:tblStylePr）は対象外です。
+fn parse_styles(xml: &str) -> StyleTable {
+    let mut table = StyleTable::default();
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut buf = Vec::new();
+
+    let mut in_defaults = false;
+    let mut cur_style: Option<(String, DocStyle)> = None;
+    let mut in_ppr = false;
+    let mut in_rpr = false;
+    let mut in_conditional = false;
+
+    loop {
+        let event = reader.read_event_into(&mut buf);
+        let is_start = matches!(event, Ok(quick_xml::events::Event::Start(_)));
+        match event {
+            Ok(quick_xml::events::Event::Start(ref e)) | Ok(quick_xml::events::Event::Empty(ref e)) => {
+                match e.local_name().as_ref() {
+                    b"docDefaults" => in_defaults = is_start,
+                    b"style" => {
+                        let (mut id, mut paragraph, mut default) = (String::new(), false, false);
                         for attr in e.attributes().flatten() {
                             match attr.key.local_name().as_ref() {
-                                b"w" => {
-                                    setup.width = parse_twip(&attr.value);
-                                }
-                                b"h" => {
-                                    setup.height = parse_twip(&attr.value);
-                                }
+                                b"styleId" => id = String::from_utf8_lossy(&attr.value).to_string(),
+                                b"type" => paragraph = attr.value.as_ref() == b"paragraph",
+                                b"default" => default = matches!(attr.value.as_ref(), b"1" | b"true"),
                                 _ => {}
                             }
                         }
+                        if paragraph && default {
+                            table.default_paragraph = Some(id.clone());
+                        }
+                        if is_start {
+                            cur_style = Some((id, DocStyle::default()));
+                        }
                     }
-                    b"pgMar" => {
-                        for attr in e.attributes().flatten() {
-                            match attr.key.local_name().as_ref() {
-                                b"top" => setup.margin_top = parse_twip(&attr.value),
-                                b"bottom" => setup.margin_bottom = parse_twip(&attr.value),
-                                b"left" => setup.margin_left = parse_twip(&attr.value),
-                                b"right" => setup.margin_right = parse_twip(&attr.value),
-                                _ => {}
-                            }
+                    b"tblStylePr" => in_conditional = is_start,
+                    b"pPr" => in_ppr = is_start,
+                    b"rPr" => in_rpr = is_start,
+                    b"basedOn" => {
+                        if let Some((_, style)) = cur_style.as_mut() {
+                            style.based_on = attr_val(e);
                         }
                     }
-                    _ => {}
+                    _ if in_conditional => {}
+                    _ => {
+                        let props = match cur_style.as_mut() {
+                            Some((_, style)) => &mut style.props,
+                            None if in_defaults => &mut table.defaults,
+                            None => continue,
+                        };
+                        apply_style_property(props, e, in_ppr, in_rpr);
+                    }
                 }
             }
-            Ok(quick_xml::events::Event::Eof) => break,
-            Err(_) => break,
+            Ok(quick_xml::events::Event::End(ref e)) => match e.local_name().as_ref() {
+                b"docDefaults" => in_defaults = false,
+                b"style" => {
+                    if let Some((id, style)) = cur_style.take() {
+                        table.styles.insert(id, style);
+                    }
+                }
+                b"tblStylePr" => in_conditional = false,
+                b"pPr" => in_ppr = false,
+                b"rPr" => in_rpr = false,
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Eof) | Err(_) => break,
             _ => {}
         }
         buf.clear();
     }
+    table
+}
 
-    setup
+/// スタイル定義内の w:pPr / w:rPr の子要素を書式に反映
+fn apply_style_property(props: &mut StyleProps, e: &quick_xml::events::BytesStart, in_ppr: bool, in_rpr: bool) {
+    let toggle = |e: &quick_xml::events::BytesStart| {
+        attr_val(e).is_none_or(|v| v != "0" && v != "false")
+    };
+    match e.local_name().as_ref() {
+        b"sz" if in_rpr => {
+            props.font_size = attr_val(e).and_then(|v| v.parse::<f64>().ok()).map(|half| half / 2.0);
+        }
+        b"b" if in_rpr => props.bold = Some(toggle(e)),
+        b"i" if in_rpr => props.italic = Some(toggle(e)),
+        b"color" if in_rpr => props.color = attr_val(e).and_then(|hex| parse_hex_color(&hex)),
+        b"rFonts" if in_rpr => {
+            props.font_name = e
+                .attributes()
+                .flatten()
+                .find(|a| matches!(a.key.local_name().as_ref(), b"ascii" | b"eastAsia" | b"hAnsi"))
+                .map(|a| String::from_utf8_lossy(&a.value).to_string());
+        }
+        b"jc" if in_ppr => {
+            props.align = attr_val(e).map(|v| match v.as_str() {
+                "center" => TextAlign::Center,
+                "right" => TextAlign::Right,
+                _ => TextAlign::Left,
+            });
+        }
+        b"spacing" if in_ppr => {
+            for attr in e.attributes().flatten() {
+                match attr.key.local_name().as_ref() {
+                    b"before" => props.spacing_before = Some(parse_twip(&attr.value)),
+                    b"after" => props.spacing_after = Some(parse_twip(&attr.value)),
+                    b"line" => {
+                        let line = String::from_utf8_lossy(&attr.value).parse::<f64>().unwrap_or(240.0);
+                        props.line_spacing = Some(line / 240.0);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
 }
 
-fn parse_twip(val: &[u8]) -> f64 {
-    let s = String::from_utf8_lossy(val);
-    s.parse::<f64>().unwrap_or(0.0) / TWIP_PER_PT
+/// w:val 属性の値
+fn attr_val(e: &quick_xml::events::BytesStart) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == b"val")
+        .map(|a| String::from_utf8_lossy(&a.value).to_string())
 }
 
 // ── ドキュメント本文解析 ──
 
-fn parse_document_body(xml: &str) -> Vec<BodyElement> {
+/// wps:spPr 内の塗り・線の色（a:srgbClr）と図形の種類（a:prstGeom）
+fn apply_shape_property(shape: &mut DrawingShape, e: &quick_xml::events::BytesStart, in_line: bool) {
+    match e.local_name().as_ref() {
+        b"prstGeom" => {
+            shape.rect = e.try_get_attribute("prst").ok().flatten().is_some_and(|prst| prst.value.as_ref() == b"rect");
+        }
+        _ => {
+            let color = attr_val(e).and_then(|hex| parse_hex_color(&hex));
+            if in_line {
+                shape.stroke = color;
+            } else {
+                shape.fill = color;
+            }
+        }
+    }
+}
+
+fn parse_document_body(xml: &str, styles: &StyleTable, tracked_changes: TrackedChanges) -> Vec<BodyElement> {
     let mut elements = Vec::new();
     let mut reader = quick_xml::Reader::from_str(xml);
     let mut buf = Vec::new();
@@ -268,40 +1077,71 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
     let mut cur_line_spacing = 1.15f64;
     let mut cur_indent_left = 0.0f64;
     let mut cur_indent_first = 0.0f64;
+    let mut cur_tab_stops: Vec<TabStop> = Vec::new();
     let mut cur_is_heading = false;
     let mut cur_heading_level = 0u32;
     let mut cur_numbering: Option<String> = None;
+    // 段落スタイルを解決した書式（ランの既定値になる）
+    let mut para_style = styles.paragraph(None);
 
     // Run state
     let mut cur_font_size = 11.0f64;
     let mut cur_bold = false;
     let mut cur_italic = false;
-    let mut cur_underline = false;
+    let mut cur_underline: Option<UnderlineStyle> = None;
     let mut cur_color = Color::BLACK;
     let mut cur_font_name: Option<String> = None;
     let mut cur_highlight: Option<Color> = None;
+    let mut cur_strike = false;
+    let mut cur_baseline_shift = 0.0f64;
     let mut in_run = false;
     let mut in_rpr = false;
     let mut in_ppr = false;
     let mut in_text = false;
     let mut cur_text = String::new();
+    // 変更履歴の挿入（w:ins / w:moveTo）・削除（w:del / w:moveFrom）の内側か
+    let mut cur_revision: Option<Revision> = None;
+    // リンク（w:hyperlink）の内側か
+    let mut in_hyperlink = false;
+    // 現在のランの最初の要素の位置（ラン終了時に変更履歴に応じて除外・装飾する）
+    let mut run_start = 0usize;
+
+    // Section state（段落内の w:sectPr）
+    let mut in_sect_pr = false;
+    let mut cur_section: Option<PageSetup> = None;
 
     // Table state
     let mut tbl_rows: Vec<DocTableRow> = Vec::new();
     let mut tbl_col_widths: Vec<f64> = Vec::new();
     let mut in_tbl_row = false;
     let mut cur_cells: Vec<DocTableCell> = Vec::new();
-    let mut cur_row_height: Option<f64> = None;
     let mut in_tbl_cell = false;
     let mut cell_paragraphs: Vec<DocParagraph> = Vec::new();
     let mut cell_width = 0.0f64;
     let mut cell_shading: Option<Color> = None;
+    let mut cell_borders: Option<CellBorders> = None;
+    let mut in_tc_borders = false;
 
     // Image state
     let mut in_drawing = false;
     let mut drawing_r_id = String::new();
     let mut drawing_cx = 0.0f64;
     let mut drawing_cy = 0.0f64;
+    let mut drawing_has_extent = false;
+    let mut drawing_anchor: Option<ImageAnchor> = None;
+    // 0 = なし, 1 = positionH, 2 = positionV
+    let mut in_position = 0u8;
+    let mut in_pos_offset = false;
+    let mut pos_offset_text = String::new();
+
+    // OLE object state
+    let mut cur_object: Option<OleObjectState> = None;
+
+    // Shape / text box state（w:drawing の wps:wsp と w:pict の VML 図形）
+    let mut in_pict = false;
+    let mut drawing_shape: Option<DrawingShape> = None;
+    let mut in_shape_props = false;
+    let mut in_shape_line = false;
 
     loop {
         match reader.read_event_into(&mut buf) {
@@ -320,9 +1160,14 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                         cur_line_spacing = 1.15;
                         cur_indent_left = 0.0;
                         cur_indent_first = 0.0;
+                        cur_tab_stops.clear();
                         cur_is_heading = false;
                         cur_heading_level = 0;
                         cur_numbering = None;
+                        para_style = styles.paragraph(None);
+                        para_style.apply_to_paragraph(
+                            &mut cur_align, &mut cur_spacing_before, &mut cur_spacing_after, &mut cur_line_spacing,
+                        );
                     }
                     b"p" if in_tbl_cell => {
                         in_paragraph = true;
@@ -334,36 +1179,140 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                         cur_line_spacing = 1.0;
                         cur_indent_left = 0.0;
                         cur_indent_first = 0.0;
+                        cur_tab_stops.clear();
                         cur_is_heading = false;
                         cur_heading_level = 0;
                         cur_numbering = None;
+                        para_style = styles.paragraph(None);
+                        para_style.apply_to_paragraph(
+                            &mut cur_align, &mut cur_spacing_before, &mut cur_spacing_after, &mut cur_line_spacing,
+                        );
                     }
                     b"pPr" if in_paragraph => {
                         in_ppr = true;
                     }
+                    b"sectPr" if in_ppr => {
+                        in_sect_pr = true;
+                        cur_section = Some(PageSetup::default());
+                    }
+                    _ if in_sect_pr => {
+                        apply_section_property(cur_section.get_or_insert_with(PageSetup::default), e);
+                    }
+                    b"ins" | b"moveTo" if in_paragraph && !in_run && !in_ppr => {
+                        cur_revision = Some(Revision::Inserted);
+                    }
+                    b"del" | b"moveFrom" if in_paragraph && !in_run && !in_ppr => {
+                        cur_revision = Some(Revision::Deleted);
+                    }
+                    b"hyperlink" if in_paragraph && !in_run => in_hyperlink = true,
                     b"r" if in_paragraph => {
                         in_run = true;
+                        run_start = cur_runs.len();
                         // Reset run state to paragraph defaults
                         cur_font_size = 11.0;
                         cur_bold = false;
                         cur_italic = false;
-                        cur_underline = false;
+                        cur_underline = None;
                         cur_color = Color::BLACK;
                         cur_font_name = None;
                         cur_highlight = None;
+                        cur_strike = false;
+                        cur_baseline_shift = 0.0;
+                        para_style.apply_to_run(
+                            &mut cur_font_size, &mut cur_bold, &mut cur_italic, &mut cur_color, &mut cur_font_name,
+                        );
                     }
                     b"rPr" if in_run => {
                         in_rpr = true;
                     }
-                    b"t" if in_run => {
+                    // 削除されたテキストは w:delText（描画するかは変更履歴の扱いで決める）
+                    b"t" | b"delText" if in_run => {
                         in_text = true;
                         cur_text.clear();
                     }
+                    b"object" if in_run => {
+                        cur_object = Some(OleObjectState::from_object(e));
+                    }
+                    _ if cur_object.is_some() => {
+                        if let Some(object) = cur_object.as_mut() {
+                            object.apply(e);
+                        }
+                    }
                     b"drawing" if in_run => {
                         in_drawing = true;
                         drawing_r_id.clear();
                         drawing_cx = 72.0;
                         drawing_cy = 72.0;
+                        drawing_has_extent = false;
+                        drawing_anchor = None;
+                    }
+                    b"pict" if in_run => {
+                        in_pict = true;
+                        drawing_shape = None;
+                    }
+                    b"shape" | b"rect" | b"roundrect" | b"oval" if in_pict && drawing_shape.is_none() => {
+                        let (shape, width, height, anchor) = DrawingShape::from_vml(e);
+                        drawing_shape = Some(shape);
+                        (drawing_cx, drawing_cy, drawing_anchor) = (width, height, anchor);
+                    }
+                    b"wsp" if in_drawing => {
+                        drawing_shape = Some(DrawingShape { stroke_width: 0.75, ..DrawingShape::default() });
+                    }
+                    b"spPr" if in_drawing && drawing_shape.is_some() => {
+                        in_shape_props = true;
+                    }
+                    b"ln" if in_shape_props => {
+                        in_shape_line = true;
+                        if let (Some(shape), Some(w)) = (drawing_shape.as_mut(), e.try_get_attribute("w").ok().flatten()) {
+                            shape.stroke_width = String::from_utf8_lossy(&w.value).parse::<f64>().unwrap_or(9525.0) / EMU_PER_PT;
+                        }
+                    }
+                    b"srgbClr" | b"prstGeom" if in_shape_props => {
+                        if let Some(shape) = drawing_shape.as_mut() {
+                            apply_shape_property(shape, e, in_shape_line);
+                        }
+                    }
+                    // テキストボックスの内容は本文と同じ解析器で段落・表として読み込む
+                    b"txbxContent" if (in_drawing || in_pict) && drawing_shape.is_some() => {
+                        let span = reader.read_to_end_into(e.name(), &mut Vec::new());
+                        depth -= 1;
+                        if let (Ok(span), Some(shape)) = (span, drawing_shape.as_mut()) {
+                            let inner = &xml[span.start as usize..span.end as usize];
+                            shape.text_box = parse_document_body(&format!("<body>{}</body>", inner), styles, tracked_changes);
+                        }
+                    }
+                    b"anchor" if in_drawing => {
+                        drawing_anchor = Some(ImageAnchor {
+                            offset_x: 0.0,
+                            offset_y: 0.0,
+                            relative_h: AnchorRelative::Margin,
+                            relative_v: AnchorRelative::Paragraph,
+                        });
+                    }
+                    b"positionH" | b"positionV" if in_drawing => {
+                        let horizontal = local.as_ref() == b"positionH";
+                        in_position = if horizontal { 1 } else { 2 };
+                        for attr in e.attributes().flatten() {
+                            if attr.key.local_name().as_ref() == b"relativeFrom" {
+                                let rel = AnchorRelative::from_attr(&String::from_utf8_lossy(&attr.value));
+                                if let Some(anchor) = drawing_anchor.as_mut() {
+                                    if horizontal {
+                                        anchor.relative_h = rel;
+                                    } else {
+                                        anchor.relative_v = rel;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    b"posOffset" if in_position != 0 => {
+                        in_pos_offset = true;
+                        pos_offset_text.clear();
+                    }
+                    b"blip" if in_drawing => {
+                        if let Some(r_id) = blip_embed_id(e) {
+                            drawing_r_id = r_id;
+                        }
                     }
                     b"tbl" if in_body => {
                         in_table = true;
@@ -374,13 +1323,17 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                     b"tr" if in_table => {
                         in_tbl_row = true;
                         cur_cells.clear();
-                        cur_row_height = None;
                     }
                     b"tc" if in_tbl_row => {
                         in_tbl_cell = true;
                         cell_paragraphs.clear();
                         cell_width = 0.0;
                         cell_shading = None;
+                        cell_borders = None;
+                    }
+                    b"tcBorders" if in_tbl_cell => {
+                        in_tc_borders = true;
+                        cell_borders = Some(CellBorders::default());
                     }
                     _ => {}
                 }
@@ -389,6 +1342,52 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
             Ok(quick_xml::events::Event::Empty(ref e)) => {
                 let local = e.local_name();
                 match local.as_ref() {
+                    // Section properties (pgSz / pgMar / type)
+                    _ if in_sect_pr => {
+                        apply_section_property(cur_section.get_or_insert_with(PageSetup::default), e);
+                    }
+                    // OLE object (v:imagedata / o:OLEObject)
+                    _ if cur_object.is_some() => {
+                        if let Some(object) = cur_object.as_mut() {
+                            object.apply(e);
+                        }
+                    }
+                    // 空の段落 <w:p/>（間隔調整の空行）
+                    b"p" if (in_body && !in_table) || in_tbl_cell => {
+                        let mut para = DocParagraph {
+                            runs: Vec::new(),
+                            align: TextAlign::Left,
+                            spacing_before: 0.0,
+                            spacing_after: if in_tbl_cell { 0.0 } else { 8.0 },
+                            line_spacing: if in_tbl_cell { 1.0 } else { 1.15 },
+                            indent_left: 0.0,
+                            indent_first: 0.0,
+                            is_heading: false,
+                            heading_level: 0,
+                            numbering: None,
+                            tab_stops: Vec::new(),
+                        };
+                        styles.paragraph(None).apply_to_paragraph(
+                            &mut para.align, &mut para.spacing_before, &mut para.spacing_after, &mut para.line_spacing,
+                        );
+                        if in_tbl_cell {
+                            cell_paragraphs.push(para);
+                        } else {
+                            elements.push(BodyElement::Paragraph(para));
+                        }
+                    }
+                    // Cell borders (w:tcBorders の各辺)
+                    edge @ (b"top" | b"bottom" | b"left" | b"start" | b"right" | b"end") if in_tc_borders => {
+                        if let Some(borders) = cell_borders.as_mut() {
+                            let spec = parse_border_edge(e);
+                            match edge {
+                                b"top" => borders.top = spec,
+                                b"bottom" => borders.bottom = spec,
+                                b"left" | b"start" => borders.left = spec,
+                                _ => borders.right = spec,
+                            }
+                        }
+                    }
                     // Paragraph properties
                     b"jc" if in_ppr => {
                         for attr in e.attributes().flatten() {
@@ -440,6 +1439,16 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                         for attr in e.attributes().flatten() {
                             if attr.key.local_name().as_ref() == b"val" {
                                 let style = String::from_utf8_lossy(&attr.value).to_string();
+                                // w:pStyle は w:pPr の先頭にあるため、続く直接書式がスタイルを上書きする
+                                para_style = styles.paragraph(Some(&style));
+                                para_style.apply_to_paragraph(
+                                    &mut cur_align, &mut cur_spacing_before, &mut cur_spacing_after,
+                                    &mut cur_line_spacing,
+                                );
+                                // styles.xml に定義があればその書式に従い、無い場合のみ名前から見出しと推定する
+                                if styles.contains(&style) {
+                                    continue;
+                                }
                                 if style.starts_with("Heading") || style.starts_with("heading") {
                                     cur_is_heading = true;
                                     cur_heading_level = style
@@ -452,7 +1461,14 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                             }
                         }
                     }
-                    // Run properties
+                    // Run properties（w:rStyle は w:rPr の先頭）
+                    b"rStyle" if in_rpr => {
+                        if let Some(style) = attr_val(e) {
+                            styles.resolve(&style).apply_to_run(
+                                &mut cur_font_size, &mut cur_bold, &mut cur_italic, &mut cur_color, &mut cur_font_name,
+                            );
+                        }
+                    }
                     b"sz" if in_rpr => {
                         for attr in e.attributes().flatten() {
                             if attr.key.local_name().as_ref() == b"val" {
@@ -481,7 +1497,43 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                         }
                     }
                     b"u" if in_rpr => {
-                        cur_underline = true;
+                        let mut underline = Some(UnderlineStyle::new(UnderlineKind::Single));
+                        let mut color = None;
+                        for attr in e.attributes().flatten() {
+                            match attr.key.local_name().as_ref() {
+                                b"val" => {
+                                    underline = parse_underline_val(&String::from_utf8_lossy(&attr.value));
+                                }
+                                b"color" => {
+                                    let hex = String::from_utf8_lossy(&attr.value).to_string();
+                                    if hex != "auto" {
+                                        color = parse_hex_color(&hex);
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                        cur_underline = underline.map(|u| UnderlineStyle { color, ..u });
+                    }
+                    b"strike" | b"dstrike" if in_rpr => {
+                        cur_strike = true;
+                        for attr in e.attributes().flatten() {
+                            if attr.key.local_name().as_ref() == b"val" {
+                                let val = String::from_utf8_lossy(&attr.value);
+                                cur_strike = val != "0" && val != "false";
+                            }
+                        }
+                    }
+                    b"vertAlign" if in_rpr => {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.local_name().as_ref() == b"val" {
+                                cur_baseline_shift = match String::from_utf8_lossy(&attr.value).as_ref() {
+                                    "superscript" => SUPERSCRIPT_SHIFT,
+                                    "subscript" => SUBSCRIPT_SHIFT,
+                                    _ => 0.0,
+                                };
+                            }
+                        }
                     }
                     b"color" if in_rpr => {
                         for attr in e.attributes().flatten() {
@@ -514,10 +1566,13 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                             }
                         }
                     }
-                    // Line break
+                    // Line break / page break
                     b"br" if in_run => {
+                        let is_page_break = e.attributes().flatten().any(|attr| {
+                            attr.key.local_name().as_ref() == b"type" && attr.value.as_ref() == b"page"
+                        });
                         cur_runs.push(DocRun {
-                            content: RunContent::LineBreak,
+                            content: if is_page_break { RunContent::PageBreak } else { RunContent::LineBreak },
                             font_size: cur_font_size,
                             bold: cur_bold,
                             italic: cur_italic,
@@ -525,8 +1580,15 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                             color: cur_color,
                             font_name: cur_font_name.clone(),
                             highlight: cur_highlight,
+                            strikethrough: cur_strike,
+                            baseline_shift: cur_baseline_shift,
+                            hyperlink: false,
                         });
                     }
+                    // Custom tab stops (w:tabs/w:tab)
+                    b"tab" if in_ppr => {
+                        cur_tab_stops.extend(parse_tab_stop(e));
+                    }
                     // Tab
                     b"tab" if in_run => {
                         cur_runs.push(DocRun {
@@ -538,6 +1600,58 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                             color: cur_color,
                             font_name: cur_font_name.clone(),
                             highlight: cur_highlight,
+                            strikethrough: cur_strike,
+                            baseline_shift: cur_baseline_shift,
+                            hyperlink: false,
+                        });
+                    }
+                    // Footnote / endnote reference
+                    b"footnoteReference" | b"endnoteReference" if in_run => {
+                        let kind = if local.as_ref() == b"footnoteReference" {
+                            NoteKind::Footnote
+                        } else {
+                            NoteKind::Endnote
+                        };
+                        let id = e
+                            .attributes()
+                            .flatten()
+                            .find(|attr| attr.key.local_name().as_ref() == b"id")
+                            .map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+                            .unwrap_or_default();
+                        cur_runs.push(DocRun {
+                            content: RunContent::NoteRef { kind, id },
+                            font_size: cur_font_size,
+                            bold: cur_bold,
+                            italic: cur_italic,
+                            underline: None,
+                            color: cur_color,
+                            font_name: cur_font_name.clone(),
+                            highlight: cur_highlight,
+                            strikethrough: false,
+                            baseline_shift: SUPERSCRIPT_SHIFT,
+                            hyperlink: false,
+                        });
+                    }
+                    // コメント参照（コメントを描画する場合の注釈の接続位置）
+                    b"commentReference" if in_run => {
+                        let id = e
+                            .attributes()
+                            .flatten()
+                            .find(|attr| attr.key.local_name().as_ref() == b"id")
+                            .map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+                            .unwrap_or_default();
+                        cur_runs.push(DocRun {
+                            content: RunContent::CommentRef { id },
+                            font_size: cur_font_size,
+                            bold: false,
+                            italic: false,
+                            underline: None,
+                            color: cur_color,
+                            font_name: None,
+                            highlight: None,
+                            strikethrough: false,
+                            baseline_shift: 0.0,
+                            hyperlink: false,
                         });
                     }
                     // Table column widths
@@ -567,41 +1681,44 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                             }
                         }
                     }
-                    // Row height
-                    b"trHeight" if in_tbl_row => {
-                        for attr in e.attributes().flatten() {
-                            if attr.key.local_name().as_ref() == b"val" {
-                                cur_row_height = Some(parse_twip(&attr.value));
-                            }
-                        }
-                    }
                     // Image blip in drawing
                     b"blip" if in_drawing => {
-                        for attr in e.attributes().flatten() {
-                            let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
-                            if key.ends_with("embed") {
-                                drawing_r_id = String::from_utf8_lossy(&attr.value).to_string();
-                            }
+                        if let Some(r_id) = blip_embed_id(e) {
+                            drawing_r_id = r_id;
                         }
                     }
-                    // Image extent
-                    b"ext" if in_drawing => {
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"cx" => {
-                                    drawing_cx = String::from_utf8_lossy(&attr.value)
-                                        .parse::<f64>()
-                                        .unwrap_or(0.0)
-                                        / 12700.0; // EMU to pt
-                                }
-                                b"cy" => {
-                                    drawing_cy = String::from_utf8_lossy(&attr.value)
-                                        .parse::<f64>()
-                                        .unwrap_or(0.0)
-                                        / 12700.0;
+                    b"shape" | b"rect" | b"roundrect" | b"oval" if in_pict && drawing_shape.is_none() => {
+                        let (shape, width, height, anchor) = DrawingShape::from_vml(e);
+                        drawing_shape = Some(shape);
+                        (drawing_cx, drawing_cy, drawing_anchor) = (width, height, anchor);
+                    }
+                    b"srgbClr" | b"prstGeom" if in_shape_props => {
+                        if let Some(shape) = drawing_shape.as_mut() {
+                            apply_shape_property(shape, e, in_shape_line);
+                        }
+                    }
+                    // Image extent (wp:extent を優先し、無い場合は a:ext で代用)
+                    b"extent" | b"ext" if in_drawing => {
+                        let is_extent = local.as_ref() == b"extent";
+                        if is_extent || !drawing_has_extent {
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"cx" => {
+                                        drawing_cx = String::from_utf8_lossy(&attr.value)
+                                            .parse::<f64>()
+                                            .unwrap_or(0.0)
+                                            / EMU_PER_PT;
+                                    }
+                                    b"cy" => {
+                                        drawing_cy = String::from_utf8_lossy(&attr.value)
+                                            .parse::<f64>()
+                                            .unwrap_or(0.0)
+                                            / EMU_PER_PT;
+                                    }
+                                    _ => {}
                                 }
-                                _ => {}
                             }
+                            drawing_has_extent |= is_extent;
                         }
                     }
                     _ => {}
@@ -612,7 +1729,9 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                 let local = e.local_name();
                 match local.as_ref() {
                     b"body" => in_body = false,
-                    b"t" => {
+                    b"tcBorders" => in_tc_borders = false,
+                    b"sectPr" => in_sect_pr = false,
+                    b"t" | b"delText" => {
                         if in_text {
                             cur_runs.push(DocRun {
                                 content: RunContent::Text(cur_text.clone()),
@@ -623,6 +1742,9 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                                 color: cur_color,
                                 font_name: cur_font_name.clone(),
                                 highlight: cur_highlight,
+                                strikethrough: cur_strike,
+                                baseline_shift: cur_baseline_shift,
+                                hyperlink: false,
                             });
                             cur_text.clear();
                             in_text = false;
@@ -630,7 +1752,17 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                     }
                     b"r" => {
                         in_run = false;
+                        if in_hyperlink {
+                            cur_runs[run_start..].iter_mut().for_each(|run| run.hyperlink = true);
+                        }
+                        if let Some(revision) = cur_revision {
+                            revision.apply(tracked_changes, &mut cur_runs, run_start);
+                        }
+                    }
+                    b"ins" | b"del" | b"moveTo" | b"moveFrom" if !in_run => {
+                        cur_revision = None;
                     }
+                    b"hyperlink" => in_hyperlink = false,
                     b"rPr" => {
                         in_rpr = false;
                     }
@@ -638,20 +1770,60 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                         in_ppr = false;
                     }
                     b"drawing" => {
+                        let shape = drawing_shape.take();
                         if in_drawing && !drawing_r_id.is_empty() {
-                            cur_runs.push(DocRun {
-                                content: RunContent::Image { r_id: drawing_r_id.clone(), width: drawing_cx, height: drawing_cy },
-                                font_size: cur_font_size,
-                                bold: false,
-                                italic: false,
-                                underline: false,
-                                color: Color::BLACK,
-                                font_name: None,
-                                highlight: None,
-                            });
+                            let content = RunContent::Image {
+                                r_id: drawing_r_id.clone(),
+                                width: drawing_cx,
+                                height: drawing_cy,
+                                anchor: drawing_anchor,
+                            };
+                            cur_runs.push(DocRun::embedded(content, cur_font_size));
+                        } else if let Some(shape) = shape.filter(|_| in_drawing) {
+                            let content = RunContent::Shape { shape, width: drawing_cx, height: drawing_cy, anchor: drawing_anchor };
+                            cur_runs.push(DocRun::embedded(content, cur_font_size));
                         }
                         in_drawing = false;
                     }
+                    b"pict" => {
+                        if let Some(shape) = drawing_shape.take().filter(|_| in_pict) {
+                            let content = RunContent::Shape { shape, width: drawing_cx, height: drawing_cy, anchor: drawing_anchor };
+                            cur_runs.push(DocRun::embedded(content, cur_font_size));
+                        }
+                        in_pict = false;
+                        drawing_anchor = None;
+                    }
+                    b"spPr" => {
+                        in_shape_props = false;
+                    }
+                    b"ln" => {
+                        in_shape_line = false;
+                    }
+                    b"object" => {
+                        if let Some(object) = cur_object.take() {
+                            let content = RunContent::Ole {
+                                prog_id: object.prog_id,
+                                preview_r_id: object.preview_r_id,
+                                width: object.width,
+                                height: object.height,
+                            };
+                            cur_runs.push(DocRun::embedded(content, cur_font_size));
+                        }
+                    }
+                    b"positionH" | b"positionV" => {
+                        in_position = 0;
+                    }
+                    b"posOffset" if in_pos_offset => {
+                        let offset = pos_offset_text.trim().parse::<f64>().unwrap_or(0.0) / EMU_PER_PT;
+                        if let Some(anchor) = drawing_anchor.as_mut() {
+                            if in_position == 1 {
+                                anchor.offset_x = offset;
+                            } else {
+                                anchor.offset_y = offset;
+                            }
+                        }
+                        in_pos_offset = false;
+                    }
                     b"p" if in_paragraph && depth == para_depth => {
                         let para = DocParagraph {
                             runs: cur_runs.clone(),
@@ -664,12 +1836,16 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                             is_heading: cur_is_heading,
                             heading_level: cur_heading_level,
                             numbering: cur_numbering.clone(),
+                            tab_stops: cur_tab_stops.clone(),
                         };
 
                         if in_tbl_cell {
                             cell_paragraphs.push(para);
                         } else {
                             elements.push(BodyElement::Paragraph(para));
+                            if let Some(section) = cur_section.take() {
+                                elements.push(BodyElement::SectionBreak(section));
+                            }
                         }
                         in_paragraph = false;
                     }
@@ -678,15 +1854,13 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                             paragraphs: cell_paragraphs.clone(),
                             width: cell_width,
                             shading: cell_shading,
+                            borders: cell_borders,
                         });
                         in_tbl_cell = false;
                         cell_paragraphs.clear();
                     }
                     b"tr" if in_tbl_row => {
-                        tbl_rows.push(DocTableRow {
-                            cells: cur_cells.clone(),
-                            height: cur_row_height,
-                        });
+                        tbl_rows.push(DocTableRow { cells: cur_cells.clone() });
                         in_tbl_row = false;
                     }
                     b"tbl" if in_table && depth == table_depth => {
@@ -697,19 +1871,298 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
                         }));
                         in_table = false;
                     }
-                    _ => {}
+                    _ => {}
+                }
+                depth -= 1;
+            }
+
+            Ok(quick_xml::events::Event::Text(ref e)) => {
+                if in_text {
+                    if let Ok(text) = e.unescape() {
+                        cur_text.push_str(&text);
+                    }
+                } else if in_pos_offset {
+                    if let Ok(text) = e.unescape() {
+                        pos_offset_text.push_str(&text);
+                    }
+                }
+            }
+
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    elements
+}
+
+/// 画像リレーションシップを解決
+/// OLEオブジェクトは描画できるプレビュー画像があれば画像に置き換え、ProgIDを警告に記録します。
+/// パッケージ内に無い画像は `supplied` のデータを使い、それも無ければ遅延アセットとして警告に記録します。
+fn resolve_images(
+    elements: &[BodyElement],
+    rels: &Option<String>,
+    archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
+    supplied: &[(String, Vec<u8>)],
+    warnings: &mut Vec<String>,
+) -> Vec<BodyElement> {
+    elements
+        .iter()
+        .map(|elem| match elem {
+            BodyElement::Paragraph(para) => {
+                let resolved_runs: Vec<DocRun> = para
+                    .runs
+                    .iter()
+                    .map(|run| {
+                        if let RunContent::Ole { prog_id, preview_r_id, width, height } = &run.content {
+                            let preview = preview_r_id
+                                .as_ref()
+                                .zip(rels.as_ref())
+                                .and_then(|(r_id, rels_xml)| resolve_rel(rels_xml, r_id))
+                                .map(|target| format!("word/{}", target))
+                                .and_then(|path| read_zip_entry_bytes(archive, &path).ok().map(|data| (data, path)))
+                                .filter(|(data, _)| ole::is_renderable_preview(data));
+                            let name = ole::program_name(prog_id);
+                            let Some((data, path)) = preview else {
+                                push_unique_warning(warnings, format!("{}{}", ole::OLE_PLACEHOLDER_WARNING, name));
+                                return run.clone();
+                            };
+                            push_unique_warning(warnings, format!("{}{}", ole::OLE_PREVIEW_WARNING, name));
+                            let mime = if path.ends_with(".png") { "image/png" } else { "image/jpeg" };
+                            return DocRun {
+                                content: RunContent::ImageData {
+                                    data,
+                                    mime_type: mime.to_string(),
+                                    width: *width,
+                                    height: *height,
+                                    anchor: None,
+                                },
+                                ..run.clone()
+                            };
+                        }
+                        // テキストボックス内の画像も解決
+                        if let RunContent::Shape { shape, width, height, anchor } = &run.content {
+                            let text_box = resolve_images(&shape.text_box, rels, archive, supplied, warnings);
+                            let shape = DrawingShape { text_box, ..shape.clone() };
+                            return DocRun {
+                                content: RunContent::Shape { shape, width: *width, height: *height, anchor: *anchor },
+                                ..run.clone()
+                            };
+                        }
+                        if let RunContent::Image { r_id, width: img_w, height: img_h, anchor } = &run.content {
+                            let img_path = rels
+                                .as_ref()
+                                .and_then(|rels_xml| resolve_rel(rels_xml, r_id))
+                                .map(|target| format!("word/{}", target));
+                            let data = img_path.as_ref().and_then(|path| {
+                                read_zip_entry_bytes(archive, path)
+                                    .ok()
+                                    .or_else(|| deferred_assets::find_supplied(supplied, path).map(<[u8]>::to_vec))
+                            });
+                            let (Some(data), Some(img_path)) = (data, img_path.as_ref()) else {
+                                let asset = DeferredAsset { relationship_id: r_id.clone(), path: img_path };
+                                push_unique_warning(warnings, asset.warning());
+                                return run.clone();
+                            };
+                            let mime = if img_path.ends_with(".png") { "image/png" } else { "image/jpeg" };
+                            return DocRun {
+                                content: RunContent::ImageData {
+                                    data,
+                                    mime_type: mime.to_string(),
+                                    width: *img_w,
+                                    height: *img_h,
+                                    anchor: *anchor,
+                                },
+                                ..run.clone()
+                            };
+                        }
+                        run.clone()
+                    })
+                    .collect();
+                BodyElement::Paragraph(DocParagraph {
+                    runs: resolved_runs,
+                    ..para.clone()
+                })
+            }
+            other => other.clone(),
+        })
+        .collect()
+}
+
+/// テキストのみのモード用に、未解決の画像のランを取り除く（プレースホルダーのラベルを出力しないため）
+fn without_images(elements: &[BodyElement]) -> Vec<BodyElement> {
+    elements
+        .iter()
+        .map(|elem| match elem {
+            BodyElement::Paragraph(para) => BodyElement::Paragraph(DocParagraph {
+                runs: para
+                    .runs
+                    .iter()
+                    .filter(|run| !matches!(run.content, RunContent::Image { .. }))
+                    .map(|run| match &run.content {
+                        RunContent::Shape { shape, width, height, anchor } => DocRun {
+                            content: RunContent::Shape {
+                                shape: DrawingShape { text_box: without_images(&shape.text_box), ..shape.clone() },
+                                width: *width,
+                                height: *height,
+                                anchor: *anchor,
+                            },
+                            ..run.clone()
+                        },
+                        _ => run.clone(),
+                    })
+                    .collect(),
+                ..para.clone()
+            }),
+            other => other.clone(),
+        })
+        .collect()
+}
+
+/// リンクの付いたランの書式を `HyperlinkStyle` に合わせる（表のセル・テキストボックスの中も含む）
+/// 強調はテーマの hlink の色と下線、プレーンは下線を除いてテーマの hlink の色を既定の文字色に戻します。
+fn apply_hyperlink_style(elements: &mut [BodyElement], style: HyperlinkStyle, link_color: Color) {
+    if style == HyperlinkStyle::Source {
+        return;
+    }
+    for element in elements {
+        match element {
+            BodyElement::Paragraph(para) => apply_hyperlink_style_to_runs(&mut para.runs, style, link_color),
+            BodyElement::Table(table) => {
+                let cells = table.rows.iter_mut().flat_map(|row| row.cells.iter_mut());
+                for para in cells.flat_map(|cell| cell.paragraphs.iter_mut()) {
+                    apply_hyperlink_style_to_runs(&mut para.runs, style, link_color);
+                }
+            }
+            BodyElement::SectionBreak(_) => {}
+        }
+    }
+}
+
+fn apply_hyperlink_style_to_runs(runs: &mut [DocRun], style: HyperlinkStyle, link_color: Color) {
+    for run in runs {
+        if let RunContent::Shape { shape, .. } = &mut run.content {
+            apply_hyperlink_style(&mut shape.text_box, style, link_color);
+        }
+        if !run.hyperlink {
+            continue;
+        }
+        if style == HyperlinkStyle::Emphasize {
+            run.color = link_color;
+            run.underline = Some(UnderlineStyle::new(UnderlineKind::Single));
+        } else {
+            run.underline = None;
+            if run.color == link_color {
+                run.color = Color::BLACK;
+            }
+        }
+    }
+}
+
+/// 同じ警告を重複させずに追加
+fn push_unique_warning(warnings: &mut Vec<String>, warning: String) {
+    if !warnings.contains(&warning) {
+        warnings.push(warning);
+    }
+}
+
+/// a:blip の r:embed 属性からリレーションシップIDを取得
+fn blip_embed_id(e: &quick_xml::events::BytesStart) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == b"embed")
+        .map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+}
+
+fn resolve_rel(rels_xml: &str, r_id: &str) -> Option<String> {
+    let mut reader = quick_xml::Reader::from_str(rels_xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Empty(ref e))
+            | Ok(quick_xml::events::Event::Start(ref e)) => {
+                if e.local_name().as_ref() == b"Relationship" {
+                    let mut id = String::new();
+                    let mut target = String::new();
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"Id" => id = String::from_utf8_lossy(&attr.value).to_string(),
+                            b"Target" => target = String::from_utf8_lossy(&attr.value).to_string(),
+                            _ => {}
+                        }
+                    }
+                    if id == r_id {
+                        return Some(target);
+                    }
+                }
+            }
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    None
+}
+
+// ── 脚注 ──
+
+/// footnotes.xml / endnotes.xml から各脚注の段落テキストを取り出す
+/// 区切り線（w:type="separator" 等）の特殊な脚注は除外します。
+fn parse_notes(xml: &str) -> HashMap<String, Vec<String>> {
+    let mut notes = HashMap::new();
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut cur_id: Option<String> = None;
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) => match e.local_name().as_ref() {
+                b"footnote" | b"endnote" => {
+                    let mut id = None;
+                    let mut is_normal = true;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.local_name().as_ref() {
+                            b"id" => id = Some(String::from_utf8_lossy(&attr.value).to_string()),
+                            b"type" => is_normal = attr.value.as_ref() == b"normal",
+                            _ => {}
+                        }
+                    }
+                    cur_id = id.filter(|_| is_normal);
+                    paragraphs.clear();
                 }
-                depth -= 1;
-            }
-
-            Ok(quick_xml::events::Event::Text(ref e)) => {
-                if in_text {
-                    if let Ok(text) = e.unescape() {
-                        cur_text.push_str(&text);
+                b"p" if cur_id.is_some() => paragraphs.push(String::new()),
+                b"t" => in_text = true,
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Empty(ref e)) if cur_id.is_some() => match e.local_name().as_ref() {
+                b"p" => paragraphs.push(String::new()),
+                b"tab" => paragraphs.last_mut().into_iter().for_each(|p| p.push(' ')),
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::End(ref e)) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"footnote" | b"endnote" => {
+                    if let Some(id) = cur_id.take() {
+                        let texts: Vec<String> = paragraphs
+                            .drain(..)
+                            .map(|p| p.trim().to_string())
+                            .filter(|p| !p.is_empty())
+                            .collect();
+                        notes.insert(id, texts);
                     }
                 }
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Text(ref e)) if in_text && cur_id.is_some() => {
+                if let (Ok(text), Some(p)) = (e.unescape(), paragraphs.last_mut()) {
+                    p.push_str(&text);
+                }
             }
-
             Ok(quick_xml::events::Event::Eof) => break,
             Err(_) => break,
             _ => {}
@@ -717,80 +2170,198 @@ fn parse_document_body(xml: &str) -> Vec<BodyElement> {
         buf.clear();
     }
 
-    elements
+    notes
 }
 
-/// 画像リレーションシップを解決
-fn resolve_images(
-    elements: &[BodyElement],
-    rels: &Option<String>,
-    archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
-) -> Vec<BodyElement> {
-    elements
+/// 脚注のフォントサイズ
+const NOTE_FONT_SIZE: f64 = 9.0;
+/// 脚注の行の高さ
+const NOTE_LINE_HEIGHT: f64 = NOTE_FONT_SIZE * 1.2;
+/// 脚注番号の後ろから本文までの字下げ
+const NOTE_INDENT: f64 = 12.0;
+/// 本文と脚注領域の間隔（中央に区切り線を引く）
+const NOTE_SEPARATOR_GAP: f64 = 12.0;
+
+/// 現在のページに配置する脚注（ページ確定時に本文領域の下端へ描画）
+#[derive(Default)]
+struct PageFootnotes {
+    /// (番号, 折り返し済みの行)
+    entries: Vec<(String, Vec<String>)>,
+    /// 脚注領域として確保した高さ（区切り線を含む）
+    height: f64,
+}
+
+impl PageFootnotes {
+    /// 脚注を追加し、その分だけ本文領域を縮める
+    fn add(&mut self, marker: String, paragraphs: &[String], width: f64) {
+        let lines = wrap_note_paragraphs(paragraphs, width);
+        if self.entries.is_empty() {
+            self.height += NOTE_SEPARATOR_GAP;
+        }
+        self.height += lines.len().max(1) as f64 * NOTE_LINE_HEIGHT;
+        self.entries.push((marker, lines));
+    }
+
+    /// 確保した領域に区切り線と脚注を描画してページを返す
+    fn finish_page(&mut self, mut page: Page, setup: &PageSetup) -> Page {
+        if self.entries.is_empty() {
+            return page;
+        }
+        let mut y = setup.margin_top + setup.usable_height() - self.height;
+        page.elements.push(note_separator(setup, y + NOTE_SEPARATOR_GAP / 2.0));
+        y += NOTE_SEPARATOR_GAP;
+        for (marker, lines) in self.entries.drain(..) {
+            y = push_note(&mut page, setup, y, &marker, &lines);
+        }
+        self.height = 0.0;
+        page
+    }
+}
+
+/// 脚注本文を脚注用のフォントサイズで折り返す
+fn wrap_note_paragraphs(paragraphs: &[String], width: f64) -> Vec<String> {
+    paragraphs
         .iter()
-        .map(|elem| match elem {
-            BodyElement::Paragraph(para) => {
-                let resolved_runs: Vec<DocRun> = para
-                    .runs
-                    .iter()
-                    .map(|run| {
-                        if let RunContent::Image { r_id, width: img_w, height: img_h } = &run.content {
-                            if let Some(ref rels_xml) = rels {
-                                if let Some(target) = resolve_rel(rels_xml, r_id) {
-                                    let img_path = format!("word/{}", target);
-                                    if let Ok(data) = read_zip_entry_bytes(archive, &img_path) {
-                                        let mime = if img_path.ends_with(".png") {
-                                            "image/png"
-                                        } else {
-                                            "image/jpeg"
-                                        };
-                                        return DocRun {
-                                            content: RunContent::ImageData {
-                                                data,
-                                                mime_type: mime.to_string(),
-                                                width: *img_w,
-                                                height: *img_h,
-                                            },
-                                            ..run.clone()
-                                        };
-                                    }
-                                }
-                            }
-                        }
-                        run.clone()
-                    })
-                    .collect();
-                BodyElement::Paragraph(DocParagraph {
-                    runs: resolved_runs,
-                    ..para.clone()
-                })
-            }
-            other => other.clone(),
-        })
+        .flat_map(|p| wrap_text_width(p, width - NOTE_INDENT, NOTE_FONT_SIZE))
         .collect()
 }
 
-fn resolve_rel(rels_xml: &str, r_id: &str) -> Option<String> {
-    let mut reader = quick_xml::Reader::from_str(rels_xml);
+/// 本文と脚注を区切る短い横線
+fn note_separator(setup: &PageSetup, y: f64) -> PageElement {
+    PageElement::Line {
+        x1: setup.margin_left,
+        y1: y,
+        x2: setup.margin_left + setup.usable_width() / 3.0,
+        y2: y,
+        width: 0.5,
+        color: Color::BLACK,
+        stroke_style: Default::default(),
+    }
+}
+
+/// 上付きの番号と脚注本文の行を `y` から描画し、次の行のY座標を返す
+fn push_note(page: &mut Page, setup: &PageSetup, mut y: f64, marker: &str, lines: &[String]) -> f64 {
+    page.elements.push(PageElement::Text {
+        x: setup.margin_left,
+        y,
+        width: NOTE_INDENT,
+        text: marker.to_string(),
+        style: FontStyle {
+            font_size: NOTE_FONT_SIZE,
+            baseline_shift: SUPERSCRIPT_SHIFT,
+            ..FontStyle::default()
+        },
+        align: TextAlign::Left,
+    });
+    for line in lines {
+        page.elements.push(PageElement::Text {
+            x: setup.margin_left + NOTE_INDENT,
+            y,
+            width: setup.usable_width() - NOTE_INDENT,
+            text: line.clone(),
+            style: FontStyle { font_size: NOTE_FONT_SIZE, ..FontStyle::default() },
+            align: TextAlign::Left,
+        });
+        y += NOTE_LINE_HEIGHT;
+    }
+    y + if lines.is_empty() { NOTE_LINE_HEIGHT } else { 0.0 }
+}
+
+// ── コメント ──
+
+/// DOCXのコメント（comments.xml の w:comment）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocComment {
+    /// w:id（本文の w:commentRangeStart / w:commentReference から参照）
+    pub id: String,
+    pub author: String,
+    /// 記入日時（w:date、ISO 8601 の文字列のまま）
+    pub date: Option<String>,
+    /// コメント本文（段落は改行で区切る）
+    pub text: String,
+    /// コメントが付けられた本文の範囲（w:commentRangeStart〜w:commentRangeEnd）のテキスト
+    pub anchored_text: String,
+}
+
+/// DOCXのコメントを本文での出現順に関わらず comments.xml の順で抽出
+/// comments.xml が無い場合は空の一覧を返します。
+pub fn extract_comments(input: &[u8]) -> Result<Vec<DocComment>, ConvertError> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(input))
+        .map_err(|e| ConvertError::new("DOCX", &format!("ZIPアーカイブを開けません: {}", e)))?;
+    let Ok(comments_xml) = read_zip_entry_string(&mut archive, "word/comments.xml") else {
+        return Ok(Vec::new());
+    };
+    let mut comments = parse_comments(&comments_xml);
+    let doc_xml = read_zip_entry_string(&mut archive, "word/document.xml")?;
+    let mut anchored = parse_comment_ranges(&doc_xml);
+    for comment in &mut comments {
+        comment.anchored_text = anchored.remove(&comment.id).unwrap_or_default().trim().to_string();
+    }
+    Ok(comments)
+}
+
+/// comments.xml の各コメントの作成者・日時・本文を取り出す（`anchored_text` は空）
+fn parse_comments(xml: &str) -> Vec<DocComment> {
+    let mut comments = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(xml);
     let mut buf = Vec::new();
+    let mut current: Option<DocComment> = None;
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut in_text = false;
+
     loop {
         match reader.read_event_into(&mut buf) {
-            Ok(quick_xml::events::Event::Empty(ref e))
-            | Ok(quick_xml::events::Event::Start(ref e)) => {
-                if e.local_name().as_ref() == b"Relationship" {
-                    let mut id = String::new();
-                    let mut target = String::new();
+            Ok(quick_xml::events::Event::Start(ref e)) => match e.local_name().as_ref() {
+                b"comment" => {
+                    let mut comment = DocComment {
+                        id: String::new(),
+                        author: String::new(),
+                        date: None,
+                        text: String::new(),
+                        anchored_text: String::new(),
+                    };
                     for attr in e.attributes().flatten() {
-                        match attr.key.as_ref() {
-                            b"Id" => id = String::from_utf8_lossy(&attr.value).to_string(),
-                            b"Target" => target = String::from_utf8_lossy(&attr.value).to_string(),
+                        let value = String::from_utf8_lossy(&attr.value).to_string();
+                        match attr.key.local_name().as_ref() {
+                            b"id" => comment.id = value,
+                            b"author" => comment.author = value,
+                            b"date" => comment.date = Some(value),
                             _ => {}
                         }
                     }
-                    if id == r_id {
-                        return Some(target);
+                    current = Some(comment);
+                    paragraphs.clear();
+                }
+                b"p" if current.is_some() => paragraphs.push(String::new()),
+                b"t" => in_text = true,
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Empty(ref e)) if current.is_some() => match e.local_name().as_ref() {
+                b"p" => paragraphs.push(String::new()),
+                b"tab" => paragraphs.last_mut().into_iter().for_each(|p| p.push(' ')),
+                b"br" => paragraphs.last_mut().into_iter().for_each(|p| p.push('\n')),
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::End(ref e)) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"comment" => {
+                    if let Some(mut comment) = current.take() {
+                        let texts: Vec<String> = paragraphs
+                            .drain(..)
+                            .map(|p| p.trim().to_string())
+                            .filter(|p| !p.is_empty())
+                            .collect();
+                        comment.text = texts.join("\n");
+                        comments.push(comment);
                     }
                 }
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Text(ref e)) if in_text && current.is_some() => {
+                if let (Ok(text), Some(p)) = (e.unescape(), paragraphs.last_mut()) {
+                    p.push_str(&text);
+                }
             }
             Ok(quick_xml::events::Event::Eof) => break,
             Err(_) => break,
@@ -798,27 +2369,160 @@ fn resolve_rel(rels_xml: &str, r_id: &str) -> Option<String> {
         }
         buf.clear();
     }
-    None
+
+    comments
+}
+
+/// document.xml の w:commentRangeStart〜w:commentRangeEnd に含まれる本文テキスト（コメントID → テキスト）
+/// 範囲が段落をまたぐ場合は段落の区切りを改行にします。
+fn parse_comment_ranges(xml: &str) -> HashMap<String, String> {
+    let mut ranges: HashMap<String, String> = HashMap::new();
+    let mut open: Vec<String> = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut in_text = false;
+    let id_of = |e: &quick_xml::events::BytesStart| {
+        e.attributes()
+            .flatten()
+            .find(|attr| attr.key.local_name().as_ref() == b"id")
+            .map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+            .unwrap_or_default()
+    };
+    let mut append = |open: &[String], text: &str| {
+        for id in open {
+            ranges.entry(id.clone()).or_default().push_str(text);
+        }
+    };
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) if e.local_name().as_ref() == b"t" => in_text = true,
+            Ok(quick_xml::events::Event::Empty(ref e)) | Ok(quick_xml::events::Event::Start(ref e)) => {
+                match e.local_name().as_ref() {
+                    b"commentRangeStart" => open.push(id_of(e)),
+                    b"commentRangeEnd" => {
+                        let id = id_of(e);
+                        open.retain(|open_id| *open_id != id);
+                    }
+                    b"tab" => append(&open, " "),
+                    b"br" => append(&open, "\n"),
+                    _ => {}
+                }
+            }
+            Ok(quick_xml::events::Event::End(ref e)) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" => append(&open, "\n"),
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Text(ref e)) if in_text && !open.is_empty() => {
+                if let Ok(text) = e.unescape() {
+                    append(&open, &text);
+                }
+            }
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    ranges
+}
+
+/// 余白に描画するコメントのフォントサイズ
+const COMMENT_FONT_SIZE: f64 = 7.0;
+/// 余白に描画するコメントの行の高さ
+const COMMENT_LINE_HEIGHT: f64 = COMMENT_FONT_SIZE * 1.2;
+/// 本文領域・他のコメントとの間隔
+const COMMENT_GAP: f64 = 6.0;
+/// コメントの枠内の余白
+const COMMENT_PADDING: f64 = 3.0;
+/// 右余白が狭い場合もコメントに確保する最小幅
+const COMMENT_MIN_WIDTH: f64 = 48.0;
+
+/// コメントを右余白に枠付きの注釈として描画し、参照位置 `anchor` と細線で結ぶ
+/// 注釈の上端は `min_y`（同じページの前のコメントの下端）より下にずらします。次の注釈に使う下端を返します。
+fn push_comment_note(page: &mut Page, setup: &PageSetup, comment: &DocComment, anchor: (f64, f64), min_y: f64) -> f64 {
+    let x = setup.width - setup.margin_right + COMMENT_GAP;
+    let width = (setup.margin_right - COMMENT_GAP * 2.0).max(COMMENT_MIN_WIDTH);
+    let lines: Vec<String> = comment
+        .text
+        .lines()
+        .flat_map(|p| wrap_text_width(p, width - COMMENT_PADDING * 2.0, COMMENT_FONT_SIZE))
+        .collect();
+    let top = (anchor.1 - COMMENT_LINE_HEIGHT).max(min_y);
+    let height = (lines.len() + 1) as f64 * COMMENT_LINE_HEIGHT + COMMENT_PADDING * 2.0;
+    let border = Color::rgb(214, 180, 60);
+
+    page.elements.push(PageElement::Line {
+        x1: anchor.0,
+        y1: anchor.1,
+        x2: x,
+        y2: top + COMMENT_PADDING + COMMENT_LINE_HEIGHT / 2.0,
+        width: 0.5,
+        color: border,
+        stroke_style: Default::default(),
+    });
+    page.elements.push(PageElement::Rect {
+        x,
+        y: top,
+        width,
+        height,
+        fill: Some(Color::rgb(255, 249, 196)),
+        stroke: Some(border),
+        stroke_width: 0.5,
+        stroke_style: Default::default(),
+        rotation_deg: 0.0,
+    });
+    let style = FontStyle { font_size: COMMENT_FONT_SIZE, ..FontStyle::default() };
+    let mut y = top + COMMENT_PADDING;
+    for (text, bold) in std::iter::once((&comment.author, true)).chain(lines.iter().map(|l| (l, false))) {
+        page.elements.push(PageElement::Text {
+            x: x + COMMENT_PADDING,
+            y,
+            width: width - COMMENT_PADDING * 2.0,
+            text: text.clone(),
+            style: FontStyle { bold, ..style.clone() },
+            align: TextAlign::Left,
+        });
+        y += COMMENT_LINE_HEIGHT;
+    }
+    top + height + COMMENT_GAP
 }
 
 // ── ページレイアウト ──
 
-fn layout_pages(elements: &[BodyElement], setup: &PageSetup) -> Vec<Page> {
+fn layout_pages(
+    elements: &[BodyElement],
+    final_setup: &PageSetup,
+    notes: &DocNotes,
+    comments: &HashMap<String, DocComment>,
+) -> Vec<Page> {
     let mut pages = Vec::new();
-    let usable_width = setup.width - setup.margin_left - setup.margin_right;
-    let usable_height = setup.height - setup.margin_top - setup.margin_bottom;
+    // 脚注は参照されたページの下部に、文末脚注は文書末尾にまとめて配置（それぞれ通し番号）
+    let mut footnotes = PageFootnotes::default();
+    let mut endnotes: Vec<(String, Vec<String>)> = Vec::new();
+    let mut note_counts = (0u32, 0u32);
+    // 右余白のコメントの (ページ番号, 次のコメントを置ける上端)
+    let mut comment_column = (0usize, 0.0f64);
+
+    // セクションごとのページ設定（最後のセクションは本文末尾の w:sectPr）
+    let sections: Vec<&PageSetup> = elements
+        .iter()
+        .filter_map(|element| match element {
+            BodyElement::SectionBreak(section) => Some(section),
+            _ => None,
+        })
+        .chain(std::iter::once(final_setup))
+        .collect();
+    let mut section_index = 0;
+    let mut setup = sections[0];
+    let mut usable_width = setup.usable_width();
+    let mut usable_height = setup.usable_height();
 
     let mut cur_y = 0.0f64; // Current Y position relative to content area
 
-    let new_page = || -> Page {
-        Page {
-            width: setup.width,
-            height: setup.height,
-            elements: Vec::new(),
-        }
-    };
-
-    let mut page = new_page();
+    let mut page = setup.blank_page();
 
     for element in elements {
         match element {
@@ -851,19 +2555,33 @@ fn layout_pages(elements: &[BodyElement], setup: &PageSetup) -> Vec<Page> {
                 let line_height = effective_font_size * para.line_spacing;
 
                 // Check if we need a new page
-                if cur_y + line_height > usable_height && !page.elements.is_empty() {
-                    pages.push(page);
-                    page = new_page();
+                if cur_y + line_height > usable_height - footnotes.height && !page.elements.is_empty() {
+                    pages.push(footnotes.finish_page(page, setup));
+                    page = setup.blank_page();
                     cur_y = 0.0;
                 }
 
                 // Render paragraph runs
                 let abs_x = setup.margin_left + para.indent_left;
 
+                // 空の段落（間隔調整の空行）は行の高さだけ進め、テキスト抽出で空行になるよう空のテキストを置く
+                if para.numbering.is_none()
+                    && para.runs.iter().all(|r| matches!(&r.content, RunContent::Text(text) if text.is_empty()))
+                {
+                    page.elements.push(PageElement::Text {
+                        x: abs_x,
+                        y: setup.margin_top + cur_y,
+                        width: 0.0,
+                        text: String::new(),
+                        style: FontStyle { font_size: effective_font_size, ..FontStyle::default() },
+                        align: para.align,
+                    });
+                }
+
                 // Concatenate all text runs for this line, respecting formatting
                 let mut line_x = abs_x + para.indent_first.max(0.0);
 
-                for run in &para.runs {
+                for (ri, run) in para.runs.iter().enumerate() {
                     match &run.content {
                         RunContent::Text(text) => {
                             if text.is_empty() {
@@ -873,14 +2591,28 @@ fn layout_pages(elements: &[BodyElement], setup: &PageSetup) -> Vec<Page> {
                             let font_size = if para.is_heading { base_font_size } else { run.font_size };
                             let bold = run.bold || is_bold;
 
+                            let style = FontStyle {
+                                font_size,
+                                bold,
+                                italic: run.italic,
+                                color: run.color,
+                                strikethrough: run.strikethrough,
+                                baseline_shift: run.baseline_shift,
+                                underline: run.underline,
+                                font_name: run.font_name.clone().unwrap_or_else(|| FontStyle::default().font_name),
+                                letter_spacing: 0.0,
+                            };
+                            // 上付き・下付きは縮小後のサイズで幅を見積もる
+                            let (advance_size, _) = style.script_metrics(0.0);
+
                             // Word wrap within available width
                             let available = setup.margin_left + usable_width - line_x;
-                            let lines = wrap_text_width(text, available, font_size);
+                            let lines = wrap_text_width(text, available, advance_size);
 
                             for (li, line_text) in lines.iter().enumerate() {
-                                if cur_y + font_size > usable_height {
-                                    pages.push(page);
-                                    page = new_page();
+                                if cur_y + font_size > usable_height - footnotes.height {
+                                    pages.push(footnotes.finish_page(page, setup));
+                                    page = setup.blank_page();
                                     cur_y = 0.0;
                                     line_x = abs_x;
                                 }
@@ -889,7 +2621,7 @@ fn layout_pages(elements: &[BodyElement], setup: &PageSetup) -> Vec<Page> {
 
                                 // Highlight background
                                 if let Some(hl_color) = run.highlight {
-                                    let text_width = estimate_text_width(line_text, font_size);
+                                    let text_width = estimate_text_width(line_text, advance_size);
                                     page.elements.push(PageElement::Rect {
                                         x: line_x,
                                         y: text_y,
@@ -899,6 +2631,7 @@ fn layout_pages(elements: &[BodyElement], setup: &PageSetup) -> Vec<Page> {
                                         stroke: None,
                                         stroke_width: 0.0,
                                         rotation_deg: 0.0,
+                                        stroke_style: Default::default(),
                                     });
                                 }
 
@@ -907,13 +2640,7 @@ fn layout_pages(elements: &[BodyElement], setup: &PageSetup) -> Vec<Page> {
                                     y: text_y,
                                     width: available,
                                     text: line_text.clone(),
-                                    style: FontStyle {
-                                        font_size,
-                                        bold,
-                                        italic: run.italic,
-                                        color: run.color,
-                                        ..FontStyle::default()
-                                    },
+                                    style: style.clone(),
                                     align: para.align,
                                 });
 
@@ -922,11 +2649,36 @@ fn layout_pages(elements: &[BodyElement], setup: &PageSetup) -> Vec<Page> {
                                     line_x = abs_x;
                                 } else {
                                     // Update x position for next inline run
-                                    line_x += estimate_text_width(line_text, font_size);
+                                    line_x += estimate_text_width(line_text, advance_size);
                                 }
                             }
                         }
-                        RunContent::ImageData { data, mime_type, width, height } => {
+                        RunContent::ImageData { data, mime_type, width, height, anchor: Some(anchor) } => {
+                            // フローティング画像はテキストの流れに影響させず絶対位置に配置
+                            let (x, y) = anchor.position(setup, cur_y);
+                            page.elements.push(PageElement::Image {
+                                x,
+                                y,
+                                width: *width,
+                                height: *height,
+                                data: data.clone(),
+                                mime_type: mime_type.clone(),
+                            });
+                        }
+                        RunContent::Shape { shape, width, height, anchor: Some(anchor) } => {
+                            // フローティングのテキストボックス・図形も同様に絶対位置に配置
+                            let (x, y) = anchor.position(setup, cur_y);
+                            page.elements.extend(shape.elements(x, y, *width, *height));
+                        }
+                        RunContent::Image { r_id, width, height, anchor: Some(anchor) } => {
+                            // 読み込めなかった画像は後から供給できるようにIDを記したプレースホルダーで表示
+                            let (x, y) = anchor.position(setup, cur_y);
+                            page.elements.extend(deferred_assets::placeholder_elements(x, y, *width, *height, 0.0, r_id));
+                        }
+                        RunContent::ImageData { width, height, anchor: None, .. }
+                        | RunContent::Image { width, height, anchor: None, .. }
+                        | RunContent::Ole { width, height, .. }
+                        | RunContent::Shape { width, height, anchor: None, .. } => {
                             // Constrain image to page width
                             let max_w = usable_width;
                             let (img_w, img_h) = if *width > max_w {
@@ -936,20 +2688,44 @@ fn layout_pages(elements: &[BodyElement], setup: &PageSetup) -> Vec<Page> {
                                 (*width, *height)
                             };
 
-                            if cur_y + img_h > usable_height {
-                                pages.push(page);
-                                page = new_page();
+                            // 行の残り幅に収まらない場合は次の行へ
+                            if line_x > abs_x && line_x + img_w > setup.margin_left + usable_width {
+                                cur_y += line_height;
+                                line_x = abs_x;
+                            }
+
+                            if cur_y + img_h > usable_height - footnotes.height {
+                                pages.push(footnotes.finish_page(page, setup));
+                                page = setup.blank_page();
                                 cur_y = 0.0;
                             }
 
-                            page.elements.push(PageElement::Image {
-                                x: setup.margin_left,
-                                y: setup.margin_top + cur_y,
-                                width: img_w,
-                                height: img_h,
-                                data: data.clone(),
-                                mime_type: mime_type.clone(),
-                            });
+                            let img_x = line_x.min(setup.margin_left + usable_width - img_w);
+                            let img_y = setup.margin_top + cur_y;
+                            match &run.content {
+                                RunContent::ImageData { data, mime_type, .. } => {
+                                    page.elements.push(PageElement::Image {
+                                        x: img_x,
+                                        y: img_y,
+                                        width: img_w,
+                                        height: img_h,
+                                        data: data.clone(),
+                                        mime_type: mime_type.clone(),
+                                    });
+                                }
+                                RunContent::Ole { prog_id, .. } => {
+                                    page.elements.extend(ole::placeholder_elements(img_x, img_y, img_w, img_h, prog_id));
+                                }
+                                RunContent::Image { r_id, .. } => {
+                                    page.elements.extend(deferred_assets::placeholder_elements(
+                                        img_x, img_y, img_w, img_h, 0.0, r_id,
+                                    ));
+                                }
+                                RunContent::Shape { shape, .. } => {
+                                    page.elements.extend(shape.elements(img_x, img_y, img_w, img_h));
+                                }
+                                _ => {}
+                            }
                             cur_y += img_h + 4.0;
                             line_x = abs_x;
                         }
@@ -957,11 +2733,65 @@ fn layout_pages(elements: &[BodyElement], setup: &PageSetup) -> Vec<Page> {
                             cur_y += line_height;
                             line_x = abs_x;
                         }
+                        RunContent::PageBreak => {
+                            let finished = std::mem::replace(&mut page, setup.blank_page());
+                            pages.push(footnotes.finish_page(finished, setup));
+                            cur_y = 0.0;
+                            line_x = abs_x;
+                        }
                         RunContent::Tab => {
-                            line_x += 36.0; // ~0.5 inch tab
+                            // 次のタブ位置へ進める（右・中央・小数点揃えは続くテキストの幅だけ手前から始める）
+                            let stop = TabStop::next(
+                                line_x - setup.margin_left, &para.tab_stops, TabStop::DEFAULT_INTERVAL,
+                            );
+                            let (width, before_decimal) =
+                                tab_segment_width(&para.runs[ri + 1..], para.is_heading.then_some(base_font_size));
+                            line_x = (setup.margin_left + stop.segment_start(width, before_decimal)).max(line_x);
+                            if line_x >= setup.margin_left + usable_width {
+                                cur_y += line_height;
+                                line_x = abs_x;
+                            }
+                        }
+                        RunContent::NoteRef { kind, id } => {
+                            // 本文には上付きの通し番号を置き、脚注本文はページ下部（文末脚注は文書末尾）へ
+                            let count = match kind {
+                                NoteKind::Footnote => &mut note_counts.0,
+                                NoteKind::Endnote => &mut note_counts.1,
+                            };
+                            *count += 1;
+                            let marker = count.to_string();
+                            let style = FontStyle {
+                                font_size: run.font_size,
+                                color: run.color,
+                                baseline_shift: SUPERSCRIPT_SHIFT,
+                                ..FontStyle::default()
+                            };
+                            let (advance_size, _) = style.script_metrics(0.0);
+                            let marker_width = estimate_text_width(&marker, advance_size);
+                            page.elements.push(PageElement::Text {
+                                x: line_x,
+                                y: setup.margin_top + cur_y,
+                                width: marker_width,
+                                text: marker.clone(),
+                                style,
+                                align: TextAlign::Left,
+                            });
+                            line_x += marker_width;
+                            let paragraphs = notes.paragraphs(*kind, id);
+                            match kind {
+                                NoteKind::Footnote => footnotes.add(marker, paragraphs, usable_width),
+                                NoteKind::Endnote => endnotes.push((marker, paragraphs.to_vec())),
+                            }
                         }
-                        RunContent::Image { .. } => {
-                            // Unresolved - skip
+                        RunContent::CommentRef { id } => {
+                            // コメントは参照位置の行の高さから右余白へ順に並べる
+                            if let Some(comment) = comments.get(id) {
+                                if comment_column.0 != pages.len() {
+                                    comment_column = (pages.len(), 0.0);
+                                }
+                                let anchor = (line_x, setup.margin_top + cur_y + run.font_size);
+                                comment_column.1 = push_comment_note(&mut page, setup, comment, anchor, comment_column.1);
+                            }
                         }
                     }
                 }
@@ -974,105 +2804,88 @@ fn layout_pages(elements: &[BodyElement], setup: &PageSetup) -> Vec<Page> {
             }
 
             BodyElement::Table(table) => {
-                // Calculate table dimensions
-                let total_width: f64 = if table.column_widths.is_empty() {
+                // セルの網掛け・枠線は共有のテーブル（TableBlock）として描画し、ページに収まる行ごとに分割
+                let table = table.to_table();
+                let total_width = if table.column_widths.is_empty() {
                     usable_width
                 } else {
                     table.column_widths.iter().sum()
                 };
-                let col_count = table.rows.first().map_or(1, |r| r.cells.len()).max(1);
-                let default_col_width = total_width / col_count as f64;
-
-                let row_height = 20.0;
-
-                for tbl_row in &table.rows {
-                    let rh = tbl_row.height.unwrap_or(row_height);
-
-                    // Check page break
-                    if cur_y + rh > usable_height {
-                        pages.push(page);
-                        page = new_page();
-                        cur_y = 0.0;
-                    }
-
-                    let abs_y = setup.margin_top + cur_y;
-                    let mut cell_x = setup.margin_left;
-
-                    for (ci, cell) in tbl_row.cells.iter().enumerate() {
-                        let cw = if ci < table.column_widths.len() {
-                            table.column_widths[ci]
-                        } else if cell.width > 0.0 {
-                            cell.width
-                        } else {
-                            default_col_width
-                        };
-
-                        // Cell background
-                        if let Some(shading) = cell.shading {
-                            page.elements.push(PageElement::Rect {
-                                x: cell_x,
-                                y: abs_y,
-                                width: cw,
-                                height: rh,
-                                fill: Some(shading),
-                                stroke: None,
-                                stroke_width: 0.0,
-                                rotation_deg: 0.0,
+                let heights = table.row_heights(total_width, None);
+
+                let mut chunk: Vec<Vec<TableCell>> = Vec::new();
+                let mut chunk_y = cur_y;
+                for (row, rh) in table.rows.into_iter().zip(heights) {
+                    if cur_y + rh > usable_height - footnotes.height && cur_y > 0.0 {
+                        if !chunk.is_empty() {
+                            let rows = std::mem::take(&mut chunk);
+                            page.elements.push(PageElement::TableBlock {
+                                x: setup.margin_left,
+                                y: setup.margin_top + chunk_y,
+                                width: total_width,
+                                table: Table { rows, column_widths: table.column_widths.clone() },
                             });
                         }
-
-                        // Cell border
-                        page.elements.push(PageElement::Rect {
-                            x: cell_x,
-                            y: abs_y,
-                            width: cw,
-                            height: rh,
-                            fill: None,
-                            stroke: Some(Color::rgb(0, 0, 0)),
-                            stroke_width: 0.5,
-                            rotation_deg: 0.0,
-                        });
-
-                        // Cell text
-                        let mut text_y = abs_y + 3.0;
-                        for cp in &cell.paragraphs {
-                            for run in &cp.runs {
-                                if let RunContent::Text(text) = &run.content {
-                                    if !text.is_empty() {
-                                        page.elements.push(PageElement::Text {
-                                            x: cell_x + 3.0,
-                                            y: text_y,
-                                            width: cw - 6.0,
-                                            text: text.clone(),
-                                            style: FontStyle {
-                                                font_size: run.font_size.min(rh - 4.0),
-                                                bold: run.bold,
-                                                italic: run.italic,
-                                                color: run.color,
-                                                ..FontStyle::default()
-                                            },
-                                            align: cp.align,
-                                        });
-                                        text_y += run.font_size * 1.2;
-                                    }
-                                }
-                            }
-                        }
-
-                        cell_x += cw;
+                        pages.push(footnotes.finish_page(page, setup));
+                        page = setup.blank_page();
+                        cur_y = 0.0;
+                        chunk_y = 0.0;
                     }
-
+                    chunk.push(row);
                     cur_y += rh;
                 }
+                if !chunk.is_empty() {
+                    page.elements.push(PageElement::TableBlock {
+                        x: setup.margin_left,
+                        y: setup.margin_top + chunk_y,
+                        width: total_width,
+                        table: Table { rows: chunk, column_widths: table.column_widths },
+                    });
+                }
 
                 cur_y += 8.0; // Table spacing
             }
+
+            BodyElement::SectionBreak(_) => {
+                section_index += 1;
+                let next = sections[section_index];
+                let same_size = next.width == setup.width && next.height == setup.height;
+                let finished_setup = setup;
+                setup = next;
+                usable_width = setup.usable_width();
+                usable_height = setup.usable_height();
+                // 連続セクションで用紙サイズが変わらない場合は同じページに続ける
+                if !(setup.continuous && same_size) {
+                    if !page.elements.is_empty() {
+                        pages.push(footnotes.finish_page(page, finished_setup));
+                    }
+                    page = setup.blank_page();
+                    cur_y = 0.0;
+                }
+            }
+        }
+    }
+
+    // 文末脚注は本文の後に区切り線を引いて続ける
+    if !endnotes.is_empty() {
+        cur_y += NOTE_SEPARATOR_GAP / 2.0;
+        page.elements.push(note_separator(setup, setup.margin_top + cur_y));
+        cur_y += NOTE_SEPARATOR_GAP / 2.0;
+        for (marker, paragraphs) in &endnotes {
+            let lines = wrap_note_paragraphs(paragraphs, usable_width);
+            let height = lines.len().max(1) as f64 * NOTE_LINE_HEIGHT;
+            if cur_y + height > usable_height - footnotes.height && !page.elements.is_empty() {
+                pages.push(footnotes.finish_page(page, setup));
+                page = setup.blank_page();
+                cur_y = 0.0;
+            }
+            cur_y = push_note(&mut page, setup, setup.margin_top + cur_y, marker, &lines) - setup.margin_top;
         }
     }
 
     // Push final page
     if !page.elements.is_empty() {
-        pages.push(page);
+        pages.push(footnotes.finish_page(page, setup));
     }
 
     pages
@@ -1091,6 +2904,33 @@ fn estimate_text_width(text: &str, font_size: f64) -> f64 {
         .sum()
 }
 
+/// タブに続くテキスト片（次のタブ・改行まで）の幅と、小数点より前の部分の幅を見積もる
+/// 見出しでは `heading_size` の文字サイズで計測します。
+fn tab_segment_width(runs: &[DocRun], heading_size: Option<f64>) -> (f64, f64) {
+    let mut width = 0.0;
+    let mut before_decimal = None;
+    for run in runs {
+        let text = match &run.content {
+            RunContent::Text(text) => text,
+            RunContent::Tab | RunContent::LineBreak | RunContent::PageBreak => break,
+            _ => continue,
+        };
+        let style = FontStyle {
+            font_size: heading_size.unwrap_or(run.font_size),
+            baseline_shift: run.baseline_shift,
+            ..FontStyle::default()
+        };
+        let (advance_size, _) = style.script_metrics(0.0);
+        if before_decimal.is_none() {
+            if let Some(pos) = text.find('.') {
+                before_decimal = Some(width + estimate_text_width(&text[..pos], advance_size));
+            }
+        }
+        width += estimate_text_width(text, advance_size);
+    }
+    (width, before_decimal.unwrap_or(width))
+}
+
 /// テキストを利用可能幅で折り返す
 fn wrap_text_width(text: &str, available_width: f64, font_size: f64) -> Vec<String> {
     if text.is_empty() || available_width <= 0.0 {
@@ -1140,6 +2980,42 @@ fn parse_hex_color(hex: &str) -> Option<Color> {
     }
 }
 
+/// w:tcBorders の辺要素を枠線に変換（w:sz は1/8ポイント単位、"nil"/"none" は線なし）
+fn parse_border_edge(e: &quick_xml::events::BytesStart) -> Option<BorderSpec> {
+    let mut spec = BorderSpec { width: 0.5, color: Color::BLACK, compound: CompoundLine::Single };
+    for attr in e.attributes().flatten() {
+        let val = String::from_utf8_lossy(&attr.value);
+        match attr.key.local_name().as_ref() {
+            b"val" if val == "nil" || val == "none" => return None,
+            b"val" if val == "double" => spec.compound = CompoundLine::Double,
+            b"val" if val == "triple" => spec.compound = CompoundLine::Triple,
+            b"sz" => {
+                if let Ok(eighths) = val.parse::<f64>() {
+                    spec.width = eighths / 8.0;
+                }
+            }
+            b"color" => {
+                if let Some(color) = parse_hex_color(&val) {
+                    spec.color = color;
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(spec)
+}
+
+/// w:u の val 属性を下線スタイルに変換（"none" は下線なし）
+fn parse_underline_val(val: &str) -> Option<UnderlineStyle> {
+    let kind = match val {
+        "none" => return None,
+        "double" | "wavyDouble" => UnderlineKind::Double,
+        v if v.starts_with("dot") => UnderlineKind::Dotted,
+        _ => UnderlineKind::Single,
+    };
+    Some(UnderlineStyle::new(kind))
+}
+
 fn highlight_name_to_color(name: &str) -> Option<Color> {
     Some(match name {
         "yellow" => Color::rgb(255, 255, 0),
@@ -1157,52 +3033,11 @@ fn highlight_name_to_color(name: &str) -> Option<Color> {
         "darkGray" => Color::rgb(169, 169, 169),
         "lightGray" => Color::rgb(211, 211, 211),
         "black" => Color::rgb(0, 0, 0),
+        "white" => Color::WHITE,
         _ => return None,
     })
 }
 
-/// DOCXメタデータを読み取る
-fn read_docx_metadata(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>) -> Metadata {
-    let mut metadata = Metadata::default();
-
-    if let Ok(core_xml) = read_zip_entry_string(archive, "docProps/core.xml") {
-        let mut reader = quick_xml::Reader::from_str(&core_xml);
-        let mut buf = Vec::new();
-        let mut current_tag = String::new();
-        let mut in_tag = false;
-
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(quick_xml::events::Event::Start(ref e)) => {
-                    current_tag =
-                        String::from_utf8_lossy(e.local_name().as_ref()).to_string();
-                    in_tag = true;
-                }
-                Ok(quick_xml::events::Event::Text(ref e)) => {
-                    if in_tag {
-                        if let Ok(text) = e.unescape() {
-                            match current_tag.as_str() {
-                                "title" => metadata.title = Some(text.to_string()),
-                                "creator" => metadata.author = Some(text.to_string()),
-                                "subject" => metadata.subject = Some(text.to_string()),
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-                Ok(quick_xml::events::Event::End(_)) => in_tag = false,
-                Ok(quick_xml::events::Event::Eof) => break,
-                Err(_) => break,
-                _ => {}
-            }
-            buf.clear();
-        }
-    }
-
-    metadata.creator = Some("WASM Document Converter".to_string());
-    metadata
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1223,6 +3058,27 @@ mod tests {
         assert!((setup.margin_top - 72.0).abs() < 0.1); // 1 inch
     }
 
+    #[test]
+    fn test_style_inheritance_overrides_and_cycles() {
+        let xml = r#"<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+          <w:style w:type="paragraph" w:styleId="Base"><w:rPr><w:sz w:val="24"/><w:b/></w:rPr></w:style>
+          <w:style w:type="paragraph" w:styleId="Child">
+            <w:basedOn w:val="Base"/>
+            <w:pPr><w:spacing w:after="240"/></w:pPr>
+            <w:rPr><w:b w:val="0"/></w:rPr>
+          </w:style>
+          <w:style w:type="paragraph" w:styleId="LoopA"><w:basedOn w:val="LoopB"/></w:style>
+          <w:style w:type="paragraph" w:styleId="LoopB"><w:basedOn w:val="LoopA"/></w:style>
+        </w:styles>"#;
+        let styles = parse_styles(xml);
+        let child = styles.resolve("Child");
+        assert_eq!(child.font_size, Some(12.0));
+        assert_eq!(child.bold, Some(false));
+        assert_eq!(child.spacing_after, Some(12.0));
+        // 循環した継承でも停止する
+        assert_eq!(styles.resolve("LoopA").font_size, None);
+    }
+
     #[test]
     fn test_parse_formatted_paragraph() {
         let xml = r#"<?xml version="1.0"?>
@@ -1244,7 +3100,7 @@ mod tests {
             </w:p>
           </w:body>
         </w:document>"#;
-        let elements = parse_document_body(xml);
+        let elements = parse_document_body(xml, &StyleTable::default(), TrackedChanges::default());
         assert_eq!(elements.len(), 2);
 
         if let BodyElement::Paragraph(para) = &elements[0] {
@@ -1259,6 +3115,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_anchored_drawing() {
+        let xml = r#"<?xml version="1.0"?>
+        <w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"
+                    xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing"
+                    xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+                    xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+          <w:body>
+            <w:p>
+              <w:r>
+                <w:drawing>
+                  <wp:anchor>
+                    <wp:positionH relativeFrom="page"><wp:posOffset>1270000</wp:posOffset></wp:positionH>
+                    <wp:positionV relativeFrom="margin"><wp:posOffset>635000</wp:posOffset></wp:positionV>
+                    <wp:extent cx="1270000" cy="635000"/>
+                    <a:graphic><a:graphicData><a:blip r:embed="rId5"/><a:ext cx="1" cy="1"/></a:graphicData></a:graphic>
+                  </wp:anchor>
+                </w:drawing>
+              </w:r>
+            </w:p>
+          </w:body>
+        </w:document>"#;
+        let elements = parse_document_body(xml, &StyleTable::default(), TrackedChanges::default());
+        let BodyElement::Paragraph(para) = &elements[0] else {
+            panic!("Expected paragraph");
+        };
+        match &para.runs[0].content {
+            RunContent::Image { r_id, width, height, anchor: Some(anchor) } => {
+                assert_eq!(r_id, "rId5");
+                assert!((width - 100.0).abs() < 0.01);
+                assert!((height - 50.0).abs() < 0.01);
+                assert!((anchor.offset_x - 100.0).abs() < 0.01);
+                assert!((anchor.offset_y - 50.0).abs() < 0.01);
+                assert_eq!(anchor.relative_h, AnchorRelative::Page);
+                assert_eq!(anchor.relative_v, AnchorRelative::Margin);
+            }
+            other => panic!("Expected anchored image, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_custom_tab_stops() {
+        let xml = r#"<?xml version="1.0"?>
+        <w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+          <w:body>
+            <w:p>
+              <w:pPr>
+                <w:tabs>
+                  <w:tab w:val="right" w:pos="2880"/>
+                  <w:tab w:val="decimal" w:pos="5760"/>
+                  <w:tab w:val="clear" w:pos="720"/>
+                </w:tabs>
+              </w:pPr>
+              <w:r><w:t>a</w:t><w:tab/><w:t>bb</w:t><w:tab/><w:t>12.5</w:t><w:tab/><w:t>c</w:t></w:r>
+            </w:p>
+          </w:body>
+        </w:document>"#;
+        let elements = parse_document_body(xml, &StyleTable::default(), TrackedChanges::default());
+        let BodyElement::Paragraph(para) = &elements[0] else {
+            panic!("Expected paragraph");
+        };
+        assert_eq!(para.tab_stops.len(), 2);
+        assert_eq!(para.tab_stops[0], TabStop { position: 144.0, align: TabAlign::Right });
+
+        let setup = PageSetup::default();
+        let pages = layout_pages(&elements, &setup, &DocNotes::default(), &HashMap::new());
+        let xs: Vec<f64> = pages[0]
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                PageElement::Text { x, .. } => Some(*x - setup.margin_left),
+                _ => None,
+            })
+            .collect();
+        let size = para.runs[0].font_size;
+        assert_eq!(xs.len(), 4);
+        assert!(xs[0].abs() < 0.01);
+        // 右揃え: 右端がタブ位置に揃う
+        assert!((xs[1] + estimate_text_width("bb", size) - 144.0).abs() < 0.01);
+        // 小数点揃え: 小数点がタブ位置に揃う
+        assert!((xs[2] + estimate_text_width("12", size) - 288.0).abs() < 0.01);
+        // 定義済みのタブ位置より右は既定の間隔
+        assert!((xs[3] - 324.0).abs() < 0.01);
+    }
+
     #[test]
     fn test_wrap_text() {
         let lines = wrap_text_width("Hello World Test", 50.0, 12.0);
@@ -0,0 +1,289 @@
+// formats/embedded_fonts.rs - 埋め込みフォント抽出モジュール
+//
+// PPTX の p:embeddedFontLst（ppt/fonts/*.fntdata）と、DOCX の fontTable.xml が
+// 参照する難読化フォント（word/fonts/*.odttf）を取り出し、描画に使える
+// TrueType / OpenType のバイト列に復元します。
+
+use crate::converter::EmbeddedFont;
+
+type ZipArchive<'a> = zip::ZipArchive<std::io::Cursor<&'a [u8]>>;
+
+/// EOTヘッダーのマジックナンバー（オフセット34）
+const EOT_MAGIC: u16 = 0x504C;
+/// EOT: フォントデータがMTX圧縮されている
+const EOT_FLAG_COMPRESSED: u32 = 0x0000_0004;
+/// EOT: フォントデータが0x50でXOR暗号化されている
+const EOT_FLAG_XOR: u32 = 0x1000_0000;
+
+/// ドキュメントに埋め込まれたフォントを抽出
+/// 対応していないフォーマットや、解析できないフォントは無視します。
+pub fn extract_embedded_fonts(ext: &str, data: &[u8]) -> Vec<EmbeddedFont> {
+    let ext = ext.to_lowercase();
+    if ext != "pptx" && ext != "docx" {
+        return Vec::new();
+    }
+    let Ok(mut archive) = zip::ZipArchive::new(std::io::Cursor::new(data)) else {
+        return Vec::new();
+    };
+    let fonts = match ext.as_str() {
+        "pptx" => extract_pptx_fonts(&mut archive),
+        _ => extract_docx_fonts(&mut archive),
+    };
+    fonts
+        .into_iter()
+        .filter(|font| ab_glyph::FontRef::try_from_slice(&font.data).is_ok())
+        .collect()
+}
+
+/// PPTX: presentation.xml の p:embeddedFont を読み取り ppt/fonts/ のデータを復元
+fn extract_pptx_fonts(archive: &mut ZipArchive) -> Vec<EmbeddedFont> {
+    let Some(xml) = read_entry_string(archive, "ppt/presentation.xml") else {
+        return Vec::new();
+    };
+    let rels = read_entry_string(archive, "ppt/_rels/presentation.xml.rels").unwrap_or_default();
+
+    let mut fonts = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(&xml);
+    let mut buf = Vec::new();
+    let mut typeface = String::new();
+    let mut variants: Vec<(&'static str, String)> = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) | Ok(quick_xml::events::Event::Empty(ref e)) => {
+                let local = e.local_name();
+                match local.as_ref() {
+                    b"embeddedFont" => {
+                        typeface.clear();
+                        variants.clear();
+                    }
+                    b"font" => typeface = attr_value(e, b"typeface").unwrap_or_default(),
+                    variant => {
+                        let suffix = variant_suffix(variant);
+                        variants.extend(suffix.zip(attr_value(e, b"id")));
+                    }
+                }
+            }
+            Ok(quick_xml::events::Event::End(ref e)) if e.local_name().as_ref() == b"embeddedFont" => {
+                for (suffix, r_id) in &variants {
+                    let Some(target) = resolve_target(&rels, r_id, "ppt") else { continue };
+                    let Some(raw) = read_entry_bytes(archive, &target) else { continue };
+                    if let Some(data) = decode_font_data(raw) {
+                        fonts.push(EmbeddedFont { name: variant_name(&typeface, suffix), data });
+                    }
+                }
+            }
+            Ok(quick_xml::events::Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    fonts
+}
+
+/// DOCX: fontTable.xml の w:embedRegular 等を読み取り、難読化を解除
+fn extract_docx_fonts(archive: &mut ZipArchive) -> Vec<EmbeddedFont> {
+    let Some(xml) = read_entry_string(archive, "word/fontTable.xml") else {
+        return Vec::new();
+    };
+    let rels = read_entry_string(archive, "word/_rels/fontTable.xml.rels").unwrap_or_default();
+
+    let mut fonts = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(&xml);
+    let mut buf = Vec::new();
+    let mut font_name = String::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) | Ok(quick_xml::events::Event::Empty(ref e)) => {
+                let local = e.local_name();
+                match local.as_ref() {
+                    b"font" => font_name = attr_value(e, b"name").unwrap_or_default(),
+                    b"embedRegular" | b"embedBold" | b"embedItalic" | b"embedBoldItalic" => {
+                        let suffix = variant_suffix(&local.as_ref()[5..]).unwrap_or("");
+                        if let Some(data) = read_obfuscated_font(archive, &rels, e) {
+                            fonts.push(EmbeddedFont { name: variant_name(&font_name, suffix), data });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(quick_xml::events::Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    fonts
+}
+
+/// w:embedXxx 要素が参照する難読化フォントを読み込んで復元
+fn read_obfuscated_font(
+    archive: &mut ZipArchive,
+    rels: &str,
+    e: &quick_xml::events::BytesStart,
+) -> Option<Vec<u8>> {
+    let r_id = attr_value(e, b"id")?;
+    let key = attr_value(e, b"fontKey")?;
+    let target = resolve_target(rels, &r_id, "word")?;
+    let mut data = read_entry_bytes(archive, &target)?;
+    deobfuscate_font(&mut data, &key).then_some(data)
+}
+
+/// 埋め込みフォントのスタイル要素名から、登録名に付ける接尾辞を取得
+fn variant_suffix(local: &[u8]) -> Option<&'static str> {
+    match local {
+        b"regular" | b"Regular" => Some(""),
+        b"bold" | b"Bold" => Some(" Bold"),
+        b"italic" | b"Italic" => Some(" Italic"),
+        b"boldItalic" | b"BoldItalic" => Some(" Bold Italic"),
+        _ => None,
+    }
+}
+
+fn variant_name(family: &str, suffix: &str) -> String {
+    format!("{}{}", family, suffix)
+}
+
+/// ECMA-376 の難読化フォントを復元
+/// フォントキー（GUID）の16バイトを逆順にしたものと、先頭32バイトをXORします。
+fn deobfuscate_font(data: &mut [u8], font_key: &str) -> bool {
+    let hex: String = font_key.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+    if hex.len() != 32 || data.len() < 32 {
+        return false;
+    }
+    let mut key = [0u8; 16];
+    for (i, byte) in key.iter_mut().enumerate() {
+        let Ok(value) = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16) else {
+            return false;
+        };
+        *byte = value;
+    }
+    key.reverse();
+    for (i, byte) in data.iter_mut().take(32).enumerate() {
+        *byte ^= key[i % 16];
+    }
+    true
+}
+
+/// 埋め込みフォントデータを TrueType / OpenType に復元
+/// 素のフォントファイルはそのまま、EOT（Embedded OpenType）はヘッダーを除去します。
+/// MTX圧縮されたEOTは未対応です。
+fn decode_font_data(data: Vec<u8>) -> Option<Vec<u8>> {
+    if is_sfnt(&data) {
+        return Some(data);
+    }
+    let u32_at = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let magic = data.get(34..36).map(|b| u16::from_le_bytes([b[0], b[1]]))?;
+    if magic != EOT_MAGIC {
+        return None;
+    }
+    let eot_size = u32_at(0)? as usize;
+    let font_data_size = u32_at(4)? as usize;
+    let flags = u32_at(12)?;
+    if flags & EOT_FLAG_COMPRESSED != 0 || eot_size > data.len() || font_data_size > eot_size {
+        return None;
+    }
+    let mut font = data[eot_size - font_data_size..eot_size].to_vec();
+    if flags & EOT_FLAG_XOR != 0 {
+        font.iter_mut().for_each(|b| *b ^= 0x50);
+    }
+    is_sfnt(&font).then_some(font)
+}
+
+/// TrueType / OpenType / TrueTypeコレクションのシグネチャか
+fn is_sfnt(data: &[u8]) -> bool {
+    matches!(
+        data.get(0..4),
+        Some([0x00, 0x01, 0x00, 0x00]) | Some(b"OTTO") | Some(b"true") | Some(b"ttcf")
+    )
+}
+
+pub(crate) fn attr_value(e: &quick_xml::events::BytesStart, local: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == local)
+        .map(|a| String::from_utf8_lossy(&a.value).to_string())
+}
+
+/// リレーションシップXMLからrIdのTargetを解決し、アーカイブ内のパスに変換
+pub(crate) fn resolve_target(rels_xml: &str, r_id: &str, base_dir: &str) -> Option<String> {
+    let mut reader = quick_xml::Reader::from_str(rels_xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Empty(ref e)) | Ok(quick_xml::events::Event::Start(ref e))
+                if e.local_name().as_ref() == b"Relationship"
+                    && attr_value(e, b"Id").as_deref() == Some(r_id) =>
+            {
+                let target = attr_value(e, b"Target")?;
+                return Some(match target.strip_prefix('/') {
+                    Some(absolute) => absolute.to_string(),
+                    None => normalize_path(&format!("{}/{}", base_dir, target)),
+                });
+            }
+            Ok(quick_xml::events::Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    None
+}
+
+fn normalize_path(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            ".." => {
+                parts.pop();
+            }
+            "." | "" => {}
+            _ => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+fn read_entry_string(archive: &mut ZipArchive, path: &str) -> Option<String> {
+    let mut content = String::new();
+    super::limits::read_limited_string(archive.by_name(path).ok()?, &mut content).ok()?;
+    Some(content)
+}
+
+fn read_entry_bytes(archive: &mut ZipArchive, path: &str) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    super::limits::read_limited(archive.by_name(path).ok()?, &mut data).ok()?;
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deobfuscate_font_round_trip() {
+        let original: Vec<u8> = (0u8..64).collect();
+        let key = "{01234567-89AB-CDEF-0123-456789ABCDEF}";
+        let mut data = original.clone();
+        assert!(deobfuscate_font(&mut data, key));
+        // 先頭32バイトのみ変化し、キーは逆順で適用される
+        assert_eq!(data[0], original[0] ^ 0xEF);
+        assert_eq!(data[16], original[16] ^ 0xEF);
+        assert_eq!(data[32..], original[32..]);
+        assert!(deobfuscate_font(&mut data, key));
+        assert_eq!(data, original);
+    }
+
+    #[test]
+    fn test_decode_xor_encrypted_eot() {
+        let font = [0x00, 0x01, 0x00, 0x00, 0xAA, 0xBB];
+        let mut eot = vec![0u8; 40];
+        let total = (eot.len() + font.len()) as u32;
+        eot[0..4].copy_from_slice(&total.to_le_bytes());
+        eot[4..8].copy_from_slice(&(font.len() as u32).to_le_bytes());
+        eot[12..16].copy_from_slice(&EOT_FLAG_XOR.to_le_bytes());
+        eot[34..36].copy_from_slice(&EOT_MAGIC.to_le_bytes());
+        eot.extend(font.iter().map(|b| b ^ 0x50));
+        assert_eq!(decode_font_data(eot), Some(font.to_vec()));
+    }
+}
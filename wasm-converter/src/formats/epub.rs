@@ -106,13 +106,12 @@ fn read_zip_entry(
     archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
     path: &str,
 ) -> Result<String, ConvertError> {
-    use std::io::Read;
     let mut file = archive
         .by_name(path)
         .map_err(|e| ConvertError::new("EPUB", &format!("{}が見つかりません: {}", path, e)))?;
 
     let mut content = String::new();
-    file.read_to_string(&mut content)
+    super::limits::read_limited_string(&mut file, &mut content)
         .map_err(|e| ConvertError::new("EPUB", &format!("{}の読み込みエラー: {}", path, e)))?;
 
     Ok(content)
@@ -0,0 +1,1225 @@
+// formats/html.rs - HTML変換モジュール
+//
+// HTMLファイルを解析し、見出し・段落・リスト・テーブル・画像と基本のインライン書式
+// （b / i / u / s / span など）をページに流し込んでドキュメントモデルに変換します。
+// CSSは要素の `style` 属性のうち color / font-size / font-weight / font-style /
+// text-decoration / text-align だけを解釈し、外部のスタイルシートとスクリプトは読み込みません。
+// リンク（a 要素）の見た目は `TextFlowOptions::hyperlink_style` で強調・プレーンに揃えられます。
+// 画像は data URI と `Converter::add_asset` で供給されたもの（PNG / JPEG）を描画し、
+// それ以外は遅延アセットとしてプレースホルダーで表示します。
+
+use super::deferred_assets::{find_supplied, placeholder_elements, DeferredAsset};
+use crate::converter::{
+    image_pixel_size, Color, ConvertError, Document, DocumentConverter, FontStyle, HyperlinkStyle, Metadata, Page,
+    PageElement, Table, TableCell, TextAlign, TextFlowOptions, UnderlineKind, UnderlineStyle,
+};
+use crate::font_manager::estimate_text_width;
+
+/// ページの余白（ポイント）
+const MARGIN: f64 = 50.0;
+/// 行送り（フォントサイズ比）
+const LINE_SPACING: f64 = 1.4;
+/// 段落の後の間隔（フォントサイズ比）
+const PARAGRAPH_SPACING: f64 = 0.5;
+/// リスト項目の後の間隔（ポイント）
+const LIST_ITEM_SPACING: f64 = 2.0;
+/// リスト・引用の入れ子1段あたりの字下げ（ポイント）
+const INDENT_STEP: f64 = 20.0;
+/// テーブル・画像の後の間隔（ポイント）
+const BLOCK_SPACING: f64 = 8.0;
+/// 見出し h1〜h6 の本文に対する文字サイズの倍率
+const HEADING_SCALES: [f64; 6] = [2.0, 1.5, 1.17, 1.0, 0.83, 0.67];
+/// CSSの1pxのポイント換算（96dpi）
+const PX_TO_PT: f64 = 0.75;
+/// 寸法の分からない画像のプレースホルダーの大きさ（ポイント）
+const PLACEHOLDER_SIZE: (f64, f64) = (120.0, 90.0);
+/// 強調したリンクの色（Office の既定テーマの hlink）
+const LINK_COLOR: Color = Color::rgb(5, 99, 193);
+/// 上付き・下付き文字のベースラインのずれ（フォントサイズ比）
+const SUPERSCRIPT_SHIFT: f64 = 0.33;
+const SUBSCRIPT_SHIFT: f64 = -0.14;
+
+/// 終了タグを持たない要素
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+/// 中身を描画しない要素
+const SKIPPED_ELEMENTS: &[&str] = &["noscript", "template", "select", "textarea", "button"];
+/// 前後で改行するブロック要素（見出し・リスト・テーブル・pre は個別に処理）
+const BLOCK_ELEMENTS: &[&str] = &[
+    "html", "body", "p", "div", "section", "article", "header", "footer", "main", "nav", "aside", "blockquote",
+    "figure", "figcaption", "address", "center", "dl", "dt", "dd", "form", "fieldset", "caption",
+];
+
+/// HTMLコンバーター
+pub struct HtmlConverter {
+    options: TextFlowOptions,
+}
+
+impl HtmlConverter {
+    pub fn new() -> Self {
+        Self::with_options(TextFlowOptions::default())
+    }
+
+    /// 既定フォント・用紙サイズ・供給済みの画像を指定してコンバーターを作成
+    pub fn with_options(options: TextFlowOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl DocumentConverter for HtmlConverter {
+    fn convert(&self, input: &[u8]) -> Result<Document, ConvertError> {
+        let text = super::txt::decode_text(input)?;
+        let mut builder = BlockBuilder::new(self.options.font_style(), self.options.hyperlink_style);
+        for token in tokenize(&text) {
+            builder.token(token);
+        }
+        let (blocks, title) = builder.finish();
+
+        let mut layout = FlowLayout::new(&self.options);
+        for block in blocks {
+            layout.block(block);
+        }
+
+        let mut doc = Document::new();
+        doc.metadata = Metadata {
+            title: Some(title.unwrap_or_else(|| "HTML Document".to_string())),
+            ..Default::default()
+        };
+        doc.warnings = layout.warnings.clone();
+        doc.pages = layout.finish();
+        Ok(doc)
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["html", "htm"]
+    }
+
+    fn format_name(&self) -> &str {
+        "HTML"
+    }
+}
+
+// ──────────────────────────────────────────────
+// トークン化
+// ──────────────────────────────────────────────
+
+/// HTMLのトークン
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// 開始タグ（要素名は小文字）
+    Start { name: String, attrs: Vec<(String, String)> },
+    /// 終了タグ（要素名は小文字）
+    End(String),
+    /// 文字参照を展開したテキスト
+    Text(String),
+}
+
+/// HTMLをトークンに分割（閉じ忘れ・引用符の無い属性値などの崩れた記述も読み進める）
+/// コメント・DOCTYPE・処理命令は読み飛ばし、script / style の中身は捨てます。
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        if lt > 0 {
+            tokens.push(Token::Text(decode_entities(&rest[..lt])));
+            rest = &rest[lt..];
+        }
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+        let Some(tag) = parse_tag(rest) else {
+            // タグとして読めない '<' は文字として扱う
+            tokens.push(Token::Text("<".to_string()));
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[tag.consumed..];
+        match tag.token {
+            Token::Start { ref name, .. } if name == "script" || name == "style" => {
+                let close = format!("</{}", name);
+                let end = rest.to_ascii_lowercase().find(&close);
+                rest = end.map_or("", |end| &rest[end..]);
+                rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            }
+            token => tokens.push(token),
+        }
+    }
+    tokens
+}
+
+/// 解析したタグと消費したバイト数
+struct ParsedTag {
+    token: Token,
+    consumed: usize,
+}
+
+/// `<` から始まるタグを解析（要素名が無い場合は None）
+fn parse_tag(input: &str) -> Option<ParsedTag> {
+    let bytes = input.as_bytes();
+    let closing = bytes.get(1) == Some(&b'/');
+    let mut pos = if closing { 2 } else { 1 };
+    let name_start = pos;
+    while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'-' || bytes[pos] == b':') {
+        pos += 1;
+    }
+    if pos == name_start || !bytes[name_start].is_ascii_alphabetic() {
+        return None;
+    }
+    let name = input[name_start..pos].to_ascii_lowercase();
+
+    let mut attrs = Vec::new();
+    loop {
+        while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'/') {
+            pos += 1;
+        }
+        if pos >= bytes.len() {
+            break;
+        }
+        if bytes[pos] == b'>' {
+            pos += 1;
+            break;
+        }
+        let key_start = pos;
+        while pos < bytes.len() && !matches!(bytes[pos], b'=' | b'>' | b'/') && !bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let key = input[key_start..pos].to_ascii_lowercase();
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(pos) == Some(&b'=') {
+            pos += 1;
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            match bytes.get(pos) {
+                Some(&quote) if quote == b'"' || quote == b'\'' => {
+                    let end = input[pos + 1..].find(quote as char).map_or(bytes.len(), |e| pos + 1 + e);
+                    value = decode_entities(&input[pos + 1..end]);
+                    pos = (end + 1).min(bytes.len());
+                }
+                _ => {
+                    let start = pos;
+                    while pos < bytes.len() && bytes[pos] != b'>' && !bytes[pos].is_ascii_whitespace() {
+                        pos += 1;
+                    }
+                    value = decode_entities(&input[start..pos]);
+                }
+            }
+        }
+        if !key.is_empty() {
+            attrs.push((key, value));
+        }
+    }
+
+    let token = if closing { Token::End(name) } else { Token::Start { name, attrs } };
+    Some(ParsedTag { token, consumed: pos })
+}
+
+/// 文字参照（&amp; / &#39; / &#x3042; など）を展開（未知の参照はそのまま残す）
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| entity_char(&rest[1..end + 1]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 文字参照の名前（'&' と ';' を除く）に対応する文字
+fn entity_char(name: &str) -> Option<char> {
+    if let Some(num) = name.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "laquo" => '«',
+        "raquo" => '»',
+        "yen" => '¥',
+        "times" => '×',
+        "middot" => '·',
+        "bull" => '•',
+        _ => return None,
+    })
+}
+
+// ──────────────────────────────────────────────
+// ブロックの組み立て
+// ──────────────────────────────────────────────
+
+/// 同じ書式のテキスト
+struct Run {
+    text: String,
+    style: FontStyle,
+}
+
+/// ページに流し込む単位
+enum Block {
+    /// 段落（見出し・リスト項目を含む。テキスト中の '\n' は強制改行）
+    Paragraph { runs: Vec<Run>, align: TextAlign, indent: f64, space_after: f64 },
+    /// テーブルの行（結合で隠れるセルは col_span = 0）
+    Table(Vec<Vec<TableCell>>),
+    /// 画像（寸法は width / height 属性のピクセル値）
+    Image { src: String, width: Option<f64>, height: Option<f64>, align: TextAlign },
+    /// 水平線（hr）
+    Rule,
+}
+
+/// 開いている要素ごとの書式
+struct Frame {
+    tag: String,
+    style: FontStyle,
+    align: TextAlign,
+    indent: f64,
+}
+
+/// 組み立て中の段落
+struct OpenParagraph {
+    runs: Vec<Run>,
+    align: TextAlign,
+    indent: f64,
+    space_after: f64,
+}
+
+/// 組み立て中のテーブル
+#[derive(Default)]
+struct OpenTable {
+    rows: Vec<Vec<TableCell>>,
+    row: Option<Vec<TableCell>>,
+    cell: Option<TableCell>,
+}
+
+impl OpenTable {
+    fn finish_cell(&mut self) {
+        if let Some(mut cell) = self.cell.take() {
+            cell.text = cell.text.trim_matches(|c| c == ' ' || c == '\n').to_string();
+            let span = cell.col_span;
+            let row = self.row.get_or_insert_with(Vec::new);
+            let mut hidden = TableCell::new("");
+            hidden.col_span = 0;
+            row.push(cell);
+            row.extend((1..span).map(|_| hidden.clone()));
+        }
+    }
+
+    fn finish_row(&mut self) {
+        self.finish_cell();
+        if let Some(row) = self.row.take().filter(|row| !row.is_empty()) {
+            self.rows.push(row);
+        }
+    }
+
+    /// 入れ子のテーブルを外側のセルに入れるテキスト（セルは空白、行は改行で区切る）
+    fn into_text(mut self) -> String {
+        self.finish_row();
+        self.rows
+            .iter()
+            .map(|row| row.iter().filter(|c| c.col_span > 0).map(|c| c.text.as_str()).collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// トークンを順に受け取り、書式付きのブロックに組み立てる
+struct BlockBuilder {
+    frames: Vec<Frame>,
+    blocks: Vec<Block>,
+    paragraph: Option<OpenParagraph>,
+    /// 開いているリスト（番号付きか, 直前の項目番号）
+    lists: Vec<(bool, u32)>,
+    /// 次の段落の先頭に付けるリストの記号
+    marker: Option<String>,
+    tables: Vec<OpenTable>,
+    pre_depth: usize,
+    skip_depth: usize,
+    title: Option<String>,
+    in_title: bool,
+    hyperlink_style: HyperlinkStyle,
+}
+
+impl BlockBuilder {
+    fn new(base: FontStyle, hyperlink_style: HyperlinkStyle) -> Self {
+        Self {
+            frames: vec![Frame { tag: String::new(), style: base, align: TextAlign::Left, indent: 0.0 }],
+            blocks: Vec::new(),
+            paragraph: None,
+            lists: Vec::new(),
+            marker: None,
+            tables: Vec::new(),
+            pre_depth: 0,
+            skip_depth: 0,
+            title: None,
+            in_title: false,
+            hyperlink_style,
+        }
+    }
+
+    fn frame(&self) -> &Frame {
+        self.frames.last().expect("基底の書式は取り除かない")
+    }
+
+    fn token(&mut self, token: Token) {
+        match token {
+            Token::Start { name, attrs } => self.start(name, &attrs),
+            Token::End(name) => self.end(&name),
+            Token::Text(text) => self.text(&text),
+        }
+    }
+
+    fn start(&mut self, name: String, attrs: &[(String, String)]) {
+        let void = VOID_ELEMENTS.contains(&name.as_str());
+        if self.skip_depth > 0 || SKIPPED_ELEMENTS.contains(&name.as_str()) {
+            if !void {
+                self.skip_depth += 1;
+            }
+            return;
+        }
+        match name.as_str() {
+            "title" => self.in_title = true,
+            "br" => self.line_break(),
+            "hr" => {
+                self.flush();
+                if self.tables.is_empty() {
+                    self.blocks.push(Block::Rule);
+                }
+            }
+            "img" => self.image(attrs),
+            "table" => {
+                self.flush();
+                self.tables.push(OpenTable::default());
+            }
+            "tr" => {
+                if let Some(table) = self.tables.last_mut() {
+                    table.finish_row();
+                    table.row = Some(Vec::new());
+                }
+            }
+            "td" | "th" => {
+                let mut style = self.frame().style.clone();
+                apply_element_style(&name, &mut style);
+                apply_css(attrs, &mut style, &mut TextAlign::Left);
+                if let Some(table) = self.tables.last_mut() {
+                    table.finish_cell();
+                    let mut cell = TableCell::new("");
+                    cell.style = style;
+                    cell.col_span = attr(attrs, "colspan").and_then(|v| v.trim().parse().ok()).unwrap_or(1).clamp(1, 100);
+                    table.cell = Some(cell);
+                }
+            }
+            "ul" | "ol" => {
+                self.flush();
+                self.lists.push((name == "ol", 0));
+            }
+            "li" => {
+                self.flush();
+                if let Some((ordered, number)) = self.lists.last_mut() {
+                    *number += 1;
+                    self.marker = Some(if *ordered { format!("{}. ", number) } else { "• ".to_string() });
+                }
+            }
+            "pre" => {
+                self.flush();
+                self.pre_depth += 1;
+            }
+            _ if is_block(&name) => self.flush(),
+            _ => {}
+        }
+        if void {
+            return;
+        }
+
+        let parent = self.frame();
+        let mut style = parent.style.clone();
+        let mut align = parent.align;
+        let mut indent = parent.indent;
+        apply_element_style(&name, &mut style);
+        if matches!(name.as_str(), "ul" | "ol" | "blockquote" | "dd") {
+            indent += INDENT_STEP;
+        }
+        if let Some(value) = attr(attrs, "align") {
+            align = parse_align(value).unwrap_or(align);
+        }
+        if name == "center" {
+            align = TextAlign::Center;
+        }
+        apply_css(attrs, &mut style, &mut align);
+        if name == "a" && attr(attrs, "href").is_some() {
+            match self.hyperlink_style {
+                HyperlinkStyle::Source => {}
+                HyperlinkStyle::Emphasize => {
+                    style.color = LINK_COLOR;
+                    style.underline = Some(UnderlineStyle::new(UnderlineKind::Single));
+                }
+                HyperlinkStyle::Plain => style.underline = None,
+            }
+        }
+        self.frames.push(Frame { tag: name, style, align, indent });
+    }
+
+    fn end(&mut self, name: &str) {
+        if self.skip_depth > 0 {
+            if SKIPPED_ELEMENTS.contains(&name) {
+                self.skip_depth -= 1;
+            }
+            return;
+        }
+        match name {
+            "title" => self.in_title = false,
+            "td" | "th" => {
+                if let Some(table) = self.tables.last_mut() {
+                    table.finish_cell();
+                }
+            }
+            "tr" => {
+                if let Some(table) = self.tables.last_mut() {
+                    table.finish_row();
+                }
+            }
+            "table" => {
+                if let Some(table) = self.tables.pop() {
+                    match self.tables.last_mut() {
+                        Some(outer) => {
+                            let text = table.into_text();
+                            if let Some(cell) = outer.cell.as_mut() {
+                                append_cell_text(cell, &text);
+                            }
+                        }
+                        None => {
+                            let mut table = table;
+                            table.finish_row();
+                            if !table.rows.is_empty() {
+                                self.blocks.push(Block::Table(table.rows));
+                            }
+                        }
+                    }
+                }
+            }
+            "ul" | "ol" => {
+                self.flush();
+                self.lists.pop();
+            }
+            "pre" => {
+                self.flush();
+                self.pre_depth = self.pre_depth.saturating_sub(1);
+            }
+            _ if is_block(name) => self.flush(),
+            _ => {}
+        }
+        // 対応する開始タグまでの書式を閉じる（開始タグが無い終了タグは無視）
+        if let Some(pos) = self.frames.iter().rposition(|f| f.tag == name).filter(|&pos| pos > 0) {
+            self.frames.truncate(pos);
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.skip_depth > 0 {
+            return;
+        }
+        if self.in_title {
+            self.title.get_or_insert_with(String::new).push_str(&collapse_whitespace(text));
+            return;
+        }
+        let text = if self.pre_depth > 0 {
+            text.replace("\r\n", "\n").replace('\t', "    ")
+        } else {
+            collapse_whitespace(text)
+        };
+
+        if let Some(table) = self.tables.last_mut() {
+            // テーブル内ではセルの外のテキスト（行間の空白など）を捨てる
+            if let Some(cell) = table.cell.as_mut() {
+                append_cell_text(cell, &text);
+            }
+            return;
+        }
+
+        let style = self.frame().style.clone();
+        if self.paragraph.is_none() {
+            if text.trim_matches(' ').is_empty() {
+                return;
+            }
+            self.open_paragraph();
+        }
+        let paragraph = self.paragraph.as_mut().expect("段落を開いた直後");
+        let at_line_start = paragraph.runs.last().is_none_or(|r| r.text.is_empty() || r.text.ends_with([' ', '\n']));
+        let text = if at_line_start && self.pre_depth == 0 { text.trim_start_matches(' ') } else { &text };
+        if !text.is_empty() {
+            paragraph.runs.push(Run { text: text.to_string(), style });
+        }
+    }
+
+    /// 現在の書式で段落を開始（リスト項目の記号があれば先頭に付ける）
+    fn open_paragraph(&mut self) {
+        let marker = self.marker.take();
+        let frame = self.frame();
+        let in_list = !self.lists.is_empty();
+        let mut paragraph = OpenParagraph {
+            runs: Vec::new(),
+            align: frame.align,
+            indent: frame.indent,
+            space_after: if in_list { LIST_ITEM_SPACING } else { frame.style.font_size * PARAGRAPH_SPACING },
+        };
+        if let Some(marker) = marker {
+            paragraph.runs.push(Run { text: marker, style: frame.style.clone() });
+        }
+        self.paragraph = Some(paragraph);
+    }
+
+    fn line_break(&mut self) {
+        if let Some(table) = self.tables.last_mut() {
+            if let Some(cell) = table.cell.as_mut() {
+                cell.text.push('\n');
+            }
+            return;
+        }
+        if self.paragraph.is_none() {
+            self.open_paragraph();
+        }
+        let style = self.frame().style.clone();
+        if let Some(paragraph) = self.paragraph.as_mut() {
+            paragraph.runs.push(Run { text: "\n".to_string(), style });
+        }
+    }
+
+    fn image(&mut self, attrs: &[(String, String)]) {
+        let Some(src) = attr(attrs, "src").filter(|s| !s.is_empty()) else {
+            return;
+        };
+        if let Some(table) = self.tables.last_mut() {
+            // セル内の画像は代替テキストで表す
+            if let (Some(cell), Some(alt)) = (table.cell.as_mut(), attr(attrs, "alt")) {
+                append_cell_text(cell, alt);
+            }
+            return;
+        }
+        self.flush();
+        let pixels = |name: &str| attr(attrs, name).and_then(|v| v.trim().trim_end_matches("px").parse::<f64>().ok());
+        self.blocks.push(Block::Image {
+            src: src.to_string(),
+            width: pixels("width"),
+            height: pixels("height"),
+            align: self.frame().align,
+        });
+    }
+
+    /// 組み立て中の段落を確定（テーブル内ではセル内の改行にする）
+    fn flush(&mut self) {
+        if let Some(table) = self.tables.last_mut() {
+            if let Some(cell) = table.cell.as_mut().filter(|c| !c.text.is_empty() && !c.text.ends_with('\n')) {
+                cell.text.push('\n');
+            }
+            return;
+        }
+        if let Some(p) = self.paragraph.take() {
+            if p.runs.iter().any(|r| !r.text.trim().is_empty()) {
+                self.blocks.push(Block::Paragraph { runs: p.runs, align: p.align, indent: p.indent, space_after: p.space_after });
+            }
+        }
+    }
+
+    fn finish(mut self) -> (Vec<Block>, Option<String>) {
+        while !self.tables.is_empty() {
+            self.end("table");
+        }
+        self.flush();
+        let title = self.title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        (self.blocks, title)
+    }
+}
+
+fn is_block(name: &str) -> bool {
+    BLOCK_ELEMENTS.contains(&name) || matches!(name, "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "li")
+}
+
+fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+}
+
+/// 連続する空白・改行を1つの空白にまとめる（ノーブレークスペースは残す）
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0c') {
+            if !space {
+                out.push(' ');
+            }
+            space = true;
+        } else {
+            out.push(c);
+            space = false;
+        }
+    }
+    out
+}
+
+fn append_cell_text(cell: &mut TableCell, text: &str) {
+    let text = if cell.text.is_empty() || cell.text.ends_with([' ', '\n']) { text.trim_start_matches(' ') } else { text };
+    cell.text.push_str(text);
+}
+
+/// 要素の既定の書式（見出し・太字・斜体・下線・取り消し線・上付き／下付き）を適用
+fn apply_element_style(name: &str, style: &mut FontStyle) {
+    match name {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = (name.as_bytes()[1] - b'1') as usize;
+            style.font_size *= HEADING_SCALES[level];
+            style.bold = true;
+        }
+        "b" | "strong" | "th" | "dt" => style.bold = true,
+        "i" | "em" | "cite" | "var" | "dfn" | "address" => style.italic = true,
+        "u" | "ins" => style.underline = Some(UnderlineStyle::new(UnderlineKind::Single)),
+        "s" | "strike" | "del" => style.strikethrough = true,
+        "small" => style.font_size *= 0.83,
+        "big" => style.font_size *= 1.2,
+        "sup" => style.baseline_shift = SUPERSCRIPT_SHIFT,
+        "sub" => style.baseline_shift = SUBSCRIPT_SHIFT,
+        _ => {}
+    }
+}
+
+/// `style` 属性の対応するプロパティを適用
+fn apply_css(attrs: &[(String, String)], style: &mut FontStyle, align: &mut TextAlign) {
+    let Some(css) = attr(attrs, "style") else {
+        return;
+    };
+    for declaration in css.split(';') {
+        let Some((property, value)) = declaration.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().trim_end_matches("!important").trim();
+        match property.trim().to_ascii_lowercase().as_str() {
+            "color" => {
+                if let Some(color) = crate::svg::parse_color(value) {
+                    style.color = color;
+                }
+            }
+            "font-size" => {
+                if let Some(size) = parse_font_size(value, style.font_size) {
+                    style.font_size = size;
+                }
+            }
+            "font-weight" => {
+                style.bold = match value {
+                    "bold" | "bolder" => true,
+                    "normal" | "lighter" => false,
+                    v => v.parse::<u32>().map_or(style.bold, |w| w >= 600),
+                }
+            }
+            "font-style" => style.italic = matches!(value, "italic" | "oblique"),
+            "text-decoration" | "text-decoration-line" => {
+                if value.contains("none") {
+                    style.underline = None;
+                    style.strikethrough = false;
+                }
+                if value.contains("underline") {
+                    style.underline = Some(UnderlineStyle::new(UnderlineKind::Single));
+                }
+                if value.contains("line-through") {
+                    style.strikethrough = true;
+                }
+            }
+            "text-align" => *align = parse_align(value).unwrap_or(*align),
+            _ => {}
+        }
+    }
+}
+
+/// CSSの font-size（pt / px / em / % とキーワード）をポイントに変換
+fn parse_font_size(value: &str, current: f64) -> Option<f64> {
+    let value = value.to_ascii_lowercase();
+    let keyword = match value.as_str() {
+        "xx-small" => Some(7.0),
+        "x-small" => Some(7.5),
+        "small" => Some(10.0),
+        "medium" => Some(12.0),
+        "large" => Some(13.5),
+        "x-large" => Some(18.0),
+        "xx-large" => Some(24.0),
+        "smaller" => Some(current * 0.83),
+        "larger" => Some(current * 1.2),
+        _ => None,
+    };
+    if keyword.is_some() {
+        return keyword;
+    }
+    let (number, factor) = if let Some(n) = value.strip_suffix("pt") {
+        (n, 1.0)
+    } else if let Some(n) = value.strip_suffix("px") {
+        (n, PX_TO_PT)
+    } else if let Some(n) = value.strip_suffix("rem").or_else(|| value.strip_suffix("em")) {
+        (n, current)
+    } else if let Some(n) = value.strip_suffix('%') {
+        (n, current / 100.0)
+    } else {
+        (value.as_str(), PX_TO_PT)
+    };
+    number.trim().parse::<f64>().ok().map(|n| n * factor).filter(|size| *size > 0.0 && size.is_finite())
+}
+
+fn parse_align(value: &str) -> Option<TextAlign> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "left" | "start" | "justify" => Some(TextAlign::Left),
+        "center" | "middle" => Some(TextAlign::Center),
+        "right" | "end" => Some(TextAlign::Right),
+        _ => None,
+    }
+}
+
+// ──────────────────────────────────────────────
+// ページへの流し込み
+// ──────────────────────────────────────────────
+
+/// 1行に並べるテキストの断片（元のランの番号, テキスト, 幅）
+struct Segment {
+    run: usize,
+    text: String,
+    width: f64,
+}
+
+/// 折り返した1行
+#[derive(Default)]
+struct Line {
+    segments: Vec<Segment>,
+    width: f64,
+    /// 行内の最大の文字サイズ
+    font_size: f64,
+}
+
+/// 段落のランを幅 `max_width` で折り返す（欧文は単語単位、CJKは1文字単位）
+fn break_lines(runs: &[Run], max_width: f64) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut line = Line::default();
+    for (index, run) in runs.iter().enumerate() {
+        let size = run.style.script_metrics(0.0).0;
+        line.font_size = line.font_size.max(run.style.font_size);
+        for piece in split_pieces(&run.text) {
+            if piece == "\n" {
+                lines.push(finish_line(std::mem::take(&mut line), runs));
+                line.font_size = run.style.font_size;
+                continue;
+            }
+            let measure = |text: &str| estimate_text_width(text, size, None) + run.style.letter_spacing_width(text);
+            // 1行に収まらない長い単語は1文字ずつ折り返す
+            let chars: Vec<String> = if measure(piece.trim_end_matches(' ')) > max_width {
+                piece.chars().map(String::from).collect()
+            } else {
+                vec![piece.to_string()]
+            };
+            for piece in chars {
+                let fits = line.width + measure(piece.trim_end_matches(' ')) <= max_width;
+                if !line.segments.is_empty() && !fits {
+                    lines.push(finish_line(std::mem::take(&mut line), runs));
+                    line.font_size = run.style.font_size;
+                }
+                let piece = if line.segments.is_empty() { piece.trim_start_matches(' ') } else { piece.as_str() };
+                if piece.is_empty() {
+                    continue;
+                }
+                let width = measure(piece);
+                line.width += width;
+                match line.segments.last_mut() {
+                    Some(last) if last.run == index => {
+                        last.text.push_str(piece);
+                        last.width += width;
+                    }
+                    _ => line.segments.push(Segment { run: index, text: piece.to_string(), width }),
+                }
+            }
+        }
+    }
+    if !line.segments.is_empty() {
+        lines.push(finish_line(line, runs));
+    }
+    lines
+}
+
+/// 行末の空白を取り除いて幅を詰める
+fn finish_line(mut line: Line, runs: &[Run]) -> Line {
+    if let Some(last) = line.segments.last_mut() {
+        let trimmed = last.text.trim_end_matches(' ').len();
+        if trimmed < last.text.len() {
+            let style = &runs[last.run].style;
+            let removed = estimate_text_width(&last.text[trimmed..], style.script_metrics(0.0).0, None);
+            last.text.truncate(trimmed);
+            last.width -= removed;
+            line.width -= removed;
+        }
+    }
+    line
+}
+
+/// 折り返し位置で区切った断片（空白の後・CJK文字の前後・強制改行）
+fn split_pieces(text: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut prev_space = false;
+    for (i, c) in text.char_indices() {
+        let wide = c as u32 >= 0x2E80;
+        if c == '\n' || wide || (prev_space && c != ' ') {
+            if i > start {
+                pieces.push(&text[start..i]);
+            }
+            start = i;
+        }
+        if c == '\n' || wide {
+            let end = i + c.len_utf8();
+            // CJK文字の直後の空白は同じ断片に含める
+            pieces.push(&text[start..end]);
+            start = end;
+        }
+        prev_space = c == ' ';
+    }
+    if start < text.len() {
+        pieces.push(&text[start..]);
+    }
+    pieces
+}
+
+/// ブロックを上から順にページへ配置する
+struct FlowLayout<'a> {
+    options: &'a TextFlowOptions,
+    pages: Vec<Page>,
+    page: Page,
+    y: f64,
+    image_count: usize,
+    warnings: Vec<String>,
+}
+
+impl<'a> FlowLayout<'a> {
+    fn new(options: &'a TextFlowOptions) -> Self {
+        Self {
+            options,
+            pages: Vec::new(),
+            page: options.blank_page(),
+            y: MARGIN,
+            image_count: 0,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn content_width(&self) -> f64 {
+        self.page.width - MARGIN * 2.0
+    }
+
+    fn bottom(&self) -> f64 {
+        self.page.height - MARGIN
+    }
+
+    fn new_page(&mut self) {
+        let blank = self.options.blank_page();
+        self.pages.push(std::mem::replace(&mut self.page, blank));
+        self.y = MARGIN;
+    }
+
+    /// 高さ `height` が残りに収まらなければ改ページ（ページ先頭では改ページしない）
+    fn ensure_room(&mut self, height: f64) {
+        if self.y + height > self.bottom() && self.y > MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn block(&mut self, block: Block) {
+        match block {
+            Block::Paragraph { runs, align, indent, space_after } => self.paragraph(&runs, align, indent, space_after),
+            Block::Table(rows) => self.table(rows),
+            Block::Image { src, width, height, align } => self.image(&src, width, height, align),
+            Block::Rule => {
+                self.ensure_room(BLOCK_SPACING);
+                let y = self.y + BLOCK_SPACING / 2.0;
+                self.page.elements.push(PageElement::Line {
+                    x1: MARGIN,
+                    y1: y,
+                    x2: self.page.width - MARGIN,
+                    y2: y,
+                    width: 0.75,
+                    color: Color::rgb(160, 160, 160),
+                    stroke_style: Default::default(),
+                });
+                self.y += BLOCK_SPACING;
+            }
+        }
+    }
+
+    fn paragraph(&mut self, runs: &[Run], align: TextAlign, indent: f64, space_after: f64) {
+        let indent = indent.min(self.content_width() / 2.0);
+        let width = self.content_width() - indent;
+        for line in break_lines(runs, width) {
+            let height = line.font_size * LINE_SPACING;
+            self.ensure_room(height);
+            let mut x = MARGIN
+                + indent
+                + match align {
+                    TextAlign::Left => 0.0,
+                    TextAlign::Center => ((width - line.width) / 2.0).max(0.0),
+                    TextAlign::Right => (width - line.width).max(0.0),
+                };
+            for segment in line.segments {
+                let style = runs[segment.run].style.clone();
+                // 文字サイズの異なるランはベースラインを揃える
+                let y = self.y + (line.font_size - style.font_size);
+                self.page.elements.push(PageElement::Text {
+                    x,
+                    y,
+                    width: segment.width,
+                    text: segment.text.replace('\u{a0}', " "),
+                    style,
+                    align: TextAlign::Left,
+                });
+                x += segment.width;
+            }
+            self.y += height;
+        }
+        self.y += space_after;
+    }
+
+    /// テーブルをページの残りに収まる行ごとに分割して配置
+    /// 先頭行がすべて見出しセル（th）の場合、`repeat_header` なら続きのページにも繰り返します。
+    fn table(&mut self, mut rows: Vec<Vec<TableCell>>) {
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(1).max(1);
+        for row in &mut rows {
+            while row.len() < columns {
+                row.push(TableCell::new(""));
+            }
+        }
+        let width = self.content_width();
+        let column_widths = vec![width / columns as f64; columns];
+        let header_row = rows[0].iter().all(|cell| cell.style.bold || cell.col_span == 0);
+        let header = (self.options.repeat_header && header_row && rows.len() > 1).then(|| rows.remove(0));
+        let header_height = header.as_ref().map_or(0.0, |row| {
+            Table { rows: vec![row.clone()], column_widths: column_widths.clone() }.row_heights(width, None)[0]
+        });
+        let heights = Table { rows: rows.clone(), column_widths: column_widths.clone() }.row_heights(width, None);
+
+        let mut chunk: Vec<Vec<TableCell>> = Vec::new();
+        let mut used = 0.0;
+        for (row, height) in rows.into_iter().zip(heights) {
+            if self.y + used + height > self.bottom() {
+                if chunk.is_empty() {
+                    self.ensure_room(header_height + height);
+                } else {
+                    self.place_table(std::mem::take(&mut chunk), &column_widths, used);
+                    self.new_page();
+                }
+            }
+            if chunk.is_empty() {
+                used = 0.0;
+                if let Some(header) = &header {
+                    chunk.push(header.clone());
+                    used = header_height;
+                }
+            }
+            chunk.push(row);
+            used += height;
+        }
+        if !chunk.is_empty() {
+            self.place_table(chunk, &column_widths, used);
+        }
+        self.y += BLOCK_SPACING;
+    }
+
+    fn place_table(&mut self, rows: Vec<Vec<TableCell>>, column_widths: &[f64], height: f64) {
+        self.page.elements.push(PageElement::TableBlock {
+            x: MARGIN,
+            y: self.y,
+            width: column_widths.iter().sum(),
+            table: Table { rows, column_widths: column_widths.to_vec() },
+        });
+        self.y += height;
+    }
+
+    /// 画像を配置（読み込めない画像はプレースホルダーと遅延アセットの警告）
+    fn image(&mut self, src: &str, width: Option<f64>, height: Option<f64>, align: TextAlign) {
+        self.image_count += 1;
+        let data = decode_data_uri(src).or_else(|| find_supplied(&self.options.supplied_assets, src).map(<[u8]>::to_vec));
+        let data = data.filter(|d| image_mime(d).is_some());
+        let natural = data.as_deref().and_then(image_pixel_size).map(|(w, h)| (w as f64, h as f64));
+
+        // 属性の片方だけ指定された場合は画像の縦横比を保つ
+        let (w, h) = match (width, height, natural) {
+            (Some(w), Some(h), _) => (w, h),
+            (Some(w), None, Some((nw, nh))) => (w, w * nh / nw),
+            (None, Some(h), Some((nw, nh))) => (h * nw / nh, h),
+            (None, None, Some(size)) => size,
+            (w, h, None) => (
+                w.unwrap_or(PLACEHOLDER_SIZE.0 / PX_TO_PT),
+                h.unwrap_or(PLACEHOLDER_SIZE.1 / PX_TO_PT),
+            ),
+        };
+        let (mut w, mut h) = (w * PX_TO_PT, h * PX_TO_PT);
+        let scale = (self.content_width() / w).min((self.bottom() - MARGIN) / h).min(1.0);
+        if scale > 0.0 && scale.is_finite() {
+            w *= scale;
+            h *= scale;
+        }
+
+        self.ensure_room(h);
+        let x = MARGIN
+            + match align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => (self.content_width() - w) / 2.0,
+                TextAlign::Right => self.content_width() - w,
+            };
+        match data {
+            Some(data) => {
+                let mime_type = image_mime(&data).unwrap_or("image/png").to_string();
+                self.page.elements.push(PageElement::Image { x, y: self.y, width: w, height: h, data, mime_type });
+            }
+            None => {
+                let asset = DeferredAsset {
+                    relationship_id: format!("img{}", self.image_count),
+                    path: (!src.starts_with("data:")).then(|| src.to_string()),
+                };
+                self.page.elements.extend(placeholder_elements(x, self.y, w, h, 0.0, &asset.relationship_id));
+                self.warnings.push(asset.warning());
+            }
+        }
+        self.y += h + BLOCK_SPACING;
+    }
+
+    fn finish(mut self) -> Vec<Page> {
+        if !self.page.elements.is_empty() || self.pages.is_empty() {
+            self.pages.push(self.page);
+        }
+        self.pages
+    }
+}
+
+/// 描画できる画像（PNG / JPEG）のMIMEタイプ
+fn image_mime(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(&[0xFF, 0xD8]) {
+        Some("image/jpeg")
+    } else {
+        None
+    }
+}
+
+/// base64 の data URI（`data:image/png;base64,...`）の画像データを取り出す
+fn decode_data_uri(src: &str) -> Option<Vec<u8>> {
+    let (header, payload) = src.strip_prefix("data:")?.split_once(',')?;
+    if !header.ends_with(";base64") {
+        return None;
+    }
+    let mut out = Vec::with_capacity(payload.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for byte in payload.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            b if b.is_ascii_whitespace() => continue,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_entities_and_attributes() {
+        let tokens = tokenize("<!DOCTYPE html><p class=note ALIGN='center'>a &amp; b&#x3042;&nbsp;<br/></p><!-- x -->");
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Start {
+                    name: "p".to_string(),
+                    attrs: vec![("class".to_string(), "note".to_string()), ("align".to_string(), "center".to_string())],
+                },
+                Token::Text("a & bあ\u{a0}".to_string()),
+                Token::Start { name: "br".to_string(), attrs: Vec::new() },
+                Token::End("p".to_string()),
+            ]
+        );
+        // script / style の中身は捨てる
+        assert_eq!(tokenize("<script>if (a < b) {}</script>x"), vec![Token::Text("x".to_string())]);
+        assert_eq!(decode_data_uri("data:image/png;base64,iVBORw=="), Some(vec![0x89, 0x50, 0x4E, 0x47]));
+    }
+
+    #[test]
+    fn test_inline_styles_and_wrapping() {
+        let doc = HtmlConverter::new()
+            .convert(
+                b"<p style=\"color:#ff0000; font-size:18pt; text-align:right\">Red <b>bold</b></p>\
+                  <ul><li>one</li><li>two</li></ul>",
+            )
+            .unwrap();
+        let texts: Vec<_> = doc.pages[0]
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                PageElement::Text { x, text, style, .. } => Some((*x, text.as_str(), style)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts[0].1, "Red ");
+        assert_eq!((texts[0].2.color.r, texts[0].2.font_size), (255, 18.0));
+        assert!(texts[0].0 > MARGIN, "右揃えの行は右に寄せる");
+        assert!(texts[1].2.bold && texts[1].1 == "bold");
+        assert_eq!((texts[2].1, texts[3].1), ("• ", "one"));
+
+        let runs = vec![Run { text: "aaa bbb ccc".to_string(), style: FontStyle::default() }];
+        let lines = break_lines(&runs, estimate_text_width("aaa bbb", 10.0, None));
+        let lines: Vec<String> = lines.iter().map(|l| l.segments.iter().map(|s| s.text.as_str()).collect()).collect();
+        assert_eq!(lines, vec!["aaa bbb", "ccc"]);
+    }
+}
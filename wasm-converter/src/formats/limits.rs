@@ -0,0 +1,197 @@
+// formats/limits.rs - 変換リソース上限モジュール
+//
+// 悪意のあるZIP（大量のスライドや巨大なメディアを宣言するPPTX / XLSX 等）で
+// WASMのメモリを使い果たさないよう、ページ数・画像サイズ・展開量の上限を検査します。
+// 展開量は変換中のスレッドごとに追跡し、各フォーマットのZIP読み込みヘルパーが
+// `read_limited` / `read_limited_string` を通じて消費します。展開を自前で行う
+// calamine には `inflate_archive` で展開済みのZIPを渡します。
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::io::Read;
+
+use crate::converter::{ConversionLimits, ConvertError, Document};
+
+thread_local! {
+    /// 変換中に残っている展開可能バイト数（None は上限なし）
+    static REMAINING: Cell<Option<u64>> = const { Cell::new(None) };
+    /// 変換中に展開量の上限を超えたか
+    static EXCEEDED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 変換の間だけ展開量の追跡を有効にするガード（ドロップで解除）
+pub(crate) struct DecompressionGuard;
+
+impl DecompressionGuard {
+    pub(crate) fn new(max_bytes: u64) -> Self {
+        REMAINING.set(Some(max_bytes));
+        EXCEEDED.set(false);
+        DecompressionGuard
+    }
+
+    /// 追跡開始以降に展開量の上限を超えたか
+    pub(crate) fn exceeded(&self) -> bool {
+        EXCEEDED.get()
+    }
+}
+
+impl Drop for DecompressionGuard {
+    fn drop(&mut self) {
+        REMAINING.set(None);
+        EXCEEDED.set(false);
+    }
+}
+
+/// エントリを末尾まで読み込み、変換全体の展開量に加算
+/// 上限を超えた時点で読み込みを打ち切りエラーを返します。
+pub(crate) fn read_limited<R: Read>(reader: R, out: &mut Vec<u8>) -> std::io::Result<usize> {
+    let Some(remaining) = REMAINING.get() else {
+        let mut reader = reader;
+        return reader.read_to_end(out);
+    };
+    let read = reader.take(remaining.saturating_add(1)).read_to_end(out)? as u64;
+    if read > remaining {
+        REMAINING.set(Some(0));
+        EXCEEDED.set(true);
+        return Err(std::io::Error::other("展開サイズの上限を超えました"));
+    }
+    REMAINING.set(Some(remaining - read));
+    Ok(read as usize)
+}
+
+/// `read_limited` の文字列版（UTF-8として不正な場合はエラー）
+pub(crate) fn read_limited_string<R: Read>(reader: R, out: &mut String) -> std::io::Result<usize> {
+    let mut buf = Vec::new();
+    let read = read_limited(reader, &mut buf)?;
+    let text = String::from_utf8(buf)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    out.push_str(&text);
+    Ok(read)
+}
+
+/// ZIPの各エントリを `read_limited` で展開し、無圧縮（Stored）で詰め直したZIPを返す
+/// 宣言された展開後サイズに関わらず実際の展開量を上限に加算します。ZIPでないデータはそのまま返します。
+pub(crate) fn inflate_archive(data: &[u8]) -> std::io::Result<Cow<'_, [u8]>> {
+    let Ok(mut archive) = zip::ZipArchive::new(std::io::Cursor::new(data)) else {
+        return Ok(Cow::Borrowed(data));
+    };
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let mut content = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        content.clear();
+        read_limited(entry, &mut content)?;
+        writer.start_file(name, options)?;
+        std::io::Write::write_all(&mut writer, &content)?;
+    }
+    Ok(Cow::Owned(writer.finish()?.into_inner()))
+}
+
+/// 展開量を数えずに `f` を実行（`inflate_archive` で加算済みのパーツを読み直す場合）
+pub(crate) fn uncounted<T>(f: impl FnOnce() -> T) -> T {
+    let remaining = REMAINING.replace(None);
+    let result = f();
+    REMAINING.set(remaining);
+    result
+}
+
+/// 展開前にZIPの中央ディレクトリを検査
+/// 宣言された展開後サイズの合計・画像サイズ・ページとなるパーツ数が上限を超えていればエラー。
+/// ZIPとして開けないデータは各コンバーターの判定に任せます。
+pub fn check_archive(ext: &str, data: &[u8], limits: &ConversionLimits) -> Result<(), ConvertError> {
+    let ext = ext.to_lowercase();
+    let Ok(mut archive) = zip::ZipArchive::new(std::io::Cursor::new(data)) else {
+        return Ok(());
+    };
+    let format = ext.to_uppercase();
+    let mut total: u64 = 0;
+    let mut pages = 0usize;
+    for i in 0..archive.len() {
+        let Ok(entry) = archive.by_index_raw(i) else { continue };
+        let name = entry.name();
+        let size = entry.size();
+        total = total.saturating_add(size);
+        if size > limits.max_image_bytes && super::assets::asset_mime(name).starts_with("image/") {
+            return Err(ConvertError::new(
+                &format,
+                &format!(
+                    "画像 {} のサイズ（{} バイト）が上限（{} バイト）を超えています",
+                    name, size, limits.max_image_bytes
+                ),
+            ));
+        }
+        if is_page_part(&ext, name) {
+            pages += 1;
+        }
+    }
+    if total > limits.max_total_decompressed_bytes {
+        return Err(total_exceeded_error(&format, limits));
+    }
+    if pages > limits.max_pages {
+        return Err(pages_exceeded_error(&format, pages, limits));
+    }
+    Ok(())
+}
+
+/// 変換結果のページ数を検査
+pub fn check_pages(ext: &str, doc: &Document, limits: &ConversionLimits) -> Result<(), ConvertError> {
+    if doc.pages.len() > limits.max_pages {
+        return Err(pages_exceeded_error(&ext.to_uppercase(), doc.pages.len(), limits));
+    }
+    Ok(())
+}
+
+pub(crate) fn total_exceeded_error(format: &str, limits: &ConversionLimits) -> ConvertError {
+    ConvertError::new(
+        format,
+        &format!(
+            "展開後のサイズが上限（{} バイト）を超えています",
+            limits.max_total_decompressed_bytes
+        ),
+    )
+}
+
+fn pages_exceeded_error(format: &str, pages: usize, limits: &ConversionLimits) -> ConvertError {
+    ConvertError::new(
+        format,
+        &format!("ページ数（{}）が上限（{}）を超えています", pages, limits.max_pages),
+    )
+}
+
+/// 1ページ以上になるパーツか（PPTXのスライド、XLSXのワークシート）
+fn is_page_part(ext: &str, name: &str) -> bool {
+    let in_folder = |prefix: &str, stem: &str| {
+        name.strip_prefix(prefix)
+            .is_some_and(|file| file.starts_with(stem) && file.ends_with(".xml") && !file.contains('/'))
+    };
+    match ext {
+        "pptx" => in_folder("ppt/slides/", "slide"),
+        "xlsx" => in_folder("xl/worksheets/", "sheet"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_limited_tracks_total() {
+        let guard = DecompressionGuard::new(10);
+        let mut out = Vec::new();
+        assert_eq!(read_limited(&b"123456"[..], &mut out).unwrap(), 6);
+        assert!(!guard.exceeded());
+        assert!(read_limited(&b"123456"[..], &mut out).is_err());
+        assert!(guard.exceeded());
+        drop(guard);
+
+        // ガードが無ければ制限しない
+        let mut out = Vec::new();
+        assert_eq!(read_limited(&[0u8; 64][..], &mut out).unwrap(), 64);
+    }
+}
@@ -0,0 +1,110 @@
+// formats/markup_compat.rs - マークアップ互換性（mc:AlternateContent）の解決モジュール
+//
+// OOXML は新しい機能を mc:AlternateContent の mc:Choice に、古いアプリ向けの代替を mc:Fallback に記述します。
+// イベント駆動のパーサーが両方の分岐を読んで図形を重複して描画しないよう、
+// 解析前に採用しない分岐をXMLから取り除きます。
+
+use quick_xml::events::{BytesStart, Event};
+
+/// mc:Choice の Requires で理解できる名前空間の接頭辞
+/// 拡張属性・要素を読み飛ばしても標準の要素として解析できるもの。
+/// VML（v）や chartex（cx）などはこれに含めず、mc:Fallback を採用します。
+const UNDERSTOOD_NAMESPACES: &[&str] = &["a14", "p14", "p15", "wps", "wpg", "wp14", "w14", "w15", "c14"];
+
+/// mc:AlternateContent ごとに、理解できる最初の mc:Choice（無ければ mc:Fallback）だけを残す
+/// AlternateContent を含まないXMLはそのまま返します。
+pub(crate) fn resolve_alternate_content(xml: String) -> String {
+    if !xml.contains("AlternateContent") {
+        return xml;
+    }
+
+    let mut reader = quick_xml::Reader::from_str(&xml);
+    // 入れ子の AlternateContent ごとに、分岐を採用済みか
+    let mut taken: Vec<bool> = Vec::new();
+    // 取り除く分岐のバイト範囲（昇順・重なり無し）
+    let mut removed: Vec<std::ops::Range<usize>> = Vec::new();
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) if e.local_name().as_ref() == b"AlternateContent" => taken.push(false),
+            Ok(Event::Start(ref e)) if is_branch(e) && !take_branch(e, &mut taken) => {
+                if reader.read_to_end(e.name()).is_err() {
+                    break;
+                }
+                removed.push(start..reader.buffer_position() as usize);
+            }
+            Ok(Event::Empty(ref e)) if is_branch(e) && !take_branch(e, &mut taken) => {
+                removed.push(start..reader.buffer_position() as usize);
+            }
+            Ok(Event::End(ref e)) if e.local_name().as_ref() == b"AlternateContent" => {
+                taken.pop();
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    if removed.is_empty() {
+        return xml;
+    }
+    let mut resolved = String::with_capacity(xml.len());
+    let mut pos = 0;
+    for range in removed {
+        resolved.push_str(&xml[pos..range.start]);
+        pos = range.end;
+    }
+    resolved.push_str(&xml[pos..]);
+    resolved
+}
+
+/// mc:Choice / mc:Fallback 要素か
+fn is_branch(e: &BytesStart) -> bool {
+    matches!(e.local_name().as_ref(), b"Choice" | b"Fallback")
+}
+
+/// 現在の AlternateContent でこの分岐（mc:Choice / mc:Fallback）を採用するか
+/// 既に分岐を採用済みなら採用せず、mc:Choice は Requires の接頭辞をすべて理解できる場合だけ採用します。
+fn take_branch(e: &BytesStart, taken: &mut [bool]) -> bool {
+    let Some(current) = taken.last_mut() else {
+        return false;
+    };
+    if *current {
+        return false;
+    }
+    let understood = e.local_name().as_ref() == b"Fallback"
+        || e.attributes().flatten().any(|attr| {
+            attr.key.local_name().as_ref() == b"Requires"
+                && String::from_utf8_lossy(&attr.value)
+                    .split_whitespace()
+                    .all(|prefix| UNDERSTOOD_NAMESPACES.contains(&prefix))
+        });
+    *current = understood;
+    understood
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_alternate_content_keeps_one_branch() {
+        let xml = r#"<root xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006">
+          <mc:AlternateContent>
+            <mc:Choice Requires="cx1"><chartex/></mc:Choice>
+            <mc:Choice Requires="wps"><shape id="choice"/></mc:Choice>
+            <mc:Fallback><shape id="fallback"/></mc:Fallback>
+          </mc:AlternateContent>
+          <mc:AlternateContent>
+            <mc:Choice Requires="v"><vml/></mc:Choice>
+            <mc:Fallback><pic/></mc:Fallback>
+          </mc:AlternateContent>
+        </root>"#;
+        let resolved = resolve_alternate_content(xml.to_string());
+        assert!(resolved.contains(r#"<shape id="choice"/>"#));
+        assert!(!resolved.contains("fallback"));
+        assert!(!resolved.contains("chartex"));
+        assert!(!resolved.contains("<vml/>"));
+        assert!(resolved.contains("<pic/>"));
+    }
+}
@@ -8,32 +8,112 @@ pub mod rtf;
 pub mod docx;
 pub mod docx_layout;
 pub mod xlsx;
+pub mod xls;
 pub mod pptx;
 pub mod pptx_layout;
+pub mod ppt;
 pub mod chart;
 pub mod smartart;
 pub mod common_stubs;
 pub mod odt;
 pub mod epub;
+pub mod html;
 pub mod odp;
+pub mod assets;
+pub mod embedded_fonts;
+pub mod limits;
+pub mod transitions;
+pub mod ole;
+pub mod deferred_assets;
+pub mod markup_compat;
+pub mod core_properties;
 
-use crate::converter::{ConvertError, Document, DocumentConverter};
+use crate::converter::{
+    detect_format, ConversionLimits, ConvertError, Document, DocumentConverter, FeatureNote, Page, TextFlowOptions,
+};
 
 /// ファイル拡張子に基づいて適切なコンバーターで変換を実行
 pub fn convert_by_extension(ext: &str, data: &[u8]) -> Result<Document, ConvertError> {
+    convert_by_extension_with_options(ext, data, &TextFlowOptions::default())
+}
+
+/// テキストフロー系（TXT / CSV）のオプションを指定して変換を実行
+/// リソース上限は `ConversionLimits::default()` を適用します。
+pub fn convert_by_extension_with_options(
+    ext: &str,
+    data: &[u8],
+    options: &TextFlowOptions,
+) -> Result<Document, ConvertError> {
+    convert_by_extension_with_limits(ext, data, options, &ConversionLimits::default())
+}
+
+/// オプションとリソース上限を指定して変換を実行
+/// ページ数・画像サイズ・展開量が上限を超えた場合はエラーを返します。
+pub fn convert_by_extension_with_limits(
+    ext: &str,
+    data: &[u8],
+    options: &TextFlowOptions,
+    limits: &ConversionLimits,
+) -> Result<Document, ConvertError> {
+    limits::check_archive(ext, data, limits)?;
+    let guard = limits::DecompressionGuard::new(limits.max_total_decompressed_bytes);
+    let result = convert_unchecked(ext, data, options).map(|mut doc| {
+        // テキストのみのモードでは専用の処理が無いフォーマットの図形・画像もここで取り除く
+        if options.text_only {
+            doc.pages.iter_mut().for_each(Page::retain_text);
+        } else {
+            doc.embedded_fonts = embedded_fonts::extract_embedded_fonts(ext, data);
+        }
+        doc
+    });
+    if guard.exceeded() {
+        return Err(limits::total_exceeded_error(&ext.to_uppercase(), limits));
+    }
+    let doc = result?;
+    limits::check_pages(ext, &doc, limits)?;
+    Ok(doc)
+}
+
+/// 拡張子に対応するコンバーターで変換（上限の検査なし）
+fn convert_unchecked(ext: &str, data: &[u8], options: &TextFlowOptions) -> Result<Document, ConvertError> {
     match ext.to_lowercase().as_str() {
-        "txt" => txt::TxtConverter::new().convert(data),
-        "csv" => csv_conv::CsvConverter::new().convert(data),
+        "txt" => txt::TxtConverter::with_options(options.clone()).convert(data),
+        "csv" => csv_conv::CsvConverter::with_options(options.clone()).convert(data),
+        "tsv" => csv_conv::CsvConverter::with_options(options.clone()).with_delimiter(b'\t').convert(data),
+        "psv" => csv_conv::CsvConverter::with_options(options.clone()).with_delimiter(b'|').convert(data),
         "rtf" => rtf::RtfConverter::new().convert(data),
-        "docx" => docx_layout::DocxConverter::new().convert(data),
-        "xlsx" | "xls" | "ods" => xlsx::XlsxConverter::new().convert(data),
+        "docx" => docx_layout::DocxConverter::new()
+            .with_render_comments(options.render_comments)
+            .with_tracked_changes(options.tracked_changes)
+            .with_hyperlink_style(options.hyperlink_style)
+            .with_text_only(options.text_only)
+            .with_supplied_assets(options.supplied_assets.clone())
+            .convert(data),
+        "xlsx" | "ods" => xlsx::XlsxConverter::new()
+            .with_gridlines(options.show_gridlines)
+            .with_headers(options.show_headers)
+            .with_layout_font(options.layout_font.clone())
+            .convert(data),
+        "xls" => xls::XlsConverter::new()
+            .with_gridlines(options.show_gridlines)
+            .with_headers(options.show_headers)
+            .with_layout_font(options.layout_font.clone())
+            .convert(data),
         "doc" => common_stubs::StubConverter::new("DOC", &["doc"]).convert(data),
         "odt" => odt::OdtConverter::new().convert(data),
         "epub" => epub::EpubConverter::new().convert(data),
+        "html" | "htm" => html::HtmlConverter::with_options(options.clone()).convert(data),
         "xps" => common_stubs::StubConverter::new("XPS", &["xps"]).convert(data),
         "djvu" | "djv" => common_stubs::StubConverter::new("DjVu", &["djvu", "djv"]).convert(data),
-        "ppt" => common_stubs::StubConverter::new("PPT", &["ppt"]).convert(data),
-        "pptx" => pptx_layout::PptxConverter::new().convert(data),
+        "ppt" => ppt::PptConverter::new().convert(data),
+        "pptx" => pptx_layout::PptxConverter::with_unknown_geometry_policy(options.unknown_geometry_policy)
+            .with_skip_empty_pages(options.skip_empty_pages)
+            .with_skip_hidden_slides(options.skip_hidden_slides)
+            .with_merge_text_runs(options.merge_text_runs)
+            .with_hyperlink_style(options.hyperlink_style)
+            .with_text_only(options.text_only)
+            .with_supplied_assets(options.supplied_assets.clone())
+            .convert(data),
         "odp" => odp::OdpConverter::new().convert(data),
         _ => Err(ConvertError::new(
             "unknown",
@@ -42,16 +122,64 @@ pub fn convert_by_extension(ext: &str, data: &[u8]) -> Result<Document, ConvertE
     }
 }
 
+/// 複数ファイルを順番に変換し、全ページを1つのドキュメントに結合
+/// ページサイズは元ファイルごとに維持されます。メタデータは先頭ファイルのものを使用します。
+pub fn convert_batch(files: &[(&str, &[u8])]) -> Result<Document, ConvertError> {
+    convert_batch_with_options(files, &TextFlowOptions::default())
+}
+
+/// テキストフロー系のオプションを指定して複数ファイルを1つのドキュメントに結合
+pub fn convert_batch_with_options(
+    files: &[(&str, &[u8])],
+    options: &TextFlowOptions,
+) -> Result<Document, ConvertError> {
+    convert_batch_with_limits(files, options, &ConversionLimits::default())
+}
+
+/// オプションとリソース上限を指定して複数ファイルを1つのドキュメントに結合
+/// 上限はファイルごとに適用し、結合後の合計ページ数も `max_pages` 以内に制限します。
+pub fn convert_batch_with_limits(
+    files: &[(&str, &[u8])],
+    options: &TextFlowOptions,
+    limits: &ConversionLimits,
+) -> Result<Document, ConvertError> {
+    let mut combined: Option<Document> = None;
+    for (filename, data) in files {
+        let ext = detect_format(filename).ok_or_else(|| {
+            ConvertError::new(
+                "batch",
+                &format!("サポートされていないファイル形式です: {}", filename),
+            )
+        })?;
+        let doc = convert_by_extension_with_limits(ext, data, options, limits)?;
+        match combined.as_mut() {
+            Some(c) => {
+                let offset = c.pages.len();
+                c.feature_notes.extend(doc.feature_notes.into_iter().map(|note| FeatureNote { page: note.page + offset, ..note }));
+                c.pages.extend(doc.pages);
+                c.embedded_fonts.extend(doc.embedded_fonts);
+                c.warnings.extend(doc.warnings);
+                limits::check_pages("batch", c, limits)?;
+            }
+            None => combined = Some(doc),
+        }
+    }
+    combined.ok_or_else(|| ConvertError::new("batch", "変換するファイルがありません"))
+}
+
 /// サポートされているフォーマットの一覧を取得
 pub fn supported_formats() -> Vec<(&'static str, &'static [&'static str])> {
     vec![
         ("テキスト", &["txt"][..]),
         ("CSV", &["csv"][..]),
+        ("TSV (タブ区切り)", &["tsv"][..]),
+        ("PSV (パイプ区切り)", &["psv"][..]),
         ("RTF", &["rtf"][..]),
         ("DOCX (Microsoft Word)", &["docx"][..]),
         ("DOC (Microsoft Word 旧形式)", &["doc"][..]),
         ("ODT (OpenDocument Text)", &["odt"][..]),
         ("EPUB (電子書籍)", &["epub"][..]),
+        ("HTML", &["html", "htm"][..]),
         ("XPS", &["xps"][..]),
         ("DjVu", &["djvu", "djv"][..]),
         ("XLSX (Microsoft Excel)", &["xlsx"][..]),
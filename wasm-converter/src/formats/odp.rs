@@ -74,13 +74,12 @@ fn read_zip_entry(
     archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
     path: &str,
 ) -> Result<String, ConvertError> {
-    use std::io::Read;
     let mut file = archive
         .by_name(path)
         .map_err(|e| ConvertError::new("ODP", &format!("{}が見つかりません: {}", path, e)))?;
 
     let mut content = String::new();
-    file.read_to_string(&mut content)
+    super::limits::read_limited_string(&mut file, &mut content)
         .map_err(|e| ConvertError::new("ODP", &format!("{}の読み込みエラー: {}", path, e)))?;
 
     Ok(content)
@@ -245,6 +244,7 @@ fn render_slide_to_page(slide_number: usize, slide: &OdpSlide) -> Page {
         stroke: Some(Color::rgb(200, 200, 200)),
         stroke_width: 1.0,
         rotation_deg: 0.0,
+        stroke_style: Default::default(),
     });
 
     let mut y = margin + 40.0;
@@ -0,0 +1,51 @@
+// formats/ole.rs - OLE埋め込みオブジェクトの代替表示モジュール
+//
+// PPTX / DOCX に埋め込まれたOLEオブジェクト（Excelシート・数式など）は中身を描画できないため、
+// 保存されているプレビュー画像で表示するか、ProgIDを記したプレースホルダーの矩形で代替します。
+
+use crate::converter::{Color, FontStyle, PageElement, TextAlign};
+
+/// プレビュー画像で表示したOLEオブジェクトの警告の接頭辞（後ろにProgID）
+pub(crate) const OLE_PREVIEW_WARNING: &str = "OLEオブジェクトをプレビュー画像で表示しました: ";
+/// プレースホルダーで代替したOLEオブジェクトの警告の接頭辞（後ろにProgID）
+pub(crate) const OLE_PLACEHOLDER_WARNING: &str = "OLEオブジェクトを描画できないためプレースホルダーで代替しました: ";
+
+/// プレビュー画像として描画できるか（PNG / JPEG のみ。EMF / WMF は描画できない）
+pub(crate) fn is_renderable_preview(data: &[u8]) -> bool {
+    crate::converter::image_pixel_size(data).is_some()
+}
+
+/// 警告に記録するOLEオブジェクトの名前（ProgIDが無い場合は "unknown"）
+pub(crate) fn program_name(prog_id: &str) -> &str {
+    if prog_id.is_empty() { "unknown" } else { prog_id }
+}
+
+/// プレビューの無いOLEオブジェクトの代わりに描画する、ProgID付きの灰色の矩形
+pub(crate) fn placeholder_elements(x: f64, y: f64, width: f64, height: f64, prog_id: &str) -> Vec<PageElement> {
+    vec![
+        PageElement::Rect {
+            x,
+            y,
+            width,
+            height,
+            fill: Some(Color::rgb(230, 230, 230)),
+            stroke: Some(Color::rgb(180, 180, 180)),
+            stroke_width: 0.5,
+            rotation_deg: 0.0,
+            stroke_style: Default::default(),
+        },
+        PageElement::Text {
+            x: x + 4.0,
+            y: y + height / 2.0 - 6.0,
+            width: (width - 8.0).max(0.0),
+            text: format!("[OLE: {}]", program_name(prog_id)),
+            style: FontStyle {
+                font_size: 10.0,
+                color: Color::rgb(150, 150, 150),
+                italic: true,
+                ..FontStyle::default()
+            },
+            align: TextAlign::Center,
+        },
+    ]
+}
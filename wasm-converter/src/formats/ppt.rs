@@ -0,0 +1,340 @@
+// formats/ppt.rs - PPT（PowerPoint 97-2003 バイナリ）変換モジュール
+//
+// OLE複合ファイル（CFB）の "PowerPoint Document" ストリームのレコードを走査し、
+// TextCharsAtom / TextBytesAtom のテキストをスライドごとに抽出します。
+// 図形・書式は再現せず、テキストのみを配置します。
+
+use crate::converter::{
+    ConvertError, Document, DocumentConverter, FontStyle, Metadata, Page, PageElement, TextAlign,
+};
+
+/// DocumentAtom（スライドサイズを含む）
+const RT_DOCUMENT_ATOM: u16 = 0x03E9;
+/// Slide コンテナ
+const RT_SLIDE: u16 = 0x03EE;
+/// Notes コンテナ（ノートページ）
+const RT_NOTES: u16 = 0x03F0;
+/// SlidePersistAtom（SlideListWithText 内でスライドの開始を示す）
+const RT_SLIDE_PERSIST_ATOM: u16 = 0x03F3;
+/// MainMaster コンテナ（スライドマスター）
+const RT_MAIN_MASTER: u16 = 0x03F8;
+/// TextHeaderAtom（続くテキストの種類）
+const RT_TEXT_HEADER_ATOM: u16 = 0x0F9F;
+/// TextCharsAtom（UTF-16LE のテキスト）
+const RT_TEXT_CHARS_ATOM: u16 = 0x0FA0;
+/// TextBytesAtom（各バイトがUTF-16の下位バイトのテキスト）
+const RT_TEXT_BYTES_ATOM: u16 = 0x0FA8;
+/// Handout コンテナ（配布資料マスター）
+const RT_HANDOUT: u16 = 0x0FC9;
+/// SlideListWithText コンテナ
+const RT_SLIDE_LIST_WITH_TEXT: u16 = 0x0FF0;
+
+/// TextHeaderAtom のテキスト種類: タイトル・中央揃えタイトル
+const TEXT_TYPE_TITLE: u32 = 0;
+const TEXT_TYPE_CENTER_TITLE: u32 = 6;
+
+/// レコードの入れ子の上限（不正なファイルによる深い再帰の防止）
+const MAX_RECORD_DEPTH: usize = 32;
+/// マスター単位（1/576インチ）からポイントへの換算
+const MASTER_UNITS_PER_POINT: f64 = 8.0;
+/// DocumentAtom が無い場合のスライドサイズ（10×7.5インチ）
+const DEFAULT_SLIDE_SIZE: (f64, f64) = (720.0, 540.0);
+
+/// PPTコンバーター
+pub struct PptConverter;
+
+impl PptConverter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DocumentConverter for PptConverter {
+    fn convert(&self, input: &[u8]) -> Result<Document, ConvertError> {
+        let mut compound = cfb::CompoundFile::open(std::io::Cursor::new(input))
+            .map_err(|e| ConvertError::new("PPT", &format!("OLE複合ファイルを開けません: {}", e)))?;
+        let stream = compound.open_stream("/PowerPoint Document").map_err(|e| {
+            ConvertError::new("PPT", &format!("PowerPoint Documentストリームが見つかりません: {}", e))
+        })?;
+        let mut data = Vec::new();
+        super::limits::read_limited(stream, &mut data)
+            .map_err(|e| ConvertError::new("PPT", &format!("ストリームの読み込みエラー: {}", e)))?;
+
+        let mut extract = PptExtract::default();
+        walk_records(&data, Scope::Document, 0, &mut extract);
+        let (width, height) = extract.slide_size.unwrap_or(DEFAULT_SLIDE_SIZE);
+
+        let mut doc = Document::new();
+        doc.metadata = Metadata {
+            creator: Some("WASM Document Converter".to_string()),
+            ..Metadata::default()
+        };
+        for texts in extract.into_slides() {
+            doc.pages.push(render_slide_texts(&texts, width, height));
+        }
+        if doc.pages.is_empty() {
+            doc.pages.push(Page { width, height, elements: Vec::new() });
+        }
+        Ok(doc)
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["ppt"]
+    }
+
+    fn format_name(&self) -> &str {
+        "PPT"
+    }
+}
+
+/// スライド上のテキスト（段落単位）
+#[derive(Debug, Clone, PartialEq)]
+struct SlideText {
+    text: String,
+    is_title: bool,
+}
+
+/// テキスト原子レコードの帰属先
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scope {
+    /// どのスライドにも属さない位置
+    Document,
+    /// SlideListWithText（スライド一覧）の中
+    SlideList,
+    /// Slide コンテナの描画（OfficeArt のテキストボックス）の中
+    Slide,
+    /// マスター・ノートなど、抽出しない位置
+    Ignored,
+}
+
+/// ストリームから集めたテキスト
+#[derive(Debug, Default)]
+struct PptExtract {
+    /// DocumentAtom のスライドサイズ（pt）
+    slide_size: Option<(f64, f64)>,
+    /// SlideListWithText のスライドごとのテキスト（スライド順）
+    listed: Vec<Vec<SlideText>>,
+    /// Slide コンテナごとのテキスト（ストリーム内の出現順）
+    drawn: Vec<Vec<SlideText>>,
+    /// スライドを特定できなかったテキスト
+    loose: Vec<SlideText>,
+}
+
+impl PptExtract {
+    /// スライドごとのテキストに整理
+    /// スライド一覧を優先し、一覧にテキストの無いスライドは同数の Slide コンテナから補います
+    /// （PowerPoint 2007以降はテキストを図形側にのみ保存します）。
+    /// スライドを特定できなければ全テキストを1スライドにまとめます。
+    fn into_slides(self) -> Vec<Vec<SlideText>> {
+        if self.listed.is_empty() && self.drawn.is_empty() {
+            return if self.loose.is_empty() { Vec::new() } else { vec![self.loose] };
+        }
+        if self.listed.is_empty() {
+            return self.drawn;
+        }
+        let use_drawn = self.drawn.len() == self.listed.len();
+        let mut drawn = self.drawn.into_iter();
+        self.listed
+            .into_iter()
+            .map(|texts| {
+                let fallback = if use_drawn { drawn.next() } else { None };
+                match fallback {
+                    Some(drawn_texts) if texts.is_empty() => drawn_texts,
+                    _ => texts,
+                }
+            })
+            .collect()
+    }
+
+    fn target(&mut self, scope: Scope) -> Option<&mut Vec<SlideText>> {
+        match scope {
+            Scope::Document => Some(&mut self.loose),
+            Scope::SlideList => self.listed.last_mut(),
+            Scope::Slide => self.drawn.last_mut(),
+            Scope::Ignored => None,
+        }
+    }
+}
+
+/// レコードヘッダー（8バイト）と本体
+struct Record<'a> {
+    /// recVer（下位4ビット）。0xF はコンテナ
+    version: u16,
+    /// recInstance（上位12ビット）
+    instance: u16,
+    rec_type: u16,
+    body: &'a [u8],
+}
+
+/// バイト列を連続するレコードに分割（途中で切れたレコードで打ち切り）
+fn records(data: &[u8]) -> impl Iterator<Item = Record<'_>> {
+    let mut offset = 0usize;
+    std::iter::from_fn(move || {
+        let header = data.get(offset..offset + 8)?;
+        let ver_instance = u16::from_le_bytes([header[0], header[1]]);
+        let rec_type = u16::from_le_bytes([header[2], header[3]]);
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let body = data.get(offset + 8..(offset + 8).checked_add(len)?)?;
+        offset += 8 + len;
+        Some(Record { version: ver_instance & 0x000F, instance: ver_instance >> 4, rec_type, body })
+    })
+}
+
+/// レコードを再帰的に走査してテキストを集める
+fn walk_records(data: &[u8], scope: Scope, depth: usize, extract: &mut PptExtract) {
+    if depth > MAX_RECORD_DEPTH {
+        return;
+    }
+    // TextHeaderAtom は続くテキスト原子の兄弟レコードとして現れる
+    let mut is_title = false;
+    for record in records(data) {
+        match record.rec_type {
+            RT_SLIDE_LIST_WITH_TEXT => {
+                // instance 0 がスライド、1 はマスター、2 はノート
+                let scope = if record.instance == 0 && scope != Scope::Ignored {
+                    Scope::SlideList
+                } else {
+                    Scope::Ignored
+                };
+                walk_records(record.body, scope, depth + 1, extract);
+            }
+            RT_SLIDE if scope != Scope::Ignored => {
+                extract.drawn.push(Vec::new());
+                walk_records(record.body, Scope::Slide, depth + 1, extract);
+            }
+            RT_MAIN_MASTER | RT_NOTES | RT_HANDOUT => {}
+            RT_SLIDE_PERSIST_ATOM if scope == Scope::SlideList => extract.listed.push(Vec::new()),
+            RT_DOCUMENT_ATOM if record.body.len() >= 8 => {
+                let value = |i: usize| {
+                    let b = &record.body[i..i + 4];
+                    i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64 / MASTER_UNITS_PER_POINT
+                };
+                let (width, height) = (value(0), value(4));
+                if width > 0.0 && height > 0.0 {
+                    extract.slide_size = Some((width, height));
+                }
+            }
+            RT_TEXT_HEADER_ATOM if record.body.len() >= 4 => {
+                let b = record.body;
+                let text_type = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+                is_title = matches!(text_type, TEXT_TYPE_TITLE | TEXT_TYPE_CENTER_TITLE);
+            }
+            RT_TEXT_CHARS_ATOM | RT_TEXT_BYTES_ATOM => {
+                let text = decode_text_atom(record.rec_type, record.body);
+                if let Some(target) = extract.target(scope) {
+                    target.extend(
+                        split_paragraphs(&text).map(|text| SlideText { text, is_title }),
+                    );
+                }
+            }
+            _ if record.version == 0x000F => walk_records(record.body, scope, depth + 1, extract),
+            _ => {}
+        }
+    }
+}
+
+/// テキスト原子レコードの本体を文字列に変換
+fn decode_text_atom(rec_type: u16, body: &[u8]) -> String {
+    if rec_type == RT_TEXT_BYTES_ATOM {
+        return body.iter().map(|&b| b as char).collect();
+    }
+    let units: Vec<u16> = body
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// 段落区切り（CR）と行区切り（垂直タブ）で分割し、制御文字を除いた空でない行を返す
+fn split_paragraphs(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(['\r', '\n', '\u{000B}'])
+        .map(|line| line.chars().filter(|c| !c.is_control() || *c == '\t').collect::<String>())
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+}
+
+/// スライドのテキストをページに配置
+/// タイトルは上部中央に大きく、本文はその下に左揃えで並べます。
+fn render_slide_texts(texts: &[SlideText], width: f64, height: f64) -> Page {
+    let mut page = Page { width, height, elements: Vec::new() };
+    let margin = width * 0.07;
+    let usable_width = width - margin * 2.0;
+    let mut y = margin;
+
+    for slide_text in texts {
+        let (font_size, bold, align, indent) = if slide_text.is_title {
+            (28.0, true, TextAlign::Center, 0.0)
+        } else {
+            (18.0, false, TextAlign::Left, 20.0)
+        };
+        if y + font_size > height - margin {
+            break;
+        }
+        page.elements.push(PageElement::Text {
+            x: margin + indent,
+            y,
+            width: usable_width - indent,
+            text: slide_text.text.clone(),
+            style: FontStyle { font_size, bold, ..FontStyle::default() },
+            align,
+        });
+        y += font_size * if slide_text.is_title { 1.8 } else { 1.5 };
+    }
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(ver: u16, instance: u16, rec_type: u16, body: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&(ver | (instance << 4)).to_le_bytes());
+        out.extend_from_slice(&rec_type.to_le_bytes());
+        out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn chars_atom(text: &str) -> Vec<u8> {
+        let body: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        record(0, 0, RT_TEXT_CHARS_ATOM, &body)
+    }
+
+    #[test]
+    fn test_slide_container_text_fills_empty_slide_list() {
+        // スライド一覧にはテキストが無く、Slide コンテナの図形側にのみテキストがある
+        let list = [
+            record(0, 0, RT_SLIDE_PERSIST_ATOM, &[0; 20]),
+            record(0, 0, RT_SLIDE_PERSIST_ATOM, &[0; 20]),
+        ]
+        .concat();
+        let slide = |text: &str| {
+            let textbox = [record(0, 0, RT_TEXT_HEADER_ATOM, &1u32.to_le_bytes()), chars_atom(text)].concat();
+            record(0xF, 0, RT_SLIDE, &record(0xF, 0, 0xF00D, &textbox))
+        };
+        let master = record(0xF, 0, RT_MAIN_MASTER, &chars_atom("マスターのテキスト"));
+        let stream = [
+            record(0xF, 0, RT_SLIDE_LIST_WITH_TEXT, &list),
+            master,
+            slide("一枚目\u{000B}改行"),
+            slide("二枚目"),
+        ]
+        .concat();
+
+        let mut extract = PptExtract::default();
+        walk_records(&stream, Scope::Document, 0, &mut extract);
+        let slides: Vec<Vec<String>> = extract
+            .into_slides()
+            .into_iter()
+            .map(|texts| texts.into_iter().map(|t| t.text).collect())
+            .collect();
+        assert_eq!(slides, vec![vec!["一枚目", "改行"], vec!["二枚目"]]);
+    }
+
+    #[test]
+    fn test_decode_text_bytes_atom() {
+        assert_eq!(decode_text_atom(RT_TEXT_BYTES_ATOM, b"Caf\xE9"), "Café");
+        assert_eq!(decode_text_atom(RT_TEXT_CHARS_ATOM, &[0x42, 0x30]), "あ");
+    }
+}
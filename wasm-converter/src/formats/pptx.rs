@@ -4,7 +4,7 @@
 // 各スライドのテキストと基本的な構造を抽出してドキュメントモデルに変換します。
 
 use crate::converter::{
-    Color, ConvertError, Document, DocumentConverter, FontStyle, Metadata, Page, PageElement,
+    Color, ConvertError, Document, DocumentConverter, FontStyle, Page, PageElement,
     TextAlign,
 };
 
@@ -34,7 +34,7 @@ impl DocumentConverter for PptxConverter {
         }
 
         // メタデータの取得を試みる
-        let metadata = read_pptx_metadata(&mut archive);
+        let metadata = super::core_properties::read_core_properties(&mut archive);
 
         let mut doc = Document::new();
         doc.metadata = metadata;
@@ -97,13 +97,12 @@ fn read_zip_entry(
     archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
     path: &str,
 ) -> Result<String, ConvertError> {
-    use std::io::Read;
     let mut file = archive
         .by_name(path)
         .map_err(|e| ConvertError::new("PPTX", &format!("{}が見つかりません: {}", path, e)))?;
 
     let mut content = String::new();
-    file.read_to_string(&mut content)
+    super::limits::read_limited_string(&mut file, &mut content)
         .map_err(|e| ConvertError::new("PPTX", &format!("{}の読み込みエラー: {}", path, e)))?;
 
     Ok(content)
@@ -221,6 +220,7 @@ fn render_slide_to_page(slide_number: usize, texts: &[SlideText]) -> Page {
         stroke: Some(Color::rgb(200, 200, 200)),
         stroke_width: 1.0,
         rotation_deg: 0.0,
+        stroke_style: Default::default(),
     });
 
     let mut y = margin + 40.0;
@@ -283,50 +283,6 @@ fn render_slide_to_page(slide_number: usize, texts: &[SlideText]) -> Page {
     page
 }
 
-/// PPTXメタデータを読み取る
-fn read_pptx_metadata(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>) -> Metadata {
-    let mut metadata = Metadata::default();
-
-    if let Ok(core_xml) = read_zip_entry(archive, "docProps/core.xml") {
-        let mut reader = quick_xml::Reader::from_str(&core_xml);
-        let mut buf = Vec::new();
-        let mut current_tag = String::new();
-        let mut in_tag = false;
-
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(quick_xml::events::Event::Start(ref e)) => {
-                    let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
-                    current_tag = name;
-                    in_tag = true;
-                }
-                Ok(quick_xml::events::Event::Text(ref e)) => {
-                    if in_tag {
-                        if let Ok(text) = e.unescape() {
-                            match current_tag.as_str() {
-                                "title" => metadata.title = Some(text.to_string()),
-                                "creator" => metadata.author = Some(text.to_string()),
-                                "subject" => metadata.subject = Some(text.to_string()),
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-                Ok(quick_xml::events::Event::End(_)) => {
-                    in_tag = false;
-                }
-                Ok(quick_xml::events::Event::Eof) => break,
-                Err(_) => break,
-                _ => {}
-            }
-            buf.clear();
-        }
-    }
-
-    metadata.creator = Some("WASM Document Converter".to_string());
-    metadata
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
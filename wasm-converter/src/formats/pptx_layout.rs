@@ -5,10 +5,14 @@
 // Officeソフトで開いてPDF化するのと同等の出力を目指します。
 
 use crate::converter::{
-    Color, ConvertError, Document, DocumentConverter, FontStyle, GradientStop, GradientType,
-    Metadata, Page, PageElement, PathCommand, TextAlign,
+    Color, CompoundLine, ConvertError, DashPattern, Document, DocumentConverter, FeatureSupport, FontStyle, GradientStop, GradientType, HyperlinkStyle,
+    LineCap, LineJoin, Page, PageElement, PathCommand, StrokeStyle, TextAlign, UnderlineKind, UnderlineStyle,
+    UnknownGeometryPolicy,
 };
 
+use super::deferred_assets::{self, DeferredAsset};
+use super::ole;
+
 /// Diagnostic warning macro - only active when diagnostics feature is enabled
 #[cfg(all(target_arch = "wasm32", feature = "diagnostics"))]
 macro_rules! diag_warn {
@@ -26,15 +30,84 @@ macro_rules! diag_warn {
 /// 1インチ = 914400 EMU = 72 pt
 const EMU_PER_PT: f64 = 914400.0 / 72.0;
 
+/// 埋め込みSVG画像をPNGにラスタライズする解像度（DPI）
+const SVG_RASTER_DPI: f64 = 300.0;
+
 /// 3D効果の押し出し深度（ポイント単位）
 const SHAPE_3D_EXTRUSION_DEPTH: f64 = 6.0;
 
+/// 影のぼかしを近似する重ね描きの回数
+const SHADOW_BLUR_PASSES: usize = 4;
+
 /// PPTXコンバーター
-pub struct PptxConverter;
+pub struct PptxConverter {
+    unknown_geometry: UnknownGeometryPolicy,
+    /// 背景以外に要素の無いスライドを出力しない
+    skip_empty_pages: bool,
+    /// 非表示スライド（`show="0"`）を出力しない
+    skip_hidden_slides: bool,
+    /// 段落内で同じ書式が隣接するランをまとめる
+    merge_text_runs: bool,
+    /// リンクの付いたランの見た目
+    hyperlink_style: HyperlinkStyle,
+    /// テキストと表だけを出力する（画像・背景・グラフ・OLEの読み込みを省く）
+    text_only: bool,
+    /// パッケージ内に無い画像の代わりに使うデータ（パス → バイト列）
+    supplied_assets: Vec<(String, Vec<u8>)>,
+}
 
 impl PptxConverter {
     pub fn new() -> Self {
-        Self
+        Self::with_unknown_geometry_policy(UnknownGeometryPolicy::default())
+    }
+
+    /// 未実装のプリセット図形の描画方法を指定してコンバーターを作成
+    pub fn with_unknown_geometry_policy(policy: UnknownGeometryPolicy) -> Self {
+        Self {
+            unknown_geometry: policy,
+            skip_empty_pages: false,
+            skip_hidden_slides: false,
+            merge_text_runs: true,
+            hyperlink_style: HyperlinkStyle::default(),
+            text_only: false,
+            supplied_assets: Vec::new(),
+        }
+    }
+
+    /// 背景以外に要素の無いスライドをページとして出力するかどうかを指定
+    pub fn with_skip_empty_pages(mut self, skip: bool) -> Self {
+        self.skip_empty_pages = skip;
+        self
+    }
+
+    /// 非表示スライドを（スライドショーと同様に）出力から除くかどうかを指定
+    pub fn with_skip_hidden_slides(mut self, skip: bool) -> Self {
+        self.skip_hidden_slides = skip;
+        self
+    }
+
+    /// 段落内で同じ書式が隣接するランを1つのテキストにまとめるかどうかを指定（既定: まとめる）
+    pub fn with_merge_text_runs(mut self, merge: bool) -> Self {
+        self.merge_text_runs = merge;
+        self
+    }
+
+    /// リンク（a:hlinkClick）の付いたランの見た目を指定（既定: 元の書式のまま）
+    pub fn with_hyperlink_style(mut self, style: HyperlinkStyle) -> Self {
+        self.hyperlink_style = style;
+        self
+    }
+
+    /// テキストと表だけを出力するかどうかを指定（画像の読み込み・背景・グラフ・OLEを省く）
+    pub fn with_text_only(mut self, text_only: bool) -> Self {
+        self.text_only = text_only;
+        self
+    }
+
+    /// 読み込めない画像の代わりに使うデータ（`ppt/media/image1.png` などのパス → バイト列）を指定
+    pub fn with_supplied_assets(mut self, assets: Vec<(String, Vec<u8>)>) -> Self {
+        self.supplied_assets = assets;
+        self
     }
 }
 
@@ -46,6 +119,7 @@ impl DocumentConverter for PptxConverter {
 
         // テーマカラーを読み込む
         let theme_colors = read_theme_colors(&mut archive);
+        let theme_fonts = read_theme_fonts(&mut archive);
 
         // スライドサイズをpresentation.xmlから取得
         let slide_size = read_slide_size(&mut archive);
@@ -56,16 +130,29 @@ impl DocumentConverter for PptxConverter {
             return Err(ConvertError::new("PPTX", "スライドが見つかりません"));
         }
 
+        // presentation.xml の sldIdLst で非表示とされたスライド
+        let hidden_slide_paths = if self.skip_hidden_slides {
+            read_hidden_slide_paths(&mut archive)
+        } else {
+            Vec::new()
+        };
+
         // メタデータ
-        let metadata = read_pptx_metadata(&mut archive);
+        let metadata = super::core_properties::read_core_properties(&mut archive);
 
         let mut doc = Document::new();
         doc.metadata = metadata;
 
         // 各スライドを処理
-        for slide_path in &slide_paths {
+        let now = FieldDate::now();
+        for (slide_index, slide_path) in slide_paths.iter().enumerate() {
             let slide_xml = read_zip_entry_string(&mut archive, slide_path)?;
 
+            // 非表示スライドは指定があれば省く（スライド番号は元の番号のまま）
+            if self.skip_hidden_slides && (hidden_slide_paths.contains(slide_path) || is_hidden_slide(&slide_xml)) {
+                continue;
+            }
+
             // スライドのリレーションシップを読み込む（画像参照解決用）
             let rels_path = slide_path
                 .replace("ppt/slides/", "ppt/slides/_rels/")
@@ -73,15 +160,48 @@ impl DocumentConverter for PptxConverter {
             let rels = read_zip_entry_string(&mut archive, &rels_path).ok();
 
             // XMLからシェイプを解析（グループシェイプも含む）
-            let shapes = parse_slide_shapes(&slide_xml, &theme_colors);
+            let mut shapes = parse_slide_shapes(&slide_xml, &theme_colors);
+
+            // 空のプレースホルダーにスライドレイアウトの既定テキストを補う
+            let layout_shapes = read_slide_layout_shapes(&rels, &mut archive, &theme_colors);
+            apply_layout_placeholders(&mut shapes, &layout_shapes);
+
+            // スライド番号・日付フィールドを計算した値にする
+            resolve_text_fields(&mut shapes, slide_index + 1, &now);
+            resolve_theme_fonts(&mut shapes, &theme_fonts);
+            apply_hyperlink_style(&mut shapes, self.hyperlink_style, theme_colors.hlink);
+
+            // 本文の箇条書き記号とインデントをマスターのレベル別指定で補う
+            let body_style = read_master_body_style(&rels, &mut archive, &theme_colors);
+            apply_master_body_style(&mut shapes, &body_style);
+
+            if self.merge_text_runs {
+                merge_adjacent_runs(&mut shapes);
+            }
+
+            // テキストのみのモードでは画像・背景・グラフ・OLEを読み込まずにテキストと表だけを残す
+            if self.text_only {
+                shapes.retain(|shape| !matches!(shape.content, ShapeContent::Image { .. }));
+                let mut page = render_slide_page(&shapes, &slide_size, None, self.unknown_geometry, &mut Vec::new());
+                page.elements.extend(detect_and_render_smartart(&slide_xml, &rels, &mut archive, &slide_size));
+                page.elements.extend(detect_and_render_tables(&slide_xml, &theme_colors));
+                page.retain_text();
+                if self.skip_empty_pages && page.elements.iter().all(|el| matches!(el, PageElement::Text { text, .. } if text.is_empty())) {
+                    continue;
+                }
+                doc.pages.push(page);
+                continue;
+            }
 
             // スライド背景を解析（画像・グラデーション含む）
             let bg = parse_slide_background_full(&slide_xml, &rels, &mut archive, &theme_colors);
 
-            // 画像データを解決
+            // 画像データを解決（読み込めない画像は遅延アセットとして警告に記録）
+            let mut slide_warnings = Vec::new();
             let mut resolved_shapes = Vec::new();
             for shape in shapes {
-                let resolved = resolve_shape_images(shape, &rels, &mut archive);
+                let resolved =
+                    resolve_shape_images(shape, &rels, &mut archive, &self.supplied_assets, &mut slide_warnings);
                 resolved_shapes.push(resolved);
             }
 
@@ -98,7 +218,12 @@ impl DocumentConverter for PptxConverter {
             // テーブルを検出して描画要素を収集
             let table_elements = detect_and_render_tables(&slide_xml, &theme_colors);
 
-            let mut page = render_slide_page(&resolved_shapes, &slide_size, bg.as_ref());
+            // OLEオブジェクトはプレビュー画像かプレースホルダーで代替
+            let ole_elements = detect_and_render_ole_objects(&slide_xml, &rels, &mut archive, &mut slide_warnings);
+
+            let mut page = render_slide_page(
+                &resolved_shapes, &slide_size, bg.as_ref(), self.unknown_geometry, &mut slide_warnings,
+            );
 
             // チャート要素を追加
             page.elements.extend(chart_elements);
@@ -109,6 +234,30 @@ impl DocumentConverter for PptxConverter {
             // テーブル要素を追加
             page.elements.extend(table_elements);
 
+            // OLEオブジェクトの代替表示を追加
+            page.elements.extend(ole_elements);
+
+            // 警告は文書全体で重複を除いて残し、変換レポート用にページごとの再現度も記録する
+            let page_number = doc.pages.len() + 1;
+            for warning in slide_warnings {
+                if let Some((feature, support)) = classify_slide_warning(&warning, self.unknown_geometry) {
+                    doc.note_feature(page_number, &feature, support);
+                }
+                if !doc.warnings.contains(&warning) {
+                    doc.warnings.push(warning);
+                }
+            }
+
+            // 背景だけのスライドは指定があれば省く（既定では元のスライド数を保つ。空の段落の空行は数えない）
+            let content_count = page
+                .elements
+                .iter()
+                .filter(|el| !matches!(el, PageElement::Text { text, .. } if text.is_empty()))
+                .count();
+            if self.skip_empty_pages && content_count <= bg.is_some() as usize {
+                doc.feature_notes.retain(|note| note.page != page_number);
+                continue;
+            }
             doc.pages.push(page);
         }
 
@@ -128,6 +277,33 @@ impl DocumentConverter for PptxConverter {
     }
 }
 
+/// 未実装のプリセット図形の警告の接頭辞（後ろに図形名）
+const UNKNOWN_GEOMETRY_WARNING: &str = "未実装のプリセット図形です: ";
+/// 未対応の WordArt 変形の警告の接頭辞（後ろにプリセット名）
+const UNSUPPORTED_WARP_WARNING: &str = "未対応のWordArt変形のため直線で配置しました: ";
+
+/// スライド描画時の警告を変換レポートの (機能名, 再現度) に分類
+fn classify_slide_warning(warning: &str, unknown_geometry: UnknownGeometryPolicy) -> Option<(String, FeatureSupport)> {
+    if let Some(name) = warning.strip_prefix(UNKNOWN_GEOMETRY_WARNING) {
+        let support = match unknown_geometry {
+            UnknownGeometryPolicy::Skip => FeatureSupport::Dropped,
+            UnknownGeometryPolicy::BoundingRect | UnknownGeometryPolicy::Ellipse => FeatureSupport::Approximated,
+        };
+        return Some((format!("preset geometry: {}", name), support));
+    }
+    if let Some(prog_id) = warning.strip_prefix(ole::OLE_PREVIEW_WARNING) {
+        return Some((format!("OLE object: {}", prog_id), FeatureSupport::Approximated));
+    }
+    if let Some(prog_id) = warning.strip_prefix(ole::OLE_PLACEHOLDER_WARNING) {
+        return Some((format!("OLE object: {}", prog_id), FeatureSupport::Dropped));
+    }
+    if let Some(asset) = warning.strip_prefix(deferred_assets::DEFERRED_ASSET_WARNING) {
+        return Some((format!("deferred image: {}", asset), FeatureSupport::Dropped));
+    }
+    let preset = warning.strip_prefix(UNSUPPORTED_WARP_WARNING)?;
+    Some((format!("WordArt warp: {}", preset), FeatureSupport::Approximated))
+}
+
 /// スライドサイズ（ポイント単位）
 #[derive(Debug, Clone, Copy)]
 struct SlideSize {
@@ -161,7 +337,7 @@ enum SlideBg {
 
 /// テーマカラーマップ
 #[derive(Debug, Clone)]
-struct ThemeColors {
+pub(super) struct ThemeColors {
     dk1: Color,
     lt1: Color,
     dk2: Color,
@@ -172,7 +348,7 @@ struct ThemeColors {
     accent4: Color,
     accent5: Color,
     accent6: Color,
-    hlink: Color,
+    pub(super) hlink: Color,
     fol_hlink: Color,
 }
 
@@ -215,6 +391,45 @@ impl ThemeColors {
     }
 }
 
+/// テーマのフォントスキーム（a:fontScheme）
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ThemeFonts {
+    /// 見出しのフォント（a:majorFont、`+mj-lt` / `+mj-ea` / `+mj-cs`）
+    major: ScriptTypefaces,
+    /// 本文のフォント（a:minorFont、`+mn-lt` / `+mn-ea` / `+mn-cs`）
+    minor: ScriptTypefaces,
+}
+
+/// 文字種ごとのフォント名（空の typeface は None）
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ScriptTypefaces {
+    latin: Option<String>,
+    east_asian: Option<String>,
+    complex: Option<String>,
+}
+
+impl ThemeFonts {
+    /// ランの typeface を実際のフォント名にする
+    /// `+mn-lt` などのテーマフォントの参照はフォントスキームで置き換え、定義が無ければ None にします。
+    fn resolve(&self, typeface: &str) -> Option<String> {
+        let Some(reference) = typeface.strip_prefix('+') else {
+            return Some(typeface.to_string());
+        };
+        let (group, script) = reference.split_once('-')?;
+        let typefaces = match group {
+            "mj" => &self.major,
+            "mn" => &self.minor,
+            _ => return None,
+        };
+        match script {
+            "lt" => typefaces.latin.clone(),
+            "ea" => typefaces.east_asian.clone(),
+            "cs" => typefaces.complex.clone(),
+            _ => None,
+        }
+    }
+}
+
 /// シェイプ塗りつぶし
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -230,6 +445,110 @@ enum ShapeFill {
     },
 }
 
+/// 画像の塗り（a:blipFill）の配置方法
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BlipFillMode {
+    /// 図形に合わせて引き伸ばす（a:stretch）
+    /// `insets` は a:fillRect の (左, 上, 右, 下) の余白で、図形の幅・高さに対する割合（負の値は図形の外側）。
+    Stretch { insets: (f64, f64, f64, f64) },
+    /// 画像を元の大きさで並べて敷き詰める（a:tile）
+    /// オフセットはpt、倍率は1.0が等倍、`align` は最初のタイルを合わせる図形内の位置（0〜1の割合）。
+    Tile { offset: (f64, f64), scale: (f64, f64), align: (f64, f64) },
+}
+
+impl Default for BlipFillMode {
+    fn default() -> Self {
+        BlipFillMode::Stretch { insets: (0.0, 0.0, 0.0, 0.0) }
+    }
+}
+
+impl BlipFillMode {
+    /// a:fillRect（l / t / r / b は1/1000パーセント）
+    fn stretch_from(e: &quick_xml::events::BytesStart) -> Self {
+        let mut insets = (0.0, 0.0, 0.0, 0.0);
+        for attr in e.attributes().flatten() {
+            let value = String::from_utf8_lossy(&attr.value).parse::<f64>().unwrap_or(0.0) / 100000.0;
+            match attr.key.as_ref() {
+                b"l" => insets.0 = value,
+                b"t" => insets.1 = value,
+                b"r" => insets.2 = value,
+                b"b" => insets.3 = value,
+                _ => {}
+            }
+        }
+        BlipFillMode::Stretch { insets }
+    }
+
+    /// a:tile（tx / ty はEMU、sx / sy は1/1000パーセント、algn は配置の基準位置）
+    fn tile_from(e: &quick_xml::events::BytesStart) -> Self {
+        let (mut offset, mut scale, mut align) = ((0.0, 0.0), (1.0, 1.0), (0.0, 0.0));
+        for attr in e.attributes().flatten() {
+            let value = String::from_utf8_lossy(&attr.value);
+            let number = || value.parse::<f64>().unwrap_or(0.0);
+            match attr.key.as_ref() {
+                b"tx" => offset.0 = number() / EMU_PER_PT,
+                b"ty" => offset.1 = number() / EMU_PER_PT,
+                b"sx" => scale.0 = number() / 100000.0,
+                b"sy" => scale.1 = number() / 100000.0,
+                b"algn" => {
+                    align.0 = if value.ends_with('r') { 1.0 } else if value.ends_with('l') { 0.0 } else { 0.5 };
+                    align.1 = if value.starts_with('b') { 1.0 } else if value.starts_with('t') { 0.0 } else { 0.5 };
+                }
+                _ => {}
+            }
+        }
+        BlipFillMode::Tile { offset, scale, align }
+    }
+}
+
+/// 画像の塗りを敷き詰めるタイル数の上限（極小のタイルで要素数が膨らむのを防ぐ。超える場合は引き伸ばす）
+const MAX_FILL_TILES: usize = 4096;
+
+/// 図形の線（<a:ln>）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Outline {
+    color: Color,
+    width: f64,
+    /// 破線・線端・角（prstDash / cap / miter・round・bevel）
+    style: StrokeStyle,
+    /// 二重線などの複合線（cmpd）
+    compound: CompoundLine,
+}
+
+/// 解析中の <a:ln> の色以外の属性
+#[derive(Debug, Clone, Copy, Default)]
+struct LineProps {
+    /// 線幅（w 属性、ポイント）
+    width: Option<f64>,
+    compound: CompoundLine,
+    style: StrokeStyle,
+}
+
+impl LineProps {
+    /// <a:ln> の w・cap・cmpd 属性を読み込む
+    fn read_attrs(&mut self, e: &quick_xml::events::BytesStart) {
+        for attr in e.attributes().flatten() {
+            let val = String::from_utf8_lossy(&attr.value);
+            match attr.key.as_ref() {
+                b"w" => self.width = val.parse::<f64>().ok().map(|w| w / EMU_PER_PT),
+                b"cap" => self.style.cap = LineCap::from_attr(&val),
+                b"cmpd" => self.compound = CompoundLine::from_attr(&val),
+                _ => {}
+            }
+        }
+    }
+
+    /// 色と既定の線幅から図形の線を作る（w 属性があればそちらを優先）
+    fn outline(&self, color: Color, default_width: f64) -> Outline {
+        Outline {
+            color,
+            width: self.width.unwrap_or(default_width),
+            style: self.style,
+            compound: self.compound,
+        }
+    }
+}
+
 /// シェイプの解析結果
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -240,7 +559,7 @@ struct SlideShape {
     height: f64,
     content: ShapeContent,
     fill: Option<ShapeFill>,
-    outline: Option<(Color, f64)>,
+    outline: Option<Outline>,
     rotation: f64,
     shadow: Option<ShadowEffect>,
     has_3d: bool,
@@ -249,11 +568,441 @@ struct SlideShape {
     custom_path_viewport: Option<(f64, f64)>,
     /// blipFill r:embed on the shape (resolved later)
     fill_image_r_id: Option<String>,
+    /// blipFill の配置方法（a:stretch / a:tile）
+    fill_image_mode: BlipFillMode,
     /// Text body margins in points (from bodyPr lIns, tIns, rIns, bIns)
     text_margin_left: f64,
     text_margin_top: f64,
     text_margin_right: f64,
     text_margin_bottom: f64,
+    /// プレースホルダー（<p:nvPr><p:ph>）
+    placeholder: Option<Placeholder>,
+    /// テキストの自動調整（bodyPr の normAutofit / spAutoFit）
+    autofit: TextAutofit,
+    /// WordArt の変形プリセット（bodyPr の a:prstTxWarp。textNoShape は None）
+    text_warp: Option<String>,
+    /// 所属するグループシェイプの範囲 (x, y, width, height)。はみ出した部分はこの範囲でクリップする
+    clip: Option<(f64, f64, f64, f64)>,
+}
+
+/// グループシェイプの子座標系からスライド座標系への変換（軸ごとの拡大縮小 + 平行移動）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GroupTransform {
+    scale_x: f64,
+    scale_y: f64,
+    offset_x: f64,
+    offset_y: f64,
+}
+
+impl GroupTransform {
+    const IDENTITY: GroupTransform = GroupTransform { scale_x: 1.0, scale_y: 1.0, offset_x: 0.0, offset_y: 0.0 };
+
+    /// grpSpPr の a:off/a:ext（配置先）と a:chOff/a:chExt（子座標系）から変換を求める
+    /// 子座標系の大きさが0の軸は拡大縮小せずに平行移動だけを行います。
+    fn from_xfrm(off: (f64, f64), ext: (f64, f64), ch_off: (f64, f64), ch_ext: (f64, f64)) -> Self {
+        let scale = |ext: f64, ch_ext: f64| if ch_ext > 0.0 { ext / ch_ext } else { 1.0 };
+        let (scale_x, scale_y) = (scale(ext.0, ch_ext.0), scale(ext.1, ch_ext.1));
+        GroupTransform {
+            scale_x,
+            scale_y,
+            offset_x: off.0 - ch_off.0 * scale_x,
+            offset_y: off.1 - ch_off.1 * scale_y,
+        }
+    }
+
+    /// この変換の後に `outer`（親グループの変換）を適用する合成変換
+    fn then(self, outer: GroupTransform) -> Self {
+        GroupTransform {
+            scale_x: self.scale_x * outer.scale_x,
+            scale_y: self.scale_y * outer.scale_y,
+            offset_x: self.offset_x * outer.scale_x + outer.offset_x,
+            offset_y: self.offset_y * outer.scale_y + outer.offset_y,
+        }
+    }
+
+    /// 矩形 (x, y, width, height) を変換
+    fn apply_rect(&self, (x, y, width, height): (f64, f64, f64, f64)) -> (f64, f64, f64, f64) {
+        (
+            x * self.scale_x + self.offset_x,
+            y * self.scale_y + self.offset_y,
+            width * self.scale_x,
+            height * self.scale_y,
+        )
+    }
+}
+
+/// 解析中のグループシェイプ（p:grpSp）
+struct GroupFrame {
+    /// p:grpSp 要素の深さ
+    depth: u32,
+    /// 親グループまでの変換
+    parent: GroupTransform,
+    /// 子シェイプに適用する変換（grpSpPr の解析後に確定）
+    transform: GroupTransform,
+    ch_off: Option<(f64, f64)>,
+    ch_ext: Option<(f64, f64)>,
+    /// 親グループのクリップ範囲
+    parent_clip: Option<(f64, f64, f64, f64)>,
+    /// スライド座標系でのグループの範囲（親グループの範囲との共通部分）
+    clip: Option<(f64, f64, f64, f64)>,
+}
+
+/// テキストの自動調整（a:bodyPr の子要素）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum TextAutofit {
+    #[default]
+    None,
+    /// normAutofit: フォントサイズの縮小率と行間の削減率（いずれも 0..1）
+    Shrink { font_scale: f64, line_spacing_reduction: f64 },
+    /// spAutoFit: テキストが収まるよう図形の高さを広げる
+    ResizeShape,
+}
+
+impl SlideShape {
+    /// spAutoFit の図形で、テキストが収まらない場合に高さを広げた図形を返す
+    fn grown_to_fit_text(&self) -> Option<SlideShape> {
+        let ShapeContent::TextBox { paragraphs } = &self.content else {
+            return None;
+        };
+        if self.autofit != TextAutofit::ResizeShape {
+            return None;
+        }
+        let (_, text_bottom) = layout_shape_text(self, paragraphs, f64::INFINITY);
+        let needed = text_bottom + self.text_margin_bottom - self.y;
+        (needed > self.height).then(|| SlideShape {
+            height: needed,
+            ..self.clone()
+        })
+    }
+
+    /// カスタムジオメトリのパスを指定の矩形に拡大縮小して返す
+    fn custom_path_in(&self, x: f64, y: f64, width: f64, height: f64) -> Option<Vec<PathCommand>> {
+        let (cmds, (vp_w, vp_h)) = (self.custom_path.as_ref()?, self.custom_path_viewport?);
+        if cmds.is_empty() || vp_w <= 0.0 || vp_h <= 0.0 {
+            return None;
+        }
+        let sx = width / vp_w;
+        let sy = height / vp_h;
+        let map = |px: &f64, py: &f64| (x + px * sx, y + py * sy);
+        Some(cmds.iter().map(|cmd| match cmd {
+            PathCommand::MoveTo(px, py) => {
+                let (px, py) = map(px, py);
+                PathCommand::MoveTo(px, py)
+            }
+            PathCommand::LineTo(px, py) => {
+                let (px, py) = map(px, py);
+                PathCommand::LineTo(px, py)
+            }
+            PathCommand::QuadTo(cx, cy, px, py) => {
+                let ((cx, cy), (px, py)) = (map(cx, cy), map(px, py));
+                PathCommand::QuadTo(cx, cy, px, py)
+            }
+            PathCommand::CubicTo(cx1, cy1, cx2, cy2, px, py) => {
+                let ((cx1, cy1), (cx2, cy2), (px, py)) = (map(cx1, cy1), map(cx2, cy2), map(px, py));
+                PathCommand::CubicTo(cx1, cy1, cx2, cy2, px, py)
+            }
+            PathCommand::ArcTo(rx, ry, rot, large, sweep, px, py) => {
+                let (px, py) = map(px, py);
+                PathCommand::ArcTo(rx * sx, ry * sy, *rot, *large, *sweep, px, py)
+            }
+            PathCommand::Close => PathCommand::Close,
+        }).collect())
+    }
+
+    /// 図形の塗り（単色・グラデーション）と線でパスを描画する要素
+    /// `with_fill` が false の場合は線のみ描画します（複数パス図形のサブパス用）。
+    fn filled_path(&self, commands: Vec<PathCommand>, with_fill: bool) -> Vec<PageElement> {
+        let (stroke, stroke_width) = self.outline.map_or((None, 0.0), |o| (Some(o.color), o.width));
+        match &self.fill {
+            Some(ShapeFill::Gradient { stops, angle }) if with_fill => {
+                // グラデーション要素は線スタイルを持たないため、破線等は別のパスで重ねる
+                let styled = self.styled_outline_path(&commands);
+                let gradient = PageElement::GradientPath {
+                    commands,
+                    stops: stops.clone(),
+                    gradient_type: GradientType::Linear(*angle),
+                    stroke: stroke.filter(|_| styled.is_none()),
+                    stroke_width,
+                    rotation_deg: self.rotation,
+                };
+                std::iter::once(gradient).chain(styled).collect()
+            }
+            fill => vec![PageElement::Path {
+                commands,
+                fill: match fill {
+                    Some(ShapeFill::Solid(c)) if with_fill => Some(*c),
+                    _ => None,
+                },
+                stroke,
+                stroke_width,
+                rotation_deg: self.rotation,
+                stroke_style: self.outline.map(|o| o.style).unwrap_or_default(),
+            }],
+        }
+    }
+
+    /// 並べて敷き詰める、または fillRect に余白がある画像の塗りを、図形の形でクリップしたグループとして返す
+    /// 図形いっぱいに引き伸ばす既定の塗りや画像以外の塗りでは None（Image / PathImage 等で描画）。
+    fn image_fill_group(&self) -> Option<PageElement> {
+        let Some(ShapeFill::Image { data, mime_type }) = &self.fill else { return None };
+        let (x, y, w, h) = (self.x, self.y, self.width, self.height);
+        let image = |x: f64, y: f64, width: f64, height: f64| PageElement::Image {
+            x,
+            y,
+            width,
+            height,
+            data: data.clone(),
+            mime_type: mime_type.clone(),
+        };
+        let children = match self.fill_image_mode {
+            BlipFillMode::Stretch { insets: (l, t, r, b) } => {
+                let (width, height) = (w * (1.0 - l - r), h * (1.0 - t - b));
+                if (l, t, r, b) == (0.0, 0.0, 0.0, 0.0) || width <= 0.0 || height <= 0.0 {
+                    return None;
+                }
+                vec![image(x + w * l, y + h * t, width, height)]
+            }
+            BlipFillMode::Tile { offset, scale, align } => {
+                // 画像は96dpiとみなして元の大きさ（pt）を求める
+                let (px_w, px_h) = crate::converter::image_pixel_size(data)?;
+                let tile_w = px_w as f64 * 0.75 * scale.0.abs();
+                let tile_h = px_h as f64 * 0.75 * scale.1.abs();
+                if tile_w <= 0.0 || tile_h <= 0.0 {
+                    return None;
+                }
+                // 基準位置に最初のタイルを合わせ、図形全体を覆う範囲に並べる
+                let origin_x = x + (w - tile_w) * align.0 + offset.0;
+                let origin_y = y + (h - tile_h) * align.1 + offset.1;
+                let first_col = ((x - origin_x) / tile_w).floor() as i64;
+                let last_col = ((x + w - origin_x) / tile_w).ceil() as i64;
+                let first_row = ((y - origin_y) / tile_h).floor() as i64;
+                let last_row = ((y + h - origin_y) / tile_h).ceil() as i64;
+                let count = (last_col - first_col).max(0) as usize * (last_row - first_row).max(0) as usize;
+                if count == 0 || count > MAX_FILL_TILES {
+                    return None;
+                }
+                (first_row..last_row)
+                    .flat_map(|row| (first_col..last_col).map(move |col| (row, col)))
+                    .map(|(row, col)| {
+                        image(origin_x + col as f64 * tile_w, origin_y + row as f64 * tile_h, tile_w, tile_h)
+                    })
+                    .collect()
+            }
+        };
+
+        // クリップは図形の形（カスタム・プリセットのパス、楕円、それ以外は矩形）
+        let clip_path = self
+            .custom_path_in(x, y, w, h)
+            .or_else(|| match self.preset_geometry.as_deref() {
+                Some("ellipse") => Some(vec![
+                    PathCommand::MoveTo(x, y + h / 2.0),
+                    PathCommand::ArcTo(w / 2.0, h / 2.0, 0.0, false, true, x + w, y + h / 2.0),
+                    PathCommand::ArcTo(w / 2.0, h / 2.0, 0.0, false, true, x, y + h / 2.0),
+                    PathCommand::Close,
+                ]),
+                Some(name) if name != "rect" => generate_preset_path(name, x, y, w, h),
+                _ => None,
+            })
+            .unwrap_or_else(|| PathCommand::rect(x, y, w, h));
+        let group = PageElement::ClipGroup { clip_path, children };
+        Some(if self.rotation != 0.0 {
+            PageElement::TransformGroup {
+                cx: x + w / 2.0,
+                cy: y + h / 2.0,
+                rotation_deg: self.rotation,
+                opacity: 1.0,
+                children: vec![group],
+            }
+        } else {
+            group
+        })
+    }
+
+    /// 破線・線端・角の指定がある線を、線のみのパスとして描画する要素
+    /// 線スタイルを持たない要素（画像・グラデーション）の上に重ねるために使います。
+    fn styled_outline_path(&self, commands: &[PathCommand]) -> Option<PageElement> {
+        let outline = self.outline.filter(|o| !o.style.is_default())?;
+        Some(PageElement::Path {
+            commands: commands.to_vec(),
+            fill: None,
+            stroke: Some(outline.color),
+            stroke_width: outline.width,
+            rotation_deg: self.rotation,
+            stroke_style: outline.style,
+        })
+    }
+
+    /// 複合線（二重線など）を、平行にずらした線のみの要素として描画する
+    /// 図形は外接矩形を各線のずれだけ内外に広げたジオメトリで、コネクタは線に垂直な方向に
+    /// ずらして描きます。単線・線なしの場合や描画できないジオメトリでは空を返します。
+    fn compound_outline_elements(&self) -> Vec<PageElement> {
+        let Some(outline) = self.outline.filter(|o| o.compound != CompoundLine::Single) else {
+            return Vec::new();
+        };
+        let mut elements = Vec::new();
+        for (offset, width) in outline.compound.strokes(outline.width) {
+            let stroke_path = |commands| PageElement::Path {
+                commands,
+                fill: None,
+                stroke: Some(outline.color),
+                stroke_width: width,
+                rotation_deg: self.rotation,
+                stroke_style: outline.style,
+            };
+            if matches!(self.content, ShapeContent::Connector) {
+                // 始点から終点への線に垂直な方向へずらす
+                let length = self.width.hypot(self.height);
+                if length <= 0.0 {
+                    return Vec::new();
+                }
+                let (dx, dy) = (-self.height / length * offset, self.width / length * offset);
+                let (x, y) = (self.x + dx, self.y + dy);
+                match self.preset_geometry.as_deref() {
+                    Some(name) => match generate_preset_path(name, x, y, self.width, self.height) {
+                        Some(cmds) => elements.push(stroke_path(cmds)),
+                        None => return Vec::new(),
+                    },
+                    None => elements.push(PageElement::Line {
+                        x1: x,
+                        y1: y,
+                        x2: x + self.width,
+                        y2: y + self.height,
+                        width,
+                        color: outline.color,
+                        stroke_style: outline.style,
+                    }),
+                }
+                continue;
+            }
+            let (x, y) = (self.x - offset, self.y - offset);
+            let (w, h) = ((self.width + offset * 2.0).max(0.0), (self.height + offset * 2.0).max(0.0));
+            if let Some(cmds) = self.custom_path_in(x, y, w, h) {
+                elements.push(stroke_path(cmds));
+                continue;
+            }
+            match self.preset_geometry.as_deref() {
+                Some("ellipse") => elements.push(PageElement::Ellipse {
+                    cx: x + w / 2.0,
+                    cy: y + h / 2.0,
+                    rx: w / 2.0,
+                    ry: h / 2.0,
+                    fill: None,
+                    stroke: Some(outline.color),
+                    stroke_width: width,
+                    rotation_deg: self.rotation,
+                    stroke_style: outline.style,
+                }),
+                None | Some("rect") => elements.push(PageElement::Rect {
+                    x,
+                    y,
+                    width: w,
+                    height: h,
+                    fill: None,
+                    stroke: Some(outline.color),
+                    stroke_width: width,
+                    rotation_deg: self.rotation,
+                    stroke_style: outline.style,
+                }),
+                Some(name) => {
+                    let paths = generate_preset_paths(name, x, y, w, h)
+                        .or_else(|| generate_preset_path(name, x, y, w, h).map(|p| vec![p]));
+                    match paths {
+                        Some(paths) => elements.extend(paths.into_iter().map(stroke_path)),
+                        None => return Vec::new(),
+                    }
+                }
+            }
+        }
+        elements
+    }
+
+    /// 影を図形と同じジオメトリで描画する要素
+    /// 影のベクトル分ずらし、ぼかしは外側ほど大きく広げた低アルファの重ね描きで近似します。
+    fn shadow_elements(&self) -> Vec<PageElement> {
+        let Some(shadow) = &self.shadow else {
+            return Vec::new();
+        };
+        let passes = if shadow.blur_radius > 0.0 { SHADOW_BLUR_PASSES } else { 1 };
+        // 重ねた中心部が元の影の濃さ（アルファ50%）になるよう1回あたりの濃さを決める
+        let total_alpha = shadow.color.a as f64 / 255.0 * 0.5;
+        let pass_alpha = 1.0 - (1.0 - total_alpha).powf(1.0 / passes as f64);
+        let color = Color { a: (pass_alpha * 255.0).round() as u8, ..shadow.color };
+
+        (0..passes)
+            .filter_map(|i| {
+                let spread = shadow.blur_radius * (passes - 1 - i) as f64 / passes as f64;
+                let x = self.x + shadow.offset_x - spread;
+                let y = self.y + shadow.offset_y - spread;
+                let width = self.width + spread * 2.0;
+                let height = self.height + spread * 2.0;
+                self.shadow_geometry(x, y, width, height, color)
+            })
+            .collect()
+    }
+
+    /// 指定の矩形に配置した図形の輪郭を影の色で塗りつぶす要素
+    fn shadow_geometry(&self, x: f64, y: f64, width: f64, height: f64, color: Color) -> Option<PageElement> {
+        let path = |commands| PageElement::Path {
+            commands,
+            fill: Some(color),
+            stroke: None,
+            stroke_width: 0.0,
+            rotation_deg: self.rotation,
+            stroke_style: Default::default(),
+        };
+        if let Some(cmds) = self.custom_path_in(x, y, width, height) {
+            return Some(path(cmds));
+        }
+        match self.preset_geometry.as_deref() {
+            Some("ellipse") => Some(PageElement::Ellipse {
+                cx: x + width / 2.0,
+                cy: y + height / 2.0,
+                rx: width / 2.0,
+                ry: height / 2.0,
+                fill: Some(color),
+                stroke: None,
+                stroke_width: 0.0,
+                rotation_deg: self.rotation,
+                stroke_style: Default::default(),
+            }),
+            None | Some("rect") => Some(PageElement::Rect {
+                x,
+                y,
+                width,
+                height,
+                fill: Some(color),
+                stroke: None,
+                stroke_width: 0.0,
+                rotation_deg: self.rotation,
+                stroke_style: Default::default(),
+            }),
+            // 複数パスのジオメトリは外形（最初のパス）のみ
+            Some(name) => generate_preset_paths(name, x, y, width, height)
+                .and_then(|groups| groups.into_iter().next())
+                .or_else(|| generate_preset_path(name, x, y, width, height))
+                .map(path),
+        }
+    }
+}
+
+impl TextAutofit {
+    /// a:normAutofit 要素の fontScale / lnSpcReduction 属性を読み取る
+    fn shrink_from(e: &quick_xml::events::BytesStart) -> Self {
+        TextAutofit::Shrink {
+            font_scale: percent_attr(e, b"fontScale").unwrap_or(1.0).clamp(0.01, 1.0),
+            line_spacing_reduction: percent_attr(e, b"lnSpcReduction").unwrap_or(0.0).clamp(0.0, 0.9),
+        }
+    }
+}
+
+/// プレースホルダー情報
+#[derive(Debug, Clone)]
+struct Placeholder {
+    /// type属性（省略時は "obj"）
+    ph_type: String,
+    idx: Option<u32>,
+    /// 入力を促すプロンプト文字列（「クリックしてテキストを入力」等）のみを持つ
+    is_prompt: bool,
 }
 
 /// シャドウ効果
@@ -270,7 +1019,9 @@ struct ShadowEffect {
 #[derive(Debug, Clone)]
 enum ShapeContent {
     TextBox { paragraphs: Vec<ShapeParagraph> },
-    Image { r_id: String },
+    /// 未解決の画像（`svg_r_id` は a:blip の拡張 asvg:svgBlip のSVG、`r_id` はPNG等の代替画像、
+    /// `recolor` は a:blip の色の置き換え効果）
+    Image { r_id: String, svg_r_id: Option<String>, recolor: Option<ImageRecolor> },
     ImageData { data: Vec<u8>, mime_type: String },
     Connector,
     Empty,
@@ -281,10 +1032,34 @@ enum ShapeContent {
 struct ShapeParagraph {
     runs: Vec<TextRun>,
     align: TextAlign,
-    bullet: Option<String>,
+    bullet: BulletProps,
     level: u32,
 }
 
+/// 箇条書きの記号（a:buChar / a:buAutoNum / a:buNone）
+#[derive(Debug, Clone, PartialEq)]
+enum BulletKind {
+    None,
+    Char(String),
+    /// 自動番号（type属性の書式, startAt）
+    AutoNum { scheme: String, start_at: u32 },
+}
+
+/// 段落・レベルごとの箇条書きとインデントの指定（a:pPr / a:lvlNpPr）
+/// `None` の項目はマスターの txStyles から継承します。
+#[derive(Debug, Clone, Default, PartialEq)]
+struct BulletProps {
+    kind: Option<BulletKind>,
+    /// a:buSzPct（文字サイズに対する倍率）
+    size_pct: Option<f64>,
+    /// a:buClr
+    color: Option<Color>,
+    /// marL（テキストの左位置、ポイント）
+    margin_left: Option<f64>,
+    /// indent（1行目のずれ、ポイント。負の値はぶら下げで記号の位置になる）
+    indent: Option<f64>,
+}
+
 /// テキストラン（書式付きテキスト断片）
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -295,6 +1070,228 @@ struct TextRun {
     italic: bool,
     color: Option<Color>,
     font_name: Option<String>,
+    strikethrough: bool,
+    baseline_shift: f64,
+    /// 文字間隔（pt、a:rPr の spc）
+    letter_spacing: f64,
+    underline: Option<UnderlineStyle>,
+    /// 蛍光ペン（a:highlight、テキストの背景色）
+    highlight: Option<Color>,
+    /// a:fld のフィールド種別（変換時に値を計算して `text` を置き換える）
+    field: Option<TextField>,
+    /// リンク（a:rPr の a:hlinkClick）が付いている
+    hyperlink: bool,
+}
+
+impl TextRun {
+    /// `other` と同じ書式で、1つのランにまとめられるか（フィールドとリンクの境界はまとめない）
+    fn can_merge_with(&self, other: &TextRun) -> bool {
+        self.field.is_none()
+            && other.field.is_none()
+            && self.font_size == other.font_size
+            && self.bold == other.bold
+            && self.italic == other.italic
+            && self.color == other.color
+            && self.font_name == other.font_name
+            && self.strikethrough == other.strikethrough
+            && self.baseline_shift == other.baseline_shift
+            && self.letter_spacing == other.letter_spacing
+            && self.underline == other.underline
+            && self.highlight == other.highlight
+            && self.hyperlink == other.hyperlink
+    }
+}
+
+/// 値を計算できる a:fld のフィールド種別
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TextField {
+    /// スライド番号（slidenum）
+    SlideNumber,
+    /// 日付と時刻（datetime / datetime1〜13、数字は書式の番号）
+    DateTime(u8),
+}
+
+impl TextField {
+    /// a:fld の type 属性から種別を判定（未知の種別は None でキャッシュされた表示文字列を使う）
+    fn from_type(field_type: &str) -> Option<Self> {
+        if field_type == "slidenum" {
+            return Some(Self::SlideNumber);
+        }
+        let format = field_type.strip_prefix("datetime")?;
+        if format.is_empty() {
+            return Some(Self::DateTime(1));
+        }
+        format.parse().ok().filter(|n| (1..=13).contains(n)).map(Self::DateTime)
+    }
+
+    /// フィールドの表示文字列を計算
+    fn text(self, slide_number: usize, now: &FieldDate) -> String {
+        match self {
+            Self::SlideNumber => slide_number.to_string(),
+            Self::DateTime(format) => now.format(format),
+        }
+    }
+}
+
+/// 日付フィールドに使う日時
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FieldDate {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+}
+
+impl FieldDate {
+    /// 現在の日時（WASMではブラウザのローカル時刻、それ以外ではUTC）
+    #[cfg(target_arch = "wasm32")]
+    fn now() -> Self {
+        let date = js_sys::Date::new_0();
+        Self {
+            year: date.get_full_year() as i64,
+            month: date.get_month() + 1,
+            day: date.get_date(),
+            hour: date.get_hours(),
+            minute: date.get_minutes(),
+            second: date.get_seconds(),
+        }
+    }
+
+    /// 現在の日時（WASMではブラウザのローカル時刻、それ以外ではUTC）
+    #[cfg(not(target_arch = "wasm32"))]
+    fn now() -> Self {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Self::from_unix_seconds(secs)
+    }
+
+    /// UNIX時刻（秒）からグレゴリオ暦の日時を求める
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    fn from_unix_seconds(secs: i64) -> Self {
+        let days = secs.div_euclid(86400);
+        let rem = secs.rem_euclid(86400) as u32;
+        // 0000-03-01 起点の暦日から年月日を求める
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        Self { year, month, day, hour: rem / 3600, minute: rem / 60 % 60, second: rem % 60 }
+    }
+
+    /// PowerPointの datetime1〜13 の書式（英語ロケール）で文字列化
+    fn format(&self, format: u8) -> String {
+        const MONTHS: [&str; 12] = [
+            "January", "February", "March", "April", "May", "June",
+            "July", "August", "September", "October", "November", "December",
+        ];
+        const WEEKDAYS: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+        let month_name = MONTHS[(self.month as usize + 11) % 12];
+        let short_month = &month_name[..3];
+        let (y, m, d) = (self.year, self.month, self.day);
+        let yy = y.rem_euclid(100);
+        let hour12 = match self.hour % 12 {
+            0 => 12,
+            h => h,
+        };
+        let ampm = if self.hour < 12 { "AM" } else { "PM" };
+        match format {
+            2 => format!("{}, {} {}, {}", WEEKDAYS[self.weekday()], month_name, d, y),
+            3 => format!("{} {} {}", d, month_name, y),
+            4 => format!("{} {}, {}", month_name, d, y),
+            5 => format!("{}-{}-{:02}", d, short_month, yy),
+            6 => format!("{} {:02}", month_name, yy),
+            7 => format!("{}-{:02}", short_month, yy),
+            8 => format!("{}/{}/{} {}:{:02} {}", m, d, y, hour12, self.minute, ampm),
+            9 => format!("{}/{}/{} {}:{:02}:{:02} {}", m, d, y, hour12, self.minute, self.second, ampm),
+            10 => format!("{}:{:02}", self.hour, self.minute),
+            11 => format!("{}:{:02}:{:02}", self.hour, self.minute, self.second),
+            12 => format!("{}:{:02} {}", hour12, self.minute, ampm),
+            13 => format!("{}:{:02}:{:02} {}", hour12, self.minute, self.second, ampm),
+            _ => format!("{}/{}/{}", m, d, y),
+        }
+    }
+
+    /// 曜日（0 = 日曜日）
+    fn weekday(&self) -> usize {
+        // Zellerの公式（1月・2月は前年の13月・14月として扱う）
+        let (y, m) = if self.month <= 2 { (self.year - 1, self.month + 12) } else { (self.year, self.month) };
+        let k = y.rem_euclid(100);
+        let j = y.div_euclid(100);
+        let h = (self.day as i64 + 13 * (m as i64 + 1) / 5 + k + k / 4 + j / 4 + 5 * j).rem_euclid(7);
+        // h: 0 = 土曜日
+        ((h + 6) % 7) as usize
+    }
+}
+
+/// スライド番号・日付フィールドの表示文字列を計算した値に置き換える
+fn resolve_text_fields(shapes: &mut [SlideShape], slide_number: usize, now: &FieldDate) {
+    for shape in shapes {
+        let ShapeContent::TextBox { paragraphs } = &mut shape.content else { continue };
+        for run in paragraphs.iter_mut().flat_map(|p| p.runs.iter_mut()) {
+            if let Some(field) = run.field {
+                run.text = field.text(slide_number, now);
+            }
+        }
+    }
+}
+
+/// ランのフォント名のテーマフォント参照（`+mn-lt` など）をフォントスキームのフォント名にする
+fn resolve_theme_fonts(shapes: &mut [SlideShape], theme_fonts: &ThemeFonts) {
+    for shape in shapes {
+        let ShapeContent::TextBox { paragraphs } = &mut shape.content else { continue };
+        for run in paragraphs.iter_mut().flat_map(|p| p.runs.iter_mut()) {
+            run.font_name = run.font_name.as_deref().and_then(|typeface| theme_fonts.resolve(typeface));
+        }
+    }
+}
+
+/// リンクの付いたランの書式を `HyperlinkStyle` に合わせる
+/// 強調はテーマの hlink の色と下線、プレーンは下線を除いてテーマの hlink の色の指定も外します。
+fn apply_hyperlink_style(shapes: &mut [SlideShape], style: HyperlinkStyle, link_color: Color) {
+    if style == HyperlinkStyle::Source {
+        return;
+    }
+    for shape in shapes {
+        let ShapeContent::TextBox { paragraphs } = &mut shape.content else { continue };
+        for run in paragraphs.iter_mut().flat_map(|p| p.runs.iter_mut()).filter(|run| run.hyperlink) {
+            if style == HyperlinkStyle::Emphasize {
+                run.color = Some(link_color);
+                run.underline = Some(UnderlineStyle::new(UnderlineKind::Single));
+            } else {
+                run.underline = None;
+                if run.color == Some(link_color) {
+                    run.color = None;
+                }
+            }
+        }
+    }
+}
+
+/// 段落内で同じ書式が隣接するランを1つにまとめる
+/// 要素数を減らし、ランの境界をまたぐ字送りを1つのテキストとして揃えます。
+fn merge_adjacent_runs(shapes: &mut [SlideShape]) {
+    for shape in shapes {
+        let ShapeContent::TextBox { paragraphs } = &mut shape.content else { continue };
+        for para in paragraphs.iter_mut() {
+            let mut merged: Vec<TextRun> = Vec::with_capacity(para.runs.len());
+            for run in std::mem::take(&mut para.runs) {
+                match merged.last_mut() {
+                    Some(prev) if prev.can_merge_with(&run) => prev.text.push_str(&run.text),
+                    _ => merged.push(run),
+                }
+            }
+            para.runs = merged;
+        }
+    }
 }
 
 // ── ZIP helpers ──
@@ -303,26 +1300,25 @@ fn read_zip_entry_string(
     archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
     path: &str,
 ) -> Result<String, ConvertError> {
-    use std::io::Read;
     let mut file = archive
         .by_name(path)
         .map_err(|e| ConvertError::new("PPTX", &format!("{}が見つかりません: {}", path, e)))?;
     let mut content = String::new();
-    file.read_to_string(&mut content)
+    super::limits::read_limited_string(&mut file, &mut content)
         .map_err(|e| ConvertError::new("PPTX", &format!("{}の読み込みエラー: {}", path, e)))?;
-    Ok(content)
+    // mc:AlternateContent は採用する分岐だけを残して解析する
+    Ok(super::markup_compat::resolve_alternate_content(content))
 }
 
 fn read_zip_entry_bytes(
     archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
     path: &str,
 ) -> Result<Vec<u8>, ConvertError> {
-    use std::io::Read;
     let mut file = archive
         .by_name(path)
         .map_err(|e| ConvertError::new("PPTX", &format!("{}が見つかりません: {}", path, e)))?;
     let mut data = Vec::new();
-    file.read_to_end(&mut data)
+    super::limits::read_limited(&mut file, &mut data)
         .map_err(|e| ConvertError::new("PPTX", &format!("{}の読み込みエラー: {}", path, e)))?;
     Ok(data)
 }
@@ -354,6 +1350,69 @@ fn find_slide_paths(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>) -> Ve
     paths
 }
 
+/// 要素の show 属性が "0" / "false"（非表示）かどうか
+fn has_hidden_show_attr(e: &quick_xml::events::BytesStart) -> bool {
+    e.attributes()
+        .flatten()
+        .any(|a| a.key.as_ref() == b"show" && matches!(a.value.as_ref(), b"0" | b"false"))
+}
+
+/// スライドXMLのルート要素（p:sld）が非表示かどうか
+fn is_hidden_slide(slide_xml: &str) -> bool {
+    let mut reader = quick_xml::Reader::from_str(slide_xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) | Ok(quick_xml::events::Event::Empty(ref e)) => {
+                return e.local_name().as_ref() == b"sld" && has_hidden_show_attr(e);
+            }
+            Ok(quick_xml::events::Event::Eof) | Err(_) => return false,
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+/// presentation.xml の sldIdLst で show="0" が指定されたスライドのパス
+fn read_hidden_slide_paths(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>) -> Vec<String> {
+    let Ok(pres_xml) = read_zip_entry_string(archive, "ppt/presentation.xml") else {
+        return Vec::new();
+    };
+    let mut hidden_ids = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(&pres_xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) | Ok(quick_xml::events::Event::Empty(ref e))
+                if e.local_name().as_ref() == b"sldId" && has_hidden_show_attr(e) =>
+            {
+                if let Some(id) = e.attributes().flatten().find(|a| a.key.local_name().as_ref() == b"id"
+                    && a.key.prefix().is_some())
+                {
+                    hidden_ids.push(String::from_utf8_lossy(&id.value).to_string());
+                }
+            }
+            Ok(quick_xml::events::Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    if hidden_ids.is_empty() {
+        return Vec::new();
+    }
+    let Ok(rels_xml) = read_zip_entry_string(archive, "ppt/_rels/presentation.xml.rels") else {
+        return Vec::new();
+    };
+    hidden_ids
+        .iter()
+        .filter_map(|id| resolve_relationship(&rels_xml, id))
+        .map(|target| match target.strip_prefix('/') {
+            Some(absolute) => absolute.to_string(),
+            None => format!("ppt/{}", target),
+        })
+        .collect()
+}
+
 fn extract_slide_number(path: &str) -> u32 {
     path.trim_start_matches("ppt/slides/slide")
         .trim_end_matches(".xml")
@@ -365,11 +1424,14 @@ fn extract_slide_number(path: &str) -> u32 {
 
 /// テーマカラーをtheme.xmlから読み込む
 fn read_theme_colors(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>) -> ThemeColors {
-    let theme_xml = match read_zip_entry_string(archive, "ppt/theme/theme1.xml") {
-        Ok(s) => s,
-        Err(_) => return ThemeColors::default(),
-    };
-    let mut reader = quick_xml::Reader::from_str(&theme_xml);
+    read_zip_entry_string(archive, "ppt/theme/theme1.xml")
+        .map(|xml| parse_theme_colors(&xml))
+        .unwrap_or_default()
+}
+
+/// theme.xml のカラースキーム（a:clrScheme）を解析（DOCXのテーマにも使用）
+pub(super) fn parse_theme_colors(theme_xml: &str) -> ThemeColors {
+    let mut reader = quick_xml::Reader::from_str(theme_xml);
     let mut buf = Vec::new();
     let mut colors = ThemeColors::default();
     let mut current_scheme_entry = String::new();
@@ -439,42 +1501,379 @@ fn read_theme_colors(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>) -> T
                         _ => {}
                     }
                 }
-            }
-            Ok(quick_xml::events::Event::Eof) => break,
-            Err(_) => break,
-            _ => {}
+            }
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    colors
+}
+
+/// テーマのフォントスキームをtheme.xmlから読み込む
+fn read_theme_fonts(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>) -> ThemeFonts {
+    read_zip_entry_string(archive, "ppt/theme/theme1.xml")
+        .map(|xml| parse_theme_fonts(&xml))
+        .unwrap_or_default()
+}
+
+/// a:fontScheme の a:majorFont / a:minorFont 直下の a:latin / a:ea / a:cs を読む
+fn parse_theme_fonts(xml: &str) -> ThemeFonts {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut fonts = ThemeFonts::default();
+    // 読み込み中の majorFont / minorFont（a:font の言語別指定は対象外）
+    let mut current: Option<bool> = None;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) => match e.local_name().as_ref() {
+                b"majorFont" => current = Some(true),
+                b"minorFont" => current = Some(false),
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Empty(ref e)) => {
+                let Some(major) = current else {
+                    buf.clear();
+                    continue;
+                };
+                let typefaces = if major { &mut fonts.major } else { &mut fonts.minor };
+                let slot = match e.local_name().as_ref() {
+                    b"latin" => &mut typefaces.latin,
+                    b"ea" => &mut typefaces.east_asian,
+                    b"cs" => &mut typefaces.complex,
+                    _ => {
+                        buf.clear();
+                        continue;
+                    }
+                };
+                *slot = typeface_attr(e);
+            }
+            Ok(quick_xml::events::Event::End(ref e)) => {
+                if matches!(e.local_name().as_ref(), b"majorFont" | b"minorFont") {
+                    current = None;
+                }
+            }
+            Ok(quick_xml::events::Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    fonts
+}
+
+/// a:latin / a:ea / a:cs の typeface 属性（空の場合は None）
+fn typeface_attr(e: &quick_xml::events::BytesStart) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.as_ref() == b"typeface")
+        .map(|a| String::from_utf8_lossy(&a.value).to_string())
+        .filter(|typeface| !typeface.is_empty())
+}
+
+/// p:ph 要素からプレースホルダー情報を取得
+fn parse_placeholder(e: &quick_xml::events::BytesStart) -> Placeholder {
+    let mut ph = Placeholder { ph_type: "obj".to_string(), idx: None, is_prompt: false };
+    for attr in e.attributes().flatten() {
+        let val = String::from_utf8_lossy(&attr.value);
+        match attr.key.as_ref() {
+            b"type" => ph.ph_type = val.to_string(),
+            b"idx" => ph.idx = val.parse().ok(),
+            b"hasCustomPrompt" => ph.is_prompt = val == "1" || val == "true",
+            _ => {}
+        }
+    }
+    ph
+}
+
+impl Placeholder {
+    /// スライド側とレイアウト側のプレースホルダーが対応するか（idx優先、無ければtype）
+    fn matches(&self, other: &Placeholder) -> bool {
+        let normalize = |t: &str| match t {
+            "ctrTitle" => "title",
+            "subTitle" | "obj" => "body",
+            _ => t,
+        }
+        .to_string();
+        match (self.idx, other.idx) {
+            (Some(a), Some(b)) => a == b,
+            _ => normalize(&self.ph_type) == normalize(&other.ph_type),
+        }
+    }
+}
+
+/// レイアウトのプレースホルダーの段落が入力促進用のプロンプトかどうか
+/// （明示的なプロンプト指定、空の本文、PowerPoint既定の「クリックして…」文言）
+fn is_prompt_text(placeholder: &Placeholder, paragraphs: &[ShapeParagraph]) -> bool {
+    const PROMPT_PREFIXES: [&str; 3] = ["Click to ", "クリックして", "テキストを入力"];
+    let text: String = paragraphs
+        .iter()
+        .flat_map(|p| p.runs.iter().map(|r| r.text.as_str()))
+        .collect();
+    let text = text.trim();
+    placeholder.is_prompt || text.is_empty() || PROMPT_PREFIXES.iter().any(|p| text.starts_with(p))
+}
+
+/// スライドのリレーションシップからスライドレイアウトのパスを解決
+fn slide_layout_path(rels: &Option<String>) -> Option<String> {
+    let target = rels.as_deref().and_then(|r| resolve_relationship_by_type(r, "/slideLayout"))?;
+    let layout_path = if let Some(stripped) = target.strip_prefix('/') {
+        stripped.to_string()
+    } else {
+        format!("ppt/slides/{}", target)
+    };
+    Some(normalize_zip_path(&layout_path))
+}
+
+/// スライドのリレーションシップからスライドレイアウトを読み込んでシェイプを解析
+fn read_slide_layout_shapes(
+    rels: &Option<String>,
+    archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
+    theme_colors: &ThemeColors,
+) -> Vec<SlideShape> {
+    let Some(layout_path) = slide_layout_path(rels) else {
+        return Vec::new();
+    };
+    match read_zip_entry_string(archive, &layout_path) {
+        Ok(xml) => parse_slide_shapes(&xml, theme_colors),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// スライドレイアウト経由でスライドマスターを読み込み、本文のレベル別箇条書き指定を返す
+fn read_master_body_style(
+    rels: &Option<String>,
+    archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
+    theme_colors: &ThemeColors,
+) -> Vec<BulletProps> {
+    let Some((layout_dir, layout_file)) = slide_layout_path(rels).and_then(|p| {
+        p.rsplit_once('/').map(|(dir, file)| (dir.to_string(), file.to_string()))
+    }) else {
+        return Vec::new();
+    };
+    let layout_rels_path = format!("{}/_rels/{}.rels", layout_dir, layout_file);
+    let Some(target) = read_zip_entry_string(archive, &layout_rels_path)
+        .ok()
+        .and_then(|r| resolve_relationship_by_type(&r, "/slideMaster"))
+    else {
+        return Vec::new();
+    };
+    let master_path = if let Some(stripped) = target.strip_prefix('/') {
+        stripped.to_string()
+    } else {
+        format!("{}/{}", layout_dir, target)
+    };
+    match read_zip_entry_string(archive, &normalize_zip_path(&master_path)) {
+        Ok(xml) => parse_master_body_style(&xml, theme_colors),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// a:lvl1pPr〜a:lvl9pPr の要素名からレベル（0始まり）を取得
+fn level_ppr_index(local: &[u8]) -> Option<usize> {
+    match local {
+        [b'l', b'v', b'l', digit @ b'1'..=b'9', b'p', b'P', b'r'] => Some((digit - b'1') as usize),
+        _ => None,
+    }
+}
+
+/// スライドマスターの p:txStyles/p:bodyStyle からレベル別の箇条書き・インデント指定を解析
+fn parse_master_body_style(xml: &str, theme_colors: &ThemeColors) -> Vec<BulletProps> {
+    let mut levels = vec![BulletProps::default(); 9];
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut in_body_style = false;
+    let mut cur_level: Option<usize> = None;
+    let mut in_bu_clr = false;
+    let mut color_builder = ColorBuilder::default();
+
+    loop {
+        let event = reader.read_event_into(&mut buf);
+        let is_start = matches!(event, Ok(quick_xml::events::Event::Start(_)));
+        match event {
+            Ok(quick_xml::events::Event::Start(ref e)) | Ok(quick_xml::events::Event::Empty(ref e)) => {
+                let local = e.local_name();
+                let local = local.as_ref();
+                // bodyStyle 以外（titleStyle / otherStyle 等）のレベル指定は対象外
+                if local == b"bodyStyle" {
+                    in_body_style = is_start;
+                } else if let Some(level) = level_ppr_index(local).filter(|_| in_body_style) {
+                    levels[level].read_indent_attrs(e);
+                    cur_level = is_start.then_some(level);
+                } else if let Some(level) = cur_level {
+                    if local == b"buClr" {
+                        in_bu_clr = is_start;
+                    } else if in_bu_clr && is_start {
+                        color_builder.start(e, theme_colors);
+                    } else if in_bu_clr {
+                        color_builder.modify(e);
+                        levels[level].color = parse_color_element_themed(e, theme_colors).or(levels[level].color);
+                    } else {
+                        levels[level].read_bullet_element(e);
+                    }
+                }
+            }
+            Ok(quick_xml::events::Event::End(ref e)) => match e.local_name().as_ref() {
+                b"bodyStyle" => in_body_style = false,
+                b"buClr" => in_bu_clr = false,
+                clr if is_color_element(clr) => {
+                    if let (Some(level), Some(c)) = (cur_level, color_builder.finish()) {
+                        levels[level].color = Some(c);
+                    }
+                }
+                local if level_ppr_index(local).is_some() => cur_level = None,
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    levels
+}
+
+/// 本文プレースホルダーの段落に、マスターのレベル別箇条書き指定のうち未指定の項目を補う
+fn apply_master_body_style(shapes: &mut [SlideShape], body_style: &[BulletProps]) {
+    for shape in shapes.iter_mut() {
+        let is_body = shape
+            .placeholder
+            .as_ref()
+            .is_some_and(|ph| matches!(ph.ph_type.as_str(), "body" | "obj"));
+        if !is_body {
+            continue;
+        }
+        if let ShapeContent::TextBox { paragraphs } = &mut shape.content {
+            for para in paragraphs.iter_mut() {
+                if let Some(base) = body_style.get(para.level as usize) {
+                    para.bullet = para.bullet.inherit(base);
+                }
+            }
+        }
+    }
+}
+
+/// 空のプレースホルダーに対応するレイアウトのプレースホルダーの本文と位置を補う
+/// プロンプト文字列（`is_prompt_text`）は実際の内容ではないため描画しません。
+fn apply_layout_placeholders(shapes: &mut [SlideShape], layout_shapes: &[SlideShape]) {
+    for shape in shapes.iter_mut() {
+        let Some(ph) = shape.placeholder.as_ref() else { continue };
+        let Some((layout, layout_ph)) = layout_shapes.iter().find_map(|l| {
+            let lp = l.placeholder.as_ref()?;
+            ph.matches(lp).then_some((l, lp))
+        }) else {
+            continue;
+        };
+        // xfrm を持たないプレースホルダーはレイアウトの位置・サイズを継承
+        if shape.width <= 0.0 && shape.height <= 0.0 {
+            shape.x = layout.x;
+            shape.y = layout.y;
+            shape.width = layout.width;
+            shape.height = layout.height;
+        }
+        if let (ShapeContent::Empty, ShapeContent::TextBox { paragraphs }) = (&shape.content, &layout.content) {
+            if !is_prompt_text(layout_ph, paragraphs) {
+                shape.content = ShapeContent::TextBox { paragraphs: paragraphs.clone() };
+            }
+        }
+    }
+}
+
+/// 画像の色の置き換え効果（a:blip の a:grayscl / a:duotone / a:biLevel）
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImageRecolor {
+    /// 輝度によるグレースケール
+    Grayscale,
+    /// 輝度に応じて2色の間を補間（暗い部分が1色目、明るい部分が2色目）
+    Duotone(Color, Color),
+    /// 輝度がしきい値（0.0〜1.0）以上を白、未満を黒に2値化
+    BiLevel(f64),
+}
+
+impl ImageRecolor {
+    /// a:biLevel の thresh 属性（既定: 50%）
+    fn bi_level_from(e: &quick_xml::events::BytesStart) -> Self {
+        Self::BiLevel(percent_attr(e, b"thresh").unwrap_or(0.5).clamp(0.0, 1.0))
+    }
+
+    /// デコード済みのRGBAビットマップの色を置き換える（アルファは保持）
+    fn apply_to_rgba(self, pixels: &mut [u8]) {
+        for px in pixels.chunks_exact_mut(4) {
+            let level = crate::converter::luminance(px[0], px[1], px[2]);
+            let rgb = match self {
+                Self::Grayscale => [level; 3],
+                Self::Duotone(dark, light) => {
+                    let t = level as f64 / 255.0;
+                    let mix = |d: u8, l: u8| (d as f64 + (l as f64 - d as f64) * t).round() as u8;
+                    [mix(dark.r, light.r), mix(dark.g, light.g), mix(dark.b, light.b)]
+                }
+                Self::BiLevel(thresh) => [if level as f64 / 255.0 >= thresh { 255 } else { 0 }; 3],
+            };
+            px[..3].copy_from_slice(&rgb);
         }
-        buf.clear();
     }
-    colors
+
+    /// PNG / JPEG 画像の色を置き換えたPNGを返す（デコードできない画像は None）
+    fn apply(self, data: &[u8]) -> Option<Vec<u8>> {
+        let (width, height, mut pixels) = crate::image_renderer::decode_to_rgba(data)?;
+        self.apply_to_rgba(&mut pixels);
+        Some(crate::image_renderer::encode_png(&pixels, width, height))
+    }
 }
 
 /// シェイプの画像参照を解決
+/// パッケージ内に無い画像は `supplied` のデータを使い、それも無ければ遅延アセットとして警告に記録します。
 fn resolve_shape_images(
     shape: SlideShape,
     rels: &Option<String>,
     archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
+    supplied: &[(String, Vec<u8>)],
+    warnings: &mut Vec<String>,
 ) -> SlideShape {
     let mut s = shape;
 
     // Resolve content image (pic element)
-    if let ShapeContent::Image { ref r_id } = s.content {
-        if let Some(ref rels_xml) = rels {
-            if let Some(target) = resolve_relationship(rels_xml, r_id) {
-                let img_path = if target.starts_with('/') {
-                    target[1..].to_string()
-                } else {
-                    format!("ppt/slides/{}", target)
-                };
-                let img_path = normalize_zip_path(&img_path);
-                if let Ok(data) = read_zip_entry_bytes(archive, &img_path) {
-                    let mime = guess_mime(&img_path);
-                    s.content = ShapeContent::ImageData {
-                        data,
-                        mime_type: mime.to_string(),
-                    };
-                }
+    // SVG は図形の大きさに合わせてPNGにラスタライズし、描画できない場合は代替画像（a:blip）を使う
+    if let (ShapeContent::Image { r_id, svg_r_id, recolor }, Some(rels_xml)) = (&s.content, rels) {
+        let px = |pt: f64| (pt * SVG_RASTER_DPI / 72.0).ceil().max(1.0) as u32;
+        let (width_px, height_px) = (px(s.width), px(s.height));
+        let rasterized = svg_r_id
+            .as_ref()
+            .and_then(|svg_r_id| read_slide_media(rels_xml, svg_r_id, archive))
+            .and_then(|(data, _)| crate::svg::rasterize(&data, width_px, height_px))
+            .map(|png| (png, "image/png"));
+        let resolved = rasterized.or_else(|| {
+            let (data, path) = read_slide_media(rels_xml, r_id, archive).or_else(|| {
+                let path = slide_media_path(rels_xml, r_id)?;
+                let data = deferred_assets::find_supplied(supplied, &path)?.to_vec();
+                Some((data, path))
+            })?;
+            match guess_mime(&path) {
+                "image/svg+xml" => match crate::svg::rasterize(&data, width_px, height_px) {
+                    Some(png) => Some((png, "image/png")),
+                    None => Some((data, "image/svg+xml")),
+                },
+                mime => Some((data, mime)),
             }
+        });
+        // 色の置き換え効果はデコードしたビットマップに適用してPNGにする
+        let resolved = resolved.map(|(data, mime)| match recolor.and_then(|r| r.apply(&data)) {
+            Some(png) => (png, "image/png"),
+            None => (data, mime),
+        });
+        if let Some((data, mime)) = resolved {
+            s.content = ShapeContent::ImageData {
+                data,
+                mime_type: mime.to_string(),
+            };
+        }
+    }
+    if let ShapeContent::Image { r_id, .. } = &s.content {
+        let path = rels.as_deref().and_then(|rels_xml| slide_media_path(rels_xml, r_id));
+        let warning = DeferredAsset { relationship_id: r_id.clone(), path }.warning();
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
         }
     }
 
@@ -503,6 +1902,27 @@ fn resolve_shape_images(
     s
 }
 
+/// スライドのリレーションシップIDが指すメディアのZIP内のパス
+fn slide_media_path(rels_xml: &str, r_id: &str) -> Option<String> {
+    let target = resolve_relationship(rels_xml, r_id)?;
+    let path = match target.strip_prefix('/') {
+        Some(absolute) => absolute.to_string(),
+        None => format!("ppt/slides/{}", target),
+    };
+    Some(normalize_zip_path(&path))
+}
+
+/// スライドのリレーションシップIDが指すメディアを読み込み、データとZIP内のパスを返す
+fn read_slide_media(
+    rels_xml: &str,
+    r_id: &str,
+    archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
+) -> Option<(Vec<u8>, String)> {
+    let path = slide_media_path(rels_xml, r_id)?;
+    let data = read_zip_entry_bytes(archive, &path).ok()?;
+    Some((data, path))
+}
+
 /// ファイルパスからMIMEタイプを推測
 fn guess_mime(path: &str) -> &'static str {
     let lower = path.to_lowercase();
@@ -542,7 +1962,8 @@ fn parse_slide_background_full(
     let mut cur_grad_pos: f64 = 0.0;
     let mut blip_r_id = String::new();
     let mut in_gs = false;
-    let mut pending_gs_color: Option<Color> = None;
+    let mut color_builder = ColorBuilder::default();
+    let mut grad_tile: Option<GradientTileRect> = None;
 
     loop {
         match reader.read_event_into(&mut buf) {
@@ -555,7 +1976,9 @@ fn parse_slide_background_full(
                     b"gradFill" if in_bg_pr => {
                         in_grad_fill = true;
                         grad_stops.clear();
+                        grad_tile = None;
                     }
+                    b"tileRect" if in_grad_fill => grad_tile = Some(parse_tile_rect(e)),
                     b"blipFill" if in_bg_pr => in_blip_fill = true,
                     b"blip" if in_blip_fill => {
                         for attr in e.attributes().flatten() {
@@ -567,7 +1990,6 @@ fn parse_slide_background_full(
                     }
                     b"gs" if in_grad_fill => {
                         in_gs = true;
-                        pending_gs_color = None;
                         for attr in e.attributes().flatten() {
                             if attr.key.as_ref() == b"pos" {
                                 cur_grad_pos = String::from_utf8_lossy(&attr.value)
@@ -577,24 +1999,17 @@ fn parse_slide_background_full(
                             }
                         }
                     }
-                    // Handle srgbClr/schemeClr as Start elements (with child modifiers)
-                    b"srgbClr" | b"schemeClr" if in_solid_fill || in_gs => {
-                        let color = parse_color_element_themed(e, theme_colors);
-                        if let Some(c) = color {
-                            if in_solid_fill {
-                                return Some(SlideBg::Solid(c));
-                            }
-                            if in_gs {
-                                pending_gs_color = Some(c);
-                            }
-                        }
+                    // Color elements as Start elements (with child modifiers)
+                    clr if is_color_element(clr) && (in_solid_fill || in_gs) => {
+                        color_builder.start(e, theme_colors);
                     }
                     _ => {}
                 }
             }
             Ok(quick_xml::events::Event::Empty(ref e)) => {
                 let local = e.local_name();
-                if (in_solid_fill || in_gs) && (local.as_ref() == b"srgbClr" || local.as_ref() == b"schemeClr") {
+                color_builder.modify(e);
+                if (in_solid_fill || in_gs) && is_color_element(local.as_ref()) {
                     let color = parse_color_element_themed(e, theme_colors);
                     if let Some(c) = color {
                         if in_gs {
@@ -623,10 +2038,15 @@ fn parse_slide_background_full(
                             let ang_60k = String::from_utf8_lossy(&attr.value)
                                 .parse::<f64>()
                                 .unwrap_or(0.0);
-                            grad_angle = ang_60k / 60000.0 * std::f64::consts::PI / 180.0;
+                            grad_angle = normalize_gradient_angle(
+                                ang_60k / 60000.0 * std::f64::consts::PI / 180.0,
+                            );
                         }
                     }
                 }
+                if local.as_ref() == b"tileRect" && in_grad_fill {
+                    grad_tile = Some(parse_tile_rect(e));
+                }
             }
             Ok(quick_xml::events::Event::End(ref e)) => {
                 let local = e.local_name();
@@ -655,7 +2075,7 @@ fn parse_slide_background_full(
                         }
                         if !grad_stops.is_empty() {
                             return Some(SlideBg::Gradient {
-                                stops: grad_stops,
+                                stops: normalize_gradient_stops(&grad_stops, grad_angle, grad_tile),
                                 angle: grad_angle,
                             });
                         }
@@ -666,22 +2086,24 @@ fn parse_slide_background_full(
                     b"gradFill" if in_bg_pr => {
                         if !grad_stops.is_empty() {
                             return Some(SlideBg::Gradient {
-                                stops: grad_stops,
+                                stops: normalize_gradient_stops(&grad_stops, grad_angle, grad_tile),
                                 angle: grad_angle,
                             });
                         }
                         in_grad_fill = false;
                     }
                     b"blipFill" => in_blip_fill = false,
-                    b"gs" => {
-                        // Push pending gradient stop color from Start-element schemeClr/srgbClr
-                        if let Some(c) = pending_gs_color.take() {
-                            grad_stops.push(GradientStop {
+                    b"gs" => in_gs = false,
+                    clr if is_color_element(clr) => {
+                        // Start-element color with its modifiers applied
+                        match color_builder.finish() {
+                            Some(c) if in_gs => grad_stops.push(GradientStop {
                                 position: cur_grad_pos,
                                 color: c,
-                            });
+                            }),
+                            Some(c) if in_solid_fill => return Some(SlideBg::Solid(c)),
+                            _ => {}
                         }
-                        in_gs = false;
                     }
                     _ => {}
                 }
@@ -754,8 +2176,8 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
     let mut in_sp = false;       // <p:sp>
     let mut in_pic = false;      // <p:pic>
     let mut in_cxn = false;      // <p:cxnSp>
-    let mut in_grp = false;      // <p:grpSp>
-    let mut grp_depth = 0u32;
+    let mut in_ole_obj = false;  // <p:oleObj>（プレビューの p:pic は detect_and_render_ole_objects で描画）
+    let mut groups: Vec<GroupFrame> = Vec::new(); // 入れ子の <p:grpSp>
     let mut shape_depth = 0u32;
 
     // Current shape state
@@ -765,12 +2187,14 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
     let mut cur_h: f64 = 0.0;
     let mut cur_fill: Option<ShapeFill> = None;
     let mut cur_outline: Option<(Color, f64)> = None;
+    let mut cur_ln_props = LineProps::default();
     let mut cur_rotation: f64 = 0.0;
     let mut cur_shadow: Option<ShadowEffect> = None;
     let mut cur_paragraphs: Vec<ShapeParagraph> = Vec::new();
     let mut cur_runs: Vec<TextRun> = Vec::new();
     let mut cur_align = TextAlign::Left;
-    let mut cur_bullet: Option<String> = None;
+    let mut cur_bullet = BulletProps::default();
+    let mut in_bu_clr = false;
     let mut cur_level: u32 = 0;
     let mut cur_text = String::new();
     let mut cur_font_size: f64 = 18.0;
@@ -778,8 +2202,21 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
     let mut cur_italic = false;
     let mut cur_color: Option<Color> = None;
     let mut cur_font_name: Option<String> = None;
+    let mut cur_strike = false;
+    let mut cur_baseline_shift = 0.0f64;
+    let mut cur_letter_spacing = 0.0f64;
+    let mut cur_underline: Option<UnderlineStyle> = None;
+    let mut in_ufill = false;    // <a:uFill>（下線の色）
+    let mut cur_highlight: Option<Color> = None;
+    let mut in_highlight = false; // <a:highlight>（蛍光ペンの色）
+    let mut cur_hyperlink = false; // <a:hlinkClick>（ランのリンク）
     let mut in_text = false;
     let mut cur_r_id = String::new();  // image rId
+    let mut cur_svg_r_id: Option<String> = None; // asvg:svgBlip のSVG画像 rId
+    let mut cur_recolor: Option<ImageRecolor> = None; // a:blip の色の置き換え効果
+    let mut in_duotone = false; // <a:duotone>（子の2つの色要素を集める）
+    let mut duotone_colors: Vec<Color> = Vec::new();
+    let mut cur_field: Option<(Option<TextField>, usize)> = None; // <a:fld> の種別と開始位置のラン
 
     // For tracking sp offset/extent in xfrm
     let mut in_xfrm = false;
@@ -794,16 +2231,15 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
     let mut grad_angle: f64 = 0.0;
     let mut cur_grad_pos: f64 = 0.0;
     let mut in_gs = false;
+    let mut grad_tile: Option<GradientTileRect> = None;
+    let mut grad_rot_with_shape = true;
     let mut in_effect_lst = false;
     let mut in_outer_shdw = false;
     let mut shdw_color: Option<Color> = None;
     let mut shdw_blur: f64 = 0.0;
     let mut shdw_dist: f64 = 0.0;
     let mut shdw_dir: f64 = 0.0;
-
-    // Group shape offset for coordinate transform
-    let mut grp_off_x: f64 = 0.0;
-    let mut grp_off_y: f64 = 0.0;
+    let mut color_builder = ColorBuilder::default();
 
     // 3D effects and geometry
     let mut cur_has_3d = false;
@@ -820,6 +2256,7 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
     // blipFill on shape (image texture fill)
     let mut in_sp_blip_fill = false;
     let mut cur_fill_blip_r_id = String::new();
+    let mut cur_fill_blip_mode = BlipFillMode::default();
 
     // Style references (p:style > a:fillRef / a:lnRef)
     let mut in_style = false;
@@ -834,6 +2271,11 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
     let mut text_margin_right: f64 = 4.0;
     let mut text_margin_bottom: f64 = 4.0;
 
+    // Placeholder (p:nvPr > p:ph)
+    let mut cur_placeholder: Option<Placeholder> = None;
+    let mut cur_autofit = TextAutofit::None;
+    let mut cur_text_warp: Option<String> = None;
+
     macro_rules! reset_shape_state {
         () => {
             cur_x = 0.0;
@@ -842,12 +2284,13 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
             cur_h = 0.0;
             cur_fill = None;
             cur_outline = None;
+            cur_ln_props = LineProps::default();
             cur_rotation = 0.0;
             cur_shadow = None;
             cur_paragraphs = Vec::new();
             cur_runs = Vec::new();
             cur_align = TextAlign::Left;
-            cur_bullet = None;
+            cur_bullet = BulletProps::default();
             cur_level = 0;
             cur_text = String::new();
             cur_font_size = 18.0;
@@ -857,6 +2300,8 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
             cur_font_name = None;
             in_text = false;
             cur_r_id = String::new();
+            cur_svg_r_id = None;
+            cur_recolor = None;
             in_xfrm = false;
             in_sp_pr = false;
             in_ln = false;
@@ -869,12 +2314,15 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
             grad_angle = 0.0;
             cur_grad_pos = 0.0;
             in_gs = false;
+            grad_tile = None;
+            grad_rot_with_shape = true;
             in_effect_lst = false;
             in_outer_shdw = false;
             shdw_color = None;
             shdw_blur = 0.0;
             shdw_dist = 0.0;
             shdw_dir = 0.0;
+            color_builder = ColorBuilder::default();
             cur_has_3d = false;
             cur_preset_geom = None;
             in_cust_geom = false;
@@ -885,6 +2333,7 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
             cust_geom_pts.clear();
             in_sp_blip_fill = false;
             cur_fill_blip_r_id = String::new();
+            cur_fill_blip_mode = BlipFillMode::default();
             in_style = false;
             in_fill_ref = false;
             in_ln_ref = false;
@@ -894,9 +2343,52 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
             text_margin_top = 4.0;
             text_margin_right = 4.0;
             text_margin_bottom = 4.0;
+            cur_placeholder = None;
+            cur_autofit = TextAutofit::None;
+            cur_text_warp = None;
         };
     }
 
+    // 解決した色を現在の文脈（箇条書き・グラデーション停止点・塗り/線/文字/影/下線・スタイル参照）へ割り当てる
+    macro_rules! assign_color {
+        ($c:expr) => {{
+            let c: Color = $c;
+            if in_duotone {
+                duotone_colors.push(c);
+            } else if in_bu_clr {
+                cur_bullet.color = Some(c);
+            } else if in_highlight {
+                cur_highlight = Some(c);
+            } else if in_gs {
+                grad_stops.push(GradientStop {
+                    position: cur_grad_pos,
+                    color: c,
+                });
+            } else if in_solid_fill {
+                match solid_fill_ctx {
+                    0 => cur_fill = Some(ShapeFill::Solid(c)),
+                    1 => {
+                        cur_outline = Some((c, cur_outline.map_or(1.0, |o| o.1)));
+                    }
+                    2 => cur_color = Some(c),
+                    3 => shdw_color = Some(c), // shadow
+                    4 => {
+                        if let Some(u) = cur_underline.as_mut() {
+                            u.color = Some(c);
+                        }
+                    }
+                    _ => {}
+                }
+            } else if in_outer_shdw {
+                shdw_color = Some(c);
+            } else if in_fill_ref {
+                style_fill_color = Some(c);
+            } else if in_ln_ref {
+                style_ln_color = Some(c);
+            }
+        }};
+    }
+
     loop {
         match reader.read_event_into(&mut buf) {
             Ok(quick_xml::events::Event::Start(ref e)) => {
@@ -905,12 +2397,13 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                 let local = name.as_ref();
 
                 match local {
+                    b"oleObj" => in_ole_obj = true,
                     b"sp" if !in_sp && !in_pic && !in_cxn => {
                         in_sp = true;
                         shape_depth = depth;
                         reset_shape_state!();
                     }
-                    b"pic" if !in_sp && !in_pic && !in_cxn => {
+                    b"pic" if !in_sp && !in_pic && !in_cxn && !in_ole_obj => {
                         in_pic = true;
                         shape_depth = depth;
                         reset_shape_state!();
@@ -920,16 +2413,37 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                         shape_depth = depth;
                         reset_shape_state!();
                     }
-                    b"grpSp" if !in_sp && !in_pic && !in_cxn && !in_grp => {
-                        in_grp = true;
-                        grp_depth = depth;
-                        grp_off_x = 0.0;
-                        grp_off_y = 0.0;
+                    b"grpSp" if !in_sp && !in_pic && !in_cxn => {
+                        let parent = groups.last().map_or(GroupTransform::IDENTITY, |g| g.transform);
+                        let parent_clip = groups.last().and_then(|g| g.clip);
+                        groups.push(GroupFrame {
+                            depth,
+                            parent,
+                            transform: parent,
+                            ch_off: None,
+                            ch_ext: None,
+                            parent_clip,
+                            clip: parent_clip,
+                        });
+                        // グループの a:xfrm は cur_x 等に読み込む
+                        cur_x = 0.0;
+                        cur_y = 0.0;
+                        cur_w = 0.0;
+                        cur_h = 0.0;
                     }
                     b"spPr" if in_sp || in_pic || in_cxn => {
                         in_sp_pr = true;
                     }
-                    b"grpSpPr" if in_grp && !in_sp && !in_pic && !in_cxn => {
+                    b"ph" if in_sp || in_pic => {
+                        cur_placeholder = Some(parse_placeholder(e));
+                    }
+                    b"normAutofit" if in_sp || in_pic => {
+                        cur_autofit = TextAutofit::shrink_from(e);
+                    }
+                    b"prstTxWarp" if in_sp => {
+                        cur_text_warp = parse_text_warp(e);
+                    }
+                    b"grpSpPr" if !groups.is_empty() && !in_sp && !in_pic && !in_cxn => {
                         // Group shape properties - get offset
                         in_sp_pr = true;
                     }
@@ -947,10 +2461,19 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                     }
                     b"ln" if in_sp_pr => {
                         in_ln = true;
+                        cur_ln_props.read_attrs(e);
+                    }
+                    b"uFill" if in_rpr => {
+                        in_ufill = true;
+                    }
+                    b"highlight" if in_rpr => {
+                        in_highlight = true;
                     }
                     b"solidFill" => {
                         in_solid_fill = true;
-                        if in_rpr {
+                        if in_ufill {
+                            solid_fill_ctx = 4; // underline color
+                        } else if in_rpr {
                             solid_fill_ctx = 2; // text color
                         } else if in_ln {
                             solid_fill_ctx = 1; // outline
@@ -965,7 +2488,16 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                         _grad_fill_ctx = 0;
                         grad_stops.clear();
                         grad_angle = 0.0;
+                        grad_tile = None;
+                        grad_rot_with_shape = true;
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"rotWithShape" {
+                                let v = String::from_utf8_lossy(&attr.value);
+                                grad_rot_with_shape = !(v == "0" || v == "false");
+                            }
+                        }
                     }
+                    b"tileRect" if in_grad_fill => grad_tile = Some(parse_tile_rect(e)),
                     b"gs" if in_grad_fill => {
                         in_gs = true;
                         for attr in e.attributes().flatten() {
@@ -1063,6 +2595,7 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                     }
                     b"pPr" if (in_sp || in_pic) && !in_sp_pr => {
                         // Paragraph properties
+                        cur_bullet.read_indent_attrs(e);
                         for attr in e.attributes().flatten() {
                             match attr.key.as_ref() {
                                 b"algn" => {
@@ -1082,11 +2615,42 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                             }
                         }
                     }
+                    b"r" | b"fld" if (in_sp || in_pic) && !in_sp_pr => {
+                        // 取り消し線・上付き/下付きはラン単位で引き継がない
+                        cur_strike = false;
+                        cur_baseline_shift = 0.0;
+                        cur_letter_spacing = 0.0;
+                        cur_underline = None;
+                        cur_highlight = None;
+                        cur_hyperlink = false;
+                        if local == b"fld" {
+                            let field_type = e
+                                .attributes()
+                                .flatten()
+                                .find(|a| a.key.as_ref() == b"type")
+                                .map(|a| String::from_utf8_lossy(&a.value).to_string())
+                                .unwrap_or_default();
+                            cur_field = Some((TextField::from_type(&field_type), cur_runs.len()));
+                        }
+                    }
                     b"rPr" if (in_sp || in_pic) && !in_sp_pr => {
                         in_rpr = true;
+                        cur_font_name = None;
                         // Run properties
                         for attr in e.attributes().flatten() {
                             match attr.key.as_ref() {
+                                b"strike" => {
+                                    cur_strike = parse_strike_attr(&attr.value);
+                                }
+                                b"baseline" => {
+                                    cur_baseline_shift = parse_baseline_attr(&attr.value);
+                                }
+                                b"spc" => {
+                                    cur_letter_spacing = parse_spc_attr(&attr.value);
+                                }
+                                b"u" => {
+                                    cur_underline = parse_underline_attr(&attr.value);
+                                }
                                 b"sz" => {
                                     // Font size in hundredths of a point
                                     cur_font_size = String::from_utf8_lossy(&attr.value)
@@ -1109,6 +2673,9 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                     b"t" if (in_sp || in_pic) && !in_sp_pr => {
                         in_text = true;
                     }
+                    b"hlinkClick" if in_rpr => {
+                        cur_hyperlink = true;
+                    }
                     b"br" if (in_sp || in_pic) && !in_sp_pr => {
                         // Line break Start element - insert newline run
                         cur_runs.push(TextRun {
@@ -1118,13 +2685,20 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                             italic: cur_italic,
                             color: cur_color,
                             font_name: cur_font_name.clone(),
+                            strikethrough: cur_strike,
+                            baseline_shift: cur_baseline_shift,
+                            letter_spacing: cur_letter_spacing,
+                            underline: cur_underline,
+                            highlight: cur_highlight,
+                            field: None,
+                            hyperlink: cur_hyperlink,
                         });
                     }
                     b"p" if (in_sp || in_pic) && !in_sp_pr && depth > shape_depth + 1 => {
                         // New paragraph in text body
                         cur_runs.clear();
                         cur_align = TextAlign::Left;
-                        cur_bullet = None;
+                        cur_bullet = BulletProps::default();
                         cur_level = 0;
                         cur_font_size = 18.0;
                         cur_bold = false;
@@ -1135,6 +2709,17 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                     b"blipFill" if in_pic => {
                         // Image fill - look for blip with r:embed
                     }
+                    b"buClr" if (in_sp || in_pic) && !in_sp_pr => {
+                        in_bu_clr = true;
+                    }
+                    b"duotone" if in_pic => {
+                        in_duotone = true;
+                        duotone_colors.clear();
+                    }
+                    clr if is_color_element(clr) && (in_bu_clr || in_highlight || in_solid_fill || in_gs || in_outer_shdw || in_duotone) => {
+                        // 子要素の色変換は終了タグで適用
+                        color_builder.start(e, theme_colors);
+                    }
                     b"blipFill" if in_sp && in_sp_pr => {
                         // Image texture fill on shape
                         in_sp_blip_fill = true;
@@ -1155,6 +2740,12 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                             }
                         }
                     }
+                    b"fillRect" if in_sp_blip_fill => {
+                        cur_fill_blip_mode = BlipFillMode::stretch_from(e);
+                    }
+                    b"tile" if in_sp_blip_fill => {
+                        cur_fill_blip_mode = BlipFillMode::tile_from(e);
+                    }
                     // Style references (p:style)
                     b"style" if (in_sp || in_pic) && !in_sp_pr => {
                         in_style = true;
@@ -1165,21 +2756,14 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                     b"lnRef" if in_style => {
                         in_ln_ref = true;
                     }
-                    b"schemeClr" if in_fill_ref => {
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"val" {
-                                let val = String::from_utf8_lossy(&attr.value).to_string();
-                                style_fill_color = resolve_scheme_color(&val, theme_colors);
-                            }
-                        }
-                    }
-                    b"schemeClr" if in_ln_ref => {
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"val" {
-                                let val = String::from_utf8_lossy(&attr.value).to_string();
-                                style_ln_color = resolve_scheme_color(&val, theme_colors);
-                            }
-                        }
+                    b"schemeClr" if in_fill_ref || in_ln_ref => {
+                        // lnRef の既定線色は accent1 + shade のように子要素で変換されることが多い
+                        color_builder.begin(
+                            e.attributes()
+                                .flatten()
+                                .find(|a| a.key.as_ref() == b"val")
+                                .and_then(|a| resolve_scheme_color(&String::from_utf8_lossy(&a.value), theme_colors)),
+                        );
                     }
                     _ => {}
                 }
@@ -1189,6 +2773,23 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                 let name = e.local_name();
                 let local = name.as_ref();
 
+                // 線のスタイル（破線・線端・角）
+                match local {
+                    b"ln" if in_sp_pr => cur_ln_props.read_attrs(e),
+                    b"prstDash" if in_ln => {
+                        let val = e
+                            .attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == b"val")
+                            .map(|a| String::from_utf8_lossy(&a.value).to_string());
+                        cur_ln_props.style.dash = val.map_or(DashPattern::Solid, |v| DashPattern::from_preset(&v));
+                    }
+                    b"round" if in_ln => cur_ln_props.style.join = LineJoin::Round,
+                    b"bevel" if in_ln => cur_ln_props.style.join = LineJoin::Bevel,
+                    b"miter" if in_ln => cur_ln_props.style.join = LineJoin::Miter,
+                    _ => {}
+                }
+
                 if in_xfrm {
                     match local {
                         b"off" => {
@@ -1229,43 +2830,55 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                                 }
                             }
                         }
+                        b"chOff" | b"chExt" if !groups.is_empty() && !in_sp && !in_pic && !in_cxn => {
+                            // グループの子座標系（a:chOff / a:chExt）
+                            let (key_x, key_y): (&[u8], &[u8]) =
+                                if local == b"chOff" { (b"x", b"y") } else { (b"cx", b"cy") };
+                            let mut value = (0.0, 0.0);
+                            for attr in e.attributes().flatten() {
+                                let v = String::from_utf8_lossy(&attr.value).parse::<f64>().unwrap_or(0.0) / EMU_PER_PT;
+                                if attr.key.as_ref() == key_x {
+                                    value.0 = v;
+                                } else if attr.key.as_ref() == key_y {
+                                    value.1 = v;
+                                }
+                            }
+                            if let Some(group) = groups.last_mut() {
+                                if local == b"chOff" {
+                                    group.ch_off = Some(value);
+                                } else {
+                                    group.ch_ext = Some(value);
+                                }
+                            }
+                        }
                         _ => {}
                     }
                 }
 
-                // Color elements in solidFill
-                if in_solid_fill {
-                    let color = parse_color_element_themed(e, theme_colors);
-                    if let Some(c) = color {
-                        match solid_fill_ctx {
-                            0 => cur_fill = Some(ShapeFill::Solid(c)),
-                            1 => {
-                                cur_outline = Some((c, cur_outline.map_or(1.0, |o| o.1)));
+                // Placeholder and its prompt marker, text autofit (bodyPr children)
+                if in_sp || in_pic {
+                    match local {
+                        b"ph" => cur_placeholder = Some(parse_placeholder(e)),
+                        b"normAutofit" => cur_autofit = TextAutofit::shrink_from(e),
+                        b"spAutoFit" => cur_autofit = TextAutofit::ResizeShape,
+                        b"prstTxWarp" if in_sp => cur_text_warp = parse_text_warp(e),
+                        b"prompt" | b"custPrompt" => {
+                            if let Some(ph) = cur_placeholder.as_mut() {
+                                ph.is_prompt = true;
                             }
-                            2 => cur_color = Some(c),
-                            3 => shdw_color = Some(c), // shadow
-                            _ => {}
                         }
+                        _ => {}
                     }
                 }
 
-                // Gradient stop colors
-                if in_gs {
-                    let color = parse_color_element_themed(e, theme_colors);
-                    if let Some(c) = color {
-                        grad_stops.push(GradientStop {
-                            position: cur_grad_pos,
-                            color: c,
-                        });
-                    }
-                }
+                // Color modifiers inside a Start color element (lumMod / tint ...)
+                color_builder.modify(e);
 
-                // Shadow colors (in outerShdw directly)
-                if in_outer_shdw && !in_solid_fill {
-                    let color = parse_color_element_themed(e, theme_colors);
-                    if let Some(c) = color {
-                        shdw_color = Some(c);
-                    }
+                // Color elements in solidFill / gradient stops / shadow / bullet color / highlight
+                if let Some(c) = parse_color_element_themed(e, theme_colors)
+                    .filter(|_| in_solid_fill || in_gs || in_outer_shdw || in_bu_clr || in_highlight || in_duotone)
+                {
+                    assign_color!(c);
                 }
 
                 // Linear gradient angle
@@ -1275,15 +2888,44 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                             let ang_60k = String::from_utf8_lossy(&attr.value)
                                 .parse::<f64>()
                                 .unwrap_or(0.0);
-                            grad_angle = ang_60k / 60000.0 * std::f64::consts::PI / 180.0;
+                            grad_angle = normalize_gradient_angle(
+                                ang_60k / 60000.0 * std::f64::consts::PI / 180.0,
+                            );
                         }
                     }
                 }
+                if local == b"tileRect" && in_grad_fill {
+                    grad_tile = Some(parse_tile_rect(e));
+                }
+
+                // ランのフォント（a:latin を優先し、無ければ a:ea / a:cs。テーマフォントの参照は後で解決）
+                if in_rpr && matches!(local, b"latin" | b"ea" | b"cs") && (local == b"latin" || cur_font_name.is_none()) {
+                    if let Some(typeface) = typeface_attr(e) {
+                        cur_font_name = Some(typeface);
+                    }
+                }
+
+                if local == b"hlinkClick" && in_rpr {
+                    cur_hyperlink = true;
+                }
 
                 // Run properties (empty element variant)
                 if local == b"rPr" && (in_sp || in_pic) && !in_sp_pr {
+                    cur_font_name = None;
                     for attr in e.attributes().flatten() {
                         match attr.key.as_ref() {
+                            b"strike" => {
+                                cur_strike = parse_strike_attr(&attr.value);
+                            }
+                            b"baseline" => {
+                                cur_baseline_shift = parse_baseline_attr(&attr.value);
+                            }
+                            b"spc" => {
+                                cur_letter_spacing = parse_spc_attr(&attr.value);
+                            }
+                            b"u" => {
+                                cur_underline = parse_underline_attr(&attr.value);
+                            }
                             b"sz" => {
                                 cur_font_size = String::from_utf8_lossy(&attr.value)
                                     .parse::<f64>()
@@ -1303,6 +2945,7 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
 
                 // Paragraph properties (empty variant)
                 if local == b"pPr" && (in_sp || in_pic) && !in_sp_pr {
+                    cur_bullet.read_indent_attrs(e);
                     for attr in e.attributes().flatten() {
                         match attr.key.as_ref() {
                             b"algn" => {
@@ -1356,19 +2999,9 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                     }
                 }
 
-                // Bullet markers
-                if (local == b"buChar" || local == b"buAutoNum") && (in_sp || in_pic) {
-                    if local == b"buChar" {
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"char" {
-                                let raw = String::from_utf8_lossy(&attr.value).to_string();
-                                // Map Wingdings/Symbol PUA characters to standard Unicode equivalents
-                                cur_bullet = Some(normalize_bullet_char(&raw));
-                            }
-                        }
-                    } else {
-                        cur_bullet = Some(DEFAULT_BULLET.to_string());
-                    }
+                // Bullet markers (buChar / buAutoNum / buNone / buSzPct / buClr)
+                if (in_sp || in_pic) && !in_bu_clr {
+                    cur_bullet.read_bullet_element(e);
                 }
 
                 // Line break <a:br/> (empty variant) - insert newline in text
@@ -1380,6 +3013,13 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                         italic: cur_italic,
                         color: cur_color,
                         font_name: cur_font_name.clone(),
+                        strikethrough: cur_strike,
+                        baseline_shift: cur_baseline_shift,
+                        letter_spacing: cur_letter_spacing,
+                        underline: cur_underline,
+                        highlight: cur_highlight,
+                        field: None,
+                        hyperlink: cur_hyperlink,
                     });
                 }
 
@@ -1393,6 +3033,23 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                     }
                 }
 
+                // 画像の色の置き換え効果（a:blip > a:grayscl / a:biLevel）
+                if local == b"grayscl" && in_pic {
+                    cur_recolor = Some(ImageRecolor::Grayscale);
+                }
+                if local == b"biLevel" && in_pic {
+                    cur_recolor = Some(ImageRecolor::bi_level_from(e));
+                }
+
+                // SVG image (a:blip > a:extLst > a:ext > asvg:svgBlip)
+                if local == b"svgBlip" && in_pic {
+                    cur_svg_r_id = e
+                        .attributes()
+                        .flatten()
+                        .find(|attr| attr.key.local_name().as_ref() == b"embed")
+                        .map(|attr| String::from_utf8_lossy(&attr.value).to_string());
+                }
+
                 // Shape fill blip (empty variant)
                 if local == b"blip" && in_sp_blip_fill {
                     for attr in e.attributes().flatten() {
@@ -1402,6 +3059,14 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                         }
                     }
                 }
+                // 画像の塗りの配置方法（a:stretch > a:fillRect / a:tile）
+                if in_sp_blip_fill {
+                    match local {
+                        b"fillRect" => cur_fill_blip_mode = BlipFillMode::stretch_from(e),
+                        b"tile" => cur_fill_blip_mode = BlipFillMode::tile_from(e),
+                        _ => {}
+                    }
+                }
 
                 // Style schemeClr (empty variant)
                 if local == b"schemeClr" && (in_fill_ref || in_ln_ref) {
@@ -1466,6 +3131,7 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                 let local = name.as_ref();
 
                 match local {
+                    b"oleObj" => in_ole_obj = false,
                     b"sp" if in_sp && depth == shape_depth => {
                         // Build shadow from collected shadow data
                         if in_outer_shdw || shdw_color.is_some() {
@@ -1481,7 +3147,7 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                         // Finalize gradient fill if pending
                         if cur_fill.is_none() && !grad_stops.is_empty() {
                             cur_fill = Some(ShapeFill::Gradient {
-                                stops: grad_stops.clone(),
+                                stops: normalize_gradient_stops(&grad_stops, grad_angle, grad_tile),
                                 angle: grad_angle,
                             });
                         }
@@ -1504,14 +3170,18 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                                 paragraphs: cur_paragraphs.clone(),
                             }
                         };
+                        let (x, y, width, height) = groups
+                            .last()
+                            .map_or(GroupTransform::IDENTITY, |g| g.transform)
+                            .apply_rect((cur_x, cur_y, cur_w, cur_h));
                         shapes.push(SlideShape {
-                            x: cur_x + grp_off_x,
-                            y: cur_y + grp_off_y,
-                            width: cur_w,
-                            height: cur_h,
+                            x,
+                            y,
+                            width,
+                            height,
                             content,
                             fill: cur_fill.clone(),
-                            outline: cur_outline,
+                            outline: cur_outline.map(|(color, width)| cur_ln_props.outline(color, width)),
                             rotation: cur_rotation,
                             shadow: cur_shadow.clone(),
                             has_3d: cur_has_3d,
@@ -1519,10 +3189,15 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                             custom_path: if cust_path_cmds.is_empty() { None } else { Some(cust_path_cmds.clone()) },
                             custom_path_viewport: if cust_path_cmds.is_empty() { None } else { Some((cust_path_w, cust_path_h)) },
                             fill_image_r_id: if cur_fill_blip_r_id.is_empty() { None } else { Some(cur_fill_blip_r_id.clone()) },
+                            fill_image_mode: cur_fill_blip_mode,
                             text_margin_left,
                             text_margin_top,
                             text_margin_right,
                             text_margin_bottom,
+                            placeholder: cur_placeholder.clone(),
+                            autofit: cur_autofit,
+                            text_warp: cur_text_warp.clone(),
+                            clip: groups.last().and_then(|g| g.clip),
                         });
                         in_sp = false;
                     }
@@ -1541,18 +3216,24 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                         let content = if !cur_r_id.is_empty() {
                             ShapeContent::Image {
                                 r_id: cur_r_id.clone(),
+                                svg_r_id: cur_svg_r_id.clone(),
+                                recolor: cur_recolor,
                             }
                         } else {
                             ShapeContent::Empty
                         };
+                        let (x, y, width, height) = groups
+                            .last()
+                            .map_or(GroupTransform::IDENTITY, |g| g.transform)
+                            .apply_rect((cur_x, cur_y, cur_w, cur_h));
                         shapes.push(SlideShape {
-                            x: cur_x + grp_off_x,
-                            y: cur_y + grp_off_y,
-                            width: cur_w,
-                            height: cur_h,
+                            x,
+                            y,
+                            width,
+                            height,
                             content,
                             fill: cur_fill.clone(),
-                            outline: cur_outline,
+                            outline: cur_outline.map(|(color, width)| cur_ln_props.outline(color, width)),
                             rotation: cur_rotation,
                             shadow: cur_shadow.clone(),
                             has_3d: cur_has_3d,
@@ -1560,22 +3241,34 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                             custom_path: None,
                             custom_path_viewport: None,
                             fill_image_r_id: None,
+                            fill_image_mode: BlipFillMode::default(),
                             text_margin_left,
                             text_margin_top,
                             text_margin_right,
                             text_margin_bottom,
+                            placeholder: cur_placeholder.clone(),
+                            autofit: cur_autofit,
+                            text_warp: None,
+                            clip: groups.last().and_then(|g| g.clip),
                         });
                         in_pic = false;
                     }
                     b"cxnSp" if in_cxn && depth == shape_depth => {
+                        let (x, y, width, height) = groups
+                            .last()
+                            .map_or(GroupTransform::IDENTITY, |g| g.transform)
+                            .apply_rect((cur_x, cur_y, cur_w, cur_h));
                         shapes.push(SlideShape {
-                            x: cur_x + grp_off_x,
-                            y: cur_y + grp_off_y,
-                            width: cur_w,
-                            height: cur_h,
+                            x,
+                            y,
+                            width,
+                            height,
                             content: ShapeContent::Connector,
                             fill: None,
-                            outline: cur_outline.or(Some((Color::BLACK, 1.0))),
+                            outline: Some(cur_outline.map_or(
+                                cur_ln_props.outline(Color::BLACK, 1.0),
+                                |(color, width)| cur_ln_props.outline(color, width),
+                            )),
                             rotation: cur_rotation,
                             shadow: None,
                             has_3d: false,
@@ -1583,17 +3276,48 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                             custom_path: None,
                             custom_path_viewport: None,
                             fill_image_r_id: None,
+                            fill_image_mode: BlipFillMode::default(),
                             text_margin_left: 4.0,
                             text_margin_top: 4.0,
                             text_margin_right: 4.0,
                             text_margin_bottom: 4.0,
+                            placeholder: None,
+                            autofit: TextAutofit::None,
+                            text_warp: None,
+                            clip: groups.last().and_then(|g| g.clip),
                         });
                         in_cxn = false;
                     }
-                    b"grpSp" if in_grp && depth == grp_depth => {
-                        in_grp = false;
-                        grp_off_x = 0.0;
-                        grp_off_y = 0.0;
+                    b"grpSp" if groups.last().is_some_and(|g| g.depth == depth) => {
+                        groups.pop();
+                    }
+                    b"grpSpPr" if groups.last().is_some_and(|g| g.depth + 1 == depth) && !in_sp && !in_pic && !in_cxn => {
+                        in_sp_pr = false;
+                        if let Some(group) = groups.last_mut() {
+                            // a:off/a:ext は親の座標系での配置先。子座標系の指定が無ければ同じ座標系とみなす
+                            let (off, ext) = ((cur_x, cur_y), (cur_w, cur_h));
+                            let local = GroupTransform::from_xfrm(
+                                off,
+                                ext,
+                                group.ch_off.unwrap_or(off),
+                                group.ch_ext.unwrap_or(ext),
+                            );
+                            group.transform = local.then(group.parent);
+                            // グループの範囲（子シェイプのクリップ領域）
+                            let (x, y, w, h) = group.parent.apply_rect((cur_x, cur_y, cur_w, cur_h));
+                            group.clip = if w > 0.0 && h > 0.0 {
+                                match group.parent_clip {
+                                    Some((px, py, pw, ph)) => {
+                                        let (x0, y0) = (x.max(px), y.max(py));
+                                        let (x1, y1) = ((x + w).min(px + pw), (y + h).min(py + ph));
+                                        Some((x0, y0, (x1 - x0).max(0.0), (y1 - y0).max(0.0)))
+                                    }
+                                    None => Some((x, y, w, h)),
+                                }
+                            } else {
+                                group.parent_clip
+                            };
+                        }
                     }
                     b"spPr" => {
                         in_sp_pr = false;
@@ -1644,8 +3368,15 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                     }
                     b"gradFill" => {
                         if in_sp_pr && !in_ln && !grad_stops.is_empty() {
+                            // rotWithShape="0" の場合は図形の回転を打ち消して
+                            // グラデーションの向きをページに対して固定する
+                            if !grad_rot_with_shape && cur_rotation != 0.0 {
+                                grad_angle = normalize_gradient_angle(
+                                    grad_angle - cur_rotation.to_radians(),
+                                );
+                            }
                             cur_fill = Some(ShapeFill::Gradient {
-                                stops: grad_stops.clone(),
+                                stops: normalize_gradient_stops(&grad_stops, grad_angle, grad_tile),
                                 angle: grad_angle,
                             });
                         }
@@ -1673,6 +3404,49 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                     b"rPr" => {
                         in_rpr = false;
                     }
+                    b"buClr" => {
+                        in_bu_clr = false;
+                    }
+                    b"duotone" if in_duotone => {
+                        in_duotone = false;
+                        if let [dark, light] = duotone_colors[..] {
+                            cur_recolor = Some(ImageRecolor::Duotone(dark, light));
+                        }
+                    }
+                    clr if is_color_element(clr) => {
+                        if let Some(c) = color_builder.finish() {
+                            assign_color!(c);
+                        }
+                    }
+                    b"uFill" => {
+                        in_ufill = false;
+                    }
+                    b"highlight" => {
+                        in_highlight = false;
+                    }
+                    b"fld" => {
+                        // 既知のフィールドはキャッシュされた表示文字列（<a:t>、無い場合もある）を1つのランにまとめ、
+                        // 変換時に計算した値で置き換える。未知のフィールドはキャッシュのまま残す
+                        if let Some((Some(field), start)) = cur_field.take() {
+                            let start = start.min(cur_runs.len());
+                            let cached: String = cur_runs.drain(start..).map(|r| r.text).collect();
+                            cur_runs.push(TextRun {
+                                text: cached,
+                                font_size: cur_font_size,
+                                bold: cur_bold,
+                                italic: cur_italic,
+                                color: cur_color,
+                                font_name: cur_font_name.clone(),
+                                strikethrough: cur_strike,
+                                baseline_shift: cur_baseline_shift,
+                                letter_spacing: cur_letter_spacing,
+                                underline: cur_underline,
+                                highlight: cur_highlight,
+                                field: Some(field),
+                                hyperlink: cur_hyperlink,
+                            });
+                        }
+                    }
                     b"t" => {
                         if in_text {
                             // Finish text run
@@ -1683,6 +3457,13 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                                 italic: cur_italic,
                                 color: cur_color,
                                 font_name: cur_font_name.clone(),
+                                strikethrough: cur_strike,
+                                baseline_shift: cur_baseline_shift,
+                                letter_spacing: cur_letter_spacing,
+                                underline: cur_underline,
+                                highlight: cur_highlight,
+                                field: None,
+                                hyperlink: cur_hyperlink,
                             });
                             cur_text.clear();
                             in_text = false;
@@ -1701,7 +3482,7 @@ fn parse_slide_shapes(xml: &str, theme_colors: &ThemeColors) -> Vec<SlideShape>
                             });
                         }
                         cur_runs.clear();
-                        cur_bullet = None;
+                        cur_bullet = BulletProps::default();
                     }
                     b"blipFill" if in_sp_blip_fill => {
                         in_sp_blip_fill = false;
@@ -1763,6 +3544,100 @@ fn normalize_bullet_char(raw: &str) -> String {
     mapped.to_string()
 }
 
+impl BulletProps {
+    /// a:pPr / a:lvlNpPr の marL・indent 属性（EMU）を読み取る
+    fn read_indent_attrs(&mut self, e: &quick_xml::events::BytesStart) {
+        for attr in e.attributes().flatten() {
+            let emu = || String::from_utf8_lossy(&attr.value).parse::<f64>().ok().map(|v| v / EMU_PER_PT);
+            match attr.key.as_ref() {
+                b"marL" => self.margin_left = emu().or(self.margin_left),
+                b"indent" => self.indent = emu().or(self.indent),
+                _ => {}
+            }
+        }
+    }
+
+    /// 箇条書きの子要素（buChar / buAutoNum / buNone / buSzPct）を読み取る
+    fn read_bullet_element(&mut self, e: &quick_xml::events::BytesStart) {
+        let attr_value = |name: &[u8]| {
+            e.attributes()
+                .flatten()
+                .find(|a| a.key.as_ref() == name)
+                .map(|a| a.unescape_value().map(|v| v.to_string()).unwrap_or_default())
+        };
+        match e.local_name().as_ref() {
+            // Map Wingdings/Symbol PUA characters to standard Unicode equivalents
+            b"buChar" => {
+                let raw = attr_value(b"char").unwrap_or_default();
+                self.kind = Some(BulletKind::Char(normalize_bullet_char(&raw)));
+            }
+            b"buAutoNum" => {
+                self.kind = Some(BulletKind::AutoNum {
+                    scheme: attr_value(b"type").unwrap_or_else(|| "arabicPeriod".to_string()),
+                    start_at: attr_value(b"startAt").and_then(|v| v.parse().ok()).unwrap_or(1),
+                });
+            }
+            b"buNone" => self.kind = Some(BulletKind::None),
+            // 1/1000 パーセント単位（100000 = 100%）
+            b"buSzPct" => {
+                self.size_pct = attr_value(b"val").and_then(|v| v.parse::<f64>().ok()).map(|v| v / 100000.0);
+            }
+            _ => {}
+        }
+    }
+
+    /// 未指定の項目を `base`（マスターのレベル別指定）で補う
+    fn inherit(&self, base: &BulletProps) -> BulletProps {
+        BulletProps {
+            kind: self.kind.clone().or_else(|| base.kind.clone()),
+            size_pct: self.size_pct.or(base.size_pct),
+            color: self.color.or(base.color),
+            margin_left: self.margin_left.or(base.margin_left),
+            indent: self.indent.or(base.indent),
+        }
+    }
+}
+
+/// 自動番号の書式（a:buAutoNum の type）で番号を文字列化
+fn format_auto_number(scheme: &str, n: u32) -> String {
+    let n = n.max(1);
+    let body = if scheme.starts_with("alphaLc") || scheme.starts_with("alphaUc") {
+        let letter = (b'a' + ((n - 1) % 26) as u8) as char;
+        let s = letter.to_string().repeat(((n - 1) / 26 + 1) as usize);
+        if scheme.starts_with("alphaUc") { s.to_uppercase() } else { s }
+    } else if scheme.starts_with("romanLc") || scheme.starts_with("romanUc") {
+        let s = to_roman(n);
+        if scheme.starts_with("romanLc") { s.to_lowercase() } else { s }
+    } else {
+        n.to_string()
+    };
+    if scheme.ends_with("ParenBoth") {
+        format!("({})", body)
+    } else if scheme.ends_with("ParenR") {
+        format!("{})", body)
+    } else if scheme.ends_with("Plain") {
+        body
+    } else {
+        format!("{}.", body)
+    }
+}
+
+/// ローマ数字（大文字）
+fn to_roman(mut n: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"),
+        (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+    ];
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
 fn parse_color_element_themed(e: &quick_xml::events::BytesStart, theme: &ThemeColors) -> Option<Color> {
     let local = e.local_name();
     match local.as_ref() {
@@ -1820,10 +3695,243 @@ fn parse_color_element_themed(e: &quick_xml::events::BytesStart, theme: &ThemeCo
             }
             None
         }
+        b"scrgbClr" => {
+            // 各成分は 0..100000 の割合
+            let component = |key: &[u8]| percent_attr(e, key).unwrap_or(0.0);
+            Some(rgb_f_to_color([component(b"r"), component(b"g"), component(b"b")]))
+        }
+        b"hslClr" => {
+            // hue は 1/60000 度、sat / lum は 0..100000 の割合
+            let hue = attr_f64(e, b"hue").unwrap_or(0.0) / 60000.0;
+            let sat = percent_attr(e, b"sat").unwrap_or(0.0);
+            let lum = percent_attr(e, b"lum").unwrap_or(0.0);
+            Some(rgb_f_to_color(hsl_to_rgb(hue, sat, lum)))
+        }
         _ => None,
     }
 }
 
+/// 色を表す要素（srgbClr / schemeClr 等）かどうか
+fn is_color_element(local: &[u8]) -> bool {
+    matches!(
+        local,
+        b"srgbClr" | b"schemeClr" | b"sysClr" | b"prstClr" | b"scrgbClr" | b"hslClr"
+    )
+}
+
+/// 0..1 の浮動小数RGB（色変換の途中で丸め誤差を蓄積しないため）
+type RgbF = [f64; 3];
+
+fn color_to_rgb_f(color: Color) -> RgbF {
+    [color.r as f64 / 255.0, color.g as f64 / 255.0, color.b as f64 / 255.0]
+}
+
+fn rgb_f_to_color(rgb: RgbF) -> Color {
+    let to_u8 = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Color::rgb(to_u8(rgb[0]), to_u8(rgb[1]), to_u8(rgb[2]))
+}
+
+/// 子要素（lumMod / tint 等）を持つ色要素から最終的な色を組み立てる
+///
+/// 色要素が Start イベントとして現れた場合、子の色変換要素は入れ子の Empty イベントで届くため、
+/// 基本色を保持したまま変換を蓄積し、色要素の終了タグで確定します。
+#[derive(Debug, Default)]
+struct ColorBuilder {
+    pending: Option<RgbF>,
+}
+
+impl ColorBuilder {
+    /// Start イベントの色要素から基本色の蓄積を開始
+    fn start(&mut self, e: &quick_xml::events::BytesStart, theme: &ThemeColors) {
+        self.begin(parse_color_element_themed(e, theme));
+    }
+
+    /// 解決済みの基本色から蓄積を開始
+    fn begin(&mut self, base: Option<Color>) {
+        self.pending = base.map(color_to_rgb_f);
+    }
+
+    /// 色変換要素であれば蓄積中の色に適用
+    fn modify(&mut self, e: &quick_xml::events::BytesStart) {
+        if is_color_modifier(e.local_name().as_ref()) {
+            self.pending = self.pending.map(|rgb| apply_color_modifier(rgb, e));
+        }
+    }
+
+    /// 色要素の終了タグで変換済みの色を取り出す
+    fn finish(&mut self) -> Option<Color> {
+        self.pending.take().map(rgb_f_to_color)
+    }
+}
+
+/// 色要素の子として現れる色変換要素かどうか
+fn is_color_modifier(local: &[u8]) -> bool {
+    matches!(local, b"lumMod" | b"lumOff" | b"satMod" | b"shade" | b"tint")
+}
+
+/// 色変換要素（lumMod / lumOff / satMod / shade / tint）を基本色に適用
+fn apply_color_modifier(rgb: RgbF, e: &quick_xml::events::BytesStart) -> RgbF {
+    let Some(val) = percent_attr(e, b"val") else {
+        return rgb;
+    };
+    match e.local_name().as_ref() {
+        b"lumMod" => map_hsl(rgb, |s, l| (s, l * val)),
+        b"lumOff" => map_hsl(rgb, |s, l| (s, l + val)),
+        b"satMod" => map_hsl(rgb, |s, l| (s * val, l)),
+        // shade は黒方向、tint は白方向へ線形RGB空間で補間
+        b"shade" => map_linear_rgb(rgb, |c| c * val),
+        b"tint" => map_linear_rgb(rgb, |c| c * val + (1.0 - val)),
+        _ => rgb,
+    }
+}
+
+/// 属性値を数値として取得
+fn attr_f64(e: &quick_xml::events::BytesStart, key: &[u8]) -> Option<f64> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.as_ref() == key)
+        .and_then(|a| String::from_utf8_lossy(&a.value).parse::<f64>().ok())
+}
+
+/// 1/100000 単位の割合属性を 0..1 の値として取得
+fn percent_attr(e: &quick_xml::events::BytesStart, key: &[u8]) -> Option<f64> {
+    attr_f64(e, key).map(|v| v / 100000.0)
+}
+
+/// RGB を HSL（色相は度、彩度・輝度は 0..1）に変換
+fn rgb_to_hsl([r, g, b]: RgbF) -> (f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d <= f64::EPSILON {
+        return (0.0, 0.0, l);
+    }
+    let s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0, s, l)
+}
+
+/// HSL（色相は度、彩度・輝度は 0..1）を RGB に変換
+fn hsl_to_rgb(hue: f64, sat: f64, lum: f64) -> RgbF {
+    let c = (1.0 - (2.0 * lum - 1.0).abs()) * sat;
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = lum - c / 2.0;
+    [r + m, g + m, b + m]
+}
+
+/// HSL 空間で彩度・輝度を変換（色相は維持）
+fn map_hsl(rgb: RgbF, f: impl Fn(f64, f64) -> (f64, f64)) -> RgbF {
+    let (h, s, l) = rgb_to_hsl(rgb);
+    let (s, l) = f(s, l);
+    hsl_to_rgb(h, s.clamp(0.0, 1.0), l.clamp(0.0, 1.0))
+}
+
+/// sRGB の各成分を線形RGBに変換して関数を適用し、sRGB に戻す
+fn map_linear_rgb(rgb: RgbF, f: impl Fn(f64) -> f64) -> RgbF {
+    rgb.map(|s| {
+        let lin = if s <= 0.04045 { s / 12.92 } else { ((s + 0.055) / 1.055).powf(2.4) };
+        let lin = f(lin).clamp(0.0, 1.0);
+        if lin <= 0.0031308 { lin * 12.92 } else { 1.055 * lin.powf(1.0 / 2.4) - 0.055 }
+    })
+}
+
+/// グラデーションのタイル矩形（l, t, r, b：図形サイズに対する割合、負値は外側への拡張）
+type GradientTileRect = (f64, f64, f64, f64);
+
+/// a:tileRect 要素の属性を読み取る（1/100000単位のパーセント値）
+fn parse_tile_rect(e: &quick_xml::events::BytesStart) -> GradientTileRect {
+    let mut rect = (0.0, 0.0, 0.0, 0.0);
+    for attr in e.attributes().flatten() {
+        let v = String::from_utf8_lossy(&attr.value).parse::<f64>().unwrap_or(0.0) / 100000.0;
+        match attr.key.as_ref() {
+            b"l" => rect.0 = v,
+            b"t" => rect.1 = v,
+            b"r" => rect.2 = v,
+            b"b" => rect.3 = v,
+            _ => {}
+        }
+    }
+    rect
+}
+
+/// a:rPr の strike 属性（sngStrike / dblStrike / noStrike）を解析
+fn parse_strike_attr(val: &[u8]) -> bool {
+    !matches!(val, b"noStrike")
+}
+
+/// a:rPr の u 属性（sng / dbl / dotted など）を下線スタイルに変換
+fn parse_underline_attr(val: &[u8]) -> Option<UnderlineStyle> {
+    let kind = match val {
+        b"none" => return None,
+        b"dbl" | b"wavyDbl" => UnderlineKind::Double,
+        v if v.starts_with(b"dot") => UnderlineKind::Dotted,
+        _ => UnderlineKind::Single,
+    };
+    Some(UnderlineStyle::new(kind))
+}
+
+/// a:rPr の baseline 属性（1/1000 % 単位、例: 30000 = 上付き30%）をフォントサイズ比に変換
+fn parse_baseline_attr(val: &[u8]) -> f64 {
+    String::from_utf8_lossy(val).parse::<f64>().unwrap_or(0.0) / 100_000.0
+}
+
+/// a:rPr の spc 属性（1/100 pt 単位の文字間隔）をポイントに変換
+/// kern 属性（カーニングを行う最小サイズ）は、どの出力でもカーニングを適用しないため読み取りません。
+fn parse_spc_attr(val: &[u8]) -> f64 {
+    String::from_utf8_lossy(val).parse::<f64>().unwrap_or(0.0) / 100.0
+}
+
+/// グラデーション角度（ラジアン）を 0..2π に正規化（負の ang にも対応）
+fn normalize_gradient_angle(angle: f64) -> f64 {
+    angle.rem_euclid(std::f64::consts::PI * 2.0)
+}
+
+/// グラデーション停止点を正規化
+/// tileRect が指定されている場合はグラデーション方向に射影したタイル範囲へ位置を写像し、
+/// 0..1 にクランプしたうえで位置順に並べ替えます。
+fn normalize_gradient_stops(
+    stops: &[GradientStop],
+    angle: f64,
+    tile: Option<GradientTileRect>,
+) -> Vec<GradientStop> {
+    let (start, end) = match tile {
+        Some((l, t, r, b)) => {
+            let cos_a = angle.cos().abs();
+            let sin_a = angle.sin().abs();
+            let weight = (cos_a + sin_a).max(f64::EPSILON);
+            let start = (l * cos_a + t * sin_a) / weight;
+            let end = 1.0 - (r * cos_a + b * sin_a) / weight;
+            (start, end)
+        }
+        None => (0.0, 1.0),
+    };
+    let mut out: Vec<GradientStop> = stops
+        .iter()
+        .map(|s| GradientStop {
+            position: (start + s.position * (end - start)).clamp(0.0, 1.0),
+            color: s.color,
+        })
+        .collect();
+    out.sort_by(|a, b| a.position.partial_cmp(&b.position).unwrap_or(std::cmp::Ordering::Equal));
+    out
+}
+
 /// XML要素から色を解析（デフォルトテーマ用互換関数）
 #[allow(dead_code)]
 fn parse_color_element(e: &quick_xml::events::BytesStart) -> Option<Color> {
@@ -1889,6 +3997,37 @@ fn resolve_relationship(rels_xml: &str, r_id: &str) -> Option<String> {
     None
 }
 
+/// リレーションシップXMLから Type が指定の接尾辞で終わる最初のTargetを取得
+fn resolve_relationship_by_type(rels_xml: &str, type_suffix: &str) -> Option<String> {
+    let mut reader = quick_xml::Reader::from_str(rels_xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Empty(ref e)) | Ok(quick_xml::events::Event::Start(ref e))
+                if e.local_name().as_ref() == b"Relationship" =>
+            {
+                let mut rel_type = String::new();
+                let mut target = String::new();
+                for attr in e.attributes().flatten() {
+                    match attr.key.as_ref() {
+                        b"Type" => rel_type = String::from_utf8_lossy(&attr.value).to_string(),
+                        b"Target" => target = String::from_utf8_lossy(&attr.value).to_string(),
+                        _ => {}
+                    }
+                }
+                if rel_type.ends_with(type_suffix) {
+                    return Some(target);
+                }
+            }
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    None
+}
+
 // ── Chart and SmartArt detection ──
 
 /// スライドXMLからチャート参照を検出し、チャートを描画
@@ -1986,6 +4125,153 @@ fn detect_and_render_smartart(
     elements
 }
 
+/// スライドのOLEオブジェクトのフレーム（p:graphicFrame > a:graphicData > p:oleObj）
+#[derive(Debug, Clone, PartialEq)]
+struct OleFrame {
+    /// OLEのプログラムID（例: "Excel.Sheet.12"）
+    prog_id: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    /// プレビュー画像（p:oleObj 内の p:pic の a:blip r:embed）
+    preview_r_id: Option<String>,
+}
+
+/// スライドXMLからOLEオブジェクトのフレームを検出
+/// mc:AlternateContent の Choice / Fallback の両方に p:oleObj がある場合も1つのフレームとして扱います。
+fn find_ole_frames(xml: &str) -> Vec<OleFrame> {
+    let mut results = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut buf = Vec::new();
+
+    let mut frame: Option<OleFrame> = None;
+    let mut has_ole = false;
+    let mut in_ole_obj = false;
+    let mut in_frame_xfrm = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) | Ok(quick_xml::events::Event::Empty(ref e)) => {
+                match e.local_name().as_ref() {
+                    b"graphicFrame" => {
+                        frame = Some(OleFrame {
+                            prog_id: String::new(),
+                            x: 0.0,
+                            y: 0.0,
+                            width: 0.0,
+                            height: 0.0,
+                            preview_r_id: None,
+                        });
+                        has_ole = false;
+                    }
+                    b"xfrm" if frame.is_some() && !in_ole_obj => in_frame_xfrm = true,
+                    b"off" | b"ext" if in_frame_xfrm => {
+                        if let Some(f) = frame.as_mut() {
+                            for attr in e.attributes().flatten() {
+                                let value = String::from_utf8_lossy(&attr.value).parse::<f64>().unwrap_or(0.0) / EMU_PER_PT;
+                                match attr.key.as_ref() {
+                                    b"x" => f.x = value,
+                                    b"y" => f.y = value,
+                                    b"cx" => f.width = value,
+                                    b"cy" => f.height = value,
+                                    _ => {}
+                                }
+                            }
+                        }
+                    }
+                    b"oleObj" => {
+                        if let Some(f) = frame.as_mut() {
+                            has_ole = true;
+                            in_ole_obj = true;
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"progId" && f.prog_id.is_empty() {
+                                    f.prog_id = String::from_utf8_lossy(&attr.value).to_string();
+                                }
+                            }
+                        }
+                    }
+                    b"blip" if in_ole_obj => {
+                        if let Some(f) = frame.as_mut().filter(|f| f.preview_r_id.is_none()) {
+                            f.preview_r_id = e
+                                .attributes()
+                                .flatten()
+                                .find(|attr| attr.key.local_name().as_ref() == b"embed")
+                                .map(|attr| String::from_utf8_lossy(&attr.value).to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(quick_xml::events::Event::End(ref e)) => match e.local_name().as_ref() {
+                b"graphicFrame" => {
+                    if let Some(f) = frame.take().filter(|_| has_ole) {
+                        results.push(f);
+                    }
+                    in_ole_obj = false;
+                }
+                b"xfrm" => in_frame_xfrm = false,
+                b"oleObj" => in_ole_obj = false,
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    results
+}
+
+/// OLEオブジェクトをプレビュー画像（PNG / JPEG）で、無ければProgID付きのプレースホルダーで描画し、警告を記録
+fn detect_and_render_ole_objects(
+    slide_xml: &str,
+    rels: &Option<String>,
+    archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>,
+    warnings: &mut Vec<String>,
+) -> Vec<PageElement> {
+    let mut elements = Vec::new();
+    for frame in find_ole_frames(slide_xml) {
+        let preview = frame
+            .preview_r_id
+            .as_ref()
+            .zip(rels.as_ref())
+            .and_then(|(r_id, rels_xml)| resolve_relationship(rels_xml, r_id))
+            .and_then(|target| {
+                let path = match target.strip_prefix('/') {
+                    Some(absolute) => absolute.to_string(),
+                    None => format!("ppt/slides/{}", target),
+                };
+                let path = normalize_zip_path(&path);
+                read_zip_entry_bytes(archive, &path).ok().map(|data| (data, guess_mime(&path)))
+            })
+            .filter(|(data, _)| ole::is_renderable_preview(data));
+        let name = ole::program_name(&frame.prog_id);
+        let warning = match preview {
+            Some((data, mime_type)) => {
+                elements.push(PageElement::Image {
+                    x: frame.x,
+                    y: frame.y,
+                    width: frame.width,
+                    height: frame.height,
+                    data,
+                    mime_type: mime_type.to_string(),
+                });
+                format!("{}{}", ole::OLE_PREVIEW_WARNING, name)
+            }
+            None => {
+                elements.extend(ole::placeholder_elements(frame.x, frame.y, frame.width, frame.height, name));
+                format!("{}{}", ole::OLE_PLACEHOLDER_WARNING, name)
+            }
+        };
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+    elements
+}
+
 /// スライドXMLからチャートフレームの位置とrIdを検出
 /// Returns: Vec<(rId, x, y, width, height)>
 fn find_chart_frames(xml: &str) -> Vec<(String, f64, f64, f64, f64)> {
@@ -2243,7 +4529,7 @@ fn detect_and_render_tables(
     xml: &str,
     theme_colors: &ThemeColors,
 ) -> Vec<PageElement> {
-    use crate::converter::{Table, TableCell};
+    use crate::converter::{BorderSpec, CellBorders, Table, TableCell};
 
     let mut elements = Vec::new();
     let mut reader = quick_xml::Reader::from_str(xml);
@@ -2263,13 +4549,30 @@ fn detect_and_render_tables(
     let mut current_row: Vec<TableCell> = Vec::new();
     let mut current_cell_text = String::new();
     let mut cell_fill: Option<Color> = None;
+    let mut cell_borders: Option<CellBorders> = None;
+    // a:lnL/lnR/lnT/lnB 内の枠線（辺の要素名, 線の指定）
+    let mut cur_ln: Option<(Vec<u8>, Option<BorderSpec>)> = None;
     let mut in_tc_pr = false;
     let mut in_solid_fill = false;
+    let mut color_builder = ColorBuilder::default();
     let mut tc_para_count = 0u32; // 現在のセル内の段落数
     let mut cur_col_span = 1u32;
     let mut cur_row_span = 1u32;
     let mut cur_h_merge = false;
     let mut cur_v_merge = false;
+    // セルの a:bodyPr に a:normAutofit があればセルに収まるよう縮小
+    let mut cur_shrink = false;
+
+    // セルの塗り、または枠線内であれば線の色として割り当てる
+    macro_rules! assign_cell_color {
+        ($c:expr) => {
+            match cur_ln.as_mut() {
+                Some((_, Some(spec))) => spec.color = $c,
+                Some((_, None)) => {}
+                None => cell_fill = Some($c),
+            }
+        };
+    }
 
     loop {
         match reader.read_event_into(&mut buf) {
@@ -2312,11 +4615,13 @@ fn detect_and_render_tables(
                         in_tc = true;
                         current_cell_text.clear();
                         cell_fill = None;
+                        cell_borders = None;
                         tc_para_count = 0;
                         cur_col_span = 1;
                         cur_row_span = 1;
                         cur_h_merge = false;
                         cur_v_merge = false;
+                        cur_shrink = false;
                         // Parse gridSpan and rowSpan from tc attributes
                         for attr in e.attributes().flatten() {
                             match attr.key.as_ref() {
@@ -2343,9 +4648,29 @@ fn detect_and_render_tables(
                     b"tcPr" if in_tc => {
                         in_tc_pr = true;
                     }
+                    b"normAutofit" if in_tc => {
+                        cur_shrink = true;
+                    }
+                    edge @ (b"lnL" | b"lnR" | b"lnT" | b"lnB") if in_tc_pr => {
+                        let width = e
+                            .attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == b"w")
+                            .and_then(|a| String::from_utf8_lossy(&a.value).parse::<f64>().ok())
+                            .map_or(1.0, |w| w / EMU_PER_PT);
+                        let compound = e
+                            .attributes()
+                            .flatten()
+                            .find(|a| a.key.as_ref() == b"cmpd")
+                            .map_or(CompoundLine::Single, |a| CompoundLine::from_attr(&String::from_utf8_lossy(&a.value)));
+                        cur_ln = Some((edge.to_vec(), Some(BorderSpec { width, color: Color::BLACK, compound })));
+                    }
                     b"solidFill" if in_tc_pr => {
                         in_solid_fill = true;
                     }
+                    clr if in_solid_fill && in_tc_pr && is_color_element(clr) => {
+                        color_builder.start(e, theme_colors);
+                    }
                     b"p" if in_tc => {
                         // 2番目以降の段落では改行を挿入
                         if tc_para_count > 0 && !current_cell_text.is_empty() {
@@ -2400,12 +4725,21 @@ fn detect_and_render_tables(
                         // テーブルセル内の改行（Empty要素）
                         current_cell_text.push('\n');
                     }
+                    b"normAutofit" if in_tc => {
+                        cur_shrink = true;
+                    }
+                    b"noFill" if cur_ln.is_some() => {
+                        if let Some((_, spec)) = cur_ln.as_mut() {
+                            *spec = None;
+                        }
+                    }
                     _ => {
-                        // Color elements in solidFill within tcPr
+                        // Color elements in solidFill within tcPr（枠線内なら線の色）
+                        color_builder.modify(e);
                         if in_solid_fill && in_tc_pr {
                             let color = parse_color_element_themed(e, theme_colors);
                             if let Some(c) = color {
-                                cell_fill = Some(c);
+                                assign_cell_color!(c);
                             }
                         }
                     }
@@ -2458,9 +4792,9 @@ fn detect_and_render_tables(
                                 let mut cell = TableCell::new(&current_cell_text);
                                 cell.col_span = cur_col_span;
                                 cell.row_span = cur_row_span;
-                                if let Some(c) = cell_fill {
-                                    cell.style.color = c;
-                                }
+                                cell.background = cell_fill;
+                                cell.borders = cell_borders;
+                                cell.shrink_to_fit = cur_shrink;
                                 current_row.push(cell);
                             }
                         }
@@ -2471,9 +4805,25 @@ fn detect_and_render_tables(
                     b"tcPr" => {
                         in_tc_pr = false;
                     }
+                    b"lnL" | b"lnR" | b"lnT" | b"lnB" if in_tc_pr => {
+                        if let Some((edge, spec)) = cur_ln.take() {
+                            let borders = cell_borders.get_or_insert_with(CellBorders::default);
+                            match edge.as_slice() {
+                                b"lnL" => borders.left = spec,
+                                b"lnR" => borders.right = spec,
+                                b"lnT" => borders.top = spec,
+                                _ => borders.bottom = spec,
+                            }
+                        }
+                    }
                     b"solidFill" if in_tc_pr => {
                         in_solid_fill = false;
                     }
+                    clr if is_color_element(clr) => {
+                        if let Some(c) = color_builder.finish() {
+                            assign_cell_color!(c);
+                        }
+                    }
                     b"t" => {
                         in_tc_text = false;
                     }
@@ -2496,10 +4846,13 @@ fn detect_and_render_tables(
 // ── Page rendering ──
 
 /// 解析済みシェイプからページを構築
+/// 未実装のプリセット図形は `unknown_geometry` に従って描画し、図形名を `warnings` に記録します。
 fn render_slide_page(
     shapes: &[SlideShape],
     slide_size: &SlideSize,
     bg: Option<&SlideBg>,
+    unknown_geometry: UnknownGeometryPolicy,
+    warnings: &mut Vec<String>,
 ) -> Page {
     let mut page = Page {
         width: slide_size.width,
@@ -2519,6 +4872,7 @@ fn render_slide_page(
                 stroke: None,
                 stroke_width: 0.0,
                 rotation_deg: 0.0,
+                stroke_style: Default::default(),
             });
         }
         Some(SlideBg::Gradient { stops, angle }) => {
@@ -2546,30 +4900,50 @@ fn render_slide_page(
     }
 
     for shape in shapes {
+        // spAutoFit: テキストが収まるよう図形の高さを広げる
+        let grown;
+        let shape = match shape.grown_to_fit_text() {
+            Some(s) => {
+                grown = s;
+                &grown
+            }
+            None => shape,
+        };
+
         // Render shadow first (behind the shape)
-        if let Some(ref shadow) = shape.shadow {
-            let shadow_alpha = (shadow.color.a as f64 * 0.5) as u8;
-            page.elements.push(PageElement::Rect {
-                x: shape.x + shadow.offset_x,
-                y: shape.y + shadow.offset_y,
-                width: shape.width,
-                height: shape.height,
-                fill: Some(Color {
-                    r: shadow.color.r,
-                    g: shadow.color.g,
-                    b: shadow.color.b,
-                    a: shadow_alpha,
-                }),
-                stroke: None,
-                stroke_width: 0.0,
-                rotation_deg: 0.0,
-            });
-        }
+        page.elements.extend(shape.shadow_elements());
+        // 影は図形の外側に落ちるものなので、グループのクリップ対象は影より後の要素
+        let first_clipped = page.elements.len();
 
         match &shape.content {
-            ShapeContent::TextBox { paragraphs } => {
+            ShapeContent::TextBox { .. } | ShapeContent::Empty => {
+                let paragraphs: &[ShapeParagraph] = match &shape.content {
+                    ShapeContent::TextBox { paragraphs } => paragraphs,
+                    _ => &[],
+                };
+                // 複合線は線を除いた図形の上に、平行にずらした線を重ねて描画する
+                let compound_outline = shape.compound_outline_elements();
+                let without_outline;
+                let shape = if compound_outline.is_empty() {
+                    shape
+                } else {
+                    without_outline = SlideShape { outline: None, ..shape.clone() };
+                    &without_outline
+                };
+                // 敷き詰め・余白付きの画像の塗りは図形の形でクリップして先に描き、図形は塗り無しとして描画する
+                let image_fill = shape.image_fill_group();
+                let without_image_fill;
+                let shape = match image_fill {
+                    Some(group) => {
+                        page.elements.push(group);
+                        without_image_fill = SlideShape { fill: None, ..shape.clone() };
+                        &without_image_fill
+                    }
+                    None => shape,
+                };
+
                 // Check for ellipse/rounded geometry
-                let is_ellipse = shape.preset_geometry.as_deref() == Some("ellipse");
+                let mut is_ellipse = shape.preset_geometry.as_deref() == Some("ellipse");
 
                 // 3D effect: draw depth extrusion behind the shape
                 if shape.has_3d && shape.width > 0.0 && shape.height > 0.0 {
@@ -2594,6 +4968,7 @@ fn render_slide_page(
                         stroke: None,
                         stroke_width: 0.0,
                         rotation_deg: 0.0,
+                        stroke_style: Default::default(),
                     });
                     // Right depth strip
                     page.elements.push(PageElement::Rect {
@@ -2605,6 +4980,7 @@ fn render_slide_page(
                         stroke: None,
                         stroke_width: 0.0,
                         rotation_deg: 0.0,
+                        stroke_style: Default::default(),
                     });
                 }
 
@@ -2612,81 +4988,36 @@ fn render_slide_page(
                 let mut shape_rendered = false;
 
                 // Try custom geometry path rendering first
-                if let (Some(ref cmds), Some((vp_w, vp_h))) = (&shape.custom_path, shape.custom_path_viewport) {
-                    if !cmds.is_empty() && vp_w > 0.0 && vp_h > 0.0 {
-                        let scale_x = shape.width / vp_w;
-                        let scale_y = shape.height / vp_h;
-                        let scaled_cmds: Vec<PathCommand> = cmds.iter().map(|cmd| {
-                            match cmd {
-                                PathCommand::MoveTo(x, y) => PathCommand::MoveTo(shape.x + x * scale_x, shape.y + y * scale_y),
-                                PathCommand::LineTo(x, y) => PathCommand::LineTo(shape.x + x * scale_x, shape.y + y * scale_y),
-                                PathCommand::QuadTo(cx, cy, x, y) => PathCommand::QuadTo(
-                                    shape.x + cx * scale_x, shape.y + cy * scale_y,
-                                    shape.x + x * scale_x, shape.y + y * scale_y,
-                                ),
-                                PathCommand::CubicTo(cx1, cy1, cx2, cy2, x, y) => PathCommand::CubicTo(
-                                    shape.x + cx1 * scale_x, shape.y + cy1 * scale_y,
-                                    shape.x + cx2 * scale_x, shape.y + cy2 * scale_y,
-                                    shape.x + x * scale_x, shape.y + y * scale_y,
-                                ),
-                                PathCommand::ArcTo(rx, ry, rot, large, sweep, x, y) => PathCommand::ArcTo(
-                                    rx * scale_x, ry * scale_y, *rot, *large, *sweep,
-                                    shape.x + x * scale_x, shape.y + y * scale_y,
-                                ),
-                                PathCommand::Close => PathCommand::Close,
-                            }
-                        }).collect();
-
-                        // Handle image fill for custom geometries
-                        if let Some(ShapeFill::Image { data, mime_type }) = &shape.fill {
-                            let (stroke_color, stroke_w) = shape.outline.map_or((None, 0.0), |(c, w)| (Some(c), w));
-                            page.elements.push(PageElement::PathImage {
-                                commands: scaled_cmds,
-                                data: data.clone(),
-                                mime_type: mime_type.clone(),
-                                stroke: stroke_color,
-                                stroke_width: stroke_w,
-                                rotation_deg: shape.rotation,
-                            });
-                        } else {
-                            let fill_color = match &shape.fill {
-                                Some(ShapeFill::Solid(c)) => Some(*c),
-                                _ => None,
-                            };
-                            let (stroke_color, stroke_w) = shape.outline.map_or((None, 0.0), |(c, w)| (Some(c), w));
-                            page.elements.push(PageElement::Path {
-                                commands: scaled_cmds,
-                                fill: fill_color,
-                                stroke: stroke_color,
-                                stroke_width: stroke_w,
-                                rotation_deg: shape.rotation,
-                            });
-                        }
-                        shape_rendered = true;
+                if let Some(scaled_cmds) = shape.custom_path_in(shape.x, shape.y, shape.width, shape.height) {
+                    // Handle image fill for custom geometries
+                    if let Some(ShapeFill::Image { data, mime_type }) = &shape.fill {
+                        let styled = shape.styled_outline_path(&scaled_cmds);
+                        let (stroke_color, stroke_w) = shape.outline.map_or((None, 0.0), |o| (Some(o.color), o.width));
+                        page.elements.push(PageElement::PathImage {
+                            commands: scaled_cmds,
+                            data: data.clone(),
+                            mime_type: mime_type.clone(),
+                            stroke: stroke_color.filter(|_| styled.is_none()),
+                            stroke_width: stroke_w,
+                            rotation_deg: shape.rotation,
+                        });
+                        page.elements.extend(styled);
+                    } else {
+                        page.elements.extend(shape.filled_path(scaled_cmds, true));
                     }
+                    shape_rendered = true;
                 }
 
                 // Try path-based rendering for non-trivial geometries
+                let mut unknown_geometry_fallback = None;
                 if let Some(ref geom_name) = shape.preset_geometry {
                     if geom_name != "rect" && geom_name != "ellipse" {
                         // まず複数パス版を試す（サブパスを持つジオメトリ用）
                         if let Some(path_groups) = generate_preset_paths(geom_name, shape.x, shape.y, shape.width, shape.height) {
-                            let fill_color = match &shape.fill {
-                                Some(ShapeFill::Solid(c)) => Some(*c),
-                                _ => None,
-                            };
-                            let (stroke_color, stroke_w) = shape.outline.map_or((None, 0.0), |(c, w)| (Some(c), w));
                             for (i, path_cmds) in path_groups.into_iter().enumerate() {
                                 // 最初のパスのみフィルを適用（サブパスはストロークのみ）
                                 // 注: smileyFaceの目など、内側パスにもフィルが必要な場合がある
-                                let fill = if i == 0 { fill_color } else { None };
-                                page.elements.push(PageElement::Path {
-                                    commands: path_cmds,
-                                    fill,
-                                    stroke: stroke_color,
-                                    stroke_width: stroke_w,
-                                    rotation_deg: shape.rotation,
-                                });
+                                page.elements.extend(shape.filled_path(path_cmds, i == 0));
                             }
                             shape_rendered = true;
                         }
@@ -2695,49 +5026,49 @@ fn render_slide_page(
                             if let Some(path_cmds) = generate_preset_path(geom_name, shape.x, shape.y, shape.width, shape.height) {
                                 // Handle image fill for preset geometries
                                 if let Some(ShapeFill::Image { data, mime_type }) = &shape.fill {
-                                    let (stroke_color, stroke_w) = shape.outline.map_or((None, 0.0), |(c, w)| (Some(c), w));
+                                    let styled = shape.styled_outline_path(&path_cmds);
+                                    let (stroke_color, stroke_w) =
+                                        shape.outline.map_or((None, 0.0), |o| (Some(o.color), o.width));
                                     page.elements.push(PageElement::PathImage {
                                         commands: path_cmds,
                                         data: data.clone(),
                                         mime_type: mime_type.clone(),
-                                        stroke: stroke_color,
+                                        stroke: stroke_color.filter(|_| styled.is_none()),
                                         stroke_width: stroke_w,
                                         rotation_deg: shape.rotation,
                                     });
+                                    page.elements.extend(styled);
                                 } else {
-                                    let fill_color = match &shape.fill {
-                                        Some(ShapeFill::Solid(c)) => Some(*c),
-                                        _ => None,
-                                    };
-                                    let (stroke_color, stroke_w) = shape.outline.map_or((None, 0.0), |(c, w)| (Some(c), w));
-                                    page.elements.push(PageElement::Path {
-                                        commands: path_cmds,
-                                        fill: fill_color,
-                                        stroke: stroke_color,
-                                        stroke_width: stroke_w,
-                                        rotation_deg: shape.rotation,
-                                    });
+                                    page.elements.extend(shape.filled_path(path_cmds, true));
                                 }
                                 shape_rendered = true;
                             } else {
                                 // Geometry not implemented - log warning
                                 diag_warn!("Preset geometry '{}' not implemented, shape will not render fill/stroke", geom_name);
+                                let warning = format!("{}{}", UNKNOWN_GEOMETRY_WARNING, geom_name);
+                                if !warnings.contains(&warning) {
+                                    warnings.push(warning);
+                                }
+                                unknown_geometry_fallback = Some(unknown_geometry);
                             }
                         }
                     }
                 }
-                // 未実装のプリセットジオメトリは矩形に置き換えない
-                // rect/ellipse/未指定の場合のみ矩形・楕円でフォールバック描画する
+                // 未実装のプリセットジオメトリは既定では矩形に置き換えない
+                // rect/ellipse/未指定の場合と、unknown_geometry_policy で指定した場合のみ矩形・楕円で描画する
                 let is_rect_or_default = match &shape.preset_geometry {
                     None => true,
                     Some(name) => name == "rect",
-                };
+                } || unknown_geometry_fallback == Some(UnknownGeometryPolicy::BoundingRect);
+                is_ellipse |= unknown_geometry_fallback == Some(UnknownGeometryPolicy::Ellipse);
 
                 if !shape_rendered {
                     if is_ellipse {
                         // Render ellipse with image fill using elliptical clipping
                         if let Some(ShapeFill::Image { data, mime_type }) = &shape.fill {
+                            // 破線等の線は線スタイルを持てる楕円として重ねる
                             let stroke_info = shape.outline;
+                            let styled = stroke_info.filter(|o| !o.style.is_default());
                             page.elements.push(PageElement::EllipseImage {
                                 cx: shape.x + shape.width / 2.0,
                                 cy: shape.y + shape.height / 2.0,
@@ -2745,11 +5076,23 @@ fn render_slide_page(
                                 ry: shape.height / 2.0,
                                 data: data.clone(),
                                 mime_type: mime_type.clone(),
-                                stroke: stroke_info.map(|(c, _)| c),
-                                stroke_width: stroke_info.map_or(0.0, |(_, w)| w),
+                                stroke: stroke_info.filter(|_| styled.is_none()).map(|o| o.color),
+                                stroke_width: stroke_info.map_or(0.0, |o| o.width),
                                 rotation_deg: shape.rotation,
                             });
-                            shape_rendered = true;
+                            if let Some(outline) = styled {
+                                page.elements.push(PageElement::Ellipse {
+                                    cx: shape.x + shape.width / 2.0,
+                                    cy: shape.y + shape.height / 2.0,
+                                    rx: shape.width / 2.0,
+                                    ry: shape.height / 2.0,
+                                    fill: None,
+                                    stroke: Some(outline.color),
+                                    stroke_width: outline.width,
+                                    rotation_deg: shape.rotation,
+                                    stroke_style: outline.style,
+                                });
+                            }
                         } else {
                             // Solid or gradient fill
                             let fill_color = match &shape.fill {
@@ -2763,11 +5106,11 @@ fn render_slide_page(
                                 rx: shape.width / 2.0,
                                 ry: shape.height / 2.0,
                                 fill: fill_color,
-                                stroke: stroke_info.map(|(c, _)| c),
-                                stroke_width: stroke_info.map_or(0.0, |(_, w)| w),
+                                stroke: stroke_info.map(|o| o.color),
+                                stroke_width: stroke_info.map_or(0.0, |o| o.width),
                                 rotation_deg: shape.rotation,
+                                stroke_style: stroke_info.map(|o| o.style).unwrap_or_default(),
                             });
-                            shape_rendered = true;
                         }
                     } else if is_rect_or_default {
                         // 矩形フォールバックは実際の rect または未指定ジオメトリのみ
@@ -2782,6 +5125,7 @@ fn render_slide_page(
                                     stroke: None,
                                     stroke_width: 0.0,
                                     rotation_deg: shape.rotation,
+                                    stroke_style: Default::default(),
                                 });
                             }
                             Some(ShapeFill::Gradient { stops, angle }) => {
@@ -2807,174 +5151,39 @@ fn render_slide_page(
                             }
                             None => {}
                         }
-                        shape_rendered = true;
+                        // 線は塗りの上に矩形ストロークとして描画
+                        if let Some(outline) = shape.outline {
+                            page.elements.push(PageElement::Rect {
+                                x: shape.x,
+                                y: shape.y,
+                                width: shape.width,
+                                height: shape.height,
+                                fill: None,
+                                stroke: Some(outline.color),
+                                stroke_width: outline.width,
+                                rotation_deg: shape.rotation,
+                                stroke_style: outline.style,
+                            });
+                        }
                     }
-                    // else: 未実装のプリセットジオメトリ → 矩形での置換を行わない
+                    // else: 未実装のプリセットジオメトリ → 既定では矩形での置換を行わない
                 } // end shape fill
-
-                // Shape outline (rect/defaultの場合のみ矩形ストロークを描画)
-                if !shape_rendered && is_rect_or_default {
-                    if let Some((color, width)) = shape.outline {
-                        page.elements.push(PageElement::Rect {
-                            x: shape.x,
-                            y: shape.y,
-                            width: shape.width,
-                            height: shape.height,
-                            fill: None,
-                            stroke: Some(color),
-                            stroke_width: width,
-                            rotation_deg: shape.rotation,
-                        });
-                    }
-                }
+                page.elements.extend(compound_outline);
 
                 // Render text paragraphs positioned within the shape
-                let margin_left = shape.text_margin_left;
-                let margin_top = shape.text_margin_top;
-                let margin_right = shape.text_margin_right;
-                let _margin_bottom = shape.text_margin_bottom;
-                let mut text_y = shape.y + margin_top;
-
-                for para in paragraphs {
-                    let indent = para.level as f64 * 18.0;
-                    let available_width = shape.width - margin_left - margin_right - indent;
-                    if available_width <= 0.0 {
-                        continue;
-                    }
-
-                    // Collect run segments for this paragraph, handling newlines
-                    let mut segments: Vec<(&TextRun, &str)> = Vec::new();
-                    let mut has_bullet = false;
-                    if para.bullet.is_some() {
-                        if para.runs.first().is_some() {
-                            has_bullet = true;
-                        }
-                    }
-
-                    for run in &para.runs {
-                        if run.text.contains('\n') {
-                            // Split by newlines, each sub-part is a segment
-                            for (i, part) in run.text.split('\n').enumerate() {
-                                if i > 0 {
-                                    // Newline: flush the current line
-                                    segments.push((run, "\n"));
-                                }
-                                if !part.is_empty() {
-                                    segments.push((run, part));
-                                }
-                            }
-                        } else {
-                            segments.push((run, &run.text));
-                        }
-                    }
-
-                    // Check if any visible text
-                    let has_text = segments.iter().any(|(_, t)| !t.is_empty() && *t != "\n");
-                    if !has_text && !has_bullet {
-                        // Empty paragraph - add line spacing
-                        let font_size = para.runs.first().map_or(18.0, |r| r.font_size);
-                        text_y += font_size * 0.8;
-                        continue;
-                    }
-
-                    // Build lines from segments, wrapping as needed
-                    let mut current_line_x = shape.x + margin_left + indent;
-                    let mut current_line_width = 0.0;
-                    let line_start_x = current_line_x;
-                    let first_font_size = para.runs.first().map_or(18.0, |r| r.font_size);
-                    let mut line_height = first_font_size * 1.3;
-                    let mut line_started = false;
-
-                    // Output bullet first if present
-                    if has_bullet {
-                        if let Some(ref bullet) = para.bullet {
-                            let bullet_run = para.runs.first().unwrap();
-                            let fs = bullet_run.font_size;
-                            let bullet_with_space = format!("{} ", bullet);
-                            let bw = estimate_run_width(&bullet_with_space, fs);
-                            if text_y + fs <= shape.y + shape.height {
-                                page.elements.push(PageElement::Text {
-                                    x: current_line_x,
-                                    y: text_y,
-                                    width: bw,
-                                    text: bullet_with_space,
-                                    style: FontStyle {
-                                        font_size: fs,
-                                        bold: bullet_run.bold,
-                                        italic: bullet_run.italic,
-                                        color: bullet_run.color.unwrap_or(Color::BLACK),
-                                        ..FontStyle::default()
-                                    },
-                                    align: para.align,
-                                });
-                            }
-                            current_line_x += bw;
-                            current_line_width += bw;
-                            line_started = true;
-                        }
-                    }
-
-                    for (run, text) in &segments {
-                        if *text == "\n" {
-                            // Explicit line break
-                            text_y += line_height;
-                            current_line_x = line_start_x;
-                            current_line_width = 0.0;
-                            line_height = run.font_size * 1.3;
-                            line_started = false;
-                            continue;
-                        }
-                        if text.is_empty() {
-                            continue;
-                        }
-
-                        let fs = run.font_size;
-                        line_height = line_height.max(fs * 1.3);
-
-                        // Wrap this run's text within available width
-                        let remaining_width = available_width - current_line_width;
-                        let run_lines = wrap_text(text, remaining_width, fs);
-
-                        for (li, line_text) in run_lines.iter().enumerate() {
-                            if li > 0 {
-                                // Wrapped to next line
-                                text_y += line_height;
-                                current_line_x = line_start_x;
-                                current_line_width = 0.0;
-                                line_height = fs * 1.3;
-                            }
-
-                            if text_y + fs > shape.y + shape.height {
-                                break; // Clip to shape bounds
-                            }
-
-                            let tw = estimate_run_width(line_text, fs);
-                            if !line_text.trim().is_empty() {
-                                page.elements.push(PageElement::Text {
-                                    x: current_line_x,
-                                    y: text_y,
-                                    width: tw,
-                                    text: line_text.clone(),
-                                    style: FontStyle {
-                                        font_size: fs,
-                                        bold: run.bold,
-                                        italic: run.italic,
-                                        color: run.color.unwrap_or(Color::BLACK),
-                                        ..FontStyle::default()
-                                    },
-                                    align: para.align,
-                                });
-                                line_started = true;
-                            }
-                            current_line_x += tw;
-                            current_line_width += tw;
+                // （弧に沿う WordArt は文字ごとに配置し、未対応の変形は直線で配置して警告する）
+                let warp = shape.text_warp.as_deref();
+                if let Some(arc) = warp.and_then(ArcWarp::from_preset) {
+                    page.elements.extend(layout_arc_text(shape, paragraphs, arc));
+                } else {
+                    if let Some(preset) = warp {
+                        let warning = format!("{}{}", UNSUPPORTED_WARP_WARNING, preset);
+                        if !warnings.contains(&warning) {
+                            warnings.push(warning);
                         }
                     }
-
-                    // Advance to next paragraph
-                    if line_started || has_text {
-                        text_y += line_height;
-                    }
+                    let (text_elements, _) = layout_shape_text(shape, paragraphs, shape.y + shape.height);
+                    page.elements.extend(text_elements);
                 }
             }
 
@@ -2989,41 +5198,23 @@ fn render_slide_page(
                 });
             }
 
-            ShapeContent::Image { .. } => {
-                // Unresolved image - draw placeholder
-                page.elements.push(PageElement::Rect {
-                    x: shape.x,
-                    y: shape.y,
-                    width: shape.width,
-                    height: shape.height,
-                    fill: Some(Color::rgb(230, 230, 230)),
-                    stroke: Some(Color::rgb(180, 180, 180)),
-                    stroke_width: 0.5,
-                    rotation_deg: shape.rotation,
-                });
-                page.elements.push(PageElement::Text {
-                    x: shape.x + 4.0,
-                    y: shape.y + shape.height / 2.0 - 6.0,
-                    width: shape.width - 8.0,
-                    text: "[Image]".to_string(),
-                    style: FontStyle {
-                        font_size: 10.0,
-                        color: Color::rgb(150, 150, 150),
-                        italic: true,
-                        ..FontStyle::default()
-                    },
-                    align: TextAlign::Center,
-                });
+            ShapeContent::Image { r_id, .. } => {
+                // 読み込めなかった画像は後から供給できるようにIDを記したプレースホルダーで表示
+                page.elements.extend(deferred_assets::placeholder_elements(
+                    shape.x, shape.y, shape.width, shape.height, shape.rotation, r_id,
+                ));
             }
 
             ShapeContent::Connector => {
                 // Try rendering connector using preset geometry path
-                let mut connector_rendered = false;
-                if let Some(ref geom_name) = shape.preset_geometry {
+                let compound_outline = shape.compound_outline_elements();
+                let mut connector_rendered = !compound_outline.is_empty();
+                page.elements.extend(compound_outline);
+                if let Some(geom_name) = shape.preset_geometry.as_ref().filter(|_| !connector_rendered) {
                     if let Some(path_cmds) = generate_preset_path(geom_name, shape.x, shape.y, shape.width, shape.height) {
                         let (stroke_color, stroke_w) = shape.outline.map_or(
                             (Some(Color::BLACK), 1.0),
-                            |(c, w)| (Some(c), w),
+                            |o| (Some(o.color), o.width),
                         );
                         page.elements.push(PageElement::Path {
                             commands: path_cmds,
@@ -3031,6 +5222,7 @@ fn render_slide_page(
                             stroke: stroke_color,
                             stroke_width: stroke_w,
                             rotation_deg: shape.rotation,
+                            stroke_style: shape.outline.map(|o| o.style).unwrap_or_default(),
                         });
                         connector_rendered = true;
                     }
@@ -3039,9 +5231,9 @@ fn render_slide_page(
                     // Fallback: draw line from top-left to bottom-right
                     let color = shape
                         .outline
-                        .map(|(c, _)| c)
+                        .map(|o| o.color)
                         .unwrap_or(Color::rgb(0, 0, 0));
-                    let width = shape.outline.map(|(_, w)| w).unwrap_or(1.0);
+                    let width = shape.outline.map(|o| o.width).unwrap_or(1.0);
                     page.elements.push(PageElement::Line {
                         x1: shape.x,
                         y1: shape.y,
@@ -3049,70 +5241,405 @@ fn render_slide_page(
                         y2: shape.y + shape.height,
                         width,
                         color,
+                        stroke_style: shape.outline.map(|o| o.style).unwrap_or_default(),
                     });
                 }
             }
+        }
+
+        // グループの範囲からはみ出した子シェイプはグループの範囲でクリップする
+        if let Some((gx, gy, gw, gh)) = shape.clip {
+            let overflows = page.elements[first_clipped..].iter().filter_map(PageElement::bounds).any(
+                |(x0, y0, x1, y1)| x0 < gx - 0.01 || y0 < gy - 0.01 || x1 > gx + gw + 0.01 || y1 > gy + gh + 0.01,
+            );
+            if overflows {
+                let children = page.elements.split_off(first_clipped);
+                page.elements.push(PageElement::ClipGroup { clip_path: PathCommand::rect(gx, gy, gw, gh), children });
+            }
+        }
+    }
+
+    page
+}
+
+/// 図形内のテキスト段落をレイアウトし、描画要素とテキスト下端のY座標を返す
+/// `clip_bottom` を超える行は出力しません（spAutoFit の計測時は無制限）。
+fn layout_shape_text(
+    shape: &SlideShape,
+    paragraphs: &[ShapeParagraph],
+    clip_bottom: f64,
+) -> (Vec<PageElement>, f64) {
+    let mut elements = Vec::new();
+    // normAutofit: フォントサイズの縮小率と行間の削減率
+    let (font_scale, line_spacing) = match shape.autofit {
+        TextAutofit::Shrink { font_scale, line_spacing_reduction } => {
+            (font_scale, 1.3 * (1.0 - line_spacing_reduction))
+        }
+        _ => (1.0, 1.3),
+    };
+    let margin_left = shape.text_margin_left;
+    let margin_top = shape.text_margin_top;
+    let margin_right = shape.text_margin_right;
+    let mut text_y = shape.y + margin_top;
+
+    // 自動番号のレベル別の書式と現在の番号（テキスト本文内で継続）
+    let mut auto_num_counters: [Option<(&str, u32)>; 9] = Default::default();
+
+    for para in paragraphs {
+        // marL / indent の指定があればそれに従い、無ければレベルごとに一定幅ずらす
+        let (text_indent, first_line_indent) = match para.bullet.margin_left {
+            Some(mar_l) => (mar_l, (mar_l + para.bullet.indent.unwrap_or(0.0)).max(0.0)),
+            None => {
+                let indent = para.level as f64 * 18.0;
+                (indent, indent)
+            }
+        };
+        let available_width = shape.width - margin_left - margin_right - text_indent;
+        if available_width <= 0.0 {
+            continue;
+        }
+
+        // 箇条書き記号（自動番号はレベルごとに数え、浅いレベルが現れたら深いレベルをリセット。
+        // 同じレベルでも書式が変わった場合は startAt から振り直す）
+        let level = (para.level as usize).min(auto_num_counters.len() - 1);
+        auto_num_counters[level + 1..].fill(None);
+        let bullet_text = match &para.bullet.kind {
+            _ if para.runs.is_empty() => None,
+            Some(BulletKind::Char(ch)) => Some(ch.clone()),
+            Some(BulletKind::AutoNum { scheme, start_at }) => {
+                let n = match auto_num_counters[level] {
+                    Some((prev, n)) if prev == scheme.as_str() => n + 1,
+                    _ => *start_at,
+                };
+                auto_num_counters[level] = Some((scheme.as_str(), n));
+                Some(format_auto_number(scheme, n))
+            }
+            Some(BulletKind::None) | None => {
+                auto_num_counters[level] = None;
+                None
+            }
+        };
+        let has_bullet = bullet_text.is_some();
+
+        // Collect run segments for this paragraph, handling newlines
+        let mut segments: Vec<(&TextRun, &str)> = Vec::new();
+
+        for run in &para.runs {
+            if run.text.contains('\n') {
+                // Split by newlines, each sub-part is a segment
+                for (i, part) in run.text.split('\n').enumerate() {
+                    if i > 0 {
+                        // Newline: flush the current line
+                        segments.push((run, "\n"));
+                    }
+                    if !part.is_empty() {
+                        segments.push((run, part));
+                    }
+                }
+            } else {
+                segments.push((run, &run.text));
+            }
+        }
+
+        // Check if any visible text
+        let has_text = segments.iter().any(|(_, t)| !t.is_empty() && *t != "\n");
+        if !has_text && !has_bullet {
+            // Empty paragraph - add line spacing（テキスト抽出で空行になるよう空のテキストを置く）
+            let font_size = para.runs.first().map_or(18.0, |r| r.font_size) * font_scale;
+            if text_y + font_size <= clip_bottom {
+                elements.push(PageElement::Text {
+                    x: shape.x + margin_left + text_indent,
+                    y: text_y,
+                    width: 0.0,
+                    text: String::new(),
+                    style: FontStyle { font_size, ..FontStyle::default() },
+                    align: para.align,
+                });
+            }
+            text_y += font_size * 0.8;
+            continue;
+        }
+
+        // Build lines from segments, wrapping as needed
+        let line_start_x = shape.x + margin_left + text_indent;
+        let mut current_line_x = shape.x + margin_left + first_line_indent;
+        let mut current_line_width = current_line_x - line_start_x;
+        let first_font_size = para.runs.first().map_or(18.0, |r| r.font_size) * font_scale;
+        let mut line_height = first_font_size * line_spacing;
+        let mut line_started = false;
+
+        // Output bullet first if present
+        if let (Some(bullet), Some(bullet_run)) = (bullet_text, para.runs.first()) {
+            let fs = bullet_run.font_size * font_scale;
+            let bullet_fs = fs * para.bullet.size_pct.unwrap_or(1.0);
+            let bullet_with_space = format!("{} ", bullet);
+            let bw = estimate_run_width(&bullet_with_space, bullet_fs);
+            if text_y + fs <= clip_bottom {
+                elements.push(PageElement::Text {
+                    x: current_line_x,
+                    // 縮小した記号は本文の行の中央にそろえる
+                    y: text_y + (fs - bullet_fs) / 2.0,
+                    width: bw,
+                    text: bullet_with_space,
+                    style: FontStyle {
+                        font_size: bullet_fs,
+                        bold: bullet_run.bold,
+                        italic: bullet_run.italic,
+                        color: para.bullet.color.or(bullet_run.color).unwrap_or(Color::BLACK),
+                        ..FontStyle::default()
+                    },
+                    align: para.align,
+                });
+            }
+            // 記号がぶら下げインデント内に収まる場合はテキストを marL の位置から開始
+            current_line_x = if current_line_x + estimate_run_width(&bullet, bullet_fs) <= line_start_x {
+                line_start_x
+            } else {
+                current_line_x + bw
+            };
+            current_line_width = current_line_x - line_start_x;
+            line_started = true;
+        }
+
+        for (run, text) in &segments {
+            if *text == "\n" {
+                // Explicit line break
+                text_y += line_height;
+                current_line_x = line_start_x;
+                current_line_width = 0.0;
+                line_height = run.font_size * font_scale * line_spacing;
+                line_started = false;
+                continue;
+            }
+            if text.is_empty() {
+                continue;
+            }
+
+            let fs = run.font_size * font_scale;
+            line_height = line_height.max(fs * line_spacing);
+            let style = FontStyle {
+                font_name: run.font_name.clone().unwrap_or_else(|| FontStyle::default().font_name),
+                font_size: fs,
+                bold: run.bold,
+                italic: run.italic,
+                color: run.color.unwrap_or(Color::BLACK),
+                strikethrough: run.strikethrough,
+                baseline_shift: run.baseline_shift,
+                underline: run.underline,
+                letter_spacing: run.letter_spacing * font_scale,
+            };
+            // 上付き・下付きは縮小後のサイズで幅を見積もる
+            let (advance_fs, _) = style.script_metrics(0.0);
+
+            // Wrap this run's text within available width
+            let remaining_width = available_width - current_line_width;
+            let run_lines = wrap_text(text, remaining_width, advance_fs, style.letter_spacing);
+
+            for (li, line_text) in run_lines.iter().enumerate() {
+                if li > 0 {
+                    // Wrapped to next line
+                    text_y += line_height;
+                    current_line_x = line_start_x;
+                    current_line_width = 0.0;
+                    line_height = fs * line_spacing;
+                }
+
+                if text_y + fs > clip_bottom {
+                    break; // Clip to shape bounds
+                }
 
-            ShapeContent::Empty => {
-                // Shape with fill but no content
-                match &shape.fill {
-                    Some(ShapeFill::Solid(color)) => {
-                        page.elements.push(PageElement::Rect {
-                            x: shape.x,
-                            y: shape.y,
-                            width: shape.width,
-                            height: shape.height,
-                            fill: Some(*color),
-                            stroke: shape.outline.map(|(c, _)| c),
-                            stroke_width: shape.outline.map(|(_, w)| w).unwrap_or(0.0),
-                            rotation_deg: shape.rotation,
-                        });
-                    }
-                    Some(ShapeFill::Gradient { stops, angle }) => {
-                        page.elements.push(PageElement::GradientRect {
-                            x: shape.x,
-                            y: shape.y,
-                            width: shape.width,
-                            height: shape.height,
-                            stops: stops.clone(),
-                            gradient_type: GradientType::Linear(*angle),
-                            rotation_deg: shape.rotation,
-                        });
-                    }
-                    Some(ShapeFill::Image { data, mime_type }) => {
-                        page.elements.push(PageElement::Image {
-                            x: shape.x,
-                            y: shape.y,
-                            width: shape.width,
-                            height: shape.height,
-                            data: data.clone(),
-                            mime_type: mime_type.clone(),
+                let tw = estimate_run_width(line_text, advance_fs) + style.letter_spacing_width(line_text);
+                if !line_text.trim().is_empty() {
+                    // 蛍光ペンはランの幅・行の高さの矩形を文字の背面に描く
+                    if let Some(highlight) = run.highlight {
+                        elements.push(PageElement::Rect {
+                            x: current_line_x,
+                            y: text_y,
+                            width: tw,
+                            height: fs * 1.2,
+                            fill: Some(highlight),
+                            stroke: None,
+                            stroke_width: 0.0,
+                            rotation_deg: 0.0,
+                            stroke_style: Default::default(),
                         });
                     }
-                    None => {
-                        if let Some((color, width)) = shape.outline {
-                            page.elements.push(PageElement::Rect {
-                                x: shape.x,
-                                y: shape.y,
-                                width: shape.width,
-                                height: shape.height,
-                                fill: None,
-                                stroke: Some(color),
-                                stroke_width: width,
-                                rotation_deg: shape.rotation,
-                            });
-                        }
-                    }
+                    elements.push(PageElement::Text {
+                        x: current_line_x,
+                        y: text_y,
+                        width: tw,
+                        text: line_text.clone(),
+                        style: style.clone(),
+                        align: para.align,
+                    });
+                    line_started = true;
                 }
+                current_line_x += tw;
+                current_line_width += tw;
             }
         }
+
+        // Advance to next paragraph
+        if line_started || has_text {
+            text_y += line_height;
+        }
     }
 
-    page
+    (elements, text_y)
+}
+
+/// bodyPr の a:prstTxWarp から WordArt の変形プリセット名を取得（変形なしの textNoShape は None）
+fn parse_text_warp(e: &quick_xml::events::BytesStart) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == b"prst")
+        .map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+        .filter(|prst| prst != "textNoShape")
+}
+
+/// 文字を楕円の弧に沿って並べる WordArt の変形
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArcWarp {
+    /// 上側の弧に沿って左から右へ（textArchUp）
+    ArchUp,
+    /// 下側の弧に沿って左から右へ（textArchDown）
+    ArchDown,
+    /// 楕円の全周に沿って上端を中心に（textCircle）
+    Circle,
+}
+
+impl ArcWarp {
+    /// プリセット名から弧の種類を判定（Pour 系は複数行を1本の弧にまとめて扱う）
+    fn from_preset(prst: &str) -> Option<Self> {
+        match prst {
+            "textArchUp" | "textArchUpPour" => Some(ArcWarp::ArchUp),
+            "textArchDown" | "textArchDownPour" => Some(ArcWarp::ArchDown),
+            "textCircle" | "textCirclePour" => Some(ArcWarp::Circle),
+            _ => None,
+        }
+    }
+
+    /// 弧の開始角度と終了角度（ラジアン、Y軸下向きの画面座標。文字はこの順に並ぶ）
+    fn angle_range(self) -> (f64, f64) {
+        use std::f64::consts::PI;
+        match self {
+            ArcWarp::ArchUp => (-PI, 0.0),
+            ArcWarp::ArchDown => (PI, 0.0),
+            ArcWarp::Circle => (-1.5 * PI, 0.5 * PI),
+        }
+    }
+}
+
+/// 弧に沿う WordArt のテキストを1文字ずつ回転させて配置する
+/// 段落は空白でつないで1本の弧に並べ、文字は弧の全長に均等に広げます（弧に収まらない場合は縮小）。
+/// 上側の弧・全周は文字の上端が図形の枠に接するよう、ベースラインの楕円を最大の文字サイズ分だけ内側に取ります。
+fn layout_arc_text(shape: &SlideShape, paragraphs: &[ShapeParagraph], warp: ArcWarp) -> Vec<PageElement> {
+    const SAMPLES: usize = 360;
+    let font_scale = match shape.autofit {
+        TextAutofit::Shrink { font_scale, .. } => font_scale,
+        _ => 1.0,
+    };
+    let mut glyphs: Vec<(char, &TextRun)> = Vec::new();
+    for para in paragraphs {
+        if let Some(&(_, run)) = glyphs.last() {
+            glyphs.push((' ', run));
+        }
+        for run in &para.runs {
+            glyphs.extend(run.text.chars().map(|ch| (if ch == '\n' { ' ' } else { ch }, run)));
+        }
+    }
+    while glyphs.last().is_some_and(|(ch, _)| ch.is_whitespace()) {
+        glyphs.pop();
+    }
+    if glyphs.is_empty() {
+        return Vec::new();
+    }
+
+    let box_w = shape.width - shape.text_margin_left - shape.text_margin_right;
+    let box_h = shape.height - shape.text_margin_top - shape.text_margin_bottom;
+    let cx = shape.x + shape.text_margin_left + box_w / 2.0;
+    let cy = shape.y + shape.text_margin_top + box_h / 2.0;
+    let max_fs = glyphs.iter().map(|(_, run)| run.font_size * font_scale).fold(0.0, f64::max);
+    let inset = match warp {
+        ArcWarp::ArchDown => max_fs * 0.2,
+        ArcWarp::ArchUp | ArcWarp::Circle => max_fs,
+    };
+    let rx = (box_w / 2.0 - inset).max(1.0);
+    let ry = (box_h / 2.0 - inset).max(1.0);
+
+    // 弧長から角度を引くための累積長さの表
+    let (start, end) = warp.angle_range();
+    let angle_at = |i: usize| start + (end - start) * i as f64 / SAMPLES as f64;
+    let point_at = |t: f64| (cx + rx * t.cos(), cy + ry * t.sin());
+    let mut lengths = vec![0.0; SAMPLES + 1];
+    for i in 1..=SAMPLES {
+        let (x0, y0) = point_at(angle_at(i - 1));
+        let (x1, y1) = point_at(angle_at(i));
+        lengths[i] = lengths[i - 1] + (x1 - x0).hypot(y1 - y0);
+    }
+    let arc_length = lengths[SAMPLES];
+    let angle_for_length = |s: f64| {
+        let i = lengths.partition_point(|&l| l < s).clamp(1, SAMPLES);
+        let seg = lengths[i] - lengths[i - 1];
+        let frac = if seg > 0.0 { ((s - lengths[i - 1]) / seg).clamp(0.0, 1.0) } else { 0.0 };
+        angle_at(i - 1) + (angle_at(i) - angle_at(i - 1)) * frac
+    };
+
+    let advances: Vec<f64> = glyphs
+        .iter()
+        .map(|(ch, run)| {
+            let fs = run.font_size * font_scale;
+            estimate_run_width(ch.encode_utf8(&mut [0; 4]), fs) + run.letter_spacing * font_scale
+        })
+        .collect();
+    let total: f64 = advances.iter().sum();
+    if total <= 0.0 || arc_length <= 0.0 {
+        return Vec::new();
+    }
+    let spread = arc_length / total;
+    let shrink = spread.min(1.0);
+    let direction = (end - start).signum();
+
+    let mut elements = Vec::new();
+    let mut offset = 0.0;
+    for ((ch, run), advance) in glyphs.iter().zip(&advances) {
+        let t = angle_for_length((offset + advance / 2.0) * spread);
+        offset += advance;
+        if ch.is_whitespace() {
+            continue;
+        }
+        let (px, py) = point_at(t);
+        // 進行方向の接線の角度（時計回り）を文字の回転とする
+        let (tx, ty) = (-rx * t.sin() * direction, ry * t.cos() * direction);
+        let fs = run.font_size * font_scale * shrink;
+        let width = advance * shrink;
+        elements.push(PageElement::TransformGroup {
+            cx: px,
+            cy: py,
+            rotation_deg: ty.atan2(tx).to_degrees(),
+            opacity: 1.0,
+            children: vec![PageElement::Text {
+                x: px - width / 2.0,
+                y: py - fs,
+                width,
+                text: ch.to_string(),
+                style: FontStyle {
+                    font_size: fs,
+                    bold: run.bold,
+                    italic: run.italic,
+                    color: run.color.unwrap_or(Color::BLACK),
+                    underline: run.underline,
+                    ..FontStyle::default()
+                },
+                align: TextAlign::Left,
+            }],
+        });
+    }
+    elements
 }
 
-/// テキストをシェイプ幅に合わせて折り返す
-fn wrap_text(text: &str, available_width: f64, font_size: f64) -> Vec<String> {
+/// テキストをシェイプ幅に合わせて折り返す（`letter_spacing` は文字間隔）
+fn wrap_text(text: &str, available_width: f64, font_size: f64, letter_spacing: f64) -> Vec<String> {
     if text.is_empty() {
         return vec![];
     }
@@ -3141,7 +5668,7 @@ fn wrap_text(text: &str, available_width: f64, font_size: f64) -> Vec<String> {
             }
 
             current_line.push(ch);
-            current_width += char_width;
+            current_width += char_width + letter_spacing;
         }
 
         result.push(current_line);
@@ -3171,6 +5698,17 @@ fn estimate_run_width(text: &str, font_size: f64) -> f64 {
 /// シェイプのバウンディングボックス (x, y, width, height) を基にパスを計算
 ///
 /// DOCX, XLSX, PPTX で共通利用可能な86種類のプリセットジオメトリをサポート
+/// 楕円1周分の閉じたサブパス（右端から時計回りの2つの半円弧）
+fn ellipse_subpath(cx: f64, cy: f64, rx: f64, ry: f64) -> Vec<crate::converter::PathCommand> {
+    use crate::converter::PathCommand;
+    vec![
+        PathCommand::MoveTo(cx + rx, cy),
+        PathCommand::ArcTo(rx, ry, 0.0, false, true, cx - rx, cy),
+        PathCommand::ArcTo(rx, ry, 0.0, false, true, cx + rx, cy),
+        PathCommand::Close,
+    ]
+}
+
 pub fn generate_preset_path(name: &str, x: f64, y: f64, w: f64, h: f64) -> Option<Vec<crate::converter::PathCommand>> {
     use crate::converter::PathCommand;
     use std::f64::consts::PI;
@@ -3312,23 +5850,11 @@ pub fn generate_preset_path(name: &str, x: f64, y: f64, w: f64, h: f64) -> Optio
             Some(cmds)
         }
         "arc" => {
-            let cx = x + w / 2.0;
             let cy = y + h / 2.0;
-            let rx = w / 2.0;
-            let ry = h / 2.0;
-            let steps = 32;
-            let mut cmds = Vec::new();
-            for i in 0..=steps {
-                let angle = PI + PI * i as f64 / steps as f64;
-                let px = cx + rx * angle.cos();
-                let py = cy + ry * angle.sin();
-                if i == 0 {
-                    cmds.push(PathCommand::MoveTo(px, py));
-                } else {
-                    cmds.push(PathCommand::LineTo(px, py));
-                }
-            }
-            Some(cmds)
+            Some(vec![
+                PathCommand::MoveTo(x, cy),
+                PathCommand::ArcTo(w / 2.0, h / 2.0, 0.0, false, true, x + w, cy),
+            ])
         }
         "pie" | "pieWedge" => {
             let cx = x + w / 2.0;
@@ -3349,23 +5875,12 @@ pub fn generate_preset_path(name: &str, x: f64, y: f64, w: f64, h: f64) -> Optio
             Some(cmds)
         }
         "donut" => {
+            // 外側の楕円と内側の楕円を1つのパスに含め、偶奇規則で中央を抜く
             let cx = x + w / 2.0;
             let cy = y + h / 2.0;
-            let rx = w / 2.0;
-            let ry = h / 2.0;
-            let steps = 48;
-            let mut cmds = Vec::new();
-            for i in 0..=steps {
-                let angle = 2.0 * PI * i as f64 / steps as f64;
-                let px = cx + rx * angle.cos();
-                let py = cy + ry * angle.sin();
-                if i == 0 {
-                    cmds.push(PathCommand::MoveTo(px, py));
-                } else {
-                    cmds.push(PathCommand::LineTo(px, py));
-                }
-            }
-            cmds.push(PathCommand::Close);
+            let thickness = w.min(h) * 0.25;
+            let mut cmds = ellipse_subpath(cx, cy, w / 2.0, h / 2.0);
+            cmds.extend(ellipse_subpath(cx, cy, (w / 2.0 - thickness).max(0.0), (h / 2.0 - thickness).max(0.0)));
             Some(cmds)
         }
         "blockArc" => {
@@ -3687,21 +6202,16 @@ pub fn generate_preset_path(name: &str, x: f64, y: f64, w: f64, h: f64) -> Optio
             ])
         }
         // Flowchart: Terminator (stadium/rounded rectangle)
-        // ArcToの代わりにキュービックベジェで半円を近似（レンダラーのArcTo対応が不完全なため）
         "flowChartTerminator" => {
             let r = h / 2.0;
-            // 半円をキュービックベジェで近似: 制御点係数 = 4/3 * tan(π/8) ≈ 0.5523
-            let k = r * 0.5523;
             Some(vec![
                 PathCommand::MoveTo(x + r, y),
                 PathCommand::LineTo(x + w - r, y),
                 // 右側半円（上→下）
-                PathCommand::CubicTo(x + w - r + k, y, x + w, y + r - k, x + w, y + r),
-                PathCommand::CubicTo(x + w, y + r + k, x + w - r + k, y + h, x + w - r, y + h),
+                PathCommand::ArcTo(r, r, 0.0, false, true, x + w - r, y + h),
                 PathCommand::LineTo(x + r, y + h),
                 // 左側半円（下→上）
-                PathCommand::CubicTo(x + r - k, y + h, x, y + r + k, x, y + r),
-                PathCommand::CubicTo(x, y + r - k, x + r - k, y, x + r, y),
+                PathCommand::ArcTo(r, r, 0.0, false, true, x + r, y),
                 PathCommand::Close,
             ])
         }
@@ -3838,19 +6348,13 @@ pub fn generate_preset_path(name: &str, x: f64, y: f64, w: f64, h: f64) -> Optio
         "flowChartDelay" => {
             let rx = h / 2.0; // 半円の半径（高さの半分）
             let cx_arc = x + w - rx; // 半円の中心X（右端から半径分左）
-            let steps = 24;
-            let mut cmds = vec![PathCommand::MoveTo(x, y)];
-            cmds.push(PathCommand::LineTo(cx_arc, y));
-            for i in 0..=steps {
-                let angle = -PI / 2.0 + PI * i as f64 / steps as f64;
-                let px = cx_arc + rx * angle.cos();
-                let py = y + h / 2.0 + (h / 2.0) * angle.sin();
-                cmds.push(PathCommand::LineTo(px, py));
-            }
-            cmds.push(PathCommand::LineTo(cx_arc, y + h));
-            cmds.push(PathCommand::LineTo(x, y + h));
-            cmds.push(PathCommand::Close);
-            Some(cmds)
+            Some(vec![
+                PathCommand::MoveTo(x, y),
+                PathCommand::LineTo(cx_arc, y),
+                PathCommand::ArcTo(rx, h / 2.0, 0.0, false, true, cx_arc, y + h),
+                PathCommand::LineTo(x, y + h),
+                PathCommand::Close,
+            ])
         }
         // Flowchart: Display (curved sides)
         "flowChartDisplay" => {
@@ -3949,32 +6453,13 @@ pub fn generate_preset_path(name: &str, x: f64, y: f64, w: f64, h: f64) -> Optio
         }
         // Special Shapes: Moon (crescent)
         "moon" => {
+            // 外側の円から右寄りの内側の円を抜く（偶奇規則）
             let cx = x + w / 2.0;
             let cy = y + h / 2.0;
             let rx = w / 2.0;
             let ry = h / 2.0;
-            let steps = 48;
-            let mut cmds = Vec::new();
-            // Outer circle
-            for i in 0..=steps {
-                let angle = 2.0 * PI * i as f64 / steps as f64;
-                let px = cx + rx * angle.cos();
-                let py = cy + ry * angle.sin();
-                if i == 0 {
-                    cmds.push(PathCommand::MoveTo(px, py));
-                } else {
-                    cmds.push(PathCommand::LineTo(px, py));
-                }
-            }
-            // Inner crescent cutout (reverse direction for hole)
-            let offset_x = w * 0.2;
-            for i in (0..=steps).rev() {
-                let angle = 2.0 * PI * i as f64 / steps as f64;
-                let px = cx + offset_x + rx * 0.6 * angle.cos();
-                let py = cy + ry * 0.6 * angle.sin();
-                cmds.push(PathCommand::LineTo(px, py));
-            }
-            cmds.push(PathCommand::Close);
+            let mut cmds = ellipse_subpath(cx, cy, rx, ry);
+            cmds.extend(ellipse_subpath(cx + w * 0.2, cy, rx * 0.6, ry * 0.6));
             Some(cmds)
         }
         // Special Shapes: Smiley Face → generate_preset_paths() で複数パスとして出力
@@ -4056,8 +6541,22 @@ pub fn generate_preset_path(name: &str, x: f64, y: f64, w: f64, h: f64) -> Optio
                 PathCommand::Close,
             ])
         }
-        // Special Shapes: Frame → generate_preset_paths() で複数パスとして出力
-        "frame" => None,
+        // Special Shapes: Frame (hollow rectangle) - 外枠と内枠を1つのパスに含め、偶奇規則で内側を抜く
+        "frame" => {
+            let t = w.min(h) * 0.15;
+            Some(vec![
+                PathCommand::MoveTo(x, y),
+                PathCommand::LineTo(x + w, y),
+                PathCommand::LineTo(x + w, y + h),
+                PathCommand::LineTo(x, y + h),
+                PathCommand::Close,
+                PathCommand::MoveTo(x + t, y + t),
+                PathCommand::LineTo(x + t, y + h - t),
+                PathCommand::LineTo(x + w - t, y + h - t),
+                PathCommand::LineTo(x + w - t, y + t),
+                PathCommand::Close,
+            ])
+        }
         // Special Shapes: Bevel (3D beveled rectangle)
         "bevel" => {
             let bevel = w.min(h) * 0.12;
@@ -4896,9 +7395,9 @@ pub fn generate_preset_path(name: &str, x: f64, y: f64, w: f64, h: f64) -> Optio
     }
 }
 
-/// 複数のサブパスを持つプリセットジオメトリを生成
-/// ラスタレンダラーがサブパス（パス内の複数のMoveTo-Close単位）を正しく処理できないため、
-/// 別々のパス要素として返す必要があるジオメトリ用
+/// 複数のパス要素に分けて描画するプリセットジオメトリを生成
+/// 穴のある図形（donut / frame 等）は偶奇規則の単一パスで表せるため `generate_preset_path` を使用し、
+/// こちらは塗りを持たない線分（内側の区切り線など）を別要素として描く必要があるジオメトリ用
 pub fn generate_preset_paths(name: &str, x: f64, y: f64, w: f64, h: f64) -> Option<Vec<Vec<crate::converter::PathCommand>>> {
     use crate::converter::PathCommand;
     use std::f64::consts::PI;
@@ -4993,28 +7492,6 @@ pub fn generate_preset_paths(name: &str, x: f64, y: f64, w: f64, h: f64) -> Opti
             }
 
             Some(vec![face, left_eye, right_eye, smile])
-        }
-        // Special Shapes: Frame (hollow rectangle) - 外枠と内枠を別パスで出力
-        "frame" => {
-            let thickness = w.min(h) * 0.15;
-            Some(vec![
-                // 外枠
-                vec![
-                    PathCommand::MoveTo(x, y),
-                    PathCommand::LineTo(x + w, y),
-                    PathCommand::LineTo(x + w, y + h),
-                    PathCommand::LineTo(x, y + h),
-                    PathCommand::Close,
-                ],
-                // 内枠（ストロークのみ、フィルなし）
-                vec![
-                    PathCommand::MoveTo(x + thickness, y + thickness),
-                    PathCommand::LineTo(x + w - thickness, y + thickness),
-                    PathCommand::LineTo(x + w - thickness, y + h - thickness),
-                    PathCommand::LineTo(x + thickness, y + h - thickness),
-                    PathCommand::Close,
-                ],
-            ])
         }
             // === Auto-generated multi-path shapes from C++ OOXML definitions ===
 "flowChartInternalStorage" => {
@@ -6529,46 +9006,6 @@ pub fn generate_preset_paths(name: &str, x: f64, y: f64, w: f64, h: f64) -> Opti
         _ => None,
     }
 }
-fn read_pptx_metadata(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>) -> Metadata {
-    let mut metadata = Metadata::default();
-
-    if let Ok(core_xml) = read_zip_entry_string(archive, "docProps/core.xml") {
-        let mut reader = quick_xml::Reader::from_str(&core_xml);
-        let mut buf = Vec::new();
-        let mut current_tag = String::new();
-        let mut in_tag = false;
-
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(quick_xml::events::Event::Start(ref e)) => {
-                    current_tag =
-                        String::from_utf8_lossy(e.local_name().as_ref()).to_string();
-                    in_tag = true;
-                }
-                Ok(quick_xml::events::Event::Text(ref e)) => {
-                    if in_tag {
-                        if let Ok(text) = e.unescape() {
-                            match current_tag.as_str() {
-                                "title" => metadata.title = Some(text.to_string()),
-                                "creator" => metadata.author = Some(text.to_string()),
-                                "subject" => metadata.subject = Some(text.to_string()),
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-                Ok(quick_xml::events::Event::End(_)) => in_tag = false,
-                Ok(quick_xml::events::Event::Eof) => break,
-                Err(_) => break,
-                _ => {}
-            }
-            buf.clear();
-        }
-    }
-
-    metadata.creator = Some("WASM Document Converter".to_string());
-    metadata
-}
 
 #[cfg(test)]
 mod tests {
@@ -6596,7 +9033,7 @@ mod tests {
 
     #[test]
     fn test_wrap_text() {
-        let lines = wrap_text("Hello World", 100.0, 12.0);
+        let lines = wrap_text("Hello World", 100.0, 12.0, 0.0);
         assert!(!lines.is_empty());
     }
 
@@ -6606,10 +9043,501 @@ mod tests {
         assert_eq!(extract_slide_number("ppt/slides/slide10.xml"), 10);
     }
 
+    #[test]
+    fn test_gradient_stops_sorted_and_clamped() {
+        let xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+               xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+          <p:cSld><p:spTree>
+            <p:sp>
+              <p:spPr>
+                <a:xfrm><a:off x="0" y="0"/><a:ext cx="914400" cy="914400"/></a:xfrm>
+                <a:gradFill>
+                  <a:gsLst>
+                    <a:gs pos="100000"><a:srgbClr val="0000FF"/></a:gs>
+                    <a:gs pos="50000"><a:srgbClr val="00FF00"/></a:gs>
+                    <a:gs pos="0"><a:srgbClr val="FF0000"/></a:gs>
+                  </a:gsLst>
+                  <a:lin ang="-5400000" scaled="0"/>
+                </a:gradFill>
+              </p:spPr>
+            </p:sp>
+          </p:spTree></p:cSld>
+        </p:sld>"#;
+        let shapes = parse_slide_shapes(xml, &ThemeColors::default());
+        assert_eq!(shapes.len(), 1);
+        match &shapes[0].fill {
+            Some(ShapeFill::Gradient { stops, angle }) => {
+                let positions: Vec<f64> = stops.iter().map(|s| s.position).collect();
+                assert_eq!(positions, vec![0.0, 0.5, 1.0]);
+                assert_eq!(stops[0].color, Color::rgb(255, 0, 0));
+                // -90° は 270° に正規化される
+                assert!((angle - 270f64.to_radians()).abs() < 1e-9);
+            }
+            other => panic!("expected gradient fill, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_gradient_tile_rect_remaps_stops() {
+        let stops = vec![
+            GradientStop { position: 0.0, color: Color::BLACK },
+            GradientStop { position: 1.0, color: Color::WHITE },
+        ];
+        // 水平方向のグラデーションを左右25%内側のタイルに収める
+        let out = normalize_gradient_stops(&stops, 0.0, Some((0.25, 0.0, 0.25, 0.0)));
+        assert!((out[0].position - 0.25).abs() < 1e-9);
+        assert!((out[1].position - 0.75).abs() < 1e-9);
+    }
+
+    fn solid_fill_of(fill_xml: &str) -> Option<Color> {
+        let xml = format!(
+            r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+               xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+          <p:cSld><p:spTree><p:sp><p:spPr>
+            <a:xfrm><a:off x="0" y="0"/><a:ext cx="914400" cy="914400"/></a:xfrm>
+            <a:solidFill>{}</a:solidFill>
+          </p:spPr></p:sp></p:spTree></p:cSld></p:sld>"#,
+            fill_xml
+        );
+        let shapes = parse_slide_shapes(&xml, &ThemeColors::default());
+        match shapes.into_iter().next().and_then(|s| s.fill) {
+            Some(ShapeFill::Solid(c)) => Some(c),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_scrgb_and_hsl_colors() {
+        assert_eq!(
+            solid_fill_of(r#"<a:scrgbClr r="50000" g="50000" b="50000"/>"#),
+            Some(Color::rgb(128, 128, 128))
+        );
+        assert_eq!(
+            solid_fill_of(r#"<a:hslClr hue="14400000" sat="100000" lum="50000"/>"#),
+            Some(Color::rgb(0, 0, 255))
+        );
+    }
+
+    #[test]
+    fn test_scheme_color_lum_mod() {
+        // accent1 (5B9BD5) の輝度を50%にすると暗い青になる
+        assert_eq!(
+            solid_fill_of(r#"<a:schemeClr val="accent1"><a:lumMod val="50000"/></a:schemeClr>"#),
+            Some(Color::rgb(31, 78, 121))
+        );
+        // 子要素のない schemeClr はテーマ色そのもの
+        assert_eq!(
+            solid_fill_of(r#"<a:schemeClr val="accent1"/>"#),
+            Some(Color::rgb(91, 155, 213))
+        );
+    }
+
+    #[test]
+    fn test_theme_color_modifiers_on_start_elements() {
+        // accent1 + lumMod 60% + lumOff 40% は「アクセント1 白+基本色40%」（9DC3E6）
+        assert_eq!(
+            solid_fill_of(
+                r#"<a:schemeClr val="accent1"><a:lumMod val="60000"/><a:lumOff val="40000"/></a:schemeClr>"#
+            ),
+            Some(Color::rgb(157, 195, 230))
+        );
+
+        // p:style の lnRef（accent1 + shade 50%）は既定の枠線色 41719C になる
+        let xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+               xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+          <p:cSld><p:spTree><p:sp>
+            <p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="914400" cy="914400"/></a:xfrm></p:spPr>
+            <p:style>
+              <a:lnRef idx="2"><a:schemeClr val="accent1"><a:shade val="50000"/></a:schemeClr></a:lnRef>
+              <a:fillRef idx="1"><a:schemeClr val="accent1"/></a:fillRef>
+            </p:style>
+          </p:sp></p:spTree></p:cSld></p:sld>"#;
+        let shapes = parse_slide_shapes(xml, &ThemeColors::default());
+        assert_eq!(shapes[0].outline.map(|o| o.color), Some(Color::rgb(65, 113, 156)));
+    }
+
+    fn text_shape_xml(body_pr: &str, height_emu: u32, paragraphs: &str) -> String {
+        format!(
+            r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+               xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+          <p:cSld><p:spTree><p:sp>
+            <p:spPr>
+              <a:xfrm><a:off x="0" y="0"/><a:ext cx="3657600" cy="{}"/></a:xfrm>
+              <a:solidFill><a:srgbClr val="DDDDDD"/></a:solidFill>
+            </p:spPr>
+            <p:txBody>{}{}</p:txBody>
+          </p:sp></p:spTree></p:cSld></p:sld>"#,
+            height_emu, body_pr, paragraphs
+        )
+    }
+
+    #[test]
+    fn test_norm_autofit_scales_font_sizes() {
+        let xml = text_shape_xml(
+            r#"<a:bodyPr><a:normAutofit fontScale="80000" lnSpcReduction="10000"/></a:bodyPr>"#,
+            1828800,
+            r#"<a:p><a:r><a:rPr sz="2000"/><a:t>Title</a:t></a:r></a:p>
+               <a:p><a:r><a:rPr sz="1500"/><a:t>Body</a:t></a:r></a:p>"#,
+        );
+        let shapes = parse_slide_shapes(&xml, &ThemeColors::default());
+        let page = render_slide_page(&shapes, &SlideSize::default(), None, UnknownGeometryPolicy::Skip, &mut Vec::new());
+        let sizes: Vec<f64> = page
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                PageElement::Text { style, .. } => Some(style.font_size),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(sizes.len(), 2);
+        assert!((sizes[0] - 16.0).abs() < 1e-9);
+        assert!((sizes[1] - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sp_auto_fit_grows_shape() {
+        let paragraphs: String = (0..6)
+            .map(|i| format!(r#"<a:p><a:r><a:rPr sz="1800"/><a:t>Line {}</a:t></a:r></a:p>"#, i))
+            .collect();
+        // 高さ 36pt の図形に 6 行（18pt）のテキスト
+        let xml = text_shape_xml(r#"<a:bodyPr><a:spAutoFit/></a:bodyPr>"#, 457200, &paragraphs);
+        let shapes = parse_slide_shapes(&xml, &ThemeColors::default());
+        let page = render_slide_page(&shapes, &SlideSize::default(), None, UnknownGeometryPolicy::Skip, &mut Vec::new());
+        let fill_height = page.elements.iter().find_map(|e| match e {
+            PageElement::Rect { height, fill: Some(_), .. } => Some(*height),
+            _ => None,
+        });
+        assert!(fill_height.is_some_and(|h| h > 6.0 * 18.0));
+        let texts = page.elements.iter().filter(|e| matches!(e, PageElement::Text { .. })).count();
+        assert_eq!(texts, 6);
+    }
+
+    #[test]
+    fn test_run_letter_spacing_widens_run() {
+        let run_width = |rpr: &str| {
+            let xml = text_shape_xml(
+                "<a:bodyPr/>",
+                4572000,
+                &format!(r#"<a:p><a:r>{}<a:t>Title</a:t></a:r></a:p>"#, rpr),
+            );
+            let shapes = parse_slide_shapes(&xml, &ThemeColors::default());
+            let page = render_slide_page(&shapes, &SlideSize::default(), None, UnknownGeometryPolicy::Skip, &mut Vec::new());
+            page.elements
+                .iter()
+                .find_map(|e| match e {
+                    PageElement::Text { text, width, style, .. } if text == "Title" => Some((*width, style.letter_spacing)),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        let (plain, plain_spacing) = run_width(r#"<a:rPr sz="2000"/>"#);
+        let (spaced, spacing) = run_width(r#"<a:rPr sz="2000" spc="2000" kern="0"/>"#);
+        assert_eq!((plain_spacing, spacing), (0.0, 20.0));
+        // 5文字の間の4か所に20ptずつ加算される
+        assert!((spaced - plain - 20.0 * 4.0).abs() < 1e-9, "{} -> {}", plain, spaced);
+    }
+
+    #[test]
+    fn test_arch_up_word_art_follows_arc() {
+        let xml = text_shape_xml(
+            r#"<a:bodyPr><a:prstTxWarp prst="textArchUp"><a:avLst/></a:prstTxWarp></a:bodyPr>"#,
+            1828800,
+            r#"<a:p><a:r><a:rPr sz="2000"/><a:t>ARCHED</a:t></a:r></a:p>"#,
+        );
+        let shapes = parse_slide_shapes(&xml, &ThemeColors::default());
+        assert_eq!(shapes[0].text_warp.as_deref(), Some("textArchUp"));
+        let mut warnings = Vec::new();
+        let page = render_slide_page(&shapes, &SlideSize::default(), None, UnknownGeometryPolicy::Skip, &mut warnings);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        // 1文字ずつ回転グループに入り、中央の文字ほど上（Y が小さい）に並ぶ
+        let glyphs: Vec<(f64, f64, f64)> = page
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                PageElement::TransformGroup { cx, cy, rotation_deg, children, .. } => {
+                    assert!(matches!(&children[..], [PageElement::Text { text, .. }] if text.chars().count() == 1));
+                    Some((*cx, *cy, *rotation_deg))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(glyphs.len(), 6);
+        assert!(glyphs.windows(2).all(|w| w[0].0 < w[1].0));
+        let (first, middle, last) = (glyphs[0], glyphs[2], glyphs[5]);
+        assert!(middle.1 < first.1 && middle.1 < last.1, "{:?}", glyphs);
+        // 左端は右上がり（反時計回り）、右端は右下がり（時計回り）に傾く
+        assert!(first.2 < -10.0 && last.2 > 10.0, "{:?}", glyphs);
+    }
+
+    #[test]
+    fn test_unsupported_word_art_warp_falls_back_to_straight_text() {
+        let xml = text_shape_xml(
+            r#"<a:bodyPr><a:prstTxWarp prst="textWave1"><a:avLst/></a:prstTxWarp></a:bodyPr>"#,
+            1828800,
+            r#"<a:p><a:r><a:rPr sz="2000"/><a:t>Wavy</a:t></a:r></a:p>"#,
+        );
+        let shapes = parse_slide_shapes(&xml, &ThemeColors::default());
+        let mut warnings = Vec::new();
+        let page = render_slide_page(&shapes, &SlideSize::default(), None, UnknownGeometryPolicy::Skip, &mut warnings);
+        assert!(page.elements.iter().any(|e| matches!(e, PageElement::Text { text, .. } if text == "Wavy")));
+        assert!(!page.elements.iter().any(|e| matches!(e, PageElement::TransformGroup { .. })));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("textWave1"));
+    }
+
+    #[test]
+    fn test_highlighted_run_draws_background_rect() {
+        let xml = text_shape_xml(
+            "<a:bodyPr/>",
+            1828800,
+            r#"<a:p><a:r><a:rPr sz="2000"/><a:t>Plain </a:t></a:r><a:r><a:rPr sz="2000"><a:highlight><a:srgbClr val="FFFF00"/></a:highlight></a:rPr><a:t>Marked</a:t></a:r></a:p>"#,
+        );
+        let shapes = parse_slide_shapes(&xml, &ThemeColors::default());
+        let page = render_slide_page(&shapes, &SlideSize::default(), None, UnknownGeometryPolicy::Skip, &mut Vec::new());
+        let text_index = page
+            .elements
+            .iter()
+            .position(|e| matches!(e, PageElement::Text { text, .. } if text == "Marked"))
+            .unwrap();
+        let PageElement::Text { x, y, width, .. } = &page.elements[text_index] else { unreachable!() };
+        // 文字の直前（背面）にランと同じ位置・幅の黄色い矩形
+        match &page.elements[text_index - 1] {
+            PageElement::Rect { x: rx, y: ry, width: rw, height, fill, .. } => {
+                assert_eq!((rx, ry, rw), (x, y, width));
+                assert!((height - 20.0 * 1.2).abs() < 1e-9);
+                assert_eq!(*fill, Some(Color::rgb(255, 255, 0)));
+            }
+            other => panic!("背景の矩形がありません: {:?}", other),
+        }
+        // 蛍光ペンの無いランには矩形を描かない
+        let yellow = Some(Color::rgb(255, 255, 0));
+        let rects = page
+            .elements
+            .iter()
+            .filter(|e| matches!(e, PageElement::Rect { fill, .. } if *fill == yellow))
+            .count();
+        assert_eq!(rects, 1);
+    }
+
+    #[test]
+    fn test_auto_number_increments_within_text_body() {
+        let numbered = |scheme: &str, text: &str| {
+            format!(r#"<a:p><a:pPr><a:buAutoNum type="{}"/></a:pPr><a:r><a:t>{}</a:t></a:r></a:p>"#, scheme, text)
+        };
+        let paragraphs = [
+            numbered("arabicPeriod", "one"),
+            numbered("arabicPeriod", "two"),
+            numbered("arabicPeriod", "three"),
+            numbered("romanUcPeriod", "first"),
+            numbered("romanUcPeriod", "second"),
+        ]
+        .concat();
+        let xml = text_shape_xml("<a:bodyPr/>", 1828800, &paragraphs);
+        let shapes = parse_slide_shapes(&xml, &ThemeColors::default());
+        let page = render_slide_page(&shapes, &SlideSize::default(), None, UnknownGeometryPolicy::Skip, &mut Vec::new());
+        let markers: Vec<&str> = page
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                PageElement::Text { text, .. } => Some(text.trim_end()),
+                _ => None,
+            })
+            .filter(|t| t.ends_with('.'))
+            .collect();
+        // 書式が変わると番号は startAt から振り直す
+        assert_eq!(markers, vec!["1.", "2.", "3.", "I.", "II."]);
+        assert_eq!(format_auto_number("alphaLcParenR", 28), "bb)");
+        assert_eq!(format_auto_number("arabicParenBoth", 4), "(4)");
+    }
+
+    #[test]
+    fn test_ellipse_shadow_follows_geometry() {
+        let xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+               xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+          <p:cSld><p:spTree><p:sp>
+            <p:spPr>
+              <a:xfrm><a:off x="0" y="0"/><a:ext cx="1270000" cy="635000"/></a:xfrm>
+              <a:prstGeom prst="ellipse"/>
+              <a:solidFill><a:srgbClr val="4472C4"/></a:solidFill>
+              <a:effectLst><a:outerShdw blurRad="50800" dist="38100" dir="2700000">
+                <a:srgbClr val="000000"/>
+              </a:outerShdw></a:effectLst>
+            </p:spPr>
+            <p:txBody><a:bodyPr/><a:p><a:r><a:t>Oval</a:t></a:r></a:p></p:txBody>
+          </p:sp></p:spTree></p:cSld></p:sld>"#;
+        let shapes = parse_slide_shapes(xml, &ThemeColors::default());
+        let page = render_slide_page(&shapes, &SlideSize::default(), None, UnknownGeometryPolicy::Skip, &mut Vec::new());
+        assert!(!page.elements.iter().any(|e| matches!(e, PageElement::Rect { .. })));
+        let shadows: Vec<(f64, f64, f64)> = page
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                PageElement::Ellipse { cx, cy, rx, fill: Some(c), .. } if c.r == 0 => Some((*cx, *cy, *rx)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(shadows.len(), SHADOW_BLUR_PASSES);
+        // 影は右下（45°、3pt）にずれ、ぼかしの分だけ外側のパスが大きい
+        let offset = 3.0 * std::f64::consts::FRAC_1_SQRT_2;
+        assert!(shadows.iter().all(|(cx, cy, _)| (cx - 50.0 - offset).abs() < 1e-6 && (cy - 25.0 - offset).abs() < 1e-6));
+        assert!(shadows[0].2 > shadows[SHADOW_BLUR_PASSES - 1].2);
+        assert!((shadows[SHADOW_BLUR_PASSES - 1].2 - 50.0).abs() < 1e-6);
+        // 図形本体は影の後に描画される
+        assert!(matches!(
+            page.elements.get(SHADOW_BLUR_PASSES),
+            Some(PageElement::Ellipse { fill: Some(c), .. }) if c.b == 0xC4
+        ));
+    }
+
+    #[test]
+    fn test_gradient_fill_on_preset_geometry() {
+        let xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+               xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+          <p:cSld><p:spTree><p:sp>
+            <p:spPr>
+              <a:xfrm><a:off x="0" y="0"/><a:ext cx="1270000" cy="1270000"/></a:xfrm>
+              <a:prstGeom prst="hexagon"/>
+              <a:gradFill><a:gsLst>
+                <a:gs pos="0"><a:srgbClr val="FF0000"/></a:gs>
+                <a:gs pos="100000"><a:srgbClr val="0000FF"/></a:gs>
+              </a:gsLst><a:lin ang="0"/></a:gradFill>
+              <a:ln w="12700"><a:solidFill><a:srgbClr val="000000"/></a:solidFill></a:ln>
+            </p:spPr>
+            <p:txBody><a:bodyPr/><a:p><a:r><a:t>Hex</a:t></a:r></a:p></p:txBody>
+          </p:sp></p:spTree></p:cSld></p:sld>"#;
+        let shapes = parse_slide_shapes(xml, &ThemeColors::default());
+        let page = render_slide_page(&shapes, &SlideSize::default(), None, UnknownGeometryPolicy::Skip, &mut Vec::new());
+        assert!(!page.elements.iter().any(|e| matches!(e, PageElement::Path { .. })));
+        let Some(PageElement::GradientPath { commands, stops, stroke, .. }) =
+            page.elements.iter().find(|e| matches!(e, PageElement::GradientPath { .. }))
+        else {
+            panic!("GradientPath が出力されていません: {:?}", page.elements);
+        };
+        assert!(commands.len() >= 6);
+        assert_eq!(stops.first().map(|s| s.color), Some(Color::rgb(255, 0, 0)));
+        assert_eq!(stops.last().map(|s| s.color), Some(Color::rgb(0, 0, 255)));
+        assert_eq!(*stroke, Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_group_child_offset_and_extent_transform() {
+        // 外側: 子座標系 100pt 四方を (100pt, 100pt) の 200pt × 100pt に配置（横2倍）
+        // 内側: 外側の子座標系で (50pt, 50pt) の 50pt 四方に、子座標系 100pt 四方を配置（0.5倍）
+        let xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+               xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+          <p:cSld><p:spTree><p:grpSp>
+            <p:grpSpPr><a:xfrm>
+              <a:off x="1270000" y="1270000"/><a:ext cx="2540000" cy="1270000"/>
+              <a:chOff x="0" y="0"/><a:chExt cx="1270000" cy="1270000"/>
+            </a:xfrm></p:grpSpPr>
+            <p:sp><p:spPr>
+              <a:xfrm><a:off x="0" y="0"/><a:ext cx="635000" cy="635000"/></a:xfrm>
+            </p:spPr></p:sp>
+            <p:grpSp>
+              <p:grpSpPr><a:xfrm>
+                <a:off x="635000" y="635000"/><a:ext cx="635000" cy="635000"/>
+                <a:chOff x="0" y="0"/><a:chExt cx="1270000" cy="1270000"/>
+              </a:xfrm></p:grpSpPr>
+              <p:sp><p:spPr>
+                <a:xfrm><a:off x="0" y="0"/><a:ext cx="1270000" cy="1270000"/></a:xfrm>
+              </p:spPr></p:sp>
+            </p:grpSp>
+            <p:sp><p:spPr>
+              <a:xfrm><a:off x="635000" y="0"/><a:ext cx="635000" cy="635000"/></a:xfrm>
+            </p:spPr></p:sp>
+          </p:grpSp></p:spTree></p:cSld>
+        </p:sld>"#;
+        let shapes = parse_slide_shapes(xml, &ThemeColors::default());
+        let rects: Vec<_> = shapes.iter().map(|s| (s.x, s.y, s.width, s.height)).collect();
+        assert_eq!(
+            rects,
+            vec![
+                // 子座標系の 50pt 四方は横2倍に拡大される
+                (100.0, 100.0, 100.0, 50.0),
+                // 入れ子のグループは内側・外側の変換を合成
+                (200.0, 150.0, 100.0, 50.0),
+                // 入れ子のグループを抜けると外側の変換に戻る
+                (200.0, 100.0, 100.0, 50.0),
+            ]
+        );
+        assert_eq!(shapes[0].clip, Some((100.0, 100.0, 200.0, 100.0)));
+        assert_eq!(shapes[1].clip, Some((200.0, 150.0, 100.0, 50.0)));
+        assert_eq!(shapes[2].clip, Some((100.0, 100.0, 200.0, 100.0)));
+    }
+
     #[test]
     fn test_default_slide_size() {
         let ss = SlideSize::default();
         assert!((ss.width - 720.0).abs() < 0.01);
         assert!((ss.height - 540.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_run_strike_and_baseline_attrs() {
+        assert!(parse_strike_attr(b"sngStrike"));
+        assert!(parse_strike_attr(b"dblStrike"));
+        assert!(!parse_strike_attr(b"noStrike"));
+        assert!((parse_baseline_attr(b"30000") - 0.3).abs() < 1e-9);
+        assert!((parse_baseline_attr(b"-25000") + 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_table_cell_fill_and_borders() {
+        let xml = r#"<p:graphicFrame><p:xfrm><a:off x="0" y="0"/><a:ext cx="1270000" cy="254000"/></p:xfrm>
+<a:graphic><a:graphicData><a:tbl><a:tblGrid><a:gridCol w="1270000"/></a:tblGrid>
+<a:tr h="254000"><a:tc><a:txBody><a:p><a:r><a:t>A</a:t></a:r></a:p></a:txBody>
+<a:tcPr><a:lnL w="25400"><a:solidFill><a:srgbClr val="FF0000"/></a:solidFill></a:lnL>
+<a:lnR w="12700"><a:noFill/></a:lnR>
+<a:solidFill><a:srgbClr val="FFFF00"/></a:solidFill></a:tcPr></a:tc></a:tr>
+</a:tbl></a:graphicData></a:graphic></p:graphicFrame>"#;
+        let elements = detect_and_render_tables(xml, &ThemeColors::default());
+        let Some(PageElement::TableBlock { table, .. }) = elements.first() else {
+            panic!("テーブルが検出されません");
+        };
+        let cell = &table.rows[0][0];
+        assert_eq!(cell.background, Some(Color::rgb(255, 255, 0)));
+        assert_eq!(cell.style.color, Color::BLACK);
+        let borders = cell.borders.expect("枠線が解析されていません");
+        let left = borders.left.expect("左辺");
+        assert!((left.width - 2.0).abs() < 1e-9);
+        assert_eq!(left.color, Color::rgb(255, 0, 0));
+        assert!(borders.right.is_none());
+        assert!(borders.top.is_none());
+    }
+
+    #[test]
+    fn test_table_cell_norm_autofit_requests_shrink() {
+        let xml = r#"<p:graphicFrame><p:xfrm><a:off x="0" y="0"/><a:ext cx="2540000" cy="254000"/></p:xfrm>
+<a:graphic><a:graphicData><a:tbl><a:tblGrid><a:gridCol w="1270000"/><a:gridCol w="1270000"/></a:tblGrid>
+<a:tr h="254000"><a:tc><a:txBody><a:bodyPr><a:normAutofit/></a:bodyPr><a:p><a:r><a:t>A</a:t></a:r></a:p></a:txBody></a:tc>
+<a:tc><a:txBody><a:bodyPr/><a:p><a:r><a:t>B</a:t></a:r></a:p></a:txBody></a:tc></a:tr>
+</a:tbl></a:graphicData></a:graphic></p:graphicFrame>"#;
+        let elements = detect_and_render_tables(xml, &ThemeColors::default());
+        let Some(PageElement::TableBlock { table, .. }) = elements.first() else {
+            panic!("テーブルが検出されません");
+        };
+        assert!(table.rows[0][0].shrink_to_fit);
+        assert!(!table.rows[0][1].shrink_to_fit);
+    }
+
+    #[test]
+    fn test_text_field_types_and_date_formats() {
+        assert_eq!(TextField::from_type("slidenum"), Some(TextField::SlideNumber));
+        assert_eq!(TextField::from_type("datetime"), Some(TextField::DateTime(1)));
+        assert_eq!(TextField::from_type("datetime13"), Some(TextField::DateTime(13)));
+        assert_eq!(TextField::from_type("datetime14"), None);
+        assert_eq!(TextField::from_type("footer"), None);
+
+        // 2026-10-16 14:05:09 UTC（金曜日）
+        let date = FieldDate::from_unix_seconds(1_792_159_509);
+        assert_eq!(
+            date,
+            FieldDate { year: 2026, month: 10, day: 16, hour: 14, minute: 5, second: 9 }
+        );
+        assert_eq!(date.format(1), "10/16/2026");
+        assert_eq!(date.format(2), "Friday, October 16, 2026");
+        assert_eq!(date.format(5), "16-Oct-26");
+        assert_eq!(date.format(12), "2:05 PM");
+        assert_eq!(FieldDate::from_unix_seconds(0).format(4), "January 1, 1970");
+    }
 }
@@ -87,6 +87,7 @@ pub fn render_smartart(
                 stroke: Some(Color::WHITE),
                 stroke_width: 1.0,
                 rotation_deg: 0.0,
+                stroke_style: Default::default(),
             });
         } else {
             elements.push(PageElement::Rect {
@@ -95,6 +96,7 @@ pub fn render_smartart(
                 stroke: Some(Color::WHITE),
                 stroke_width: 1.0,
                 rotation_deg: 0.0,
+                stroke_style: Default::default(),
             });
         }
 
@@ -411,6 +413,7 @@ fn render_text_grid(x: f64, y: f64, width: f64, height: f64, items: &[String]) -
             stroke: Some(Color::WHITE),
             stroke_width: 1.0,
             rotation_deg: 0.0,
+            stroke_style: Default::default(),
         });
 
         let font_size = (cell_h * 0.25).min(MAX_FONT_SIZE).max(MIN_FONT_SIZE);
@@ -441,6 +444,7 @@ fn render_placeholder(x: f64, y: f64, width: f64, height: f64) -> Vec<PageElemen
             stroke: Some(Color::rgb(200, 200, 200)),
             stroke_width: 1.0,
             rotation_deg: 0.0,
+            stroke_style: Default::default(),
         },
         PageElement::Text {
             x: x + 10.0,
@@ -0,0 +1,179 @@
+// formats/transitions.rs - スライド切り替え効果の抽出モジュール
+//
+// 描画は静止画として行うため切り替え効果は無視しますが、呼び出し側で
+// 再生を再構成できるよう、PPTXの各スライドの <p:transition> を読み取ります。
+
+use crate::converter::ConvertError;
+use serde::Serialize;
+
+type ZipArchive<'a> = zip::ZipArchive<std::io::Cursor<&'a [u8]>>;
+
+/// スライドの切り替え効果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlideTransition {
+    /// スライド番号（1始まり、変換結果のページ順）
+    pub slide: usize,
+    /// 効果の種類（"fade"・"push" 等の要素名、効果要素が無ければ "none"）
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// 効果の再生時間（ミリ秒）
+    pub duration: u32,
+    /// クリックで次のスライドに進むか
+    pub advance_on_click: bool,
+    /// 自動で次のスライドに進むまでの時間（ミリ秒、未指定ならNone）
+    pub advance_after: Option<u32>,
+}
+
+/// spd 属性の速度ごとの再生時間（PowerPointの既定値、ミリ秒）
+fn speed_duration(spd: &str) -> u32 {
+    match spd {
+        "slow" => 1000,
+        "med" => 750,
+        _ => 500,
+    }
+}
+
+/// PPTXの各スライドの切り替え効果を抽出
+/// <p:transition> の無いスライドは含めません。PPTX以外のフォーマットは空の一覧を返します。
+pub fn extract_transitions(ext: &str, data: &[u8]) -> Result<Vec<SlideTransition>, ConvertError> {
+    if !ext.eq_ignore_ascii_case("pptx") {
+        return Ok(Vec::new());
+    }
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data))
+        .map_err(|e| ConvertError::new("PPTX", &format!("ZIPアーカイブを開けません: {}", e)))?;
+
+    let mut transitions = Vec::new();
+    for (index, path) in slide_paths(&archive).iter().enumerate() {
+        let mut xml = String::new();
+        let entry = archive
+            .by_name(path)
+            .map_err(|e| ConvertError::new("PPTX", &format!("{}が見つかりません: {}", path, e)))?;
+        super::limits::read_limited_string(entry, &mut xml)
+            .map_err(|e| ConvertError::new("PPTX", &format!("{}の読み込みエラー: {}", path, e)))?;
+        transitions.extend(parse_transition(&xml, index + 1));
+    }
+    Ok(transitions)
+}
+
+/// スライドのパスをスライド番号順に列挙
+fn slide_paths(archive: &ZipArchive) -> Vec<String> {
+    let number = |path: &str| {
+        path.trim_start_matches("ppt/slides/slide")
+            .trim_end_matches(".xml")
+            .parse::<u32>()
+            .unwrap_or(0)
+    };
+    let mut paths: Vec<String> = archive
+        .file_names()
+        .filter(|name| name.starts_with("ppt/slides/slide") && name.ends_with(".xml"))
+        .map(str::to_string)
+        .collect();
+    paths.sort_by_key(|path| number(path));
+    paths
+}
+
+/// スライドXMLの最初の <p:transition> を解析
+/// mc:AlternateContent で拡張版（mc:Choice）と互換版（mc:Fallback）が並ぶ場合は先頭を採用します。
+fn parse_transition(xml: &str, slide: usize) -> Option<SlideTransition> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut transition: Option<SlideTransition> = None;
+    let mut depth = 0usize;
+
+    loop {
+        let event = reader.read_event_into(&mut buf);
+        let is_start = matches!(event, Ok(quick_xml::events::Event::Start(_)));
+        match event {
+            Ok(quick_xml::events::Event::Start(ref e)) | Ok(quick_xml::events::Event::Empty(ref e)) => {
+                let local = e.local_name();
+                match (local.as_ref(), transition.as_mut()) {
+                    (b"transition", None) => {
+                        transition = Some(transition_attributes(e, slide));
+                        if !is_start {
+                            break;
+                        }
+                        depth = 1;
+                        buf.clear();
+                        continue;
+                    }
+                    // 効果を表す直下の子要素（サウンド・拡張は除く）
+                    (name, Some(t)) if depth == 1 && t.kind == "none" && name != b"sndAc" && name != b"extLst" => {
+                        t.kind = String::from_utf8_lossy(name).to_string();
+                    }
+                    _ => {}
+                }
+                if is_start && transition.is_some() {
+                    depth += 1;
+                }
+            }
+            Ok(quick_xml::events::Event::End(_)) if transition.is_some() => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            Ok(quick_xml::events::Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    transition
+}
+
+/// <p:transition> の属性から再生時間・進行条件を取得
+/// 再生時間は p14:dur（ミリ秒）を優先し、無ければ spd から求めます。
+fn transition_attributes(e: &quick_xml::events::BytesStart, slide: usize) -> SlideTransition {
+    let mut transition = SlideTransition {
+        slide,
+        kind: "none".to_string(),
+        duration: speed_duration("fast"),
+        advance_on_click: true,
+        advance_after: None,
+    };
+    let mut explicit_duration = None;
+    for attr in e.attributes().flatten() {
+        let value = String::from_utf8_lossy(&attr.value);
+        match attr.key.local_name().as_ref() {
+            b"spd" => transition.duration = speed_duration(&value),
+            b"dur" => explicit_duration = value.parse().ok(),
+            b"advClick" => transition.advance_on_click = !matches!(value.as_ref(), "0" | "false"),
+            b"advTm" => transition.advance_after = value.parse().ok(),
+            _ => {}
+        }
+    }
+    if let Some(duration) = explicit_duration {
+        transition.duration = duration;
+    }
+    transition
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transition_prefers_first_alternate() {
+        let xml = r#"<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+            xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006"
+            xmlns:p14="http://schemas.microsoft.com/office/powerpoint/2010/main">
+          <mc:AlternateContent>
+            <mc:Choice Requires="p14">
+              <p:transition spd="slow" p14:dur="1600" advClick="0" advTm="5000"><p14:vortex dir="r"/></p:transition>
+            </mc:Choice>
+            <mc:Fallback>
+              <p:transition spd="slow" advClick="0" advTm="5000"><p:fade/></p:transition>
+            </mc:Fallback>
+          </mc:AlternateContent>
+        </p:sld>"#;
+        let t = parse_transition(xml, 2).unwrap();
+        assert_eq!(t.slide, 2);
+        assert_eq!(t.kind, "vortex");
+        assert_eq!(t.duration, 1600);
+        assert!(!t.advance_on_click);
+        assert_eq!(t.advance_after, Some(5000));
+
+        assert_eq!(parse_transition(r#"<p:sld><p:transition/></p:sld>"#, 1).unwrap().kind, "none");
+        assert!(parse_transition(r#"<p:sld><p:cSld/></p:sld>"#, 1).is_none());
+    }
+}
@@ -3,14 +3,21 @@
 // TXTファイルを読み込み、自動エンコーディング検出を行い、
 // ドキュメントモデルに変換します。
 
-use crate::converter::{ConvertError, Document, DocumentConverter, FontStyle};
+use crate::converter::{ConvertError, Document, DocumentConverter, TextFlowOptions};
 
 /// テキストファイルコンバーター
-pub struct TxtConverter;
+pub struct TxtConverter {
+    options: TextFlowOptions,
+}
 
 impl TxtConverter {
     pub fn new() -> Self {
-        Self
+        Self::with_options(TextFlowOptions::default())
+    }
+
+    /// 既定フォント・サイズを指定してコンバーターを作成
+    pub fn with_options(options: TextFlowOptions) -> Self {
+        Self { options }
     }
 }
 
@@ -18,8 +25,8 @@ impl DocumentConverter for TxtConverter {
     fn convert(&self, input: &[u8]) -> Result<Document, ConvertError> {
         let text = decode_text(input)?;
         let lines: Vec<String> = text.lines().map(|l| l.to_string()).collect();
-        let style = FontStyle::default();
-        Ok(Document::from_text_lines(&lines, &style))
+        let style = self.options.font_style();
+        Ok(Document::from_text_lines_on_page(&lines, &style, self.options.tab_interval, &self.options.blank_page()))
     }
 
     fn supported_extensions(&self) -> &[&str] {
@@ -32,7 +39,7 @@ impl DocumentConverter for TxtConverter {
 }
 
 /// テキストのエンコーディングを自動検出してUTF-8に変換
-fn decode_text(input: &[u8]) -> Result<String, ConvertError> {
+pub(crate) fn decode_text(input: &[u8]) -> Result<String, ConvertError> {
     // BOMチェック
     if input.starts_with(&[0xEF, 0xBB, 0xBF]) {
         // UTF-8 BOM
@@ -99,6 +106,26 @@ mod tests {
         assert_eq!(doc.pages.len(), 1);
     }
 
+    #[test]
+    fn test_default_font_size_option() {
+        let converter = TxtConverter::with_options(TextFlowOptions {
+            default_font: Some("NotoSerifJP".to_string()),
+            default_font_size: 16.0,
+            ..Default::default()
+        });
+        let doc = converter.convert("一行目\n二行目".as_bytes()).unwrap();
+        let styles: Vec<_> = doc.pages[0]
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                crate::converter::PageElement::Text { style, .. } => Some(style),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(styles.len(), 2);
+        assert!(styles.iter().all(|s| s.font_size == 16.0 && s.font_name == "NotoSerifJP"));
+    }
+
     #[test]
     fn test_utf8_bom() {
         let mut input = vec![0xEF, 0xBB, 0xBF];
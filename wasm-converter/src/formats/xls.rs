@@ -0,0 +1,79 @@
+// formats/xls.rs - XLS（Excel 97-2003 バイナリ）変換モジュール
+//
+// calamine クレートの XLS リーダーでOLE複合ファイル内のBIFFストリームを読み込み、
+// XLSX と共通の処理でシートごとの表をページに配置します。
+
+use super::xlsx::{read_xlsx_metadata, render_sheet_to_pages, SheetPrintOptions};
+use crate::converter::{ConvertError, Document, DocumentConverter, Page};
+use calamine::{open_workbook_from_rs, Reader, Xls};
+use std::sync::Arc;
+
+/// XLSコンバーター
+pub struct XlsConverter {
+    print: SheetPrintOptions,
+    /// 行の高さの計測に使うフォントデータ
+    layout_font: Option<Arc<[u8]>>,
+}
+
+impl XlsConverter {
+    pub fn new() -> Self {
+        Self { print: SheetPrintOptions::default(), layout_font: None }
+    }
+
+    /// ページ分割で行の高さを計測するフォント（描画時と同じフォント）を設定
+    pub fn with_layout_font(mut self, font: Option<Arc<[u8]>>) -> Self {
+        self.layout_font = font;
+        self
+    }
+
+    /// セルの枠線を描画するかを設定
+    pub fn with_gridlines(mut self, gridlines: bool) -> Self {
+        self.print.gridlines = gridlines;
+        self
+    }
+
+    /// 行番号・列番号の見出しを描画するかを設定
+    pub fn with_headers(mut self, headers: bool) -> Self {
+        self.print.headers = headers;
+        self
+    }
+}
+
+impl DocumentConverter for XlsConverter {
+    fn convert(&self, input: &[u8]) -> Result<Document, ConvertError> {
+        // XLSのストリームは圧縮されていないため、calamine が読み込むファイル全体を展開量として数える
+        let mut data = Vec::new();
+        super::limits::read_limited(input, &mut data)
+            .map_err(|e| ConvertError::new("XLS", &format!("ファイルの読み込みエラー: {}", e)))?;
+        let cursor = std::io::Cursor::new(data);
+        let mut workbook: Xls<_> = open_workbook_from_rs(cursor)
+            .map_err(|e| ConvertError::new("XLS", &format!("ワークブックを開けません: {}", e)))?;
+
+        let sheet_names: Vec<String> = workbook.sheet_names().to_vec();
+
+        let mut doc = Document::new();
+        doc.metadata = read_xlsx_metadata(input);
+
+        for sheet_name in &sheet_names {
+            if let Ok(range) = workbook.worksheet_range(sheet_name) {
+                // ウィンドウ枠の固定は読み込まない
+                let pages = render_sheet_to_pages(sheet_name, &range, self.print, 0, self.layout_font.as_deref());
+                doc.pages.extend(pages);
+            }
+        }
+
+        if doc.pages.is_empty() {
+            doc.pages.push(Page::a4());
+        }
+
+        Ok(doc)
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["xls"]
+    }
+
+    fn format_name(&self) -> &str {
+        "XLS"
+    }
+}
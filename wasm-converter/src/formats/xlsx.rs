@@ -1,41 +1,79 @@
-// formats/xlsx.rs - XLSX/XLS/ODS変換モジュール
+// formats/xlsx.rs - XLSX/ODS変換モジュール
 //
 // calamine クレートを使用してスプレッドシートファイルを読み込み、
 // テーブル形式でドキュメントモデルに変換します。
 
 use crate::converter::{
-    ConvertError, Document, DocumentConverter, FontStyle, Metadata, Page, PageElement, Table,
-    TableCell,
+    BorderSpec, CellBorders, Color, ConvertError, Document, DocumentConverter, FontStyle, Metadata, Page,
+    PageElement, Table, TableCell, TABLE_MIN_COLUMN_WIDTH,
 };
 use calamine::{open_workbook_auto_from_rs, Data, Reader};
+use std::sync::Arc;
+
+/// 行番号の列の幅（ポイント）
+const ROW_HEADER_WIDTH: f64 = 32.0;
+
+/// スプレッドシートの印刷設定（XLSX / XLS 共通）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SheetPrintOptions {
+    /// 使用範囲のすべてのセル境界に薄い枠線を引く
+    pub gridlines: bool,
+    /// 先頭に列番号（A, B, C…）の行、左端に行番号（1, 2, 3…）の列を薄い網掛けのセルで追加する
+    pub headers: bool,
+}
 
 /// スプレッドシートコンバーター
-pub struct XlsxConverter;
+pub struct XlsxConverter {
+    print: SheetPrintOptions,
+    /// 行の高さの計測に使うフォントデータ
+    layout_font: Option<Arc<[u8]>>,
+}
 
 impl XlsxConverter {
     pub fn new() -> Self {
-        Self
+        Self { print: SheetPrintOptions::default(), layout_font: None }
+    }
+
+    /// ページ分割で行の高さを計測するフォント（描画時と同じフォント）を設定
+    pub fn with_layout_font(mut self, font: Option<Arc<[u8]>>) -> Self {
+        self.layout_font = font;
+        self
+    }
+
+    /// セルの枠線を描画するかを設定
+    pub fn with_gridlines(mut self, gridlines: bool) -> Self {
+        self.print.gridlines = gridlines;
+        self
+    }
+
+    /// 行番号・列番号の見出しを描画するかを設定
+    pub fn with_headers(mut self, headers: bool) -> Self {
+        self.print.headers = headers;
+        self
     }
 }
 
 impl DocumentConverter for XlsxConverter {
     fn convert(&self, input: &[u8]) -> Result<Document, ConvertError> {
-        let cursor = std::io::Cursor::new(input);
+        // calamine はパーツを自前で展開するため、展開量の上限を適用して展開済みのZIPを渡す
+        let package = super::limits::inflate_archive(input)
+            .map_err(|e| ConvertError::new("XLSX", &format!("ZIPの展開エラー: {}", e)))?;
+        let cursor = std::io::Cursor::new(package.as_ref());
         let mut workbook = open_workbook_auto_from_rs(cursor)
             .map_err(|e| ConvertError::new("XLSX", &format!("ワークブックを開けません: {}", e)))?;
 
         let sheet_names: Vec<String> = workbook.sheet_names().to_vec();
 
         let mut doc = Document::new();
-        doc.metadata = Metadata {
-            title: Some("Spreadsheet".to_string()),
-            creator: Some("WASM Document Converter".to_string()),
-            ..Default::default()
-        };
+        let frozen_rows = super::limits::uncounted(|| {
+            doc.metadata = read_xlsx_metadata(&package);
+            read_frozen_rows(&package)
+        });
 
         for sheet_name in &sheet_names {
             if let Ok(range) = workbook.worksheet_range(sheet_name) {
-                let pages = render_sheet_to_pages(sheet_name, &range);
+                let frozen = frozen_rows.iter().find(|(name, _)| name == sheet_name).map_or(0, |(_, rows)| *rows);
+                let pages = render_sheet_to_pages(sheet_name, &range, self.print, frozen, self.layout_font.as_deref());
                 doc.pages.extend(pages);
             }
         }
@@ -48,7 +86,7 @@ impl DocumentConverter for XlsxConverter {
     }
 
     fn supported_extensions(&self) -> &[&str] {
-        &["xlsx", "xls", "ods"]
+        &["xlsx", "ods"]
     }
 
     fn format_name(&self) -> &str {
@@ -56,24 +94,130 @@ impl DocumentConverter for XlsxConverter {
     }
 }
 
+/// docProps/core.xml からメタデータを読み込み（タイトルが無い場合は "Spreadsheet"）
+/// XLS等のZIP形式でないファイルでは既定値のみを設定します。
+pub(crate) fn read_xlsx_metadata(input: &[u8]) -> Metadata {
+    let mut metadata = match zip::ZipArchive::new(std::io::Cursor::new(input)) {
+        Ok(mut archive) => super::core_properties::read_core_properties(&mut archive),
+        Err(_) => Metadata { creator: Some(super::core_properties::CREATOR.to_string()), ..Default::default() },
+    };
+    metadata.title.get_or_insert_with(|| "Spreadsheet".to_string());
+    metadata
+}
+
+/// 各シートのウィンドウ枠の固定（`sheetView/pane` の `state="frozen"`）で固定された先頭の行数
+/// シート名と行数の組を返します。ZIP形式でないファイル・行を固定していないシートは含みません。
+pub(crate) fn read_frozen_rows(input: &[u8]) -> Vec<(String, u32)> {
+    use super::embedded_fonts::{attr_value, resolve_target};
+    use quick_xml::events::Event;
+
+    let Ok(mut archive) = zip::ZipArchive::new(std::io::Cursor::new(input)) else {
+        return Vec::new();
+    };
+    let mut read = |path: &str| {
+        let mut content = String::new();
+        let file = archive.by_name(path).ok()?;
+        super::limits::read_limited_string(file, &mut content).ok()?;
+        Some(content)
+    };
+    let (Some(workbook_xml), Some(rels_xml)) = (read("xl/workbook.xml"), read("xl/_rels/workbook.xml.rels")) else {
+        return Vec::new();
+    };
+
+    // workbook.xml のシート名とリレーションシップID
+    let mut sheets = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(&workbook_xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e)) if e.local_name().as_ref() == b"sheet" => {
+                if let (Some(name), Some(r_id)) = (attr_value(e, b"name"), attr_value(e, b"id")) {
+                    sheets.push((name, r_id));
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let mut frozen = Vec::new();
+    for (name, r_id) in sheets {
+        let Some(sheet_xml) = resolve_target(&rels_xml, &r_id, "xl").and_then(|path| read(&path)) else {
+            continue;
+        };
+        let mut reader = quick_xml::Reader::from_str(&sheet_xml);
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e)) if e.local_name().as_ref() == b"pane" => {
+                    let state = attr_value(e, b"state");
+                    let rows = attr_value(e, b"ySplit").and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
+                    // "split"（分割のみ）の ySplit は行数ではなく位置（twip）なので対象外
+                    if matches!(state.as_deref(), Some("frozen") | Some("frozenSplit")) && rows >= 1.0 {
+                        frozen.push((name, rows as u32));
+                    }
+                    break;
+                }
+                // ウィンドウ枠の指定はセルデータより前にある
+                Ok(Event::Start(ref e)) if e.local_name().as_ref() == b"sheetData" => break,
+                Ok(Event::Eof) | Err(_) => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        buf.clear();
+    }
+    frozen
+}
+
 /// シートデータをページに変換
-fn render_sheet_to_pages(sheet_name: &str, range: &calamine::Range<Data>) -> Vec<Page> {
+/// 枠線は `print.gridlines` が有効な場合だけ引きます。行番号・列番号の見出しは
+/// シート上の実際の位置（使用範囲の開始セル）から数え、列番号の行は各ページの先頭に繰り返します。
+/// ウィンドウ枠の固定で固定された先頭の `frozen_rows` 行（シート上の行数）も見出しとして各ページに繰り返します。
+/// シートは横方向には分割しないため、固定された列は常に各ページの左端に表示されます。
+/// 行の高さは `font_data` で計測します。
+pub(crate) fn render_sheet_to_pages(
+    sheet_name: &str,
+    range: &calamine::Range<Data>,
+    print: SheetPrintOptions,
+    frozen_rows: u32,
+    font_data: Option<&[u8]>,
+) -> Vec<Page> {
     let margin = 40.0;
     let page_width = 595.28;
     let page_height = 841.89;
     let usable_width = page_width - margin * 2.0;
     let usable_height = page_height - margin * 2.0;
-    let row_height = 20.0;
     let header_height = 30.0;
-    let rows_per_page = ((usable_height - header_height) / row_height) as usize;
 
     let (row_count, col_count) = range.get_size();
     if row_count == 0 || col_count == 0 {
         return vec![Page::a4()];
     }
 
-    let col_width = usable_width / col_count.max(1) as f64;
-    let column_widths: Vec<f64> = (0..col_count).map(|_| col_width).collect();
+    // 列数が多い場合は最小列幅を保ってページ幅を超える（FitModeで縮小可能）
+    let row_header_width = if print.headers { ROW_HEADER_WIDTH } else { 0.0 };
+    let col_width = ((usable_width - row_header_width) / col_count.max(1) as f64).max(TABLE_MIN_COLUMN_WIDTH);
+    let column_widths: Vec<f64> = print
+        .headers
+        .then_some(row_header_width)
+        .into_iter()
+        .chain((0..col_count).map(|_| col_width))
+        .collect();
+    let table_width: f64 = column_widths.iter().sum();
+
+    // 枠線の有無はセルごとの辺の指定で表す（指定が無いセルは既定の枠線になるため、無しも明示する）
+    let gridline = BorderSpec { width: 0.5, color: Color::rgb(204, 204, 204), compound: Default::default() };
+    let all_edges = CellBorders { top: Some(gridline), right: Some(gridline), bottom: Some(gridline), left: Some(gridline) };
+    let cell_borders = if print.gridlines { all_edges } else { CellBorders::default() };
+    let body_cell = |text: &str| TableCell { borders: Some(cell_borders), ..TableCell::new(text) };
+    let header_cell = |text: &str| TableCell {
+        style: FontStyle { color: Color::rgb(89, 89, 89), ..FontStyle::default() },
+        background: Some(Color::rgb(242, 242, 242)),
+        borders: Some(all_edges),
+        ..TableCell::new(text)
+    };
+    let (first_row, first_col) = range.start().unwrap_or((0, 0));
 
     let mut pages = Vec::new();
 
@@ -97,7 +241,57 @@ fn render_sheet_to_pages(sheet_name: &str, range: &calamine::Range<Data>) -> Vec
         })
         .collect();
 
-    for chunk in all_rows.chunks(rows_per_page.max(1)) {
+    // テーブルデータ（列数を揃える）
+    let mut table_rows: Vec<Vec<TableCell>> = all_rows
+        .iter()
+        .enumerate()
+        .map(|(row_idx, row)| {
+            let row_number = print.headers.then(|| header_cell(&(first_row as usize + row_idx + 1).to_string()));
+            let mut cells: Vec<TableCell> = row_number
+                .into_iter()
+                .chain(row.iter().map(|text| body_cell(text)))
+                .collect();
+            while cells.len() < column_widths.len() {
+                cells.push(body_cell(""));
+            }
+            cells
+        })
+        .collect();
+
+    // 列番号の行（左上の角は空の見出しセル）
+    let column_header = print.headers.then(|| {
+        std::iter::once(header_cell(""))
+            .chain((0..col_count).map(|col| header_cell(&column_letter(first_col as usize + col))))
+            .collect::<Vec<_>>()
+    });
+
+    // 固定された行（使用範囲内の分）を本文から外し、列番号の行に続けて各ページの先頭に置く
+    // すべての行が固定されている場合は繰り返さずに通常どおり分割する
+    let frozen = (frozen_rows as usize).saturating_sub(first_row as usize);
+    let body_rows = if frozen > 0 && frozen < table_rows.len() {
+        table_rows.split_off(frozen)
+    } else {
+        std::mem::take(&mut table_rows)
+    };
+    let repeated: Vec<Vec<TableCell>> = column_header.into_iter().chain(table_rows).collect();
+    let repeated_height: f64 = Table { rows: repeated.clone(), column_widths: column_widths.clone() }
+        .row_heights(table_width, font_data)
+        .iter()
+        .sum();
+
+    // 折り返しで高くなった行を考慮してページに分割
+    let mut chunks = Table::split_rows_by_height(
+        body_rows,
+        &column_widths,
+        table_width,
+        (usable_height - header_height - repeated_height).max(0.0),
+        font_data,
+    );
+    for chunk in &mut chunks {
+        chunk.splice(0..0, repeated.iter().cloned());
+    }
+
+    for chunk in chunks {
         let mut page = Page::a4();
 
         // シート名ヘッダー
@@ -114,30 +308,15 @@ fn render_sheet_to_pages(sheet_name: &str, range: &calamine::Range<Data>) -> Vec
             align: crate::converter::TextAlign::Left,
         });
 
-        // テーブルデータ
-        let table_rows: Vec<Vec<TableCell>> = chunk
-            .iter()
-            .map(|row| {
-                let mut cells: Vec<TableCell> = row
-                    .iter()
-                    .map(|text| TableCell::new(text))
-                    .collect();
-                while cells.len() < col_count {
-                    cells.push(TableCell::new(""));
-                }
-                cells
-            })
-            .collect();
-
         let table = Table {
-            rows: table_rows,
+            rows: chunk,
             column_widths: column_widths.clone(),
         };
 
         page.elements.push(PageElement::TableBlock {
             x: margin,
             y: margin + header_height,
-            width: usable_width,
+            width: table_width,
             table,
         });
 
@@ -151,6 +330,19 @@ fn render_sheet_to_pages(sheet_name: &str, range: &calamine::Range<Data>) -> Vec
     pages
 }
 
+/// 0始まりの列番号をスプレッドシートの列名（A, B, …, Z, AA, AB, …）に変換
+fn column_letter(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        letters.push(b'A' + rem as u8);
+        n = (n - 1) / 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -159,6 +351,15 @@ mod tests {
     fn test_format_name() {
         let converter = XlsxConverter::new();
         assert_eq!(converter.format_name(), "XLSX");
-        assert_eq!(converter.supported_extensions(), &["xlsx", "xls", "ods"]);
+        assert_eq!(converter.supported_extensions(), &["xlsx", "ods"]);
+    }
+
+    #[test]
+    fn test_column_letter() {
+        assert_eq!(column_letter(0), "A");
+        assert_eq!(column_letter(25), "Z");
+        assert_eq!(column_letter(26), "AA");
+        assert_eq!(column_letter(701), "ZZ");
+        assert_eq!(column_letter(702), "AAA");
     }
 }
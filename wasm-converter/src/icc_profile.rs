@@ -0,0 +1,179 @@
+// icc_profile.rs - ICCカラープロファイル生成
+//
+// PDFの出力インテント（/OutputIntents）に埋め込むための
+// sRGB相当のICC v2プロファイルをバイナリファイルなしで組み立てます。
+
+/// sRGB原色（D50にBradford変換済み）とIEC 61966-2-1のトーンカーブによる
+/// 最小構成のICC v2ディスプレイプロファイルを生成
+pub fn srgb_profile() -> Vec<u8> {
+    let description = b"sRGB IEC61966-2.1";
+    let copyright = b"No copyright, use freely";
+
+    // タグデータ（4バイト境界に揃える）
+    let desc = text_description_tag(description);
+    let cprt = text_tag(copyright);
+    let wtpt = xyz_tag(0.9642, 1.0, 0.8249);
+    let r_xyz = xyz_tag(0.4361, 0.2225, 0.0139);
+    let g_xyz = xyz_tag(0.3851, 0.7169, 0.0971);
+    let b_xyz = xyz_tag(0.1431, 0.0606, 0.7141);
+    let trc = srgb_curve_tag();
+
+    // (シグネチャ, データインデックス) — TRCはR/G/Bで同じデータを共有
+    let blobs: [&[u8]; 7] = [&desc, &cprt, &wtpt, &r_xyz, &g_xyz, &b_xyz, &trc];
+    let tags: [(&[u8; 4], usize); 9] = [
+        (b"desc", 0),
+        (b"cprt", 1),
+        (b"wtpt", 2),
+        (b"rXYZ", 3),
+        (b"gXYZ", 4),
+        (b"bXYZ", 5),
+        (b"rTRC", 6),
+        (b"gTRC", 6),
+        (b"bTRC", 6),
+    ];
+
+    let table_len = 4 + tags.len() * 12;
+    let mut offsets = Vec::with_capacity(blobs.len());
+    let mut pos = 128 + table_len;
+    for blob in &blobs {
+        offsets.push(pos);
+        pos += padded_len(blob.len());
+    }
+    let total_len = pos;
+
+    let mut out = Vec::with_capacity(total_len);
+    // ヘッダー（128バイト）
+    out.extend_from_slice(&(total_len as u32).to_be_bytes());
+    out.extend_from_slice(&[0; 4]); // CMMタイプ
+    out.extend_from_slice(&0x0210_0000u32.to_be_bytes()); // バージョン2.1
+    out.extend_from_slice(b"mntr");
+    out.extend_from_slice(b"RGB ");
+    out.extend_from_slice(b"XYZ ");
+    for v in [2024u16, 1, 1, 0, 0, 0] {
+        out.extend_from_slice(&v.to_be_bytes());
+    }
+    out.extend_from_slice(b"acsp");
+    out.extend_from_slice(&[0; 24]); // プラットフォーム, フラグ, メーカー, モデル, 属性
+    out.extend_from_slice(&0u32.to_be_bytes()); // レンダリングインテント（知覚的）
+    out.extend_from_slice(&s15_fixed16(0.9642));
+    out.extend_from_slice(&s15_fixed16(1.0));
+    out.extend_from_slice(&s15_fixed16(0.8249));
+    out.resize(128, 0);
+
+    // タグテーブル
+    out.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+    for (sig, idx) in &tags {
+        out.extend_from_slice(*sig);
+        out.extend_from_slice(&(offsets[*idx] as u32).to_be_bytes());
+        out.extend_from_slice(&(blobs[*idx].len() as u32).to_be_bytes());
+    }
+
+    for blob in &blobs {
+        out.extend_from_slice(blob);
+        out.resize(padded_len(out.len()), 0);
+    }
+    out
+}
+
+/// ICCプロファイルのデータ色空間から成分数を返す（PDFの /N に対応）
+pub fn profile_components(profile: &[u8]) -> Option<u32> {
+    match profile.get(16..20)? {
+        b"RGB " => Some(3),
+        b"CMYK" => Some(4),
+        b"GRAY" => Some(1),
+        _ => None,
+    }
+}
+
+fn padded_len(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn s15_fixed16(v: f64) -> [u8; 4] {
+    ((v * 65536.0).round() as i32).to_be_bytes()
+}
+
+fn xyz_tag(x: f64, y: f64, z: f64) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    tag.extend_from_slice(&s15_fixed16(x));
+    tag.extend_from_slice(&s15_fixed16(y));
+    tag.extend_from_slice(&s15_fixed16(z));
+    tag
+}
+
+/// sRGBトーンカーブのサンプル数
+const SRGB_CURVE_POINTS: usize = 1024;
+
+/// IEC 61966-2-1 のトーンカーブ（パラメトリック曲線 g=2.4, a=1/1.055, b=0.055/1.055, c=1/12.92, d=0.04045）
+fn srgb_to_linear(v: f64) -> f64 {
+    if v >= 0.04045 {
+        ((v + 0.055) / 1.055).powf(2.4)
+    } else {
+        v / 12.92
+    }
+}
+
+/// sRGBトーンカーブの curv タグ
+/// ICC v2 には parametricCurveType（para）がないため、同じ曲線をサンプリングして格納します。
+fn srgb_curve_tag() -> Vec<u8> {
+    let mut tag = b"curv\0\0\0\0".to_vec();
+    tag.extend_from_slice(&(SRGB_CURVE_POINTS as u32).to_be_bytes());
+    for i in 0..SRGB_CURVE_POINTS {
+        let linear = srgb_to_linear(i as f64 / (SRGB_CURVE_POINTS - 1) as f64);
+        tag.extend_from_slice(&((linear * 65535.0).round() as u16).to_be_bytes());
+    }
+    tag
+}
+
+fn text_tag(text: &[u8]) -> Vec<u8> {
+    let mut tag = b"text\0\0\0\0".to_vec();
+    tag.extend_from_slice(text);
+    tag.push(0);
+    tag
+}
+
+fn text_description_tag(text: &[u8]) -> Vec<u8> {
+    let mut tag = b"desc\0\0\0\0".to_vec();
+    tag.extend_from_slice(&(text.len() as u32 + 1).to_be_bytes());
+    tag.extend_from_slice(text);
+    tag.push(0);
+    tag.extend_from_slice(&[0; 8]); // Unicode言語コード, Unicode文字数
+    tag.extend_from_slice(&[0; 3]); // ScriptCodeコード, 文字数
+    tag.extend_from_slice(&[0; 67]); // ScriptCode文字列領域
+    tag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_srgb_profile_header() {
+        let profile = srgb_profile();
+        let size = u32::from_be_bytes([profile[0], profile[1], profile[2], profile[3]]);
+        assert_eq!(size as usize, profile.len());
+        assert_eq!(&profile[36..40], b"acsp");
+        assert_eq!(profile_components(&profile), Some(3));
+        assert_eq!(profile.len() % 4, 0);
+    }
+
+    #[test]
+    fn test_srgb_profile_tone_curve() {
+        let profile = srgb_profile();
+        let pos = profile.windows(4).position(|w| w == b"curv").unwrap();
+        let count = u32::from_be_bytes(profile[pos + 8..pos + 12].try_into().unwrap()) as usize;
+        assert_eq!(count, SRGB_CURVE_POINTS);
+        let sample = |i: usize| {
+            let at = pos + 12 + i * 2;
+            u16::from_be_bytes([profile[at], profile[at + 1]]) as f64 / 65535.0
+        };
+        assert_eq!(sample(0), 0.0);
+        assert_eq!(sample(count - 1), 1.0);
+        // 中間値 0.5 付近は線形値 約0.214（ガンマ2.2では約0.218）
+        let mid = sample(count / 2);
+        assert!((mid - srgb_to_linear((count / 2) as f64 / (count - 1) as f64)).abs() < 1e-4);
+        assert!((srgb_to_linear(0.5) - 0.2140).abs() < 1e-3);
+        // 暗部は線形区間
+        assert!((srgb_to_linear(0.02) - 0.02 / 12.92).abs() < 1e-9);
+    }
+}
@@ -3,11 +3,17 @@
 // ドキュメントの各ページをPNG画像にレンダリングし、
 // ZIPファイルにまとめて出力します。
 
-use crate::converter::{Color, Document, FontStyle, Page, PageElement};
-use crate::font_manager::FontManager;
-use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use crate::converter::{
+    rotate_point, Color, ColorMode, Document, FitMode, FontStyle, MissingGlyphPolicy, Page, PageElement, StrokeStyle, Table,
+    ImageFit, PageNumberConfig, UnderlineKind, Watermark,
+    TABLE_CELL_PADDING,
+    TABLE_LINE_SPACING, UNDERLINE_OFFSET,
+};
+use crate::font_manager::{font_has_glyph, missing_glyph_advance, FontManager, SyntheticStyle, SYNTHETIC_ITALIC_SLANT};
+use ab_glyph::{Font, PxScale, ScaleFont};
 
 /// 画像レンダリングの設定
+#[derive(Clone)]
 pub struct ImageRenderConfig {
     /// DPI（デフォルト: 150）
     pub dpi: f64,
@@ -15,6 +21,43 @@ pub struct ImageRenderConfig {
     pub background: Color,
     /// 画像フォーマット
     pub format: ImageFormat,
+    /// コンテンツの縦横比に合わせてページの向きを自動補正する（`Page::auto_oriented`）
+    pub auto_orient: bool,
+    /// フォントにグリフが無い文字の描画方法
+    pub missing_glyph: MissingGlyphPolicy,
+    /// スーパーサンプリング倍率（1〜4、デフォルト: 1）
+    /// N倍の解像度で描画してからボックスフィルタで縮小し、エッジを滑らかにします。
+    pub supersample: u8,
+    /// アンチエイリアスの有効/無効（デフォルト: 有効）
+    /// 無効にするとグリフを2値化し、スーパーサンプリングも行いません。
+    pub antialias: bool,
+    /// ページからはみ出したテーブルを縮小する（`Page::fitted`）
+    pub fit_mode: FitMode,
+    /// 埋め込み画像を拡大縮小するときの補間方式（デフォルト: 双線形）
+    pub image_interpolation: ImageInterpolation,
+    /// 色モード（グレースケール・白黒、`Page::with_color_mode`）
+    /// 背景色と埋め込み画像のビットマップも同じ規則で変換します。
+    pub color_mode: ColorMode,
+    /// 出力画像の幅（ピクセル、`dpi` より優先）
+    /// ページ幅がこの幅になる倍率で描画します。
+    pub target_width_px: Option<u32>,
+    /// 出力画像の高さ（ピクセル、`dpi` より優先）
+    /// 幅と両方指定した場合は、両方に収まる倍率で描画します。
+    pub target_height_px: Option<u32>,
+    /// ヒンティングの有効/無効（グリフの原点と送り幅をピクセル格子に揃える）
+    /// None の場合は描画DPIが `HINTING_AUTO_MAX_DPI` 以下のときに有効にします。
+    pub hinting: Option<bool>,
+    /// 各ページに重ねる透かし（テキストまたは画像、`Page::with_watermark`）
+    pub watermark: Option<Watermark>,
+    /// ZIP出力の各ページに付けるページ番号（`Page::with_page_number`）
+    pub page_numbers: Option<PageNumberConfig>,
+    /// 縦横比の異なる矩形への画像の収め方（`Page::with_image_fit`）
+    pub image_fit: ImageFit,
+    /// 背景が指定されていないページの最背面に敷く背景色（`Page::with_default_background`）
+    /// `background` と異なり、半透明の色は内容の下で合成されます。
+    pub default_page_background: Option<Color>,
+    /// 太字・斜体の書体が無いフォントで、輪郭の太らせ・傾けにより強調を合成する（デフォルト: 有効）
+    pub synthesize_styles: bool,
 }
 
 impl Default for ImageRenderConfig {
@@ -23,14 +66,154 @@ impl Default for ImageRenderConfig {
             dpi: 150.0,
             background: Color::WHITE,
             format: ImageFormat::Png,
+            auto_orient: false,
+            missing_glyph: MissingGlyphPolicy::default(),
+            supersample: 1,
+            antialias: true,
+            fit_mode: FitMode::None,
+            image_interpolation: ImageInterpolation::default(),
+            color_mode: ColorMode::default(),
+            target_width_px: None,
+            target_height_px: None,
+            hinting: None,
+            watermark: None,
+            page_numbers: None,
+            image_fit: ImageFit::default(),
+            default_page_background: None,
+            synthesize_styles: true,
         }
     }
 }
 
+/// DPIの下限
+pub const MIN_DPI: f64 = 72.0;
+/// DPIの上限（OOM防止）
+pub const MAX_DPI: f64 = 600.0;
+/// スーパーサンプリング倍率の上限
+pub const MAX_SUPERSAMPLE: u8 = 4;
+/// スーパーサンプリング時の内部描画DPIの上限（`dpi * supersample`）
+pub const MAX_SUPERSAMPLED_DPI: f64 = 1200.0;
+/// ピクセル数で指定した場合の画像の長辺の上限（OOM防止）
+pub const MAX_TARGET_PX: u32 = 10000;
+/// ヒンティングを自動で有効にする描画DPIの上限
+pub const HINTING_AUTO_MAX_DPI: f64 = 150.0;
+
+impl ImageRenderConfig {
+    /// 画質のプリセットに合わせたDPI・スーパーサンプリング・補間方式・ヒンティングの設定
+    /// その他の項目は既定値です。個別に指定したい項目はこの後に上書きします。
+    pub fn with_quality(quality: ImageQuality) -> Self {
+        let (dpi, supersample, image_interpolation, hinting) = match quality {
+            ImageQuality::Draft => (96.0, 1, ImageInterpolation::Nearest, None),
+            ImageQuality::Standard => (150.0, 1, ImageInterpolation::Bilinear, None),
+            ImageQuality::High => (200.0, 2, ImageInterpolation::Bilinear, None),
+            // 印刷では文字の形を優先し、ピクセル格子への位置合わせをしない
+            ImageQuality::Print => (300.0, 2, ImageInterpolation::Bilinear, Some(false)),
+        };
+        Self { dpi, supersample, image_interpolation, hinting, ..Self::default() }
+    }
+
+    /// 実際に使用するスーパーサンプリング倍率
+    /// アンチエイリアス無効時は1、`dpi * 倍率` が上限を超える場合は倍率を下げます。
+    pub fn effective_supersample(&self) -> u32 {
+        self.supersample_at_dpi(self.dpi)
+    }
+
+    /// 実際にヒンティングを行うかどうか
+    pub fn effective_hinting(&self) -> bool {
+        self.hinting_at_dpi(self.dpi)
+    }
+
+    fn hinting_at_dpi(&self, dpi: f64) -> bool {
+        self.hinting.unwrap_or(dpi <= HINTING_AUTO_MAX_DPI)
+    }
+
+    fn supersample_at_dpi(&self, dpi: f64) -> u32 {
+        if !self.antialias {
+            return 1;
+        }
+        let mut factor = self.supersample.clamp(1, MAX_SUPERSAMPLE) as u32;
+        while factor > 1 && dpi * factor as f64 > MAX_SUPERSAMPLED_DPI {
+            factor -= 1;
+        }
+        factor
+    }
+
+    /// ページを描画する倍率（1ptあたりのピクセル数）と画像の幅・高さ
+    /// ピクセル数の指定があれば `dpi` より優先し、長辺が `MAX_TARGET_PX` を超えないよう倍率を抑えます。
+    pub fn page_scale(&self, page: &Page) -> (f64, u32, u32) {
+        let fits = [
+            self.target_width_px.map(|w| w.min(MAX_TARGET_PX) as f64 / page.width),
+            self.target_height_px.map(|h| h.min(MAX_TARGET_PX) as f64 / page.height),
+        ];
+        let Some(scale) = fits
+            .into_iter()
+            .flatten()
+            .filter(|s| s.is_finite() && *s > 0.0)
+            .reduce(f64::min)
+        else {
+            let scale = self.dpi / 72.0;
+            return (scale, (page.width * scale) as u32, (page.height * scale) as u32);
+        };
+        let scale = scale.min(MAX_TARGET_PX as f64 / page.width.max(page.height));
+        (scale, (page.width * scale).round() as u32, (page.height * scale).round() as u32)
+    }
+}
+
 /// 画像フォーマット
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ImageFormat {
     Png,
+    /// WebP。`lossless` が true、または埋め込み画像の無い文字・図形だけのページは可逆で符号化します。
+    /// それ以外のページは `quality`（0〜100）に応じて各チャンネルの下位ビットを丸めます
+    /// （image-webp は非可逆形式のVP8を符号化できないため、出力は常にVP8Lです）。
+    WebP { quality: u8, lossless: bool },
+}
+
+impl ImageFormat {
+    /// 画像ファイルの拡張子
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::WebP { .. } => "webp",
+        }
+    }
+}
+
+/// 画像出力の画質のプリセット（`ImageRenderConfig::with_quality`）
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ImageQuality {
+    /// 96 DPI・スーパーサンプリング無し・最近傍補間（プレビュー向け、最も速い）
+    Draft,
+    /// 150 DPI・スーパーサンプリング無し・双線形補間（既定の設定と同じ）
+    #[default]
+    Standard,
+    /// 200 DPI・2倍スーパーサンプリング・双線形補間
+    High,
+    /// 300 DPI・2倍スーパーサンプリング・双線形補間・ヒンティング無し（印刷向け）
+    Print,
+}
+
+impl ImageQuality {
+    /// 名前（大文字小文字を区別しない）からプリセットを取得
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "draft" => Some(Self::Draft),
+            "standard" => Some(Self::Standard),
+            "high" => Some(Self::High),
+            "print" => Some(Self::Print),
+            _ => None,
+        }
+    }
+}
+
+/// 埋め込み画像の補間方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ImageInterpolation {
+    /// 最近傍（ピクセルアート・QRコード向け、境界がくっきり残る）
+    Nearest,
+    /// 双線形（写真向け、拡大時に滑らかになる）
+    #[default]
+    Bilinear,
 }
 
 /// ページを画像バイト列にレンダリング
@@ -39,21 +222,123 @@ pub fn render_page_to_image(
     config: &ImageRenderConfig,
     font_manager: &FontManager,
 ) -> Vec<u8> {
-    let scale = config.dpi / 72.0;
-    let width = (page.width * scale) as u32;
-    let height = (page.height * scale) as u32;
+    let refitted;
+    let page = match page.with_image_fit(config.image_fit) {
+        Some(p) => {
+            refitted = p;
+            &refitted
+        }
+        None => page,
+    };
+    let backed;
+    let page = match config.default_page_background.and_then(|c| page.with_default_background(c)) {
+        Some(p) => {
+            backed = p;
+            &backed
+        }
+        None => page,
+    };
+    let watermarked;
+    let page = match &config.watermark {
+        Some(watermark) => {
+            watermarked = page.with_watermark(watermark, font_manager.best_font_data());
+            &watermarked
+        }
+        None => page,
+    };
+    let converted;
+    let page = match page.with_color_mode(config.color_mode) {
+        Some(p) => {
+            converted = p;
+            &converted
+        }
+        None => page,
+    };
+    let (scale, width, height) = config.page_scale(page);
+
+    // ヒンティングの自動判定はピクセル数指定も含めた実際の描画DPIで行う
+    let resolved;
+    let config = if config.hinting.is_none() {
+        resolved = ImageRenderConfig { hinting: Some(config.hinting_at_dpi(scale * 72.0)), ..config.clone() };
+        &resolved
+    } else {
+        config
+    };
 
+    // スーパーサンプリング: N倍の解像度で描画してから縮小
+    let factor = config.supersample_at_dpi(scale * 72.0);
+    if factor > 1 {
+        let pixels = render_page_pixels(
+            page, config, font_manager, scale * factor as f64, width * factor, height * factor, factor as f64,
+        );
+        return encode_image(&downsample_box(&pixels, width, height, factor), width, height, page, config.format);
+    }
+
+    let pixels = render_page_pixels(page, config, font_manager, scale, width, height, 1.0);
+
+    encode_image(&pixels, width, height, page, config.format)
+}
+
+/// RGBAピクセルデータを指定フォーマットにエンコード
+/// WebPで表せない大きさの画像はPNGにします。
+fn encode_image(pixels: &[u8], width: u32, height: u32, page: &Page, format: ImageFormat) -> Vec<u8> {
+    match format {
+        ImageFormat::Png => encode_png(pixels, width, height),
+        ImageFormat::WebP { quality, lossless } => {
+            // 文字・図形だけのページは色を丸めると輪郭がにじみ、サイズもほとんど減らないため可逆にする
+            let has_raster = page.leaf_elements().iter().any(|e| {
+                matches!(e, PageElement::Image { .. } | PageElement::EllipseImage { .. } | PageElement::PathImage { .. })
+            });
+            crate::webp::encode_rgba(pixels, width, height, quality, lossless || !has_raster)
+                .unwrap_or_else(|| encode_png(pixels, width, height))
+        }
+    }
+}
+
+/// ページの全要素を指定スケールのRGBAピクセルバッファに描画
+/// `hairline` は出力画像の1ピクセルに相当するバッファ上のピクセル数（スーパーサンプリング倍率）です。
+fn render_page_pixels(
+    page: &Page,
+    config: &ImageRenderConfig,
+    font_manager: &FontManager,
+    scale: f64,
+    width: u32,
+    height: u32,
+    hairline: f64,
+) -> Vec<u8> {
     // RGBAピクセルバッファを作成（白背景）
+    let background = config.color_mode.apply(config.background);
     let mut pixels = vec![0u8; (width * height * 4) as usize];
     for i in (0..pixels.len()).step_by(4) {
-        pixels[i] = config.background.r;
-        pixels[i + 1] = config.background.g;
-        pixels[i + 2] = config.background.b;
-        pixels[i + 3] = config.background.a;
+        pixels[i] = background.r;
+        pixels[i + 1] = background.g;
+        pixels[i + 2] = background.b;
+        pixels[i + 3] = background.a;
     }
 
-    // 各要素をレンダリング
-    for element in &page.elements {
+    render_elements(&mut pixels, width, height, &page.elements, config, font_manager, scale, hairline);
+    pixels
+}
+
+/// 線幅（pt）を描画バッファ上のピクセル幅に変換
+/// 出力画像の1ピクセル未満（0を含む）の線は、DPIによらず消えないよう1ピクセル幅のヘアラインにします。
+fn stroke_px(width: f64, scale: f64, hairline: f64) -> f64 {
+    (width * scale).max(hairline)
+}
+
+/// 要素を描画順にピクセルバッファへ描画（クリップグループは再帰的に描画）
+#[allow(clippy::too_many_arguments)]
+fn render_elements(
+    pixels: &mut [u8],
+    width: u32,
+    height: u32,
+    elements: &[PageElement],
+    config: &ImageRenderConfig,
+    font_manager: &FontManager,
+    scale: f64,
+    hairline: f64,
+) {
+    for element in elements {
         match element {
             PageElement::Text {
                 x,
@@ -64,8 +349,8 @@ pub fn render_page_to_image(
                 align: _,
             } => {
                 render_text_to_pixels(
-                    &mut pixels, width, height, *x, *y, text, style, scale,
-                    font_manager,
+                    pixels, width, height, *x, *y, text, style, scale,
+                    font_manager, config,
                 );
             }
             PageElement::Rect {
@@ -77,9 +362,11 @@ pub fn render_page_to_image(
                 stroke,
                 stroke_width,
                 rotation_deg: _,
+                stroke_style,
             } => {
+                let dash = dash_px(stroke_style, *stroke_width, scale);
                 render_rect_to_pixels(
-                    &mut pixels,
+                    pixels,
                     width,
                     height,
                     *x * scale,
@@ -87,9 +374,18 @@ pub fn render_page_to_image(
                     *w * scale,
                     *h * scale,
                     fill.as_ref(),
-                    stroke.as_ref(),
-                    *stroke_width * scale,
+                    stroke.as_ref().filter(|_| dash.is_empty()),
+                    stroke_px(*stroke_width, scale, hairline),
                 );
+                if let Some(stroke_color) = stroke.as_ref().filter(|_| !dash.is_empty()) {
+                    let (x0, y0) = ((*x * scale).floor(), (*y * scale).floor());
+                    let (x1, y1) = (((x + w) * scale).floor(), ((y + h) * scale).floor());
+                    render_dashed_polyline_to_pixels(
+                        pixels, width, height,
+                        &[(x0, y0), (x1, y0), (x1, y1), (x0, y1), (x0, y0)],
+                        &dash, stroke_px(*stroke_width, scale, hairline), stroke_color,
+                    );
+                }
             }
             PageElement::GradientRect {
                 x,
@@ -101,7 +397,7 @@ pub fn render_page_to_image(
                 rotation_deg: _,
             } => {
                 render_gradient_rect_to_pixels(
-                    &mut pixels,
+                    pixels,
                     width,
                     height,
                     *x * scale,
@@ -118,13 +414,14 @@ pub fn render_page_to_image(
                 rx,
                 ry,
                 fill,
-                stroke: _,
-                stroke_width: _,
+                stroke,
+                stroke_width,
                 rotation_deg: _,
+                stroke_style,
             } => {
                 if let Some(fill_color) = fill {
                     render_ellipse_to_pixels(
-                        &mut pixels,
+                        pixels,
                         width,
                         height,
                         *cx * scale,
@@ -134,6 +431,26 @@ pub fn render_page_to_image(
                         fill_color,
                     );
                 }
+                let dash = dash_px(stroke_style, *stroke_width, scale);
+                if let Some(stroke_color) = stroke.as_ref().filter(|_| !dash.is_empty()) {
+                    render_dashed_polyline_to_pixels(
+                        pixels, width, height,
+                        &ellipse_points(*cx * scale, *cy * scale, *rx * scale, *ry * scale),
+                        &dash, stroke_px(*stroke_width, scale, hairline), stroke_color,
+                    );
+                } else if let Some(stroke_color) = stroke {
+                    render_ellipse_stroke_to_pixels(
+                        pixels,
+                        width,
+                        height,
+                        *cx * scale,
+                        *cy * scale,
+                        *rx * scale,
+                        *ry * scale,
+                        stroke_color,
+                        stroke_px(*stroke_width, scale, hairline),
+                    );
+                }
             }
             PageElement::EllipseImage {
                 cx,
@@ -141,13 +458,13 @@ pub fn render_page_to_image(
                 rx,
                 ry,
                 data,
-                mime_type,
+                mime_type: _,
                 stroke,
                 stroke_width,
                 rotation_deg: _,
             } => {
                 render_ellipse_image_to_pixels(
-                    &mut pixels,
+                    pixels,
                     width,
                     height,
                     *cx * scale,
@@ -155,23 +472,21 @@ pub fn render_page_to_image(
                     *rx * scale,
                     *ry * scale,
                     data,
-                    mime_type,
+                    config,
                 );
                 // Render stroke if specified
                 if let Some(stroke_color) = stroke {
-                    if *stroke_width > 0.0 {
-                        render_ellipse_stroke_to_pixels(
-                            &mut pixels,
-                            width,
-                            height,
-                            *cx * scale,
-                            *cy * scale,
-                            *rx * scale,
-                            *ry * scale,
-                            stroke_color,
-                            *stroke_width * scale,
-                        );
-                    }
+                    render_ellipse_stroke_to_pixels(
+                        pixels,
+                        width,
+                        height,
+                        *cx * scale,
+                        *cy * scale,
+                        *rx * scale,
+                        *ry * scale,
+                        stroke_color,
+                        stroke_px(*stroke_width, scale, hairline),
+                    );
                 }
             }
             PageElement::Image {
@@ -180,10 +495,10 @@ pub fn render_page_to_image(
                 width: img_w,
                 height: img_h,
                 data,
-                mime_type,
+                mime_type: _,
             } => {
                 render_image_to_pixels(
-                    &mut pixels,
+                    pixels,
                     width,
                     height,
                     *img_x * scale,
@@ -191,7 +506,7 @@ pub fn render_page_to_image(
                     *img_w * scale,
                     *img_h * scale,
                     data,
-                    mime_type,
+                    config,
                 );
             }
             PageElement::Line {
@@ -201,16 +516,15 @@ pub fn render_page_to_image(
                 y2,
                 width: w,
                 color,
+                stroke_style,
             } => {
-                render_line_to_pixels(
-                    &mut pixels,
+                render_dashed_polyline_to_pixels(
+                    pixels,
                     width,
                     height,
-                    *x1 * scale,
-                    *y1 * scale,
-                    *x2 * scale,
-                    *y2 * scale,
-                    *w * scale,
+                    &[(*x1 * scale, *y1 * scale), (*x2 * scale, *y2 * scale)],
+                    &dash_px(stroke_style, *w, scale),
+                    stroke_px(*w, scale, hairline),
                     color,
                 );
             }
@@ -220,37 +534,69 @@ pub fn render_page_to_image(
                 stroke,
                 stroke_width,
                 rotation_deg: _,
+                stroke_style,
             } => {
+                let dash = dash_px(stroke_style, *stroke_width, scale);
                 render_path_to_pixels(
-                    &mut pixels, width, height,
-                    commands, fill.as_ref(), stroke.as_ref(), *stroke_width, scale,
+                    pixels, width, height,
+                    commands, fill.as_ref(), stroke.as_ref().filter(|_| dash.is_empty()),
+                    stroke_px(*stroke_width, scale, hairline), scale,
                 );
+                if let Some(stroke_color) = stroke.as_ref().filter(|_| !dash.is_empty()) {
+                    for subpath in parse_path_commands_to_subpaths(commands, scale) {
+                        render_dashed_polyline_to_pixels(
+                            pixels, width, height,
+                            &subpath, &dash, stroke_px(*stroke_width, scale, hairline), stroke_color,
+                        );
+                    }
+                }
             }
             PageElement::PathImage {
                 commands,
                 data,
-                mime_type,
+                mime_type: _,
                 stroke,
                 stroke_width,
                 rotation_deg: _,
             } => {
                 // First render the clipped image
                 render_path_image_to_pixels(
-                    &mut pixels, width, height,
-                    commands, data, mime_type, scale,
+                    pixels, width, height,
+                    commands, data, config, scale,
                 );
                 // Then render stroke if specified
                 if let Some(stroke_color) = stroke.as_ref() {
-                    if *stroke_width > 0.0 {
-                        render_path_to_pixels(
-                            &mut pixels, width, height,
-                            commands,
-                            None,
-                            Some(stroke_color),
-                            *stroke_width,
-                            scale,
-                        );
-                    }
+                    render_path_to_pixels(
+                        pixels, width, height,
+                        commands,
+                        None,
+                        Some(stroke_color),
+                        stroke_px(*stroke_width, scale, hairline),
+                        scale,
+                    );
+                }
+            }
+            PageElement::GradientPath {
+                commands,
+                stops,
+                gradient_type,
+                stroke,
+                stroke_width,
+                rotation_deg: _,
+            } => {
+                render_gradient_path_to_pixels(
+                    pixels, width, height,
+                    commands, stops, gradient_type, scale,
+                );
+                if let Some(stroke_color) = stroke.as_ref() {
+                    render_path_to_pixels(
+                        pixels, width, height,
+                        commands,
+                        None,
+                        Some(stroke_color),
+                        stroke_px(*stroke_width, scale, hairline),
+                        scale,
+                    );
                 }
             }
             PageElement::TableBlock {
@@ -259,57 +605,269 @@ pub fn render_page_to_image(
                 width: tbl_w,
                 table,
             } => {
-                // Render table: draw grid lines and cell text
-                let row_height = 20.0; // Default row height in points
-                let padding = 4.0;
+                // Render table: draw grid lines and wrapped cell text
+                let font_data = font_manager.best_font_data();
+                // 縮小指定のセルはテキストがセルに収まるフォントサイズで配置
+                let shrunk = table.with_shrunk_cells(*tbl_w, font_data);
+                let table = shrunk.as_ref().unwrap_or(table);
+                let col_widths = table.resolved_column_widths(*tbl_w);
+                let row_heights = table.row_heights(*tbl_w, font_data);
                 let mut cy = *tbl_y;
 
-                // Calculate column widths
-                let col_widths = if table.column_widths.is_empty() {
-                    let ncols = table.rows.first().map_or(1, |r| r.len().max(1));
-                    vec![*tbl_w / ncols as f64; ncols]
-                } else {
-                    table.column_widths.clone()
-                };
-
-                for row in &table.rows {
+                for (row, row_height) in table.rows.iter().zip(row_heights) {
                     let mut cx = *tbl_x;
-                    for (ci, cell) in row.iter().enumerate() {
-                        let cw = col_widths.get(ci).copied().unwrap_or(60.0);
+                    let mut col = 0usize;
+                    for cell in row {
+                        // 結合の継続セルは枠線・テキストを描かずに1列進める
+                        if cell.col_span == 0 || cell.row_span == 0 {
+                            cx += Table::cell_width(&col_widths, col, 1);
+                            col += 1;
+                            continue;
+                        }
+                        let cw = Table::cell_width(&col_widths, col, cell.col_span);
+
+                        // Draw cell background
+                        if let Some(bg) = cell.background.as_ref() {
+                            render_rect_to_pixels(
+                                pixels, width, height,
+                                cx * scale, cy * scale, cw * scale, row_height * scale,
+                                Some(bg), None, 0.0,
+                            );
+                        }
 
-                        // Draw cell border
-                        render_rect_to_pixels(
-                            &mut pixels, width, height,
-                            cx * scale, cy * scale, cw * scale, row_height * scale,
-                            None,
-                            Some(&Color::rgb(128, 128, 128)),
-                            1.0,
-                        );
+                        // Draw cell border（辺ごとの指定が無ければ既定の灰色の枠）
+                        match cell.borders {
+                            Some(borders) => {
+                                for line in borders.edge_lines(cx, cy, cw, row_height) {
+                                    if let PageElement::Line { x1, y1, x2, y2, width: w, color, .. } = line {
+                                        render_line_to_pixels(
+                                            pixels, width, height,
+                                            x1 * scale, y1 * scale, x2 * scale, y2 * scale,
+                                            stroke_px(w, scale, hairline), &color,
+                                        );
+                                    }
+                                }
+                            }
+                            None => {
+                                render_rect_to_pixels(
+                                    pixels, width, height,
+                                    cx * scale, cy * scale, cw * scale, row_height * scale,
+                                    None,
+                                    Some(&Color::rgb(128, 128, 128)),
+                                    hairline,
+                                );
+                            }
+                        }
 
-                        // Draw cell text
-                        if !cell.text.is_empty() {
+                        // Draw cell text（セルの下端を超える行は描画しない）
+                        let line_step = cell.style.font_size * TABLE_LINE_SPACING;
+                        for (li, line) in Table::cell_lines(cell, cw, font_data).iter().enumerate() {
+                            let line_y = cy + TABLE_CELL_PADDING + li as f64 * line_step;
+                            if line_y + cell.style.font_size > cy + row_height {
+                                break;
+                            }
                             render_text_to_pixels(
-                                &mut pixels, width, height,
-                                cx + padding, cy + padding,
-                                &cell.text, &cell.style, scale, font_manager,
+                                pixels, width, height,
+                                cx + TABLE_CELL_PADDING, line_y,
+                                line, &cell.style, scale, font_manager, config,
                             );
                         }
 
                         cx += cw;
+                        col += cell.col_span as usize;
                     }
                     cy += row_height;
                 }
             }
+            PageElement::ClipGroup { clip_path, children } => {
+                // 子要素を別レイヤーに描画し、クリップ領域の内側だけを書き戻す
+                let mut layer = pixels.to_vec();
+                render_elements(&mut layer, width, height, children, config, font_manager, scale, hairline);
+                composite_clipped_layer(pixels, &layer, width, height, clip_path, scale);
+            }
+            PageElement::TransformGroup { cx, cy, rotation_deg, opacity, children } => {
+                render_transform_group(
+                    pixels, width, height, (*cx, *cy, *rotation_deg, *opacity),
+                    children, config, font_manager, scale, hairline,
+                );
+            }
         }
     }
+}
 
-    // PNGにエンコード
-    encode_png(&pixels, width, height)
+/// 変換グループ（回転・不透明度）を描画
+/// 子要素を黒地と白地の2枚のレイヤーに描画して各ピクセルの被覆率を求め、
+/// 回転の逆変換で双線形補間したレイヤーに不透明度を掛けてバッファに合成します。
+/// レイヤーは子要素の範囲のうち、回転後にページに入る部分だけを確保します。
+#[allow(clippy::too_many_arguments)]
+fn render_transform_group(
+    pixels: &mut [u8],
+    img_width: u32,
+    img_height: u32,
+    (cx, cy, rotation_deg, opacity): (f64, f64, f64, f64),
+    children: &[PageElement],
+    config: &ImageRenderConfig,
+    font_manager: &FontManager,
+    scale: f64,
+    hairline: f64,
+) {
+    // 線幅・アンチエイリアスのはみ出し分の余白（pt）
+    const MARGIN: f64 = 2.0;
+    let opacity = opacity.clamp(0.0, 1.0);
+    let Some((bx0, by0, bx1, by1)) = children
+        .iter()
+        .filter_map(PageElement::bounds)
+        .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+    else {
+        return;
+    };
+    if opacity <= 0.0 || scale <= 0.0 || img_width == 0 || img_height == 0 {
+        return;
+    }
+    let rotated_bounds = |(x0, y0, x1, y1): (f64, f64, f64, f64), degrees: f64| {
+        [(x0, y0), (x1, y0), (x1, y1), (x0, y1)]
+            .map(|(x, y)| rotate_point(x, y, cx, cy, degrees))
+            .iter()
+            .fold((f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY), |b, &(x, y)| {
+                (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y))
+            })
+    };
+    let page_bounds = (0.0, 0.0, img_width as f64 / scale, img_height as f64 / scale);
+    let visible = rotated_bounds(page_bounds, -rotation_deg);
+    let (x0, y0) = ((bx0 - MARGIN).max(visible.0), (by0 - MARGIN).max(visible.1));
+    let (x1, y1) = ((bx1 + MARGIN).min(visible.2), (by1 + MARGIN).min(visible.3));
+    if !(x0 < x1 && y0 < y1) {
+        return;
+    }
+    let layer_w = ((x1 - x0) * scale).ceil() as u32;
+    let layer_h = ((y1 - y0) * scale).ceil() as u32;
+
+    let mut shifted = children.to_vec();
+    for child in &mut shifted {
+        child.translate(-x0, -y0);
+    }
+    let render_layer = |background: u8| {
+        let mut layer = vec![background; (layer_w * layer_h * 4) as usize];
+        render_elements(&mut layer, layer_w, layer_h, &shifted, config, font_manager, scale, hairline);
+        layer
+    };
+    // 黒地では被覆率を掛けた色、白地との差から被覆率が分かる
+    let dark = render_layer(0);
+    let light = render_layer(255);
+    let sample = |lx: i64, ly: i64| -> [f64; 4] {
+        if lx < 0 || ly < 0 || lx >= layer_w as i64 || ly >= layer_h as i64 {
+            return [0.0; 4];
+        }
+        let idx = ((ly as u32 * layer_w + lx as u32) * 4) as usize;
+        let diff: f64 = (0..3).map(|c| light[idx + c] as f64 - dark[idx + c] as f64).sum::<f64>() / 3.0;
+        let alpha = (1.0 - diff / 255.0).clamp(0.0, 1.0);
+        [dark[idx] as f64, dark[idx + 1] as f64, dark[idx + 2] as f64, alpha]
+    };
+
+    let target = rotated_bounds((x0, y0, x1, y1), rotation_deg);
+    let to_px = |v: f64, max: u32| ((v * scale).floor().max(0.0) as u32).min(max);
+    let (px0, px1) = (to_px(target.0, img_width), to_px(target.2, img_width - 1) + 1);
+    let (py0, py1) = (to_px(target.1, img_height), to_px(target.3, img_height - 1) + 1);
+    for py in py0..py1.min(img_height) {
+        for px in px0..px1.min(img_width) {
+            let (sx, sy) =
+                rotate_point((px as f64 + 0.5) / scale, (py as f64 + 0.5) / scale, cx, cy, -rotation_deg);
+            let (u, v) = ((sx - x0) * scale - 0.5, (sy - y0) * scale - 0.5);
+            let (fx, fy) = (u.floor(), v.floor());
+            let (tx, ty) = (u - fx, v - fy);
+            let (ix, iy) = (fx as i64, fy as i64);
+            let mut texel = [0.0; 4];
+            for (dx, dy, weight) in
+                [(0, 0, (1.0 - tx) * (1.0 - ty)), (1, 0, tx * (1.0 - ty)), (0, 1, (1.0 - tx) * ty), (1, 1, tx * ty)]
+            {
+                if weight > 0.0 {
+                    let s = sample(ix + dx, iy + dy);
+                    for c in 0..4 {
+                        texel[c] += s[c] * weight;
+                    }
+                }
+            }
+            let alpha = texel[3] * opacity;
+            if alpha < 1.0 / 512.0 {
+                continue;
+            }
+            let idx = ((py * img_width + px) * 4) as usize;
+            for c in 0..3 {
+                let blended = pixels[idx + c] as f64 * (1.0 - alpha) + texel[c] * opacity;
+                pixels[idx + c] = blended.round().clamp(0.0, 255.0) as u8;
+            }
+            pixels[idx + 3] = 255;
+        }
+    }
+}
+
+/// 子要素を描画したレイヤーのうち、クリップパスの内側のピクセルだけをバッファに書き戻す
+/// 軸に平行な矩形はシザー矩形として行単位でコピーし、それ以外は偶奇規則の走査線で判定します。
+/// ピクセル中心がクリップ領域に含まれるピクセルを内側とみなします。
+fn composite_clipped_layer(
+    pixels: &mut [u8],
+    layer: &[u8],
+    img_width: u32,
+    img_height: u32,
+    clip_path: &[crate::converter::PathCommand],
+    scale: f64,
+) {
+    let to_px = |v: f64, max: u32| ((v * scale - 0.5).ceil().max(0.0) as u32).min(max);
+    let mut copy_span = |y: u32, x0: u32, x1: u32| {
+        let row = (y * img_width * 4) as usize;
+        let range = row + x0 as usize * 4..row + x1 as usize * 4;
+        pixels[range.clone()].copy_from_slice(&layer[range]);
+    };
+    if let Some((x0, y0, x1, y1)) = crate::converter::PathCommand::axis_aligned_rect(clip_path) {
+        let (x0, x1) = (to_px(x0, img_width), to_px(x1, img_width));
+        for y in to_px(y0, img_height)..to_px(y1, img_height) {
+            if x0 < x1 {
+                copy_span(y, x0, x1);
+            }
+        }
+        return;
+    }
+    let subpaths = parse_path_commands_to_subpaths(clip_path, scale);
+    for y in 0..img_height {
+        for (x_start, x_end) in even_odd_spans(&subpaths, y as f64 + 0.5) {
+            let x0 = ((x_start - 0.5).ceil().max(0.0) as u32).min(img_width);
+            let x1 = ((x_end - 0.5).ceil().max(0.0) as u32).min(img_width);
+            if x0 < x1 {
+                copy_span(y, x0, x1);
+            }
+        }
+    }
+}
+
+/// スーパーサンプリングしたバッファを `factor`×`factor` のボックスフィルタで縮小
+fn downsample_box(pixels: &[u8], width: u32, height: u32, factor: u32) -> Vec<u8> {
+    let src_width = width * factor;
+    let samples = factor * factor;
+    let mut out = vec![0u8; (width * height * 4) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0u32; 4];
+            for sy in y * factor..(y + 1) * factor {
+                for sx in x * factor..(x + 1) * factor {
+                    let idx = ((sy * src_width + sx) * 4) as usize;
+                    for (c, total) in sum.iter_mut().enumerate() {
+                        *total += pixels[idx + c] as u32;
+                    }
+                }
+            }
+            let idx = ((y * width + x) * 4) as usize;
+            for (c, total) in sum.iter().enumerate() {
+                out[idx + c] = ((total + samples / 2) / samples) as u8;
+            }
+        }
+    }
+    out
 }
 
 /// テキストをピクセルバッファに描画
 /// ab_glyphフォントラスタライザーを使用して正確なグリフ形状をレンダリングします。
 /// フォントが利用できない場合は簡易矩形フォールバックを使用します。
+#[allow(clippy::too_many_arguments)]
 fn render_text_to_pixels(
     pixels: &mut [u8],
     img_width: u32,
@@ -320,30 +878,99 @@ fn render_text_to_pixels(
     style: &FontStyle,
     scale: f64,
     font_manager: &FontManager,
+    config: &ImageRenderConfig,
 ) {
+    // 上付き・下付きは縮小したサイズとずらした位置で描画
+    let (font_size, y) = style.script_metrics(y);
+    let scripted;
+    let style = if font_size != style.font_size {
+        scripted = FontStyle { font_size, ..style.clone() };
+        &scripted
+    } else {
+        style
+    };
+
     let font_size_px = (style.font_size * scale) as f32;
     if font_size_px <= 0.0 || text.is_empty() {
         return;
     }
 
-    // Try to get font data and render with ab_glyph
-    let font_data = font_manager.resolve_font(&style.font_name)
-        .or_else(|| font_manager.best_font_data());
+    // 解析済みフォント（FontManagerのキャッシュ）で描画
+    // 太字・斜体の書体が無い場合は指定に従って強調を合成する
+    let (face, synthetic) = font_manager.style_face(&style.font_name, style.bold, style.italic);
+    let synthetic = if config.synthesize_styles { synthetic } else { SyntheticStyle::default() };
+    let text_width_px = if let Some(font) = font_manager.decoded_font(&face) {
+        render_text_with_font(
+            pixels, img_width, img_height, x, y, text, style, scale, font.as_ref(), config, synthetic,
+        )
+    } else {
+        // Fallback: simple rectangle rendering when no font available
+        render_text_fallback(pixels, img_width, img_height, x, y, text, style, scale)
+    };
+
+    let span = (x * scale, text_width_px);
+    let thickness = (style.font_size * 0.06 * scale).max(1.0);
 
-    if let Some(data) = font_data {
-        if let Ok(font) = FontRef::try_from_slice(data) {
-            render_text_with_font(
-                pixels, img_width, img_height, x, y, text, style, scale, &font,
-            );
-            return;
+    // 取り消し線
+    if style.strikethrough {
+        let line_y = (y + style.font_size * 0.6) * scale;
+        render_hline_to_pixels(pixels, img_width, img_height, span, (line_y, thickness), &style.color, false);
+    }
+
+    // 下線（PDF出力と同じくベースラインの少し下に描画）
+    if let Some(underline) = style.underline {
+        let color = underline.color.unwrap_or(style.color);
+        let line_y = (y + style.font_size * (1.0 + UNDERLINE_OFFSET)) * scale;
+        match underline.kind {
+            UnderlineKind::Single | UnderlineKind::Dotted => {
+                let dotted = underline.kind == UnderlineKind::Dotted;
+                render_hline_to_pixels(pixels, img_width, img_height, span, (line_y, thickness), &color, dotted);
+            }
+            UnderlineKind::Double => {
+                let gap = thickness * 1.5;
+                let thin = (thickness * 0.7).max(1.0);
+                for offset in [-gap / 2.0, gap / 2.0] {
+                    render_hline_to_pixels(pixels, img_width, img_height, span, (line_y + offset, thin), &color, false);
+                }
+            }
         }
     }
+}
 
-    // Fallback: simple rectangle rendering when no font available
-    render_text_fallback(pixels, img_width, img_height, x, y, text, style, scale);
+/// 水平線（取り消し線・下線）をピクセルバッファに描画
+/// `span` は (開始X, 幅)、`line` は (中心Y, 線幅)
+fn render_hline_to_pixels(
+    pixels: &mut [u8],
+    img_width: u32,
+    img_height: u32,
+    span: (f64, f64),
+    line: (f64, f64),
+    color: &Color,
+    dotted: bool,
+) {
+    let (x, width) = span;
+    let (center_y, thickness) = line;
+    let top = center_y - thickness / 2.0;
+    if !dotted {
+        render_rect_to_pixels(pixels, img_width, img_height, x, top, width, thickness, Some(color), None, 0.0);
+        return;
+    }
+    // 点線: 線幅と同じ長さの点を線幅の2倍間隔で並べる
+    let step = thickness * 3.0;
+    let mut dot_x = x;
+    while dot_x < x + width {
+        let dot_w = thickness.min(x + width - dot_x);
+        render_rect_to_pixels(pixels, img_width, img_height, dot_x, top, dot_w, thickness, Some(color), None, 0.0);
+        dot_x += step;
+    }
 }
 
-/// ab_glyphフォントを使用してテキストをレンダリング
+/// ab_glyphフォントを使用してテキストをレンダリングし、描画幅（ピクセル）を返す
+/// フォントにグリフが無い文字は `config.missing_glyph` に従って描画し、
+/// `config.antialias` が無効な場合はグリフのカバレッジを2値化します。
+/// ヒンティング有効時は各グリフの原点と送り幅を整数ピクセルに揃え、同じ文字が同じ形で描画されるようにします。
+/// `synthetic` の強調はグリフのカバレッジを傾け・太らせてから合成します。
+#[allow(clippy::too_many_arguments)]
 fn render_text_with_font(
     pixels: &mut [u8],
     img_width: u32,
@@ -353,21 +980,60 @@ fn render_text_with_font(
     text: &str,
     style: &FontStyle,
     scale: f64,
-    font: &FontRef,
-) {
+    font: &impl Font,
+    config: &ImageRenderConfig,
+    synthetic: SyntheticStyle,
+) -> f64 {
+    let missing_glyph = config.missing_glyph;
     let font_size_px = (style.font_size * scale) as f32;
     let px_scale = PxScale::from(font_size_px);
     let scaled_font = font.as_scaled(px_scale);
 
     let ascent = scaled_font.ascent();
-    let start_x = (x * scale) as f32;
-    let start_y = (y * scale) as f32 + ascent;
+    let hinting = config.hinting == Some(true);
+    let grid = |v: f32| if hinting { v.round() } else { v };
+    let start_x = grid((x * scale) as f32);
+    let start_y = grid((y * scale) as f32 + ascent);
+    // 文字間隔は2文字目以降の前に加算
+    let spacing = grid((style.letter_spacing * scale) as f32);
 
     let mut cursor_x = start_x;
 
-    for ch in text.chars() {
-        let glyph_id = font.glyph_id(ch);
-        let advance = scaled_font.h_advance(glyph_id);
+    for (i, ch) in text.chars().enumerate() {
+        if i > 0 {
+            cursor_x += spacing;
+        }
+        let mut glyph_id = font.glyph_id(ch);
+        if !font_has_glyph(font, ch) {
+            let replacement = match missing_glyph {
+                MissingGlyphPolicy::Replace(r) if font_has_glyph(font, r) => Some(font.glyph_id(r)),
+                _ => None,
+            };
+            match replacement {
+                Some(id) => glyph_id = id,
+                None => {
+                    let box_w = grid(missing_glyph_advance(ch, style.font_size * scale) as f32);
+                    if missing_glyph != MissingGlyphPolicy::Hide {
+                        // .notdef 相当の矩形（文字幅の内側、アセント領域）
+                        render_rect_to_pixels(
+                            pixels,
+                            img_width,
+                            img_height,
+                            (cursor_x + box_w * 0.1) as f64,
+                            (start_y - ascent * 0.8) as f64,
+                            (box_w * 0.8) as f64,
+                            (ascent * 0.8) as f64,
+                            None,
+                            Some(&style.color),
+                            1.0,
+                        );
+                    }
+                    cursor_x += box_w;
+                    continue;
+                }
+            }
+        }
+        let advance = grid(scaled_font.h_advance(glyph_id));
 
         if !ch.is_whitespace() {
             let glyph = glyph_id.with_scale_and_position(
@@ -377,31 +1043,94 @@ fn render_text_with_font(
 
             if let Some(outlined) = font.outline_glyph(glyph) {
                 let bounds = outlined.px_bounds();
-                outlined.draw(|gx, gy, coverage| {
+                let mut plot = |px: i32, py: i32, coverage: f32| {
                     const MIN_COVERAGE: f32 = 0.01;
-                    if coverage > MIN_COVERAGE {
-                        let px = (bounds.min.x as i32 + gx as i32) as u32;
-                        let py = (bounds.min.y as i32 + gy as i32) as u32;
-                        if px < img_width && py < img_height {
-                            let idx = ((py * img_width + px) * 4) as usize;
-                            if idx + 3 < pixels.len() {
-                                let alpha = coverage.min(1.0);
-                                pixels[idx] = blend_channel(pixels[idx], style.color.r, alpha);
-                                pixels[idx + 1] = blend_channel(pixels[idx + 1], style.color.g, alpha);
-                                pixels[idx + 2] = blend_channel(pixels[idx + 2], style.color.b, alpha);
-                                pixels[idx + 3] = 255;
-                            }
+                    if coverage <= MIN_COVERAGE || px < 0 || py < 0 {
+                        return;
+                    }
+                    let (px, py) = (px as u32, py as u32);
+                    if px < img_width && py < img_height {
+                        let idx = ((py * img_width + px) * 4) as usize;
+                        if idx + 3 < pixels.len() {
+                            let alpha = if config.antialias {
+                                coverage.min(1.0)
+                            } else if coverage >= 0.5 {
+                                1.0
+                            } else {
+                                return;
+                            };
+                            pixels[idx] = blend_channel(pixels[idx], style.color.r, alpha);
+                            pixels[idx + 1] = blend_channel(pixels[idx + 1], style.color.g, alpha);
+                            pixels[idx + 2] = blend_channel(pixels[idx + 2], style.color.b, alpha);
+                            pixels[idx + 3] = 255;
+                        }
+                    }
+                };
+                if synthetic.is_any() {
+                    let embolden = synthetic.embolden_width(style.font_size * scale);
+                    let (origin_x, coverage) = synthesize_glyph_coverage(&outlined, start_y, synthetic.italic, embolden);
+                    for (row_index, row) in coverage.iter().enumerate() {
+                        for (col, &c) in row.iter().enumerate() {
+                            plot(origin_x + col as i32, bounds.min.y as i32 + row_index as i32, c);
                         }
                     }
-                });
+                } else {
+                    outlined.draw(|gx, gy, coverage| {
+                        plot(bounds.min.x as i32 + gx as i32, bounds.min.y as i32 + gy as i32, coverage);
+                    });
+                }
             }
         }
 
         cursor_x += advance;
     }
+
+    (cursor_x - start_x) as f64
 }
 
-/// フォントが利用できない場合の簡易テキスト描画フォールバック
+/// 合成する斜体・太字を適用したグリフのカバレッジ（行ごと）と、その左端のX座標（ピクセル）
+/// 斜体はベースライン `baseline_y` からの高さに比例して右へずらし（端数は隣の画素に按分）、
+/// 太字は `embolden` ピクセル分だけ右へずらした重ね合わせ（最大値）で輪郭を太らせます。
+fn synthesize_glyph_coverage(
+    outlined: &ab_glyph::OutlinedGlyph,
+    baseline_y: f32,
+    italic: bool,
+    embolden: f64,
+) -> (i32, Vec<Vec<f32>>) {
+    let bounds = outlined.px_bounds();
+    let (width, height) = (bounds.width().ceil() as usize, bounds.height().ceil() as usize);
+    let slant = if italic { SYNTHETIC_ITALIC_SLANT as f32 } else { 0.0 };
+    let shift = |gy: u32| (baseline_y - (bounds.min.y + gy as f32 + 0.5)) * slant;
+    // 行ごとのずれの範囲から、左右に必要な余白を求める
+    let (top_shift, bottom_shift) = (shift(0), shift(height.saturating_sub(1) as u32));
+    let left_pad = (-top_shift.min(bottom_shift)).max(0.0).ceil() as usize;
+    let bold_px = embolden.round().max(if embolden > 0.0 { 1.0 } else { 0.0 }) as usize;
+    let row_width = width + left_pad + top_shift.max(bottom_shift).max(0.0).ceil() as usize + bold_px + 2;
+
+    let mut sheared = vec![vec![0.0f32; row_width]; height];
+    outlined.draw(|gx, gy, coverage| {
+        let Some(row) = sheared.get_mut(gy as usize) else { return };
+        let target = gx as f32 + left_pad as f32 + shift(gy);
+        let (col, frac) = (target.floor().max(0.0) as usize, target - target.floor());
+        if let Some(c) = row.get_mut(col) {
+            *c = (*c + coverage * (1.0 - frac)).min(1.0);
+        }
+        if let Some(c) = row.get_mut(col + 1) {
+            *c = (*c + coverage * frac).min(1.0);
+        }
+    });
+    let coverage = sheared
+        .iter()
+        .map(|row| {
+            (0..row_width)
+                .map(|col| (0..=bold_px.min(col)).map(|k| row[col - k]).fold(0.0f32, f32::max))
+                .collect()
+        })
+        .collect();
+    (bounds.min.x as i32 - left_pad as i32, coverage)
+}
+
+/// フォントが利用できない場合の簡易テキスト描画フォールバック（描画幅を返す）
 fn render_text_fallback(
     pixels: &mut [u8],
     img_width: u32,
@@ -411,14 +1140,18 @@ fn render_text_fallback(
     text: &str,
     style: &FontStyle,
     scale: f64,
-) {
+) -> f64 {
     let px = (x * scale) as i32;
     let py = (y * scale) as i32;
     let font_px = (style.font_size * scale) as i32;
     let char_width = font_px * 6 / 10;
+    let spacing = (style.letter_spacing * scale).round() as i32;
 
     let mut cursor_x = px;
-    for ch in text.chars() {
+    for (i, ch) in text.chars().enumerate() {
+        if i > 0 {
+            cursor_x += spacing;
+        }
         let cw = if ch.is_ascii() { char_width } else { font_px };
 
         if !ch.is_whitespace() {
@@ -441,6 +1174,8 @@ fn render_text_fallback(
 
         cursor_x += cw;
     }
+
+    (cursor_x - px) as f64
 }
 
 /// 矩形をピクセルバッファに描画
@@ -454,7 +1189,7 @@ fn render_rect_to_pixels(
     h: f64,
     fill: Option<&Color>,
     stroke: Option<&Color>,
-    _stroke_width: f64,
+    stroke_width: f64,
 ) {
     if let Some(fill_color) = fill {
         let x0 = x.max(0.0) as u32;
@@ -475,20 +1210,29 @@ fn render_rect_to_pixels(
         }
     }
     if let Some(stroke_color) = stroke {
-        let x0 = x.max(0.0) as u32;
-        let y0 = y.max(0.0) as u32;
-        let x1 = ((x + w) as u32).min(img_width.saturating_sub(1));
-        let y1 = ((y + h) as u32).min(img_height.saturating_sub(1));
-
-        // 上辺と下辺
-        for px in x0..=x1 {
-            set_pixel(pixels, img_width, px, y0, stroke_color);
-            set_pixel(pixels, img_width, px, y1, stroke_color);
-        }
-        // 左辺と右辺
-        for py in y0..=y1 {
-            set_pixel(pixels, img_width, x0, py, stroke_color);
-            set_pixel(pixels, img_width, x1, py, stroke_color);
+        // 各辺を中心に線幅の帯を描く（少なくとも1ピクセル）
+        let half = stroke_width.max(1.0) / 2.0;
+        let span = |from: f64, to: f64, max: u32| {
+            let start = from.round().max(0.0);
+            let end = to.round().max(start + 1.0);
+            (start as u32).min(max)..(end as u32).min(max)
+        };
+        let (x0, y0, x1, y1) = (x.floor(), y.floor(), (x + w).floor(), (y + h).floor());
+        let bands = [
+            // 上辺と下辺
+            (x0 - half, y0 - half, x1 + half, y0 + half),
+            (x0 - half, y1 - half, x1 + half, y1 + half),
+            // 左辺と右辺
+            (x0 - half, y0 - half, x0 + half, y1 + half),
+            (x1 - half, y0 - half, x1 + half, y1 + half),
+        ];
+        for (bx0, by0, bx1, by1) in bands {
+            // 中心のピクセル（座標 + 0.5）を基準に帯を取る
+            for py in span(by0 + 0.5, by1 + 0.5, img_height) {
+                for px in span(bx0 + 0.5, bx1 + 0.5, img_width) {
+                    set_pixel(pixels, img_width, px, py, stroke_color);
+                }
+            }
         }
     }
 }
@@ -558,16 +1302,24 @@ fn parse_path_commands_to_subpaths(
                 cx = ex;
                 cy = ey;
             }
-            PathCommand::ArcTo(_rx, _ry, _rot, _large, _sweep, x, y) => {
-                // TODO: Proper arc-to-bezier conversion for correct geometry.
-                // Currently approximated as a single line segment by adding the
-                // endpoint to the current subpath; the subpath rendering logic
-                // connects consecutive points to draw the line.
-                let ex = *x * scale;
-                let ey = *y * scale;
-                current_subpath.push((ex, ey));
-                cx = ex;
-                cy = ey;
+            PathCommand::ArcTo(rx, ry, ..) => {
+                // 弧をベジェ区間（90°以下）に分割し、半径に応じた分割数で折れ線化
+                let radius = rx.abs().max(ry.abs()) * scale;
+                let steps = (radius.sqrt().ceil() as usize).clamp(4, 32);
+                let from = (cx / scale, cy / scale);
+                for [c1x, c1y, c2x, c2y, ex, ey] in cmd.arc_cubics(from) {
+                    let (c1x, c1y, c2x, c2y) = (c1x * scale, c1y * scale, c2x * scale, c2y * scale);
+                    let (ex, ey) = (ex * scale, ey * scale);
+                    for i in 1..=steps {
+                        let t = i as f64 / steps as f64;
+                        let it = 1.0 - t;
+                        let px = it*it*it*cx + 3.0*it*it*t*c1x + 3.0*it*t*t*c2x + t*t*t*ex;
+                        let py = it*it*it*cy + 3.0*it*it*t*c1y + 3.0*it*t*t*c2y + t*t*t*ey;
+                        current_subpath.push((px, py));
+                    }
+                    cx = ex;
+                    cy = ey;
+                }
             }
             PathCommand::Close => {
                 // Close current subpath to its starting point
@@ -589,7 +1341,27 @@ fn parse_path_commands_to_subpaths(
     subpaths
 }
 
+/// 走査線 `y` と全サブパスの交点から、偶奇規則（even-odd）で塗る区間を求める
+/// 閉じていないサブパスは終点から始点への辺で暗黙に閉じて扱うため、
+/// 逆向きの内側サブパスに限らず、重なったサブパスはすべて穴になります。
+fn even_odd_spans(subpaths: &[Vec<(f64, f64)>], y: f64) -> Vec<(f64, f64)> {
+    let mut intersections: Vec<f64> = Vec::new();
+    for subpath in subpaths.iter().filter(|sp| sp.len() >= 2) {
+        let closing = (subpath[subpath.len() - 1], subpath[0]);
+        let edges = subpath.windows(2).map(|seg| (seg[0], seg[1])).chain(std::iter::once(closing));
+        for ((x1, y1), (x2, y2)) in edges {
+            if (y1 <= y && y2 > y) || (y2 <= y && y1 > y) {
+                intersections.push(x1 + (y - y1) / (y2 - y1) * (x2 - x1));
+            }
+        }
+    }
+    intersections.retain(|x| !x.is_nan());
+    intersections.sort_by(|a, b| a.total_cmp(b));
+    intersections.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
 /// パスをピクセルバッファに描画（多角形塗りつぶし + ストローク）
+/// `stroke_width` は描画バッファ上のピクセル幅です（`stroke_px` で変換済みの値）。
 fn render_path_to_pixels(
     pixels: &mut [u8],
     img_width: u32,
@@ -624,48 +1396,11 @@ fn render_path_to_pixels(
 
             // Scanline algorithm with even-odd fill rule
             for scan_y in min_y..max_y {
-                let y_f = scan_y as f64 + 0.5;
-                let mut intersections: Vec<f64> = Vec::new();
-
-                // Collect intersections from ALL subpaths
-                for subpath in &subpaths {
-                    if subpath.len() < 2 {
-                        continue;
-                    }
-
-                    for i in 0..subpath.len().saturating_sub(1) {
-                        let (x1, y1) = subpath[i];
-                        let (x2, y2) = subpath[i + 1];
-
-                        if (y1 <= y_f && y2 > y_f) || (y2 <= y_f && y1 > y_f) {
-                            let t = (y_f - y1) / (y2 - y1);
-                            intersections.push(x1 + t * (x2 - x1));
-                        }
-                    }
-                }
-
-                intersections.sort_by(|a, b| {
-                    // Use total ordering to ensure deterministic results
-                    a.partial_cmp(b).unwrap_or_else(|| {
-                        // Handle NaN cases: NaN values come last
-                        if a.is_nan() && b.is_nan() {
-                            std::cmp::Ordering::Equal
-                        } else if a.is_nan() {
-                            std::cmp::Ordering::Greater
-                        } else {
-                            std::cmp::Ordering::Less
-                        }
-                    })
-                });
-
-                // Even-odd rule: fill between pairs of intersections
-                for pair in intersections.chunks(2) {
-                    if pair.len() == 2 {
-                        let x_start = pair[0].max(0.0) as u32;
-                        let x_end = (pair[1] as u32).min(img_width);
-                        for px in x_start..x_end {
-                            set_pixel(pixels, img_width, px, scan_y, fill_color);
-                        }
+                for (x_start, x_end) in even_odd_spans(&subpaths, scan_y as f64 + 0.5) {
+                    let x_start = x_start.max(0.0) as u32;
+                    let x_end = (x_end.max(0.0) as u32).min(img_width);
+                    for px in x_start..x_end {
+                        set_pixel(pixels, img_width, px, scan_y, fill_color);
                     }
                 }
             }
@@ -674,14 +1409,13 @@ fn render_path_to_pixels(
 
     // Stroke each subpath
     if let Some(stroke_color) = stroke {
-        let scaled_width = (stroke_width * scale).max(1.0);
         for subpath in &subpaths {
             for i in 0..subpath.len().saturating_sub(1) {
                 let (x1, y1) = subpath[i];
                 let (x2, y2) = subpath[i + 1];
                 render_line_to_pixels(
                     pixels, img_width, img_height,
-                    x1, y1, x2, y2, scaled_width, stroke_color,
+                    x1, y1, x2, y2, stroke_width, stroke_color,
                 );
             }
         }
@@ -700,9 +1434,10 @@ fn render_line_to_pixels(
     width: f64,
     color: &Color,
 ) {
-    // half_w = number of pixels to expand on each side of center pixel
-    // For width=1: half_w=0 (single pixel), width=3: half_w=1 (3 pixels total), etc.
-    let half_w = ((width - 1.0) / 2.0).max(0.0) as i64;
+    // ブラシの幅（ピクセル数）。線幅を四捨五入し、1ピクセル未満の線も1ピクセルで描く
+    // For width=1: offsets 0..=0, width=2: 0..=1, width=3: -1..=1, etc.
+    let brush = width.round().max(1.0) as i64;
+    let brush_start = -(brush - 1) / 2;
     let mut x = x1 as i64;
     let mut y = y1 as i64;
     let dx = ((x2 - x1) as i64).abs();
@@ -722,8 +1457,8 @@ fn render_line_to_pixels(
     let max_iter = (dx.unsigned_abs() + dy.unsigned_abs() + 2) as usize;
     for _ in 0..max_iter.min(MAX_LINE_ITERATIONS) {
         // Draw a perpendicular brush for width > 1
-        if half_w > 0 {
-            for offset in -half_w..=half_w {
+        if brush > 1 {
+            for offset in brush_start..brush_start + brush {
                 let (px, py) = if is_steep {
                     (x + offset, y)
                 } else {
@@ -751,6 +1486,74 @@ fn render_line_to_pixels(
     }
 }
 
+/// 破線パターンを描画バッファ上の長さ（ピクセル）に変換（実線・描画できないほど短い場合は空）
+fn dash_px(style: &StrokeStyle, width: f64, scale: f64) -> Vec<f64> {
+    let dash: Vec<f64> = style.dash.lengths(width).iter().map(|l| l * scale).collect();
+    if dash.iter().sum::<f64>() < 2.0 {
+        return Vec::new();
+    }
+    dash
+}
+
+/// 楕円の輪郭を折れ線で近似した点列（始点に戻る閉じた折れ線）
+fn ellipse_points(cx: f64, cy: f64, rx: f64, ry: f64) -> Vec<(f64, f64)> {
+    let steps = ((rx.abs() + ry.abs()).ceil() as usize).clamp(16, 720);
+    (0..=steps)
+        .map(|i| {
+            let t = i as f64 / steps as f64 * std::f64::consts::TAU;
+            (cx + rx * t.cos(), cy + ry * t.sin())
+        })
+        .collect()
+}
+
+/// 折れ線を破線パターンで描画（`dash` は描画・空白の長さの繰り返し、空なら実線）
+/// パターンの位相は頂点をまたいで継続します。
+#[allow(clippy::too_many_arguments)]
+fn render_dashed_polyline_to_pixels(
+    pixels: &mut [u8],
+    img_width: u32,
+    img_height: u32,
+    points: &[(f64, f64)],
+    dash: &[f64],
+    width: f64,
+    color: &Color,
+) {
+    let mut index = 0;
+    let mut remaining = dash.first().copied().unwrap_or(0.0);
+    for segment in points.windows(2) {
+        let ((x1, y1), (x2, y2)) = (segment[0], segment[1]);
+        if dash.is_empty() {
+            render_line_to_pixels(pixels, img_width, img_height, x1, y1, x2, y2, width, color);
+            continue;
+        }
+        let length = (x2 - x1).hypot(y2 - y1);
+        // 座標が極端に大きい場合は区切りが膨大になるため実線で描く
+        if !length.is_finite() || length / dash.iter().sum::<f64>() > 10_000.0 {
+            render_line_to_pixels(pixels, img_width, img_height, x1, y1, x2, y2, width, color);
+            continue;
+        }
+        let mut t = 0.0;
+        while t < length {
+            let step = remaining.min(length - t);
+            if index % 2 == 0 && step > 0.0 {
+                let (s, e) = (t / length, (t + step) / length);
+                render_line_to_pixels(
+                    pixels, img_width, img_height,
+                    x1 + (x2 - x1) * s, y1 + (y2 - y1) * s,
+                    x1 + (x2 - x1) * e, y1 + (y2 - y1) * e,
+                    width, color,
+                );
+            }
+            t += step;
+            remaining -= step;
+            if remaining <= 1e-9 {
+                index = (index + 1) % dash.len();
+                remaining = dash[index];
+            }
+        }
+    }
+}
+
 /// ピクセルを設定
 fn set_pixel(pixels: &mut [u8], width: u32, x: u32, y: u32, color: &Color) {
     let idx = ((y * width + x) * 4) as usize;
@@ -791,37 +1594,86 @@ fn render_gradient_rect_to_pixels(
 
     for py in y0..y1 {
         for px in x0..x1 {
-            // Calculate gradient position (0.0 to 1.0)
-            let t = match gradient_type {
-                crate::converter::GradientType::Linear(angle) => {
-                    let local_x = (px as f64 - x) / w;
-                    let local_y = (py as f64 - y) / h;
-                    // Project onto gradient direction
-                    let cos_a = angle.cos();
-                    let sin_a = angle.sin();
-                    let proj = local_x * sin_a + local_y * cos_a;
-                    proj.clamp(0.0, 1.0)
-                }
-                crate::converter::GradientType::Radial => {
-                    let cx = x + w / 2.0;
-                    let cy = y + h / 2.0;
-                    let dx = (px as f64 - cx) / (w / 2.0);
-                    let dy = (py as f64 - cy) / (h / 2.0);
-                    (dx * dx + dy * dy).sqrt().min(1.0)
-                }
-            };
+            let t = gradient_position(gradient_type, px as f64, py as f64, x, y, w, h);
+            blend_gradient_pixel(pixels, img_width, px, py, interpolate_gradient(stops, t));
+        }
+    }
+}
 
-            let color = interpolate_gradient(stops, t);
-            let idx = ((py * img_width + px) * 4) as usize;
-            if idx + 3 < pixels.len() {
-                // Alpha blending
-                let alpha = color.a as f64 / 255.0;
-                pixels[idx] = (pixels[idx] as f64 * (1.0 - alpha) + color.r as f64 * alpha) as u8;
-                pixels[idx + 1] =
-                    (pixels[idx + 1] as f64 * (1.0 - alpha) + color.g as f64 * alpha) as u8;
-                pixels[idx + 2] =
-                    (pixels[idx + 2] as f64 * (1.0 - alpha) + color.b as f64 * alpha) as u8;
-                pixels[idx + 3] = 255;
+/// グラデーション内の位置（0.0〜1.0）を計算
+fn gradient_position(
+    gradient_type: &crate::converter::GradientType,
+    px: f64,
+    py: f64,
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+) -> f64 {
+    match gradient_type {
+        crate::converter::GradientType::Linear(angle) => {
+            let local_x = (px - x) / w;
+            let local_y = (py - y) / h;
+            // Project onto gradient direction
+            let proj = local_x * angle.sin() + local_y * angle.cos();
+            proj.clamp(0.0, 1.0)
+        }
+        crate::converter::GradientType::Radial => {
+            let dx = (px - (x + w / 2.0)) / (w / 2.0);
+            let dy = (py - (y + h / 2.0)) / (h / 2.0);
+            (dx * dx + dy * dy).sqrt().min(1.0)
+        }
+    }
+}
+
+/// グラデーションの色をピクセルにアルファ合成
+fn blend_gradient_pixel(pixels: &mut [u8], img_width: u32, px: u32, py: u32, color: Color) {
+    let idx = ((py * img_width + px) * 4) as usize;
+    if idx + 3 < pixels.len() {
+        let alpha = color.a as f64 / 255.0;
+        pixels[idx] = (pixels[idx] as f64 * (1.0 - alpha) + color.r as f64 * alpha) as u8;
+        pixels[idx + 1] = (pixels[idx + 1] as f64 * (1.0 - alpha) + color.g as f64 * alpha) as u8;
+        pixels[idx + 2] = (pixels[idx + 2] as f64 * (1.0 - alpha) + color.b as f64 * alpha) as u8;
+        pixels[idx + 3] = 255;
+    }
+}
+
+/// パスでクリップしたグラデーションをピクセルバッファに描画
+/// グラデーションはパスのバウンディングボックスに合わせ、偶奇規則で塗りつぶします。
+fn render_gradient_path_to_pixels(
+    pixels: &mut [u8],
+    img_width: u32,
+    img_height: u32,
+    commands: &[crate::converter::PathCommand],
+    stops: &[crate::converter::GradientStop],
+    gradient_type: &crate::converter::GradientType,
+    scale: f64,
+) {
+    let subpaths = parse_path_commands_to_subpaths(commands, scale);
+    if subpaths.is_empty() || stops.is_empty() {
+        return;
+    }
+
+    let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x, y) in subpaths.iter().flatten() {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let (w, h) = (max_x - min_x, max_y - min_y);
+    if w <= 0.0 || h <= 0.0 {
+        return;
+    }
+
+    for py in (min_y.max(0.0) as u32)..(max_y.min(img_height as f64) as u32) {
+        for (x_start, x_end) in even_odd_spans(&subpaths, py as f64 + 0.5) {
+            let x_start = x_start.max(0.0) as u32;
+            let x_end = (x_end.max(0.0) as u32).min(img_width);
+            for px in x_start..x_end {
+                let t = gradient_position(gradient_type, px as f64, py as f64, min_x, min_y, w, h);
+                blend_gradient_pixel(pixels, img_width, px, py, interpolate_gradient(stops, t));
             }
         }
     }
@@ -951,16 +1803,11 @@ fn render_image_to_pixels(
     w: f64,
     h: f64,
     data: &[u8],
-    _mime_type: &str,
+    config: &ImageRenderConfig,
 ) {
-    // Try PNG decoding first
-    if let Some(decoded) = decode_png_image(data) {
-        blit_decoded_image(pixels, img_width, img_height, x, y, w, h, &decoded);
-        return;
-    }
-    // Try JPEG decoding
-    if let Some(decoded) = decode_jpeg_image(data) {
-        blit_decoded_image(pixels, img_width, img_height, x, y, w, h, &decoded);
+    if let Some(decoded) = decode_image(data, config.color_mode) {
+        let src = ImageSampler { image: &decoded, interpolation: config.image_interpolation };
+        blit_decoded_image(pixels, img_width, img_height, x, y, w, h, src);
         return;
     }
     // Fallback: render placeholder rect
@@ -982,14 +1829,10 @@ fn render_ellipse_image_to_pixels(
     rx: f64,
     ry: f64,
     data: &[u8],
-    _mime_type: &str,
+    config: &ImageRenderConfig,
 ) {
     // Decode the image
-    let decoded = if let Some(img) = decode_png_image(data) {
-        img
-    } else if let Some(img) = decode_jpeg_image(data) {
-        img
-    } else {
+    let Some(decoded) = decode_image(data, config.color_mode) else {
         // Fallback: render placeholder
         render_rect_to_pixels(
             pixels, img_width, img_height,
@@ -1001,6 +1844,8 @@ fn render_ellipse_image_to_pixels(
         return;
     };
 
+    let src = ImageSampler { image: &decoded, interpolation: config.image_interpolation };
+
     // Calculate bounds
     let x0 = (cx - rx).max(0.0) as u32;
     let y0 = (cy - ry).max(0.0) as u32;
@@ -1015,37 +1860,9 @@ fn render_ellipse_image_to_pixels(
 
             // Check if pixel is inside ellipse
             if dx * dx + dy * dy <= 1.0 {
-                // Map pixel to source image coordinates
-                let src_x = ((px as f64 - (cx - rx)) / (rx * 2.0) * decoded.width as f64) as u32;
-                let src_y = ((py as f64 - (cy - ry)) / (ry * 2.0) * decoded.height as f64) as u32;
-
-                if src_x < decoded.width && src_y < decoded.height {
-                    let src_idx = ((src_y * decoded.width + src_x) * 4) as usize;
-                    if src_idx + 3 < decoded.pixels.len() {
-                        let src_a = decoded.pixels[src_idx + 3] as f64 / 255.0;
-
-                        if src_a > 0.99 {
-                            // Opaque pixel: direct copy
-                            set_pixel(pixels, img_width, px, py, &Color {
-                                r: decoded.pixels[src_idx],
-                                g: decoded.pixels[src_idx + 1],
-                                b: decoded.pixels[src_idx + 2],
-                                a: 255,
-                            });
-                        } else if src_a > 0.01 {
-                            // Semi-transparent: alpha blend
-                            let dst_idx = ((py * img_width + px) * 4) as usize;
-                            if dst_idx + 3 < pixels.len() {
-                                pixels[dst_idx] = (pixels[dst_idx] as f64 * (1.0 - src_a)
-                                    + decoded.pixels[src_idx] as f64 * src_a) as u8;
-                                pixels[dst_idx + 1] = (pixels[dst_idx + 1] as f64 * (1.0 - src_a)
-                                    + decoded.pixels[src_idx + 1] as f64 * src_a) as u8;
-                                pixels[dst_idx + 2] = (pixels[dst_idx + 2] as f64 * (1.0 - src_a)
-                                    + decoded.pixels[src_idx + 2] as f64 * src_a) as u8;
-                            }
-                        }
-                    }
-                }
+                let u = (px as f64 + 0.5 - (cx - rx)) / (rx * 2.0);
+                let v = (py as f64 + 0.5 - (cy - ry)) / (ry * 2.0);
+                blend_image_pixel(pixels, img_width, px, py, src.sample(u, v));
             }
         }
     }
@@ -1058,19 +1875,17 @@ fn render_path_image_to_pixels(
     img_height: u32,
     commands: &[crate::converter::PathCommand],
     data: &[u8],
-    _mime_type: &str,
+    config: &ImageRenderConfig,
     scale: f64,
 ) {
     // Decode the image first
-    let decoded = if let Some(img) = decode_png_image(data) {
-        img
-    } else if let Some(img) = decode_jpeg_image(data) {
-        img
-    } else {
+    let Some(decoded) = decode_image(data, config.color_mode) else {
         // Fallback: render placeholder
         return;
     };
 
+    let src = ImageSampler { image: &decoded, interpolation: config.image_interpolation };
+
     // Parse path commands into separate subpaths for clipping mask using helper function
     let subpaths = parse_path_commands_to_subpaths(commands, scale);
 
@@ -1106,78 +1921,14 @@ fn render_path_image_to_pixels(
 
     // Render image with path clipping using scanline algorithm
     for py in y0..y1 {
-        let y_f = py as f64 + 0.5;
-
-        // Find all intersections with the path at this scanline
-        let mut intersections: Vec<f64> = Vec::new();
-
-        for subpath in &subpaths {
-            if subpath.len() < 2 {
-                continue;
-            }
-
-            for i in 0..subpath.len().saturating_sub(1) {
-                let (x1, y1) = subpath[i];
-                let (x2, y2) = subpath[i + 1];
-
-                if (y1 <= y_f && y2 > y_f) || (y2 <= y_f && y1 > y_f) {
-                    let t = (y_f - y1) / (y2 - y1);
-                    intersections.push(x1 + t * (x2 - x1));
-                }
-            }
-        }
-
-        intersections.sort_by(|a, b| {
-            // Use total ordering to ensure deterministic results
-            a.partial_cmp(b).unwrap_or_else(|| {
-                // Handle NaN cases: NaN values come last
-                if a.is_nan() && b.is_nan() {
-                    std::cmp::Ordering::Equal
-                } else if a.is_nan() {
-                    std::cmp::Ordering::Greater
-                } else {
-                    std::cmp::Ordering::Less
-                }
-            })
-        });
-
-        // Fill pixels between pairs of intersections
-        for pair in intersections.chunks(2) {
-            if pair.len() == 2 {
-                let x_start = (pair[0].max(0.0) as u32).max(x0);
-                let x_end = ((pair[1] as u32).min(img_width)).min(x1);
-
-                for px in x_start..x_end {
-                    // Map pixel to source image coordinates
-                    let src_x = ((px as f64 - min_x) / path_width * decoded.width as f64) as u32;
-                    let src_y = ((py as f64 - min_y) / path_height * decoded.height as f64) as u32;
-
-                    if src_x < decoded.width && src_y < decoded.height {
-                        let src_idx = ((src_y * decoded.width + src_x) * 4) as usize;
-                        if src_idx + 3 < decoded.pixels.len() {
-                            let src_a = decoded.pixels[src_idx + 3] as f64 / 255.0;
-
-                            if src_a > 0.99 {
-                                set_pixel(pixels, img_width, px, py, &Color {
-                                    r: decoded.pixels[src_idx],
-                                    g: decoded.pixels[src_idx + 1],
-                                    b: decoded.pixels[src_idx + 2],
-                                    a: 255,
-                                });
-                            } else if src_a > 0.01 {
-                                let dst_idx = ((py * img_width + px) * 4) as usize;
-                                if dst_idx + 3 < pixels.len() {
-                                    pixels[dst_idx] = (pixels[dst_idx] as f64 * (1.0 - src_a)
-                                        + decoded.pixels[src_idx] as f64 * src_a) as u8;
-                                    pixels[dst_idx + 1] = (pixels[dst_idx + 1] as f64 * (1.0 - src_a)
-                                        + decoded.pixels[src_idx + 1] as f64 * src_a) as u8;
-                                    pixels[dst_idx + 2] = (pixels[dst_idx + 2] as f64 * (1.0 - src_a)
-                                        + decoded.pixels[src_idx + 2] as f64 * src_a) as u8;
-                                }
-                            }
-                        }
-                    }
-                }
+        for (x_start, x_end) in even_odd_spans(&subpaths, py as f64 + 0.5) {
+            let x_start = (x_start.max(0.0) as u32).max(x0);
+            let x_end = ((x_end.max(0.0) as u32).min(img_width)).min(x1);
+            for px in x_start..x_end {
+                // Map pixel to source image coordinates
+                let u = (px as f64 + 0.5 - min_x) / path_width;
+                let v = (py as f64 + 0.5 - min_y) / path_height;
+                blend_image_pixel(pixels, img_width, px, py, src.sample(u, v));
             }
         }
     }
@@ -1190,6 +1941,83 @@ struct DecodedImage {
     pixels: Vec<u8>, // RGBA
 }
 
+/// 補間方式を指定して画像の色を取り出すサンプラー
+#[derive(Clone, Copy)]
+struct ImageSampler<'a> {
+    image: &'a DecodedImage,
+    interpolation: ImageInterpolation,
+}
+
+impl ImageSampler<'_> {
+    /// 正規化座標 (u, v)（0.0〜1.0）の色をRGBAで取得
+    fn sample(&self, u: f64, v: f64) -> [u8; 4] {
+        let image = self.image;
+        if image.width == 0 || image.height == 0 {
+            return [0; 4];
+        }
+        let texel = |x: u32, y: u32| -> [f64; 4] {
+            let idx = ((y.min(image.height - 1) * image.width + x.min(image.width - 1)) * 4) as usize;
+            match image.pixels.get(idx..idx + 4) {
+                Some(p) => [p[0] as f64, p[1] as f64, p[2] as f64, p[3] as f64],
+                None => [0.0; 4],
+            }
+        };
+        let fx = (u * image.width as f64).max(0.0);
+        let fy = (v * image.height as f64).max(0.0);
+        let color = match self.interpolation {
+            ImageInterpolation::Nearest => texel(fx as u32, fy as u32),
+            ImageInterpolation::Bilinear => {
+                // テクセル中心を基準に隣接4テクセルを重み付け
+                let sx = (fx - 0.5).max(0.0);
+                let sy = (fy - 0.5).max(0.0);
+                let (x0, y0) = (sx as u32, sy as u32);
+                let (tx, ty) = (sx.fract(), sy.fract());
+                let (c00, c10) = (texel(x0, y0), texel(x0 + 1, y0));
+                let (c01, c11) = (texel(x0, y0 + 1), texel(x0 + 1, y0 + 1));
+                std::array::from_fn(|i| {
+                    let top = c00[i] + (c10[i] - c00[i]) * tx;
+                    let bottom = c01[i] + (c11[i] - c01[i]) * tx;
+                    top + (bottom - top) * ty
+                })
+            }
+        };
+        color.map(|c| c.round().clamp(0.0, 255.0) as u8)
+    }
+}
+
+/// 画像の色をピクセルにアルファ合成（ほぼ不透明ならそのまま、ほぼ透明なら描画しない）
+fn blend_image_pixel(pixels: &mut [u8], img_width: u32, px: u32, py: u32, rgba: [u8; 4]) {
+    const ALPHA_OPAQUE_THRESHOLD: f64 = 0.99;
+    const ALPHA_TRANSPARENT_THRESHOLD: f64 = 0.01;
+    let dst_idx = ((py * img_width + px) * 4) as usize;
+    if dst_idx + 3 >= pixels.len() {
+        return;
+    }
+    let src_a = rgba[3] as f64 / 255.0;
+    if src_a > ALPHA_OPAQUE_THRESHOLD {
+        pixels[dst_idx..dst_idx + 3].copy_from_slice(&rgba[..3]);
+        pixels[dst_idx + 3] = 255;
+    } else if src_a > ALPHA_TRANSPARENT_THRESHOLD {
+        for c in 0..3 {
+            pixels[dst_idx + c] = blend_channel(pixels[dst_idx + c], rgba[c], src_a as f32);
+        }
+        pixels[dst_idx + 3] = 255;
+    }
+}
+
+/// PNG / JPEG 画像をRGBAビットマップ（幅, 高さ, ピクセル）にデコード
+pub(crate) fn decode_to_rgba(data: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+    let decoded = decode_image(data, ColorMode::Color)?;
+    Some((decoded.width, decoded.height, decoded.pixels))
+}
+
+/// PNG / JPEG 画像をデコードし、色モードに合わせてビットマップを変換
+fn decode_image(data: &[u8], color_mode: ColorMode) -> Option<DecodedImage> {
+    let mut decoded = decode_png_image(data).or_else(|| decode_jpeg_image(data))?;
+    color_mode.apply_to_rgba(&mut decoded.pixels);
+    Some(decoded)
+}
+
 /// PNG画像をデコード
 fn decode_png_image(data: &[u8]) -> Option<DecodedImage> {
     let decoder = png::Decoder::new(std::io::Cursor::new(data));
@@ -1314,9 +2142,9 @@ fn blit_decoded_image(
     y: f64,
     w: f64,
     h: f64,
-    src: &DecodedImage,
+    src: ImageSampler,
 ) {
-    if src.width == 0 || src.height == 0 || w <= 0.0 || h <= 0.0 {
+    if src.image.width == 0 || src.image.height == 0 || w <= 0.0 || h <= 0.0 {
         return;
     }
 
@@ -1327,42 +2155,15 @@ fn blit_decoded_image(
 
     for py in y0..y1 {
         for px in x0..x1 {
-            // Map to source pixel (nearest neighbor)
-            let src_x = ((px as f64 - x) / w * src.width as f64) as u32;
-            let src_y = ((py as f64 - y) / h * src.height as f64) as u32;
-            if src_x < src.width && src_y < src.height {
-                let src_idx = ((src_y * src.width + src_x) * 4) as usize;
-                let dst_idx = ((py * img_width + px) * 4) as usize;
-                if src_idx + 3 < src.pixels.len() && dst_idx + 3 < pixels.len() {
-                    let src_a = src.pixels[src_idx + 3] as f64 / 255.0;
-                    // Thresholds for fast-path (fully opaque) and skip (fully transparent)
-                    const ALPHA_OPAQUE_THRESHOLD: f64 = 0.99;
-                    const ALPHA_TRANSPARENT_THRESHOLD: f64 = 0.01;
-                    if src_a > ALPHA_OPAQUE_THRESHOLD {
-                        pixels[dst_idx] = src.pixels[src_idx];
-                        pixels[dst_idx + 1] = src.pixels[src_idx + 1];
-                        pixels[dst_idx + 2] = src.pixels[src_idx + 2];
-                        pixels[dst_idx + 3] = 255;
-                    } else if src_a > ALPHA_TRANSPARENT_THRESHOLD {
-                        pixels[dst_idx] = (pixels[dst_idx] as f64 * (1.0 - src_a)
-                            + src.pixels[src_idx] as f64 * src_a)
-                            as u8;
-                        pixels[dst_idx + 1] = (pixels[dst_idx + 1] as f64 * (1.0 - src_a)
-                            + src.pixels[src_idx + 1] as f64 * src_a)
-                            as u8;
-                        pixels[dst_idx + 2] = (pixels[dst_idx + 2] as f64 * (1.0 - src_a)
-                            + src.pixels[src_idx + 2] as f64 * src_a)
-                            as u8;
-                        pixels[dst_idx + 3] = 255;
-                    }
-                }
-            }
+            let u = (px as f64 + 0.5 - x) / w;
+            let v = (py as f64 + 0.5 - y) / h;
+            blend_image_pixel(pixels, img_width, px, py, src.sample(u, v));
         }
     }
 }
 
 /// RGBAピクセルデータをPNGにエンコード
-fn encode_png(pixels: &[u8], width: u32, height: u32) -> Vec<u8> {
+pub(crate) fn encode_png(pixels: &[u8], width: u32, height: u32) -> Vec<u8> {
     let mut output = Vec::new();
     {
         let mut encoder = png::Encoder::new(&mut output, width, height);
@@ -1382,11 +2183,85 @@ pub fn render_to_images_zip(doc: &Document, font_manager: &FontManager) -> Vec<u
 }
 
 /// 設定指定でドキュメント全ページを画像化してZIPにまとめる
+/// `parallel` フィーチャー有効時（ネイティブのみ）はページごとの描画を並列に行います。
+/// ZIP内のページ順と内容は逐次描画と同一です。
 pub fn render_to_images_zip_with_config(
     doc: &Document,
     font_manager: &FontManager,
     config: &ImageRenderConfig,
 ) -> Vec<u8> {
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    let images: Vec<Vec<u8>> = {
+        use rayon::prelude::*;
+        doc.pages
+            .par_iter()
+            .enumerate()
+            .map(|(i, page)| render_zip_page(page, i + 1, doc.pages.len(), config, font_manager))
+            .collect()
+    };
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    let images: Vec<Vec<u8>> = doc
+        .pages
+        .iter()
+        .enumerate()
+        .map(|(i, page)| render_zip_page(page, i + 1, doc.pages.len(), config, font_manager))
+        .collect();
+    assemble_images_zip(&images)
+}
+
+/// 設定指定でドキュメント全ページを1ページずつ順番に画像化してZIPにまとめる
+/// `parallel` フィーチャーの有無に関わらず並列化しません。
+pub fn render_to_images_zip_sequential(
+    doc: &Document,
+    font_manager: &FontManager,
+    config: &ImageRenderConfig,
+) -> Vec<u8> {
+    let images: Vec<Vec<u8>> = doc
+        .pages
+        .iter()
+        .enumerate()
+        .map(|(i, page)| render_zip_page(page, i + 1, doc.pages.len(), config, font_manager))
+        .collect();
+    assemble_images_zip(&images)
+}
+
+/// ZIP出力用に `number` / `total` ページ目を描画（向きの自動補正・はみ出し縮小・ページ番号を適用）
+fn render_zip_page(
+    page: &Page,
+    number: usize,
+    total: usize,
+    config: &ImageRenderConfig,
+    font_manager: &FontManager,
+) -> Vec<u8> {
+    let oriented;
+    let page = match config.auto_orient.then(|| page.auto_oriented()).flatten() {
+        Some(p) => {
+            oriented = p;
+            &oriented
+        }
+        None => page,
+    };
+    let fitted;
+    let page = match page.fitted(config.fit_mode) {
+        Some(p) => {
+            fitted = p;
+            &fitted
+        }
+        None => page,
+    };
+    let numbered;
+    let page = match &config.page_numbers {
+        Some(numbers) => {
+            numbered = page.with_page_number(numbers, number, total, font_manager.best_font_data());
+            &numbered
+        }
+        None => page,
+    };
+    render_page_to_image(page, config, font_manager)
+}
+
+/// 描画済みのページ画像をページ順にZIPへ格納
+fn assemble_images_zip(images: &[Vec<u8>]) -> Vec<u8> {
     use std::io::Write;
 
     let mut zip_buffer = Vec::new();
@@ -1395,11 +2270,12 @@ pub fn render_to_images_zip_with_config(
         let options = zip::write::SimpleFileOptions::default()
             .compression_method(zip::CompressionMethod::Deflated);
 
-        for (i, page) in doc.pages.iter().enumerate() {
-            let image_data = render_page_to_image(page, config, font_manager);
-            let filename = format!("page_{:04}.png", i + 1);
+        for (i, image_data) in images.iter().enumerate() {
+            // WebPの上限を超えてPNGになったページは拡張子もPNGにする
+            let extension = if image_data.starts_with(b"RIFF") { "webp" } else { "png" };
+            let filename = format!("page_{:04}.{}", i + 1, extension);
             if zip.start_file(&filename, options).is_ok() {
-                let _ = zip.write_all(&image_data);
+                let _ = zip.write_all(image_data);
             }
         }
 
@@ -1,16 +1,24 @@
 // lib.rs - WebAssemblyエントリーポイント
 //
 // wasm-bindgen を使用してJavaScriptから呼び出し可能なAPIを公開します。
-// ドキュメント変換の全フローを統合します。
+// ドキュメント変換の全フローは `api::Converter` に統合されており、
+// Rustから利用する場合は `Converter` を直接使用できます。
 
+pub mod api;
 pub mod converter;
 pub mod font_manager;
 pub mod formats;
+pub mod icc_profile;
 pub mod image_renderer;
+pub mod pdf_encryption;
 pub mod pdf_writer;
+pub mod search;
+pub mod svg;
+pub mod webp;
+
+pub use api::Converter;
 
 use converter::detect_format;
-use font_manager::FontManager;
 use wasm_bindgen::prelude::*;
 
 #[cfg(all(target_arch = "wasm32", feature = "diagnostics"))]
@@ -57,7 +65,17 @@ macro_rules! diag_warn {
 /// JavaScriptからこのオブジェクトを作成して使用します。
 #[wasm_bindgen]
 pub struct WasmConverter {
-    font_manager: FontManager,
+    inner: Converter,
+    /// 画像ZIPの各ページの画像フォーマット
+    image_format: image_renderer::ImageFormat,
+    /// 画像ZIPで縦横比の異なる矩形に画像を収める方法
+    image_fit: converter::ImageFit,
+    /// PDF・画像ZIPの各ページに重ねる透かし
+    watermark: Option<converter::Watermark>,
+    /// PDF・画像ZIPの各ページに付けるページ番号
+    page_numbers: Option<converter::PageNumberConfig>,
+    /// PDF・画像ZIPで背景が指定されていないページに敷く背景色
+    default_page_background: Option<converter::Color>,
 }
 
 #[wasm_bindgen]
@@ -66,7 +84,12 @@ impl WasmConverter {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
         Self {
-            font_manager: FontManager::new(),
+            inner: Converter::new(),
+            image_format: image_renderer::ImageFormat::Png,
+            image_fit: converter::ImageFit::Stretch,
+            watermark: None,
+            page_numbers: None,
+            default_page_background: None,
         }
     }
 
@@ -77,39 +100,192 @@ impl WasmConverter {
     /// @param data フォントファイルのバイト列（Uint8Array）
     #[wasm_bindgen(js_name = addFont)]
     pub fn add_font(&mut self, name: String, data: Vec<u8>) {
-        self.font_manager.add_font(name, data);
+        self.inner.add_font(name, data);
     }
 
     /// 外部フォントを削除
     /// @param name 削除するフォント名
     #[wasm_bindgen(js_name = removeFont)]
     pub fn remove_font(&mut self, name: &str) {
-        self.font_manager.remove_font(name);
+        self.inner.remove_font(name);
+    }
+
+    /// フォント名の別名を追加
+    /// ドキュメント内の`requested`フォントを`provided`フォントで描画します。
+    /// @param requested ドキュメントで参照されるフォント名（例: "Calibri"）
+    /// @param provided 代わりに使用するフォント名（例: "Carlito"）
+    #[wasm_bindgen(js_name = addFontAlias)]
+    pub fn add_font_alias(&mut self, requested: String, provided: String) {
+        self.inner.add_font_alias(requested, provided);
+    }
+
+    /// 不足フォントを遅延読み込みするコールバックを設定
+    /// 変換中に利用できないフォント名が見つかると、フォント名ごとに一度だけ
+    /// `resolver(name)` を呼び出します。`Uint8Array` を返すとそのフォントを追加して使用し、
+    /// `null` を返すか例外を投げた場合は従来どおり代替フォントで描画します。
+    /// コールバックは同期的に値を返す必要があります（Promise は待ちません）。
+    /// @param resolver `(name: string) => Uint8Array | null`（null / undefined で解除）
+    #[wasm_bindgen(js_name = setFontResolver)]
+    pub fn set_font_resolver(&mut self, resolver: Option<js_sys::Function>) {
+        let resolver = resolver.map(|callback| -> font_manager::FontResolver {
+            Box::new(move |name: &str| {
+                let value = callback.call1(&JsValue::NULL, &JsValue::from_str(name)).ok()?;
+                value
+                    .dyn_into::<js_sys::Uint8Array>()
+                    .ok()
+                    .map(|array| array.to_vec())
+            })
+        });
+        self.inner.set_font_resolver(resolver);
+    }
+
+    /// 直前の変換で参照されたが利用できなかったフォント名の一覧をJSON配列で取得
+    #[wasm_bindgen(js_name = missingFonts)]
+    pub fn missing_fonts(&self) -> String {
+        serde_json::to_string(self.inner.missing_fonts()).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// 直前の変換で読み込めず、プレースホルダーで表示した画像の一覧をJSON配列で取得
+    /// `addAsset(path, data)` でデータを供給して再変換すると画像を描画します。
+    /// @returns `[{ relationshipId, path }]` のJSON文字列（`path` はパッケージ内のパス。解決できない場合は null）
+    #[wasm_bindgen(js_name = deferredAssets)]
+    pub fn deferred_assets(&self) -> String {
+        serde_json::to_string(&self.inner.deferred_assets()).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// 読み込めなかった画像のデータを供給（以降の変換で使用）
+    /// @param path `deferredAssets` の `path`（例: "word/media/image1.png"）
+    /// @param data 画像ファイルのバイト列（Uint8Array）
+    #[wasm_bindgen(js_name = addAsset)]
+    pub fn add_asset(&mut self, path: String, data: Vec<u8>) {
+        self.inner.add_asset(path, data);
+    }
+
+    /// `addAsset` で供給した画像のデータをすべて破棄
+    #[wasm_bindgen(js_name = clearAssets)]
+    pub fn clear_assets(&mut self) {
+        self.inner.clear_assets();
+    }
+
+    /// TXT / CSV 変換の既定フォント・サイズを設定
+    /// @param options_json オプションのJSON文字列（例: `{"default_font": "NotoSansMono", "default_font_size": 10, "monospace": true, "repeat_header": true}`）
+    #[wasm_bindgen(js_name = setTextOptions)]
+    pub fn set_text_options(&mut self, options_json: &str) -> Result<(), JsValue> {
+        let options = serde_json::from_str(options_json)
+            .map_err(|e| JsValue::from_str(&format!("テキストオプションの解析エラー: {}", e)))?;
+        self.inner.set_text_options(options);
+        Ok(())
+    }
+
+    /// 変換時のリソース上限を設定（ZIP爆弾対策）
+    /// @param limits_json 上限のJSON文字列（例: `{"max_pages": 500, "max_image_bytes": 16777216, "max_total_decompressed_bytes": 268435456}`）
+    #[wasm_bindgen(js_name = setLimits)]
+    pub fn set_limits(&mut self, limits_json: &str) -> Result<(), JsValue> {
+        let limits = serde_json::from_str(limits_json)
+            .map_err(|e| JsValue::from_str(&format!("上限設定の解析エラー: {}", e)))?;
+        self.inner.set_limits(limits);
+        Ok(())
+    }
+
+    /// 画像ZIPの各ページの画像フォーマットを設定
+    /// @param format "png" または "webp"
+    /// @param quality 非可逆時の品質 0〜100（デフォルト: 90、100で丸めなし）
+    /// @param lossless 常に可逆で符号化するか（デフォルト: false）。false の場合も画像を含まないページは可逆で、
+    ///   それ以外は品質に応じて色の下位ビットを丸めます（出力は常にVP8L）
+    #[wasm_bindgen(js_name = setImageFormat)]
+    pub fn set_image_format(&mut self, format: &str, quality: Option<u8>, lossless: Option<bool>) -> Result<(), JsValue> {
+        self.image_format = match format.to_lowercase().as_str() {
+            "png" => image_renderer::ImageFormat::Png,
+            "webp" => image_renderer::ImageFormat::WebP {
+                quality: quality.unwrap_or(90).min(100),
+                lossless: lossless.unwrap_or(false),
+            },
+            _ => return Err(JsValue::from_str(&format!("不正な画像フォーマットです: {}", format))),
+        };
+        Ok(())
+    }
+
+    /// 画像ZIPで縦横比の異なる矩形に画像を収める方法を設定
+    /// PDFでは `convertToPdfWithOptions` のオプション `image_fit` で指定します。
+    /// @param mode "Stretch"（引き伸ばし、デフォルト）/ "Contain"（全体を収める）/ "Cover"（覆うように切り取る）
+    #[wasm_bindgen(js_name = setImageFit)]
+    pub fn set_image_fit(&mut self, mode: &str) -> Result<(), JsValue> {
+        self.image_fit = serde_json::from_value(serde_json::Value::String(mode.to_string()))
+            .map_err(|_| JsValue::from_str(&format!("不正な画像の収め方です: {}", mode)))?;
+        Ok(())
+    }
+
+    /// PDF・画像ZIPの各ページに重ねる透かしを設定
+    /// `convertToPdfWithOptions` のオプションで `watermark` を指定した場合はそちらを優先します。
+    /// @param watermark_json 透かしのJSON文字列（例: `{"content": {"Text": {"text": "CONFIDENTIAL"}}, "opacity": 0.2}`、
+    ///   画像は `{"content": {"Image": {"data": [...], "scale": 0.5}}}`）。null / undefined で解除
+    #[wasm_bindgen(js_name = setWatermark)]
+    pub fn set_watermark(&mut self, watermark_json: Option<String>) -> Result<(), JsValue> {
+        self.watermark = match watermark_json {
+            Some(json) => Some(
+                serde_json::from_str(&json)
+                    .map_err(|e| JsValue::from_str(&format!("透かし設定の解析エラー: {}", e)))?,
+            ),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// PDF・画像ZIPの各ページに付けるページ番号を設定（元のドキュメントのフッターとは別に描画）
+    /// `convertToPdfWithOptions` のオプションで `page_numbers` を指定した場合はそちらを優先します。
+    /// @param config_json ページ番号設定のJSON文字列（例: `{"position": "BottomRight", "format": "{n}/{total}", "font_size": 9, "margin": 20}`）。
+    ///   null / undefined で解除
+    #[wasm_bindgen(js_name = setPageNumbers)]
+    pub fn set_page_numbers(&mut self, config_json: Option<String>) -> Result<(), JsValue> {
+        self.page_numbers = match config_json {
+            Some(json) => Some(
+                serde_json::from_str(&json)
+                    .map_err(|e| JsValue::from_str(&format!("ページ番号設定の解析エラー: {}", e)))?,
+            ),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// PDF・画像ZIPで背景が指定されていないページに敷く背景色を設定
+    /// 元のドキュメントで指定された背景（スライドの背景など）は上書きしません。
+    /// `convertToPdfWithOptions` のオプションで `default_page_background` を指定した場合はそちらを優先します。
+    /// @param color_json 色のJSON文字列（例: `{"r": 240, "g": 240, "b": 240, "a": 255}`）。null / undefined で解除
+    #[wasm_bindgen(js_name = setDefaultPageBackground)]
+    pub fn set_default_page_background(&mut self, color_json: Option<String>) -> Result<(), JsValue> {
+        self.default_page_background = match color_json {
+            Some(json) => Some(
+                serde_json::from_str(&json)
+                    .map_err(|e| JsValue::from_str(&format!("背景色の解析エラー: {}", e)))?,
+            ),
+            None => None,
+        };
+        Ok(())
     }
 
     /// 日本語内蔵フォントが利用可能かどうか
     #[wasm_bindgen(js_name = hasJapaneseFont)]
     pub fn has_japanese_font(&self) -> bool {
-        self.font_manager.has_builtin_japanese_font()
+        self.inner.font_manager().has_builtin_japanese_font()
     }
 
     /// いずれかのフォントが利用可能かどうか（外部フォント含む）
     #[wasm_bindgen(js_name = hasAnyFont)]
     pub fn has_any_font(&self) -> bool {
-        self.font_manager.has_any_font()
+        self.inner.font_manager().has_any_font()
     }
 
     /// 利用可能なフォント名の一覧をJSON配列で取得
     #[wasm_bindgen(js_name = listFonts)]
     pub fn list_fonts(&self) -> String {
-        let fonts = self.font_manager.available_fonts();
+        let fonts = self.inner.font_manager().available_fonts();
         serde_json::to_string(&fonts).unwrap_or_else(|_| "[]".to_string())
     }
 
     /// 読み込まれた外部フォントの数を取得
     #[wasm_bindgen(js_name = externalFontCount)]
     pub fn external_font_count(&self) -> usize {
-        self.font_manager.external_font_count()
+        self.inner.font_manager().external_font_count()
     }
 
     /// サポートされているフォーマット一覧をJSON文字列で取得
@@ -133,42 +309,226 @@ impl WasmConverter {
     /// @param data ファイルのバイト列
     /// @returns PDFバイト列
     #[wasm_bindgen(js_name = convertToPdf)]
-    pub fn convert_to_pdf(&self, filename: &str, data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    pub fn convert_to_pdf(&mut self, filename: &str, data: &[u8]) -> Result<Vec<u8>, JsValue> {
         diag_log!("Converting {} to PDF (size: {} bytes)", filename, data.len());
 
-        let ext = detect_format(filename).ok_or_else(|| {
-            JsValue::from_str(&format!(
-                "サポートされていないファイル形式です: {}",
-                filename
-            ))
-        })?;
-
-        let doc = formats::convert_by_extension(ext, data).map_err(|e| {
+        let result = if self.watermark.is_some() || self.page_numbers.is_some() || self.default_page_background.is_some() {
+            let mut options = pdf_writer::PdfOptions::default();
+            self.apply_instance_defaults(&mut options);
+            self.inner.to_pdf_with_options(filename, data, &options)
+        } else {
+            self.inner.to_pdf(filename, data)
+        };
+        let pdf_data = result.map_err(|e| {
             diag_error!("Conversion error: {}", e);
-            JsValue::from_str(&e.to_string())
+            js_error(e)
         })?;
-
-        diag_log!("Document has {} pages", doc.pages.len());
-
-        let pdf_data = pdf_writer::render_to_pdf_with_fonts(&doc, &self.font_manager);
+        if !self.inner.warnings().is_empty() {
+            diag_warn!("{}", self.inner.warnings().join("\n"));
+        }
 
         diag_log!("PDF generation complete ({} bytes)", pdf_data.len());
 
         Ok(pdf_data)
     }
 
+    /// 出力オプションを指定してファイルをPDFに変換
+    /// @param filename ファイル名（拡張子でフォーマットを判定）
+    /// @param data ファイルのバイト列
+    /// @param options_json PDF出力オプションのJSON文字列（例: `{"linearize": true}`）
+    /// @returns PDFバイト列
+    #[wasm_bindgen(js_name = convertToPdfWithOptions)]
+    pub fn convert_to_pdf_with_options(
+        &mut self,
+        filename: &str,
+        data: &[u8],
+        options_json: &str,
+    ) -> Result<Vec<u8>, JsValue> {
+        let mut options: pdf_writer::PdfOptions = serde_json::from_str(options_json)
+            .map_err(|e| JsValue::from_str(&format!("PDFオプションの解析エラー: {}", e)))?;
+        self.apply_instance_defaults(&mut options);
+
+        self.inner.to_pdf_with_options(filename, data, &options).map_err(js_error)
+    }
+
+    /// ファイルをPDFに変換し、検索に一致したテキストに半透明のハイライトを敷く
+    /// 大文字・小文字を区別せずに検索し、同じ行のランをまたぐ一致は1つの矩形にします。
+    /// `setWatermark` などのインスタンスの設定も適用します。
+    /// @param filename ファイル名（拡張子でフォーマットを判定）
+    /// @param data ファイルのバイト列
+    /// @param query 検索文字列
+    /// @param regex `query` を正規表現として扱う（デフォルト: false）
+    /// @returns PDFバイト列
+    #[wasm_bindgen(js_name = convertToPdfWithHighlights)]
+    pub fn convert_to_pdf_with_highlights(
+        &mut self,
+        filename: &str,
+        data: &[u8],
+        query: &str,
+        regex: Option<bool>,
+    ) -> Result<Vec<u8>, JsValue> {
+        let mut options = pdf_writer::PdfOptions {
+            search_highlight: Some(converter::SearchHighlight::new(query, regex.unwrap_or(false))),
+            ..Default::default()
+        };
+        self.apply_instance_defaults(&mut options);
+        self.inner.to_pdf_with_options(filename, data, &options).map_err(js_error)
+    }
+
+    /// ファイルを配布用に保護したPDFに変換
+    /// オプションで `encryption` を指定しない場合は閲覧専用（パスワードなしで開け、印刷・コピー・変更・注釈は禁止）で
+    /// 暗号化し、`watermark` も `setWatermark` も指定していない場合は「CONFIDENTIAL」の透かしを重ねます。
+    /// @param filename ファイル名（拡張子でフォーマットを判定）
+    /// @param data ファイルのバイト列
+    /// @param options_json PDF出力オプションのJSON文字列（例: `{"encryption": {"user_password": "secret",
+    ///   "permissions": {"print": true, "copy": false, "modify": false, "annotate": false}}}`）。null / undefined で既定値
+    /// @returns PDFバイト列
+    #[wasm_bindgen(js_name = convertToProtectedPdf)]
+    pub fn convert_to_protected_pdf(
+        &mut self,
+        filename: &str,
+        data: &[u8],
+        options_json: Option<String>,
+    ) -> Result<Vec<u8>, JsValue> {
+        let mut options: pdf_writer::PdfOptions = match options_json {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| JsValue::from_str(&format!("PDFオプションの解析エラー: {}", e)))?,
+            None => pdf_writer::PdfOptions::default(),
+        };
+        self.apply_instance_defaults(&mut options);
+
+        self.inner.to_pdf_with_options(filename, data, &options.protected()).map_err(js_error)
+    }
+
+    /// ファイルをページ（スライド）ごとに独立した1ページのPDFに変換し、ZIPで返す
+    /// `setWatermark` の透かしは各PDFに適用します（ページ番号は各PDFが1ページのため付けません）。
+    /// @param filename ファイル名（拡張子でフォーマットを判定）
+    /// @param data ファイルのバイト列
+    /// @returns ZIPバイト列（`page-001.pdf`, `page-002.pdf`, ...）
+    #[wasm_bindgen(js_name = convertToPdfZip)]
+    pub fn convert_to_pdf_zip(&mut self, filename: &str, data: &[u8]) -> Result<Vec<u8>, JsValue> {
+        let options = pdf_writer::PdfOptions {
+            watermark: self.watermark.clone(),
+            default_page_background: self.default_page_background,
+            ..Default::default()
+        };
+        self.inner.to_pdf_zip(filename, data, &options).map_err(js_error)
+    }
+
+    /// 複数ファイルを順番に変換し、1つのPDFに結合
+    /// `setWatermark` / `setPageNumbers` / `setDefaultPageBackground` の設定を結合したPDF全体に適用します。
+    /// @param files `{ filename, data }` オブジェクトの配列（dataはUint8Array）
+    /// @returns 結合されたPDFバイト列
+    #[wasm_bindgen(js_name = convertBatchToPdf)]
+    pub fn convert_batch_to_pdf(&mut self, files: js_sys::Array) -> Result<Vec<u8>, JsValue> {
+        let mut inputs: Vec<(String, Vec<u8>)> = Vec::new();
+        for (index, entry) in files.iter().enumerate() {
+            let filename = js_sys::Reflect::get(&entry, &JsValue::from_str("filename"))?
+                .as_string()
+                .ok_or_else(|| JsValue::from_str(&format!("{}番目の filename が文字列ではありません", index + 1)))?;
+            let data = js_sys::Reflect::get(&entry, &JsValue::from_str("data"))?;
+            if !data.is_instance_of::<js_sys::Uint8Array>() {
+                return Err(JsValue::from_str(&format!(
+                    "{}番目のファイル（{}）の data が Uint8Array ではありません",
+                    index + 1,
+                    filename
+                )));
+            }
+            inputs.push((filename, js_sys::Uint8Array::new(&data).to_vec()));
+        }
+
+        let refs: Vec<(&str, &[u8])> = inputs
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice()))
+            .collect();
+        let mut options = pdf_writer::PdfOptions::default();
+        self.apply_instance_defaults(&mut options);
+        self.inner.to_pdf_batch(&refs, &options).map_err(js_error)
+    }
+
     /// ファイルを画像に変換してZIPで返す
     /// @param filename ファイル名
     /// @param data ファイルのバイト列
     /// @param dpi 画像の解像度（デフォルト: 150）
-    /// @returns ZIPバイト列（各ページがPNG画像）
+    /// @param supersample スーパーサンプリング倍率 1〜4（デフォルト: 1）
+    /// @param antialias アンチエイリアスの有効/無効（デフォルト: true）
+    /// @param color_mode 色モード "Color" / "Grayscale" / "BlackWhite"（デフォルト: "Color"）
+    /// @param quality 画質のプリセット "Draft" / "Standard" / "High" / "Print"（デフォルト: "Standard"）
+    ///   DPI・スーパーサンプリング・補間方式・ヒンティングをまとめて設定します。`dpi` と `supersample` の指定が優先されます。
+    /// @returns ZIPバイト列（各ページがPNG画像、`setImageFormat` でWebPも指定可能）
     #[wasm_bindgen(js_name = convertToImagesZip)]
+    #[allow(clippy::too_many_arguments)]
     pub fn convert_to_images_zip(
-        &self,
+        &mut self,
         filename: &str,
         data: &[u8],
         dpi: Option<f64>,
+        supersample: Option<u8>,
+        antialias: Option<bool>,
+        color_mode: Option<String>,
+        quality: Option<String>,
     ) -> Result<Vec<u8>, JsValue> {
+        let quality = match quality {
+            Some(name) => image_renderer::ImageQuality::from_name(&name)
+                .ok_or_else(|| JsValue::from_str(&format!("不正な画質のプリセットです: {}", name)))?,
+            None => image_renderer::ImageQuality::default(),
+        };
+        let mut config = image_renderer::ImageRenderConfig {
+            format: self.image_format,
+            watermark: self.watermark.clone(),
+            page_numbers: self.page_numbers.clone(),
+            image_fit: self.image_fit,
+            default_page_background: self.default_page_background,
+            ..image_renderer::ImageRenderConfig::with_quality(quality)
+        };
+        if let Some(d) = dpi {
+            // DPIは変換時に72〜600の範囲にクランプしてOOMを防止
+            config.dpi = d;
+        }
+        if let Some(n) = supersample {
+            config.supersample = n;
+        }
+        if let Some(aa) = antialias {
+            config.antialias = aa;
+        }
+        if let Some(mode) = color_mode {
+            config.color_mode = serde_json::from_value(serde_json::Value::String(mode.clone()))
+                .map_err(|_| JsValue::from_str(&format!("不正な色モードです: {}", mode)))?;
+        }
+
+        self.inner.to_images_zip(filename, data, config).map_err(js_error)
+    }
+
+    /// ファイルからテキストのみを抽出（検索インデックス用）
+    /// @param filename ファイル名
+    /// @param data ファイルのバイト列
+    /// @returns ページごとのテキストのJSON配列（読み順、表のセルはタブ区切り）
+    #[wasm_bindgen(js_name = extractText)]
+    pub fn extract_text(&self, filename: &str, data: &[u8]) -> Result<String, JsValue> {
+        let text = self.inner.extract_text(filename, data).map_err(js_error)?;
+
+        serde_json::to_string(&text)
+            .map_err(|e| JsValue::from_str(&format!("JSONシリアライズエラー: {}", e)))
+    }
+
+    /// DOCXのコメントを抽出
+    /// @param filename ファイル名（DOCXのみ対応）
+    /// @param data ファイルのバイト列
+    /// @returns `{ id, author, date, text, anchoredText }` のJSON配列（anchoredText はコメントが付けられた本文のテキスト）
+    #[wasm_bindgen(js_name = extractComments)]
+    pub fn extract_comments(&self, filename: &str, data: &[u8]) -> Result<String, JsValue> {
+        let comments = self.inner.extract_comments(filename, data).map_err(js_error)?;
+
+        serde_json::to_string(&comments)
+            .map_err(|e| JsValue::from_str(&format!("JSONシリアライズエラー: {}", e)))
+    }
+
+    /// ドキュメントの埋め込みフォント・画像を変換せずに列挙
+    /// @param filename ファイル名
+    /// @param data ファイルのバイト列
+    /// @returns `{ name, kind, mime_type, size }` のJSON配列（kindは "font" または "image"）
+    #[wasm_bindgen(js_name = listEmbeddedAssets)]
+    pub fn list_embedded_assets(&self, filename: &str, data: &[u8]) -> Result<String, JsValue> {
         let ext = detect_format(filename).ok_or_else(|| {
             JsValue::from_str(&format!(
                 "サポートされていないファイル形式です: {}",
@@ -176,27 +536,19 @@ impl WasmConverter {
             ))
         })?;
 
-        let doc = formats::convert_by_extension(ext, data).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let assets = formats::assets::list_embedded_assets(ext, data)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let mut config = image_renderer::ImageRenderConfig::default();
-        if let Some(d) = dpi {
-            // DPIを72〜600の範囲にクランプしてOOMを防止
-            config.dpi = d.clamp(72.0, 600.0);
-        }
-
-        Ok(image_renderer::render_to_images_zip_with_config(
-            &doc,
-            &self.font_manager,
-            &config,
-        ))
+        serde_json::to_string(&assets)
+            .map_err(|e| JsValue::from_str(&format!("JSONシリアライズエラー: {}", e)))
     }
 
-    /// ファイルをJSON形式のドキュメントモデルに変換（デバッグ用）
+    /// PPTXの各スライドの切り替え効果を抽出（描画には影響しません）
     /// @param filename ファイル名
     /// @param data ファイルのバイト列
-    /// @returns ドキュメントモデルのJSON文字列
-    #[wasm_bindgen(js_name = convertToJson)]
-    pub fn convert_to_json(&self, filename: &str, data: &[u8]) -> Result<String, JsValue> {
+    /// @returns `{ slide, type, duration, advanceOnClick, advanceAfter }` のJSON配列（時間はミリ秒）
+    #[wasm_bindgen(js_name = extractTransitions)]
+    pub fn extract_transitions(&self, filename: &str, data: &[u8]) -> Result<String, JsValue> {
         let ext = detect_format(filename).ok_or_else(|| {
             JsValue::from_str(&format!(
                 "サポートされていないファイル形式です: {}",
@@ -204,11 +556,85 @@ impl WasmConverter {
             ))
         })?;
 
-        let doc = formats::convert_by_extension(ext, data).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let transitions = formats::transitions::extract_transitions(ext, data)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_json::to_string(&transitions)
+            .map_err(|e| JsValue::from_str(&format!("JSONシリアライズエラー: {}", e)))
+    }
+
+    /// 変換を実行し、ページごとの機能の再現度をまとめたレポートを返す（本番導入前の確認用）
+    /// @param filename ファイル名
+    /// @param data ファイルのバイト列
+    /// @returns `{ pages: [{ page, ok, approximated, dropped }], warnings, missing_fonts }` のJSON文字列
+    #[wasm_bindgen(js_name = conversionReport)]
+    pub fn conversion_report(&mut self, filename: &str, data: &[u8]) -> Result<String, JsValue> {
+        let report = self.inner.conversion_report(filename, data).map_err(js_error)?;
 
-        serde_json::to_string_pretty(&doc)
+        serde_json::to_string(&report)
             .map_err(|e| JsValue::from_str(&format!("JSONシリアライズエラー: {}", e)))
     }
+
+    /// ファイルをJSON形式のドキュメントモデルに変換（デバッグ・データ交換用）
+    /// @param filename ファイル名
+    /// @param data ファイルのバイト列
+    /// @returns `{ schemaVersion, document }` 形式のJSON文字列（モデルを変更すると `schemaVersion` が上がります）
+    #[wasm_bindgen(js_name = convertToJson)]
+    pub fn convert_to_json(&mut self, filename: &str, data: &[u8]) -> Result<String, JsValue> {
+        self.inner.to_json(filename, data).map_err(js_error)
+    }
+
+    /// `convertToJson` 形式のJSON（編集したものを含む）をPDFに描画
+    /// `setWatermark` / `setPageNumbers` / `setDefaultPageBackground` の設定を適用します。
+    /// @param json `{ schemaVersion, document }` 形式のJSON文字列（バージョンが異なる場合はエラー）
+    /// @returns PDFバイト列
+    #[wasm_bindgen(js_name = renderJsonToPdf)]
+    pub fn render_json_to_pdf(&mut self, json: &str) -> Result<Vec<u8>, JsValue> {
+        let mut options = pdf_writer::PdfOptions::default();
+        self.apply_instance_defaults(&mut options);
+        self.inner.render_json_to_pdf(json, &options).map_err(js_error)
+    }
+
+    /// `convertToJson` 形式のJSON（編集したものを含む）をページごとの画像に描画してZIPで返す
+    /// @param json `{ schemaVersion, document }` 形式のJSON文字列（バージョンが異なる場合はエラー）
+    /// @param dpi 解像度（デフォルト: 150）
+    /// @returns ZIPバイト列（各ページの画像、`setImageFormat` の形式）
+    #[wasm_bindgen(js_name = renderJsonToImagesZip)]
+    pub fn render_json_to_images_zip(&mut self, json: &str, dpi: Option<f64>) -> Result<Vec<u8>, JsValue> {
+        let mut config = image_renderer::ImageRenderConfig {
+            format: self.image_format,
+            watermark: self.watermark.clone(),
+            page_numbers: self.page_numbers.clone(),
+            image_fit: self.image_fit,
+            default_page_background: self.default_page_background,
+            ..Default::default()
+        };
+        if let Some(d) = dpi {
+            config.dpi = d;
+        }
+        self.inner.render_json_to_images_zip(json, config).map_err(js_error)
+    }
+}
+
+impl WasmConverter {
+    /// `setWatermark` / `setPageNumbers` / `setDefaultPageBackground` の設定を、
+    /// オプションで指定されていない項目に適用
+    fn apply_instance_defaults(&self, options: &mut pdf_writer::PdfOptions) {
+        if options.watermark.is_none() {
+            options.watermark = self.watermark.clone();
+        }
+        if options.page_numbers.is_none() {
+            options.page_numbers = self.page_numbers.clone();
+        }
+        if options.default_page_background.is_none() {
+            options.default_page_background = self.default_page_background;
+        }
+    }
+}
+
+/// 変換エラーをJavaScriptの例外値に変換
+fn js_error(e: converter::ConvertError) -> JsValue {
+    JsValue::from_str(&e.to_string())
 }
 
 /// 簡易変換関数（インスタンスなしで使用可能）
@@ -222,11 +648,11 @@ pub fn convert_document(
     data: &[u8],
     output_format: &str,
 ) -> Result<Vec<u8>, JsValue> {
-    let converter = WasmConverter::new();
+    let mut converter = WasmConverter::new();
 
     match output_format {
         "pdf" => converter.convert_to_pdf(filename, data),
-        "images_zip" | "zip" => converter.convert_to_images_zip(filename, data, None),
+        "images_zip" | "zip" => converter.convert_to_images_zip(filename, data, None, None, None, None, None),
         _ => Err(JsValue::from_str(&format!(
             "サポートされていない出力形式です: {} (pdf または images_zip を指定してください)",
             output_format
@@ -254,6 +680,8 @@ mod tests {
         assert_eq!(detect_format("test.docx"), Some("docx"));
         assert_eq!(detect_format("test.xlsx"), Some("xlsx"));
         assert_eq!(detect_format("test.csv"), Some("csv"));
+        assert_eq!(detect_format("test.TSV"), Some("tsv"));
+        assert_eq!(detect_format("test.psv"), Some("psv"));
         assert_eq!(detect_format("test.unknown"), None);
     }
 
@@ -0,0 +1,443 @@
+// pdf_encryption.rs - PDFの暗号化（標準セキュリティハンドラ）
+//
+// PDF 1.6 の標準セキュリティハンドラ（/V 4 /R 4、AESV2 = AES-128-CBC）で
+// 文字列とストリームを暗号化します。ユーザーパスワード・オーナーパスワードと
+// 印刷・コピー・変更・注釈の許可フラグを指定できます。
+// MD5・AES は md-5 / aes / cbc クレートを使い、/O・/U の計算にだけ使う RC4 はこのモジュールで実装しています。
+
+use aes::cipher::{block_padding::Pkcs7, BlockEncryptMut, KeyIvInit};
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+
+/// AESV2 の暗号（AES-128-CBC）
+type Aes128CbcEnc = cbc::Encryptor<aes::Aes128>;
+
+/// 暗号化したPDFで許可する操作
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfPermissions {
+    /// 印刷（高品質印刷を含む）
+    pub print: bool,
+    /// テキスト・画像のコピー（アクセシビリティのための抽出を含む）
+    pub copy: bool,
+    /// 内容の変更（ページの挿入・削除・回転を含む）
+    pub modify: bool,
+    /// 注釈の追加・フォームへの入力
+    pub annotate: bool,
+}
+
+impl Default for PdfPermissions {
+    fn default() -> Self {
+        Self { print: true, copy: true, modify: true, annotate: true }
+    }
+}
+
+impl PdfPermissions {
+    /// 閲覧のみ（印刷・コピー・変更・注釈をすべて禁止）
+    pub fn view_only() -> Self {
+        Self { print: false, copy: false, modify: false, annotate: false }
+    }
+
+    /// 暗号化辞書の /P の値（予約ビットは1、許可ビットは PDF 32000-1 表22）
+    pub fn p_value(&self) -> i32 {
+        let mut p: u32 = 0xFFFF_F0C0;
+        let mut set = |allowed: bool, bits: &[u32]| {
+            if allowed {
+                for bit in bits {
+                    p |= 1 << (bit - 1);
+                }
+            }
+        };
+        set(self.print, &[3, 12]);
+        set(self.modify, &[4, 11]);
+        set(self.copy, &[5, 10]);
+        set(self.annotate, &[6, 9]);
+        p as i32
+    }
+}
+
+/// PDFの暗号化設定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfEncryption {
+    /// 文書を開くためのパスワード（空の場合はパスワードなしで開ける）
+    pub user_password: String,
+    /// 許可フラグを変更するためのパスワード（None の場合はランダムに生成）
+    pub owner_password: Option<String>,
+    pub permissions: PdfPermissions,
+}
+
+impl PdfEncryption {
+    /// 閲覧専用の配布向けプリセット（パスワードなしで開け、印刷・コピー・変更・注釈は禁止）
+    pub fn view_only() -> Self {
+        Self { permissions: PdfPermissions::view_only(), ..Self::default() }
+    }
+}
+
+/// パスワードを32バイトに揃えるためのパディング（PDF 32000-1 7.6.3.3）
+const PASSWORD_PADDING: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08,
+    0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE, 0x64, 0x53, 0x69, 0x7A,
+];
+
+/// パスワードの先頭32バイトにパディングを足した32バイト
+/// パスワードは PDFDocEncoding とみなし、ASCII以外の文字は '?' に置き換えます。
+fn padded_password(password: &str) -> [u8; 32] {
+    let mut out = PASSWORD_PADDING;
+    let bytes: Vec<u8> = password.chars().map(|c| if c.is_ascii() { c as u8 } else { b'?' }).take(32).collect();
+    out[..bytes.len()].copy_from_slice(&bytes);
+    out[bytes.len()..].copy_from_slice(&PASSWORD_PADDING[..32 - bytes.len()]);
+    out
+}
+
+/// RC4で `key` と各バイトに 0〜19 をXORした鍵で20回暗号化（/O・/U の計算）
+fn rc4_20_rounds(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    for i in 0..20u8 {
+        let round_key: Vec<u8> = key.iter().map(|b| b ^ i).collect();
+        out = rc4(&round_key, &out);
+    }
+    out
+}
+
+/// 暗号化辞書と、オブジェクトごとの文字列・ストリームの暗号化
+pub(crate) struct SecurityHandler {
+    /// ファイル暗号化キー（16バイト）
+    key: [u8; 16],
+    owner_hash: Vec<u8>,
+    user_hash: Vec<u8>,
+    p: i32,
+    /// トレーラーの /ID の1要素目
+    file_id: [u8; 16],
+}
+
+impl SecurityHandler {
+    /// 設定とファイルID（/ID）から鍵と /O・/U を計算（アルゴリズム 2・3・5、リビジョン4）
+    pub(crate) fn new(encryption: &PdfEncryption, file_id: [u8; 16]) -> Self {
+        let user = padded_password(&encryption.user_password);
+        let owner_password = match &encryption.owner_password {
+            Some(password) if !password.is_empty() => password.clone(),
+            _ => random_password(&file_id),
+        };
+
+        // アルゴリズム3: /O（オーナーパスワードから導いた鍵でユーザーパスワードを暗号化）
+        let mut owner_key = md5(&padded_password(&owner_password));
+        for _ in 0..50 {
+            owner_key = md5(&owner_key);
+        }
+        let owner_hash = rc4_20_rounds(&owner_key, &user);
+
+        // アルゴリズム2: ファイル暗号化キー
+        let p = encryption.permissions.p_value();
+        let mut input = user.to_vec();
+        input.extend_from_slice(&owner_hash);
+        input.extend_from_slice(&p.to_le_bytes());
+        input.extend_from_slice(&file_id);
+        let mut key = md5(&input);
+        for _ in 0..50 {
+            key = md5(&key);
+        }
+
+        // アルゴリズム5: /U（パディングとファイルIDのハッシュを暗号化し、残り16バイトは0で埋める）
+        let mut seed = PASSWORD_PADDING.to_vec();
+        seed.extend_from_slice(&file_id);
+        let mut user_hash = rc4_20_rounds(&key, &md5(&seed));
+        user_hash.resize(32, 0);
+
+        Self { key, owner_hash, user_hash, p, file_id }
+    }
+
+    /// 暗号化辞書（/Encrypt の参照先）
+    pub(crate) fn encrypt_dict(&self) -> String {
+        format!(
+            "<< /Filter /Standard /V 4 /R 4 /Length 128 \
+             /CF << /StdCF << /Type /CryptFilter /CFM /AESV2 /AuthEvent /DocOpen /Length 16 >> >> \
+             /StmF /StdCF /StrF /StdCF /O <{}> /U <{}> /P {} >>",
+            to_hex(&self.owner_hash),
+            to_hex(&self.user_hash),
+            self.p
+        )
+    }
+
+    /// トレーラーの /ID 配列
+    pub(crate) fn id_array(&self) -> String {
+        let id = to_hex(&self.file_id);
+        format!("[<{}> <{}>]", id, id)
+    }
+
+    /// オブジェクトごとの鍵（アルゴリズム1、AESの場合は "sAlT" を付加）
+    fn object_key(&self, id: u32) -> [u8; 16] {
+        let mut input = self.key.to_vec();
+        input.extend_from_slice(&id.to_le_bytes()[..3]);
+        input.extend_from_slice(&[0, 0]);
+        input.extend_from_slice(b"sAlT");
+        md5(&input)
+    }
+
+    /// オブジェクトの内容の文字列・ストリームを暗号化
+    /// 文字列は暗号化後の16進文字列に置き換え、ストリームは /Length を暗号化後の長さに更新します。
+    pub(crate) fn encrypt_object(&self, id: u32, data: &[u8]) -> Vec<u8> {
+        let key = self.object_key(id);
+        let mut counter = 0u32;
+        let mut encrypt = |plain: &[u8]| {
+            // IVは文字列・ストリームごとの乱数（先頭16バイトとして出力に含める）
+            let mut seed = key.to_vec();
+            seed.extend_from_slice(&counter.to_le_bytes());
+            counter += 1;
+            let iv = random_bytes(&seed);
+            let mut out = iv.to_vec();
+            out.extend(Aes128CbcEnc::new(&key.into(), &iv.into()).encrypt_padded_vec_mut::<Pkcs7>(plain));
+            out
+        };
+
+        let (dict_end, stream) = match find_stream(data) {
+            Some((dict_end, start, end)) => (dict_end, Some(&data[start..end])),
+            None => (data.len(), None),
+        };
+        let mut out = encrypt_strings(&data[..dict_end], &mut encrypt);
+        if let Some(stream) = stream {
+            let encrypted = encrypt(stream);
+            out = replace_length(&out, encrypted.len());
+            out.extend_from_slice(b"\nstream\n");
+            out.extend_from_slice(&encrypted);
+            out.extend_from_slice(b"\nendstream");
+        }
+        out
+    }
+}
+
+/// オーナーパスワード未指定時のランダムなパスワード
+fn random_password(file_id: &[u8; 16]) -> String {
+    let mut seed = file_id.to_vec();
+    seed.extend_from_slice(b"owner");
+    to_hex(&random_bytes(&seed))
+}
+
+/// 16バイトの乱数（乱数が使えない環境では `seed` のMD5）
+fn random_bytes(seed: &[u8]) -> [u8; 16] {
+    let mut bytes = [0u8; 16];
+    if getrandom::fill(&mut bytes).is_err() {
+        bytes = md5(seed);
+    }
+    bytes
+}
+
+/// `stream` キーワードの位置から (辞書の終わり, データの始まり, データの終わり) を返す
+fn find_stream(data: &[u8]) -> Option<(usize, usize, usize)> {
+    let mut i = 0;
+    let mut depth = 0usize;
+    while i < data.len() {
+        match data[i] {
+            b'(' => i = skip_literal_string(data, i),
+            b'<' if data.get(i + 1) == Some(&b'<') => {
+                depth += 1;
+                i += 2;
+            }
+            b'>' if data.get(i + 1) == Some(&b'>') => {
+                depth = depth.saturating_sub(1);
+                i += 2;
+            }
+            b's' if depth == 0 && data[i..].starts_with(b"stream") => {
+                let mut start = i + b"stream".len();
+                if data.get(start) == Some(&b'\r') {
+                    start += 1;
+                }
+                if data.get(start) == Some(&b'\n') {
+                    start += 1;
+                }
+                let end = data.windows(b"endstream".len()).rposition(|w| w == b"endstream")?;
+                let mut data_end = end;
+                if data_end > start && data[data_end - 1] == b'\n' {
+                    data_end -= 1;
+                    if data_end > start && data[data_end - 1] == b'\r' {
+                        data_end -= 1;
+                    }
+                }
+                let dict_end = data[..i].iter().rposition(|b| !b.is_ascii_whitespace()).map_or(0, |p| p + 1);
+                return Some((dict_end, start, data_end.max(start)));
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// リテラル文字列 `(`〜`)` の次の位置（入れ子の括弧とエスケープを考慮）
+fn skip_literal_string(data: &[u8], start: usize) -> usize {
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < data.len() {
+        match data[i] {
+            b'\\' => i += 1,
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    data.len()
+}
+
+/// リテラル文字列のエスケープを解除したバイト列（括弧を除いた内容）
+fn unescape_literal(body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len());
+    let mut i = 0;
+    while i < body.len() {
+        if body[i] != b'\\' {
+            out.push(body[i]);
+            i += 1;
+            continue;
+        }
+        i += 1;
+        let Some(&c) = body.get(i) else { break };
+        match c {
+            b'n' => out.push(b'\n'),
+            b'r' => out.push(b'\r'),
+            b't' => out.push(b'\t'),
+            b'b' => out.push(0x08),
+            b'f' => out.push(0x0C),
+            b'0'..=b'7' => {
+                let digits = body[i..].iter().take(3).take_while(|d| (b'0'..=b'7').contains(d)).count();
+                let value = body[i..i + digits].iter().fold(0u32, |v, d| v * 8 + (d - b'0') as u32);
+                out.push(value as u8);
+                i += digits;
+                continue;
+            }
+            // 行末の '\' は改行を含めて無視
+            b'\n' => {}
+            b'\r' => {
+                if body.get(i + 1) == Some(&b'\n') {
+                    i += 1;
+                }
+            }
+            other => out.push(other),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// 辞書・配列内のリテラル文字列と16進文字列を暗号化した16進文字列に置き換える
+fn encrypt_strings(data: &[u8], encrypt: &mut impl FnMut(&[u8]) -> Vec<u8>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        match data[i] {
+            b'(' => {
+                let end = skip_literal_string(data, i);
+                let plain = unescape_literal(&data[i + 1..end.saturating_sub(1).max(i + 1)]);
+                out.push(b'<');
+                out.extend_from_slice(to_hex(&encrypt(&plain)).as_bytes());
+                out.push(b'>');
+                i = end;
+            }
+            b'<' if data.get(i + 1) == Some(&b'<') => {
+                out.extend_from_slice(b"<<");
+                i += 2;
+            }
+            b'<' => {
+                let end = data[i..].iter().position(|&b| b == b'>').map_or(data.len(), |p| i + p);
+                let plain = from_hex(&data[i + 1..end]);
+                out.push(b'<');
+                out.extend_from_slice(to_hex(&encrypt(&plain)).as_bytes());
+                out.push(b'>');
+                i = end + 1;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// ストリーム辞書の `/Length n`（`/Length1` 等は除く）を `length` に置き換える
+fn replace_length(dict: &[u8], length: usize) -> Vec<u8> {
+    let key = b"/Length";
+    let Some(pos) = dict
+        .windows(key.len() + 1)
+        .position(|w| w.starts_with(key) && w[key.len()].is_ascii_whitespace())
+    else {
+        return dict.to_vec();
+    };
+    let value_start = pos + key.len() + 1;
+    let value_end = dict[value_start..]
+        .iter()
+        .position(|b| !b.is_ascii_digit())
+        .map_or(dict.len(), |p| value_start + p);
+    let mut out = dict[..value_start].to_vec();
+    out.extend_from_slice(length.to_string().as_bytes());
+    out.extend_from_slice(&dict[value_end..]);
+    out
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// 16進文字列の内容をバイト列に変換（空白は無視し、奇数桁の場合は末尾に0を補う）
+fn from_hex(hex: &[u8]) -> Vec<u8> {
+    let digits: Vec<u8> = hex
+        .iter()
+        .filter_map(|&c| (c as char).to_digit(16).map(|d| d as u8))
+        .collect();
+    digits.chunks(2).map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0)).collect()
+}
+
+// ── 暗号プリミティブ ──
+
+/// MD5（鍵の導出とファイルID）
+pub(crate) fn md5(data: &[u8]) -> [u8; 16] {
+    Md5::digest(data).into()
+}
+
+/// RC4（/O・/U の計算にのみ使用）
+fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut s: Vec<u8> = (0..=255).collect();
+    let mut j = 0u8;
+    for i in 0..256 {
+        j = j.wrapping_add(s[i]).wrapping_add(key[i % key.len()]);
+        s.swap(i, j as usize);
+    }
+    let (mut i, mut j) = (0u8, 0u8);
+    data.iter()
+        .map(|&byte| {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(s[i as usize]);
+            s.swap(i as usize, j as usize);
+            byte ^ s[s[i as usize].wrapping_add(s[j as usize]) as usize]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rc4_and_permissions() {
+        assert_eq!(to_hex(&rc4(b"Key", b"Plaintext")), "BBF316E8D940AF0AD3");
+
+        // 閲覧のみは許可ビットがすべて0
+        assert_eq!(PdfPermissions::view_only().p_value(), -3904);
+        assert_eq!(PdfPermissions::default().p_value(), -4);
+    }
+
+    #[test]
+    fn test_encrypt_object_replaces_strings_and_stream_length() {
+        let handler = SecurityHandler::new(&PdfEncryption::view_only(), md5(b"id"));
+        let data = b"<< /Title (A \\(b\\)) /Length 5 /Length1 5 >>\nstream\nhello\nendstream";
+        let out = String::from_utf8_lossy(&handler.encrypt_object(7, data)).to_string();
+        assert!(!out.contains("(A"));
+        // 5バイト → IV16バイト + パディング後16バイト
+        assert!(out.contains("/Length 32 /Length1 5"), "{}", out);
+        assert!(out.ends_with("\nendstream"));
+        assert!(!out.contains("hello"));
+    }
+}
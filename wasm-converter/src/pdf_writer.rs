@@ -1,10 +1,164 @@
 // pdf_writer.rs - 軽量PDF生成エンジン
 //
-// 外部クレートに依存せず、PDF 1.4仕様に準拠したPDFバイト列を直接生成します。
+// PDF 1.4仕様に準拠したPDFバイト列を直接生成します（オブジェクトの組み立ては自前で行い、
+// 画像のデコード・圧縮とフォントの解析には外部クレートを使用します）。
 // 日本語テキスト（Unicode）をサポートします。
 
-use crate::converter::{Color, Document, FontStyle, GradientStop, GradientType, Page, PageElement, Table, TextAlign};
-use crate::font_manager::FontManager;
+use crate::converter::{
+    Color, ColorMode, Document, FitMode, FontStyle, Metadata, GradientStop, GradientType, MissingGlyphPolicy, Page, PageElement, Table,
+    ImageFit, PageNumberConfig, SearchHighlight, StrokeStyle, TextAlign, UnderlineKind, Watermark,
+    TABLE_CELL_PADDING, TABLE_LINE_SPACING, UNDERLINE_OFFSET,
+};
+use crate::font_manager::{
+    estimate_text_width, font_has_glyph, missing_glyph_advance, text_outline_commands, FontManager, SyntheticStyle, SYNTHETIC_ITALIC_SLANT,
+};
+use crate::pdf_encryption::{md5, PdfEncryption, SecurityHandler};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// PDF出力オプション
+/// JavaScript側からはJSON文字列として受け取り、未指定の項目はデフォルト値になります。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfOptions {
+    /// リニアライズ（Web表示用に最適化）されたPDFを出力する
+    /// 1ページ目の表示に必要なオブジェクトをファイル先頭付近に配置し、
+    /// ダウンロード完了前に最初のページを表示できるようにします。
+    pub linearize: bool,
+    /// 出力インテントとしてICCカラープロファイルを埋め込む
+    /// `color_profile` が未指定の場合は内蔵のsRGBプロファイルを使用します。
+    pub embed_color_profile: bool,
+    /// 埋め込むICCプロファイルのバイト列（指定時は `embed_color_profile` に関わらず埋め込み）
+    pub color_profile: Option<Vec<u8>>,
+    /// コンテンツの縦横比に合わせてページの向きを自動補正する（`Page::auto_oriented`）
+    pub auto_orient: bool,
+    /// フォントにグリフが無い文字の描画方法（`"Hide"` / `"Box"` / `{"Replace": "□"}`）
+    pub missing_glyph: MissingGlyphPolicy,
+    /// 半透明の要素を下地と合成して不透明色で出力する（`Page::flattened_transparency`）
+    /// アルファ合成に対応しない古いビューア向けです。
+    pub flatten_transparency: bool,
+    /// PNG画像も正規化する（写真と判定したPNGはベースラインJPEGに再エンコード）
+    /// CMYK・グレースケール・プログレッシブのJPEGはこの設定に関わらず常にRGBへ変換します。
+    pub normalize_images: bool,
+    /// ページからはみ出したテーブルを縮小する（`"None"` / `"FitWidth"` / `"FitPage"`、`Page::fitted`）
+    pub fit_mode: FitMode,
+    /// 各ページを画像レンダラーでラスタライズし、ページ全面の画像として埋め込む
+    /// 画像出力とピクセル単位で同じ見た目（フォントを含む）を保証したい場合に使用します。
+    /// テキストは選択・検索できなくなります。
+    pub rasterize_pages: bool,
+    /// ラスタライズ時の解像度（未指定時は150dpi、72〜600の範囲に制限）
+    pub raster_dpi: Option<f64>,
+    /// `rasterize_pages` 有効時、ページのテキストを不可視テキスト（描画モード `3 Tr`）として画像に重ねる
+    /// スキャン画像のような見た目のまま、テキストの検索・選択ができるPDFになります。
+    pub text_layer: bool,
+    /// 色モード（`"Color"` / `"Grayscale"` / `"BlackWhite"`、`Page::with_color_mode`）
+    /// グレースケール・白黒では塗り・線を DeviceGray で出力し、画像もグレーに変換して埋め込みます。
+    pub color_mode: ColorMode,
+    /// 各ページに重ねる透かし（テキストまたは画像、`Page::with_watermark`）
+    pub watermark: Option<Watermark>,
+    /// 各ページに付けるページ番号（`Page::with_page_number`）
+    pub page_numbers: Option<PageNumberConfig>,
+    /// 縦横比の異なる矩形への画像の収め方（`"Stretch"` / `"Contain"` / `"Cover"`、`Page::with_image_fit`）
+    pub image_fit: ImageFit,
+    /// 背景が指定されていないページの最背面に敷く背景色（`Page::with_default_background`）
+    /// 元のドキュメントで指定された背景は上書きしません。
+    pub default_page_background: Option<Color>,
+    /// 印刷用の裁ち落とし幅（pt）
+    /// 0より大きい場合、ページの大きさを仕上がり（`/TrimBox`）とし、その外側に `/BleedBox` を設けます。
+    pub bleed: f64,
+    /// 裁ち落としの外側にトンボ（仕上がり位置を示す細線）を描画する
+    pub crop_marks: bool,
+    /// 検索に一致したテキストに半透明のハイライトを敷く（`Page::with_search_highlights`）
+    pub search_highlight: Option<SearchHighlight>,
+    /// パスワードと許可フラグによる暗号化（標準セキュリティハンドラ、AES-128）
+    /// 暗号化する場合は `linearize` を指定してもリニアライズしません。
+    pub encryption: Option<PdfEncryption>,
+    /// 変換元のファイルを添付ファイル（`/EmbeddedFiles`）としてPDFに埋め込む（`render_to_pdf_with_source`）
+    pub attach_source: bool,
+    /// 太字・斜体のランを、輪郭の太らせ（描画モード2）と傾き（12°）で合成して描画する（既定: 有効）
+    pub synthesize_styles: bool,
+    /// テキストをフォントの輪郭（glyf / CFF）から作った塗りつぶしのパスで描画し、フォントを埋め込まない
+    /// ビューアのフォント置換に左右されず同じ見た目になりますが、テキストは選択・検索できず、
+    /// ファイルは大きくなります。使用できるフォントが無い場合は通常どおりテキストで出力します。
+    pub text_as_paths: bool,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            linearize: false,
+            embed_color_profile: false,
+            color_profile: None,
+            auto_orient: false,
+            missing_glyph: MissingGlyphPolicy::default(),
+            flatten_transparency: false,
+            normalize_images: false,
+            fit_mode: FitMode::default(),
+            rasterize_pages: false,
+            raster_dpi: None,
+            text_layer: false,
+            color_mode: ColorMode::default(),
+            watermark: None,
+            page_numbers: None,
+            image_fit: ImageFit::default(),
+            default_page_background: None,
+            bleed: 0.0,
+            crop_marks: false,
+            search_highlight: None,
+            encryption: None,
+            attach_source: false,
+            synthesize_styles: true,
+            text_as_paths: false,
+        }
+    }
+}
+
+/// `rasterize_pages` の既定の解像度
+const DEFAULT_RASTER_DPI: f64 = 150.0;
+
+/// トンボの線の長さ（pt）
+const CROP_MARK_LENGTH: f64 = 18.0;
+/// 裁ち落としの外端からトンボまでの間隔（pt）
+const CROP_MARK_OFFSET: f64 = 3.0;
+/// トンボの線幅（pt）
+const CROP_MARK_WIDTH: f64 = 0.25;
+
+/// `PdfOptions::protected` で透かしが未指定の場合に使う文字列
+pub const PROTECTED_WATERMARK_TEXT: &str = "CONFIDENTIAL";
+
+impl PdfOptions {
+    /// 配布用の保護設定を補ったオプション
+    /// 暗号化が未指定なら閲覧専用（`PdfEncryption::view_only`）、透かしが未指定なら
+    /// 「CONFIDENTIAL」のテキスト透かしを設定します。指定済みの項目はそのまま使います。
+    pub fn protected(mut self) -> Self {
+        self.encryption.get_or_insert_with(PdfEncryption::view_only);
+        self.watermark.get_or_insert_with(|| Watermark::text(PROTECTED_WATERMARK_TEXT));
+        self
+    }
+
+    /// 仕上がり（`/TrimBox`）の外側に確保する余白（裁ち落とし + トンボ）
+    fn print_margin(&self) -> f64 {
+        let bleed = self.bleed.max(0.0);
+        if self.crop_marks {
+            bleed + CROP_MARK_OFFSET + CROP_MARK_LENGTH
+        } else {
+            bleed
+        }
+    }
+
+    /// `/TrimBox` / `/BleedBox` を出力するか
+    fn has_print_boxes(&self) -> bool {
+        self.bleed > 0.0 || self.crop_marks
+    }
+    /// 埋め込み対象のICCプロファイルを解決
+    fn resolved_color_profile(&self) -> Option<Vec<u8>> {
+        match &self.color_profile {
+            Some(profile) if !profile.is_empty() => Some(profile.clone()),
+            _ if self.embed_color_profile => Some(crate::icc_profile::srgb_profile()),
+            _ => None,
+        }
+    }
+}
 
 /// ページ内の画像XObject情報
 struct PdfImageXObject {
@@ -14,6 +168,42 @@ struct PdfImageXObject {
     smask_id: Option<u32>, // Optional SMask object ID for alpha
 }
 
+/// PDFに添付するファイル
+pub struct PdfAttachment<'a> {
+    /// ファイル名（パスを含む場合は末尾の名前のみを使用）
+    pub filename: &'a str,
+    pub data: &'a [u8],
+}
+
+impl PdfAttachment<'_> {
+    /// パス区切りを除いたファイル名
+    fn display_name(&self) -> &str {
+        self.filename.rsplit(['/', '\\']).next().filter(|name| !name.is_empty()).unwrap_or("source")
+    }
+}
+
+/// 拡張子から添付ファイルのMIMEタイプを推定
+fn source_mime(filename: &str) -> &'static str {
+    let ext = filename.rsplit('.').next().unwrap_or("").to_lowercase();
+    match ext.as_str() {
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "odt" => "application/vnd.oasis.opendocument.text",
+        "ods" => "application/vnd.oasis.opendocument.spreadsheet",
+        "odp" => "application/vnd.oasis.opendocument.presentation",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "tsv" => "text/tab-separated-values",
+        "md" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
 /// PDFオブジェクト
 struct PdfObject {
     id: u32,
@@ -27,18 +217,43 @@ pub struct PdfWriter<'a> {
     next_id: u32,
     page_ids: Vec<u32>,
     font_manager: &'a FontManager,
+    options: PdfOptions,
+    /// RGB画像XObjectの色空間（ICCプロファイル埋め込み時は /ICCBased）
+    rgb_color_space: String,
+    /// 文書情報辞書（/Info）のオブジェクトID
+    info_id: Option<u32>,
+    /// 埋め込み対象のフォントデータ（グリフ有無の判定と文字幅の計測に使用）
+    font_data: Option<&'a [u8]>,
+    /// 添付ファイル（`with_attachment`）
+    attachment: Option<PdfAttachment<'a>>,
 }
 
 impl<'a> PdfWriter<'a> {
     pub fn new(font_manager: &'a FontManager) -> Self {
+        Self::with_options(font_manager, PdfOptions::default())
+    }
+
+    /// 出力オプションを指定してPDF生成器を作成
+    pub fn with_options(font_manager: &'a FontManager, options: PdfOptions) -> Self {
         Self {
             objects: Vec::new(),
             next_id: 1,
             page_ids: Vec::new(),
             font_manager,
+            options,
+            rgb_color_space: "/DeviceRGB".to_string(),
+            info_id: None,
+            font_data: None,
+            attachment: None,
         }
     }
 
+    /// ファイルを添付する（カタログの `/Names /EmbeddedFiles` から参照）
+    pub fn with_attachment(mut self, attachment: PdfAttachment<'a>) -> Self {
+        self.attachment = Some(attachment);
+        self
+    }
+
     fn alloc_id(&mut self) -> u32 {
         let id = self.next_id;
         self.next_id += 1;
@@ -52,9 +267,11 @@ impl<'a> PdfWriter<'a> {
     /// ドキュメントをPDFバイト列に変換
     pub fn render(&mut self, doc: &Document) -> Vec<u8> {
         // ab_glyphでパース可能なフォントデータを見つけてコピー
-        // CIDToGIDMapとフォント埋め込みの両方で同じフォントを使用することを保証
-        let usable_font_data: Option<Vec<u8>> = find_usable_font_data(self.font_manager);
+        // CIDToGIDMap・フォント埋め込み・透かしやページ番号の計測で同じフォントを使用することを保証
+        self.font_data = self.font_manager.usable_font_data();
+        let usable_font_data: Option<Vec<u8>> = self.font_data.map(|d| d.to_vec());
         let has_font = usable_font_data.is_some();
+        let outline_text = self.outlines_text();
 
         // IDを事前割り当て
         let catalog_id = self.alloc_id();
@@ -78,6 +295,14 @@ impl<'a> PdfWriter<'a> {
             None
         };
 
+        // ICCプロファイルと出力インテント用のID
+        let color_profile = self.options.resolved_color_profile();
+        let output_intent_ids = if color_profile.is_some() {
+            Some((self.alloc_id(), self.alloc_id()))
+        } else {
+            None
+        };
+
         // ページオブジェクトのID割り当て
         let mut page_content_pairs: Vec<(u32, u32)> = Vec::new();
         for _ in &doc.pages {
@@ -86,16 +311,93 @@ impl<'a> PdfWriter<'a> {
             page_content_pairs.push((page_id, content_id));
         }
 
+        // 添付ファイル（ファイル指定辞書と埋め込みファイルストリーム）
+        let attachment_ids = if self.attachment.is_some() {
+            Some((self.alloc_id(), self.alloc_id()))
+        } else {
+            None
+        };
+
         // カタログ
+        let output_intents = output_intent_ids
+            .map(|(_, intent_id)| format!(" /OutputIntents [{} 0 R]", intent_id))
+            .unwrap_or_default();
+        let names = match (attachment_ids, &self.attachment) {
+            (Some((filespec_id, _)), Some(attachment)) => format!(
+                " /Names << /EmbeddedFiles << /Names [{} {} 0 R] >> >> /AF [{} 0 R]",
+                pdf_text_string(attachment.display_name()),
+                filespec_id,
+                filespec_id
+            ),
+            _ => String::new(),
+        };
         self.add_object(
             catalog_id,
             format!(
-                "<< /Type /Catalog /Pages {} 0 R >>",
-                pages_id
+                "<< /Type /Catalog /Pages {} 0 R{}{} >>",
+                pages_id, output_intents, names
             )
             .into_bytes(),
         );
 
+        if let (Some((filespec_id, file_id)), Some(attachment)) = (attachment_ids, self.attachment.take()) {
+            let name = pdf_text_string(attachment.display_name());
+            self.add_object(
+                filespec_id,
+                format!(
+                    "<< /Type /Filespec /F {} /UF {} /Desc (Source document) /AFRelationship /Source \
+                     /EF << /F {} 0 R /UF {} 0 R >> >>",
+                    name, name, file_id, file_id
+                )
+                .into_bytes(),
+            );
+            // MIMEタイプの '/' は名前オブジェクト内で #2F にエスケープ
+            let mut file_data = format!(
+                "<< /Type /EmbeddedFile /Subtype /{} /Params << /Size {} >> /Length {} >>\nstream\n",
+                source_mime(attachment.filename).replace('/', "#2F"),
+                attachment.data.len(),
+                attachment.data.len()
+            )
+            .into_bytes();
+            file_data.extend_from_slice(attachment.data);
+            file_data.extend_from_slice(b"\nendstream");
+            self.add_object(file_id, file_data);
+        }
+
+        // ICCプロファイルストリームと出力インテント
+        if let (Some((icc_id, intent_id)), Some(profile)) = (output_intent_ids, &color_profile) {
+            let components = crate::icc_profile::profile_components(profile).unwrap_or(3);
+            let mut icc_data = format!(
+                "<< /N {} /Length {} >>\nstream\n",
+                components,
+                profile.len()
+            )
+            .into_bytes();
+            icc_data.extend_from_slice(profile);
+            icc_data.extend_from_slice(b"\nendstream");
+            self.add_object(icc_id, icc_data);
+
+            let condition = match &self.options.color_profile {
+                Some(p) if !p.is_empty() => "Custom",
+                _ => "sRGB IEC61966-2.1",
+            };
+            self.add_object(
+                intent_id,
+                format!(
+                    "<< /Type /OutputIntent /S /GTS_PDFX \
+                     /OutputConditionIdentifier ({}) /Info ({}) \
+                     /DestOutputProfile {} 0 R >>",
+                    condition, condition, icc_id
+                )
+                .into_bytes(),
+            );
+
+            // RGBプロファイルの場合のみ画像の色空間をICCBasedにする
+            if components == 3 {
+                self.rgb_color_space = format!("[/ICCBased {} 0 R]", icc_id);
+            }
+        }
+
         // ページツリー
         let page_refs: Vec<String> = page_content_pairs
             .iter()
@@ -111,106 +413,222 @@ impl<'a> PdfWriter<'a> {
             .into_bytes(),
         );
 
-        // ToUnicode CMap（日本語テキスト用）
-        let tounicode_stream = self.create_tounicode_cmap();
-        let tounicode_compressed = tounicode_stream.as_bytes().to_vec();
-        self.add_object(
-            tounicode_id,
-            format!(
-                "<< /Length {} >>\nstream\n{}\nendstream",
-                tounicode_compressed.len(),
-                tounicode_stream
-            )
-            .into_bytes(),
-        );
-
-        // CIDToGIDMapストリームを生成（フォントのcmapテーブルに基づく）
-        // usable_font_dataと同じデータを使用してマッピングの一致を保証
-        {
-            let cid_to_gid_data = Self::build_cid_to_gid_map_from_data(usable_font_data.as_deref());
-            let mut stream_data = format!(
-                "<< /Length {} >>\nstream\n",
-                cid_to_gid_data.len()
-            ).into_bytes();
-            stream_data.extend_from_slice(&cid_to_gid_data);
-            stream_data.extend_from_slice(b"\nendstream");
-            self.add_object(cid_to_gid_map_id, stream_data);
-        }
-
-        // CIDFont
-        let mut cid_font_dict = format!(
-            "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /NotoSansJP \
-             /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> \
-             /DW 1000 \
-             /FontDescriptor {} 0 R \
-             /CIDToGIDMap {} 0 R",
-            descriptor_id, cid_to_gid_map_id
-        );
-        cid_font_dict.push_str(" >>");
-        self.add_object(cid_font_id, cid_font_dict.into_bytes());
-
-        // フォントディスクリプタ
-        let mut desc = format!(
-            "<< /Type /FontDescriptor /FontName /NotoSansJP \
-             /Flags 4 /ItalicAngle 0 /Ascent 880 /Descent -120 \
-             /CapHeight 733 /StemV 80 \
-             /FontBBox [-200 -200 1200 1000]"
-        );
-        if let Some(ff_id) = font_file_id {
-            desc.push_str(&format!(" /FontFile2 {} 0 R", ff_id));
-        }
-        desc.push_str(" >>");
-        self.add_object(descriptor_id, desc.into_bytes());
+        // フォント関連のオブジェクト（テキストをパスで描画する場合は埋め込まない。
+        // 割り当て済みのIDはxrefで未使用のエントリになります）
+        if !outline_text {
+            // ToUnicode CMap（日本語テキスト用）
+            let tounicode_stream = self.create_tounicode_cmap();
+            let tounicode_compressed = tounicode_stream.as_bytes().to_vec();
+            self.add_object(
+                tounicode_id,
+                format!(
+                    "<< /Length {} >>\nstream\n{}\nendstream",
+                    tounicode_compressed.len(),
+                    tounicode_stream
+                )
+                .into_bytes(),
+            );
 
-        // Type0フォント（複合フォント）- /F1
-        self.add_object(
-            font_id,
-            format!(
-                "<< /Type /Font /Subtype /Type0 /BaseFont /NotoSansJP \
-                 /Encoding /Identity-H \
-                 /DescendantFonts [{} 0 R] \
-                 /ToUnicode {} 0 R >>",
-                cid_font_id, tounicode_id
-            )
-            .into_bytes(),
-        );
+            // CIDToGIDMapストリームを生成（フォントのcmapテーブルに基づく）
+            // usable_font_dataと同じデータを使用してマッピングの一致を保証
+            {
+                let cid_to_gid_data = Self::build_cid_to_gid_map_from_data(usable_font_data.as_deref());
+                let mut stream_data = format!(
+                    "<< /Length {} >>\nstream\n",
+                    cid_to_gid_data.len()
+                ).into_bytes();
+                stream_data.extend_from_slice(&cid_to_gid_data);
+                stream_data.extend_from_slice(b"\nendstream");
+                self.add_object(cid_to_gid_map_id, stream_data);
+            }
 
-        // 標準フォント（Helvetica）- /F2: フォント未埋め込み時のフォールバック
-        self.add_object(
-            fallback_font_id,
-            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
-                .to_vec(),
-        );
+            // CIDFont
+            let mut cid_font_dict = format!(
+                "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /NotoSansJP \
+                 /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> \
+                 /DW 1000 \
+                 /FontDescriptor {} 0 R \
+                 /CIDToGIDMap {} 0 R",
+                descriptor_id, cid_to_gid_map_id
+            );
+            cid_font_dict.push_str(" >>");
+            self.add_object(cid_font_id, cid_font_dict.into_bytes());
+
+            // フォントディスクリプタ
+            let mut desc = format!(
+                "<< /Type /FontDescriptor /FontName /NotoSansJP \
+                 /Flags 4 /ItalicAngle 0 /Ascent 880 /Descent -120 \
+                 /CapHeight 733 /StemV 80 \
+                 /FontBBox [-200 -200 1200 1000]"
+            );
+            if let Some(ff_id) = font_file_id {
+                desc.push_str(&format!(" /FontFile2 {} 0 R", ff_id));
+            }
+            desc.push_str(" >>");
+            self.add_object(descriptor_id, desc.into_bytes());
 
-        // フォントファイルの埋め込み（CIDToGIDMapと同じusable_font_dataを使用）
-        if let (Some(ff_id), Some(ref font_data)) =
-            (font_file_id, &usable_font_data)
-        {
+            // Type0フォント（複合フォント）- /F1
             self.add_object(
-                ff_id,
+                font_id,
                 format!(
-                    "<< /Length {} /Length1 {} >>\nstream\n",
-                    font_data.len(),
-                    font_data.len()
+                    "<< /Type /Font /Subtype /Type0 /BaseFont /NotoSansJP \
+                     /Encoding /Identity-H \
+                     /DescendantFonts [{} 0 R] \
+                     /ToUnicode {} 0 R >>",
+                    cid_font_id, tounicode_id
                 )
                 .into_bytes(),
             );
-            // フォントストリームは特別処理（後でバイナリデータを追加）
-            if let Some(obj) = self.objects.last_mut() {
-                obj.data.extend_from_slice(font_data);
-                obj.data.extend_from_slice(b"\nendstream");
+
+            // 標準フォント（Helvetica）- /F2: フォント未埋め込み時のフォールバック
+            self.add_object(
+                fallback_font_id,
+                b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                    .to_vec(),
+            );
+
+            // フォントファイルの埋め込み（CIDToGIDMapと同じusable_font_dataを使用）
+            if let (Some(ff_id), Some(ref font_data)) =
+                (font_file_id, &usable_font_data)
+            {
+                self.add_object(
+                    ff_id,
+                    format!(
+                        "<< /Length {} /Length1 {} >>\nstream\n",
+                        font_data.len(),
+                        font_data.len()
+                    )
+                    .into_bytes(),
+                );
+                // フォントストリームは特別処理（後でバイナリデータを追加）
+                if let Some(obj) = self.objects.last_mut() {
+                    obj.data.extend_from_slice(font_data);
+                    obj.data.extend_from_slice(b"\nendstream");
+                }
             }
         }
 
+        // 文書情報辞書
+        let info_id = self.alloc_id();
+        self.add_object(info_id, build_info_dict(&doc.metadata).into_bytes());
+        self.info_id = Some(info_id);
+
+        // 検索パターンは全ページで共通（不正なパターンは `Converter` 側でエラーにする）
+        let search = self.options.search_highlight.clone().and_then(|h| h.pattern().ok().map(|pattern| (h, pattern)));
+
         // 各ページ
         for (i, page) in doc.pages.iter().enumerate() {
             let (page_id, content_id) = page_content_pairs[i];
 
+            let oriented;
+            let page = match self.options.auto_orient.then(|| page.auto_oriented()).flatten() {
+                Some(p) => {
+                    oriented = p;
+                    &oriented
+                }
+                None => page,
+            };
+
+            let fitted;
+            let page = match page.fitted(self.options.fit_mode) {
+                Some(p) => {
+                    fitted = p;
+                    &fitted
+                }
+                None => page,
+            };
+
+            let refitted;
+            let page = match page.with_image_fit(self.options.image_fit) {
+                Some(p) => {
+                    refitted = p;
+                    &refitted
+                }
+                None => page,
+            };
+
+            let highlighted;
+            let page = match search.as_ref().and_then(|(h, pattern)| page.with_search_highlights(h, pattern, self.font_data)) {
+                Some(p) => {
+                    highlighted = p;
+                    &highlighted
+                }
+                None => page,
+            };
+
+            let backed;
+            let page = match self.options.default_page_background.and_then(|c| page.with_default_background(c)) {
+                Some(p) => {
+                    backed = p;
+                    &backed
+                }
+                None => page,
+            };
+
+            let watermarked;
+            let page = match &self.options.watermark {
+                Some(watermark) => {
+                    watermarked = page.with_watermark(watermark, self.font_data);
+                    &watermarked
+                }
+                None => page,
+            };
+
+            let numbered;
+            let page = match &self.options.page_numbers {
+                Some(config) => {
+                    numbered = page.with_page_number(config, i + 1, doc.pages.len(), self.font_data);
+                    &numbered
+                }
+                None => page,
+            };
+
+            let flattened;
+            let page = match self
+                .options
+                .flatten_transparency
+                .then(|| page.flattened_transparency(self.options.default_page_background.unwrap_or(Color::WHITE)))
+                .flatten()
+            {
+                Some(p) => {
+                    flattened = p;
+                    &flattened
+                }
+                None => page,
+            };
+
+            let converted;
+            let page = match page.with_color_mode(self.options.color_mode) {
+                Some(p) => {
+                    converted = p;
+                    &converted
+                }
+                None => page,
+            };
+
+            let vector_page = page;
+            let rasterized;
+            let page = match self.options.rasterize_pages.then(|| self.rasterized_page(page)) {
+                Some(p) => {
+                    rasterized = p;
+                    &rasterized
+                }
+                None => page,
+            };
+
             // ページ内の画像を収集してXObjectを作成
             let image_xobjects = self.create_page_image_xobjects(page);
 
             // ページコンテンツストリーム（画像参照付き）
-            let content = self.render_page_content(page, has_font, &image_xobjects);
+            let mut content = self.render_page_content(page, has_font, &image_xobjects);
+            // テキストレイヤーはフォントを必要とするため、テキストをパスで描画する場合は付けない
+            if self.options.rasterize_pages && self.options.text_layer && !outline_text {
+                self.render_invisible_text(&mut content, vector_page, has_font);
+            }
+            let margin = self.options.print_margin();
+            if self.options.has_print_boxes() {
+                content = self.with_print_marks(content, page, margin);
+            }
             self.add_object(
                 content_id,
                 format!(
@@ -231,32 +649,185 @@ impl<'a> PdfWriter<'a> {
                 format!(" /XObject << {} >>", refs.join(" "))
             };
 
+            // 変換グループの不透明度ごとのグラフィックス状態リソース
+            let mut opacities = Vec::new();
+            collect_group_opacities(&page.elements, &mut opacities);
+            let gs_dict = if opacities.is_empty() {
+                String::new()
+            } else {
+                let entries: Vec<String> = opacities
+                    .iter()
+                    .map(|&o| format!("/{} << /ca {:.3} /CA {:.3} >>", opacity_gs_name(o), o, o))
+                    .collect();
+                format!(" /ExtGState << {} >>", entries.join(" "))
+            };
+
+            // 裁ち落とし・トンボの指定があれば、ページの大きさを仕上がりとしてその外側まで用紙を広げる
+            let print_boxes = if self.options.has_print_boxes() {
+                let bleed = self.options.bleed.max(0.0);
+                format!(
+                    " /TrimBox [{} {} {} {}] /BleedBox [{} {} {} {}]",
+                    margin, margin, margin + page.width, margin + page.height,
+                    margin - bleed, margin - bleed, margin + page.width + bleed, margin + page.height + bleed
+                )
+            } else {
+                String::new()
+            };
+
             // ページオブジェクト（/F1: CIDフォント, /F2: Helveticaフォールバック + XObject）
+            let font_dict = if outline_text {
+                String::new()
+            } else {
+                format!(" /Font << /F1 {} 0 R /F2 {} 0 R >>", font_id, fallback_font_id)
+            };
             self.add_object(
                 page_id,
                 format!(
                     "<< /Type /Page /Parent {} 0 R \
-                     /MediaBox [0 0 {} {}] \
+                     /MediaBox [0 0 {} {}]{} \
                      /Contents {} 0 R \
-                     /Resources << /Font << /F1 {} 0 R /F2 {} 0 R >>{} >> >>",
-                    pages_id, page.width, page.height, content_id, font_id,
-                    fallback_font_id, xobj_dict
+                     /Resources <<{}{}{} >> >>",
+                    pages_id, page.width + margin * 2.0, page.height + margin * 2.0, print_boxes, content_id,
+                    font_dict, xobj_dict, gs_dict
                 )
                 .into_bytes(),
             );
             self.page_ids.push(page_id);
         }
 
-        // PDF出力
-        self.serialize(catalog_id)
+        // PDF出力（暗号化する場合はリニアライズしない）
+        if let Some(encryption) = self.options.encryption.clone() {
+            self.serialize_encrypted(catalog_id, &encryption)
+        } else if self.options.linearize {
+            self.serialize_linearized(catalog_id, pages_id)
+        } else {
+            self.serialize(catalog_id)
+        }
+    }
+
+    /// ページの内容を仕上がりの位置（用紙の端から `margin`）へ移動し、指定があればトンボを描き足す
+    /// ページからはみ出した要素は裁ち落とし（`/BleedBox`）の範囲まで描画します。
+    /// トンボは四隅の縦横それぞれに、裁ち落としの外側から仕上がり線の延長上に引きます。
+    fn with_print_marks(&self, content: Vec<u8>, page: &Page, margin: f64) -> Vec<u8> {
+        let bleed = self.options.bleed.max(0.0);
+        let mut out = format!(
+            "q\n1 0 0 1 {} {} cm\n{} {} {} {} re W n\n",
+            margin, margin, -bleed, -bleed, page.width + bleed * 2.0, page.height + bleed * 2.0
+        )
+        .into_bytes();
+        out.extend_from_slice(&content);
+        out.extend_from_slice(b"\nQ\n");
+        if self.options.crop_marks {
+            let start = bleed + CROP_MARK_OFFSET;
+            let end = start + CROP_MARK_LENGTH;
+            let (x0, y0, x1, y1) = (margin, margin, margin + page.width, margin + page.height);
+            out.extend_from_slice(format!("q\n0 G\n{} w\n", CROP_MARK_WIDTH).as_bytes());
+            for (x, dx) in [(x0, -1.0), (x1, 1.0)] {
+                for (y, dy) in [(y0, -1.0), (y1, 1.0)] {
+                    // 横線（仕上がりの上下端の延長）と縦線（左右端の延長）
+                    out.extend_from_slice(
+                        format!(
+                            "{} {} m {} {} l S\n{} {} m {} {} l S\n",
+                            x + dx * start, y, x + dx * end, y,
+                            x, y + dy * start, x, y + dy * end
+                        )
+                        .as_bytes(),
+                    );
+                }
+            }
+            out.extend_from_slice(b"Q\n");
+        }
+        out
+    }
+
+    /// ページを画像レンダラーで描画し、ページ全面の画像1枚だけを持つページに置き換える
+    fn rasterized_page(&self, page: &Page) -> Page {
+        let config = crate::image_renderer::ImageRenderConfig {
+            dpi: self
+                .options
+                .raster_dpi
+                .unwrap_or(DEFAULT_RASTER_DPI)
+                .clamp(crate::image_renderer::MIN_DPI, crate::image_renderer::MAX_DPI),
+            missing_glyph: self.options.missing_glyph,
+            color_mode: self.options.color_mode,
+            synthesize_styles: self.options.synthesize_styles,
+            ..Default::default()
+        };
+        let png = crate::image_renderer::render_page_to_image(page, &config, self.font_manager);
+        Page {
+            width: page.width,
+            height: page.height,
+            elements: vec![PageElement::Image {
+                x: 0.0,
+                y: 0.0,
+                width: page.width,
+                height: page.height,
+                data: png,
+                mime_type: "image/png".to_string(),
+            }],
+        }
+    }
+
+    /// ページのテキスト要素を不可視テキスト（描画モード3）として出力
+    /// ラスタライズした画像の上に重ね、検索・選択用のテキストレイヤーにします。
+    fn render_invisible_text(&self, stream: &mut Vec<u8>, page: &Page, has_font: bool) {
+        for element in page.leaf_elements() {
+            let PageElement::Text { x, y, text, style, .. } = element else { continue };
+            let clean_text: String = text
+                .chars()
+                .map(|c| if matches!(c, '\n' | '\r' | '\t') { ' ' } else { c })
+                .filter(|c| !c.is_control())
+                .collect();
+            if clean_text.trim().is_empty() {
+                continue;
+            }
+            let (font_size, top_y) = style.script_metrics(*y);
+            let pdf_y = page.height - top_y - font_size;
+            let (font, shown) = if has_font {
+                ("/F1", format!("<{}>", self.text_to_pdf_hex(&clean_text)))
+            } else {
+                ("/F2", format!("({})", pdf_escape_string(&text_to_winansi(&clean_text))))
+            };
+            stream.extend_from_slice(
+                format!("BT\n3 Tr\n{} {} Tf\n{} {} Td\n{} Tj\nET\n", font, font_size, x, pdf_y, shown)
+                    .as_bytes(),
+            );
+        }
     }
 
     /// ページコンテンツのPDFストリームを生成
     fn render_page_content(&self, page: &Page, has_font: bool, image_xobjects: &[PdfImageXObject]) -> Vec<u8> {
         let mut stream = Vec::new();
         let mut img_idx = 0usize; // 画像XObjectカウンター
+        self.render_elements(&mut stream, &page.elements, page.height, has_font, image_xobjects, &mut img_idx);
+        stream
+    }
 
-        for element in &page.elements {
+    /// 要素を描画順にPDFストリームへ出力（クリップグループは再帰的に出力）
+    /// `img_idx` は `create_page_image_xobjects` が作成した画像XObjectの通し番号です。
+    fn render_elements(
+        &self,
+        stream: &mut Vec<u8>,
+        elements: &[PageElement],
+        page_height: f64,
+        has_font: bool,
+        image_xobjects: &[PdfImageXObject],
+        img_idx: &mut usize,
+    ) {
+        for element in elements {
+            // 破線・線端・角の指定がある線は描画状態を保存して設定し、要素の描画後に戻す
+            let stroke_style = element.stroke_style().filter(|s| !s.is_default());
+            if let Some(style) = stroke_style {
+                let width = match element {
+                    PageElement::Line { width, .. } => *width,
+                    PageElement::Rect { stroke_width, .. }
+                    | PageElement::Ellipse { stroke_width, .. }
+                    | PageElement::Path { stroke_width, .. } => *stroke_width,
+                    _ => 0.0,
+                };
+                stream.extend_from_slice(b"q\n");
+                stream.extend_from_slice(pdf_stroke_style_operators(style, width).as_bytes());
+            }
             match element {
                 PageElement::Text {
                     x,
@@ -266,7 +837,7 @@ impl<'a> PdfWriter<'a> {
                     style,
                     align,
                 } => {
-                    self.render_text(&mut stream, *x, *y, *width, text, style, *align, page.height, has_font);
+                    self.render_text(stream, *x, *y, *width, text, style, *align, page_height, has_font);
                 }
                 PageElement::Line {
                     x1,
@@ -275,16 +846,15 @@ impl<'a> PdfWriter<'a> {
                     y2,
                     width,
                     color,
+                    stroke_style: _,
                 } => {
-                    let py1 = page.height - y1;
-                    let py2 = page.height - y2;
+                    let py1 = page_height - y1;
+                    let py2 = page_height - y2;
                     stream.extend_from_slice(
                         format!(
-                            "{} {} {} RG\n{} w\n{} {} m\n{} {} l\nS\n",
-                            color.r as f64 / 255.0,
-                            color.g as f64 / 255.0,
-                            color.b as f64 / 255.0,
-                            width,
+                            "{}\n{} w\n{} {} m\n{} {} l\nS\n",
+                            self.color_operator(color, "RG"),
+                            pdf_line_width(*width),
                             x1,
                             py1,
                             x2,
@@ -302,22 +872,21 @@ impl<'a> PdfWriter<'a> {
                     stroke,
                     stroke_width,
                     rotation_deg,
+                    stroke_style: _,
                 } => {
-                    let py = page.height - y - height;
+                    let py = page_height - y - height;
                     let has_rotation = *rotation_deg != 0.0;
                     if has_rotation {
                         let cx_pdf = x + width / 2.0;
-                        let cy_pdf = page.height - y - height / 2.0;
+                        let cy_pdf = page_height - y - height / 2.0;
                         stream.extend_from_slice(b"q\n");
-                        Self::write_rotation_transform(&mut stream, cx_pdf, cy_pdf, *rotation_deg);
+                        Self::write_rotation_transform(stream, cx_pdf, cy_pdf, *rotation_deg);
                     }
                     if let Some(fill_color) = fill {
                         stream.extend_from_slice(
                             format!(
-                                "{} {} {} rg\n{} {} {} {} re\nf\n",
-                                fill_color.r as f64 / 255.0,
-                                fill_color.g as f64 / 255.0,
-                                fill_color.b as f64 / 255.0,
+                                "{}\n{} {} {} {} re\nf\n",
+                                self.color_operator(fill_color, "rg"),
                                 x,
                                 py,
                                 width,
@@ -329,11 +898,9 @@ impl<'a> PdfWriter<'a> {
                     if let Some(stroke_color) = stroke {
                         stream.extend_from_slice(
                             format!(
-                                "{} {} {} RG\n{} w\n{} {} {} {} re\nS\n",
-                                stroke_color.r as f64 / 255.0,
-                                stroke_color.g as f64 / 255.0,
-                                stroke_color.b as f64 / 255.0,
-                                stroke_width,
+                                "{}\n{} w\n{} {} {} {} re\nS\n",
+                                self.color_operator(stroke_color, "RG"),
+                                pdf_line_width(*stroke_width),
                                 x,
                                 py,
                                 width,
@@ -354,8 +921,8 @@ impl<'a> PdfWriter<'a> {
                     ..
                 } => {
                     // 画像XObjectを配置
-                    if let Some(xobj) = image_xobjects.get(img_idx) {
-                        let py = page.height - img_y - img_h;
+                    if let Some(xobj) = image_xobjects.get(*img_idx) {
+                        let py = page_height - img_y - img_h;
                         stream.extend_from_slice(
                             format!(
                                 "q\n{} 0 0 {} {} {} cm\n/{} Do\nQ\n",
@@ -364,7 +931,7 @@ impl<'a> PdfWriter<'a> {
                             .as_bytes(),
                         );
                     }
-                    img_idx += 1;
+                    *img_idx += 1;
                 }
                 PageElement::GradientRect {
                     x,
@@ -377,13 +944,13 @@ impl<'a> PdfWriter<'a> {
                 } => {
                     if *rotation_deg != 0.0 {
                         let cx_pdf = x + w / 2.0;
-                        let cy_pdf = page.height - y - h / 2.0;
+                        let cy_pdf = page_height - y - h / 2.0;
                         stream.extend_from_slice(b"q\n");
-                        Self::write_rotation_transform(&mut stream, cx_pdf, cy_pdf, *rotation_deg);
+                        Self::write_rotation_transform(stream, cx_pdf, cy_pdf, *rotation_deg);
                     }
                     // Approximate gradient with multiple thin strips
                     self.render_gradient_rect(
-                        &mut stream, *x, *y, *w, *h, stops, gradient_type, page.height,
+                        stream, *x, *y, *w, *h, stops, gradient_type, page_height,
                     );
                     if *rotation_deg != 0.0 {
                         stream.extend_from_slice(b"Q\n");
@@ -398,15 +965,16 @@ impl<'a> PdfWriter<'a> {
                     stroke,
                     stroke_width,
                     rotation_deg,
+                    stroke_style: _,
                 } => {
                     if *rotation_deg != 0.0 {
-                        let cy_pdf = page.height - cy;
+                        let cy_pdf = page_height - cy;
                         stream.extend_from_slice(b"q\n");
-                        Self::write_rotation_transform(&mut stream, *cx, cy_pdf, *rotation_deg);
+                        Self::write_rotation_transform(stream, *cx, cy_pdf, *rotation_deg);
                     }
                     self.render_ellipse(
-                        &mut stream, *cx, *cy, *rx, *ry, fill, stroke, *stroke_width,
-                        page.height,
+                        stream, *cx, *cy, *rx, *ry, fill, stroke, *stroke_width,
+                        page_height,
                     );
                     if *rotation_deg != 0.0 {
                         stream.extend_from_slice(b"Q\n");
@@ -429,12 +997,12 @@ impl<'a> PdfWriter<'a> {
                     let img_w = *rx * 2.0;
                     let img_h = *ry * 2.0;
                     if *rotation_deg != 0.0 {
-                        let cy_pdf = page.height - cy;
+                        let cy_pdf = page_height - cy;
                         stream.extend_from_slice(b"q\n");
-                        Self::write_rotation_transform(&mut stream, *cx, cy_pdf, *rotation_deg);
+                        Self::write_rotation_transform(stream, *cx, cy_pdf, *rotation_deg);
                     }
-                    if let Some(xobj) = image_xobjects.get(img_idx) {
-                        let py = page.height - img_y - img_h;
+                    if let Some(xobj) = image_xobjects.get(*img_idx) {
+                        let py = page_height - img_y - img_h;
                         stream.extend_from_slice(
                             format!(
                                 "q\n{} 0 0 {} {} {} cm\n/{} Do\nQ\n",
@@ -443,12 +1011,12 @@ impl<'a> PdfWriter<'a> {
                             .as_bytes(),
                         );
                     }
-                    img_idx += 1;
+                    *img_idx += 1;
                     // Draw ellipse outline if stroke is specified
-                    if stroke.is_some() && *stroke_width > 0.0 {
+                    if stroke.is_some() {
                         self.render_ellipse(
-                            &mut stream, *cx, *cy, *rx, *ry, &None, stroke, *stroke_width,
-                            page.height,
+                            stream, *cx, *cy, *rx, *ry, &None, stroke, *stroke_width,
+                            page_height,
                         );
                     }
                     if *rotation_deg != 0.0 {
@@ -461,7 +1029,7 @@ impl<'a> PdfWriter<'a> {
                     width,
                     table,
                 } => {
-                    self.render_table(&mut stream, *x, *y, *width, table, page.height, has_font);
+                    self.render_table(stream, *x, *y, *width, table, page_height, has_font);
                 }
                 PageElement::Path {
                     commands,
@@ -469,20 +1037,54 @@ impl<'a> PdfWriter<'a> {
                     stroke,
                     stroke_width,
                     rotation_deg,
+                    stroke_style: _,
                 } => {
                     if *rotation_deg != 0.0 {
                         // Compute bounding box center for rotation
-                        let (cx_center, cy_center) = path_bbox_center(commands, page.height);
+                        let (cx_center, cy_center) = path_bbox_center(commands, page_height);
                         stream.extend_from_slice(b"q\n");
-                        Self::write_rotation_transform(&mut stream, cx_center, cy_center, *rotation_deg);
+                        Self::write_rotation_transform(stream, cx_center, cy_center, *rotation_deg);
                     }
                     self.render_path(
-                        &mut stream, commands, fill, stroke, *stroke_width, page.height,
+                        stream, commands, fill, stroke, *stroke_width, page_height,
                     );
                     if *rotation_deg != 0.0 {
                         stream.extend_from_slice(b"Q\n");
                     }
                 }
+                PageElement::GradientPath {
+                    commands,
+                    stops,
+                    gradient_type,
+                    stroke,
+                    stroke_width,
+                    rotation_deg,
+                } => {
+                    if *rotation_deg != 0.0 {
+                        let (cx_center, cy_center) = path_bbox_center(commands, page_height);
+                        stream.extend_from_slice(b"q\n");
+                        Self::write_rotation_transform(stream, cx_center, cy_center, *rotation_deg);
+                    }
+                    // パスでクリップし、バウンディングボックスにグラデーションを描画
+                    if let Some((min_x, min_y, max_x, max_y)) = element.bounds() {
+                        stream.extend_from_slice(b"q\n");
+                        stream.extend_from_slice(pdf_path_operators(commands, page_height).as_bytes());
+                        stream.extend_from_slice(b"W* n\n");
+                        self.render_gradient_rect(
+                            stream, min_x, min_y, max_x - min_x, max_y - min_y, stops, gradient_type,
+                            page_height,
+                        );
+                        stream.extend_from_slice(b"Q\n");
+                    }
+                    if stroke.is_some() {
+                        self.render_path(
+                            stream, commands, &None, stroke, *stroke_width, page_height,
+                        );
+                    }
+                    if *rotation_deg != 0.0 {
+                        stream.extend_from_slice(b"Q\n");
+                    }
+                }
                 PageElement::PathImage {
                     commands,
                     data: _,
@@ -529,16 +1131,16 @@ impl<'a> PdfWriter<'a> {
                     }
 
                     if *rotation_deg != 0.0 {
-                        let (cx_center, cy_center) = path_bbox_center(commands, page.height);
+                        let (cx_center, cy_center) = path_bbox_center(commands, page_height);
                         stream.extend_from_slice(b"q\n");
-                        Self::write_rotation_transform(&mut stream, cx_center, cy_center, *rotation_deg);
+                        Self::write_rotation_transform(stream, cx_center, cy_center, *rotation_deg);
                     }
 
                     if min_x < max_x && min_y < max_y {
-                        if let Some(xobj) = image_xobjects.get(img_idx) {
+                        if let Some(xobj) = image_xobjects.get(*img_idx) {
                             let img_w = max_x - min_x;
                             let img_h = max_y - min_y;
-                            let py = page.height - min_y - img_h;
+                            let py = page_height - min_y - img_h;
                             stream.extend_from_slice(
                                 format!(
                                     "q\n{} 0 0 {} {} {} cm\n/{} Do\nQ\n",
@@ -548,22 +1150,50 @@ impl<'a> PdfWriter<'a> {
                             );
                         }
                     }
-                    img_idx += 1;
+                    *img_idx += 1;
 
                     // Render the path stroke if specified
-                    if stroke.is_some() && *stroke_width > 0.0 {
+                    if stroke.is_some() {
                         self.render_path(
-                            &mut stream, commands, &None, stroke, *stroke_width, page.height,
+                            stream, commands, &None, stroke, *stroke_width, page_height,
                         );
                     }
                     if *rotation_deg != 0.0 {
                         stream.extend_from_slice(b"Q\n");
                     }
                 }
+                PageElement::ClipGroup { clip_path, children } => {
+                    // クリップ領域を設定して子要素を描画（矩形は re、それ以外は偶奇規則のパス）
+                    stream.extend_from_slice(b"q\n");
+                    match crate::converter::PathCommand::axis_aligned_rect(clip_path) {
+                        Some((x0, y0, x1, y1)) => stream.extend_from_slice(
+                            format!("{} {} {} {} re W n\n", x0, page_height - y1, x1 - x0, y1 - y0).as_bytes(),
+                        ),
+                        None => {
+                            stream.extend_from_slice(pdf_path_operators(clip_path, page_height).as_bytes());
+                            stream.extend_from_slice(b"W* n\n");
+                        }
+                    }
+                    self.render_elements(stream, children, page_height, has_font, image_xobjects, img_idx);
+                    stream.extend_from_slice(b"Q\n");
+                }
+                PageElement::TransformGroup { cx, cy, rotation_deg, opacity, children } => {
+                    // 不透明度は子要素ごとに適用される（重なった子要素どうしは透けて見える）
+                    stream.extend_from_slice(b"q\n");
+                    if rounded_opacity(*opacity) < 1.0 {
+                        stream.extend_from_slice(format!("/{} gs\n", opacity_gs_name(*opacity)).as_bytes());
+                    }
+                    if *rotation_deg != 0.0 {
+                        Self::write_rotation_transform(stream, *cx, page_height - cy, *rotation_deg);
+                    }
+                    self.render_elements(stream, children, page_height, has_font, image_xobjects, img_idx);
+                    stream.extend_from_slice(b"Q\n");
+                }
+            }
+            if stroke_style.is_some() {
+                stream.extend_from_slice(b"Q\n");
             }
         }
-
-        stream
     }
 
     /// テキストをPDFストリームに出力
@@ -596,44 +1226,246 @@ impl<'a> PdfWriter<'a> {
         if clean_text.is_empty() {
             return;
         }
-        let pdf_y = page_height - y - style.font_size;
+        let (font_size, top_y) = style.script_metrics(y);
+        let pdf_y = page_height - top_y - font_size;
 
-        if has_font {
+        let text_width = if has_font {
             // CIDフォント（/F1）: UTF-16BEヘックス文字列で全Unicode対応
-            let hex_text = self.text_to_pdf_hex(&clean_text);
+            // フォントにグリフが無い文字は missing_glyph ポリシーに従って描画
+            let policy = self.options.missing_glyph;
+            let font = self.font_data.and_then(|d| ab_glyph::FontRef::try_from_slice(d).ok());
+            let has_glyph = |ch: char| font.as_ref().is_none_or(|f| font_has_glyph(f, ch));
+            let mut cursor_x = x;
+            let mut run = String::new();
+            for ch in clean_text.chars() {
+                if has_glyph(ch) {
+                    run.push(ch);
+                    continue;
+                }
+                if let MissingGlyphPolicy::Replace(r) = policy {
+                    if has_glyph(r) {
+                        run.push(r);
+                        continue;
+                    }
+                }
+                cursor_x += self.write_cid_text(stream, &run, cursor_x, pdf_y, font_size, style);
+                run.clear();
+                let box_w = missing_glyph_advance(ch, font_size);
+                if policy != MissingGlyphPolicy::Hide {
+                    // .notdef 相当の矩形
+                    stream.extend_from_slice(
+                        format!(
+                            "{}\n{} w\n{} {} {} {} re\nS\n",
+                            self.color_operator(&style.color, "RG"),
+                            (font_size * 0.05).max(0.3),
+                            cursor_x + box_w * 0.1,
+                            pdf_y,
+                            box_w * 0.8,
+                            font_size * 0.7
+                        )
+                        .as_bytes(),
+                    );
+                }
+                cursor_x += box_w;
+            }
+            cursor_x += self.write_cid_text(stream, &run, cursor_x, pdf_y, font_size, style);
+            cursor_x - x
+        } else {
+            // フォールバック（/F2 Helvetica）: WinAnsiEncoding（Latin-1）
+            // 非ASCII文字を '?' に置換してLatin-1の範囲内に収める
+            let safe_text = text_to_winansi(&clean_text);
+            let escaped = pdf_escape_string(&safe_text);
 
+            let (spacing_on, spacing_off) = char_spacing_operators(style.letter_spacing);
+            let (begin, position, end) = self.text_placement_operators(x, pdf_y, font_size, style);
             stream.extend_from_slice(
                 format!(
-                    "BT\n/F1 {} Tf\n{} {} {} rg\n{} {} Td\n<{}> Tj\nET\n",
-                    style.font_size,
-                    style.color.r as f64 / 255.0,
-                    style.color.g as f64 / 255.0,
-                    style.color.b as f64 / 255.0,
-                    x,
-                    pdf_y,
-                    hex_text
+                    "{}BT\n/F2 {} Tf\n{}\n{}{}({}) Tj\n{}ET\n{}",
+                    begin,
+                    font_size,
+                    self.color_operator(&style.color, "rg"),
+                    spacing_on,
+                    position,
+                    escaped,
+                    spacing_off,
+                    end
                 )
                 .as_bytes(),
             );
+            estimate_text_width(&clean_text, font_size, None) + style.letter_spacing_width(&clean_text)
+        };
+
+        if !style.strikethrough && style.underline.is_none() {
+            return;
+        }
+        let thickness = (font_size * 0.06).max(0.5);
+
+        // 取り消し線（文字の高さの中央付近に水平線を引く）
+        if style.strikethrough {
+            let stroke = self.color_operator(&style.color, "RG");
+            Self::write_hline(stream, x, x + text_width, pdf_y + font_size * 0.3, thickness, &stroke, None);
+        }
+
+        // 下線（ベースラインの少し下、色指定が無ければ文字色）
+        if let Some(underline) = style.underline {
+            let stroke = self.color_operator(&underline.color.unwrap_or(style.color), "RG");
+            let line_y = pdf_y - font_size * UNDERLINE_OFFSET;
+            match underline.kind {
+                UnderlineKind::Single => {
+                    Self::write_hline(stream, x, x + text_width, line_y, thickness, &stroke, None);
+                }
+                UnderlineKind::Double => {
+                    let gap = thickness * 1.5;
+                    Self::write_hline(stream, x, x + text_width, line_y + gap / 2.0, thickness * 0.7, &stroke, None);
+                    Self::write_hline(stream, x, x + text_width, line_y - gap / 2.0, thickness * 0.7, &stroke, None);
+                }
+                UnderlineKind::Dotted => {
+                    Self::write_hline(stream, x, x + text_width, line_y, thickness, &stroke, Some(thickness));
+                }
+            }
+        }
+    }
+
+    /// CIDフォント（/F1）でテキストを出力し、その幅を返す
+    /// 文字間隔は各文字の後に加算されるため、幅には最後の文字の後の間隔も含みます。
+    fn write_cid_text(&self, stream: &mut Vec<u8>, text: &str, x: f64, pdf_y: f64, font_size: f64, style: &FontStyle) -> f64 {
+        if text.is_empty() {
+            return 0.0;
+        }
+        if self.outlines_text() {
+            return self.write_text_outlines(stream, text, x, pdf_y, font_size, style);
+        }
+        let hex_text = self.text_to_pdf_hex(text);
+        let (spacing_on, spacing_off) = char_spacing_operators(style.letter_spacing);
+        let (begin, position, end) = self.text_placement_operators(x, pdf_y, font_size, style);
+        stream.extend_from_slice(
+            format!(
+                "{}BT\n/F1 {} Tf\n{}\n{}{}<{}> Tj\n{}ET\n{}",
+                begin,
+                font_size,
+                self.color_operator(&style.color, "rg"),
+                spacing_on,
+                position,
+                hex_text,
+                spacing_off,
+                end
+            )
+            .as_bytes(),
+        );
+        estimate_text_width(text, font_size, self.font_data) + style.letter_spacing * text.chars().count() as f64
+    }
+
+    /// テキストの配置演算子と、合成する太字・斜体のために前後に置く演算子（`(BTの前, 配置, ETの後)`）
+    /// PDFには1つのフォント（/F1）だけを埋め込むため、太字・斜体のランは `synthesize_styles` が有効なら常に合成します。
+    /// 太字は描画モード2（塗りと線）で輪郭を太らせ、斜体はテキスト行列（Tm）で傾けます。
+    fn text_placement_operators(&self, x: f64, pdf_y: f64, font_size: f64, style: &FontStyle) -> (String, String, &'static str) {
+        let synthetic = self.synthetic_style(style);
+        let position = if synthetic.italic {
+            format!("1 0 {} 1 {} {} Tm\n", SYNTHETIC_ITALIC_SLANT, x, pdf_y)
         } else {
-            // フォールバック（/F2 Helvetica）: WinAnsiEncoding（Latin-1）
-            // 非ASCII文字を '?' に置換してLatin-1の範囲内に収める
-            let safe_text = text_to_winansi(&clean_text);
-            let escaped = pdf_escape_string(&safe_text);
+            format!("{} {} Td\n", x, pdf_y)
+        };
+        if !synthetic.bold {
+            return (String::new(), position, "");
+        }
+        // 描画モード・線幅はグラフィックス状態なので q / Q で戻す
+        let begin = format!(
+            "q\n{}\n{:.3} w\n",
+            self.color_operator(&style.color, "RG"),
+            synthetic.embolden_width(font_size)
+        );
+        (begin, format!("2 Tr\n{}", position), "Q\n")
+    }
 
+    /// 合成して描画する太字・斜体（`synthesize_styles` が無効なら合成しない）
+    fn synthetic_style(&self, style: &FontStyle) -> SyntheticStyle {
+        if self.options.synthesize_styles {
+            SyntheticStyle { bold: style.bold, italic: style.italic }
+        } else {
+            SyntheticStyle::default()
+        }
+    }
+
+    /// テキストをフォントの輪郭のパスで描画するか（`text_as_paths` かつ使用できるフォントがある）
+    fn outlines_text(&self) -> bool {
+        self.options.text_as_paths && self.font_data.is_some()
+    }
+
+    /// テキストを /F1 と同じフォントのグリフの輪郭で塗りつぶして出力し、その幅を返す
+    /// 輪郭はノンゼロ規則で塗り、合成する太字は同じ色の線で輪郭を太らせます（塗りと線の `B`）。
+    fn write_text_outlines(&self, stream: &mut Vec<u8>, text: &str, x: f64, pdf_y: f64, font_size: f64, style: &FontStyle) -> f64 {
+        let Some(font) = self.font_data.and_then(|d| ab_glyph::FontRef::try_from_slice(d).ok()) else {
+            return 0.0;
+        };
+        let synthetic = self.synthetic_style(style);
+        let slant = if synthetic.italic { SYNTHETIC_ITALIC_SLANT } else { 0.0 };
+        // ページ座標のY（下向き）を反転した位置にベースラインを置き、高さ0で変換するとPDF座標になる
+        let (commands, width) =
+            text_outline_commands(&font, text, x, -pdf_y, font_size, style.letter_spacing, slant);
+        if commands.is_empty() {
+            return width;
+        }
+        let path = pdf_path_operators(&commands, 0.0);
+        let fill = self.color_operator(&style.color, "rg");
+        if synthetic.bold {
             stream.extend_from_slice(
                 format!(
-                    "BT\n/F2 {} Tf\n{} {} {} rg\n{} {} Td\n({}) Tj\nET\n",
-                    style.font_size,
-                    style.color.r as f64 / 255.0,
-                    style.color.g as f64 / 255.0,
-                    style.color.b as f64 / 255.0,
-                    x,
-                    pdf_y,
-                    escaped
+                    "{}\n{}\n{:.3} w\n{}B\n",
+                    fill,
+                    self.color_operator(&style.color, "RG"),
+                    synthetic.embolden_width(font_size),
+                    path
                 )
                 .as_bytes(),
             );
+        } else {
+            stream.extend_from_slice(format!("{}\n{}f\n", fill, path).as_bytes());
+        }
+        width
+    }
+
+    /// 色指定の演算子（塗りは `op` = "rg"、線は "RG"）
+    /// グレースケール・白黒モードでは DeviceGray の `g` / `G` で出力します。
+    fn color_operator(&self, color: &Color, op: &str) -> String {
+        match self.options.color_mode.gray_level(color.r, color.g, color.b) {
+            Some(level) => format!("{} {}", level as f64 / 255.0, if op == "RG" { "G" } else { "g" }),
+            None => format!(
+                "{} {} {} {}",
+                color.r as f64 / 255.0,
+                color.g as f64 / 255.0,
+                color.b as f64 / 255.0,
+                op
+            ),
+        }
+    }
+
+    /// 水平線を出力（`dash` 指定時は点線）
+    fn write_hline(
+        stream: &mut Vec<u8>,
+        x1: f64,
+        x2: f64,
+        y: f64,
+        width: f64,
+        stroke_color: &str,
+        dash: Option<f64>,
+    ) {
+        if let Some(d) = dash {
+            stream.extend_from_slice(format!("[{} {}] 0 d\n", d, d * 2.0).as_bytes());
+        }
+        stream.extend_from_slice(
+            format!(
+                "{}\n{} w\n{} {} m\n{} {} l\nS\n",
+                stroke_color,
+                width,
+                x1,
+                y,
+                x2,
+                y
+            )
+            .as_bytes(),
+        );
+        if dash.is_some() {
+            stream.extend_from_slice(b"[] 0 d\n");
         }
     }
 
@@ -648,43 +1480,15 @@ impl<'a> PdfWriter<'a> {
         page_height: f64,
         has_font: bool,
     ) {
-        let base_row_height = 20.0;
-        let padding = 4.0;
-        let line_spacing = 1.3;
-        let num_cols = table.column_widths.len().max(1);
-        let col_width = if table.column_widths.is_empty() {
-            width / num_cols as f64
-        } else {
-            0.0 // 個別幅を使用
-        };
+        let font_data = if has_font { self.font_data } else { None };
+        // 縮小指定のセルはテキストがセルに収まるフォントサイズで配置
+        let shrunk = table.with_shrunk_cells(width, font_data);
+        let table = shrunk.as_ref().unwrap_or(table);
+        let column_widths = table.resolved_column_widths(width);
+        let get_col_w = |ci: usize| -> f64 { Table::cell_width(&column_widths, ci, 1) };
 
-        // Helper to get column width for a given column index
-        let get_col_w = |ci: usize| -> f64 {
-            if table.column_widths.is_empty() || ci >= table.column_widths.len() {
-                col_width
-            } else {
-                table.column_widths[ci]
-            }
-        };
-
-        // 各行の高さを事前計算（セルごとの行高見積もりの最大に基づく動的高さ）
-        let row_heights: Vec<f64> = table.rows.iter().map(|row| {
-            let row_max_height = row
-                .iter()
-                .filter(|cell| cell.col_span > 0 && cell.row_span > 0)
-                .map(|cell| {
-                    let lines = if cell.text.is_empty() {
-                        1
-                    } else {
-                        cell.text.split('\n').count().max(1)
-                    };
-                    let fs = cell.style.font_size;
-                    fs * line_spacing * lines as f64 + padding * 2.0
-                })
-                .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-                .unwrap_or(base_row_height);
-            row_max_height.max(base_row_height)
-        }).collect();
+        // 各行の高さを事前計算（セル幅で折り返した行数に基づく自動拡張）
+        let row_heights = table.row_heights(width, font_data);
 
         let mut row_y_offset = 0.0f64;
         for (row_idx, row) in table.rows.iter().enumerate() {
@@ -718,41 +1522,86 @@ impl<'a> PdfWriter<'a> {
                     })
                     .sum();
 
-                // セル枠線
+                // セル背景
                 let py = page_height - row_y - merged_h;
-                stream.extend_from_slice(
-                    format!(
-                        "0.8 0.8 0.8 RG\n0.5 w\n{} {} {} {} re\nS\n",
-                        cell_x, py, merged_w, merged_h
-                    )
-                    .as_bytes(),
-                );
+                if let Some(bg) = cell.background {
+                    stream.extend_from_slice(
+                        format!(
+                            "{}\n{} {} {} {} re\nf\n",
+                            self.color_operator(&bg, "rg"),
+                            cell_x, py, merged_w, merged_h
+                        )
+                        .as_bytes(),
+                    );
+                }
 
-                // セルテキスト（複数行対応）
-                if !cell.text.is_empty() {
+                // セル枠線（辺ごとの指定が無ければ既定の灰色の枠）
+                match cell.borders {
+                    Some(borders) => {
+                        for line in borders.edge_lines(cell_x, row_y, merged_w, merged_h) {
+                            if let PageElement::Line { x1, y1, x2, y2, width, color, .. } = line {
+                                stream.extend_from_slice(
+                                    format!(
+                                        "{}\n{} w\n{} {} m\n{} {} l\nS\n",
+                                        self.color_operator(&color, "RG"),
+                                        pdf_line_width(width),
+                                        x1,
+                                        page_height - y1,
+                                        x2,
+                                        page_height - y2
+                                    )
+                                    .as_bytes(),
+                                );
+                            }
+                        }
+                    }
+                    None => {
+                        stream.extend_from_slice(
+                            format!(
+                                "0.8 0.8 0.8 RG\n0.5 w\n{} {} {} {} re\nS\n",
+                                cell_x, py, merged_w, merged_h
+                            )
+                            .as_bytes(),
+                        );
+                    }
+                }
+
+                // セルテキスト（セル幅で折り返し、セル範囲でクリップ）
+                let lines = Table::cell_lines(cell, merged_w, font_data);
+                if !lines.is_empty() {
                     let fs = cell.style.font_size;
                     let font_name = if has_font { "F1" } else { "F2" };
-                    let lines: Vec<&str> = cell.text.split('\n').collect();
+                    stream.extend_from_slice(
+                        format!("q\n{} {} {} {} re W n\n", cell_x, py, merged_w, merged_h).as_bytes(),
+                    );
                     for (line_idx, line) in lines.iter().enumerate() {
-                        let text_y = page_height - row_y - padding - fs
-                            - (line_idx as f64 * fs * line_spacing);
+                        let line_top = TABLE_CELL_PADDING + line_idx as f64 * fs * TABLE_LINE_SPACING;
+                        if line_top >= merged_h {
+                            break;
+                        }
+                        let text_y = page_height - row_y - line_top - fs;
                         if line.is_empty() {
                             // 空行はYオフセットだけ進める（描画はスキップ）
                             continue;
                         }
+                        if self.outlines_text() {
+                            self.write_text_outlines(stream, line, cell_x + TABLE_CELL_PADDING, text_y, fs, &cell.style);
+                            continue;
+                        }
+                        let (begin, position, end) =
+                            self.text_placement_operators(cell_x + TABLE_CELL_PADDING, text_y, fs, &cell.style);
                         if has_font {
                             let hex_text = self.text_to_pdf_hex(line);
                             stream.extend_from_slice(
                                 format!(
-                                    "BT\n/{} {} Tf\n{} {} {} rg\n{} {} Td\n<{}> Tj\nET\n",
+                                    "{}BT\n/{} {} Tf\n{}\n{}<{}> Tj\nET\n{}",
+                                    begin,
                                     font_name,
                                     fs,
-                                    cell.style.color.r as f64 / 255.0,
-                                    cell.style.color.g as f64 / 255.0,
-                                    cell.style.color.b as f64 / 255.0,
-                                    cell_x + padding,
-                                    text_y,
-                                    hex_text
+                                    self.color_operator(&cell.style.color, "rg"),
+                                    position,
+                                    hex_text,
+                                    end
                                 )
                                 .as_bytes(),
                             );
@@ -761,20 +1610,20 @@ impl<'a> PdfWriter<'a> {
                             let escaped = pdf_escape_string(&safe_text);
                             stream.extend_from_slice(
                                 format!(
-                                    "BT\n/{} {} Tf\n{} {} {} rg\n{} {} Td\n({}) Tj\nET\n",
+                                    "{}BT\n/{} {} Tf\n{}\n{}({}) Tj\nET\n{}",
+                                    begin,
                                     font_name,
                                     fs,
-                                    cell.style.color.r as f64 / 255.0,
-                                    cell.style.color.g as f64 / 255.0,
-                                    cell.style.color.b as f64 / 255.0,
-                                    cell_x + padding,
-                                    text_y,
-                                    escaped
+                                    self.color_operator(&cell.style.color, "rg"),
+                                    position,
+                                    escaped,
+                                    end
                                 )
                                 .as_bytes(),
                             );
                         }
                     }
+                    stream.extend_from_slice(b"Q\n");
                 }
 
                 cell_x += merged_w;
@@ -824,10 +1673,8 @@ impl<'a> PdfWriter<'a> {
             let strip_y = page_height - y - (i + 1) as f64 * strip_height;
             stream.extend_from_slice(
                 format!(
-                    "{:.4} {:.4} {:.4} rg\n{} {} {} {} re\nf\n",
-                    color.r as f64 / 255.0,
-                    color.g as f64 / 255.0,
-                    color.b as f64 / 255.0,
+                    "{}\n{} {} {} {} re\nf\n",
+                    self.color_operator(&color, "rg"),
                     x,
                     strip_y,
                     w,
@@ -927,10 +1774,8 @@ impl<'a> PdfWriter<'a> {
         if let Some(fill_color) = fill {
             stream.extend_from_slice(
                 format!(
-                    "{} {} {} rg\n{}f\n",
-                    fill_color.r as f64 / 255.0,
-                    fill_color.g as f64 / 255.0,
-                    fill_color.b as f64 / 255.0,
+                    "{}\n{}f\n",
+                    self.color_operator(fill_color, "rg"),
                     path
                 )
                 .as_bytes(),
@@ -939,11 +1784,9 @@ impl<'a> PdfWriter<'a> {
         if let Some(stroke_color) = stroke {
             stream.extend_from_slice(
                 format!(
-                    "{} {} {} RG\n{} w\n{}S\n",
-                    stroke_color.r as f64 / 255.0,
-                    stroke_color.g as f64 / 255.0,
-                    stroke_color.b as f64 / 255.0,
-                    stroke_width,
+                    "{}\n{} w\n{}S\n",
+                    self.color_operator(stroke_color, "RG"),
+                    pdf_line_width(stroke_width),
                     path
                 )
                 .as_bytes(),
@@ -979,63 +1822,13 @@ impl<'a> PdfWriter<'a> {
         stroke_width: f64,
         page_height: f64,
     ) {
-        use crate::converter::PathCommand;
-
-        let mut path_str = String::new();
-        let mut cur_x = 0.0_f64;
-        let mut cur_y = 0.0_f64;
-        for cmd in commands {
-            match cmd {
-                PathCommand::MoveTo(x, y) => {
-                    cur_x = *x;
-                    cur_y = *y;
-                    path_str.push_str(&format!("{} {} m\n", x, page_height - y));
-                }
-                PathCommand::LineTo(x, y) => {
-                    cur_x = *x;
-                    cur_y = *y;
-                    path_str.push_str(&format!("{} {} l\n", x, page_height - y));
-                }
-                PathCommand::QuadTo(qcx, qcy, x, y) => {
-                    // Convert quadratic to cubic: cp1 = cur + 2/3*(qc - cur), cp2 = end + 2/3*(qc - end)
-                    let cp1x = cur_x + 2.0 / 3.0 * (qcx - cur_x);
-                    let cp1y = cur_y + 2.0 / 3.0 * (qcy - cur_y);
-                    let cp2x = x + 2.0 / 3.0 * (qcx - x);
-                    let cp2y = y + 2.0 / 3.0 * (qcy - y);
-                    cur_x = *x;
-                    cur_y = *y;
-                    path_str.push_str(&format!(
-                        "{} {} {} {} {} {} c\n",
-                        cp1x, page_height - cp1y, cp2x, page_height - cp2y, x, page_height - y
-                    ));
-                }
-                PathCommand::CubicTo(cx1, cy1, cx2, cy2, x, y) => {
-                    cur_x = *x;
-                    cur_y = *y;
-                    path_str.push_str(&format!(
-                        "{} {} {} {} {} {} c\n",
-                        cx1, page_height - cy1, cx2, page_height - cy2, x, page_height - y
-                    ));
-                }
-                PathCommand::ArcTo(_rx, _ry, _rot, _large, _sweep, x, y) => {
-                    // Approximate as line (proper arc-to-bezier conversion is complex)
-                    cur_x = *x;
-                    cur_y = *y;
-                    path_str.push_str(&format!("{} {} l\n", x, page_height - y));
-                }
-                PathCommand::Close => {
-                    path_str.push_str("h\n");
-                }
-            }
-        }
+        let path_str = pdf_path_operators(commands, page_height);
 
         if let Some(fill_color) = fill {
             stream.extend_from_slice(
                 format!(
-                    "{} {} {} rg\n{}f\n",
-                    fill_color.r as f64 / 255.0,
-                    fill_color.g as f64 / 255.0,
-                    fill_color.b as f64 / 255.0,
+                    "{}\n{}f*\n",
+                    self.color_operator(fill_color, "rg"),
                     path_str
                 )
                 .as_bytes(),
@@ -1044,11 +1837,9 @@ impl<'a> PdfWriter<'a> {
         if let Some(stroke_color) = stroke {
             stream.extend_from_slice(
                 format!(
-                    "{} {} {} RG\n{} w\n{}S\n",
-                    stroke_color.r as f64 / 255.0,
-                    stroke_color.g as f64 / 255.0,
-                    stroke_color.b as f64 / 255.0,
-                    stroke_width,
+                    "{}\n{} w\n{}S\n",
+                    self.color_operator(stroke_color, "RG"),
+                    pdf_line_width(stroke_width),
                     path_str
                 )
                 .as_bytes(),
@@ -1081,7 +1872,7 @@ impl<'a> PdfWriter<'a> {
 
     /// CIDToGIDMapストリームを生成
     /// フォントのcmapテーブルからUnicodeコードポイント(CID)→グリフID(GID)のマッピングを構築
-    /// 指定されたフォントデータを使用（find_usable_font_dataで検証済みのもの）
+    /// 指定されたフォントデータを使用（`FontManager::usable_font_data` で検証済みのもの）
     fn build_cid_to_gid_map_from_data(font_data: Option<&[u8]>) -> Vec<u8> {
         use ab_glyph::{Font, FontRef};
         // 65536 entries × 2 bytes each = 131072 bytes
@@ -1103,12 +1894,27 @@ impl<'a> PdfWriter<'a> {
         map
     }
 
+    /// JPEGデータを /DCTDecode の画像XObjectとして追加し、オブジェクトIDを返す
+    fn add_dct_image(&mut self, width: u32, height: u32, jpeg: &[u8]) -> u32 {
+        let obj_id = self.alloc_id();
+        let mut obj_data = format!(
+            "<< /Type /XObject /Subtype /Image /Width {} /Height {} \
+             /ColorSpace {} /BitsPerComponent 8 \
+             /Filter /DCTDecode /Length {} >>\nstream\n",
+            width, height, self.rgb_color_space, jpeg.len()
+        ).into_bytes();
+        obj_data.extend_from_slice(jpeg);
+        obj_data.extend_from_slice(b"\nendstream");
+        self.add_object(obj_id, obj_data);
+        obj_id
+    }
+
     /// ページ内の画像要素からPDF XObjectを作成
     fn create_page_image_xobjects(&mut self, page: &Page) -> Vec<PdfImageXObject> {
         let mut xobjects = Vec::new();
         let mut counter = 0u32;
 
-        for element in &page.elements {
+        for element in page.leaf_elements() {
             let image_data: Option<(&[u8], &str)> = match element {
                 PageElement::Image { data, mime_type, .. } => Some((data, mime_type)),
                 PageElement::EllipseImage { data, mime_type, .. } => Some((data, mime_type)),
@@ -1121,54 +1927,70 @@ impl<'a> PdfWriter<'a> {
                 let is_jpeg = mime_type.contains("jpeg") || mime_type.contains("jpg")
                     || (data.len() >= 2 && data[0] == 0xFF && data[1] == 0xD8);
 
-                if is_jpeg {
+                let gray_mode = self.options.color_mode != ColorMode::Color;
+
+                if !gray_mode && is_jpeg && parse_jpeg_frame(data).is_some_and(|f| f.is_pdf_safe()) {
+                    // ベースラインのRGB JPEGはそのまま埋め込む
                     if let Some((w, h)) = extract_jpeg_dimensions(data) {
+                        let obj_id = self.add_dct_image(w, h, data);
+                        xobjects.push(PdfImageXObject { name, obj_id, smask_id: None });
+                    }
+                } else if let Some((w, h, rgb_data, alpha_data)) = decode_image_to_raw_rgb(data) {
+                    // CMYK/グレースケール/プログレッシブJPEGやパレットPNGはRGBに正規化して再エンコード
+                    // （写真的な画像はベースラインJPEG、それ以外はFlate）
+                    let allow_jpeg = !gray_mode && (is_jpeg || self.options.normalize_images);
+                    let reencoded_jpeg = (allow_jpeg && alpha_data.is_none() && is_photographic(&rgb_data))
+                        .then(|| encode_baseline_jpeg(w, h, &rgb_data))
+                        .flatten();
+                    if let Some(jpeg) = reencoded_jpeg {
+                        let obj_id = self.add_dct_image(w, h, &jpeg);
+                        xobjects.push(PdfImageXObject { name, obj_id, smask_id: None });
+                    } else {
                         let obj_id = self.alloc_id();
+
+                        // アルファチャンネルがあればSMaskを作成
+                        let smask_id = if let Some(ref alpha) = alpha_data {
+                            let sid = self.alloc_id();
+                            let compressed_alpha = miniz_oxide::deflate::compress_to_vec(alpha, 6);
+                            let mut smask_data = format!(
+                                "<< /Type /XObject /Subtype /Image /Width {} /Height {} \
+                                 /ColorSpace /DeviceGray /BitsPerComponent 8 \
+                                 /Filter /FlateDecode /Length {} >>\nstream\n",
+                                w, h, compressed_alpha.len()
+                            ).into_bytes();
+                            smask_data.extend_from_slice(&compressed_alpha);
+                            smask_data.extend_from_slice(b"\nendstream");
+                            self.add_object(sid, smask_data);
+                            Some(sid)
+                        } else {
+                            None
+                        };
+
+                        // グレースケール・白黒モードでは1チャンネルの DeviceGray として埋め込む
+                        let (pixel_data, color_space) = if gray_mode {
+                            let mode = self.options.color_mode;
+                            let gray: Vec<u8> = rgb_data
+                                .chunks_exact(3)
+                                .map(|px| mode.gray_level(px[0], px[1], px[2]).unwrap_or(px[0]))
+                                .collect();
+                            (gray, "/DeviceGray")
+                        } else {
+                            (rgb_data, self.rgb_color_space.as_str())
+                        };
+                        let compressed_rgb = miniz_oxide::deflate::compress_to_vec(&pixel_data, 6);
+                        let smask_ref = smask_id.map_or(String::new(),
+                            |sid| format!(" /SMask {} 0 R", sid));
                         let mut obj_data = format!(
                             "<< /Type /XObject /Subtype /Image /Width {} /Height {} \
-                             /ColorSpace /DeviceRGB /BitsPerComponent 8 \
-                             /Filter /DCTDecode /Length {} >>\nstream\n",
-                            w, h, data.len()
+                             /ColorSpace {} /BitsPerComponent 8 \
+                             /Filter /FlateDecode /Length {}{} >>\nstream\n",
+                            w, h, color_space, compressed_rgb.len(), smask_ref
                         ).into_bytes();
-                        obj_data.extend_from_slice(data);
+                        obj_data.extend_from_slice(&compressed_rgb);
                         obj_data.extend_from_slice(b"\nendstream");
                         self.add_object(obj_id, obj_data);
-                        xobjects.push(PdfImageXObject { name, obj_id, smask_id: None });
+                        xobjects.push(PdfImageXObject { name, obj_id, smask_id });
                     }
-                } else if let Some((w, h, rgb_data, alpha_data)) = decode_image_to_raw_rgb(data) {
-                    let obj_id = self.alloc_id();
-
-                    // アルファチャンネルがあればSMaskを作成
-                    let smask_id = if let Some(ref alpha) = alpha_data {
-                        let sid = self.alloc_id();
-                        let compressed_alpha = miniz_oxide::deflate::compress_to_vec(alpha, 6);
-                        let mut smask_data = format!(
-                            "<< /Type /XObject /Subtype /Image /Width {} /Height {} \
-                             /ColorSpace /DeviceGray /BitsPerComponent 8 \
-                             /Filter /FlateDecode /Length {} >>\nstream\n",
-                            w, h, compressed_alpha.len()
-                        ).into_bytes();
-                        smask_data.extend_from_slice(&compressed_alpha);
-                        smask_data.extend_from_slice(b"\nendstream");
-                        self.add_object(sid, smask_data);
-                        Some(sid)
-                    } else {
-                        None
-                    };
-
-                    let compressed_rgb = miniz_oxide::deflate::compress_to_vec(&rgb_data, 6);
-                    let smask_ref = smask_id.map_or(String::new(),
-                        |sid| format!(" /SMask {} 0 R", sid));
-                    let mut obj_data = format!(
-                        "<< /Type /XObject /Subtype /Image /Width {} /Height {} \
-                         /ColorSpace /DeviceRGB /BitsPerComponent 8 \
-                         /Filter /FlateDecode /Length {}{} >>\nstream\n",
-                        w, h, compressed_rgb.len(), smask_ref
-                    ).into_bytes();
-                    obj_data.extend_from_slice(&compressed_rgb);
-                    obj_data.extend_from_slice(b"\nendstream");
-                    self.add_object(obj_id, obj_data);
-                    xobjects.push(PdfImageXObject { name, obj_id, smask_id });
                 }
                 counter += 1;
             }
@@ -1179,10 +2001,31 @@ impl<'a> PdfWriter<'a> {
 
     /// PDFバイト列をシリアライズ
     fn serialize(&self, catalog_id: u32) -> Vec<u8> {
+        self.serialize_with_trailer(catalog_id, "1.4", "")
+    }
+
+    /// 文字列・ストリームを暗号化してシリアライズ
+    /// ファイルID（/ID）はオブジェクトの内容のMD5とし、暗号化辞書自体は暗号化せずに追加します。
+    /// AESV2 の暗号化フィルタはPDF 1.6で追加されたため、ヘッダーのバージョンも1.6にします。
+    fn serialize_encrypted(&mut self, catalog_id: u32, encryption: &PdfEncryption) -> Vec<u8> {
+        let content: Vec<u8> = self.objects.iter().flat_map(|obj| obj.data.iter().copied()).collect();
+        let handler = SecurityHandler::new(encryption, md5(&content));
+        for obj in &mut self.objects {
+            obj.data = handler.encrypt_object(obj.id, &obj.data);
+        }
+        let encrypt_id = self.alloc_id();
+        self.add_object(encrypt_id, handler.encrypt_dict().into_bytes());
+        let trailer = format!(" /Encrypt {} 0 R /ID {}", encrypt_id, handler.id_array());
+        self.serialize_with_trailer(catalog_id, "1.6", &trailer)
+    }
+
+    /// ヘッダーのバージョンとトレーラーに追加する項目を指定してシリアライズ
+    fn serialize_with_trailer(&self, catalog_id: u32, version: &str, trailer_extra: &str) -> Vec<u8> {
         let mut output = Vec::new();
 
         // ヘッダー
-        output.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+        output.extend_from_slice(format!("%PDF-{}\n", version).as_bytes());
+        output.extend_from_slice(b"%\xE2\xE3\xCF\xD3\n");
 
         // オブジェクト書き出しとオフセット記録
         let mut offsets: Vec<(u32, usize)> = Vec::new();
@@ -1212,10 +2055,14 @@ impl<'a> PdfWriter<'a> {
         }
 
         // トレーラー
+        let info_ref = self
+            .info_id
+            .map(|id| format!(" /Info {} 0 R", id))
+            .unwrap_or_default();
         output.extend_from_slice(
             format!(
-                "trailer\n<< /Size {} /Root {} 0 R >>\n",
-                max_id, catalog_id
+                "trailer\n<< /Size {} /Root {} 0 R{}{} >>\n",
+                max_id, catalog_id, info_ref, trailer_extra
             )
             .as_bytes(),
         );
@@ -1223,6 +2070,512 @@ impl<'a> PdfWriter<'a> {
 
         output
     }
+
+    /// リニアライズ形式でPDFバイト列をシリアライズ
+    ///
+    /// オブジェクト配置:
+    ///   ヘッダー → リニアライズ辞書 → 1ページ目用xref/トレーラー → カタログ・ページツリー
+    ///   → ヒントストリーム → 1ページ目のオブジェクト → 残りのページ → メインxref/トレーラー
+    ///
+    /// 1ページ目のセクションのオブジェクト番号はメインセクションより大きくなるよう振り直します。
+    /// オフセット値は固定幅で書き出し、1パス目の計測結果を2パス目で埋め込みます。
+    /// ヒントテーブルは項目幅を固定した簡略版です（ページ単位のオブジェクト数と長さのみ）。
+    fn serialize_linearized(&self, catalog_id: u32, pages_id: u32) -> Vec<u8> {
+        let first_page_id = match self.page_ids.first() {
+            Some(id) => *id,
+            None => return self.serialize(catalog_id),
+        };
+
+        // 1ページ目の表示に必要なオブジェクト（/Parentのページツリーは別扱い）
+        let mut assigned: Vec<u32> = vec![catalog_id, pages_id];
+        let first_group = self.collect_object_closure(first_page_id, &mut assigned);
+
+        // 2ページ目以降は各ページ単位でまとめ、どのページからも参照されないものは末尾へ
+        let mut page_groups: Vec<Vec<u32>> = Vec::new();
+        for &pid in self.page_ids.iter().skip(1) {
+            page_groups.push(self.collect_object_closure(pid, &mut assigned));
+        }
+        let others: Vec<u32> = self
+            .objects
+            .iter()
+            .map(|o| o.id)
+            .filter(|id| !assigned.contains(id))
+            .collect();
+
+        // オブジェクト番号の振り直し（メインセクション → 1ページ目セクションの順）
+        let main_order: Vec<u32> = page_groups.iter().flatten().copied().chain(others.iter().copied()).collect();
+        let mut renumber: HashMap<u32, u32> = HashMap::new();
+        for (i, id) in main_order.iter().enumerate() {
+            renumber.insert(*id, i as u32 + 1);
+        }
+        let main_count = main_order.len() as u32;
+        let lin_id = main_count + 1;
+        let mut next = lin_id + 1;
+        for id in [catalog_id, pages_id].iter().chain(first_group.iter()) {
+            renumber.insert(*id, next);
+            next += 1;
+        }
+        let hint_id = next;
+        let total_size = hint_id + 1;
+
+        let layout = LinearizedLayout {
+            lin_id,
+            hint_id,
+            main_count,
+            total_size,
+            catalog_id,
+            pages_id,
+            first_group: &first_group,
+            page_groups: &page_groups,
+            others: &others,
+            renumber: &renumber,
+        };
+
+        // 1パス目でオフセットを計測し、2パス目で実際の値を書き込む（固定幅なので長さは不変）
+        let (_, measured) = self.write_linearized(&layout, &LinearizedOffsets::default());
+        let (output, _) = self.write_linearized(&layout, &measured);
+        output
+    }
+
+    /// 指定オブジェクトから参照を辿り、未割り当てのオブジェクトIDを出現順に収集
+    fn collect_object_closure(&self, root: u32, assigned: &mut Vec<u32>) -> Vec<u32> {
+        let mut group = Vec::new();
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            if assigned.contains(&id) {
+                continue;
+            }
+            let obj = match self.objects.iter().find(|o| o.id == id) {
+                Some(o) => o,
+                None => continue,
+            };
+            assigned.push(id);
+            group.push(id);
+            let mut refs = collect_object_refs(&obj.data);
+            refs.reverse();
+            stack.extend(refs);
+        }
+        group
+    }
+
+    /// リニアライズ形式のバイト列を1パス分生成し、実際のオフセットを返す
+    fn write_linearized(
+        &self,
+        layout: &LinearizedLayout,
+        prev: &LinearizedOffsets,
+    ) -> (Vec<u8>, LinearizedOffsets) {
+        let mut out = Vec::new();
+        let mut measured = LinearizedOffsets::default();
+        let mut offsets: HashMap<u32, usize> = HashMap::new();
+        let new_id = |id: u32| layout.renumber.get(&id).copied().unwrap_or(0);
+
+        out.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+
+        // リニアライズパラメータ辞書
+        offsets.insert(layout.lin_id, out.len());
+        out.extend_from_slice(
+            format!(
+                "{} 0 obj\n<< /Linearized 1 /L {:>10} /H [{:>10} {:>10}] /O {} /E {:>10} /N {} /T {:>10} >>\nendobj\n",
+                layout.lin_id,
+                prev.file_length,
+                prev.hint_offset,
+                prev.hint_length,
+                new_id(self.page_ids[0]),
+                prev.first_page_end,
+                self.page_ids.len(),
+                prev.main_xref_first_entry,
+            )
+            .as_bytes(),
+        );
+
+        // 1ページ目用の相互参照テーブル（リニアライズ辞書〜ヒントストリーム）
+        measured.first_xref = out.len();
+        out.extend_from_slice(
+            format!("xref\n{} {}\n", layout.lin_id, layout.total_size - layout.lin_id).as_bytes(),
+        );
+        for id in layout.lin_id..layout.total_size {
+            let off = prev.object_offsets.get(&id).copied().unwrap_or(0);
+            out.extend_from_slice(format!("{:010} 00000 n \n", off).as_bytes());
+        }
+        let info_ref = self
+            .info_id
+            .map(|id| format!(" /Info {} 0 R", new_id(id)))
+            .unwrap_or_default();
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root {} 0 R{} /Prev {:>10} >>\nstartxref\n0\n%%EOF\n",
+                layout.total_size,
+                new_id(layout.catalog_id),
+                info_ref,
+                prev.main_xref,
+            )
+            .as_bytes(),
+        );
+
+        // カタログとページツリー
+        for id in [layout.catalog_id, layout.pages_id] {
+            self.write_renumbered_object(&mut out, &mut offsets, id, layout.renumber);
+        }
+
+        // ヒントストリーム
+        measured.hint_offset = out.len();
+        offsets.insert(layout.hint_id, out.len());
+        let (hint_data, shared_offset) = self.build_hint_tables(layout, prev);
+        out.extend_from_slice(
+            format!(
+                "{} 0 obj\n<< /S {} /Length {} >>\nstream\n",
+                layout.hint_id,
+                shared_offset,
+                hint_data.len()
+            )
+            .as_bytes(),
+        );
+        out.extend_from_slice(&hint_data);
+        out.extend_from_slice(b"\nendstream\nendobj\n\n");
+        measured.hint_length = out.len() - measured.hint_offset;
+
+        // 1ページ目のオブジェクト
+        for &id in layout.first_group {
+            self.write_renumbered_object(&mut out, &mut offsets, id, layout.renumber);
+        }
+        measured.first_page_end = out.len();
+
+        // 残りのページとその他のオブジェクト
+        for &id in layout.page_groups.iter().flatten().chain(layout.others.iter()) {
+            self.write_renumbered_object(&mut out, &mut offsets, id, layout.renumber);
+        }
+
+        // メイン相互参照テーブル
+        measured.main_xref = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n", layout.main_count + 1).as_bytes());
+        measured.main_xref_first_entry = out.len();
+        out.extend_from_slice(b"0000000000 65535 f \n");
+        for id in 1..=layout.main_count {
+            let off = offsets.get(&id).copied().unwrap_or(0);
+            out.extend_from_slice(format!("{:010} 00000 n \n", off).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} >>\nstartxref\n{}\n%%EOF\n",
+                layout.main_count + 1,
+                measured.first_xref
+            )
+            .as_bytes(),
+        );
+
+        measured.file_length = out.len();
+        measured.object_offsets = offsets;
+        (out, measured)
+    }
+
+    /// オブジェクト番号と参照を振り直して書き出す
+    fn write_renumbered_object(
+        &self,
+        out: &mut Vec<u8>,
+        offsets: &mut HashMap<u32, usize>,
+        id: u32,
+        renumber: &HashMap<u32, u32>,
+    ) {
+        let obj = match self.objects.iter().find(|o| o.id == id) {
+            Some(o) => o,
+            None => return,
+        };
+        let new_id = renumber.get(&id).copied().unwrap_or(id);
+        offsets.insert(new_id, out.len());
+        out.extend_from_slice(format!("{} 0 obj\n", new_id).as_bytes());
+        out.extend_from_slice(&rewrite_object_refs(&obj.data, renumber));
+        out.extend_from_slice(b"\nendobj\n\n");
+    }
+
+    /// ページオフセットヒントテーブルと共有オブジェクトヒントテーブルを生成
+    /// 戻り値: (ヒントストリームデータ, 共有オブジェクトヒントテーブルの開始位置)
+    fn build_hint_tables(&self, layout: &LinearizedLayout, prev: &LinearizedOffsets) -> (Vec<u8>, usize) {
+        let object_len = |id: u32| -> u32 {
+            let new_id = layout.renumber.get(&id).copied().unwrap_or(0);
+            let start = prev.object_offsets.get(&new_id).copied().unwrap_or(0);
+            // 次のオブジェクトの開始位置（書き出し順）までを長さとみなす
+            let end = prev
+                .object_offsets
+                .values()
+                .copied()
+                .filter(|&o| o > start)
+                .min()
+                .unwrap_or(prev.main_xref);
+            end.saturating_sub(start) as u32
+        };
+
+        // ページごとの（オブジェクト数, バイト長）
+        let mut pages: Vec<(u32, u32)> = Vec::new();
+        pages.push((
+            layout.first_group.len() as u32,
+            layout.first_group.iter().map(|&id| object_len(id)).sum(),
+        ));
+        for group in layout.page_groups {
+            pages.push((group.len() as u32, group.iter().map(|&id| object_len(id)).sum()));
+        }
+        let least_objects = pages.iter().map(|p| p.0).min().unwrap_or(0);
+        let least_length = pages.iter().map(|p| p.1).min().unwrap_or(0);
+        let first_page_offset = layout
+            .renumber
+            .get(&self.page_ids[0])
+            .and_then(|id| prev.object_offsets.get(id))
+            .copied()
+            .unwrap_or(0) as u32;
+
+        let mut bits = BitWriter::default();
+        // ページオフセットヒントテーブル ヘッダー
+        bits.write(least_objects, 32);
+        bits.write(first_page_offset, 32);
+        bits.write(32, 16);
+        bits.write(least_length, 32);
+        bits.write(32, 16);
+        bits.write(0, 32); // コンテンツストリーム開始位置（未使用）
+        bits.write(0, 16);
+        bits.write(0, 32); // コンテンツストリーム長（未使用）
+        bits.write(0, 16);
+        bits.write(0, 16); // 共有オブジェクト参照数のビット幅
+        bits.write(0, 16);
+        bits.write(0, 16);
+        bits.write(1, 16);
+        for p in &pages {
+            bits.write(p.0 - least_objects, 32);
+        }
+        for p in &pages {
+            bits.write(p.1 - least_length, 32);
+        }
+        bits.align();
+
+        // 共有オブジェクトヒントテーブル（1ページ目のオブジェクトのみ）
+        let shared_offset = bits.bytes.len();
+        let group_lengths: Vec<u32> = layout.first_group.iter().map(|&id| object_len(id)).collect();
+        let least_group = group_lengths.iter().copied().min().unwrap_or(0);
+        let first_shared = layout.first_group.first().and_then(|id| layout.renumber.get(id)).copied().unwrap_or(0);
+        bits.write(first_shared, 32);
+        bits.write(first_page_offset, 32);
+        bits.write(group_lengths.len() as u32, 32);
+        bits.write(group_lengths.len() as u32, 32);
+        bits.write(0, 16);
+        bits.write(least_group, 32);
+        bits.write(32, 16);
+        for len in &group_lengths {
+            bits.write(len - least_group, 32);
+        }
+        for _ in &group_lengths {
+            bits.write(0, 1); // MD5署名なし
+        }
+        bits.align();
+
+        (bits.bytes, shared_offset)
+    }
+}
+
+/// リニアライズ出力時のオブジェクト配置情報
+struct LinearizedLayout<'l> {
+    lin_id: u32,
+    hint_id: u32,
+    main_count: u32,
+    total_size: u32,
+    catalog_id: u32,
+    pages_id: u32,
+    first_group: &'l [u32],
+    page_groups: &'l [Vec<u32>],
+    others: &'l [u32],
+    renumber: &'l HashMap<u32, u32>,
+}
+
+/// リニアライズ出力の1パス目で計測したオフセット
+#[derive(Default)]
+struct LinearizedOffsets {
+    file_length: usize,
+    hint_offset: usize,
+    hint_length: usize,
+    first_page_end: usize,
+    first_xref: usize,
+    main_xref: usize,
+    main_xref_first_entry: usize,
+    object_offsets: HashMap<u32, usize>,
+}
+
+/// ヒントテーブル用のビット単位ライター（MSBファースト）
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u8,
+    used: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, width: u8) {
+        for i in (0..width).rev() {
+            let bit = ((value >> i) & 1) as u8;
+            self.current = (self.current << 1) | bit;
+            self.used += 1;
+            if self.used == 8 {
+                self.bytes.push(self.current);
+                self.current = 0;
+                self.used = 0;
+            }
+        }
+    }
+
+    fn align(&mut self) {
+        if self.used > 0 {
+            self.bytes.push(self.current << (8 - self.used));
+            self.current = 0;
+            self.used = 0;
+        }
+    }
+}
+
+/// オブジェクトデータの辞書部分（ストリーム本体より前）を返す
+fn object_dict_part(data: &[u8]) -> &[u8] {
+    match data.windows(7).position(|w| w == b"stream\n") {
+        Some(pos) => &data[..pos],
+        None => data,
+    }
+}
+
+/// 辞書部分に含まれる間接参照（"N 0 R"）のオブジェクトIDを出現順に返す
+fn collect_object_refs(data: &[u8]) -> Vec<u32> {
+    let dict = object_dict_part(data);
+    let mut refs = Vec::new();
+    let mut i = 0;
+    while i < dict.len() {
+        if dict[i] == b'(' {
+            i = literal_string_end(dict, i);
+        } else if dict[i].is_ascii_digit() && (i == 0 || !is_pdf_number_char(dict[i - 1])) {
+            let start = i;
+            while i < dict.len() && dict[i].is_ascii_digit() {
+                i += 1;
+            }
+            if is_indirect_ref_suffix(&dict[i..]) {
+                if let Ok(id) = std::str::from_utf8(&dict[start..i]).unwrap_or("").parse::<u32>() {
+                    refs.push(id);
+                }
+            }
+        } else {
+            i += 1;
+        }
+    }
+    refs
+}
+
+/// 辞書部分の間接参照を新しいオブジェクト番号に置き換える（ストリーム本体はそのまま）
+fn rewrite_object_refs(data: &[u8], renumber: &HashMap<u32, u32>) -> Vec<u8> {
+    let dict_len = object_dict_part(data).len();
+    let dict = &data[..dict_len];
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < dict.len() {
+        if dict[i] == b'(' {
+            // リテラル文字列の中身は参照ではないためそのまま書き出す
+            let end = literal_string_end(dict, i);
+            out.extend_from_slice(&dict[i..end]);
+            i = end;
+        } else if dict[i].is_ascii_digit() && (i == 0 || !is_pdf_number_char(dict[i - 1])) {
+            let start = i;
+            while i < dict.len() && dict[i].is_ascii_digit() {
+                i += 1;
+            }
+            let digits = &dict[start..i];
+            let mapped = if is_indirect_ref_suffix(&dict[i..]) {
+                std::str::from_utf8(digits)
+                    .ok()
+                    .and_then(|d| d.parse::<u32>().ok())
+                    .and_then(|id| renumber.get(&id))
+            } else {
+                None
+            };
+            match mapped {
+                Some(new_id) => out.extend_from_slice(new_id.to_string().as_bytes()),
+                None => out.extend_from_slice(digits),
+            }
+        } else {
+            out.push(dict[i]);
+            i += 1;
+        }
+    }
+    out.extend_from_slice(&data[dict_len..]);
+    out
+}
+
+/// `start` の "(" から始まるリテラル文字列の終端（閉じ括弧の次）の位置
+/// 括弧の入れ子とバックスラッシュによるエスケープを考慮します。
+fn literal_string_end(data: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i < data.len() {
+        match data[i] {
+            b'\\' => i += 1,
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    data.len()
+}
+
+fn is_pdf_number_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'.' || b == b'-' || b == b'+'
+}
+
+/// 数字の直後が " 0 R" で区切られているか
+fn is_indirect_ref_suffix(rest: &[u8]) -> bool {
+    rest.starts_with(b" 0 R") && rest.get(4).is_none_or(|c| !c.is_ascii_alphanumeric())
+}
+
+/// PDFの線幅（w オペレーター）
+/// 0 はPDFの仕様で出力デバイスで描ける最も細い線（ヘアライン）を表すため、0以下・NaN は 0 にします。
+fn pdf_line_width(width: f64) -> f64 {
+    if width > 0.0 { width } else { 0.0 }
+}
+
+/// 文字間隔（Tc）の設定・解除オペレーター（間隔が0の場合は空）
+/// Tc はテキストオブジェクトの外にも引き継がれるため、設定した場合は ET の前に0へ戻します。
+fn char_spacing_operators(spacing: f64) -> (String, &'static str) {
+    if spacing == 0.0 {
+        return (String::new(), "");
+    }
+    (format!("{:.3} Tc\n", spacing), "0 Tc\n")
+}
+
+/// 0〜1に制限し0.1%単位に丸めた不透明度
+fn rounded_opacity(opacity: f64) -> f64 {
+    (opacity.clamp(0.0, 1.0) * 1000.0).round() / 1000.0
+}
+
+/// 不透明度を設定するグラフィックス状態のリソース名（丸めた不透明度ごとに共有）
+fn opacity_gs_name(opacity: f64) -> String {
+    format!("GSa{}", (rounded_opacity(opacity) * 1000.0).round() as u32)
+}
+
+/// 半透明の変換グループの不透明度を重複なく収集（0.1%単位に丸める）
+fn collect_group_opacities(elements: &[PageElement], out: &mut Vec<f64>) {
+    for element in elements {
+        match element {
+            PageElement::TransformGroup { opacity, children, .. } => {
+                let rounded = rounded_opacity(*opacity);
+                if rounded < 1.0 && !out.contains(&rounded) {
+                    out.push(rounded);
+                }
+                collect_group_opacities(children, out);
+            }
+            PageElement::ClipGroup { children, .. } => collect_group_opacities(children, out),
+            _ => {}
+        }
+    }
+}
+
+/// 線スタイルの描画状態オペレーター（破線パターン d・線端 J・角 j）
+fn pdf_stroke_style_operators(style: &StrokeStyle, width: f64) -> String {
+    let dash: Vec<String> = style.dash.lengths(width).iter().map(|l| format!("{:.2}", l)).collect();
+    format!("[{}] 0 d\n{} J\n{} j\n", dash.join(" "), style.cap.pdf_value(), style.join.pdf_value())
 }
 
 /// パスコマンドのバウンディングボックス中心をPDF座標系で返す
@@ -1268,29 +2621,126 @@ fn path_bbox_center(commands: &[crate::converter::PathCommand], page_height: f64
     (cx, cy)
 }
 
-/// ab_glyphでパース可能なフォントデータを見つける（フリー関数版）
-/// 外部フォント → 内蔵フォントの順に試行し、パース可能な最初のフォントデータをコピーして返す
-fn find_usable_font_data(font_manager: &FontManager) -> Option<Vec<u8>> {
-    use ab_glyph::FontRef;
-    // まず外部フォントを順に試す
-    for (_, data) in font_manager.external_fonts_iter() {
-        if !data.is_empty() && FontRef::try_from_slice(data).is_ok() {
-            return Some(data.to_vec());
+/// パスコマンドをPDFのパス構築オペレーター（m / l / c / h）に変換
+fn pdf_path_operators(commands: &[crate::converter::PathCommand], page_height: f64) -> String {
+    use crate::converter::PathCommand;
+
+    let mut path_str = String::new();
+    let mut cur_x = 0.0_f64;
+    let mut cur_y = 0.0_f64;
+    for cmd in commands {
+        match cmd {
+            PathCommand::MoveTo(x, y) => {
+                cur_x = *x;
+                cur_y = *y;
+                path_str.push_str(&format!("{} {} m\n", x, page_height - y));
+            }
+            PathCommand::LineTo(x, y) => {
+                cur_x = *x;
+                cur_y = *y;
+                path_str.push_str(&format!("{} {} l\n", x, page_height - y));
+            }
+            PathCommand::QuadTo(qcx, qcy, x, y) => {
+                // Convert quadratic to cubic: cp1 = cur + 2/3*(qc - cur), cp2 = end + 2/3*(qc - end)
+                let cp1x = cur_x + 2.0 / 3.0 * (qcx - cur_x);
+                let cp1y = cur_y + 2.0 / 3.0 * (qcy - cur_y);
+                let cp2x = x + 2.0 / 3.0 * (qcx - x);
+                let cp2y = y + 2.0 / 3.0 * (qcy - y);
+                cur_x = *x;
+                cur_y = *y;
+                path_str.push_str(&format!(
+                    "{} {} {} {} {} {} c\n",
+                    cp1x, page_height - cp1y, cp2x, page_height - cp2y, x, page_height - y
+                ));
+            }
+            PathCommand::CubicTo(cx1, cy1, cx2, cy2, x, y) => {
+                cur_x = *x;
+                cur_y = *y;
+                path_str.push_str(&format!(
+                    "{} {} {} {} {} {} c\n",
+                    cx1, page_height - cy1, cx2, page_height - cy2, x, page_height - y
+                ));
+            }
+            PathCommand::ArcTo(_, _, _, _, _, x, y) => {
+                for [cx1, cy1, cx2, cy2, ex, ey] in cmd.arc_cubics((cur_x, cur_y)) {
+                    path_str.push_str(&format!(
+                        "{} {} {} {} {} {} c\n",
+                        cx1, page_height - cy1, cx2, page_height - cy2, ex, page_height - ey
+                    ));
+                }
+                cur_x = *x;
+                cur_y = *y;
+            }
+            PathCommand::Close => {
+                path_str.push_str("h\n");
+            }
         }
     }
-    // 内蔵フォントを試す
-    if let Some(data) = font_manager.builtin_japanese_font() {
-        if FontRef::try_from_slice(data).is_ok() {
-            return Some(data.to_vec());
+    path_str
+}
+
+/// ドキュメントメタデータから文書情報辞書（/Info）を生成
+fn build_info_dict(metadata: &Metadata) -> String {
+    let mut dict = String::from("<<");
+    let entries = [
+        ("Title", &metadata.title),
+        ("Author", &metadata.author),
+        ("Subject", &metadata.subject),
+        ("Keywords", &metadata.keywords),
+        ("Creator", &metadata.creator),
+    ];
+    for (key, value) in entries {
+        if let Some(v) = value.as_deref().filter(|v| !v.is_empty()) {
+            dict.push_str(&format!(" /{} {}", key, pdf_text_string(v)));
         }
     }
-    // LINE Seed JPを試す
-    if let Some(data) = font_manager.builtin_line_seed_jp() {
-        if FontRef::try_from_slice(data).is_ok() {
-            return Some(data.to_vec());
+    if let Some(date) = metadata.created.as_deref().and_then(format_pdf_date) {
+        dict.push_str(&format!(" /CreationDate ({})", date));
+    }
+    dict.push_str(&format!(
+        " /Producer (WASM Document Converter v{}) >>",
+        env!("CARGO_PKG_VERSION")
+    ));
+    dict
+}
+
+/// 文書情報用のテキスト文字列を生成
+/// ASCII印字可能文字のみならリテラル文字列、それ以外はBOM付きUTF-16BEのヘックス文字列
+fn pdf_text_string(text: &str) -> String {
+    if text.chars().all(|c| (' '..='~').contains(&c)) {
+        format!("({})", pdf_escape_string(text))
+    } else {
+        let mut hex = String::from("<FEFF");
+        for unit in text.encode_utf16() {
+            hex.push_str(&format!("{:04X}", unit));
         }
+        hex.push('>');
+        hex
     }
-    None
+}
+
+/// ISO 8601日時（例: "2024-01-15T10:30:00Z"）をPDF日付形式 "D:YYYYMMDDHHmmSS" に変換
+/// タイムゾーン表記は無視し、時刻が省略されている場合は0で補完します。
+fn format_pdf_date(iso: &str) -> Option<String> {
+    let (date, time) = match iso.find('T') {
+        Some(t) => (&iso[..t], &iso[t + 1..]),
+        None => (iso, ""),
+    };
+    // 時刻の後ろのタイムゾーン（"Z", "+09:00", "-05:00"）を除去
+    let time = time.split(['Z', '+', '-']).next().unwrap_or("");
+    let mut digits: String = date
+        .chars()
+        .chain(time.chars())
+        .filter(|c| c.is_ascii_digit())
+        .take(14)
+        .collect();
+    if digits.len() < 8 {
+        return None;
+    }
+    while digits.len() < 14 {
+        digits.push('0');
+    }
+    Some(format!("D:{}", digits))
 }
 
 /// テキストをASCIIに制限してHelveticaフォールバック用に使用
@@ -1338,8 +2788,63 @@ pub fn render_to_pdf_with_fonts(doc: &Document, font_manager: &FontManager) -> V
     writer.render(doc)
 }
 
+/// 出力オプションを指定してドキュメントをPDFバイト列に変換
+pub fn render_to_pdf_with_options(
+    doc: &Document,
+    font_manager: &FontManager,
+    options: &PdfOptions,
+) -> Vec<u8> {
+    let mut writer = PdfWriter::with_options(font_manager, options.clone());
+    writer.render(doc)
+}
+
+/// 出力オプションを指定してドキュメントをPDFバイト列に変換し、`attach_source` が有効なら変換元のファイルを添付
+pub fn render_to_pdf_with_source(
+    doc: &Document,
+    font_manager: &FontManager,
+    options: &PdfOptions,
+    filename: &str,
+    data: &[u8],
+) -> Vec<u8> {
+    let mut writer = PdfWriter::with_options(font_manager, options.clone());
+    if options.attach_source {
+        writer = writer.with_attachment(PdfAttachment { filename, data });
+    }
+    writer.render(doc)
+}
+
+/// ドキュメントの各ページを独立した1ページのPDFに変換し、ZIP（`page-001.pdf`, `page-002.pdf`, ...）にまとめる
+/// 各PDFには元のドキュメントのメタデータを引き継ぎます。
+pub fn render_to_pdf_zip(doc: &Document, font_manager: &FontManager, options: &PdfOptions) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut zip_buffer = Vec::new();
+    {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut zip_buffer));
+        let zip_options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        for (i, page) in doc.pages.iter().enumerate() {
+            let single = Document {
+                pages: vec![page.clone()],
+                metadata: doc.metadata.clone(),
+                warnings: Vec::new(),
+                embedded_fonts: Vec::new(),
+                feature_notes: Vec::new(),
+            };
+            let pdf = render_to_pdf_with_options(&single, font_manager, options);
+            if zip.start_file(format!("page-{:03}.pdf", i + 1), zip_options).is_ok() {
+                let _ = zip.write_all(&pdf);
+            }
+        }
+
+        let _ = zip.finish();
+    }
+    zip_buffer
+}
+
 /// JPEGバイト列から画像の幅と高さを抽出する
-fn extract_jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+pub(crate) fn extract_jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
     if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
         return None;
     }
@@ -1379,6 +2884,76 @@ fn extract_jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
     None
 }
 
+/// JPEGのフレーム情報（SOFマーカー）
+struct JpegFrameInfo {
+    /// 色成分数（1: グレースケール, 3: YCbCr/RGB, 4: CMYK/YCCK）
+    components: u8,
+    /// ベースライン（SOF0/SOF1のシーケンシャルHuffman）かどうか
+    baseline: bool,
+}
+
+impl JpegFrameInfo {
+    /// /DeviceRGB の /DCTDecode としてそのまま埋め込めるか
+    fn is_pdf_safe(&self) -> bool {
+        self.baseline && self.components == 3
+    }
+}
+
+/// JPEGのSOFマーカーから色成分数と符号化方式を取得
+fn parse_jpeg_frame(data: &[u8]) -> Option<JpegFrameInfo> {
+    if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
+        return None;
+    }
+    let mut i = 2;
+    while i + 3 < data.len() {
+        if data[i] != 0xFF {
+            i += 1;
+            continue;
+        }
+        let marker = data[i + 1];
+        if matches!(marker, 0xC0..=0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF) {
+            // FF Cx | 長さ(2) | 精度(1) | 高さ(2) | 幅(2) | 成分数(1)
+            let components = *data.get(i + 9)?;
+            return Some(JpegFrameInfo { components, baseline: matches!(marker, 0xC0 | 0xC1) });
+        }
+        let seg_len = ((data[i + 2] as usize) << 8) | (data[i + 3] as usize);
+        if seg_len < 2 {
+            return None;
+        }
+        i += 2 + seg_len;
+    }
+    None
+}
+
+/// 写真的な画像か（色数の多い画像）を簡易判定
+/// 最大4096画素を等間隔にサンプリングし、異なる色の数で判断します。
+fn is_photographic(rgb: &[u8]) -> bool {
+    const MAX_SAMPLES: usize = 4096;
+    let npixels = rgb.len() / 3;
+    if npixels == 0 {
+        return false;
+    }
+    let step = (npixels / MAX_SAMPLES).max(1);
+    let mut colors = std::collections::HashSet::new();
+    let mut samples = 0usize;
+    for px in rgb.chunks_exact(3).step_by(step) {
+        colors.insert([px[0], px[1], px[2]]);
+        samples += 1;
+    }
+    colors.len() > 256.min(samples / 4).max(16)
+}
+
+/// RGBバイト列をベースラインJPEGにエンコード（品質90）
+fn encode_baseline_jpeg(width: u32, height: u32, rgb: &[u8]) -> Option<Vec<u8>> {
+    const JPEG_QUALITY: u8 = 90;
+    let (w, h) = (u16::try_from(width).ok()?, u16::try_from(height).ok()?);
+    let mut out = Vec::new();
+    jpeg_encoder::Encoder::new(&mut out, JPEG_QUALITY)
+        .encode(rgb, w, h, jpeg_encoder::ColorType::Rgb)
+        .ok()?;
+    Some(out)
+}
+
 /// 画像データ（PNG/JPEG）をRGBバイト列にデコードする
 /// 戻り値: (width, height, rgb_data, optional_alpha_data)
 fn decode_image_to_raw_rgb(data: &[u8]) -> Option<(u32, u32, Vec<u8>, Option<Vec<u8>>)> {
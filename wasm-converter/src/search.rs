@@ -0,0 +1,111 @@
+// search.rs - テキスト検索
+//
+// 検索ハイライト（`Page::with_search_highlights`）で使用する、大文字・小文字を区別しない検索です。
+// 正規表現は regex クレートで照合するため、パターンに関わらず照合時間はテキストの長さに比例します。
+// `$` は選択肢の末尾（テキストの終端）にだけ書けます。
+
+use crate::converter::ConvertError;
+use regex_syntax::ast::{Ast, AssertionKind};
+
+/// コンパイル済みの検索パターン
+#[derive(Debug, Clone)]
+pub struct TextPattern {
+    regex: regex::Regex,
+}
+
+fn search_error(message: &str) -> ConvertError {
+    ConvertError::new("search", message)
+}
+
+impl TextPattern {
+    /// 検索文字列をコンパイル（`regex` が false の場合は文字列そのものを検索）
+    pub fn new(query: &str, regex: bool) -> Result<Self, ConvertError> {
+        if query.is_empty() {
+            return Err(search_error("検索文字列が空です"));
+        }
+        let pattern = if regex {
+            let ast = regex_syntax::ast::parse::Parser::new()
+                .parse(query)
+                .map_err(|e| search_error(&format!("正規表現が不正です: {}", e)))?;
+            if has_misplaced_end_anchor(&ast, true) {
+                return Err(search_error("'$' は正規表現（選択肢）の末尾にだけ指定できます"));
+            }
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let regex = regex::RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| search_error(&format!("正規表現が不正です: {}", e)))?;
+        Ok(Self { regex })
+    }
+
+    /// テキスト内の重ならない一致範囲（文字単位のインデックス、開始〜終了の半開区間）を先頭から順に返す
+    /// 空文字列への一致は含めません。
+    pub fn find_all(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut matches = Vec::new();
+        // 前の一致の終了位置（バイト・文字）から数え進める
+        let (mut last_byte, mut last_char) = (0, 0);
+        for m in self.regex.find_iter(text).filter(|m| !m.is_empty()) {
+            let start = last_char + text[last_byte..m.start()].chars().count();
+            let end = start + text[m.start()..m.end()].chars().count();
+            matches.push((start, end));
+            (last_byte, last_char) = (m.end(), end);
+        }
+        matches
+    }
+}
+
+/// 終端のアンカー（`$`）が、後ろに続く要素のある位置に書かれているか
+/// `at_end` は `ast` がパターン（選択肢）の末尾にあるかどうかです。
+fn has_misplaced_end_anchor(ast: &Ast, at_end: bool) -> bool {
+    match ast {
+        Ast::Assertion(assertion) => {
+            matches!(assertion.kind, AssertionKind::EndLine | AssertionKind::EndText) && !at_end
+        }
+        Ast::Concat(concat) => {
+            let last = concat.asts.len().saturating_sub(1);
+            concat.asts.iter().enumerate().any(|(i, ast)| has_misplaced_end_anchor(ast, at_end && i == last))
+        }
+        Ast::Alternation(alternation) => alternation.asts.iter().any(|ast| has_misplaced_end_anchor(ast, at_end)),
+        Ast::Group(group) => has_misplaced_end_anchor(&group.ast, at_end),
+        Ast::Repetition(repetition) => has_misplaced_end_anchor(&repetition.ast, false),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_pattern_literal_and_regex() {
+        let literal = TextPattern::new("alice", false).unwrap();
+        assert_eq!(literal.find_all("Alice and ALICE"), vec![(0, 5), (10, 15)]);
+        // 正規表現でない場合は記号もそのまま検索
+        assert_eq!(TextPattern::new("a.c", false).unwrap().find_all("abc a.c"), vec![(4, 7)]);
+
+        let regex = TextPattern::new(r"b[a-z]+\d?", true).unwrap();
+        assert_eq!(regex.find_all("Bob7, bOB x"), vec![(0, 4), (6, 9)]);
+        let anchored = TextPattern::new("^al|ce$", true).unwrap();
+        assert_eq!(anchored.find_all("alice alice"), vec![(0, 2), (9, 11)]);
+        // 一致範囲は文字単位
+        assert_eq!(TextPattern::new("straße", false).unwrap().find_all("日本 STRASSE Straße"), vec![(11, 17)]);
+
+        assert!(TextPattern::new("", false).is_err());
+        assert!(TextPattern::new("[abc", true).is_err());
+        assert!(TextPattern::new("*a", true).is_err());
+        assert!(TextPattern::new("a$b", true).is_err());
+        assert!(TextPattern::new("(a$)b", true).is_err());
+        assert!(TextPattern::new("a$|(b|c$)", true).is_ok());
+    }
+
+    #[test]
+    fn test_pathological_pattern_is_linear() {
+        // バックトラッキングでは指数時間になるパターン
+        let pattern = TextPattern::new("(a*)*b", true).unwrap();
+        let text = "a".repeat(100_000);
+        assert!(pattern.find_all(&text).is_empty());
+    }
+}
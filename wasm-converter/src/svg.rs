@@ -0,0 +1,719 @@
+// svg.rs - SVGラスタライザー
+//
+// PPTX に埋め込まれたSVG画像（asvg:svgBlip）をPNGに変換します。
+// 基本図形（rect / circle / ellipse / line / polyline / polygon / path）と g の transform・塗り・線を
+// ドキュメントモデルのパスに変換し、ページ画像のレンダラーで描画する簡易実装です。
+// テキスト・画像・グラデーション・クリップ・マスク・フィルターを使うSVGは正しく描画できないため、
+// 解析に失敗したものとして扱います（呼び出し側はPNGの代替画像を使います）。
+
+use quick_xml::events::{BytesStart, Event};
+
+use crate::converter::{Color, Page, PageElement, PathCommand};
+use crate::font_manager::FontManager;
+use crate::image_renderer::{self, ImageRenderConfig};
+
+/// ラスタライズする画像の幅・高さの上限（ピクセル）
+pub const MAX_RASTER_PX: u32 = 4096;
+
+/// 描画しない定義用の要素（中身ごと読み飛ばす）
+const SKIPPED_ELEMENTS: &[&[u8]] = &[b"defs", b"title", b"desc", b"metadata", b"style", b"symbol", b"marker"];
+
+/// 描画できない要素（含まれる場合はSVG全体を描画できないものとする）
+const UNSUPPORTED_ELEMENTS: &[&[u8]] = &[
+    b"text", b"image", b"use", b"foreignObject", b"clipPath", b"mask", b"filter", b"pattern",
+    b"linearGradient", b"radialGradient",
+];
+
+/// SVGを `width_px` × `height_px` に収まる（縦横比を保つ）PNGにラスタライズ
+/// 描画できない要素を含む・解析できない・描画する図形が無い場合は None。
+pub fn rasterize(data: &[u8], width_px: u32, height_px: u32) -> Option<Vec<u8>> {
+    let page = parse(data)?;
+    let config = ImageRenderConfig {
+        background: Color { r: 255, g: 255, b: 255, a: 0 },
+        target_width_px: Some(width_px.clamp(1, MAX_RASTER_PX)),
+        target_height_px: Some(height_px.clamp(1, MAX_RASTER_PX)),
+        supersample: 2,
+        ..ImageRenderConfig::default()
+    };
+    Some(image_renderer::render_page_to_image(&page, &config, &FontManager::new()))
+}
+
+/// SVGをページに変換（ページの大きさは viewBox、無ければ width / height）
+pub fn parse(data: &[u8]) -> Option<Page> {
+    let xml = std::str::from_utf8(data).ok()?;
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut page: Option<Page> = None;
+    // 開始タグごとの継承される書式（終了タグで戻す）
+    let mut stack: Vec<SvgStyle> = Vec::new();
+
+    loop {
+        let event = reader.read_event();
+        let (e, is_start) = match event {
+            Ok(Event::Start(ref e)) => (e.clone(), true),
+            Ok(Event::Empty(ref e)) => (e.clone(), false),
+            Ok(Event::End(_)) => {
+                stack.pop();
+                continue;
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => return None,
+            _ => continue,
+        };
+        let name = e.local_name();
+        let name = name.as_ref();
+        if UNSUPPORTED_ELEMENTS.contains(&name) {
+            return None;
+        }
+        if SKIPPED_ELEMENTS.contains(&name) {
+            if is_start {
+                reader.read_to_end(e.name()).ok()?;
+            }
+            continue;
+        }
+
+        let attrs = SvgAttributes::read(&e);
+        let parent = match (stack.last(), page.as_mut()) {
+            (Some(style), Some(_)) => style.clone(),
+            _ if name == b"svg" => {
+                let (root, style) = root_page(&attrs)?;
+                page = Some(root);
+                if is_start {
+                    stack.push(style);
+                }
+                continue;
+            }
+            // ルートが svg でない
+            _ => return None,
+        };
+        let style = parent.inherit(&attrs)?;
+        if let Some(element) = style.visible().then(|| shape_element(name, &attrs, &style)).flatten() {
+            page.as_mut()?.elements.push(element);
+        }
+        if is_start {
+            stack.push(style);
+        }
+    }
+
+    page.filter(|p| !p.elements.is_empty())
+}
+
+/// ルートの svg 要素から、ページと viewBox をページ座標に写す書式を作成
+fn root_page(attrs: &SvgAttributes) -> Option<(Page, SvgStyle)> {
+    let length = |name: &str| attrs.get(name).and_then(parse_length).filter(|v| *v > 0.0);
+    let view_box: Option<Vec<f64>> = attrs.get("viewBox").map(parse_numbers).filter(|v| v.len() == 4);
+    let (min_x, min_y, width, height) = match view_box {
+        Some(v) if v[2] > 0.0 && v[3] > 0.0 => (v[0], v[1], v[2], v[3]),
+        _ => (0.0, 0.0, length("width")?, length("height")?),
+    };
+    let page = Page { width, height, elements: Vec::new() };
+    let style = SvgStyle {
+        transform: [1.0, 0.0, 0.0, 1.0, -min_x, -min_y],
+        ..SvgStyle::default()
+    }
+    .inherit(attrs)?;
+    Some((page, style))
+}
+
+/// 図形要素をパスに変換（図形でない要素・大きさが0の図形は None）
+fn shape_element(name: &[u8], attrs: &SvgAttributes, style: &SvgStyle) -> Option<PageElement> {
+    let num = |key: &str| attrs.get(key).and_then(parse_length).unwrap_or(0.0);
+    let commands = match name {
+        b"rect" => {
+            let (x, y, w, h) = (num("x"), num("y"), num("width"), num("height"));
+            if w <= 0.0 || h <= 0.0 {
+                return None;
+            }
+            // rx / ry は片方だけ指定された場合もう片方と同じ
+            let (rx, ry) = match (attrs.get("rx").and_then(parse_length), attrs.get("ry").and_then(parse_length)) {
+                (Some(rx), Some(ry)) => (rx, ry),
+                (Some(r), None) | (None, Some(r)) => (r, r),
+                (None, None) => (0.0, 0.0),
+            };
+            let (rx, ry) = (rx.clamp(0.0, w / 2.0), ry.clamp(0.0, h / 2.0));
+            if rx == 0.0 || ry == 0.0 {
+                PathCommand::rect(x, y, w, h)
+            } else {
+                let arc = |x, y| PathCommand::ArcTo(rx, ry, 0.0, false, true, x, y);
+                vec![
+                    PathCommand::MoveTo(x + rx, y),
+                    PathCommand::LineTo(x + w - rx, y),
+                    arc(x + w, y + ry),
+                    PathCommand::LineTo(x + w, y + h - ry),
+                    arc(x + w - rx, y + h),
+                    PathCommand::LineTo(x + rx, y + h),
+                    arc(x, y + h - ry),
+                    PathCommand::LineTo(x, y + ry),
+                    arc(x + rx, y),
+                    PathCommand::Close,
+                ]
+            }
+        }
+        b"circle" | b"ellipse" => {
+            let (cx, cy) = (num("cx"), num("cy"));
+            let (rx, ry) = if name == b"circle" { (num("r"), num("r")) } else { (num("rx"), num("ry")) };
+            if rx <= 0.0 || ry <= 0.0 {
+                return None;
+            }
+            vec![
+                PathCommand::MoveTo(cx + rx, cy),
+                PathCommand::ArcTo(rx, ry, 0.0, false, true, cx - rx, cy),
+                PathCommand::ArcTo(rx, ry, 0.0, false, true, cx + rx, cy),
+                PathCommand::Close,
+            ]
+        }
+        b"line" => vec![PathCommand::MoveTo(num("x1"), num("y1")), PathCommand::LineTo(num("x2"), num("y2"))],
+        b"polyline" | b"polygon" => {
+            let points = parse_numbers(attrs.get("points")?);
+            let mut commands: Vec<PathCommand> = points
+                .chunks_exact(2)
+                .enumerate()
+                .map(|(i, p)| if i == 0 { PathCommand::MoveTo(p[0], p[1]) } else { PathCommand::LineTo(p[0], p[1]) })
+                .collect();
+            if name == b"polygon" && !commands.is_empty() {
+                commands.push(PathCommand::Close);
+            }
+            commands
+        }
+        b"path" => parse_path_data(attrs.get("d")?),
+        _ => return None,
+    };
+    if commands.len() < 2 {
+        return None;
+    }
+    // 直線は塗りつぶさない
+    let fill = if name == b"line" { None } else { style.fill_color() };
+    let stroke = style.stroke_color();
+    if fill.is_none() && stroke.is_none() {
+        return None;
+    }
+    Some(PageElement::Path {
+        commands: transform_commands(&commands, &style.transform),
+        fill,
+        stroke,
+        stroke_width: style.stroke_width * style.scale_factor(),
+        stroke_style: Default::default(),
+        rotation_deg: 0.0,
+    })
+}
+
+/// 要素の属性（style 属性の宣言は同名の属性より優先）
+struct SvgAttributes(Vec<(String, String)>);
+
+impl SvgAttributes {
+    fn read(e: &BytesStart) -> Self {
+        let mut attrs: Vec<(String, String)> = e
+            .attributes()
+            .flatten()
+            .map(|attr| {
+                (
+                    String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string(),
+                    attr.unescape_value().map(|v| v.to_string()).unwrap_or_default(),
+                )
+            })
+            .collect();
+        let declarations: Vec<(String, String)> = attrs
+            .iter()
+            .filter(|(key, _)| key == "style")
+            .flat_map(|(_, value)| value.split(';'))
+            .filter_map(|decl| decl.split_once(':'))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect();
+        attrs.extend(declarations);
+        Self(attrs)
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().rev().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// 親から継承される書式と座標変換
+#[derive(Debug, Clone)]
+struct SvgStyle {
+    /// 塗り（None は "none"）
+    fill: Option<Color>,
+    stroke: Option<Color>,
+    stroke_width: f64,
+    fill_opacity: f64,
+    stroke_opacity: f64,
+    /// 要素と祖先の opacity の積
+    opacity: f64,
+    hidden: bool,
+    /// 要素座標 → ページ座標のアフィン変換 [a, b, c, d, e, f]
+    transform: [f64; 6],
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            fill: Some(Color::BLACK),
+            stroke: None,
+            stroke_width: 1.0,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+            opacity: 1.0,
+            hidden: false,
+            transform: [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+        }
+    }
+}
+
+impl SvgStyle {
+    /// 要素の属性を反映した書式（グラデーション等の描画できない塗り・クリップを使う場合は None）
+    fn inherit(&self, attrs: &SvgAttributes) -> Option<Self> {
+        if ["clip-path", "mask", "filter"].iter().any(|key| attrs.get(key).is_some_and(|v| v != "none")) {
+            return None;
+        }
+        let mut style = self.clone();
+        if let Some(fill) = attrs.get("fill") {
+            style.fill = parse_paint(fill)?;
+        }
+        if let Some(stroke) = attrs.get("stroke") {
+            style.stroke = parse_paint(stroke)?;
+        }
+        let number = |key: &str| attrs.get(key).and_then(parse_length);
+        style.stroke_width = number("stroke-width").unwrap_or(style.stroke_width);
+        style.fill_opacity = number("fill-opacity").map_or(style.fill_opacity, |v| v.clamp(0.0, 1.0));
+        style.stroke_opacity = number("stroke-opacity").map_or(style.stroke_opacity, |v| v.clamp(0.0, 1.0));
+        style.opacity *= number("opacity").map_or(1.0, |v| v.clamp(0.0, 1.0));
+        style.hidden |= attrs.get("display") == Some("none");
+        if let Some(visibility) = attrs.get("visibility") {
+            style.hidden = visibility == "hidden" || visibility == "collapse";
+        }
+        if let Some(transform) = attrs.get("transform") {
+            style.transform = multiply(&style.transform, &parse_transform(transform)?);
+        }
+        Some(style)
+    }
+
+    fn visible(&self) -> bool {
+        !self.hidden && self.opacity > 0.0
+    }
+
+    fn fill_color(&self) -> Option<Color> {
+        self.fill.map(|c| with_opacity(c, self.fill_opacity * self.opacity))
+    }
+
+    fn stroke_color(&self) -> Option<Color> {
+        self.stroke.filter(|_| self.stroke_width > 0.0).map(|c| with_opacity(c, self.stroke_opacity * self.opacity))
+    }
+
+    /// 線幅に掛ける座標変換の拡大率（縦横の拡大率の相乗平均）
+    fn scale_factor(&self) -> f64 {
+        let [a, b, c, d, _, _] = self.transform;
+        (a * d - b * c).abs().sqrt()
+    }
+}
+
+fn with_opacity(color: Color, opacity: f64) -> Color {
+    Color { a: (color.a as f64 * opacity).round() as u8, ..color }
+}
+
+/// 塗りの指定を解析（"none" は Some(None)、グラデーション等の参照は描画できないため None）
+fn parse_paint(value: &str) -> Option<Option<Color>> {
+    match value.trim() {
+        "none" | "transparent" => Some(None),
+        "currentColor" => Some(Some(Color::BLACK)),
+        v if v.starts_with("url(") => None,
+        v => parse_color(v).map(Some),
+    }
+}
+
+/// CSSの色（#rgb / #rrggbb / rgb(r, g, b) / 基本の色名）を解析
+pub(crate) fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        let digit = |i: usize| u8::from_str_radix(hex.get(i..i + 1)?, 16).ok();
+        return match hex.len() {
+            3 => Some(Color::rgb(digit(0)? * 17, digit(1)? * 17, digit(2)? * 17)),
+            6 => {
+                let byte = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+                Some(Color::rgb(byte(0)?, byte(2)?, byte(4)?))
+            }
+            _ => None,
+        };
+    }
+    if let Some(args) = value.strip_prefix("rgb(").and_then(|v| v.strip_suffix(')')) {
+        let channels: Vec<u8> = args
+            .split(',')
+            .map(|c| {
+                let c = c.trim();
+                match c.strip_suffix('%') {
+                    Some(pct) => pct.trim().parse::<f64>().ok().map(|p| p * 2.55),
+                    None => c.parse::<f64>().ok(),
+                }
+                .map(|v| v.round().clamp(0.0, 255.0) as u8)
+            })
+            .collect::<Option<_>>()?;
+        return (channels.len() == 3).then(|| Color::rgb(channels[0], channels[1], channels[2]));
+    }
+    let (r, g, b) = match value.to_ascii_lowercase().as_str() {
+        "black" => (0, 0, 0),
+        "white" => (255, 255, 255),
+        "red" => (255, 0, 0),
+        "lime" => (0, 255, 0),
+        "green" => (0, 128, 0),
+        "blue" => (0, 0, 255),
+        "yellow" => (255, 255, 0),
+        "orange" => (255, 165, 0),
+        "aqua" | "cyan" => (0, 255, 255),
+        "fuchsia" | "magenta" => (255, 0, 255),
+        "gray" | "grey" => (128, 128, 128),
+        "silver" => (192, 192, 192),
+        "maroon" => (128, 0, 0),
+        "olive" => (128, 128, 0),
+        "purple" => (128, 0, 128),
+        "teal" => (0, 128, 128),
+        "navy" => (0, 0, 128),
+        _ => return None,
+    };
+    Some(Color::rgb(r, g, b))
+}
+
+/// 長さを解析（px / pt 等の単位は無視してユーザー単位として扱う。% は解析しない）
+fn parse_length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if value.ends_with('%') {
+        return None;
+    }
+    let number = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    number.trim().parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// 空白・カンマ区切りの数値列を解析
+fn parse_numbers(value: &str) -> Vec<f64> {
+    let mut numbers = Vec::new();
+    let mut lexer = NumberLexer::new(value);
+    while let Some(v) = lexer.number() {
+        numbers.push(v);
+    }
+    numbers
+}
+
+/// transform 属性（matrix / translate / scale / rotate / skewX / skewY の並び）を1つの行列に合成
+fn parse_transform(value: &str) -> Option<[f64; 6]> {
+    let mut result = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+    for part in value.split(')') {
+        let Some((name, args)) = part.split_once('(') else {
+            if part.trim().trim_matches(',').trim().is_empty() {
+                continue;
+            }
+            return None;
+        };
+        let v = parse_numbers(args);
+        let arg = |i: usize| v.get(i).copied();
+        let m = match name.trim().trim_start_matches(',').trim() {
+            "matrix" if v.len() == 6 => [v[0], v[1], v[2], v[3], v[4], v[5]],
+            "translate" => [1.0, 0.0, 0.0, 1.0, arg(0)?, arg(1).unwrap_or(0.0)],
+            "scale" => {
+                let sx = arg(0)?;
+                [sx, 0.0, 0.0, arg(1).unwrap_or(sx), 0.0, 0.0]
+            }
+            "rotate" => {
+                let (sin, cos) = arg(0)?.to_radians().sin_cos();
+                let (cx, cy) = (arg(1).unwrap_or(0.0), arg(2).unwrap_or(0.0));
+                let rotation = [cos, sin, -sin, cos, 0.0, 0.0];
+                multiply(&multiply(&[1.0, 0.0, 0.0, 1.0, cx, cy], &rotation), &[1.0, 0.0, 0.0, 1.0, -cx, -cy])
+            }
+            "skewX" => [1.0, 0.0, arg(0)?.to_radians().tan(), 1.0, 0.0, 0.0],
+            "skewY" => [1.0, arg(0)?.to_radians().tan(), 0.0, 1.0, 0.0, 0.0],
+            _ => return None,
+        };
+        result = multiply(&result, &m);
+    }
+    Some(result)
+}
+
+/// アフィン変換の合成（`n` を適用してから `m` を適用）
+fn multiply(m: &[f64; 6], n: &[f64; 6]) -> [f64; 6] {
+    [
+        m[0] * n[0] + m[2] * n[1],
+        m[1] * n[0] + m[3] * n[1],
+        m[0] * n[2] + m[2] * n[3],
+        m[1] * n[2] + m[3] * n[3],
+        m[0] * n[4] + m[2] * n[5] + m[4],
+        m[1] * n[4] + m[3] * n[5] + m[5],
+    ]
+}
+
+/// パスに座標変換を適用（弧は変換で歪むため先に三次ベジェ曲線に変換）
+fn transform_commands(commands: &[PathCommand], m: &[f64; 6]) -> Vec<PathCommand> {
+    let map = |x: f64, y: f64| (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5]);
+    let mut result = Vec::with_capacity(commands.len());
+    let mut current = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+    for command in commands {
+        match *command {
+            PathCommand::MoveTo(x, y) => {
+                let (px, py) = map(x, y);
+                result.push(PathCommand::MoveTo(px, py));
+                current = (x, y);
+                start = current;
+            }
+            PathCommand::LineTo(x, y) => {
+                let (px, py) = map(x, y);
+                result.push(PathCommand::LineTo(px, py));
+                current = (x, y);
+            }
+            PathCommand::QuadTo(cx, cy, x, y) => {
+                let ((pcx, pcy), (px, py)) = (map(cx, cy), map(x, y));
+                result.push(PathCommand::QuadTo(pcx, pcy, px, py));
+                current = (x, y);
+            }
+            PathCommand::CubicTo(c1x, c1y, c2x, c2y, x, y) => {
+                let ((a, b), (c, d), (e, f)) = (map(c1x, c1y), map(c2x, c2y), map(x, y));
+                result.push(PathCommand::CubicTo(a, b, c, d, e, f));
+                current = (x, y);
+            }
+            PathCommand::ArcTo(rx, ry, _, _, _, x, y) => {
+                let cubics = command.arc_cubics(current);
+                if cubics.is_empty() && (rx == 0.0 || ry == 0.0) {
+                    let (px, py) = map(x, y);
+                    result.push(PathCommand::LineTo(px, py));
+                }
+                for [c1x, c1y, c2x, c2y, ex, ey] in cubics {
+                    let ((a, b), (c, d), (e, f)) = (map(c1x, c1y), map(c2x, c2y), map(ex, ey));
+                    result.push(PathCommand::CubicTo(a, b, c, d, e, f));
+                }
+                current = (x, y);
+            }
+            PathCommand::Close => {
+                result.push(PathCommand::Close);
+                current = start;
+            }
+        }
+    }
+    result
+}
+
+/// パスデータ（d 属性）を絶対座標のコマンド列に変換
+/// 解析できない箇所以降は無視します。
+fn parse_path_data(d: &str) -> Vec<PathCommand> {
+    let mut lexer = NumberLexer::new(d);
+    let mut path = PathBuilder::default();
+    let mut command = b' ';
+    loop {
+        if let Some(c) = lexer.command() {
+            command = c;
+        } else if lexer.at_end() || command == b' ' {
+            break;
+        } else if command == b'M' || command == b'm' {
+            // M の後に続く座標は LineTo
+            command = if command == b'M' { b'L' } else { b'l' };
+        }
+        if path.segment(&mut lexer, command).is_none() {
+            break;
+        }
+        if command.eq_ignore_ascii_case(&b'Z') {
+            // Z の後は次のコマンド文字が必要
+            command = b' ';
+        }
+    }
+    path.commands
+}
+
+/// パスデータの解析中の状態
+#[derive(Default)]
+struct PathBuilder {
+    commands: Vec<PathCommand>,
+    current: (f64, f64),
+    /// 現在のサブパスの始点
+    start: (f64, f64),
+    /// S / T で反転する直前の制御点
+    last_cubic: Option<(f64, f64)>,
+    last_quad: Option<(f64, f64)>,
+}
+
+impl PathBuilder {
+    /// コマンド1つ分の引数を読んで絶対座標のコマンドを追加
+    fn segment(&mut self, lexer: &mut NumberLexer, command: u8) -> Option<()> {
+        let (ox, oy) = if command.is_ascii_lowercase() { self.current } else { (0.0, 0.0) };
+        let point = |lexer: &mut NumberLexer| Some((lexer.number()? + ox, lexer.number()? + oy));
+        let reflect = |control: Option<(f64, f64)>, (x, y): (f64, f64)| {
+            control.map_or((x, y), |(cx, cy)| (2.0 * x - cx, 2.0 * y - cy))
+        };
+        let (mut last_cubic, mut last_quad) = (None, None);
+        let end = match command.to_ascii_uppercase() {
+            b'M' => {
+                let p = point(lexer)?;
+                self.commands.push(PathCommand::MoveTo(p.0, p.1));
+                self.start = p;
+                p
+            }
+            b'L' => {
+                let p = point(lexer)?;
+                self.commands.push(PathCommand::LineTo(p.0, p.1));
+                p
+            }
+            b'H' => {
+                let p = (lexer.number()? + ox, self.current.1);
+                self.commands.push(PathCommand::LineTo(p.0, p.1));
+                p
+            }
+            b'V' => {
+                let p = (self.current.0, lexer.number()? + oy);
+                self.commands.push(PathCommand::LineTo(p.0, p.1));
+                p
+            }
+            b'C' | b'S' => {
+                let c1 = if command.eq_ignore_ascii_case(&b'C') {
+                    point(lexer)?
+                } else {
+                    reflect(self.last_cubic, self.current)
+                };
+                let c2 = point(lexer)?;
+                let p = point(lexer)?;
+                self.commands.push(PathCommand::CubicTo(c1.0, c1.1, c2.0, c2.1, p.0, p.1));
+                last_cubic = Some(c2);
+                p
+            }
+            b'Q' | b'T' => {
+                let c = if command.eq_ignore_ascii_case(&b'Q') {
+                    point(lexer)?
+                } else {
+                    reflect(self.last_quad, self.current)
+                };
+                let p = point(lexer)?;
+                self.commands.push(PathCommand::QuadTo(c.0, c.1, p.0, p.1));
+                last_quad = Some(c);
+                p
+            }
+            b'A' => {
+                let (rx, ry, rotation) = (lexer.number()?, lexer.number()?, lexer.number()?);
+                let (large_arc, sweep) = (lexer.flag()?, lexer.flag()?);
+                let p = point(lexer)?;
+                self.commands.push(PathCommand::ArcTo(rx, ry, rotation, large_arc, sweep, p.0, p.1));
+                p
+            }
+            b'Z' => {
+                self.commands.push(PathCommand::Close);
+                self.start
+            }
+            _ => return None,
+        };
+        self.current = end;
+        self.last_cubic = last_cubic;
+        self.last_quad = last_quad;
+        Some(())
+    }
+}
+
+/// パスデータ・数値列の字句解析
+struct NumberLexer<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> NumberLexer<'a> {
+    fn new(value: &'a str) -> Self {
+        Self { bytes: value.as_bytes(), pos: 0 }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.pos < self.bytes.len() && (self.bytes[self.pos].is_ascii_whitespace() || self.bytes[self.pos] == b',') {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.pos >= self.bytes.len()
+    }
+
+    /// 次がコマンド文字なら読み進めて返す
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let c = *self.bytes.get(self.pos)?;
+        (c.is_ascii_alphabetic() && c != b'e' && c != b'E').then(|| {
+            self.pos += 1;
+            c
+        })
+    }
+
+    /// 弧のフラグ（区切り無しで続く "0" / "1"）
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.bytes.get(self.pos)? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(flag)
+    }
+
+    /// 数値（符号・小数点・指数を含む。"1.5.5" は 1.5 と .5）
+    fn number(&mut self) -> Option<f64> {
+        self.skip_separators();
+        let start = self.pos;
+        let mut end = start;
+        if matches!(self.bytes.get(end), Some(b'+' | b'-')) {
+            end += 1;
+        }
+        let mut seen_dot = false;
+        let mut seen_digit = false;
+        while let Some(&c) = self.bytes.get(end) {
+            match c {
+                b'0'..=b'9' => seen_digit = true,
+                b'.' if !seen_dot => seen_dot = true,
+                _ => break,
+            }
+            end += 1;
+        }
+        if !seen_digit {
+            return None;
+        }
+        if matches!(self.bytes.get(end), Some(b'e' | b'E')) {
+            let mut exp_end = end + 1;
+            if matches!(self.bytes.get(exp_end), Some(b'+' | b'-')) {
+                exp_end += 1;
+            }
+            if self.bytes.get(exp_end).is_some_and(u8::is_ascii_digit) {
+                while self.bytes.get(exp_end).is_some_and(u8::is_ascii_digit) {
+                    exp_end += 1;
+                }
+                end = exp_end;
+            }
+        }
+        let value = std::str::from_utf8(&self.bytes[start..end]).ok()?.parse::<f64>().ok()?;
+        self.pos = end;
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path_data_relative_and_arcs() {
+        let commands = parse_path_data("M10 10h20v-5l-5-5zm1,1 a5 5 0 01 10 0");
+        assert_eq!(commands[0], PathCommand::MoveTo(10.0, 10.0));
+        assert_eq!(commands[1], PathCommand::LineTo(30.0, 10.0));
+        assert_eq!(commands[2], PathCommand::LineTo(30.0, 5.0));
+        assert_eq!(commands[3], PathCommand::LineTo(25.0, 0.0));
+        assert_eq!(commands[4], PathCommand::Close);
+        // Z の後の相対移動はサブパスの始点から
+        assert_eq!(commands[5], PathCommand::MoveTo(11.0, 11.0));
+        assert_eq!(commands[6], PathCommand::ArcTo(5.0, 5.0, 0.0, false, true, 21.0, 11.0));
+    }
+
+    #[test]
+    fn test_parse_maps_view_box_and_transform() {
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="10 10 100 50">
+            <g transform="translate(20 0) scale(2)" fill="#00f"><rect x="0" y="5" width="10" height="10"/></g>
+        </svg>"##;
+        let page = parse(svg).unwrap();
+        assert_eq!((page.width, page.height), (100.0, 50.0));
+        let PageElement::Path { commands, fill, .. } = &page.elements[0] else {
+            panic!("Expected path");
+        };
+        assert_eq!(*fill, Some(Color::rgb(0, 0, 255)));
+        assert_eq!(commands[0], PathCommand::MoveTo(10.0, 0.0));
+        assert_eq!(commands[2], PathCommand::LineTo(30.0, 20.0));
+
+        // グラデーションの塗りは描画できない
+        let gradient = br##"<svg viewBox="0 0 10 10"><defs/><rect width="10" height="10" fill="url(#g)"/></svg>"##;
+        assert!(parse(gradient).is_none());
+    }
+}
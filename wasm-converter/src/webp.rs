@@ -0,0 +1,110 @@
+// webp.rs - WebPエンコーダー
+//
+// image-webp クレートでRGBAピクセルデータをWebPにエンコードします。
+// image-webp が符号化できるのは可逆形式（VP8L）だけのため、非可逆を指定した場合も VP8L で出力し、
+// 品質に応じて各チャンネルの下位ビットを丸める（ニアロスレス）ことでファイルを小さくします。
+
+use image_webp::{ColorType, WebPEncoder};
+
+/// VP8Lで表現できる画像の幅・高さの上限
+pub const MAX_DIMENSION: u32 = 16384;
+
+/// RGBAピクセルデータをWebPにエンコード
+/// `lossless` が false で `quality` が100未満の場合は、品質に応じて各チャンネルの下位ビットを丸めてから符号化します。
+/// 幅・高さが `MAX_DIMENSION` を超える場合は None を返します。
+pub fn encode_rgba(pixels: &[u8], width: u32, height: u32, quality: u8, lossless: bool) -> Option<Vec<u8>> {
+    if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return None;
+    }
+    let pixels = pixels.get(..(width * height * 4) as usize)?;
+    let shift = if lossless { 0 } else { near_lossless_shift(quality) };
+    let quantized: Vec<u8>;
+    let pixels = if shift == 0 {
+        pixels
+    } else {
+        quantized = pixels.iter().map(|&c| quantize(c, shift)).collect();
+        &quantized
+    };
+    let mut out = Vec::new();
+    WebPEncoder::new(&mut out).encode(pixels, width, height, ColorType::Rgba8).ok()?;
+    Some(out)
+}
+
+/// 品質（0〜100）から丸める下位ビット数を求める
+fn near_lossless_shift(quality: u8) -> u32 {
+    match quality {
+        100.. => 0,
+        80..=99 => 1,
+        60..=79 => 2,
+        40..=59 => 3,
+        _ => 4,
+    }
+}
+
+/// 下位 `shift` ビットを四捨五入で丸める
+fn quantize(value: u8, shift: u32) -> u8 {
+    if shift == 0 {
+        return value;
+    }
+    let step = 1u32 << shift;
+    (((value as u32 + step / 2) / step) * step).min(255) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_oversized_image() {
+        assert!(encode_rgba(&[], MAX_DIMENSION + 1, 1, 100, true).is_none());
+        assert!(encode_rgba(&[0; 4], 1, 1, 100, true).is_some());
+    }
+
+    /// 後方参照・半透明を含むテスト画像
+    fn sample_pixels(width: u32, height: u32) -> Vec<u8> {
+        (0..width * height)
+            .flat_map(|i| {
+                let (x, y) = (i % width, i / width);
+                if y % 4 == 0 {
+                    [200, 30, 60, 255]
+                } else {
+                    [(x * 7) as u8, (y * 13) as u8, (x * y) as u8, if x < 3 { 128 } else { 255 }]
+                }
+            })
+            .collect()
+    }
+
+    fn decode(webp: &[u8], width: u32, height: u32) -> Vec<u8> {
+        let mut decoder = image_webp::WebPDecoder::new(std::io::Cursor::new(webp)).unwrap();
+        assert_eq!(decoder.dimensions(), (width, height));
+        let mut out = vec![0; decoder.output_buffer_size().unwrap()];
+        decoder.read_image(&mut out).unwrap();
+        if !decoder.has_alpha() {
+            return out.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect();
+        }
+        out
+    }
+
+    #[test]
+    fn test_lossless_roundtrip() {
+        let (width, height) = (37, 21);
+        let pixels = sample_pixels(width, height);
+        let webp = encode_rgba(&pixels, width, height, 50, true).unwrap();
+        assert_eq!(decode(&webp, width, height), pixels);
+
+        // 品質100では非可逆を指定しても丸めない
+        let opaque: Vec<u8> = pixels.chunks_exact(4).flat_map(|p| [p[0], p[1], p[2], 255]).collect();
+        let webp = encode_rgba(&opaque, width, height, 100, false).unwrap();
+        assert_eq!(decode(&webp, width, height), opaque);
+    }
+
+    #[test]
+    fn test_near_lossless_stays_within_quantization_step() {
+        let (width, height) = (37, 21);
+        let pixels = sample_pixels(width, height);
+        let webp = encode_rgba(&pixels, width, height, 60, false).unwrap();
+        let decoded = decode(&webp, width, height);
+        let expected: Vec<u8> = pixels.iter().map(|&c| quantize(c, near_lossless_shift(60))).collect();
+        assert_eq!(decoded, expected);
+    }
+}
@@ -29,6 +29,78 @@ fn test_csv_to_pdf_full_pipeline() {
     assert!(pdf.starts_with(b"%PDF"));
 }
 
+#[test]
+fn test_tsv_and_psv_split_on_fixed_delimiter() {
+    use wasm_document_converter::converter::PageElement;
+
+    let cell_rows = |ext: &str, input: &[u8]| -> Vec<Vec<String>> {
+        let doc = formats::convert_by_extension(ext, input).unwrap();
+        match &doc.pages[0].elements[0] {
+            PageElement::TableBlock { table, .. } => table
+                .rows
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.text.clone()).collect())
+                .collect(),
+            other => panic!("テーブルではありません: {:?}", other),
+        }
+    };
+
+    // カンマを含むデータでもタブだけで列を分ける
+    let tsv = cell_rows("tsv", b"Name\tAddress\tScore\nTaro\tTokyo, Japan\t1,000\n");
+    assert_eq!(tsv, vec![vec!["Name", "Address", "Score"], vec!["Taro", "Tokyo, Japan", "1,000"]]);
+
+    let psv = cell_rows("psv", b"a|b,c|d\n");
+    assert_eq!(psv, vec![vec!["a", "b,c", "d"]]);
+    assert_eq!(detect_format("data.tsv"), Some("tsv"));
+    assert!(formats::supported_formats().iter().any(|(_, exts)| exts.contains(&"psv")));
+}
+
+#[test]
+fn test_html_heading_and_table_to_pdf() {
+    use wasm_document_converter::converter::PageElement;
+
+    let html = r#"<!DOCTYPE html>
+<html><head><title>売上報告</title><style>h1 { color: red }</style></head>
+<body>
+  <h1 style="color:#336699">四半期の売上</h1>
+  <p>本文の<b>段落</b>です。</p>
+  <table>
+    <tr><th>地域</th><th>売上</th></tr>
+    <tr><td>東京</td><td>1,200</td></tr>
+    <tr><td colspan="2">合計 &amp; 備考</td></tr>
+  </table>
+</body></html>"#;
+    let doc = formats::convert_by_extension("html", html.as_bytes()).unwrap();
+    assert_eq!(doc.metadata.title.as_deref(), Some("売上報告"));
+
+    let elements = &doc.pages[0].elements;
+    let heading = elements
+        .iter()
+        .find_map(|e| match e {
+            PageElement::Text { text, style, .. } if text == "四半期の売上" => Some(style),
+            _ => None,
+        })
+        .expect("見出しのテキストがありません");
+    assert!(heading.bold && heading.font_size > FontStyle::default().font_size);
+    assert_eq!((heading.color.r, heading.color.g, heading.color.b), (0x33, 0x66, 0x99));
+
+    let table = elements
+        .iter()
+        .find_map(|e| match e {
+            PageElement::TableBlock { table, .. } => Some(table),
+            _ => None,
+        })
+        .expect("テーブルがありません");
+    let texts: Vec<Vec<&str>> = table.rows.iter().map(|row| row.iter().map(|c| c.text.as_str()).collect()).collect();
+    assert_eq!(texts, vec![vec!["地域", "売上"], vec!["東京", "1,200"], vec!["合計 & 備考", ""]]);
+    assert!(table.rows[0][0].style.bold && !table.rows[1][0].style.bold);
+    assert_eq!((table.rows[2][0].col_span, table.rows[2][1].col_span), (2, 0));
+
+    assert_eq!(detect_format("report.htm"), Some("html"));
+    let pdf = pdf_writer::render_to_pdf(&doc);
+    assert!(pdf.starts_with(b"%PDF"));
+}
+
 #[test]
 fn test_txt_to_images_zip_full_pipeline() {
     let input = "Test Document\nPage 1 content".as_bytes();
@@ -102,6 +174,50 @@ fn test_document_from_text_lines_pagination() {
     assert!(doc.pages.len() > 1, "Expected multiple pages for 200 lines");
 }
 
+#[test]
+fn test_paper_size_dimensions() {
+    use wasm_document_converter::converter::{Page, PageOrientation, PaperSize, TextFlowOptions};
+
+    let size = |page: Page| (page.width.round(), page.height.round());
+    assert_eq!(size(Page::with_size(PaperSize::A3)), (842.0, 1191.0));
+    assert_eq!(size(Page::with_size(PaperSize::Letter)), (612.0, 792.0));
+    assert_eq!(size(Page::with_size(PaperSize::A4)), size(Page::a4()));
+    assert_eq!(size(Page::with_size_oriented(PaperSize::A5, PageOrientation::Landscape)), (595.0, 420.0));
+    assert_eq!(size(Page::with_size(PaperSize::Custom { width: 300.0, height: 400.0 })), (300.0, 400.0));
+
+    // TXT / CSV はオプションの用紙サイズ・向きでページを作る
+    let options = TextFlowOptions {
+        paper_size: PaperSize::B5,
+        orientation: PageOrientation::Landscape,
+        ..TextFlowOptions::default()
+    };
+    for (ext, data) in [("txt", &b"hello"[..]), ("csv", &b"a,b\n1,2"[..])] {
+        let doc = formats::convert_by_extension_with_options(ext, data, &options).unwrap();
+        assert_eq!(size(doc.pages[0].clone()), (729.0, 516.0), "{}", ext);
+    }
+}
+
+#[test]
+fn test_txt_tabs_advance_to_tab_stops() {
+    use wasm_document_converter::converter::{PageElement, TabStop};
+
+    let doc = formats::convert_by_extension("txt", b"a\tb\tc").unwrap();
+    let texts: Vec<(&str, f64)> = doc.pages[0]
+        .elements
+        .iter()
+        .filter_map(|e| match e {
+            PageElement::Text { x, text, .. } => Some((text.as_str(), *x)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(texts.iter().map(|(t, _)| *t).collect::<Vec<_>>(), ["a", "b", "c"]);
+    // 固定幅の空白ではなく、連続するタブ位置に揃う
+    let left = texts[0].1;
+    for (i, (_, x)) in texts.iter().enumerate() {
+        assert!((x - (left + TabStop::DEFAULT_INTERVAL * i as f64)).abs() < 0.01, "{:?}", texts);
+    }
+}
+
 #[test]
 fn test_unsupported_format_error() {
     let result = formats::convert_by_extension("xyz", b"data");
@@ -278,6 +394,15 @@ fn test_line_seed_jp_font_manager() {
 
 /// 最小限の有効なDOCXファイルを作成するヘルパー
 fn create_sample_docx(text_paragraphs: &[&str]) -> Vec<u8> {
+    let body: String = text_paragraphs
+        .iter()
+        .map(|para| format!("\n    <w:p><w:r><w:t>{}</w:t></w:r></w:p>", para))
+        .collect();
+    create_docx_with_body(&body)
+}
+
+/// 任意の w:body 内容でDOCXファイルを作成するヘルパー
+fn create_docx_with_body(body_xml: &str) -> Vec<u8> {
     use std::io::Write;
     let buf = Vec::new();
     let cursor = std::io::Cursor::new(buf);
@@ -305,12 +430,7 @@ fn create_sample_docx(text_paragraphs: &[&str]) -> Vec<u8> {
     let mut doc_xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
 <w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
   <w:body>"#);
-    for para in text_paragraphs {
-        doc_xml.push_str(&format!(
-            "\n    <w:p><w:r><w:t>{}</w:t></w:r></w:p>",
-            para
-        ));
-    }
+    doc_xml.push_str(body_xml);
     doc_xml.push_str("\n  </w:body>\n</w:document>");
     zip.write_all(doc_xml.as_bytes()).unwrap();
 
@@ -326,8 +446,25 @@ fn create_sample_docx(text_paragraphs: &[&str]) -> Vec<u8> {
     zip.finish().unwrap().into_inner()
 }
 
+/// パスと内容の組からZIPパッケージ（DOCX / PPTX / XLSX）を作成するヘルパー
+fn zip_package(files: &[(&str, &[u8])]) -> Vec<u8> {
+    use std::io::Write;
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    for (path, data) in files {
+        zip.start_file(*path, options).unwrap();
+        zip.write_all(data).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
 /// 最小限の有効なPPTXファイルを作成するヘルパー
 fn create_sample_pptx(slides: &[(&str, &[&str])]) -> Vec<u8> {
+    create_sample_pptx_with_title(slides, "サンプルPPTXプレゼンテーション")
+}
+
+/// 文書タイトル（docProps/core.xml の dc:title）を指定してPPTXを作成するヘルパー
+fn create_sample_pptx_with_title(slides: &[(&str, &[&str])], doc_title: &str) -> Vec<u8> {
     use std::io::Write;
     let buf = Vec::new();
     let cursor = std::io::Cursor::new(buf);
@@ -398,12 +535,15 @@ fn create_sample_pptx(slides: &[(&str, &[&str])]) -> Vec<u8> {
 
     // docProps/core.xml
     zip.start_file("docProps/core.xml", options).unwrap();
-    zip.write_all("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+    zip.write_all(format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
 <cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\"\n\
-                   xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n\
-  <dc:title>サンプルPPTXプレゼンテーション</dc:title>\n\
+                   xmlns:dc=\"http://purl.org/dc/elements/1.1/\"\n\
+                   xmlns:dcterms=\"http://purl.org/dc/terms/\">\n\
+  <dc:title>{}</dc:title>\n\
   <dc:creator>テストユーザー</dc:creator>\n\
-</cp:coreProperties>".as_bytes()).unwrap();
+  <cp:keywords>sample, test</cp:keywords>\n\
+  <dcterms:created>2024-01-15T10:30:00Z</dcterms:created>\n\
+</cp:coreProperties>", doc_title).as_bytes()).unwrap();
 
     zip.finish().unwrap().into_inner()
 }
@@ -620,6 +760,112 @@ fn test_sample_xlsx_to_pdf_with_calamine() {
     eprintln!("✅ XLSX→PDF出力: {} ({} bytes, {}ページ)", out_path, pdf.len(), doc.pages.len());
 }
 
+/// シート1枚（名前 "Sheet1"）だけのXLSXを作成（共有文字列を使わないシートXMLを指定）
+fn create_xlsx_with_sheet(sheet_xml: &str) -> Vec<u8> {
+    zip_package(&[
+        ("_rels/.rels", br#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>
+</Relationships>"#),
+        ("xl/_rels/workbook.xml.rels", br#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>
+</Relationships>"#),
+        ("xl/workbook.xml", br#"<?xml version="1.0" encoding="UTF-8"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets>
+</workbook>"#),
+        ("xl/worksheets/sheet1.xml", sheet_xml.as_bytes()),
+    ])
+}
+
+#[test]
+fn test_xlsx_headers_and_gridlines_options() {
+    use wasm_document_converter::converter::{PageElement, Table, TextFlowOptions};
+
+    let xlsx = create_xlsx_with_sheet(r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+  <sheetData>
+    <row r="1"><c r="A1" t="inlineStr"><is><t>Item</t></is></c><c r="B1" t="inlineStr"><is><t>Qty</t></is></c></row>
+    <row r="2"><c r="A2" t="inlineStr"><is><t>Apple</t></is></c><c r="B2"><v>3</v></c></row>
+  </sheetData>
+</worksheet>"#);
+
+    let first_table = |options: &TextFlowOptions| -> Table {
+        let doc = formats::convert_by_extension_with_options("xlsx", &xlsx, options).unwrap();
+        doc.pages[0]
+            .elements
+            .iter()
+            .find_map(|el| match el {
+                PageElement::TableBlock { table, .. } => Some(table.clone()),
+                _ => None,
+            })
+            .unwrap()
+    };
+    let texts = |table: &Table| -> Vec<Vec<String>> {
+        table.rows.iter().map(|row| row.iter().map(|c| c.text.clone()).collect()).collect()
+    };
+
+    // 既定では見出しも枠線も付けない
+    let plain = first_table(&TextFlowOptions::default());
+    assert_eq!(texts(&plain), vec![vec!["Item", "Qty"], vec!["Apple", "3.00"]]);
+    assert!(plain.rows.iter().flatten().all(|c| c.borders.is_some_and(|b| b.top.is_none() && b.left.is_none())));
+
+    // 見出し: 先頭に列番号の行、左端に行番号の列（網掛けのセル）
+    let headed = first_table(&TextFlowOptions { show_headers: true, ..TextFlowOptions::default() });
+    assert_eq!(texts(&headed), vec![vec!["", "A", "B"], vec!["1", "Item", "Qty"], vec!["2", "Apple", "3.00"]]);
+    assert!(headed.rows[0][1].background.is_some() && headed.rows[1][0].background.is_some());
+    assert!(headed.rows[1][1].background.is_none());
+    assert_eq!(headed.column_widths.len(), 3);
+
+    // 枠線: すべてのセルの4辺に線を引く
+    let grid = first_table(&TextFlowOptions { show_gridlines: true, ..TextFlowOptions::default() });
+    assert!(grid.rows.iter().flatten().all(|c| c.borders.is_some_and(|b| b.top.is_some() && b.right.is_some()
+        && b.bottom.is_some() && b.left.is_some())));
+}
+
+#[test]
+fn test_xlsx_frozen_top_row_repeats_on_each_page() {
+    use wasm_document_converter::converter::PageElement;
+
+    let rows: String = (2..=120)
+        .map(|r| format!(r#"<row r="{r}"><c r="A{r}" t="inlineStr"><is><t>Item {r}</t></is></c><c r="B{r}"><v>{r}</v></c></row>"#))
+        .collect();
+    let xlsx = create_xlsx_with_sheet(&format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+  <sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews>
+  <sheetData>
+    <row r="1"><c r="A1" t="inlineStr"><is><t>Name</t></is></c><c r="B1" t="inlineStr"><is><t>Value</t></is></c></row>
+    {rows}
+  </sheetData>
+</worksheet>"#
+    ));
+
+    let doc = formats::convert_by_extension("xlsx", &xlsx).unwrap();
+    assert!(doc.pages.len() >= 2, "複数ページに分割されること: {}", doc.pages.len());
+    let first_rows: Vec<Vec<String>> = doc
+        .pages
+        .iter()
+        .map(|page| {
+            page.elements
+                .iter()
+                .find_map(|el| match el {
+                    PageElement::TableBlock { table, .. } => {
+                        Some(table.rows[..2].iter().map(|row| row[0].text.clone()).collect())
+                    }
+                    _ => None,
+                })
+                .unwrap()
+        })
+        .collect();
+    // 固定された1行目が各ページの先頭に繰り返され、本文の行は重複しない
+    assert_eq!(first_rows[0], vec!["Name", "Item 2"]);
+    assert_eq!(first_rows[1][0], "Name");
+    assert_ne!(first_rows[1][1], "Item 2");
+    assert!(first_rows.iter().all(|rows| rows[0] == "Name"));
+}
+
 /// Sample_12.pptxでのレイアウト保持変換テスト（背景画像・グラデーション・シャドウ含む）
 /// (ファイルが存在しない場合はスキップ)
 #[test]
@@ -655,6 +901,9 @@ fn test_real_sample12_pptx_layout() {
                 PageElement::Path { .. } => "Path",
                 PageElement::EllipseImage { .. } => "EllipseImage",
                 PageElement::PathImage { .. } => "PathImage",
+                PageElement::GradientPath { .. } => "GradientPath",
+                PageElement::ClipGroup { .. } => "ClipGroup",
+                PageElement::TransformGroup { .. } => "TransformGroup",
             };
             *counts.entry(key).or_insert(0u32) += 1;
         }
@@ -1104,6 +1353,45 @@ fn test_external_font_loading() {
     assert_eq!(fm.get_font_data("AnotherFont").unwrap().len(), 300);
 }
 
+#[test]
+fn test_font_resolver_loads_missing_font_once() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use wasm_document_converter::converter::{Page, PageElement, TextAlign};
+
+    let font_data = FontManager::new().builtin_japanese_font().unwrap().to_vec();
+    let requests: Rc<RefCell<Vec<String>>> = Rc::default();
+    let mut fm = FontManager::new();
+    let log = Rc::clone(&requests);
+    let mut resolver: wasm_document_converter::font_manager::FontResolver = Box::new(move |name: &str| {
+        log.borrow_mut().push(name.to_string());
+        (name == "CdnSans").then(|| font_data.clone())
+    });
+
+    let mut doc = Document::new();
+    let mut page = Page::a4();
+    for font_name in ["CdnSans", "Unavailable", "CdnSans"] {
+        page.elements.push(PageElement::Text {
+            x: 50.0,
+            y: 50.0,
+            width: 200.0,
+            text: "Lazy".to_string(),
+            style: FontStyle { font_name: font_name.to_string(), ..FontStyle::default() },
+            align: TextAlign::Left,
+        });
+    }
+    doc.pages.push(page);
+
+    // 初回: 不足フォントごとに一度だけ問い合わせ、得られたフォントを保持する
+    assert_eq!(fm.request_missing_fonts(&doc, &mut resolver), 1);
+    assert!(fm.get_font_data("CdnSans").is_some());
+    assert_eq!(fm.missing_fonts(&doc), vec!["Unavailable".to_string()]);
+
+    // 2回目の変換では問い合わせ済みの名前を再度問い合わせない
+    assert_eq!(fm.request_missing_fonts(&doc, &mut resolver), 0);
+    assert_eq!(*requests.borrow(), vec!["CdnSans".to_string(), "Unavailable".to_string()]);
+}
+
 #[test]
 fn test_font_resolve_cjk_names() {
     let fm = FontManager::new();
@@ -1119,6 +1407,34 @@ fn test_font_resolve_cjk_names() {
     }
 }
 
+#[test]
+fn test_font_alias_resolution() {
+    use wasm_document_converter::converter::PageElement;
+
+    let mut fm = FontManager::new();
+    fm.add_font("Fallback".to_string(), vec![1u8; 100]);
+    fm.add_font("Carlito".to_string(), vec![2u8; 200]);
+
+    // 別名なし: Calibri は先頭の外部フォントにフォールバック
+    assert!(fm.get_font_data("Calibri").is_none());
+    assert_eq!(fm.resolve_font("Calibri").unwrap().len(), 100);
+
+    // 別名あり: Calibri は Carlito のデータに解決される（大文字小文字は無視）
+    fm.add_alias("Calibri".to_string(), "Carlito".to_string());
+    assert_eq!(fm.resolve_font("Calibri").unwrap().len(), 200);
+    assert_eq!(fm.get_font_data("calibri").unwrap().len(), 200);
+
+    // ドキュメントで参照される未解決フォント名を列挙
+    let mut doc = formats::convert_by_extension("txt", b"alpha\nbeta").unwrap();
+    let mut names = ["Calibri", "Wingdings"].iter().cycle();
+    for element in doc.pages.iter_mut().flat_map(|p| p.elements.iter_mut()) {
+        if let PageElement::Text { style, .. } = element {
+            style.font_name = names.next().unwrap().to_string();
+        }
+    }
+    assert_eq!(fm.missing_fonts(&doc), vec!["Wingdings".to_string()]);
+}
+
 #[test]
 fn test_pdf_with_external_font_manager() {
     let mut fm = FontManager::new();
@@ -1627,3 +1943,3885 @@ fn test_table_merge_cell_rendering() {
         tj_count
     );
 }
+
+/// リニアライズPDF出力テスト
+#[test]
+fn test_linearized_pdf_output() {
+    let lines: Vec<String> = (0..200).map(|i| format!("Line {}", i)).collect();
+    let mut doc = Document::from_text_lines(&lines, &FontStyle::default());
+    assert!(doc.pages.len() > 1);
+    // 参照に似た文字列を含むタイトル（オブジェクト番号の振り直しで書き換えてはならない）
+    doc.metadata.title = Some("Figure 3 0 R (draft)".to_string());
+
+    let fm = FontManager::new();
+    let options = pdf_writer::PdfOptions {
+        linearize: true,
+        ..Default::default()
+    };
+    let pdf = pdf_writer::render_to_pdf_with_options(&doc, &fm, &options);
+
+    assert!(pdf.starts_with(b"%PDF"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+    let head = String::from_utf8_lossy(&pdf[..1024]);
+    assert!(head.contains("/Linearized 1"), "first 1KB should contain /Linearized dict");
+
+    // /L はファイル長と一致すること
+    let l_pos = head.find("/L ").unwrap() + 3;
+    let l_value: usize = head[l_pos..].trim_start().split(' ').next().unwrap().parse().unwrap();
+    assert_eq!(l_value, pdf.len());
+
+    // 2つの相互参照テーブル（1ページ目用 + メイン）が存在すること
+    let pdf_str = String::from_utf8_lossy(&pdf);
+    assert_eq!(pdf_str.matches("xref\n").count() - pdf_str.matches("startxref\n").count(), 2);
+    assert!(pdf_str.contains("/Prev"));
+
+    // 最終startxrefは1ページ目用xrefを指す
+    let last_startxref = pdf_str.rfind("startxref\n").unwrap() + "startxref\n".len();
+    let first_xref: usize = pdf_str[last_startxref..].lines().next().unwrap().parse().unwrap();
+    assert!(pdf[first_xref..].starts_with(b"xref\n"));
+    assert!(first_xref < 1024);
+
+    // /T はメイン相互参照テーブルの最初のエントリを指す
+    let t_pos = head.find("/T ").unwrap() + 3;
+    let t_value: usize = head[t_pos..].trim_start().split(' ').next().unwrap().parse().unwrap();
+    assert!(pdf[t_value..].starts_with(b"0000000000 65535 f "));
+
+    assert!(pdf_str.contains("/Title (Figure 3 0 R \\(draft\\))"));
+}
+
+/// テスト用の小さなRGB PNGを生成
+fn create_test_png(width: u32, height: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        let pixels = vec![200u8; (width * height * 3) as usize];
+        writer.write_image_data(&pixels).unwrap();
+    }
+    out
+}
+
+/// ICCプロファイル（出力インテント）埋め込みテスト
+#[test]
+fn test_pdf_output_intent_icc_profile() {
+    use wasm_document_converter::converter::{Page, PageElement};
+    use wasm_document_converter::icc_profile;
+
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Image {
+        x: 50.0,
+        y: 50.0,
+        width: 100.0,
+        height: 100.0,
+        data: create_test_png(4, 4),
+        mime_type: "image/png".to_string(),
+    });
+    let mut doc = Document::new();
+    doc.pages.push(page);
+
+    let fm = FontManager::new();
+    let options = pdf_writer::PdfOptions {
+        embed_color_profile: true,
+        ..Default::default()
+    };
+    let pdf = pdf_writer::render_to_pdf_with_options(&doc, &fm, &options);
+    let pdf_str = String::from_utf8_lossy(&pdf);
+
+    assert!(pdf_str.contains("/OutputIntents ["), "catalog should have /OutputIntents array");
+    assert!(pdf_str.contains("/DestOutputProfile"));
+    let expected_len = icc_profile::srgb_profile().len();
+    assert!(
+        pdf_str.contains(&format!("<< /N 3 /Length {} >>", expected_len)),
+        "ICC stream should have length {}",
+        expected_len
+    );
+    assert!(pdf_str.contains("/ColorSpace [/ICCBased"), "RGB images should be tagged as ICCBased");
+
+    // デフォルトでは埋め込まない
+    let plain = pdf_writer::render_to_pdf_with_fonts(&doc, &fm);
+    let plain_str = String::from_utf8_lossy(&plain);
+    assert!(!plain_str.contains("/OutputIntents"));
+    assert!(plain_str.contains("/ColorSpace /DeviceRGB"));
+}
+
+/// メタデータがPDFの文書情報辞書（/Info）に出力されるテスト
+#[test]
+fn test_pptx_metadata_in_pdf_info() {
+    let pptx = create_sample_pptx_with_title(&[("Slide", &["Body"])], "That Title");
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    let fm = FontManager::new();
+    let pdf = pdf_writer::render_to_pdf_with_fonts(&doc, &fm);
+    let pdf_str = String::from_utf8_lossy(&pdf);
+
+    assert!(pdf_str.contains("/Title (That Title)"), "PDF /Info should contain the title");
+    assert!(pdf_str.contains("/Keywords (sample, test)"));
+    assert!(pdf_str.contains("/CreationDate (D:20240115103000)"));
+    assert!(pdf_str.contains("/Producer (WASM Document Converter"));
+    assert!(pdf_str.contains("/Info "), "trailer should reference /Info");
+
+    // 非ASCIIの作成者はUTF-16BEヘックス文字列で出力される
+    assert!(pdf_str.contains("/Author <FEFF"));
+}
+
+/// 複数ファイルを1つのPDFに結合するバッチ変換テスト
+#[test]
+fn test_batch_conversion_combines_pages() {
+    let lines: Vec<String> = (0..120).map(|i| format!("行 {}", i)).collect();
+    let txt = lines.join("\n");
+    let csv = "Name,Age\nAlice,30\nBob,25";
+
+    let txt_pages = formats::convert_by_extension("txt", txt.as_bytes()).unwrap().pages.len();
+    let csv_pages = formats::convert_by_extension("csv", csv.as_bytes()).unwrap().pages.len();
+
+    let doc = formats::convert_batch(&[
+        ("notes.txt", txt.as_bytes()),
+        ("table.csv", csv.as_bytes()),
+    ])
+    .unwrap();
+    assert_eq!(doc.pages.len(), txt_pages + csv_pages);
+
+    let fm = FontManager::new();
+    let pdf = pdf_writer::render_to_pdf_with_fonts(&doc, &fm);
+    let pdf_str = String::from_utf8_lossy(&pdf);
+    assert!(pdf_str.contains(&format!("/Count {}", txt_pages + csv_pages)));
+
+    // 2つ目以降のファイルの警告も結合したドキュメントに残る
+    let html = r#"<p>図</p><img src="images/missing.png">"#;
+    let doc = formats::convert_batch(&[("notes.txt", txt.as_bytes()), ("page.html", html.as_bytes())]).unwrap();
+    assert!(doc.warnings.iter().any(|w| w.contains("images/missing.png")), "{:?}", doc.warnings);
+
+    assert!(formats::convert_batch(&[("a.unknown", b"x".as_slice())]).is_err());
+    assert!(formats::convert_batch(&[]).is_err());
+
+    // 出力オプションのページ番号は結合したドキュメントの通し番号になる
+    let options = pdf_writer::PdfOptions {
+        page_numbers: Some(wasm_document_converter::converter::PageNumberConfig {
+            format: "{n}/{total}".to_string(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut converter = wasm_document_converter::Converter::new();
+    let files = [("notes.txt", txt.as_bytes()), ("table.csv", csv.as_bytes())];
+    let count_tj = |pdf: Vec<u8>| String::from_utf8_lossy(&pdf).matches(" Tj\n").count();
+    let plain = count_tj(converter.to_pdf_batch(&files, &pdf_writer::PdfOptions::default()).unwrap());
+    let numbered = converter.to_pdf_batch(&files, &options).unwrap();
+    assert!(String::from_utf8_lossy(&numbered).contains(&format!("/Count {}", txt_pages + csv_pages)));
+    assert_eq!(count_tj(numbered), plain + txt_pages + csv_pages);
+}
+
+/// 横長コンテンツを持つ縦長ページの自動向き補正テスト
+#[test]
+fn test_auto_orient_wide_page() {
+    use wasm_document_converter::converter::{Color, Page, PageElement};
+
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Rect {
+        x: 20.0,
+        y: 20.0,
+        width: 500.0,
+        height: 200.0,
+        fill: Some(Color::rgb(0, 0, 255)),
+        stroke: None,
+        stroke_width: 0.0,
+        rotation_deg: 0.0,
+        stroke_style: Default::default(),
+    });
+    let mut doc = Document::new();
+    doc.pages.push(page);
+    let fm = FontManager::new();
+
+    // デフォルトでは向きを変えない
+    let pdf = pdf_writer::render_to_pdf_with_fonts(&doc, &fm);
+    assert!(String::from_utf8_lossy(&pdf).contains("/MediaBox [0 0 595.28 841.89]"));
+
+    // 縦横を入れ替え、コンテンツは90°回転したグループになる
+    let oriented = doc.pages[0].auto_oriented().unwrap();
+    assert_eq!((oriented.width, oriented.height), (doc.pages[0].height, doc.pages[0].width));
+    assert!(matches!(
+        oriented.elements.as_slice(),
+        [PageElement::TransformGroup { rotation_deg, .. }] if *rotation_deg == 90.0
+    ));
+
+    let options = pdf_writer::PdfOptions {
+        auto_orient: true,
+        ..Default::default()
+    };
+    let pdf = pdf_writer::render_to_pdf_with_options(&doc, &fm, &options);
+    assert!(
+        String::from_utf8_lossy(&pdf).contains("/MediaBox [0 0 841.89 595.28]"),
+        "wide content should produce a landscape page"
+    );
+
+    // 画像出力でも横長になり、矩形は (height - y, x) の位置に回転して描かれる
+    let config = image_renderer::ImageRenderConfig {
+        dpi: 72.0,
+        auto_orient: true,
+        ..Default::default()
+    };
+    let zip_data = image_renderer::render_to_images_zip_with_config(&doc, &fm, &config);
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip_data)).unwrap();
+    let png_file = archive.by_name("page_0001.png").unwrap();
+    let mut reader = png::Decoder::new(png_file).read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).unwrap();
+    assert!(info.width > info.height);
+    let channels = info.color_type.samples();
+    let pixel = |x: usize, y: usize| {
+        let i = (y * info.width as usize + x) * channels;
+        (buf[i], buf[i + 1], buf[i + 2])
+    };
+    // 回転後の矩形: x = 621.89〜821.89, y = 20〜520
+    assert_eq!(pixel(720, 270), (0, 0, 255));
+    // 回転前の位置（左上）は空白
+    assert_eq!(pixel(100, 100), (255, 255, 255));
+
+    // 本文が縦に続く通常ページは補正されない
+    let lines: Vec<String> = (0..40).map(|i| format!("line {}", i)).collect();
+    let text_doc = Document::from_text_lines(&lines, &FontStyle::default());
+    assert!(text_doc.pages[0].auto_oriented().is_none());
+}
+
+/// DOCXインライン画像の変換テスト
+#[test]
+fn test_docx_inline_image() {
+    use wasm_document_converter::converter::PageElement;
+
+    let docx_data = zip_package(&[
+        ("word/document.xml", br#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"
+            xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing"
+            xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+            xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture"
+            xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <w:body>
+    <w:p><w:r><w:t>Before image</w:t></w:r></w:p>
+    <w:p><w:r><w:drawing>
+      <wp:inline>
+        <wp:extent cx="1905000" cy="952500"/>
+        <a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">
+          <pic:pic><pic:blipFill><a:blip r:embed="rIdImg1"/></pic:blipFill></pic:pic>
+        </a:graphicData></a:graphic>
+      </wp:inline>
+    </w:drawing></w:r></w:p>
+  </w:body>
+</w:document>"#),
+        ("word/_rels/document.xml.rels", br#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rIdImg1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="media/image1.png"/>
+</Relationships>"#),
+        ("word/media/image1.png", &create_test_png(4, 2)),
+    ]);
+
+    let doc = formats::convert_by_extension("docx", &docx_data).unwrap();
+    let image = doc.pages[0]
+        .elements
+        .iter()
+        .find_map(|el| match el {
+            PageElement::Image { width, height, mime_type, .. } => Some((*width, *height, mime_type.clone())),
+            _ => None,
+        })
+        .expect("画像要素が見つかりません");
+    assert!((image.0 - 150.0).abs() < 0.01);
+    assert!((image.1 - 75.0).abs() < 0.01);
+    assert_eq!(image.2, "image/png");
+}
+
+/// 上付き文字・取り消し線の描画テスト
+#[test]
+fn test_docx_superscript_and_strikethrough() {
+    use wasm_document_converter::converter::PageElement;
+
+    let docx_data = create_docx_with_body(
+        r#"<w:p>
+      <w:r><w:t>E=mc</w:t></w:r>
+      <w:r><w:rPr><w:vertAlign w:val="superscript"/></w:rPr><w:t>2</w:t></w:r>
+      <w:r><w:rPr><w:strike/></w:rPr><w:t>old</w:t></w:r>
+    </w:p>"#,
+    );
+    let doc = formats::convert_by_extension("docx", &docx_data).unwrap();
+    let texts: Vec<_> = doc.pages[0]
+        .elements
+        .iter()
+        .filter_map(|el| match el {
+            PageElement::Text { y, text, style, .. } => Some((text.as_str(), *y, style.clone())),
+            _ => None,
+        })
+        .collect();
+    let (_, base_y, base_style) = texts.iter().find(|(t, _, _)| *t == "E=mc").unwrap();
+    let (_, sup_y, sup_style) = texts.iter().find(|(t, _, _)| *t == "2").unwrap();
+    let (_, _, strike_style) = texts.iter().find(|(t, _, _)| *t == "old").unwrap();
+    assert!(sup_style.baseline_shift > 0.0);
+    assert!(strike_style.strikethrough);
+    assert!(!base_style.strikethrough);
+
+    // 上付き文字は隣接テキストより小さく、高い位置に描画される
+    let (base_size, base_top) = base_style.script_metrics(*base_y);
+    let (sup_size, sup_top) = sup_style.script_metrics(*sup_y);
+    assert!(sup_size < base_size * 0.8);
+    assert!(sup_top + sup_size < base_top + base_size);
+
+    // PDFのコンテンツストリームでも縮小サイズ・高いベースラインで出力される
+    let pdf = pdf_writer::render_to_pdf(&doc);
+    let pdf_str = String::from_utf8_lossy(&pdf);
+    let runs: Vec<(f64, f64)> = pdf_str
+        .split("BT\n")
+        .skip(1)
+        .filter_map(|block| {
+            let size = block.split(" Tf").next()?.rsplit(' ').next()?.parse().ok()?;
+            let td_line = block.lines().find(|l| l.ends_with(" Td"))?;
+            let y = td_line.split(' ').nth(1)?.parse().ok()?;
+            Some((size, y))
+        })
+        .collect();
+    let base = runs.iter().find(|(size, _)| (*size - 11.0).abs() < 0.01).unwrap();
+    let sup = runs.iter().find(|(size, _)| *size < 11.0).unwrap();
+    assert!(sup.1 > base.1);
+    // 取り消し線はストロークとして出力される
+    assert!(pdf_str.contains(" l\nS\n"));
+}
+
+/// 下線の描画テスト
+#[test]
+fn test_underline_rendering() {
+    use wasm_document_converter::converter::{
+        Color, FontStyle, Page, PageElement, TextAlign, UnderlineKind, UnderlineStyle,
+    };
+
+    // DOCXの w:u から線種と色を取得
+    let docx_data = create_docx_with_body(
+        r#"<w:p><w:r><w:rPr><w:u w:val="double" w:color="FF0000"/></w:rPr><w:t>under</w:t></w:r></w:p>"#,
+    );
+    let doc = formats::convert_by_extension("docx", &docx_data).unwrap();
+    let underline = doc.pages[0]
+        .elements
+        .iter()
+        .find_map(|el| match el {
+            PageElement::Text { style, .. } => style.underline,
+            _ => None,
+        })
+        .expect("下線付きテキストが見つかりません");
+    assert_eq!(underline.kind, UnderlineKind::Double);
+    assert_eq!(underline.color, Some(Color::rgb(255, 0, 0)));
+
+    // 下線付きのランはベースラインの下に線を1本追加で出力する
+    let stroke_lines = |underline: Option<UnderlineStyle>| -> Vec<f64> {
+        let mut page = Page::a4();
+        page.elements.push(PageElement::Text {
+            x: 50.0,
+            y: 100.0,
+            width: 200.0,
+            text: "Underlined".to_string(),
+            style: FontStyle { font_size: 20.0, underline, ..FontStyle::default() },
+            align: TextAlign::Left,
+        });
+        let mut doc = wasm_document_converter::converter::Document::new();
+        doc.pages.push(page);
+        let pdf = pdf_writer::render_to_pdf(&doc);
+        String::from_utf8_lossy(&pdf)
+            .lines()
+            .filter(|l| l.ends_with(" m"))
+            .filter_map(|l| l.split(' ').nth(1)?.parse().ok())
+            .collect()
+    };
+    assert!(stroke_lines(None).is_empty());
+    let lines = stroke_lines(Some(UnderlineStyle::new(UnderlineKind::Single)));
+    assert_eq!(lines.len(), 1);
+    let baseline = 841.89 - 100.0 - 20.0;
+    assert!(lines[0] < baseline);
+    assert!((baseline - lines[0] - 20.0 * 0.12).abs() < 0.01);
+}
+
+/// テーブルセルの折り返しと行の自動高さのテスト
+#[test]
+fn test_table_cell_wrap_row_auto_height() {
+    use wasm_document_converter::converter::{Page, PageElement, Table, TableCell};
+
+    let long_text = "This cell contains a long sentence that cannot fit in a narrow column";
+    let table = Table {
+        rows: vec![
+            vec![TableCell::new("A"), TableCell::new(long_text)],
+            vec![TableCell::new("B"), TableCell::new("short")],
+        ],
+        column_widths: vec![40.0, 60.0],
+    };
+
+    let lines = Table::cell_lines(&table.rows[0][1], 60.0, None);
+    assert!(lines.len() > 3, "長いテキストは複数行に折り返される: {:?}", lines);
+    assert!(lines.iter().all(|l| long_text.contains(l.as_str())));
+
+    let heights = table.row_heights(100.0, None);
+    assert!((heights[1] - 21.0).abs() < 0.01);
+    let font_size = table.rows[0][1].style.font_size;
+    assert!(heights[0] >= font_size * 1.3 * lines.len() as f64);
+
+    // PDFでは折り返した各行が個別のテキストとして出力される
+    let mut page = Page::a4();
+    page.elements.push(PageElement::TableBlock { x: 50.0, y: 50.0, width: 100.0, table });
+    let mut doc = wasm_document_converter::converter::Document::new();
+    doc.pages.push(page);
+    let pdf = pdf_writer::render_to_pdf(&doc);
+    let pdf_str = String::from_utf8_lossy(&pdf);
+    // "A", "B", "short" と折り返した2行以上
+    assert!(pdf_str.matches(" Tj\n").count() >= 5);
+    assert!(pdf_str.contains(" re W n\n"));
+}
+
+/// CSVの表のページ分割が描画時と同じフォントで行の高さを計測するテスト
+#[test]
+fn test_csv_table_pages_measured_with_render_font() {
+    use wasm_document_converter::converter::PageElement;
+    use wasm_document_converter::Converter;
+
+    // 概算の文字幅（0.6em）より実際のフォントで幅の広い文字は、描画時に多くの行に折り返される
+    let wide = "m".repeat(270);
+    let csv: String = (0..40).map(|i| format!("{},{},{}\n", i, wide, wide)).collect();
+
+    let mut converter = Converter::new();
+    let doc = converter.to_document("wide.csv", csv.as_bytes()).unwrap();
+    let font_data = converter.font_manager().usable_font_data();
+    assert!(font_data.is_some());
+    let mut grew = false;
+    for page in &doc.pages {
+        for element in &page.elements {
+            if let PageElement::TableBlock { width, table, .. } = element {
+                let rendered: f64 = table.row_heights(*width, font_data).iter().sum();
+                let estimated: f64 = table.row_heights(*width, None).iter().sum();
+                grew |= rendered > estimated;
+                // 描画時の高さで余白を除いたページの高さに収まる
+                assert!(rendered <= page.height - 80.0, "表がページからはみ出す: {}", rendered);
+            }
+        }
+    }
+    assert!(grew, "実際のフォントでは概算より行が高くなる");
+}
+
+#[test]
+fn test_missing_glyph_box_policy() {
+    use wasm_document_converter::converter::{MissingGlyphPolicy, Page, PageElement, TextAlign};
+    use wasm_document_converter::pdf_writer::PdfOptions;
+
+    let font_manager = FontManager::new();
+    // 組み込みフォントのサブセットに含まれない稀な漢字（𠮷: U+20BB7）
+    let text = "\u{20BB7}";
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Text {
+        x: 50.0,
+        y: 100.0,
+        width: 200.0,
+        text: text.to_string(),
+        style: FontStyle { font_size: 20.0, ..FontStyle::default() },
+        align: TextAlign::Left,
+    });
+    let mut doc = Document::new();
+    doc.pages.push(page);
+
+    // 欠落したコードポイントは警告として記録される（重複なし）
+    doc.record_missing_glyphs(font_manager.best_font_data());
+    doc.record_missing_glyphs(font_manager.best_font_data());
+    assert_eq!(doc.warnings.len(), 1, "{:?}", doc.warnings);
+    assert!(doc.warnings[0].contains("U+20BB7"));
+
+    let render = |policy: MissingGlyphPolicy| -> String {
+        let options = PdfOptions { missing_glyph: policy, ..PdfOptions::default() };
+        let pdf = pdf_writer::render_to_pdf_with_options(&doc, &font_manager, &options);
+        String::from_utf8_lossy(&pdf).into_owned()
+    };
+    // Boxでは.notdef相当の矩形を線で描く
+    assert!(render(MissingGlyphPolicy::Box).contains(" re\nS\n"));
+    assert!(!render(MissingGlyphPolicy::Hide).contains(" re\nS\n"));
+
+    // 画像出力でも矩形が描かれ、Hideでは何も描かれない
+    let dark_pixels = |policy: MissingGlyphPolicy| -> usize {
+        let config = image_renderer::ImageRenderConfig { missing_glyph: policy, ..Default::default() };
+        let png = image_renderer::render_page_to_image(&doc.pages[0], &config, &font_manager);
+        decode_png_pixels(&png).2.chunks(4).filter(|p| p[0] < 128).count()
+    };
+    assert!(dark_pixels(MissingGlyphPolicy::Box) > 0);
+    assert_eq!(dark_pixels(MissingGlyphPolicy::Hide), 0);
+}
+
+/// PNGをデコードして (幅, 高さ, RGBAピクセル) を返す
+fn decode_png_pixels(png: &[u8]) -> (u32, u32, Vec<u8>) {
+    let mut reader = png::Decoder::new(std::io::Cursor::new(png)).read_info().unwrap();
+    let mut buf = vec![0u8; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buf).unwrap();
+    assert_eq!(frame.color_type, png::ColorType::Rgba);
+    buf.truncate(frame.buffer_size());
+    (frame.width, frame.height, buf)
+}
+
+#[test]
+fn test_gradient_path_clipped_to_path() {
+    use wasm_document_converter::converter::{Color, GradientStop, GradientType, Page, PageElement, PathCommand};
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+
+    // 左上の直角三角形を、左から右へ赤→青のグラデーションで塗る
+    let mut page = Page::a4();
+    page.elements.push(PageElement::GradientPath {
+        commands: vec![
+            PathCommand::MoveTo(0.0, 0.0),
+            PathCommand::LineTo(100.0, 0.0),
+            PathCommand::LineTo(0.0, 100.0),
+            PathCommand::Close,
+        ],
+        stops: vec![
+            GradientStop { position: 0.0, color: Color::rgb(255, 0, 0) },
+            GradientStop { position: 1.0, color: Color::rgb(0, 0, 255) },
+        ],
+        gradient_type: GradientType::Linear(std::f64::consts::FRAC_PI_2),
+        stroke: None,
+        stroke_width: 0.0,
+        rotation_deg: 0.0,
+    });
+
+    let config = ImageRenderConfig { dpi: 72.0, ..Default::default() };
+    let png = image_renderer::render_page_to_image(&page, &config, &FontManager::new());
+    let (width, _, pixels) = decode_png_pixels(&png);
+    let pixel = |x: u32, y: u32| {
+        let i = ((y * width + x) * 4) as usize;
+        (pixels[i], pixels[i + 1], pixels[i + 2])
+    };
+    // パス内部はグラデーション（左ほど赤、右ほど青）、外側は塗られない
+    let (left, right) = (pixel(5, 5), pixel(80, 5));
+    assert!(left.0 > 200 && left.2 < 50, "{:?}", left);
+    assert!(right.2 > right.0, "{:?}", right);
+    assert_eq!(pixel(90, 90), (255, 255, 255));
+
+    let mut doc = Document::new();
+    doc.pages.push(page);
+    let pdf = String::from_utf8_lossy(&pdf_writer::render_to_pdf(&doc)).to_string();
+    assert!(pdf.contains("W* n"), "グラデーションはパスでクリップされる");
+}
+
+#[test]
+fn test_image_fit_contain_letterboxes_square_image() {
+    use wasm_document_converter::converter::{ImageFit, Page, PageElement};
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+
+    // 横長の矩形 (0, 0)-(200, 50) に正方形の画像を配置
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Image {
+        x: 0.0,
+        y: 0.0,
+        width: 200.0,
+        height: 50.0,
+        data: create_test_png(20, 20),
+        mime_type: "image/png".to_string(),
+    });
+    let render = |image_fit: ImageFit| {
+        let config = ImageRenderConfig { dpi: 72.0, image_fit, ..Default::default() };
+        let png = image_renderer::render_page_to_image(&page, &config, &FontManager::new());
+        let (width, _, pixels) = decode_png_pixels(&png);
+        move |x: u32, y: u32| {
+            let i = ((y * width + x) * 4) as usize;
+            (pixels[i], pixels[i + 1], pixels[i + 2])
+        }
+    };
+    let gray = (200, 200, 200);
+    let white = (255, 255, 255);
+
+    // Stretch（既定）は矩形全体を覆う
+    let stretched = render(ImageFit::Stretch);
+    assert_eq!((stretched(10, 25), stretched(190, 25)), (gray, gray));
+
+    // Contain は中央の 50×50 に収まり、左右に余白の帯が残る
+    let contained = render(ImageFit::Contain);
+    assert_eq!(contained(100, 25), gray);
+    assert_eq!((contained(10, 25), contained(190, 25)), (white, white));
+    assert_eq!((contained(70, 25), contained(130, 25)), (white, white));
+
+    // Cover は矩形を覆うよう 200×200 に拡大し、矩形の外は切り取る
+    let fitted = page.with_image_fit(ImageFit::Cover).unwrap();
+    match &fitted.elements[0] {
+        PageElement::ClipGroup { children, .. } => match &children[..] {
+            [PageElement::Image { x, y, width, height, .. }] => {
+                assert_eq!((*x, *y, *width, *height), (0.0, -75.0, 200.0, 200.0));
+            }
+            other => panic!("クリップグループの中身が画像1枚ではありません: {} 要素", other.len()),
+        },
+        _ => panic!("Cover はクリップグループで切り取る"),
+    }
+    let covered = render(ImageFit::Cover);
+    assert_eq!((covered(10, 25), covered(190, 25)), (gray, gray));
+    assert_eq!(covered(100, 60), white);
+    assert!(page.with_image_fit(ImageFit::Stretch).is_none());
+}
+
+#[test]
+fn test_image_interpolation_modes() {
+    use wasm_document_converter::converter::{Page, PageElement};
+    use wasm_document_converter::image_renderer::{ImageInterpolation, ImageRenderConfig};
+
+    // 2×2 の白黒チェッカーボード
+    let mut checker = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut checker, 2, 2);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer
+            .write_image_data(&[0, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255, 255, 0, 0, 0, 255])
+            .unwrap();
+    }
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Image {
+        x: 0.0,
+        y: 0.0,
+        width: 20.0,
+        height: 20.0,
+        data: checker,
+        mime_type: "image/png".to_string(),
+    });
+
+    let fm = FontManager::new();
+    let gray_count = |image_interpolation| {
+        let config = ImageRenderConfig { dpi: 72.0, image_interpolation, ..Default::default() };
+        let png = image_renderer::render_page_to_image(&page, &config, &fm);
+        let (width, _, pixels) = decode_png_pixels(&png);
+        (0..20u32)
+            .flat_map(|y| (0..20u32).map(move |x| ((y * width + x) * 4) as usize))
+            .filter(|&i| pixels[i] != 0 && pixels[i] != 255)
+            .count()
+    };
+
+    // 10倍拡大: 最近傍は境界がくっきり、双線形は境界に中間色が出る
+    assert_eq!(gray_count(ImageInterpolation::Nearest), 0);
+    assert!(gray_count(ImageInterpolation::Bilinear) > 0);
+    assert_eq!(ImageRenderConfig::default().image_interpolation, ImageInterpolation::Bilinear);
+}
+
+#[test]
+fn test_image_supersample_antialiasing() {
+    use wasm_document_converter::converter::{Color, Page, PageElement};
+    use wasm_document_converter::image_renderer::{ImageRenderConfig, MAX_SUPERSAMPLED_DPI};
+
+    let mut page = Page::a4();
+    let page_width = page.width as u32;
+    page.elements.push(PageElement::Line {
+        x1: 10.0,
+        y1: 10.0,
+        x2: 90.0,
+        y2: 60.0,
+        width: 1.0,
+        color: Color::BLACK,
+        stroke_style: Default::default(),
+    });
+    let fm = FontManager::new();
+    let gray_pixels = |supersample: u8, antialias: bool| -> (u32, usize) {
+        let config = ImageRenderConfig { dpi: 72.0, supersample, antialias, ..Default::default() };
+        let png = image_renderer::render_page_to_image(&page, &config, &fm);
+        let (width, _, pixels) = decode_png_pixels(&png);
+        let gray = pixels.chunks(4).filter(|p| p[0] > 0 && p[0] < 255).count();
+        (width, gray)
+    };
+
+    // 等倍では中間色が無く、2倍スーパーサンプリングではエッジに中間色が出る
+    assert_eq!(gray_pixels(1, true), (page_width, 0));
+    let (width, gray) = gray_pixels(2, true);
+    assert_eq!(width, page_width, "出力サイズは倍率に依存しない");
+    assert!(gray > 0);
+    // アンチエイリアス無効時はスーパーサンプリングしない
+    assert_eq!(gray_pixels(2, false), (page_width, 0));
+
+    // 倍率は1〜4に制限され、内部DPIの上限を超えないよう下げられる
+    let config = ImageRenderConfig { supersample: 8, dpi: 150.0, ..Default::default() };
+    assert_eq!(config.effective_supersample(), 4);
+    let config = ImageRenderConfig { supersample: 4, dpi: 600.0, ..Default::default() };
+    assert_eq!(config.effective_supersample(), 2);
+    assert!(600.0 * config.effective_supersample() as f64 <= MAX_SUPERSAMPLED_DPI);
+}
+
+#[test]
+fn test_render_at_target_pixel_width() {
+    use wasm_document_converter::converter::Page;
+    use wasm_document_converter::image_renderer::{ImageRenderConfig, MAX_TARGET_PX};
+
+    let page = Page::a4();
+    let size = |config: ImageRenderConfig| {
+        let png = image_renderer::render_page_to_image(&page, &config, &FontManager::new());
+        let (width, height, _) = decode_png_pixels(&png);
+        (width, height)
+    };
+
+    // DPIを指定しても、ピクセル指定が優先される
+    let config = ImageRenderConfig { dpi: 300.0, target_width_px: Some(1000), ..Default::default() };
+    assert_eq!(size(config), (1000, 1414));
+
+    // 幅と高さの両方を指定すると両方に収まる倍率になる
+    let config = ImageRenderConfig {
+        target_width_px: Some(1000),
+        target_height_px: Some(707),
+        ..Default::default()
+    };
+    assert_eq!(size(config), (500, 707));
+
+    // 巨大な指定は長辺が上限に収まるよう抑えられる
+    let config = ImageRenderConfig { target_width_px: Some(u32::MAX), ..Default::default() };
+    let (_, width, height) = config.page_scale(&page);
+    assert_eq!(height, MAX_TARGET_PX);
+    assert!(width < MAX_TARGET_PX);
+}
+
+/// 改ページ（w:br w:type="page"）のテスト
+#[test]
+fn test_docx_manual_page_break() {
+    use wasm_document_converter::converter::PageElement;
+
+    let docx_data = create_docx_with_body(
+        r#"<w:p><w:r><w:t>first</w:t></w:r></w:p>
+    <w:p><w:r><w:br w:type="page"/><w:t>second</w:t></w:r></w:p>"#,
+    );
+    let doc = formats::convert_by_extension("docx", &docx_data).unwrap();
+    assert_eq!(doc.pages.len(), 2);
+    let page_texts = |index: usize| -> Vec<String> {
+        doc.pages[index]
+            .elements
+            .iter()
+            .filter_map(|el| match el {
+                PageElement::Text { text, .. } => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(page_texts(0), vec!["first".to_string()]);
+    assert_eq!(page_texts(1), vec!["second".to_string()]);
+}
+
+/// 段落スタイル（見出し）・文字スタイルを styles.xml から継承をたどって解決するテスト
+#[test]
+fn test_docx_styles_resolve_heading_and_character_styles() {
+    use wasm_document_converter::converter::{Color, PageElement, TextAlign};
+
+    let docx = zip_package(&[
+        ("word/document.xml", br#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:body>
+    <w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Chapter One</w:t></w:r></w:p>
+    <w:p>
+      <w:r><w:t>Plain body</w:t></w:r>
+      <w:r><w:rPr><w:rStyle w:val="Emphasis"/></w:rPr><w:t>stressed</w:t></w:r>
+    </w:p>
+  </w:body>
+</w:document>"#),
+        ("word/styles.xml", br#"<?xml version="1.0" encoding="UTF-8"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:docDefaults>
+    <w:rPrDefault><w:rPr><w:rFonts w:ascii="NotoSerifJP"/><w:sz w:val="20"/></w:rPr></w:rPrDefault>
+  </w:docDefaults>
+  <w:style w:type="paragraph" w:default="1" w:styleId="Normal">
+    <w:name w:val="Normal"/>
+    <w:rPr><w:sz w:val="21"/></w:rPr>
+  </w:style>
+  <w:style w:type="paragraph" w:styleId="Heading1">
+    <w:name w:val="heading 1"/>
+    <w:basedOn w:val="Normal"/>
+    <w:pPr><w:jc w:val="center"/></w:pPr>
+    <w:rPr><w:b/><w:color w:val="2F5496"/><w:sz w:val="36"/></w:rPr>
+  </w:style>
+  <w:style w:type="character" w:styleId="Emphasis">
+    <w:name w:val="Emphasis"/>
+    <w:rPr><w:i/></w:rPr>
+  </w:style>
+</w:styles>"#),
+    ]);
+
+    let doc = formats::convert_by_extension("docx", &docx).unwrap();
+    let text_style = |needle: &str| {
+        doc.pages[0].elements.iter().find_map(|el| match el {
+            PageElement::Text { text, style, align, .. } if text.contains(needle) => Some((style.clone(), *align)),
+            _ => None,
+        })
+    };
+
+    // 見出し: Heading1 の 18pt・太字・色・中央揃え、フォントは docDefaults から継承
+    let (heading, align) = text_style("Chapter One").unwrap();
+    assert_eq!(heading.font_size, 18.0);
+    assert!(heading.bold);
+    assert_eq!(heading.color, Color::rgb(0x2F, 0x54, 0x96));
+    assert_eq!(heading.font_name, "NotoSerifJP");
+    assert!(matches!(align, TextAlign::Center));
+
+    // 本文: 既定の段落スタイル Normal の 10.5pt、文字スタイルで斜体
+    let (body, _) = text_style("Plain body").unwrap();
+    assert_eq!(body.font_size, 10.5);
+    assert!(!body.bold && !body.italic);
+    let (emphasis, _) = text_style("stressed").unwrap();
+    assert_eq!(emphasis.font_size, 10.5);
+    assert!(emphasis.italic);
+}
+
+/// 脚注の番号が本文に上付きで入り、脚注本文がページ下部に描画されるテスト
+#[test]
+fn test_docx_footnote_at_page_bottom() {
+    use wasm_document_converter::converter::PageElement;
+
+    let docx_data = zip_package(&[
+        ("word/document.xml", br#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:body>
+    <w:p>
+      <w:r><w:t>Body text</w:t></w:r>
+      <w:r><w:rPr><w:rStyle w:val="FootnoteReference"/></w:rPr><w:footnoteReference w:id="1"/></w:r>
+    </w:p>
+  </w:body>
+</w:document>"#),
+        ("word/footnotes.xml", br#"<?xml version="1.0" encoding="UTF-8"?>
+<w:footnotes xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:footnote w:type="separator" w:id="-1"><w:p><w:r><w:separator/></w:r></w:p></w:footnote>
+  <w:footnote w:type="continuationSeparator" w:id="0"><w:p><w:r><w:continuationSeparator/></w:r></w:p></w:footnote>
+  <w:footnote w:id="1"><w:p><w:r><w:footnoteRef/></w:r><w:r><w:t xml:space="preserve"> Footnote source</w:t></w:r></w:p></w:footnote>
+</w:footnotes>"#),
+    ]);
+
+    let doc = formats::convert_by_extension("docx", &docx_data).unwrap();
+    assert_eq!(doc.pages.len(), 1);
+    let page = &doc.pages[0];
+    let texts: Vec<_> = page
+        .elements
+        .iter()
+        .filter_map(|el| match el {
+            PageElement::Text { y, text, style, .. } => Some((text.as_str(), *y, style.clone())),
+            _ => None,
+        })
+        .collect();
+
+    let (_, body_y, _) = texts.iter().find(|(t, _, _)| *t == "Body text").unwrap();
+    let markers: Vec<_> = texts.iter().filter(|(t, _, _)| *t == "1").collect();
+    assert_eq!(markers.len(), 2, "本文と脚注領域の両方に番号が必要です");
+    assert!(markers.iter().all(|(_, _, style)| style.baseline_shift > 0.0));
+    assert!(markers.iter().any(|(_, y, _)| (y - body_y).abs() < 0.01));
+
+    let (_, note_y, note_style) = texts.iter().find(|(t, _, _)| *t == "Footnote source").unwrap();
+    assert!(*note_y > page.height * 0.8);
+    assert!(note_style.font_size < 11.0);
+    // 区切り線が脚注の上に引かれる
+    assert!(page.elements.iter().any(|el| matches!(
+        el,
+        PageElement::Line { y1, y2, .. } if y1 == y2 && *y1 < *note_y && *y1 > page.height * 0.8
+    )));
+}
+
+/// セクション区切りで用紙の向きが変わるテスト
+#[test]
+fn test_docx_landscape_section() {
+    let docx_data = create_docx_with_body(
+        r#"<w:p>
+      <w:pPr><w:sectPr><w:pgSz w:w="11906" w:h="16838"/></w:sectPr></w:pPr>
+      <w:r><w:t>portrait</w:t></w:r>
+    </w:p>
+    <w:p><w:r><w:t>landscape</w:t></w:r></w:p>
+    <w:sectPr><w:pgSz w:w="16838" w:h="11906" w:orient="landscape"/></w:sectPr>"#,
+    );
+    let doc = formats::convert_by_extension("docx", &docx_data).unwrap();
+    assert_eq!(doc.pages.len(), 2);
+    let first = &doc.pages[0];
+    let second = &doc.pages[1];
+    assert!(first.height > first.width);
+    assert!((second.width - first.height).abs() < 0.01);
+    assert!((second.height - first.width).abs() < 0.01);
+}
+
+#[test]
+fn test_docx_table_cell_shading_and_borders() {
+    use wasm_document_converter::converter::{Color, PageElement};
+
+    let docx_data = create_docx_with_body(
+        r#"<w:tbl><w:tblGrid><w:gridCol w:w="2000"/></w:tblGrid>
+<w:tr><w:tc><w:tcPr><w:tcW w:w="2000" w:type="dxa"/>
+<w:tcBorders>
+<w:top w:val="single" w:sz="8" w:color="FF0000"/>
+<w:left w:val="single" w:sz="8" w:color="FF0000"/>
+<w:bottom w:val="single" w:sz="8" w:color="FF0000"/>
+<w:right w:val="single" w:sz="8" w:color="FF0000"/>
+</w:tcBorders>
+<w:shd w:val="clear" w:color="auto" w:fill="FFFF00"/></w:tcPr>
+<w:p><w:r><w:t>cell</w:t></w:r></w:p></w:tc></w:tr></w:tbl>"#,
+    );
+    let doc = formats::convert_by_extension("docx", &docx_data).unwrap();
+
+    // PPTXと同じ共有のテーブル（TableBlock）のセルとして出力される
+    let cell = doc.pages[0]
+        .elements
+        .iter()
+        .find_map(|el| match el {
+            PageElement::TableBlock { table, .. } => table.rows.first().and_then(|row| row.first()).cloned(),
+            _ => None,
+        })
+        .expect("テーブルが出力されていません");
+    assert_eq!(cell.text, "cell");
+    assert_eq!(cell.background, Some(Color::rgb(255, 255, 0)));
+
+    // 4辺それぞれが 1pt（w:sz=8）の赤い線として描画される
+    let borders: Vec<(f64, Color)> = cell
+        .borders
+        .expect("w:tcBorders の枠線がありません")
+        .edge_lines(0.0, 0.0, 100.0, 20.0)
+        .iter()
+        .filter_map(|el| match el {
+            PageElement::Line { width, color, .. } => Some((*width, *color)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(borders.len(), 4);
+    assert!(borders.iter().all(|&(w, c)| (w - 1.0).abs() < 1e-9 && c == Color::rgb(255, 0, 0)));
+}
+
+#[test]
+fn test_pdf_flatten_transparency() {
+    use wasm_document_converter::converter::{Color, Page, PageElement};
+    use wasm_document_converter::pdf_writer::PdfOptions;
+
+    let half_red = Color { r: 255, g: 0, b: 0, a: 128 };
+    let rect = |x: f64, fill: Color| PageElement::Rect {
+        x,
+        y: 50.0,
+        width: 100.0,
+        height: 100.0,
+        fill: Some(fill),
+        stroke: None,
+        stroke_width: 0.0,
+        rotation_deg: 0.0,
+        stroke_style: Default::default(),
+    };
+    let mut page = Page::a4();
+    // 白地の上の50%赤と、青い矩形の上の50%赤
+    page.elements.push(rect(50.0, half_red));
+    page.elements.push(rect(300.0, Color::rgb(0, 0, 255)));
+    page.elements.push(rect(300.0, half_red));
+
+    let flat = page.flattened_transparency(Color::WHITE).expect("半透明の要素があるので平坦化される");
+    let fills: Vec<Color> = flat
+        .elements
+        .iter()
+        .filter_map(|el| match el {
+            PageElement::Rect { fill, .. } => *fill,
+            _ => None,
+        })
+        .collect();
+    assert_eq!(fills[0], Color::rgb(255, 127, 127));
+    assert_eq!(fills[2], Color::rgb(128, 0, 127));
+    assert!(fills.iter().all(|c| c.a == 255));
+    assert!(Page::a4().flattened_transparency(Color::WHITE).is_none());
+
+    let mut doc = Document::new();
+    doc.pages.push(page);
+    let fm = FontManager::new();
+    let render = |flatten_transparency: bool| -> String {
+        let options = PdfOptions { flatten_transparency, ..PdfOptions::default() };
+        String::from_utf8_lossy(&pdf_writer::render_to_pdf_with_options(&doc, &fm, &options)).into_owned()
+    };
+    // 合成後のピンクが不透明色として書き出され、アルファ指定は含まれない
+    let pink = format!("1 {} {} rg", 127.0 / 255.0, 127.0 / 255.0);
+    let pdf = render(true);
+    assert!(pdf.contains(&pink), "合成色が出力されていません");
+    assert!(!pdf.contains("/ca") && !pdf.contains("/CA"));
+    assert!(!render(false).contains(&pink));
+}
+
+/// 透かし（半透明の変換グループ）と完全に透明な要素の平坦化テスト
+#[test]
+fn test_pdf_flatten_transparency_with_watermark() {
+    use wasm_document_converter::converter::{Color, Page, PageElement, Watermark};
+    use wasm_document_converter::pdf_writer::PdfOptions;
+
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Rect {
+        x: 0.0,
+        y: 0.0,
+        width: 100.0,
+        height: 100.0,
+        fill: Some(Color { r: 255, g: 0, b: 0, a: 0 }),
+        stroke: None,
+        stroke_width: 0.0,
+        rotation_deg: 0.0,
+        stroke_style: Default::default(),
+    });
+
+    // 透かしの不透明度は文字色に畳み込まれ、指定した下地の色と合成される
+    let blue = Color::rgb(0, 0, 255);
+    let flat = page.with_watermark(&Watermark::text("DRAFT"), None).flattened_transparency(blue).unwrap();
+    let [PageElement::TransformGroup { opacity, children, .. }] = flat.elements.as_slice() else {
+        panic!("完全に透明な矩形は取り除かれ、透かしのグループだけが残る: {:?}", flat.elements);
+    };
+    assert_eq!(*opacity, 1.0);
+    let [PageElement::Text { style, .. }] = children.as_slice() else { panic!("透かしのテキストがありません") };
+    assert_eq!(style.color, Color { r: 128, g: 128, b: 128, a: 77 }.composite_over(blue));
+
+    let mut doc = Document::new();
+    doc.pages.push(page);
+    let fm = FontManager::new();
+    let render = |flatten_transparency: bool| -> String {
+        let options = PdfOptions {
+            watermark: Some(Watermark::text("DRAFT")),
+            flatten_transparency,
+            ..PdfOptions::default()
+        };
+        String::from_utf8_lossy(&pdf_writer::render_to_pdf_with_options(&doc, &fm, &options)).into_owned()
+    };
+    assert!(render(false).contains("/ca"));
+    let pdf = render(true);
+    assert!(!pdf.contains("/ca") && !pdf.contains("/CA"), "平坦化したPDFにアルファ指定が残っています");
+}
+
+/// 幅の広いCSVを FitWidth でページ幅に縮小するテスト
+#[test]
+fn test_fit_width_wide_csv() {
+    use wasm_document_converter::converter::{FitMode, PageElement};
+    use wasm_document_converter::pdf_writer::PdfOptions;
+
+    let header: Vec<String> = (1..=30).map(|i| format!("Column{}", i)).collect();
+    let values: Vec<String> = (1..=30).map(|i| (i * 100).to_string()).collect();
+    let csv = format!("{}\n{}\n", header.join(","), values.join(","));
+    let doc = formats::convert_by_extension("csv", csv.as_bytes()).unwrap();
+    let page = &doc.pages[0];
+    let (_, _, max_x, _) = page.content_bounds().unwrap();
+    assert!(max_x > page.width, "縮小前はページ幅を超える");
+    assert!(page.fitted(FitMode::None).is_none());
+
+    let fitted = page.fitted(FitMode::FitWidth).expect("はみ出したテーブルは縮小される");
+    let (min_x, _, max_x, _) = fitted.content_bounds().unwrap();
+    assert!(max_x <= page.width - min_x + 0.01);
+    let PageElement::TableBlock { x, width, table, .. } = &fitted.elements[0] else {
+        panic!("テーブルが見つかりません");
+    };
+    assert!(x + width < page.width);
+    assert!(table.rows[0][0].style.font_size < 11.0);
+
+    // PDFに出力されるセル枠の座標もページ幅内に収まる
+    let options = PdfOptions { fit_mode: FitMode::FitWidth, ..PdfOptions::default() };
+    let pdf = String::from_utf8_lossy(&pdf_writer::render_to_pdf_with_options(&doc, &FontManager::new(), &options))
+        .into_owned();
+    let rect_right_edges: Vec<f64> = pdf
+        .lines()
+        .filter(|l| l.ends_with(" re"))
+        .filter_map(|l| {
+            let v: Vec<f64> = l.split(' ').filter_map(|n| n.parse().ok()).collect();
+            (v.len() == 4).then(|| v[0] + v[2])
+        })
+        .collect();
+    assert!(!rect_right_edges.is_empty());
+    assert!(rect_right_edges.iter().all(|&right| right < page.width));
+}
+
+/// プレーンテキスト抽出のテスト
+#[test]
+fn test_extract_text_per_page() {
+    let doc = formats::convert_by_extension("csv", b"Name,Age\nAlice,30\nBob,25").unwrap();
+    let texts = doc.extract_text();
+    assert_eq!(texts.len(), doc.pages.len());
+    assert!(texts[0].contains("Name\tAge"));
+    assert!(texts[0].contains("Alice\t30\nBob\t25"));
+
+    // 同じ行のランは連結され、段落は改行で区切られる
+    let docx_data = create_docx_with_body(
+        r#"<w:p><w:r><w:t>Hello </w:t></w:r><w:r><w:rPr><w:b/></w:rPr><w:t>World</w:t></w:r></w:p>
+    <w:p><w:r><w:t>Second line</w:t></w:r></w:p>"#,
+    );
+    let doc = formats::convert_by_extension("docx", &docx_data).unwrap();
+    assert_eq!(doc.extract_text(), vec!["Hello World\nSecond line".to_string()]);
+}
+
+/// 連続する空の段落が空行として抽出・描画されることのテスト
+#[test]
+fn test_empty_paragraphs_preserved_as_blank_lines() {
+    use wasm_document_converter::converter::PageElement;
+
+    let docx_data = create_docx_with_body(
+        r#"<w:p><w:r><w:t>First</w:t></w:r></w:p>
+    <w:p/>
+    <w:p><w:r><w:t></w:t></w:r></w:p>
+    <w:p><w:r><w:t>Last</w:t></w:r></w:p>"#,
+    );
+    let doc = formats::convert_by_extension("docx", &docx_data).unwrap();
+    assert_eq!(doc.extract_text(), vec!["First\n\n\nLast".to_string()]);
+
+    // 空の段落も1段落分の高さを占め、最後の段落は3段落分下に描画される
+    let text_y = |needle: &str| {
+        doc.pages[0]
+            .elements
+            .iter()
+            .find_map(|el| match el {
+                PageElement::Text { y, text, .. } if text == needle => Some(*y),
+                _ => None,
+            })
+            .unwrap()
+    };
+    let single_gap = {
+        let doc = formats::convert_by_extension("docx", &create_sample_docx(&["First", "Last"])).unwrap();
+        let ys: Vec<f64> = doc.pages[0]
+            .elements
+            .iter()
+            .filter_map(|el| match el {
+                PageElement::Text { y, text, .. } if !text.is_empty() => Some(*y),
+                _ => None,
+            })
+            .collect();
+        ys[1] - ys[0]
+    };
+    assert!((text_y("Last") - text_y("First") - 3.0 * single_gap).abs() < 1e-6);
+
+    // TXT の空行も同様に残る
+    let doc = formats::convert_by_extension("txt", b"a\n\n\nb\n").unwrap();
+    assert_eq!(doc.extract_text(), vec!["a\n\n\nb".to_string()]);
+}
+
+#[test]
+fn test_pptx_layout_placeholder_text() {
+    use wasm_document_converter::converter::PageElement;
+
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", r#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#.as_bytes()),
+        (
+            "ppt/slides/slide1.xml",
+            r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree>
+<p:sp><p:nvSpPr><p:cNvPr id="2" name="Title"/><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:spPr/><p:txBody><a:bodyPr/><a:p><a:endParaRPr/></a:p></p:txBody></p:sp>
+<p:sp><p:nvSpPr><p:cNvPr id="3" name="Body"/><p:nvPr><p:ph idx="1"/></p:nvPr></p:nvSpPr><p:spPr/><p:txBody><a:bodyPr/><a:p><a:endParaRPr/></a:p></p:txBody></p:sp>
+<p:sp><p:nvSpPr><p:cNvPr id="4" name="Footer"/><p:nvPr><p:ph type="ftr" idx="11"/></p:nvPr></p:nvSpPr><p:spPr/></p:sp>
+</p:spTree></p:cSld></p:sld>"#.as_bytes(),
+        ),
+        (
+            "ppt/slides/_rels/slide1.xml.rels",
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/></Relationships>"#.as_bytes(),
+        ),
+        (
+            "ppt/slideLayouts/slideLayout1.xml",
+            r#"<p:sldLayout xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree>
+<p:sp><p:nvSpPr><p:cNvPr id="2" name="Title"/><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x="457200" y="274638"/><a:ext cx="8229600" cy="1143000"/></a:xfrm></p:spPr><p:txBody><a:bodyPr/><a:p><a:r><a:t>Quarterly Report</a:t></a:r></a:p></p:txBody></p:sp>
+<p:sp><p:nvSpPr><p:cNvPr id="3" name="Body"/><p:nvPr><p:ph idx="1" hasCustomPrompt="1"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x="457200" y="1600200"/><a:ext cx="8229600" cy="4525963"/></a:xfrm></p:spPr><p:txBody><a:bodyPr/><a:p><a:r><a:t>Enter agenda here</a:t></a:r></a:p></p:txBody></p:sp>
+<p:sp><p:nvSpPr><p:cNvPr id="4" name="Footer"/><p:nvPr><p:ph type="ftr" idx="11"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x="457200" y="6356350"/><a:ext cx="2133600" cy="365125"/></a:xfrm></p:spPr><p:txBody><a:bodyPr/><a:p><a:r><a:t>Click to edit footer</a:t></a:r></a:p></p:txBody></p:sp>
+</p:spTree></p:cSld></p:sldLayout>"#.as_bytes(),
+        ),
+    ]);
+
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    let texts: Vec<(f64, &str)> = doc.pages[0]
+        .elements
+        .iter()
+        .filter_map(|el| match el {
+            PageElement::Text { y, text, .. } => Some((*y, text.as_str())),
+            _ => None,
+        })
+        .collect();
+    // レイアウトの既定段落はスライドの空のタイトルに、レイアウト上の位置で描画される
+    let (title_y, _) = texts
+        .iter()
+        .find(|(_, t)| t.contains("Quarterly Report"))
+        .expect("レイアウトの既定テキストが描画されていません");
+    assert!(*title_y >= 274638.0 / 12700.0, "タイトルはレイアウトの位置に配置される");
+    // プロンプト文字列は実際の内容として描画しない
+    assert!(!texts.iter().any(|(_, t)| t.contains("Enter agenda") || t.contains("Click to")), "{:?}", texts);
+}
+
+#[test]
+fn test_pptx_master_bullet_levels() {
+    use wasm_document_converter::converter::{Color, PageElement};
+
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", r#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#.as_bytes()),
+        (
+            "ppt/slides/slide1.xml",
+            r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree>
+<p:sp><p:nvSpPr><p:cNvPr id="3" name="Body"/><p:nvPr><p:ph idx="1"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x="457200" y="1600200"/><a:ext cx="8229600" cy="2286000"/></a:xfrm></p:spPr><p:txBody><a:bodyPr/>
+<a:p><a:r><a:t>Top level</a:t></a:r></a:p>
+<a:p><a:pPr lvl="1"/><a:r><a:t>Second level</a:t></a:r></a:p>
+</p:txBody></p:sp>
+<p:sp><p:nvSpPr><p:cNvPr id="4" name="List"/><p:nvPr/></p:nvSpPr><p:spPr><a:xfrm><a:off x="457200" y="4114800"/><a:ext cx="8229600" cy="2286000"/></a:xfrm></p:spPr><p:txBody><a:bodyPr/>
+<a:p><a:pPr><a:buAutoNum type="arabicPeriod"/></a:pPr><a:r><a:t>one</a:t></a:r></a:p>
+<a:p><a:pPr><a:buAutoNum type="arabicPeriod"/></a:pPr><a:r><a:t>two</a:t></a:r></a:p>
+<a:p><a:pPr lvl="1"><a:buAutoNum type="alphaLcParenR"/></a:pPr><a:r><a:t>nested</a:t></a:r></a:p>
+<a:p><a:pPr><a:buAutoNum type="arabicPeriod"/></a:pPr><a:r><a:t>three</a:t></a:r></a:p>
+</p:txBody></p:sp>
+</p:spTree></p:cSld></p:sld>"#.as_bytes(),
+        ),
+        (
+            "ppt/slides/_rels/slide1.xml.rels",
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/></Relationships>"#.as_bytes(),
+        ),
+        (
+            "ppt/slideLayouts/slideLayout1.xml",
+            r#"<p:sldLayout xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree/></p:cSld></p:sldLayout>"#.as_bytes(),
+        ),
+        (
+            "ppt/slideLayouts/_rels/slideLayout1.xml.rels",
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="../slideMasters/slideMaster1.xml"/></Relationships>"#.as_bytes(),
+        ),
+        (
+            "ppt/slideMasters/slideMaster1.xml",
+            r#"<p:sldMaster xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree/></p:cSld><p:txStyles>
+<p:titleStyle><a:lvl1pPr><a:buNone/></a:lvl1pPr></p:titleStyle>
+<p:bodyStyle>
+<a:lvl1pPr marL="228600" indent="-228600"><a:buFont typeface="Arial"/><a:buChar char="&#8226;"/></a:lvl1pPr>
+<a:lvl2pPr marL="685800" indent="-228600"><a:buClr><a:srgbClr val="FF0000"/></a:buClr><a:buSzPct val="80000"/><a:buChar char="&#8211;"/></a:lvl2pPr>
+</p:bodyStyle>
+</p:txStyles></p:sldMaster>"#.as_bytes(),
+        ),
+    ]);
+
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    let texts: Vec<(f64, &str, f64, Color)> = doc.pages[0]
+        .elements
+        .iter()
+        .filter_map(|el| match el {
+            PageElement::Text { x, text, style, .. } => Some((*x, text.as_str(), style.font_size, style.color)),
+            _ => None,
+        })
+        .collect();
+    let find = |prefix: &str| *texts.iter().find(|(_, t, _, _)| t.starts_with(prefix)).unwrap_or_else(|| panic!("{} が見つかりません: {:?}", prefix, texts));
+
+    // マスターのレベル別指定：レベル1は記号・テキストともに深くインデントされ、専用の記号・サイズ・色を使う
+    let (top_bullet_x, _, top_size, _) = find("\u{2022}");
+    let (sub_bullet_x, _, sub_size, sub_color) = find("\u{2013}");
+    let (top_text_x, ..) = find("Top level");
+    let (sub_text_x, ..) = find("Second level");
+    assert!(sub_bullet_x > top_bullet_x);
+    assert!(sub_text_x > top_text_x);
+    // marL の位置からテキストが始まる（ぶら下げインデント）
+    assert!((top_text_x - top_bullet_x - 228600.0 / 12700.0).abs() < 0.01);
+    assert!((sub_size - top_size * 0.8).abs() < 0.01);
+    assert_eq!(sub_color, Color::rgb(255, 0, 0));
+
+    // 自動番号はレベルごとに数え、浅いレベルに戻ると続きから番号が振られる
+    let numbers: Vec<&str> = texts
+        .iter()
+        .map(|(_, t, _, _)| t.trim_end())
+        .filter(|t| ["1.", "2.", "3.", "a)"].contains(t))
+        .collect();
+    assert_eq!(numbers, vec!["1.", "2.", "a)", "3."]);
+}
+
+#[test]
+fn test_list_embedded_assets_pptx() {
+    use wasm_document_converter::formats::assets::{list_embedded_assets, AssetKind};
+
+    // 一覧は画像をデコードしないため、PNGシグネチャ付きのダミーデータで十分
+    let png: Vec<u8> = b"\x89PNG\r\n\x1a\n".iter().copied().chain(0..64u8).collect();
+
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", br#"<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/media/image1.png", &png),
+    ]);
+
+    let assets = list_embedded_assets("pptx", &pptx).unwrap();
+    assert_eq!(assets.len(), 1, "{:?}", assets);
+    assert_eq!(assets[0].name, "ppt/media/image1.png");
+    assert_eq!(assets[0].kind, AssetKind::Image);
+    assert_eq!(assets[0].mime_type, "image/png");
+    assert_eq!(assets[0].size, png.len() as u64);
+
+    // ZIPベースでないフォーマットは空の一覧
+    assert!(list_embedded_assets("txt", b"plain").unwrap().is_empty());
+}
+
+#[test]
+fn test_unknown_preset_geometry_policy() {
+    use wasm_document_converter::converter::{Color, PageElement, TextFlowOptions, UnknownGeometryPolicy};
+
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", br#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld><p:spTree><p:sp>
+    <p:spPr>
+      <a:xfrm><a:off x="1270000" y="1270000"/><a:ext cx="2540000" cy="1270000"/></a:xfrm>
+      <a:prstGeom prst="madeUpShape"><a:avLst/></a:prstGeom>
+      <a:solidFill><a:srgbClr val="3366CC"/></a:solidFill>
+    </p:spPr>
+  </p:sp></p:spTree></p:cSld>
+</p:sld>"#),
+    ]);
+
+    let fill = Some(Color::rgb(0x33, 0x66, 0xCC));
+    let convert = |policy: UnknownGeometryPolicy| {
+        let options = TextFlowOptions { unknown_geometry_policy: policy, ..Default::default() };
+        formats::convert_by_extension_with_options("pptx", &pptx, &options).unwrap()
+    };
+
+    // 既定（Skip）では描画しないが、図形名は警告に残る
+    let doc = convert(UnknownGeometryPolicy::Skip);
+    assert!(!doc.pages[0].elements.iter().any(|el| matches!(el, PageElement::Rect { .. })));
+    assert!(doc.warnings.iter().any(|w| w.contains("madeUpShape")), "{:?}", doc.warnings);
+
+    // BoundingRect では図形の位置・大きさに塗りの矩形を描く
+    let doc = convert(UnknownGeometryPolicy::BoundingRect);
+    let rect = doc.pages[0].elements.iter().find_map(|el| match el {
+        PageElement::Rect { x, y, width, height, fill: f, .. } if *f == fill => Some((*x, *y, *width, *height)),
+        _ => None,
+    });
+    assert_eq!(rect, Some((100.0, 100.0, 200.0, 100.0)));
+    assert!(doc.warnings.iter().any(|w| w.contains("madeUpShape")));
+
+    // Ellipse では外接矩形に内接する楕円
+    let doc = convert(UnknownGeometryPolicy::Ellipse);
+    assert!(doc.pages[0].elements.iter().any(|el| matches!(
+        el,
+        PageElement::Ellipse { rx, ry, fill: f, .. } if *f == fill && *rx == 100.0 && *ry == 50.0
+    )));
+}
+
+#[test]
+fn test_pptx_tiled_blip_fill_repeats_texture() {
+    use wasm_document_converter::converter::PageElement;
+
+    // 8×8px（96dpiで6pt四方）の画像を 200×100pt の図形に敷き詰める / fillRect の余白付きで引き伸ばす
+    let slide = |fill_mode: &str| {
+        format!(
+            r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+       xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <p:cSld><p:spTree><p:sp>
+    <p:spPr>
+      <a:xfrm><a:off x="1270000" y="1270000"/><a:ext cx="2540000" cy="1270000"/></a:xfrm>
+      <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
+      <a:blipFill><a:blip r:embed="rId2"/>{}</a:blipFill>
+    </p:spPr>
+  </p:sp></p:spTree></p:cSld>
+</p:sld>"#,
+            fill_mode
+        )
+    };
+    let png = create_test_png(8, 8);
+    let convert = |fill_mode: &str| {
+        let slide_xml = slide(fill_mode);
+        let pptx = zip_package(&[
+            ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+            ("ppt/slides/slide1.xml", slide_xml.as_bytes()),
+            ("ppt/slides/_rels/slide1.xml.rels", br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.png"/>
+</Relationships>"#),
+            ("ppt/media/image1.png", &png),
+        ]);
+        formats::convert_by_extension("pptx", &pptx).unwrap()
+    };
+    let clipped_images = |doc: &wasm_document_converter::converter::Document| {
+        doc.pages[0]
+            .elements
+            .iter()
+            .find_map(|el| match el {
+                PageElement::ClipGroup { children, .. } => Some(
+                    children
+                        .iter()
+                        .filter_map(|c| match c {
+                            PageElement::Image { x, y, width, height, .. } => Some((*x, *y, *width, *height)),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                _ => None,
+            })
+            .unwrap_or_default()
+    };
+
+    // 敷き詰め: 6pt四方のタイルが図形の左上から並ぶ（34列×17行）
+    let tiles = clipped_images(&convert(r#"<a:tile tx="0" ty="0" sx="100000" sy="100000" algn="tl"/>"#));
+    assert_eq!(tiles.len(), 34 * 17);
+    assert!(tiles.iter().all(|&(_, _, w, h)| (w - 6.0).abs() < 1e-9 && (h - 6.0).abs() < 1e-9));
+    assert_eq!((tiles[0].0, tiles[0].1), (100.0, 100.0));
+    assert!((tiles[1].0 - 106.0).abs() < 1e-9);
+
+    // 引き伸ばし: fillRect の左右25%の余白の内側に1枚
+    let inset = clipped_images(&convert(r#"<a:stretch><a:fillRect l="25000" r="25000"/></a:stretch>"#));
+    assert_eq!(inset, vec![(150.0, 100.0, 100.0, 100.0)]);
+
+    // 余白の無い引き伸ばしは従来どおり図形いっぱいの画像
+    let doc = convert("<a:stretch><a:fillRect/></a:stretch>");
+    assert!(clipped_images(&doc).is_empty());
+    assert!(doc.pages[0].elements.iter().any(|el| matches!(
+        el,
+        PageElement::Image { x, y, width, height, .. } if (*x, *y, *width, *height) == (100.0, 100.0, 200.0, 100.0)
+    )));
+}
+
+#[test]
+fn test_pptx_ole_object_renders_preview_image() {
+    use wasm_document_converter::converter::PageElement;
+
+    // プレビューPNG付きのExcelシート（Choice / Fallback の両方に p:oleObj）と、プレビューの無い数式オブジェクト
+    let slide_xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+       xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"
+       xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006">
+  <p:cSld><p:spTree>
+    <p:graphicFrame>
+      <p:nvGraphicFramePr><p:cNvPr id="2" name="Object 1"/><p:cNvGraphicFramePr/><p:nvPr/></p:nvGraphicFramePr>
+      <p:xfrm><a:off x="1270000" y="1270000"/><a:ext cx="2540000" cy="1270000"/></p:xfrm>
+      <a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/presentationml/2006/ole">
+        <mc:AlternateContent>
+          <mc:Choice Requires="v"><p:oleObj spid="_x0000_s1026" name="Worksheet" r:id="rId3" imgW="1" imgH="1" progId="Excel.Sheet.12"/></mc:Choice>
+          <mc:Fallback>
+            <p:oleObj name="Worksheet" r:id="rId3" imgW="1" imgH="1" progId="Excel.Sheet.12">
+              <p:embed/>
+              <p:pic>
+                <p:nvPicPr><p:cNvPr id="0" name=""/><p:cNvPicPr/><p:nvPr/></p:nvPicPr>
+                <p:blipFill><a:blip r:embed="rId2"/><a:stretch><a:fillRect/></a:stretch></p:blipFill>
+                <p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="10" cy="10"/></a:xfrm></p:spPr>
+              </p:pic>
+            </p:oleObj>
+          </mc:Fallback>
+        </mc:AlternateContent>
+      </a:graphicData></a:graphic>
+    </p:graphicFrame>
+    <p:graphicFrame>
+      <p:xfrm><a:off x="1270000" y="3810000"/><a:ext cx="1270000" cy="635000"/></p:xfrm>
+      <a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/presentationml/2006/ole">
+        <p:oleObj r:id="rId4" progId="Equation.3"><p:embed/></p:oleObj>
+      </a:graphicData></a:graphic>
+    </p:graphicFrame>
+  </p:spTree></p:cSld>
+</p:sld>"#;
+    let png = create_test_png(4, 2);
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", slide_xml.as_bytes()),
+        ("ppt/slides/_rels/slide1.xml.rels", br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.png"/>
+  <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/package" Target="../embeddings/Microsoft_Excel_Worksheet.xlsx"/>
+</Relationships>"#),
+        ("ppt/media/image1.png", &png),
+    ]);
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+
+    // プレビュー画像がフレームの位置・サイズで1枚だけ描画される
+    let images: Vec<_> = doc.pages[0]
+        .elements
+        .iter()
+        .filter_map(|el| match el {
+            PageElement::Image { x, y, width, height, .. } => Some((*x, *y, *width, *height)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(images, vec![(100.0, 100.0, 200.0, 100.0)]);
+
+    // プレビューの無いオブジェクトはProgID付きのプレースホルダー
+    assert!(doc.pages[0].elements.iter().any(|el| matches!(
+        el,
+        PageElement::Rect { x, y, width, height, .. } if (*x, *y, *width, *height) == (100.0, 300.0, 100.0, 50.0)
+    )));
+    assert!(doc.pages[0].elements.iter().any(|el| matches!(
+        el,
+        PageElement::Text { text, .. } if text == "[OLE: Equation.3]"
+    )));
+
+    assert!(doc.warnings.iter().any(|w| w.contains("プレビュー画像") && w.contains("Excel.Sheet.12")));
+    assert!(doc.warnings.iter().any(|w| w.contains("プレースホルダー") && w.contains("Equation.3")));
+}
+
+#[test]
+fn test_pptx_alternate_content_renders_shape_once() {
+    use wasm_document_converter::converter::{Color, PageElement};
+
+    // 同じ図形を mc:Choice（赤）と mc:Fallback（青）の両方に持つスライド
+    let shape = |color: &str| {
+        format!(
+            r#"<p:sp><p:spPr>
+              <a:xfrm><a:off x="1270000" y="1270000"/><a:ext cx="2540000" cy="1270000"/></a:xfrm>
+              <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
+              <a:solidFill><a:srgbClr val="{}"/></a:solidFill>
+            </p:spPr></p:sp>"#,
+            color
+        )
+    };
+    let slide_xml = format!(
+        r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+       xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006"
+       xmlns:a14="http://schemas.microsoft.com/office/drawing/2010/main">
+  <p:cSld><p:spTree>
+    <mc:AlternateContent>
+      <mc:Choice Requires="a14">{}</mc:Choice>
+      <mc:Fallback>{}</mc:Fallback>
+    </mc:AlternateContent>
+  </p:spTree></p:cSld>
+</p:sld>"#,
+        shape("FF0000"),
+        shape("0000FF")
+    );
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", slide_xml.as_bytes()),
+    ]);
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+
+    let fills: Vec<Color> = doc.pages[0]
+        .elements
+        .iter()
+        .filter_map(|el| match el {
+            PageElement::Rect { fill: Some(fill), .. } | PageElement::Path { fill: Some(fill), .. } => Some(*fill),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(fills, vec![Color::rgb(255, 0, 0)]);
+}
+
+#[test]
+fn test_pptx_svg_blip_is_rasterized() {
+    use wasm_document_converter::converter::PageElement;
+
+    // PNGの代替画像（rId2）とSVG（rId3、緑の円1つ）を持つ 100×100pt の画像
+    let slide_xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+       xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <p:cSld><p:spTree><p:pic>
+    <p:nvPicPr><p:cNvPr id="2" name="Graphic 1"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr>
+    <p:blipFill>
+      <a:blip r:embed="rId2"><a:extLst><a:ext uri="{96DAC541-7B7A-43D3-8B79-37D633B846F1}">
+        <asvg:svgBlip xmlns:asvg="http://schemas.microsoft.com/office/drawing/2016/SVG/main" r:embed="rId3"/>
+      </a:ext></a:extLst></a:blip>
+      <a:stretch><a:fillRect/></a:stretch>
+    </p:blipFill>
+    <p:spPr><a:xfrm><a:off x="1270000" y="1270000"/><a:ext cx="1270000" cy="1270000"/></a:xfrm></p:spPr>
+  </p:pic></p:spTree></p:cSld>
+</p:sld>"#;
+    let png = create_test_png(4, 4);
+    let convert = |svg: &str| {
+        let pptx = zip_package(&[
+            ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+            ("ppt/slides/slide1.xml", slide_xml.as_bytes()),
+            ("ppt/slides/_rels/slide1.xml.rels", br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.png"/>
+  <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image2.svg"/>
+</Relationships>"#),
+            ("ppt/media/image1.png", &png),
+            ("ppt/media/image2.svg", svg.as_bytes()),
+        ]);
+        let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+        doc.pages[0]
+            .elements
+            .iter()
+            .find_map(|el| match el {
+                PageElement::Image { x, y, width, height, data, mime_type } => {
+                    assert_eq!((*x, *y, *width, *height), (100.0, 100.0, 100.0, 100.0));
+                    Some((data.clone(), mime_type.clone()))
+                }
+                _ => None,
+            })
+            .expect("image element")
+    };
+
+    // SVGは図形の大きさ（300dpi）でPNGに変換され、円の中心は緑・四隅は透明
+    let (data, mime_type) = convert(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100"><circle cx="50" cy="50" r="40" fill="#00C000"/></svg>"##,
+    );
+    assert_eq!(mime_type, "image/png");
+    assert_ne!(data, png);
+    let (width, height, pixels) = decode_png_pixels(&data);
+    assert_eq!((width, height), (417, 417));
+    let pixel = |x: u32, y: u32| {
+        let i = ((y * width + x) * 4) as usize;
+        [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+    };
+    assert_eq!(pixel(width / 2, height / 2), [0, 192, 0, 255]);
+    assert_eq!(pixel(2, 2)[3], 0);
+
+    // 描画できないSVG（グラデーション）はPNGの代替画像を使う
+    let (data, mime_type) = convert(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><rect width="10" height="10" fill="url(#g)"/></svg>"##,
+    );
+    assert_eq!(mime_type, "image/png");
+    assert_eq!(data, png);
+}
+
+#[test]
+fn test_conversion_report_lists_dropped_features() {
+    use wasm_document_converter::converter::{TextFlowOptions, UnknownGeometryPolicy};
+    use wasm_document_converter::Converter;
+
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", br#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld><p:spTree>
+  <p:sp>
+    <p:spPr>
+      <a:xfrm><a:off x="1270000" y="1270000"/><a:ext cx="2540000" cy="1270000"/></a:xfrm>
+      <a:prstGeom prst="madeUpShape"><a:avLst/></a:prstGeom>
+      <a:solidFill><a:srgbClr val="3366CC"/></a:solidFill>
+    </p:spPr>
+  </p:sp>
+  <p:sp>
+    <p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="2540000" cy="635000"/></a:xfrm></p:spPr>
+    <p:txBody><a:bodyPr><a:prstTxWarp prst="textWave1"><a:avLst/></a:prstTxWarp></a:bodyPr>
+      <a:p><a:r><a:t>Wavy</a:t></a:r></a:p></p:txBody>
+  </p:sp>
+  </p:spTree></p:cSld>
+</p:sld>"#),
+    ]);
+
+    let mut converter = Converter::new();
+    let report = converter.conversion_report("slides.pptx", &pptx).unwrap();
+    assert_eq!(report.pages.len(), 1);
+    let page = &report.pages[0];
+    assert_eq!(page.page, 1);
+    assert_eq!(page.dropped, vec!["preset geometry: madeUpShape".to_string()]);
+    assert_eq!(page.approximated, vec!["WordArt warp: textWave1".to_string()]);
+    assert!(page.ok.iter().any(|f| f == "text"), "{:?}", page.ok);
+    assert_eq!(report.warnings.len(), 2);
+
+    // 代替描画を指定した図形は「近似」に分類される
+    converter.set_text_options(TextFlowOptions {
+        unknown_geometry_policy: UnknownGeometryPolicy::BoundingRect,
+        ..TextFlowOptions::default()
+    });
+    let report = converter.conversion_report("slides.pptx", &pptx).unwrap();
+    assert!(report.pages[0].dropped.is_empty());
+    assert!(report.pages[0].approximated.contains(&"preset geometry: madeUpShape".to_string()));
+    assert!(report.pages[0].ok.iter().any(|f| f == "shape"));
+}
+
+/// PPTバイナリのレコード（8バイトのヘッダー + 本体）
+fn ppt_record(ver: u16, instance: u16, rec_type: u16, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(ver | (instance << 4)).to_le_bytes());
+    out.extend_from_slice(&rec_type.to_le_bytes());
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(body);
+    out
+}
+
+#[test]
+fn test_ppt_extracts_slide_text() {
+    use std::io::Write;
+
+    let text_header = |text_type: u32| ppt_record(0, 0, 0x0F9F, &text_type.to_le_bytes());
+    let chars = |text: &str| {
+        let body: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        ppt_record(0, 0, 0x0FA0, &body)
+    };
+    let persist = ppt_record(0, 0, 0x03F3, &[0; 20]);
+    let slide_list = [
+        persist.clone(),
+        text_header(0),
+        chars("四半期報告"),
+        text_header(1),
+        chars("売上は前年比12%増\r新規顧客 48社"),
+        persist,
+        text_header(0),
+        ppt_record(0, 0, 0x0FA8, b"Summary"),
+    ]
+    .concat();
+    // DocumentAtom: スライドサイズ 5760×4320 マスター単位（720×540pt）
+    let mut document_atom = Vec::new();
+    document_atom.extend_from_slice(&5760i32.to_le_bytes());
+    document_atom.extend_from_slice(&4320i32.to_le_bytes());
+    document_atom.extend_from_slice(&[0; 32]);
+    let document = [
+        ppt_record(1, 0, 0x03E9, &document_atom),
+        ppt_record(0xF, 0, 0x0FF0, &slide_list),
+        // マスターのスライド一覧（instance 1）は抽出しない
+        ppt_record(0xF, 1, 0x0FF0, &[text_header(0), chars("マスタータイトルの書式設定")].concat()),
+    ]
+    .concat();
+    let stream = ppt_record(0xF, 0, 0x03E8, &document);
+
+    let mut compound = cfb::CompoundFile::create(std::io::Cursor::new(Vec::new())).unwrap();
+    compound.create_stream("/PowerPoint Document").unwrap().write_all(&stream).unwrap();
+    let ppt = compound.into_inner().into_inner();
+
+    let doc = formats::convert_by_extension("ppt", &ppt).unwrap();
+    assert_eq!(doc.pages.len(), 2);
+    assert_eq!((doc.pages[0].width, doc.pages[0].height), (720.0, 540.0));
+    assert_eq!(doc.pages[0].plain_text(), "四半期報告\n売上は前年比12%増\n新規顧客 48社");
+    assert_eq!(doc.pages[1].plain_text(), "Summary");
+    assert!(!doc.extract_text().concat().contains("マスター"));
+
+    assert!(formats::convert_by_extension("ppt", b"not a compound file").is_err());
+}
+
+/// XLS（BIFF8）のレコード（4バイトのヘッダー + 本体）
+fn biff_record(rec_type: u16, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&rec_type.to_le_bytes());
+    out.extend_from_slice(&(body.len() as u16).to_le_bytes());
+    out.extend_from_slice(body);
+    out
+}
+
+#[test]
+fn test_xls_extracts_cell_values() {
+    use std::io::Write;
+
+    let bof = |dt: u16| biff_record(0x0809, &[&0x0600u16.to_le_bytes()[..], &dt.to_le_bytes(), &[0; 12]].concat());
+    let cell = |rec_type: u16, row: u16, col: u16, value: &[u8]| {
+        biff_record(rec_type, &[&row.to_le_bytes()[..], &col.to_le_bytes(), &[0, 0], value].concat())
+    };
+
+    // 共有文字列: "品名", "りんご", "Total"（圧縮・非圧縮の混在）
+    let mut sst = Vec::new();
+    sst.extend_from_slice(&3u32.to_le_bytes());
+    sst.extend_from_slice(&3u32.to_le_bytes());
+    for text in ["品名", "りんご"] {
+        sst.extend_from_slice(&(text.chars().count() as u16).to_le_bytes());
+        sst.push(0x01);
+        sst.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+    }
+    sst.extend_from_slice(&[5, 0, 0]);
+    sst.extend_from_slice(b"Total");
+
+    let sheet = [
+        bof(0x0010),
+        cell(0x00FD, 0, 0, &0u32.to_le_bytes()),
+        cell(0x00FD, 1, 0, &1u32.to_le_bytes()),
+        cell(0x0203, 1, 1, &2.5f64.to_le_bytes()),
+        // RK: 整数 120
+        cell(0x027E, 1, 2, &((120u32 << 2) | 0x02).to_le_bytes()),
+        cell(0x00FD, 2, 0, &2u32.to_le_bytes()),
+        biff_record(0x000A, &[]),
+    ]
+    .concat();
+
+    let sheet_name = "在庫";
+    let boundsheet = |offset: u32| {
+        let mut body = offset.to_le_bytes().to_vec();
+        body.extend_from_slice(&[0, 0, sheet_name.chars().count() as u8, 0x01]);
+        body.extend(sheet_name.encode_utf16().flat_map(u16::to_le_bytes));
+        biff_record(0x0085, &body)
+    };
+    let globals_len = [bof(0x0005), boundsheet(0), biff_record(0x00FC, &sst), biff_record(0x000A, &[])]
+        .concat()
+        .len();
+    let workbook = [
+        bof(0x0005),
+        boundsheet(globals_len as u32),
+        biff_record(0x00FC, &sst),
+        biff_record(0x000A, &[]),
+        sheet,
+    ]
+    .concat();
+
+    let mut compound = cfb::CompoundFile::create(std::io::Cursor::new(Vec::new())).unwrap();
+    compound.create_stream("/Workbook").unwrap().write_all(&workbook).unwrap();
+    let xls = compound.into_inner().into_inner();
+
+    let doc = formats::convert_by_extension("xls", &xls).unwrap();
+    assert_eq!(doc.pages.len(), 1);
+    let text = doc.pages[0].plain_text();
+    assert!(text.contains(sheet_name), "{}", text);
+    assert!(text.contains("品名"));
+    assert!(text.contains("りんご\t2.50\t120"), "{}", text);
+    assert!(text.contains("Total"));
+}
+
+#[test]
+fn test_extract_transitions_reports_fade() {
+    use wasm_document_converter::formats::transitions::extract_transitions;
+
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", br#"<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld/></p:sld>"#),
+        ("ppt/slides/slide2.xml", br#"<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld/><p:transition spd="med" advTm="3000"><p:fade/></p:transition></p:sld>"#),
+    ]);
+
+    let transitions = extract_transitions("pptx", &pptx).unwrap();
+    let json: serde_json::Value = serde_json::to_value(&transitions).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([{
+            "slide": 2,
+            "type": "fade",
+            "duration": 750,
+            "advanceOnClick": true,
+            "advanceAfter": 3000
+        }])
+    );
+}
+
+#[test]
+fn test_pptx_embedded_font_registered() {
+    use wasm_document_converter::font_manager::FontManager;
+
+    let ttf = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/fonts/NotoSansJP-Regular.ttf")).unwrap();
+
+    let pptx = zip_package(&[
+        (
+            "ppt/presentation.xml",
+            br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:embeddedFontLst><p:embeddedFont><p:font typeface="Embedded Sans"/><p:regular r:id="rId9"/></p:embeddedFont></p:embeddedFontLst></p:presentation>"#,
+        ),
+        (
+            "ppt/_rels/presentation.xml.rels",
+            br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId9" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/font" Target="fonts/font1.fntdata"/></Relationships>"#,
+        ),
+        ("ppt/slides/slide1.xml", br#"<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/fonts/font1.fntdata", &ttf),
+    ]);
+
+    let mut doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    assert_eq!(doc.embedded_fonts.len(), 1);
+    assert_eq!(doc.embedded_fonts[0].name, "Embedded Sans");
+
+    let mut fm = FontManager::new();
+    assert!(!fm.available_fonts().contains(&"Embedded Sans".to_string()));
+    fm.set_document_fonts(std::mem::take(&mut doc.embedded_fonts));
+    assert!(fm.available_fonts().contains(&"Embedded Sans".to_string()));
+    assert_eq!(fm.get_font_data("embedded sans").map(<[u8]>::len), Some(ttf.len()));
+}
+
+#[test]
+fn test_pdf_normalizes_cmyk_jpeg() {
+    use wasm_document_converter::converter::{Page, PageElement};
+
+    // シアン（横方向）とマゼンタ（縦方向）のインク量が変化するCMYK JPEG
+    let size = 64u16;
+    let mut cmyk = Vec::new();
+    for y in 0..size {
+        for x in 0..size {
+            cmyk.extend_from_slice(&[(x * 4) as u8, (y * 4) as u8, 0, 0]);
+        }
+    }
+    let mut jpeg = Vec::new();
+    jpeg_encoder::Encoder::new(&mut jpeg, 95)
+        .encode(&cmyk, size, size, jpeg_encoder::ColorType::Cmyk)
+        .unwrap();
+
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Image {
+        x: 50.0,
+        y: 50.0,
+        width: 64.0,
+        height: 64.0,
+        data: jpeg.clone(),
+        mime_type: "image/jpeg".to_string(),
+    });
+    let mut doc = Document::new();
+    doc.pages.push(page);
+    let pdf = pdf_writer::render_to_pdf(&doc);
+
+    // 画像XObjectのストリームを取り出す（フォント等のバイナリを含むためバイト列で検索）
+    let find = |from: usize, needle: &[u8]| {
+        pdf[from..].windows(needle.len()).position(|w| w == needle).map(|p| p + from)
+    };
+    let dict_pos = find(0, b"/Subtype /Image").expect("画像XObjectがありません");
+    let dict_end = find(dict_pos, b">>").unwrap();
+    let dict = String::from_utf8_lossy(&pdf[dict_pos..dict_end]).into_owned();
+    assert!(dict.contains("/DeviceRGB"));
+    let stream_start = find(dict_end, b"stream\n").unwrap() + 7;
+    let stream_end = find(stream_start, b"\nendstream").unwrap();
+    let stream = &pdf[stream_start..stream_end];
+    assert_ne!(stream, jpeg.as_slice(), "CMYK JPEGをそのまま埋め込んではいけない");
+
+    let rgb = if dict.contains("/DCTDecode") {
+        let mut decoder = jpeg_decoder::Decoder::new(stream);
+        let pixels = decoder.decode().unwrap();
+        assert_eq!(decoder.info().unwrap().pixel_format, jpeg_decoder::PixelFormat::RGB24);
+        pixels
+    } else {
+        miniz_oxide::inflate::decompress_to_vec_zlib(stream).unwrap()
+    };
+    let pixel = |x: usize, y: usize| {
+        let i = (y * size as usize + x) * 3;
+        (rgb[i], rgb[i + 1], rgb[i + 2])
+    };
+    // インクなし → 白、シアンのみ → 赤成分が小さい、マゼンタのみ → 緑成分が小さい
+    let (r, g, b) = pixel(2, 2);
+    assert!(r > 220 && g > 220 && b > 220, "{:?}", (r, g, b));
+    let (r, g, b) = pixel(60, 2);
+    assert!(r < 50 && g > 200 && b > 200, "{:?}", (r, g, b));
+    let (r, g, b) = pixel(2, 60);
+    assert!(r > 200 && g < 50 && b > 200, "{:?}", (r, g, b));
+}
+
+#[test]
+fn test_limits_reject_excessive_slide_count() {
+    use wasm_document_converter::converter::{ConversionLimits, TextFlowOptions};
+
+    let slide_paths: Vec<String> = (1..=20).map(|i| format!("ppt/slides/slide{}.xml", i)).collect();
+    let mut files: Vec<(&str, &[u8])> = vec![(
+        "ppt/presentation.xml",
+        br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#,
+    )];
+    for path in &slide_paths {
+        files.push((path, br#"<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#));
+    }
+    let pptx = zip_package(&files);
+
+    let limits = ConversionLimits { max_pages: 10, ..Default::default() };
+    let err = formats::convert_by_extension_with_limits("pptx", &pptx, &TextFlowOptions::default(), &limits)
+        .unwrap_err();
+    assert_eq!(err.format, "PPTX");
+    assert!(err.message.contains("上限"), "{}", err.message);
+
+    // 展開量の上限も同様にエラーになる
+    let limits = ConversionLimits { max_total_decompressed_bytes: 256, ..Default::default() };
+    assert!(formats::convert_by_extension_with_limits("pptx", &pptx, &TextFlowOptions::default(), &limits).is_err());
+
+    // 既定の上限内であれば変換できる
+    assert!(formats::convert_by_extension("pptx", &pptx).is_ok());
+}
+
+/// 展開後サイズを偽ったXLSXのパーツも、calamine に渡す前に展開量の上限で打ち切るテスト
+#[test]
+fn test_limits_reject_xlsx_with_falsified_uncompressed_size() {
+    use std::io::Write;
+    use wasm_document_converter::converter::{ConversionLimits, TextFlowOptions};
+
+    let xlsx = create_xlsx_with_sheet(r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+  <sheetData><row r="1"><c r="A1"><v>1</v></c></row></sheetData>
+</worksheet>"#);
+    // calamine だけが読む共有文字列のパーツに、よく圧縮される1MBの空白を入れる
+    let mut zip = zip::ZipWriter::new_append(std::io::Cursor::new(xlsx)).unwrap();
+    zip.start_file("xl/sharedStrings.xml", zip::write::SimpleFileOptions::default()).unwrap();
+    zip.write_all(br#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#).unwrap();
+    zip.write_all(&vec![b' '; 1 << 20]).unwrap();
+    zip.write_all(b"</sst>").unwrap();
+    let mut xlsx = zip.finish().unwrap().into_inner();
+
+    // ローカルヘッダーと中央ディレクトリの展開後サイズを 100 バイトに書き換える
+    let name = b"xl/sharedStrings.xml";
+    for (signature, size_offset, name_offset) in [(b"PK\x03\x04", 22, 30), (b"PK\x01\x02", 24, 46)] {
+        let start = (0..xlsx.len() - name_offset - name.len())
+            .find(|&i| &xlsx[i..i + 4] == signature && &xlsx[i + name_offset..i + name_offset + name.len()] == name)
+            .unwrap();
+        xlsx[start + size_offset..start + size_offset + 4].copy_from_slice(&100u32.to_le_bytes());
+    }
+
+    // 宣言上のサイズは上限内なので中央ディレクトリの検査は通るが、実際の展開量で打ち切られる
+    let limits = ConversionLimits { max_total_decompressed_bytes: 64 * 1024, ..Default::default() };
+    assert!(formats::limits::check_archive("xlsx", &xlsx, &limits).is_ok());
+    let err = formats::convert_by_extension_with_limits("xlsx", &xlsx, &TextFlowOptions::default(), &limits)
+        .unwrap_err();
+    assert_eq!(err.format, "XLSX");
+    assert!(err.message.contains("上限"), "{}", err.message);
+}
+
+#[test]
+fn test_arc_to_quarter_circle_matches_analytic_arc() {
+    use wasm_document_converter::converter::{Color, Page, PageElement, PathCommand};
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+
+    // 中心 (100, 100)・半径 80 の四分円（0°→90°、時計回り）
+    let (cx, cy, r) = (100.0, 100.0, 80.0);
+    let arc = PathCommand::ArcTo(r, r, 0.0, false, true, cx, cy + r);
+
+    // ベジェ近似の各点は解析的な円から 0.1pt 以内
+    let cubics = arc.arc_cubics((cx + r, cy));
+    assert!(!cubics.is_empty());
+    let mut start = (cx + r, cy);
+    for [c1x, c1y, c2x, c2y, ex, ey] in cubics {
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            let it = 1.0 - t;
+            let px = it * it * it * start.0 + 3.0 * it * it * t * c1x + 3.0 * it * t * t * c2x + t * t * t * ex;
+            let py = it * it * it * start.1 + 3.0 * it * it * t * c1y + 3.0 * it * t * t * c2y + t * t * t * ey;
+            let dist = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+            assert!((dist - r).abs() < 0.1, "t={} dist={}", t, dist);
+            assert!(px >= cx - 0.01 && py >= cy - 0.01, "右下の四分円に収まる");
+        }
+        start = (ex, ey);
+    }
+    assert_eq!(start, (cx, cy + r));
+
+    // 扇形として塗り、円周の内側・外側のピクセルを確認
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Path {
+        commands: vec![PathCommand::MoveTo(cx + r, cy), arc, PathCommand::LineTo(cx, cy), PathCommand::Close],
+        fill: Some(Color::BLACK),
+        stroke: None,
+        stroke_width: 0.0,
+        rotation_deg: 0.0,
+        stroke_style: Default::default(),
+    });
+    let config = ImageRenderConfig { dpi: 72.0, ..Default::default() };
+    let png = image_renderer::render_page_to_image(&page, &config, &FontManager::new());
+    let (width, _, pixels) = decode_png_pixels(&png);
+    let pixel = |x: f64, y: f64| pixels[((y as u32 * width + x as u32) * 4) as usize];
+    for deg in [10.0_f64, 30.0, 45.0, 60.0, 80.0] {
+        let (s, c) = deg.to_radians().sin_cos();
+        assert!(pixel(cx + (r - 3.0) * c, cy + (r - 3.0) * s) < 50, "{}° 内側", deg);
+        assert!(pixel(cx + (r + 3.0) * c, cy + (r + 3.0) * s) > 200, "{}° 外側", deg);
+    }
+
+    // PDFでは直線ではなくベジェ曲線として出力される
+    let mut doc = Document::new();
+    doc.pages.push(page);
+    let pdf = String::from_utf8_lossy(&pdf_writer::render_to_pdf(&doc)).to_string();
+    assert!(pdf.contains(" c\n"), "弧はベジェ曲線で出力される");
+}
+
+#[test]
+fn test_donut_center_is_unfilled() {
+    use wasm_document_converter::converter::{Color, Page, PageElement};
+    use wasm_document_converter::formats::pptx_layout::generate_preset_path;
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+
+    // 外周と内周が1つのパスに含まれ、偶奇規則で中央が抜ける
+    let commands = generate_preset_path("donut", 0.0, 0.0, 100.0, 100.0).unwrap();
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Path {
+        commands,
+        fill: Some(Color::rgb(255, 0, 0)),
+        stroke: None,
+        stroke_width: 0.0,
+        rotation_deg: 0.0,
+        stroke_style: Default::default(),
+    });
+
+    let config = ImageRenderConfig { dpi: 72.0, ..Default::default() };
+    let png = image_renderer::render_page_to_image(&page, &config, &FontManager::new());
+    let (width, _, pixels) = decode_png_pixels(&png);
+    let pixel = |x: u32, y: u32| {
+        let i = ((y * width + x) * 4) as usize;
+        (pixels[i], pixels[i + 1], pixels[i + 2])
+    };
+    assert_eq!(pixel(50, 50), (255, 255, 255), "中央は塗られない");
+    assert_eq!(pixel(12, 50), (255, 0, 0), "リング部分は塗られる");
+    assert_eq!(pixel(50, 88), (255, 0, 0), "リング部分は塗られる");
+
+    let mut doc = Document::new();
+    doc.pages.push(page);
+    let pdf = String::from_utf8_lossy(&pdf_writer::render_to_pdf(&doc)).to_string();
+    assert!(pdf.contains("f*\n"), "PDFも偶奇規則で塗る");
+}
+
+#[test]
+fn test_pdf_rasterize_pages_embeds_single_full_page_image() {
+    use wasm_document_converter::converter::{Color, Page, PageElement};
+
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Rect {
+        x: 50.0,
+        y: 50.0,
+        width: 200.0,
+        height: 100.0,
+        fill: Some(Color::rgb(255, 0, 0)),
+        stroke: None,
+        stroke_width: 0.0,
+        rotation_deg: 0.0,
+        stroke_style: Default::default(),
+    });
+    page.elements.push(PageElement::Text {
+        x: 50.0,
+        y: 200.0,
+        width: 300.0,
+        text: "Raster".to_string(),
+        style: FontStyle::default(),
+        align: wasm_document_converter::converter::TextAlign::Left,
+    });
+    let mut doc = Document::new();
+    doc.pages.push(page);
+
+    let options = pdf_writer::PdfOptions { rasterize_pages: true, raster_dpi: Some(72.0), ..Default::default() };
+    let pdf = pdf_writer::render_to_pdf_with_options(&doc, &FontManager::new(), &options);
+    let pdf_str = String::from_utf8_lossy(&pdf);
+
+    // ページ全面の画像XObjectが1つだけで、ベクターのテキスト・図形は出力されない
+    assert_eq!(pdf_str.matches("/Subtype /Image").count(), 1);
+    assert!(pdf_str.contains("/XObject << /Im0 "));
+    assert!(pdf_str.contains("/Width 595 /Height 841"));
+    assert!(pdf_str.contains("595.28 0 0 841.89 0 0 cm\n/Im0 Do"));
+    assert!(!pdf_str.contains("BT\n"));
+    assert!(!pdf_str.contains(" re\nf\n"));
+
+    // 既定ではベクター出力のまま
+    let pdf = String::from_utf8_lossy(&pdf_writer::render_to_pdf(&doc)).to_string();
+    assert!(!pdf.contains("/Subtype /Image"));
+}
+
+#[test]
+fn test_pdf_rasterized_page_with_invisible_text_layer() {
+    use wasm_document_converter::converter::{Page, PageElement, TextAlign};
+
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Text {
+        x: 72.0,
+        y: 100.0,
+        width: 300.0,
+        text: "Searchable".to_string(),
+        style: FontStyle::default(),
+        align: TextAlign::Left,
+    });
+    let mut doc = Document::new();
+    doc.pages.push(page);
+
+    let options = pdf_writer::PdfOptions {
+        rasterize_pages: true,
+        raster_dpi: Some(72.0),
+        text_layer: true,
+        ..Default::default()
+    };
+    let pdf = pdf_writer::render_to_pdf_with_options(&doc, &FontManager::new(), &options);
+    let pdf_str = String::from_utf8_lossy(&pdf);
+
+    // 同じページのコンテンツに、全面画像の描画と不可視テキストが両方含まれる
+    assert_eq!(pdf_str.matches("/Subtype /Image").count(), 1);
+    let image_pos = pdf_str.find("/Im0 Do").expect("全面画像");
+    let text_pos = pdf_str.find("BT\n3 Tr\n").expect("不可視テキスト");
+    assert!(text_pos > image_pos, "テキストは画像の上に重ねる");
+    let text_op = &pdf_str[text_pos..pdf_str[text_pos..].find("ET\n").unwrap() + text_pos];
+    assert!(text_op.contains(&format!("72 {} Td", 841.89 - 100.0 - 10.0)), "{}", text_op);
+
+    // text_layer を指定しなければテキストは出力されない
+    let options = pdf_writer::PdfOptions { rasterize_pages: true, raster_dpi: Some(72.0), ..Default::default() };
+    let pdf = pdf_writer::render_to_pdf_with_options(&doc, &FontManager::new(), &options);
+    assert!(!String::from_utf8_lossy(&pdf).contains("3 Tr"));
+}
+
+#[test]
+fn test_grayscale_and_black_white_color_modes() {
+    use wasm_document_converter::converter::{Color, ColorMode, Page, PageElement};
+
+    let rect = |x: f64, color: Color| PageElement::Rect {
+        x,
+        y: 100.0,
+        width: 100.0,
+        height: 100.0,
+        fill: Some(color),
+        stroke: None,
+        stroke_width: 0.0,
+        rotation_deg: 0.0,
+        stroke_style: Default::default(),
+    };
+    let mut page = Page::a4();
+    page.elements.push(rect(100.0, Color::rgb(255, 0, 0)));
+    page.elements.push(rect(300.0, Color::rgb(255, 255, 0)));
+
+    let pixel_at = |mode: ColorMode, x: u32, y: u32| {
+        let config = image_renderer::ImageRenderConfig { dpi: 72.0, color_mode: mode, ..Default::default() };
+        let png = image_renderer::render_page_to_image(&page, &config, &FontManager::new());
+        let (width, _, pixels) = decode_png_pixels(&png);
+        let i = ((y * width + x) * 4) as usize;
+        [pixels[i], pixels[i + 1], pixels[i + 2]]
+    };
+
+    // 赤の輝度は 0.299 * 255 ≈ 76
+    assert_eq!(pixel_at(ColorMode::Color, 150, 150), [255, 0, 0]);
+    assert_eq!(pixel_at(ColorMode::Grayscale, 150, 150), [76, 76, 76]);
+    // 白黒ではしきい値より暗い赤は黒、明るい黄は白
+    assert_eq!(pixel_at(ColorMode::BlackWhite, 150, 150), [0, 0, 0]);
+    assert_eq!(pixel_at(ColorMode::BlackWhite, 350, 150), [255, 255, 255]);
+
+    // PDFでは塗りを DeviceGray の g 演算子で出力
+    let mut doc = Document::new();
+    doc.pages.push(page.clone());
+    let options = pdf_writer::PdfOptions { color_mode: ColorMode::Grayscale, ..Default::default() };
+    let pdf = pdf_writer::render_to_pdf_with_options(&doc, &FontManager::new(), &options);
+    let pdf_str = String::from_utf8_lossy(&pdf);
+    assert!(pdf_str.contains(&format!("{} g\n", 76.0 / 255.0)), "{}", pdf_str);
+    assert!(!pdf_str.contains(" rg\n"));
+}
+
+/// グループシェイプの範囲からはみ出した子シェイプはグループの範囲でクリップされる
+#[test]
+fn test_group_shape_clips_oversized_child() {
+    use wasm_document_converter::converter::PageElement;
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+
+    // グループ: (100pt, 100pt) から 100pt 四方。子の矩形: (50pt, 50pt) から 200pt 四方
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", br#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld><p:spTree><p:grpSp>
+    <p:grpSpPr><a:xfrm>
+      <a:off x="1270000" y="1270000"/><a:ext cx="1270000" cy="1270000"/>
+      <a:chOff x="1270000" y="1270000"/><a:chExt cx="1270000" cy="1270000"/>
+    </a:xfrm></p:grpSpPr>
+    <p:sp><p:spPr>
+      <a:xfrm><a:off x="635000" y="635000"/><a:ext cx="2540000" cy="2540000"/></a:xfrm>
+      <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
+      <a:solidFill><a:srgbClr val="FF0000"/></a:solidFill>
+    </p:spPr></p:sp>
+  </p:grpSp></p:spTree></p:cSld>
+</p:sld>"#),
+    ]);
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    let page = &doc.pages[0];
+
+    let clip_group = page.elements.iter().find_map(|el| match el {
+        PageElement::ClipGroup { clip_path, children } => Some((clip_path, children)),
+        _ => None,
+    });
+    let (clip_path, children) = clip_group.expect("はみ出した子シェイプはクリップグループに入る");
+    assert!(children.iter().any(|el| matches!(el, PageElement::Rect { .. })));
+    assert_eq!(
+        wasm_document_converter::converter::PathCommand::axis_aligned_rect(clip_path),
+        Some((100.0, 100.0, 200.0, 200.0))
+    );
+    // クリップ後の範囲はグループの範囲に収まる
+    assert_eq!(page.content_bounds(), Some((100.0, 100.0, 200.0, 200.0)));
+
+    // 画像出力: グループ内は塗られ、グループ外（子の矩形の内側）は塗られない
+    let config = ImageRenderConfig { dpi: 72.0, ..Default::default() };
+    let png = image_renderer::render_page_to_image(page, &config, &FontManager::new());
+    let (width, _, pixels) = decode_png_pixels(&png);
+    let rgb = |x: u32, y: u32| {
+        let i = ((y * width + x) * 4) as usize;
+        (pixels[i], pixels[i + 1], pixels[i + 2])
+    };
+    assert_eq!(rgb(150, 150), (255, 0, 0));
+    assert_eq!(rgb(60, 60), (255, 255, 255));
+    assert_eq!(rgb(230, 150), (255, 255, 255));
+    assert_eq!(rgb(150, 230), (255, 255, 255));
+
+    // PDF出力: 矩形のクリップ領域はシザー矩形（re W n）として出力される
+    let pdf = pdf_writer::render_to_pdf(&doc);
+    let pdf_text = String::from_utf8_lossy(&pdf);
+    assert!(pdf_text.contains(&format!("100 {} 100 100 re W n", 540.0 - 200.0)), "クリップ領域がありません");
+}
+
+/// 1ピクセル未満の線はDPIやスーパーサンプリングによらず1ピクセル幅のヘアラインとして描画される
+#[test]
+fn test_sub_pixel_stroke_renders_as_hairline() {
+    use wasm_document_converter::converter::{Color, Page, PageElement};
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Line {
+        x1: 50.0,
+        y1: 100.3,
+        x2: 250.0,
+        y2: 100.3,
+        width: 0.1,
+        color: Color::BLACK,
+        stroke_style: Default::default(),
+    });
+    page.elements.push(PageElement::Rect {
+        x: 300.0,
+        y: 300.0,
+        width: 100.0,
+        height: 100.0,
+        fill: None,
+        stroke: Some(Color::BLACK),
+        stroke_width: 0.0,
+        rotation_deg: 0.0,
+        stroke_style: Default::default(),
+    });
+
+    for supersample in [1, 4] {
+        let config = ImageRenderConfig { dpi: 72.0, supersample, ..Default::default() };
+        let png = image_renderer::render_page_to_image(&page, &config, &FontManager::new());
+        let (width, height, pixels) = decode_png_pixels(&png);
+        let dark = |x: u32, y: u32| pixels[((y * width + x) * 4) as usize] < 128;
+
+        // 線: 少なくとも1行は線の全長にわたって濃く塗られる
+        let line_rows = (0..height).filter(|&y| (60..240).all(|x| dark(x, y))).count();
+        assert!(line_rows >= 1, "supersample={}: 0.1pt の線が消えています", supersample);
+
+        // 線幅0の矩形の枠も1ピクセル幅で残る
+        let left_columns = (295..305).filter(|&x| (310..390).all(|y| dark(x, y))).count();
+        assert!(left_columns >= 1, "supersample={}: 線幅0の枠が消えています", supersample);
+    }
+
+    // PDFでは線幅0はそのまま 0 w（出力デバイスの最も細い線）として出力される
+    let mut doc = Document::new();
+    doc.pages.push(page);
+    let pdf = String::from_utf8_lossy(&pdf_writer::render_to_pdf(&doc)).into_owned();
+    assert!(pdf.contains("\n0 w\n300 "), "線幅0の枠がPDFにありません");
+}
+
+#[test]
+fn test_rust_converter_api() {
+    use wasm_document_converter::converter::TextFlowOptions;
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+    use wasm_document_converter::Converter;
+
+    let mut converter = Converter::new();
+    let input = "Hello\nこんにちは".as_bytes();
+
+    let pdf = converter.to_pdf("memo.txt", input).unwrap();
+    assert!(pdf.starts_with(b"%PDF"));
+
+    let zip_data = converter
+        .to_images_zip("memo.txt", input, ImageRenderConfig { dpi: 72.0, ..Default::default() })
+        .unwrap();
+    assert!(zip_data.starts_with(b"PK"));
+
+    converter.set_text_options(TextFlowOptions { default_font_size: 20.0, ..Default::default() });
+    let json: serde_json::Value = serde_json::from_str(&converter.to_json("memo.txt", input).unwrap()).unwrap();
+    let pages = json["document"]["pages"].as_array().unwrap();
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0]["elements"][0]["Text"]["style"]["font_size"], 20.0);
+
+    assert_eq!(converter.extract_text("memo.txt", input).unwrap(), ["Hello\nこんにちは"]);
+
+    // 未対応の拡張子は ConvertError として返る
+    let err = converter.to_pdf("memo.xyz", input).unwrap_err();
+    assert!(err.message.contains("memo.xyz"));
+}
+
+#[test]
+fn test_hinting_option_changes_glyph_rasterization() {
+    use wasm_document_converter::converter::{Page, PageElement, TextAlign};
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+
+    let ttf = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/fonts/NotoSansJP-Regular.ttf")).unwrap();
+    let mut fm = FontManager::new();
+    fm.add_font("Noto Sans JP".to_string(), ttf);
+
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Text {
+        x: 10.3,
+        y: 20.45,
+        width: 500.0,
+        text: "Hamburgefonstiv 0123".to_string(),
+        style: FontStyle { font_name: "Noto Sans JP".to_string(), font_size: 9.7, ..FontStyle::default() },
+        align: TextAlign::Left,
+    });
+
+    // 既定では低DPIでのみ有効
+    assert!(ImageRenderConfig::default().effective_hinting());
+    assert!(!ImageRenderConfig { dpi: 300.0, ..Default::default() }.effective_hinting());
+
+    let render = |hinting| {
+        let config = ImageRenderConfig { dpi: 96.0, hinting: Some(hinting), ..Default::default() };
+        decode_png_pixels(&image_renderer::render_page_to_image(&page, &config, &fm)).2
+    };
+    let hinted = render(true);
+    let unhinted = render(false);
+    assert!(hinted.iter().any(|&v| v < 128), "テキストが描画されていません");
+    assert_ne!(hinted, unhinted, "ヒンティングの有無で描画結果が変わりません");
+}
+
+#[test]
+fn test_pptx_skip_empty_pages() {
+    use wasm_document_converter::converter::TextFlowOptions;
+
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", br#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld><p:spTree><p:sp>
+    <p:spPr><a:xfrm><a:off x="1270000" y="1270000"/><a:ext cx="2540000" cy="1270000"/></a:xfrm></p:spPr>
+    <p:txBody><a:p><a:r><a:t>Title</a:t></a:r></a:p></p:txBody>
+  </p:sp></p:spTree></p:cSld>
+</p:sld>"#),
+        // 背景だけの空のスライド
+        ("ppt/slides/slide2.xml", br#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld>
+    <p:bg><p:bgPr><a:solidFill><a:srgbClr val="EEEEEE"/></a:solidFill></p:bgPr></p:bg>
+    <p:spTree/>
+  </p:cSld>
+</p:sld>"#),
+    ]);
+
+    // 既定では元のスライド数を保つ
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    assert_eq!(doc.pages.len(), 2);
+    assert_eq!(doc.pages[1].elements.len(), 1);
+
+    let options = TextFlowOptions { skip_empty_pages: true, ..Default::default() };
+    let doc = formats::convert_by_extension_with_options("pptx", &pptx, &options).unwrap();
+    assert_eq!(doc.pages.len(), 1);
+    assert_eq!(doc.extract_text(), ["Title"]);
+}
+
+#[test]
+fn test_pptx_slide_number_field() {
+
+    let slide = |field: &str| {
+        format!(
+            r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld><p:spTree><p:sp>
+    <p:spPr><a:xfrm><a:off x="1270000" y="1270000"/><a:ext cx="2540000" cy="1270000"/></a:xfrm></p:spPr>
+    <p:txBody><a:p>{}</a:p></p:txBody>
+  </p:sp></p:spTree></p:cSld>
+</p:sld>"#,
+            field
+        )
+    };
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", r#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#.to_string().as_bytes()),
+        ("ppt/slides/slide1.xml", slide("<a:r><a:t>One</a:t></a:r>").as_bytes()),
+        ("ppt/slides/slide2.xml", slide("<a:r><a:t>Two</a:t></a:r>").as_bytes()),
+        // キャッシュされた表示文字列が古い
+        (
+            "ppt/slides/slide3.xml",
+            slide(r#"<a:fld id="{B6F15528-21DE-4FAA-801E-634DDDAF4B2B}" type="slidenum"><a:rPr lang="en-US"/><a:t>7</a:t></a:fld>"#).as_bytes(),
+        ),
+    ]);
+
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    assert_eq!(doc.pages.len(), 3);
+    assert_eq!(doc.extract_text()[2], "3");
+}
+
+#[test]
+fn test_images_zip_webp_output() {
+    use std::io::Read;
+    use wasm_document_converter::image_renderer::{ImageFormat, ImageRenderConfig};
+
+    let doc = formats::convert_by_extension("txt", b"WebP page").unwrap();
+    let config = ImageRenderConfig {
+        dpi: 72.0,
+        format: ImageFormat::WebP { quality: 80, lossless: false },
+        ..Default::default()
+    };
+    let zip_data = image_renderer::render_to_images_zip_with_config(&doc, &FontManager::new(), &config);
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip_data)).unwrap();
+    let mut entry = archive.by_name("page_0001.webp").unwrap();
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data).unwrap();
+
+    assert_eq!(&data[0..4], b"RIFF");
+    assert_eq!(&data[8..12], b"WEBP");
+    assert_eq!(u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize, data.len() - 8);
+    // 画像を含まないページは丸めずに可逆（VP8L）で符号化され、幅・高さがヘッダーに入る
+    assert_eq!(&data[12..16], b"VP8L");
+    let bits = u32::from_le_bytes([data[21], data[22], data[23], data[24]]);
+    assert_eq!((bits & 0x3FFF) + 1, 595);
+    assert_eq!(((bits >> 14) & 0x3FFF) + 1, 841);
+
+    // 復号したピクセルは同じページのPNG出力と一致する
+    let mut decoder = image_webp::WebPDecoder::new(std::io::Cursor::new(&data)).unwrap();
+    let mut webp_pixels = vec![0; decoder.output_buffer_size().unwrap()];
+    decoder.read_image(&mut webp_pixels).unwrap();
+    let png_config = ImageRenderConfig { dpi: 72.0, ..Default::default() };
+    let zip_data = image_renderer::render_to_images_zip_with_config(&doc, &FontManager::new(), &png_config);
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip_data)).unwrap();
+    let mut png_data = Vec::new();
+    archive.by_name("page_0001.png").unwrap().read_to_end(&mut png_data).unwrap();
+    let mut reader = png::Decoder::new(std::io::Cursor::new(png_data)).read_info().unwrap();
+    let mut png_pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut png_pixels).unwrap();
+    png_pixels.truncate(info.buffer_size());
+    let rgb = |pixels: &[u8], channels: usize| pixels.chunks_exact(channels).map(|p| [p[0], p[1], p[2]]).collect::<Vec<_>>();
+    let webp_channels = if decoder.has_alpha() { 4 } else { 3 };
+    assert!(rgb(&webp_pixels, webp_channels) == rgb(&png_pixels, info.color_type.samples()));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_images_zip_matches_sequential() {
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+
+    let text: String = (1..=150).map(|i| format!("行 {} parallel render\n", i)).collect();
+    let doc = formats::convert_by_extension("txt", text.as_bytes()).unwrap();
+    assert!(doc.pages.len() >= 3);
+
+    let fm = FontManager::new();
+    let config = ImageRenderConfig { dpi: 72.0, ..Default::default() };
+    let parallel = image_renderer::render_to_images_zip_with_config(&doc, &fm, &config);
+    let sequential = image_renderer::render_to_images_zip_sequential(&doc, &fm, &config);
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn test_pptx_dashed_outline() {
+    use wasm_document_converter::converter::{DashPattern, LineCap, LineJoin, PageElement};
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", br#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld><p:spTree><p:sp>
+    <p:spPr>
+      <a:xfrm><a:off x="1270000" y="1270000"/><a:ext cx="2540000" cy="1270000"/></a:xfrm>
+      <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
+      <a:ln w="12700" cap="rnd">
+        <a:solidFill><a:srgbClr val="FF0000"/></a:solidFill>
+        <a:prstDash val="dash"/>
+        <a:round/>
+      </a:ln>
+    </p:spPr>
+  </p:sp></p:spTree></p:cSld>
+</p:sld>"#),
+    ]);
+
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    let style = doc.pages[0]
+        .elements
+        .iter()
+        .find_map(|e| match e {
+            PageElement::Rect { stroke: Some(_), stroke_style, .. } => Some(*stroke_style),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(style.dash, DashPattern::Dash);
+    assert_eq!(style.cap, LineCap::Round);
+    assert_eq!(style.join, LineJoin::Round);
+
+    // PDFでは破線パターン（d）・線端（J）・角（j）を設定する
+    let pdf = pdf_writer::render_to_pdf_with_fonts(&doc, &FontManager::new());
+    let content = String::from_utf8_lossy(&pdf);
+    assert!(content.contains("[4.00 3.00] 0 d\n1 J\n1 j\n"));
+
+    // 画像では上辺に沿って線と隙間が交互に現れる
+    let config = ImageRenderConfig { dpi: 144.0, ..Default::default() };
+    let png = image_renderer::render_page_to_image(&doc.pages[0], &config, &FontManager::new());
+    let (width, _, pixels) = decode_png_pixels(&png);
+    let row = 200; // y = 100pt
+    let is_red = |x: usize| {
+        let i = (row * width as usize + x) * 4;
+        pixels[i] > 200 && pixels[i + 1] < 80 && pixels[i + 2] < 80
+    };
+    let runs: Vec<bool> = (210..590).map(is_red).collect();
+    let transitions = runs.windows(2).filter(|w| w[0] != w[1]).count();
+    assert!(runs.iter().any(|&r| r) && runs.iter().any(|&r| !r));
+    assert!(transitions >= 20, "transitions = {}", transitions);
+}
+
+#[test]
+fn test_pptx_double_compound_outline() {
+    use wasm_document_converter::converter::{BorderSpec, CellBorders, Color, CompoundLine, PageElement};
+
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", br#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld><p:spTree><p:sp>
+    <p:spPr>
+      <a:xfrm><a:off x="1270000" y="1270000"/><a:ext cx="2540000" cy="1270000"/></a:xfrm>
+      <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
+      <a:ln w="38100" cmpd="dbl"><a:solidFill><a:srgbClr val="0000FF"/></a:solidFill></a:ln>
+    </p:spPr>
+  </p:sp></p:spTree></p:cSld>
+</p:sld>"#),
+    ]);
+
+    // 3ptの二重線は、1ptの線が外側と内側に1ptずつずれた2本の矩形ストロークになる
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    let strokes: Vec<(f64, f64, f64, f64, f64)> = doc.pages[0]
+        .elements
+        .iter()
+        .filter_map(|e| match e {
+            PageElement::Rect { x, y, width, height, stroke: Some(_), stroke_width, .. } => {
+                Some((*x, *y, *width, *height, *stroke_width))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(strokes.len(), 2);
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+    let (outer, inner) = (strokes[0], strokes[1]);
+    assert!(close(outer.0, 99.0) && close(outer.1, 99.0) && close(outer.2, 202.0) && close(outer.3, 102.0));
+    assert!(close(inner.0, 101.0) && close(inner.1, 101.0) && close(inner.2, 198.0) && close(inner.3, 98.0));
+    assert!(close(outer.4, 1.0) && close(inner.4, 1.0));
+
+    // 表の枠線も辺ごとに平行な2本の線になる
+    let border = BorderSpec { width: 3.0, color: Color::BLACK, compound: CompoundLine::Double };
+    let borders = CellBorders { top: Some(border), ..Default::default() };
+    let lines: Vec<f64> = borders
+        .edge_lines(0.0, 10.0, 50.0, 20.0)
+        .iter()
+        .filter_map(|e| match e {
+            PageElement::Line { y1, y2, width, .. } if y1 == y2 && close(*width, 1.0) => Some(*y1),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(lines, vec![9.0, 11.0]);
+}
+
+#[test]
+fn test_text_watermark_on_every_page() {
+    use wasm_document_converter::converter::{Page, PageElement, Watermark};
+
+    let mut doc = Document::new();
+    for i in 0..2 {
+        let mut page = Page::a4();
+        page.elements.push(PageElement::Text {
+            x: 72.0,
+            y: 72.0,
+            width: 200.0,
+            text: format!("ページ {}", i + 1),
+            style: FontStyle::default(),
+            align: wasm_document_converter::converter::TextAlign::Left,
+        });
+        doc.pages.push(page);
+    }
+    let watermark = Watermark::text("CONFIDENTIAL");
+
+    // 各ページの末尾に、ページ中央を中心に45度（反時計回り）回転した半透明のテキストが追加される
+    for page in &doc.pages {
+        let watermarked = page.with_watermark(&watermark, None);
+        match watermarked.elements.last() {
+            Some(PageElement::TransformGroup { cx, cy, rotation_deg, opacity, children }) => {
+                assert_eq!((*cx, *cy), (page.width / 2.0, page.height / 2.0));
+                assert_eq!(*rotation_deg, -45.0);
+                assert!((opacity - 0.3).abs() < 1e-9);
+                assert!(matches!(&children[..], [PageElement::Text { text, .. }] if text == "CONFIDENTIAL"));
+            }
+            other => panic!("透かしのグループがありません: {:?}", other),
+        }
+    }
+
+    // PDF: 全ページで不透明度のグラフィックス状態を設定して透かしを描画する
+    let fm = FontManager::new();
+    let options = pdf_writer::PdfOptions { watermark: Some(watermark.clone()), ..Default::default() };
+    let pdf = pdf_writer::render_to_pdf_with_options(&doc, &fm, &options);
+    let pdf_text = String::from_utf8_lossy(&pdf);
+    assert_eq!(pdf_text.matches("/GSa300 gs\n").count(), 2);
+    assert_eq!(pdf_text.matches("/ExtGState << /GSa300 << /ca 0.300 /CA 0.300 >> >>").count(), 2);
+
+    // 画像: 全ページの中央付近に透かしの灰色が描画される
+    let config = image_renderer::ImageRenderConfig {
+        dpi: 72.0,
+        watermark: Some(watermark),
+        ..Default::default()
+    };
+    for page in &doc.pages {
+        let (w, h, pixels) = decode_png_pixels(&image_renderer::render_page_to_image(page, &config, &fm));
+        let tinted = (h / 2 - 60..h / 2 + 60)
+            .flat_map(|y| (w / 2 - 60..w / 2 + 60).map(move |x| ((y * w + x) * 4) as usize))
+            .filter(|&i| pixels[i] < 250 && pixels[i] > 150 && pixels[i] == pixels[i + 2])
+            .count();
+        assert!(tinted > 50, "中央付近に透かしがありません: {}", tinted);
+    }
+}
+
+#[test]
+fn test_page_numbers_on_three_page_txt() {
+    use wasm_document_converter::converter::{PageElement, PageNumberConfig, PageNumberPosition};
+
+    let text: String = (1..=120).map(|i| format!("行 {}\n", i)).collect();
+    let doc = formats::convert_by_extension("txt", text.as_bytes()).unwrap();
+    assert_eq!(doc.pages.len(), 3);
+
+    let config = PageNumberConfig {
+        position: PageNumberPosition::BottomRight,
+        format: "{n}/{total}".to_string(),
+        ..Default::default()
+    };
+    let total = doc.pages.len();
+    for (i, page) in doc.pages.iter().enumerate() {
+        let numbered = page.with_page_number(&config, i + 1, total, None);
+        match numbered.elements.last() {
+            Some(PageElement::Text { x, y, width, text, .. }) => {
+                assert_eq!(text, &format!("{}/3", i + 1));
+                assert!((x + width - (page.width - config.margin)).abs() < 1e-6);
+                assert!(*y > page.height - config.margin - config.font_size - 1e-6);
+            }
+            other => panic!("ページ番号がありません: {:?}", other),
+        }
+    }
+
+    // PDF: 各ページにページ番号のテキストが1つずつ追加される
+    let fm = FontManager::new();
+    let count_tj = |pdf: Vec<u8>| String::from_utf8_lossy(&pdf).matches(" Tj\n").count();
+    let plain = count_tj(pdf_writer::render_to_pdf_with_fonts(&doc, &fm));
+    let options = pdf_writer::PdfOptions { page_numbers: Some(config), ..Default::default() };
+    let numbered = count_tj(pdf_writer::render_to_pdf_with_options(&doc, &fm, &options));
+    assert_eq!(numbered, plain + 3);
+}
+
+#[test]
+fn test_pptx_to_per_slide_pdf_zip() {
+    use std::io::Read;
+
+    let slide = |text: &str| {
+        format!(
+            r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld><p:spTree><p:sp>
+    <p:spPr><a:xfrm><a:off x="914400" y="914400"/><a:ext cx="4572000" cy="914400"/></a:xfrm></p:spPr>
+    <p:txBody><a:bodyPr/><a:p><a:r><a:t>{}</a:t></a:r></a:p></p:txBody>
+  </p:sp></p:spTree></p:cSld>
+</p:sld>"#,
+            text
+        )
+    };
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", r#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#.to_string().as_bytes()),
+        ("ppt/slides/slide1.xml", slide("First").as_bytes()),
+        ("ppt/slides/slide2.xml", slide("Second").as_bytes()),
+    ]);
+
+    let mut converter = wasm_document_converter::Converter::new();
+    let zip_bytes = converter.to_pdf_zip("deck.pptx", &pptx, &pdf_writer::PdfOptions::default()).unwrap();
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip_bytes)).unwrap();
+    assert_eq!(archive.len(), 2);
+    for (i, name) in ["page-001.pdf", "page-002.pdf"].iter().enumerate() {
+        let mut pdf = Vec::new();
+        archive.by_name(name).unwrap().read_to_end(&mut pdf).unwrap();
+        assert!(pdf.starts_with(b"%PDF"), "{} がPDFではありません", name);
+        // 各PDFは1ページだけを持つ
+        assert_eq!(String::from_utf8_lossy(&pdf).matches("/Type /Page ").count(), 1, "{}ページ目", i + 1);
+    }
+}
+
+#[test]
+fn test_default_page_background_applies_only_without_background() {
+    use wasm_document_converter::converter::{Color, PageElement};
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+
+    let pptx = create_sample_pptx(&[("Title", &["Body"])]);
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    let page = &doc.pages[0];
+    assert!(!page.has_background());
+
+    let gray = Color::rgb(230, 230, 230);
+    let config = ImageRenderConfig { dpi: 72.0, default_page_background: Some(gray), ..Default::default() };
+    let font_manager = FontManager::new();
+    let (_, _, pixels) = decode_png_pixels(&image_renderer::render_page_to_image(page, &config, &font_manager));
+    assert_eq!(&pixels[0..4], &[230, 230, 230, 255]);
+
+    // 明示的な背景は上書きしない
+    let mut page = page.clone();
+    page.elements.insert(0, PageElement::Rect {
+        x: 0.0,
+        y: 0.0,
+        width: page.width,
+        height: page.height,
+        fill: Some(Color::rgb(0, 0, 128)),
+        stroke: None,
+        stroke_width: 0.0,
+        stroke_style: Default::default(),
+        rotation_deg: 0.0,
+    });
+    assert!(page.with_default_background(gray).is_none());
+    let (_, _, pixels) = decode_png_pixels(&image_renderer::render_page_to_image(&page, &config, &font_manager));
+    assert_eq!(&pixels[0..4], &[0, 0, 128, 255]);
+}
+
+#[test]
+fn test_table_cell_shrink_to_fit_reduces_font_size() {
+    use wasm_document_converter::converter::{Page, PageElement, Table, TableCell, TABLE_MIN_SHRINK_FONT_SIZE};
+
+    // 80pt幅のセルに18ptでは収まらない文字列を入れ、隣の11ptのセルが行の高さを決める
+    let mut long = TableCell::new("Quarterly revenue total");
+    long.style.font_size = 18.0;
+    long.shrink_to_fit = true;
+    let mut short = TableCell::new("Q1");
+    short.style.font_size = 11.0;
+    let table = Table { rows: vec![vec![long, short]], column_widths: vec![80.0, 80.0] };
+
+    let mut doc = Document::new();
+    doc.pages.push(Page {
+        width: 300.0,
+        height: 200.0,
+        elements: vec![PageElement::TableBlock { x: 10.0, y: 10.0, width: 160.0, table }],
+    });
+    let pdf = pdf_writer::render_to_pdf_with_fonts(&doc, &FontManager::new());
+    let pdf_str = String::from_utf8_lossy(&pdf);
+
+    // セルの順に出力される Tf 命令のフォントサイズ
+    let sizes: Vec<f64> = pdf_str
+        .lines()
+        .filter_map(|line| line.strip_suffix(" Tf"))
+        .filter_map(|line| line.rsplit(' ').next()?.parse().ok())
+        .collect();
+    assert_eq!(sizes.len(), 2, "縮小したセルのテキストは1行に収まるべき: {:?}", sizes);
+    assert_eq!(sizes[1], 11.0);
+    assert!(sizes[0] < 11.0 && sizes[0] > TABLE_MIN_SHRINK_FONT_SIZE, "font size: {}", sizes[0]);
+}
+
+#[test]
+fn test_pptx_hidden_slides_skipped_when_requested() {
+    use wasm_document_converter::converter::TextFlowOptions;
+
+    // スライド2はスライド自身の show="0"、スライド3は presentation.xml の sldId で非表示
+    let slide = |show: &str, text: &str| {
+        format!(
+            r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"{}>
+  <p:cSld><p:spTree><p:sp>
+    <p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="2540000" cy="635000"/></a:xfrm></p:spPr>
+    <p:txBody><a:p><a:r><a:t>{}</a:t></a:r></a:p></p:txBody>
+  </p:sp></p:spTree></p:cSld>
+</p:sld>"#,
+            show, text
+        )
+    };
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", r#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+    xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <p:sldIdLst><p:sldId id="256" r:id="rId2"/><p:sldId id="257" r:id="rId3"/><p:sldId id="258" r:id="rId4" show="0"/></p:sldIdLst>
+</p:presentation>"#.to_string().as_bytes()),
+        ("ppt/_rels/presentation.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide1.xml"/>
+  <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide2.xml"/>
+  <Relationship Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide3.xml"/>
+</Relationships>"#.to_string().as_bytes()),
+        ("ppt/slides/slide1.xml", slide("", "Visible").as_bytes()),
+        ("ppt/slides/slide2.xml", slide(r#" show="0""#, "Hidden by slide").as_bytes()),
+        ("ppt/slides/slide3.xml", slide("", "Hidden by list").as_bytes()),
+    ]);
+
+    let texts = |skip_hidden_slides: bool| {
+        let options = TextFlowOptions { skip_hidden_slides, ..Default::default() };
+        formats::convert_by_extension_with_options("pptx", &pptx, &options).unwrap().extract_text()
+    };
+    assert_eq!(texts(false), vec!["Visible", "Hidden by slide", "Hidden by list"]);
+    assert_eq!(texts(true), vec!["Visible"]);
+}
+
+#[test]
+fn test_json_output_has_schema_version_envelope() {
+    use wasm_document_converter::converter::{DocumentEnvelope, DOCUMENT_SCHEMA_VERSION};
+    use wasm_document_converter::Converter;
+
+    let json = Converter::new().to_json("memo.txt", b"Hello").unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["schemaVersion"], DOCUMENT_SCHEMA_VERSION);
+    assert_eq!(value["document"]["pages"][0]["elements"][0]["Text"]["text"], "Hello");
+
+    // エンベロープはそのまま読み戻せる
+    let envelope: DocumentEnvelope = serde_json::from_str(&json).unwrap();
+    assert_eq!(envelope.schema_version, DOCUMENT_SCHEMA_VERSION);
+    assert_eq!(envelope.document.pages.len(), 1);
+}
+
+#[test]
+fn test_json_round_trip_renders_pdf() {
+    use wasm_document_converter::converter::DOCUMENT_SCHEMA_VERSION;
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+    use wasm_document_converter::pdf_writer::PdfOptions;
+    use wasm_document_converter::Converter;
+
+    let mut converter = Converter::new();
+    let pptx = create_sample_pptx(&[("Title", &["Body"])]);
+    let json = converter.to_json("slides.pptx", &pptx).unwrap();
+
+    let pdf = converter.render_json_to_pdf(&json, &PdfOptions::default()).unwrap();
+    assert!(pdf.starts_with(b"%PDF"));
+    assert!(String::from_utf8_lossy(&pdf).contains("%%EOF"));
+
+    let zip = converter.render_json_to_images_zip(&json, ImageRenderConfig { dpi: 72.0, ..Default::default() }).unwrap();
+    assert!(zip.starts_with(b"PK"));
+
+    // バージョンが異なるJSONは明確なエラーにする
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["schemaVersion"] = (DOCUMENT_SCHEMA_VERSION + 1).into();
+    let err = converter.render_json_to_pdf(&value.to_string(), &PdfOptions::default()).unwrap_err();
+    assert!(err.message.contains("バージョン"), "{}", err.message);
+}
+
+#[test]
+fn test_json_render_enforces_limits() {
+    use wasm_document_converter::converter::ConversionLimits;
+    use wasm_document_converter::image_renderer::ImageRenderConfig;
+    use wasm_document_converter::pdf_writer::PdfOptions;
+    use wasm_document_converter::Converter;
+
+    let mut converter = Converter::new();
+    let json = converter.to_json("memo.txt", b"Hello").unwrap();
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    // ページ数の上限
+    let page = value["document"]["pages"][0].clone();
+    value["document"]["pages"] = serde_json::Value::Array(vec![page.clone(); 3]);
+    converter.set_limits(ConversionLimits { max_pages: 2, ..Default::default() });
+    let err = converter.render_json_to_pdf(&value.to_string(), &PdfOptions::default()).unwrap_err();
+    assert!(err.message.contains("ページ数"), "{}", err.message);
+    let config = ImageRenderConfig { dpi: 72.0, ..Default::default() };
+    assert!(converter.render_json_to_images_zip(&value.to_string(), config.clone()).is_err());
+    converter.set_limits(ConversionLimits::default());
+
+    // 大きすぎる・0以下のページ
+    for (key, size) in [("width", 1.0e9), ("height", 0.0), ("width", -10.0)] {
+        let mut page = page.clone();
+        page[key] = size.into();
+        value["document"]["pages"] = serde_json::Value::Array(vec![page]);
+        let err = converter.render_json_to_images_zip(&value.to_string(), config.clone()).unwrap_err();
+        assert!(err.message.contains("ページ 1 の大きさ"), "{}", err.message);
+        assert!(converter.render_json_to_pdf(&value.to_string(), &PdfOptions::default()).is_err());
+    }
+}
+
+#[test]
+fn test_pptx_same_styled_runs_merge_into_one_text() {
+    use wasm_document_converter::converter::{PageElement, TextFlowOptions};
+
+    // 同じ書式の3つのランと、太字のラン
+    let slide_xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld><p:spTree><p:sp>
+    <p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="6350000" cy="635000"/></a:xfrm></p:spPr>
+    <p:txBody><a:p>
+      <a:r><a:rPr sz="1800"/><a:t>Hel</a:t></a:r>
+      <a:r><a:rPr sz="1800"/><a:t>lo </a:t></a:r>
+      <a:r><a:rPr sz="1800"/><a:t>World</a:t></a:r>
+      <a:r><a:rPr sz="1800" b="1"/><a:t>!</a:t></a:r>
+    </a:p></p:txBody>
+  </p:sp></p:spTree></p:cSld>
+</p:sld>"#;
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", slide_xml.as_bytes()),
+    ]);
+
+    let texts = |merge_text_runs: bool| -> Vec<String> {
+        let options = TextFlowOptions { merge_text_runs, ..Default::default() };
+        let doc = formats::convert_by_extension_with_options("pptx", &pptx, &options).unwrap();
+        doc.pages[0]
+            .elements
+            .iter()
+            .filter_map(|el| match el {
+                PageElement::Text { text, .. } if !text.is_empty() => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(texts(true), vec!["Hello World", "!"]);
+    assert_eq!(texts(false).len(), 4);
+}
+
+#[test]
+fn test_pptx_grayscale_blip_desaturates_image() {
+    use wasm_document_converter::converter::PageElement;
+
+    // 4×4 の純赤のPNG
+    let mut red_png = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut red_png, 4, 4);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[255, 0, 0].repeat(16)).unwrap();
+    }
+    let slide_xml = |effect: &str| {
+        format!(
+            r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+       xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <p:cSld><p:spTree><p:pic>
+    <p:nvPicPr><p:cNvPr id="2" name="Picture 1"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr>
+    <p:blipFill><a:blip r:embed="rId2">{}</a:blip><a:stretch><a:fillRect/></a:stretch></p:blipFill>
+    <p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="1270000" cy="1270000"/></a:xfrm></p:spPr>
+  </p:pic></p:spTree></p:cSld>
+</p:sld>"#,
+            effect
+        )
+    };
+    let image_data = |effect: &str| {
+        let slide = slide_xml(effect);
+        let pptx = zip_package(&[
+            ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+            ("ppt/slides/slide1.xml", slide.as_bytes()),
+            ("ppt/slides/_rels/slide1.xml.rels", br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.png"/>
+</Relationships>"#),
+            ("ppt/media/image1.png", &red_png),
+        ]);
+        let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+        doc.pages[0]
+            .elements
+            .iter()
+            .find_map(|el| match el {
+                PageElement::Image { data, .. } => Some(data.clone()),
+                _ => None,
+            })
+            .expect("image element")
+    };
+    let image_pixel = |effect: &str| decode_png_pixels(&image_data(effect)).2[..4].to_vec();
+
+    // 効果が無ければ元の画像のまま
+    assert_eq!(image_data(""), red_png);
+    // 輝度（0.299 × 255 ≈ 76）のグレー
+    assert_eq!(image_pixel("<a:grayscl/>"), [76, 76, 76, 255]);
+    assert_eq!(image_pixel(r#"<a:biLevel thresh="25000"/>"#), [255, 255, 255, 255]);
+    // 黒〜白の2色の間を輝度で補間すると、グレースケールと同じになる
+    assert_eq!(
+        image_pixel(r#"<a:duotone><a:prstClr val="black"/><a:srgbClr val="FFFFFF"/></a:duotone>"#),
+        [76, 76, 76, 255]
+    );
+}
+
+#[test]
+fn test_pdf_bleed_and_crop_marks_boxes() {
+    use wasm_document_converter::pdf_writer::PdfOptions;
+
+    let doc = formats::convert_by_extension("txt", b"Print").unwrap();
+    let (width, height) = (doc.pages[0].width, doc.pages[0].height);
+    let fm = FontManager::new();
+    let pdf_box = |pdf: &str, name: &str| -> [f64; 4] {
+        let start = pdf.find(&format!("/{} [", name)).unwrap_or_else(|| panic!("{} が無い", name)) + name.len() + 3;
+        let end = start + pdf[start..].find(']').unwrap();
+        let values: Vec<f64> = pdf[start..end].split_whitespace().map(|v| v.parse().unwrap()).collect();
+        values.try_into().unwrap()
+    };
+
+    // 既定では仕上がり・裁ち落としの指定を出力しない
+    let plain = String::from_utf8_lossy(&pdf_writer::render_to_pdf_with_options(&doc, &fm, &PdfOptions::default())).to_string();
+    assert!(!plain.contains("/TrimBox") && !plain.contains("/BleedBox"));
+
+    let options = PdfOptions { bleed: 9.0, crop_marks: true, ..Default::default() };
+    let pdf = String::from_utf8_lossy(&pdf_writer::render_to_pdf_with_options(&doc, &fm, &options)).to_string();
+    let media = pdf_box(&pdf, "MediaBox");
+    let trim = pdf_box(&pdf, "TrimBox");
+    let bleed = pdf_box(&pdf, "BleedBox");
+
+    // TrimBox はページの大きさ、BleedBox はその外側に裁ち落とし幅ずつ広げた範囲
+    assert!((trim[2] - trim[0] - width).abs() < 1e-6 && (trim[3] - trim[1] - height).abs() < 1e-6);
+    for i in 0..2 {
+        assert!((trim[i] - bleed[i] - 9.0).abs() < 1e-6);
+        assert!((bleed[i + 2] - trim[i + 2] - 9.0).abs() < 1e-6);
+    }
+    // MediaBox はトンボの分だけ BleedBox よりさらに広い
+    assert!(media[0] < bleed[0] && media[1] < bleed[1] && media[2] > bleed[2] && media[3] > bleed[3]);
+    assert!(pdf.contains(" l S\n"), "トンボの線が描画されていません");
+}
+
+#[test]
+fn test_mixed_page_sizes_render_per_page() {
+    use std::io::Read;
+    use wasm_document_converter::converter::{Page, PageElement, TextAlign};
+
+    // A4縦と16:9のスライド（960×540pt）が混在するドキュメント
+    let text_page = |width: f64, height: f64| Page {
+        width,
+        height,
+        elements: vec![PageElement::Text {
+            x: 20.0,
+            y: 20.0,
+            width: 200.0,
+            text: "page".to_string(),
+            style: FontStyle::default(),
+            align: TextAlign::Left,
+        }],
+    };
+    let mut doc = Document::new();
+    doc.pages.push(text_page(595.28, 841.89));
+    doc.pages.push(text_page(960.0, 540.0));
+
+    let fm = FontManager::new();
+    let pdf = String::from_utf8_lossy(&pdf_writer::render_to_pdf_with_fonts(&doc, &fm)).to_string();
+    let media_boxes: Vec<&str> = pdf.match_indices("/MediaBox [").map(|(i, _)| &pdf[i..i + pdf[i..].find(']').unwrap() + 1]).collect();
+    assert_eq!(media_boxes, ["/MediaBox [0 0 595.28 841.89]", "/MediaBox [0 0 960 540]"]);
+
+    let config = image_renderer::ImageRenderConfig { dpi: 72.0, ..Default::default() };
+    let zip_data = image_renderer::render_to_images_zip_with_config(&doc, &fm, &config);
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip_data)).unwrap();
+    let sizes: Vec<(u32, u32)> = ["page_0001.png", "page_0002.png"]
+        .iter()
+        .map(|name| {
+            let mut png = Vec::new();
+            archive.by_name(name).unwrap().read_to_end(&mut png).unwrap();
+            let (width, height, _) = decode_png_pixels(&png);
+            (width, height)
+        })
+        .collect();
+    assert_eq!(sizes, [(595, 841), (960, 540)]);
+}
+
+#[test]
+fn test_search_highlight_marks_matching_csv_cell() {
+    use wasm_document_converter::converter::{PageElement, SearchHighlight};
+
+    let csv = "Name,Age\nAlice,30\nBob,25";
+    let highlight = SearchHighlight::new("alice", false);
+
+    // ハイライトの矩形は「Alice」のセル（2行目・1列目）の内側に、テーブルの下に敷かれる
+    let doc = formats::convert_by_extension("csv", csv.as_bytes()).unwrap();
+    let page = doc.pages[0].with_search_highlights(&highlight, &highlight.pattern().unwrap(), None).unwrap();
+    let PageElement::TransformGroup { opacity, children, .. } = &page.elements[0] else {
+        panic!("ハイライトのグループがテーブルの前にありません: {:?}", page.elements[0]);
+    };
+    assert_eq!(*opacity, 0.4);
+    assert_eq!(children.len(), 1);
+    let PageElement::TableBlock { x: tx, y: ty, width, table } = &page.elements[1] else { panic!() };
+    let (col_w, heights) = (table.resolved_column_widths(*width)[0], table.row_heights(*width, None));
+    let PageElement::Rect { x, y, width: w, height: h, fill, .. } = &children[0] else { panic!() };
+    assert_eq!(*fill, Some(highlight.color));
+    assert!(*x >= *tx && x + w <= tx + col_w, "x={} w={}", x, w);
+    assert!(*y >= ty + heights[0] && y + h <= ty + heights[0] + heights[1], "y={} h={}", y, h);
+    assert!(*w > 0.0 && *h > 0.0);
+
+    // PDFには不透明度0.4のグラフィックス状態で黄色の矩形が1つ描画される
+    let options = pdf_writer::PdfOptions { search_highlight: Some(highlight.clone()), ..Default::default() };
+    let mut converter = wasm_document_converter::Converter::new();
+    let pdf = converter.to_pdf_with_options("people.csv", csv.as_bytes(), &options).unwrap();
+    let pdf = String::from_utf8_lossy(&pdf);
+    assert!(pdf.contains("/ca 0.400"));
+    assert_eq!(pdf.matches("1 0.9019607843137255 0 rg").count(), 1);
+
+    // 正規表現の指定と不正なパターン
+    let regex_options = pdf_writer::PdfOptions {
+        search_highlight: Some(SearchHighlight::new("^(a", true)),
+        ..Default::default()
+    };
+    assert!(converter.to_pdf_with_options("people.csv", csv.as_bytes(), &regex_options).is_err());
+    let page = doc.pages[0]
+        .with_search_highlights(&highlight, &SearchHighlight::new("^[ab]\\w+$", true).pattern().unwrap(), None)
+        .unwrap();
+    let PageElement::TransformGroup { children, .. } = &page.elements[0] else { panic!() };
+    assert_eq!(children.len(), 3, "Age・Alice・Bob");
+
+    // 同じ行の2つのランにまたがる一致は連続した1つの矩形になる
+    let run = |x: f64, text: &str| PageElement::Text {
+        x,
+        y: 100.0,
+        width: 50.0,
+        text: text.to_string(),
+        style: FontStyle::default(),
+        align: wasm_document_converter::converter::TextAlign::Left,
+    };
+    let mut page = wasm_document_converter::converter::Page::a4();
+    page.elements = vec![run(50.0, "Hi Ali"), run(80.0, "ce!")];
+    let page = page.with_search_highlights(&highlight, &highlight.pattern().unwrap(), None).unwrap();
+    let PageElement::TransformGroup { children, .. } = &page.elements[0] else { panic!() };
+    assert_eq!(children.len(), 1);
+    let PageElement::Rect { x, width, .. } = &children[0] else { panic!() };
+    assert!(*x > 50.0 && *x < 80.0 && x + width > 80.0, "x={} width={}", x, width);
+}
+
+/// DOCXのコメントの抽出と右余白への描画のテスト
+#[test]
+fn test_docx_comment_extraction_and_margin_note() {
+    use wasm_document_converter::converter::{PageElement, TextFlowOptions};
+
+    let docx_data = zip_package(&[
+        ("word/document.xml", br#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:body>
+    <w:p>
+      <w:r><w:t xml:space="preserve">The </w:t></w:r>
+      <w:commentRangeStart w:id="0"/>
+      <w:r><w:t>quarterly figures</w:t></w:r>
+      <w:commentRangeEnd w:id="0"/>
+      <w:r><w:commentReference w:id="0"/></w:r>
+      <w:r><w:t xml:space="preserve"> are final.</w:t></w:r>
+    </w:p>
+  </w:body>
+</w:document>"#),
+        ("word/comments.xml", br#"<?xml version="1.0" encoding="UTF-8"?>
+<w:comments xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:comment w:id="0" w:author="Hanako Sato" w:date="2024-05-01T09:30:00Z" w:initials="HS">
+    <w:p><w:r><w:annotationRef/></w:r><w:r><w:t>Please double-check</w:t></w:r></w:p>
+  </w:comment>
+</w:comments>"#),
+    ]);
+
+    let mut converter = wasm_document_converter::Converter::new();
+    let comments = converter.extract_comments("review.docx", &docx_data).unwrap();
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].author, "Hanako Sato");
+    assert_eq!(comments[0].date.as_deref(), Some("2024-05-01T09:30:00Z"));
+    assert_eq!(comments[0].text, "Please double-check");
+    assert_eq!(comments[0].anchored_text, "quarterly figures");
+    let json: serde_json::Value = serde_json::to_value(&comments).unwrap();
+    assert_eq!(json[0]["anchoredText"], "quarterly figures");
+    assert!(converter.extract_comments("data.csv", b"a,b").is_err());
+
+    // 既定ではコメントを描画せず、指定時は右余白に作成者と本文を描画して参照位置と線で結ぶ
+    let texts = |doc: &wasm_document_converter::converter::Document| -> Vec<(String, f64)> {
+        doc.pages[0]
+            .elements
+            .iter()
+            .filter_map(|el| match el {
+                PageElement::Text { x, text, .. } => Some((text.clone(), *x)),
+                _ => None,
+            })
+            .collect()
+    };
+    let plain = converter.to_document("review.docx", &docx_data).unwrap();
+    assert!(!texts(&plain).iter().any(|(t, _)| t == "Hanako Sato"));
+
+    converter.set_text_options(TextFlowOptions { render_comments: true, ..Default::default() });
+    let doc = converter.to_document("review.docx", &docx_data).unwrap();
+    let page = &doc.pages[0];
+    let texts = texts(&doc);
+    let margin_left = page.width - 72.0;
+    let (_, author_x) = texts.iter().find(|(t, _)| t == "Hanako Sato").unwrap();
+    assert!(*author_x > margin_left);
+    assert!(texts.iter().any(|(t, x)| t.contains("Please") && *x > margin_left));
+    assert!(page.elements.iter().any(|el| matches!(
+        el,
+        PageElement::Line { x1, x2, .. } if *x1 < margin_left && *x2 > margin_left
+    )));
+}
+
+/// DOCXの変更履歴の承諾・却下・表示のテスト
+#[test]
+fn test_docx_tracked_changes_modes() {
+    use wasm_document_converter::converter::{PageElement, TextFlowOptions, TrackedChanges};
+
+    let docx_data = create_docx_with_body(r#"
+    <w:p>
+      <w:r><w:t xml:space="preserve">Ship the </w:t></w:r>
+      <w:ins w:id="1" w:author="Reviewer" w:date="2024-05-01T00:00:00Z"><w:r><w:t>revised</w:t></w:r></w:ins>
+      <w:del w:id="2" w:author="Reviewer" w:date="2024-05-01T00:00:00Z"><w:r><w:delText>draft</w:delText></w:r></w:del>
+      <w:r><w:t xml:space="preserve"> report</w:t></w:r>
+    </w:p>"#);
+    let convert = |mode: TrackedChanges| {
+        let options = TextFlowOptions { tracked_changes: mode, ..Default::default() };
+        let doc = formats::convert_by_extension_with_options("docx", &docx_data, &options).unwrap();
+        doc.pages[0]
+            .elements
+            .iter()
+            .filter_map(|el| match el {
+                PageElement::Text { text, style, .. } if !text.is_empty() => Some((text.clone(), style.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let words = |texts: &[(String, FontStyle)]| texts.iter().map(|(t, _)| t.trim().to_string()).collect::<Vec<_>>();
+
+    assert_eq!(words(&convert(TrackedChanges::Accept)), ["Ship the", "revised", "report"]);
+    assert_eq!(words(&convert(TrackedChanges::Reject)), ["Ship the", "draft", "report"]);
+
+    let markup = convert(TrackedChanges::Markup);
+    assert_eq!(words(&markup), ["Ship the", "revised", "draft", "report"]);
+    let (_, inserted) = &markup[1];
+    assert!(inserted.underline.is_some() && !inserted.strikethrough);
+    let (_, deleted) = &markup[2];
+    assert!(deleted.strikethrough && deleted.underline.is_none());
+    assert_ne!(inserted.color, deleted.color);
+    assert!(markup[0].1.underline.is_none() && !markup[0].1.strikethrough);
+}
+
+#[test]
+fn test_protected_pdf_encrypts_and_watermarks() {
+    use wasm_document_converter::converter::{PageElement, Watermark, WatermarkContent};
+    use wasm_document_converter::pdf_encryption::{PdfEncryption, PdfPermissions};
+
+    let csv = "Name,Age\nAlice,30\nBob,25";
+
+    // 未指定の項目は閲覧専用の暗号化と「CONFIDENTIAL」の透かしで補われる
+    let options = pdf_writer::PdfOptions::default().protected();
+    assert_eq!(options.encryption, Some(PdfEncryption::view_only()));
+    let watermark = options.watermark.clone().expect("既定の透かしがありません");
+    assert!(matches!(&watermark.content, WatermarkContent::Text { text, .. } if text == "CONFIDENTIAL"));
+    let doc = formats::convert_by_extension("csv", csv.as_bytes()).unwrap();
+    let page = doc.pages[0].with_watermark(&watermark, None);
+    assert!(matches!(page.elements.last(), Some(PageElement::TransformGroup { .. })));
+
+    // 暗号化辞書は印刷・コピー・変更・注釈をすべて禁止（/P のビット3〜6・9〜12が0）
+    let mut converter = wasm_document_converter::Converter::new();
+    let pdf = converter.to_pdf_with_options("people.csv", csv.as_bytes(), &options).unwrap();
+    let pdf_text = String::from_utf8_lossy(&pdf);
+    assert!(pdf_text.starts_with("%PDF-1.6\n"));
+    assert!(pdf_text.contains("/Filter /Standard /V 4 /R 4"));
+    assert!(pdf_text.contains("/CFM /AESV2"));
+    assert!(pdf_text.contains("/P -3904 "));
+    assert!(pdf_text.contains(" /Encrypt ") && pdf_text.contains(" /ID [<"));
+    // 透かしのグラフィックス状態は参照されるが、コンテンツストリームは暗号化されて平文で現れない
+    assert!(pdf_text.contains("/GSa300"));
+    assert!(!pdf_text.contains("/GSa300 gs"));
+
+    // 指定した暗号化・透かしはそのまま使われる
+    let custom = pdf_writer::PdfOptions {
+        encryption: Some(PdfEncryption {
+            user_password: "secret".to_string(),
+            permissions: PdfPermissions { print: true, ..PdfPermissions::view_only() },
+            ..Default::default()
+        }),
+        watermark: Some(Watermark::text("DRAFT")),
+        ..Default::default()
+    }
+    .protected();
+    assert!(matches!(&custom.watermark.as_ref().unwrap().content, WatermarkContent::Text { text, .. } if text == "DRAFT"));
+    let pdf = converter.to_pdf_with_options("people.csv", csv.as_bytes(), &custom).unwrap();
+    assert!(String::from_utf8_lossy(&pdf).contains("/P -1852 "));
+
+    // 暗号化しない場合は従来どおりPDF 1.4で出力
+    let plain = converter.to_pdf_with_options("people.csv", csv.as_bytes(), &Default::default()).unwrap();
+    assert!(plain.starts_with(b"%PDF-1.4\n") && !String::from_utf8_lossy(&plain).contains("/Encrypt"));
+}
+
+#[test]
+fn test_encrypted_pdf_decrypts_with_user_password() {
+    use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+    use md5::{Digest, Md5};
+    use wasm_document_converter::pdf_encryption::PdfEncryption;
+
+    let text = "Name,Age\nAlice,30\n";
+    let options = pdf_writer::PdfOptions {
+        encryption: Some(PdfEncryption { user_password: "secret".to_string(), ..Default::default() }),
+        attach_source: true,
+        ..Default::default()
+    };
+    let mut converter = wasm_document_converter::Converter::new();
+    let pdf = converter.to_pdf_with_options("people.csv", text.as_bytes(), &options).unwrap();
+
+    let find = |needle: &[u8], from: usize| pdf[from..].windows(needle.len()).position(|w| w == needle).map(|p| from + p);
+    // 暗号化したストリームのバイト列と取り違えないよう、暗号化辞書と末尾のトレーラーから探す
+    let encrypt_dict = find(b"/Filter /Standard", 0).unwrap();
+    let trailer = pdf.windows(7).rposition(|w| w == b"trailer").unwrap();
+    // `from` 以降で `prefix` の直後にある16進文字列
+    let hex_after = |prefix: &str, from: usize| {
+        let start = find(prefix.as_bytes(), from).unwrap() + prefix.len();
+        let end = find(b">", start).unwrap();
+        let hex = std::str::from_utf8(&pdf[start..end]).unwrap();
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect::<Vec<u8>>()
+    };
+    // `offset` を含むオブジェクトの番号
+    let object_id = |offset: usize| {
+        let obj = pdf[..offset].windows(6).rposition(|w| w == b" 0 obj").unwrap();
+        let line = pdf[..obj].iter().rposition(|&b| b == b'\n').unwrap() + 1;
+        std::str::from_utf8(&pdf[line..obj]).unwrap().parse::<u32>().unwrap()
+    };
+
+    // アルゴリズム2: ユーザーパスワードからファイル暗号化キーを導出
+    let encrypt_text = String::from_utf8_lossy(&pdf[encrypt_dict..]);
+    let p: i32 = encrypt_text.split("/P ").nth(1).unwrap().split(' ').next().unwrap().parse().unwrap();
+    // パスワードを32バイトに揃えるパディング（PDF 32000-1 7.6.3.3）の先頭26バイト
+    let mut password = b"secret".to_vec();
+    password.extend_from_slice(&[
+        0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08, 0x2E, 0x2E, 0x00,
+        0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C,
+    ]);
+    let mut key = Md5::new()
+        .chain_update(&password)
+        .chain_update(hex_after("/O <", encrypt_dict))
+        .chain_update(p.to_le_bytes())
+        .chain_update(hex_after("/ID [<", trailer))
+        .finalize();
+    for _ in 0..50 {
+        key = Md5::digest(key);
+    }
+    let decrypt = |id: u32, data: &[u8]| {
+        let object_key = Md5::new()
+            .chain_update(key)
+            .chain_update(&id.to_le_bytes()[..3])
+            .chain_update([0, 0])
+            .chain_update(b"sAlT")
+            .finalize();
+        cbc::Decryptor::<aes::Aes128>::new(&object_key, data[..16].into())
+            .decrypt_padded_vec_mut::<Pkcs7>(&data[16..])
+            .unwrap()
+    };
+
+    // 文字列: 文書情報の /Producer
+    let producer = find(b"/Producer <", 0).unwrap();
+    let decrypted = decrypt(object_id(producer), &hex_after("/Producer <", producer));
+    assert!(String::from_utf8(decrypted).unwrap().starts_with("WASM Document Converter v"));
+
+    // ストリーム: 添付した元のファイル
+    let embedded = find(b"/Type /EmbeddedFile ", 0).unwrap();
+    let length_start = find(b"/Length ", embedded).unwrap() + 8;
+    let length_end = find(b" ", length_start).unwrap();
+    let length: usize = std::str::from_utf8(&pdf[length_start..length_end]).unwrap().parse().unwrap();
+    let stream = find(b"stream\n", embedded).unwrap() + 7;
+    assert_eq!(decrypt(object_id(embedded), &pdf[stream..stream + length]), text.as_bytes());
+
+    // IVは毎回ランダムなため、同じ入力でも暗号文が異なる
+    let again = converter.to_pdf_with_options("people.csv", text.as_bytes(), &options).unwrap();
+    assert_ne!(pdf, again);
+}
+
+#[test]
+fn test_attach_source_embeds_original_file() {
+    let text = "出典の確認用\nsecond line\n";
+    let mut converter = wasm_document_converter::Converter::new();
+    let options = pdf_writer::PdfOptions { attach_source: true, ..Default::default() };
+    let pdf = converter.to_pdf_with_options("docs/notes.txt", text.as_bytes(), &options).unwrap();
+    let pdf_text = String::from_utf8_lossy(&pdf);
+
+    // カタログの /Names /EmbeddedFiles からファイル指定辞書を参照し、元のファイル名を保持する
+    assert!(pdf_text.contains("/Names << /EmbeddedFiles << /Names [(notes.txt) "));
+    assert!(pdf_text.contains("/Type /Filespec /F (notes.txt) /UF (notes.txt)"));
+
+    // 埋め込みファイルストリームは元のバイト列そのまま（MIMEタイプ付き）
+    let header = format!(
+        "<< /Type /EmbeddedFile /Subtype /text#2Fplain /Params << /Size {} >> /Length {} >>\nstream\n",
+        text.len(),
+        text.len()
+    );
+    let start = pdf.windows(header.len()).position(|w| w == header.as_bytes()).expect("埋め込みファイルがありません")
+        + header.len();
+    assert_eq!(&pdf[start..start + text.len()], text.as_bytes());
+    assert_eq!(&pdf[start + text.len()..start + text.len() + 10], b"\nendstream");
+
+    // 既定では添付しない
+    let plain = converter.to_pdf_with_options("notes.txt", text.as_bytes(), &Default::default()).unwrap();
+    assert!(!String::from_utf8_lossy(&plain).contains("/EmbeddedFile"));
+}
+
+#[test]
+fn test_text_only_mode_drops_images_and_keeps_text() {
+    use wasm_document_converter::converter::{PageElement, TextFlowOptions};
+
+    // 画像の背景・画像・塗りつぶし図形（テキスト付き）のスライド
+    let slide_xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+       xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <p:cSld>
+    <p:bg><p:bgPr><a:blipFill><a:blip r:embed="rId2"/><a:stretch><a:fillRect/></a:stretch></a:blipFill></p:bgPr></p:bg>
+    <p:spTree>
+      <p:pic>
+        <p:nvPicPr><p:cNvPr id="2" name="Picture"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr>
+        <p:blipFill><a:blip r:embed="rId2"/><a:stretch><a:fillRect/></a:stretch></p:blipFill>
+        <p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="2540000" cy="1270000"/></a:xfrm></p:spPr>
+      </p:pic>
+      <p:sp>
+        <p:nvSpPr><p:cNvPr id="3" name="Title"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr>
+        <p:spPr><a:xfrm><a:off x="1270000" y="2540000"/><a:ext cx="5080000" cy="1270000"/></a:xfrm>
+          <a:prstGeom prst="roundRect"/><a:solidFill><a:srgbClr val="4472C4"/></a:solidFill></p:spPr>
+        <p:txBody><a:bodyPr/><a:p><a:r><a:rPr lang="ja-JP" sz="2400"/><a:t>四半期の売上報告</a:t></a:r></a:p></p:txBody>
+      </p:sp>
+    </p:spTree>
+  </p:cSld>
+</p:sld>"#;
+    let png = create_test_png(4, 2);
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", slide_xml.as_bytes()),
+        ("ppt/slides/_rels/slide1.xml.rels", br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.png"/>
+</Relationships>"#),
+        ("ppt/media/image1.png", &png),
+    ]);
+
+    // 通常の変換では画像と図形が描画される
+    let full = formats::convert_by_extension("pptx", &pptx).unwrap();
+    assert!(full.pages[0].leaf_elements().iter().any(|el| matches!(el, PageElement::Image { .. })));
+
+    // テキストのみのモードではテキストと表以外の要素が無く、テキストは同じ
+    let text_only = TextFlowOptions { text_only: true, ..Default::default() };
+    let doc = formats::convert_by_extension_with_options("pptx", &pptx, &text_only).unwrap();
+    assert_eq!(doc.pages.len(), 1);
+    assert!(!doc.pages[0].elements.is_empty());
+    assert!(doc.pages[0]
+        .elements
+        .iter()
+        .all(|el| matches!(el, PageElement::Text { .. } | PageElement::TableBlock { .. })));
+    assert_eq!(doc.extract_text(), full.extract_text());
+    assert!(doc.extract_text()[0].contains("四半期の売上報告"));
+
+    // extractText もテキストのみのモードで同じテキストを返す
+    let converter = wasm_document_converter::Converter::new();
+    assert_eq!(converter.extract_text("slides.pptx", &pptx).unwrap(), full.extract_text());
+}
+
+#[test]
+fn test_docx_floating_text_boxes_render_at_anchor() {
+    use wasm_document_converter::converter::{Color, PageElement};
+
+    // DrawingML のテキストボックス（VMLの代替付き）と、VMLだけのテキストボックス
+    let docx_data = create_docx_with_body(r##"
+    <w:p>
+      <w:r><w:t>Body text</w:t></w:r>
+      <w:r>
+        <mc:AlternateContent>
+          <mc:Choice Requires="wps">
+            <w:drawing>
+              <wp:anchor>
+                <wp:positionH relativeFrom="page"><wp:posOffset>1828800</wp:posOffset></wp:positionH>
+                <wp:positionV relativeFrom="page"><wp:posOffset>2743200</wp:posOffset></wp:positionV>
+                <wp:extent cx="2286000" cy="914400"/>
+                <a:graphic><a:graphicData uri="http://schemas.microsoft.com/office/word/2010/wordprocessingShape">
+                  <wps:wsp>
+                    <wps:cNvSpPr txBox="1"/>
+                    <wps:spPr>
+                      <a:xfrm><a:off x="0" y="0"/><a:ext cx="2286000" cy="914400"/></a:xfrm>
+                      <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
+                      <a:solidFill><a:srgbClr val="FFF2CC"/></a:solidFill>
+                      <a:ln w="12700"><a:solidFill><a:srgbClr val="BF9000"/></a:solidFill></a:ln>
+                    </wps:spPr>
+                    <wps:txbx><w:txbxContent>
+                      <w:p><w:pPr><w:spacing w:after="0"/></w:pPr><w:r><w:rPr><w:b/></w:rPr><w:t>Sidebar note</w:t></w:r></w:p>
+                    </w:txbxContent></wps:txbx>
+                    <wps:bodyPr/>
+                  </wps:wsp>
+                </a:graphicData></a:graphic>
+              </wp:anchor>
+            </w:drawing>
+          </mc:Choice>
+          <mc:Fallback>
+            <w:pict><v:shape style="position:absolute;width:180pt;height:72pt"><v:textbox><w:txbxContent>
+              <w:p><w:r><w:t>Sidebar note</w:t></w:r></w:p>
+            </w:txbxContent></v:textbox></v:shape></w:pict>
+          </mc:Fallback>
+        </mc:AlternateContent>
+      </w:r>
+    </w:p>
+    <w:p>
+      <w:r>
+        <w:pict>
+          <v:shape style="position:absolute;margin-left:36pt;margin-top:400pt;width:200pt;height:50pt;mso-position-horizontal-relative:page;mso-position-vertical-relative:page" fillcolor="#DDEBF7" stroked="f">
+            <v:textbox><w:txbxContent><w:p><w:r><w:t>Legacy callout</w:t></w:r></w:p></w:txbxContent></v:textbox>
+          </v:shape>
+        </w:pict>
+      </w:r>
+    </w:p>"##);
+    let doc = formats::convert_by_extension("docx", &docx_data).unwrap();
+    let elements = &doc.pages[0].elements;
+    let text_at = |needle: &str| {
+        elements
+            .iter()
+            .filter_map(|el| match el {
+                PageElement::Text { x, y, text, style, .. } if text == needle => Some((*x, *y, style.bold)),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // 枠はページ左上から (144pt, 216pt) に 180×72pt で、塗りと線付き
+    let frame = elements.iter().find_map(|el| match el {
+        PageElement::Rect { x, y, width, height, fill, stroke, stroke_width, .. } if fill.is_some() && *x == 144.0 => {
+            Some((*y, *width, *height, *fill, *stroke, *stroke_width))
+        }
+        _ => None,
+    });
+    let (y, width, height, fill, stroke, stroke_width) = frame.expect("テキストボックスの枠がありません");
+    assert_eq!((y, width, height), (216.0, 180.0, 72.0));
+    assert_eq!(fill, Some(Color::rgb(0xFF, 0xF2, 0xCC)));
+    assert_eq!(stroke, Some(Color::rgb(0xBF, 0x90, 0x00)));
+    assert!((stroke_width - 1.0).abs() < 1e-9);
+
+    // テキストは枠の内側の余白（左 7.2pt・上 3.6pt）から、代替のVMLと重複せずに1回だけ描画される
+    let sidebar = text_at("Sidebar note");
+    assert_eq!(sidebar.len(), 1);
+    let (x, y, bold) = sidebar[0];
+    assert!((x - 151.2).abs() < 1e-9 && (y - 219.6).abs() < 1e-9, "({}, {})", x, y);
+    assert!(bold);
+
+    // 本文の流れには影響しない
+    assert_eq!(text_at("Body text")[0].1, 72.0);
+
+    // VMLのテキストボックスは style の位置・大きさと fillcolor で描画し、stroked="f" なら線なし
+    assert!(elements.iter().any(|el| matches!(
+        el,
+        PageElement::Rect { x, y, width, height, fill: Some(fill), stroke: None, .. }
+            if (*x, *y, *width, *height) == (36.0, 400.0, 200.0, 50.0) && *fill == Color::rgb(0xDD, 0xEB, 0xF7)
+    )));
+    let legacy = text_at("Legacy callout");
+    assert_eq!(legacy.len(), 1);
+    assert!((legacy[0].0 - 43.2).abs() < 1e-9 && (legacy[0].1 - 403.6).abs() < 1e-9);
+
+    // テキスト抽出にもテキストボックスの内容が含まれる
+    assert!(doc.extract_text()[0].contains("Sidebar note"));
+}
+
+/// 読み込めない画像は遅延アセットとして記録し、供給したデータで再変換できる
+#[test]
+fn test_unresolved_image_is_recorded_as_deferred_asset() {
+    use wasm_document_converter::converter::PageElement;
+    use wasm_document_converter::formats::deferred_assets::DeferredAsset;
+    use wasm_document_converter::Converter;
+
+    // リレーションシップはあるが画像のパーツがパッケージに含まれていないDOCX
+    let docx_data = zip_package(&[
+        ("word/document.xml", br#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"
+            xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing"
+            xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+            xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture"
+            xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <w:body>
+    <w:p><w:r><w:drawing>
+      <wp:inline>
+        <wp:extent cx="1905000" cy="952500"/>
+        <a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">
+          <pic:pic><pic:blipFill><a:blip r:embed="rId5"/></pic:blipFill></pic:pic>
+        </a:graphicData></a:graphic>
+      </wp:inline>
+    </w:drawing></w:r></w:p>
+  </w:body>
+</w:document>"#),
+        ("word/_rels/document.xml.rels", br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId5" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="media/chart.png"/>
+</Relationships>"#),
+    ]);
+
+    // 灰色の矩形だけでなく、リレーションシップIDを記したプレースホルダーを元の大きさで表示する
+    let mut converter = Converter::new();
+    let doc = converter.to_document("report.docx", &docx_data).unwrap();
+    let expected = DeferredAsset { relationship_id: "rId5".to_string(), path: Some("word/media/chart.png".to_string()) };
+    assert_eq!(doc.warnings.iter().filter_map(|w| DeferredAsset::from_warning(w)).collect::<Vec<_>>(), vec![expected.clone()]);
+    assert!(doc.warnings[0].contains("rId5"));
+    assert!(doc.pages[0].elements.iter().any(|el| matches!(el,
+        PageElement::Rect { width, height, .. } if (width - 150.0).abs() < 0.01 && (height - 75.0).abs() < 0.01)));
+    assert!(doc.pages[0].elements.iter().any(|el| matches!(el, PageElement::Text { text, .. } if text.contains("rId5"))));
+    assert!(!doc.pages[0].elements.iter().any(|el| matches!(el, PageElement::Image { .. })));
+    assert_eq!(converter.deferred_assets(), vec![expected]);
+
+    // 供給したデータで再変換すると画像を描画し、遅延アセットは無くなる
+    converter.add_asset("word/media/chart.png".to_string(), create_test_png(4, 2));
+    let doc = converter.to_document("report.docx", &docx_data).unwrap();
+    assert!(doc.pages[0].elements.iter().any(|el| matches!(el, PageElement::Image { .. })));
+    assert!(converter.deferred_assets().is_empty());
+
+    // テキストのみのモードではプレースホルダーのラベルを出力しない
+    converter.clear_assets();
+    assert_eq!(converter.extract_text("report.docx", &docx_data).unwrap(), vec![String::new()]);
+}
+
+/// PPTXのテーマフォントの参照（+mn-lt / +mj-ea）をフォントスキームのフォント名に解決する
+#[test]
+fn test_pptx_theme_font_references_resolve_to_font_scheme() {
+    use wasm_document_converter::converter::PageElement;
+
+    let theme_xml = r#"<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" name="Office">
+  <a:themeElements>
+    <a:fontScheme name="Custom">
+      <a:majorFont><a:latin typeface="Georgia"/><a:ea typeface="Yu Mincho"/><a:cs typeface=""/></a:majorFont>
+      <a:minorFont><a:latin typeface="Aptos"/><a:ea typeface="Yu Gothic"/><a:cs typeface=""/>
+        <a:font script="Jpan" typeface="Meiryo"/></a:minorFont>
+    </a:fontScheme>
+  </a:themeElements>
+</a:theme>"#;
+    let slide_xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
+  <p:cSld><p:spTree>
+    <p:sp>
+      <p:nvSpPr><p:cNvPr id="2" name="Body"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr>
+      <p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="5080000" cy="2540000"/></a:xfrm></p:spPr>
+      <p:txBody><a:bodyPr/>
+        <a:p><a:r><a:rPr lang="en-US" sz="1800"><a:latin typeface="+mn-lt"/><a:ea typeface="+mn-ea"/></a:rPr><a:t>Minor</a:t></a:r></a:p>
+        <a:p><a:r><a:rPr lang="ja-JP" sz="1800"><a:ea typeface="+mj-ea"/></a:rPr><a:t>見出し</a:t></a:r></a:p>
+        <a:p><a:r><a:rPr lang="en-US" sz="1800"><a:latin typeface="Arial"/></a:rPr><a:t>Explicit</a:t></a:r></a:p>
+        <a:p><a:r><a:rPr lang="en-US" sz="1800"><a:latin typeface="+mj-cs"/></a:rPr><a:t>Undefined</a:t></a:r></a:p>
+      </p:txBody>
+    </p:sp>
+  </p:spTree></p:cSld>
+</p:sld>"#;
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", slide_xml.as_bytes()),
+        ("ppt/theme/theme1.xml", theme_xml.as_bytes()),
+    ]);
+
+    let doc = formats::convert_by_extension("pptx", &pptx).unwrap();
+    let font_of = |needle: &str| {
+        doc.pages[0]
+            .elements
+            .iter()
+            .find_map(|el| match el {
+                PageElement::Text { text, style, .. } if text == needle => Some(style.font_name.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("テキストが見つかりません: {}", needle))
+    };
+    // +mn-lt は本文の Latin フォント、a:latin が無い場合は a:ea（+mj-ea は見出しの東アジアフォント）
+    assert_eq!(font_of("Minor"), "Aptos");
+    assert_eq!(font_of("見出し"), "Yu Mincho");
+    assert_eq!(font_of("Explicit"), "Arial");
+    // フォントスキームで定義されていない参照は既定のフォント
+    assert_eq!(font_of("Undefined"), FontStyle::default().font_name);
+
+    // 解決したフォント名は不足フォントとしてリゾルバーの問い合わせ対象になる
+    let missing = FontManager::new().missing_fonts(&doc);
+    assert!(missing.contains(&"Aptos".to_string()), "{:?}", missing);
+    assert!(!missing.iter().any(|name| name.starts_with('+')), "{:?}", missing);
+}
+
+/// PPTXのリンクの付いたランを hyperlink_style に合わせて強調・プレーンにする
+#[test]
+fn test_pptx_hyperlink_style_emphasize_uses_theme_hlink() {
+    use wasm_document_converter::converter::{Color, HyperlinkStyle, PageElement, TextFlowOptions, UnderlineKind};
+
+    let theme_xml = r#"<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" name="Office">
+  <a:themeElements>
+    <a:clrScheme name="Custom"><a:hlink><a:srgbClr val="FF6600"/></a:hlink></a:clrScheme>
+  </a:themeElements>
+</a:theme>"#;
+    let slide_xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+       xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <p:cSld><p:spTree>
+    <p:sp>
+      <p:nvSpPr><p:cNvPr id="2" name="Body"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr>
+      <p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="5080000" cy="2540000"/></a:xfrm></p:spPr>
+      <p:txBody><a:bodyPr/>
+        <a:p>
+          <a:r><a:rPr lang="en-US" sz="1800"/><a:t>See </a:t></a:r>
+          <a:r><a:rPr lang="en-US" sz="1800"><a:hlinkClick r:id="rId2"/></a:rPr><a:t>the site</a:t></a:r>
+        </a:p>
+      </p:txBody>
+    </p:sp>
+  </p:spTree></p:cSld>
+</p:sld>"#;
+    let pptx = zip_package(&[
+        ("ppt/presentation.xml", br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#),
+        ("ppt/slides/slide1.xml", slide_xml.as_bytes()),
+        ("ppt/theme/theme1.xml", theme_xml.as_bytes()),
+    ]);
+
+    let style_of = |hyperlink_style: HyperlinkStyle, needle: &str| {
+        let options = TextFlowOptions { hyperlink_style, ..Default::default() };
+        let doc = formats::convert_by_extension_with_options("pptx", &pptx, &options).unwrap();
+        doc.pages[0]
+            .elements
+            .iter()
+            .find_map(|el| match el {
+                PageElement::Text { text, style, .. } if text == needle => Some(style.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("テキストが見つかりません: {}", needle))
+    };
+
+    let link = style_of(HyperlinkStyle::Emphasize, "the site");
+    assert_eq!(link.color, Color::rgb(0xFF, 0x66, 0x00));
+    assert_eq!(link.underline.map(|u| u.kind), Some(UnderlineKind::Single));
+    // リンクの無いランはそのまま
+    let plain_run = style_of(HyperlinkStyle::Emphasize, "See ");
+    assert!(plain_run.underline.is_none() && plain_run.color != Color::rgb(0xFF, 0x66, 0x00));
+
+    // 既定では元の書式のまま
+    let source = style_of(HyperlinkStyle::Source, "the site");
+    assert!(source.underline.is_none());
+    assert_eq!(source.color, plain_run.color);
+}
+
+#[test]
+fn test_docx_hyperlink_style_emphasize_uses_theme_hlink() {
+    use wasm_document_converter::converter::{Color, HyperlinkStyle, PageElement, TextFlowOptions, UnderlineKind};
+
+    let theme_xml = r#"<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" name="Office">
+  <a:themeElements>
+    <a:clrScheme name="Custom"><a:hlink><a:srgbClr val="FF6600"/></a:hlink></a:clrScheme>
+  </a:themeElements>
+</a:theme>"#;
+    let document_xml = r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"
+    xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <w:body>
+    <w:p>
+      <w:r><w:t xml:space="preserve">See </w:t></w:r>
+      <w:hyperlink r:id="rId5"><w:r><w:rPr><w:color w:val="FF6600"/></w:rPr><w:t>the site</w:t></w:r></w:hyperlink>
+    </w:p>
+    <w:tbl><w:tr><w:tc><w:p>
+      <w:hyperlink w:anchor="top"><w:r><w:t>back</w:t></w:r></w:hyperlink>
+    </w:p></w:tc></w:tr></w:tbl>
+  </w:body>
+</w:document>"#;
+    let docx = zip_package(&[("word/document.xml", document_xml.as_bytes()), ("word/theme/theme1.xml", theme_xml.as_bytes())]);
+
+    let style_of = |hyperlink_style: HyperlinkStyle, needle: &str| {
+        let options = TextFlowOptions { hyperlink_style, ..Default::default() };
+        let doc = formats::convert_by_extension_with_options("docx", &docx, &options).unwrap();
+        let mut found = None;
+        for el in &doc.pages[0].elements {
+            match el {
+                PageElement::Text { text, style, .. } if text.trim() == needle => found = Some(style.clone()),
+                PageElement::TableBlock { table, .. } => {
+                    found = found.or_else(|| {
+                        table.rows.iter().flatten().find(|cell| cell.text == needle).map(|cell| cell.style.clone())
+                    })
+                }
+                _ => {}
+            }
+        }
+        found.unwrap_or_else(|| panic!("テキストが見つかりません: {}", needle))
+    };
+
+    let link_color = Color::rgb(0xFF, 0x66, 0x00);
+    let link = style_of(HyperlinkStyle::Emphasize, "the site");
+    assert_eq!(link.color, link_color);
+    assert_eq!(link.underline.map(|u| u.kind), Some(UnderlineKind::Single));
+    // 表のセル内のリンクも強調する
+    let cell_link = style_of(HyperlinkStyle::Emphasize, "back");
+    assert_eq!(cell_link.color, link_color);
+    assert!(cell_link.underline.is_some());
+    // リンクの無いランはそのまま
+    let plain_run = style_of(HyperlinkStyle::Emphasize, "See");
+    assert!(plain_run.underline.is_none() && plain_run.color != link_color);
+
+    // 既定では元の書式のまま、プレーンではテーマの hlink の色も外す
+    assert_eq!(style_of(HyperlinkStyle::Source, "the site").color, link_color);
+    let plain = style_of(HyperlinkStyle::Plain, "the site");
+    assert!(plain.underline.is_none());
+    assert_eq!(plain.color, plain_run.color);
+}
+
+/// 太字・斜体の書体が無いフォントでは強調を合成し、書体がある場合はその書体で描画する
+#[test]
+fn test_synthetic_bold_and_italic_with_regular_only_font() {
+    use wasm_document_converter::converter::{Page, PageElement, TextAlign};
+    use wasm_document_converter::pdf_writer::PdfOptions;
+
+    let text_at = |y: f64, bold: bool, italic: bool| PageElement::Text {
+        x: 50.0,
+        y,
+        width: 400.0,
+        text: "Emphasis 強調".to_string(),
+        style: FontStyle { font_size: 24.0, bold, italic, ..FontStyle::default() },
+        align: TextAlign::Left,
+    };
+    let mut page = Page::a4();
+    page.elements.push(text_at(100.0, false, false));
+    page.elements.push(text_at(300.0, true, false));
+    page.elements.push(text_at(500.0, false, true));
+
+    // 上から順に通常・太字・斜体の帯のインク量（暗さの合計）
+    let ink = |font_manager: &FontManager, synthesize_styles: bool| -> Vec<u64> {
+        let config = image_renderer::ImageRenderConfig { dpi: 72.0, synthesize_styles, ..Default::default() };
+        let png = image_renderer::render_page_to_image(&page, &config, font_manager);
+        let (width, _, pixels) = decode_png_pixels(&png);
+        [100usize, 300, 500]
+            .iter()
+            .map(|&top| {
+                pixels[top * width as usize * 4..(top + 40) * width as usize * 4]
+                    .chunks(4)
+                    .map(|p| 255 - p[0] as u64)
+                    .sum()
+            })
+            .collect()
+    };
+
+    // 通常の書体だけのフォント: 太字は輪郭を太らせてインクが増え、斜体は形が変わる
+    let regular_only = FontManager::new();
+    let synthesized = ink(&regular_only, true);
+    assert!(synthesized[1] as f64 > synthesized[0] as f64 * 1.1, "{:?}", synthesized);
+    assert_ne!(synthesized[2], synthesized[0]);
+
+    // 合成を無効にすると通常の書体のまま描画する
+    let plain = ink(&regular_only, false);
+    assert_eq!(plain[1], plain[0]);
+    assert_eq!(plain[2], plain[0]);
+
+    // 同じ名前の太字の書体がある場合は合成しない（ここでは通常の書体のデータで代用）
+    let mut with_bold_face = FontManager::new();
+    let regular = with_bold_face.builtin_japanese_font().unwrap().to_vec();
+    with_bold_face.add_font(format!("{} Bold", FontStyle::default().font_name), regular);
+    let faces = ink(&with_bold_face, true);
+    assert_eq!(faces[1], faces[0]);
+
+    // PDFでは描画モード2（塗りと線）とテキスト行列の傾きで合成する
+    let mut doc = Document::new();
+    doc.pages.push(page.clone());
+    let pdf = |options: &PdfOptions| {
+        String::from_utf8_lossy(&pdf_writer::render_to_pdf_with_options(&doc, &regular_only, options)).into_owned()
+    };
+    let synthesized_pdf = pdf(&PdfOptions::default());
+    assert_eq!(synthesized_pdf.matches("2 Tr\n").count(), 1);
+    assert_eq!(synthesized_pdf.matches(" Tm\n").count(), 1);
+    let plain_pdf = pdf(&PdfOptions { synthesize_styles: false, ..PdfOptions::default() });
+    assert!(!plain_pdf.contains("2 Tr\n") && !plain_pdf.contains(" Tm\n"));
+}
+
+#[test]
+fn test_text_as_paths_outlines_glyphs_without_fonts() {
+    use wasm_document_converter::converter::{Page, PageElement, Table, TableCell, TextAlign};
+    use wasm_document_converter::pdf_writer::PdfOptions;
+
+    let mut page = Page::a4();
+    page.elements.push(PageElement::Text {
+        x: 50.0,
+        y: 100.0,
+        width: 400.0,
+        text: "Outline 輪郭".to_string(),
+        style: FontStyle { font_size: 24.0, bold: true, ..FontStyle::default() },
+        align: TextAlign::Left,
+    });
+    page.elements.push(PageElement::TableBlock {
+        x: 50.0,
+        y: 200.0,
+        width: 200.0,
+        table: Table { rows: vec![vec![TableCell::new("Cell")]], column_widths: Vec::new() },
+    });
+    let mut doc = Document::new();
+    doc.pages.push(page);
+    let font_manager = FontManager::new();
+
+    let text_pdf = String::from_utf8_lossy(&pdf_writer::render_to_pdf_with_fonts(&doc, &font_manager)).into_owned();
+    assert!(text_pdf.contains("/Type /Font"));
+
+    let options = PdfOptions { text_as_paths: true, ..PdfOptions::default() };
+    let pdf = String::from_utf8_lossy(&pdf_writer::render_to_pdf_with_options(&doc, &font_manager, &options)).into_owned();
+    assert!(!pdf.contains("/Type /Font"), "フォントオブジェクトを埋め込まない");
+    assert!(!pdf.contains(" Tj\n") && !pdf.contains("/Font <<"));
+    // 通常のテキストはノンゼロ規則の塗り、合成する太字は塗りと線で描く
+    assert!(pdf.contains(" c\n") && pdf.contains("\nf\n"), "セルのテキストを塗りつぶしのパスで描く");
+    assert!(pdf.contains("\nB\n"), "太字のテキストを塗りと線のパスで描く");
+}
+
+#[test]
+fn test_image_quality_presets() {
+    use wasm_document_converter::image_renderer::{ImageInterpolation, ImageQuality, ImageRenderConfig};
+
+    let doc = formats::convert_by_extension("txt", "Quality preset".as_bytes()).unwrap();
+    let font_manager = FontManager::new();
+    let size = |config: &ImageRenderConfig| {
+        let png = image_renderer::render_page_to_image(&doc.pages[0], config, &font_manager);
+        let (width, height, _) = decode_png_pixels(&png);
+        (width, height)
+    };
+
+    let draft = ImageRenderConfig::with_quality(ImageQuality::Draft);
+    let print = ImageRenderConfig::with_quality(ImageQuality::Print);
+    assert_eq!((draft.dpi, draft.supersample, draft.image_interpolation), (96.0, 1, ImageInterpolation::Nearest));
+    assert_eq!((print.dpi, print.supersample, print.image_interpolation), (300.0, 2, ImageInterpolation::Bilinear));
+    let (draft_size, print_size) = (size(&draft), size(&print));
+    assert!(print_size.0 > draft_size.0 && print_size.1 > draft_size.1, "{:?} / {:?}", draft_size, print_size);
+
+    // 個別の指定はプリセットより優先される
+    let overridden = ImageRenderConfig { dpi: 96.0, ..ImageRenderConfig::with_quality(ImageQuality::Print) };
+    assert_eq!(size(&overridden), draft_size);
+    assert_eq!(ImageQuality::from_name("print"), Some(ImageQuality::Print));
+    assert_eq!(ImageQuality::from_name("ultra"), None);
+}